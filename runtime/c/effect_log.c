@@ -0,0 +1,539 @@
+/*
+ * effect_log.c — Record and replay of builtin effects (`flux --native --record/--replay`).
+ *
+ * The builtin operations that read the outside world (read_file, read_lines,
+ * read_stdin, clock_now) report each call here.  With $FLUX_EFFECT_RECORD set,
+ * every call is appended to that file with its arguments and its result or
+ * error.  With $FLUX_EFFECT_REPLAY set, calls are answered from that file
+ * instead of the outside world, and the binary exits on the first call that
+ * differs from the log, on a call past its end, and at exit when logged calls
+ * were never made.
+ *
+ * The log is the same JSON lines file the VM writes (src/runtime/effect_log.rs):
+ *
+ *   {"flux_effect_log":1}
+ *   {"op":"read_file","args":["input.txt"],"result":"hello\n"}
+ *   {"op":"read_file","args":["missing.txt"],"error":"read_file failed for ..."}
+ *   {"op":"clock_now","args":[],"result":1760745600000}
+ */
+
+#include "flux_rt.h"
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define EFFECT_LOG_VERSION 1
+
+enum { LOG_CLOSED, LOG_OFF, LOG_RECORD, LOG_REPLAY };
+
+enum { VALUE_STRING, VALUE_INT, VALUE_ARRAY };
+
+/* A value decoded from the log. */
+typedef struct LogValue {
+    int kind;
+    char *text;                 /* VALUE_STRING */
+    uint32_t len;
+    int64_t num;                /* VALUE_INT */
+    struct LogValue *items;     /* VALUE_ARRAY */
+    uint32_t count;
+} LogValue;
+
+/* One decoded line of the log. */
+typedef struct {
+    char *op;
+    LogValue args;
+    LogValue result;
+    char *error;
+    int has_result;
+    int64_t version;
+} LogEntry;
+
+static int mode = LOG_CLOSED;
+static FILE *record_out;
+static const char *log_path;
+
+static char *replay_text;
+static char **replay_lines;
+static size_t replay_count;
+static size_t replay_next;
+
+/* ── Writing ────────────────────────────────────────────────────────── */
+
+static void write_json_string(FILE *out, const char *text, uint32_t len) {
+    fputc('"', out);
+    for (uint32_t i = 0; i < len; i++) {
+        unsigned char c = (unsigned char)text[i];
+        switch (c) {
+        case '"':  fputs("\\\"", out); break;
+        case '\\': fputs("\\\\", out); break;
+        case '\b': fputs("\\b", out); break;
+        case '\f': fputs("\\f", out); break;
+        case '\n': fputs("\\n", out); break;
+        case '\r': fputs("\\r", out); break;
+        case '\t': fputs("\\t", out); break;
+        default:
+            if (c < 0x20) {
+                fprintf(out, "\\u%04x", c);
+            } else {
+                fputc(c, out);
+            }
+        }
+    }
+    fputc('"', out);
+}
+
+static int is_heap(int64_t value, uint8_t tag) {
+    return flux_is_ptr(value) && flux_obj_tag(flux_untag_ptr(value)) == tag;
+}
+
+/* Writes a runtime value: strings, integers and arrays of them. */
+static void write_flux_value(FILE *out, int64_t value) {
+    if (flux_is_int(value)) {
+        fprintf(out, "%lld", (long long)flux_untag_int(value));
+    } else if (is_heap(value, FLUX_OBJ_STRING)) {
+        write_json_string(out, flux_string_data(value), flux_string_len(value));
+    } else if (is_heap(value, FLUX_OBJ_ARRAY)) {
+        int64_t len = flux_untag_int(flux_array_len(value));
+        fputc('[', out);
+        for (int64_t i = 0; i < len; i++) {
+            if (i > 0) fputc(',', out);
+            write_flux_value(out, flux_array_get(value, flux_tag_int(i)));
+        }
+        fputc(']', out);
+    } else {
+        int64_t text = flux_to_string(value);
+        write_json_string(out, flux_string_data(text), flux_string_len(text));
+        flux_drop(text);
+    }
+}
+
+static void write_log_value(FILE *out, const LogValue *value) {
+    switch (value->kind) {
+    case VALUE_STRING:
+        write_json_string(out, value->text, value->len);
+        break;
+    case VALUE_INT:
+        fprintf(out, "%lld", (long long)value->num);
+        break;
+    case VALUE_ARRAY:
+        fputc('[', out);
+        for (uint32_t i = 0; i < value->count; i++) {
+            if (i > 0) fputc(',', out);
+            write_log_value(out, &value->items[i]);
+        }
+        fputc(']', out);
+        break;
+    }
+}
+
+/* Writes `op(arg, ...)` for divergence messages. */
+static void write_flux_call(FILE *out, const char *op, const int64_t *args, int32_t count) {
+    fprintf(out, "%s(", op);
+    for (int32_t i = 0; i < count; i++) {
+        if (i > 0) fputs(", ", out);
+        write_flux_value(out, args[i]);
+    }
+    fputc(')', out);
+}
+
+static void write_log_call(FILE *out, const LogEntry *entry) {
+    fprintf(out, "%s(", entry->op);
+    for (uint32_t i = 0; i < entry->args.count; i++) {
+        if (i > 0) fputs(", ", out);
+        write_log_value(out, &entry->args.items[i]);
+    }
+    fputc(')', out);
+}
+
+/* ── Reading ────────────────────────────────────────────────────────── */
+
+static void skip_ws(const char **p) {
+    while (**p == ' ' || **p == '\t' || **p == '\r') (*p)++;
+}
+
+static int parse_hex4(const char **p, uint32_t *out) {
+    uint32_t code = 0;
+    for (int i = 0; i < 4; i++) {
+        char c = *(*p)++;
+        code <<= 4;
+        if (c >= '0' && c <= '9') code |= (uint32_t)(c - '0');
+        else if (c >= 'a' && c <= 'f') code |= (uint32_t)(c - 'a' + 10);
+        else if (c >= 'A' && c <= 'F') code |= (uint32_t)(c - 'A' + 10);
+        else return 0;
+    }
+    *out = code;
+    return 1;
+}
+
+static void put_utf8(char *buf, uint32_t *len, uint32_t code) {
+    if (code < 0x80) {
+        buf[(*len)++] = (char)code;
+    } else if (code < 0x800) {
+        buf[(*len)++] = (char)(0xC0 | (code >> 6));
+        buf[(*len)++] = (char)(0x80 | (code & 0x3F));
+    } else if (code < 0x10000) {
+        buf[(*len)++] = (char)(0xE0 | (code >> 12));
+        buf[(*len)++] = (char)(0x80 | ((code >> 6) & 0x3F));
+        buf[(*len)++] = (char)(0x80 | (code & 0x3F));
+    } else {
+        buf[(*len)++] = (char)(0xF0 | (code >> 18));
+        buf[(*len)++] = (char)(0x80 | ((code >> 12) & 0x3F));
+        buf[(*len)++] = (char)(0x80 | ((code >> 6) & 0x3F));
+        buf[(*len)++] = (char)(0x80 | (code & 0x3F));
+    }
+}
+
+/* Decodes a JSON string into a NUL-terminated buffer; escapes only shrink it. */
+static int parse_string(const char **p, char **text, uint32_t *len) {
+    if (**p != '"') return 0;
+    (*p)++;
+    const char *start = *p;
+    char *buf = (char *)malloc(strlen(start) + 1);
+    if (!buf) return 0;
+    uint32_t n = 0;
+    while (**p != '"') {
+        char c = *(*p)++;
+        if (c == '\0' || c == '\n') {
+            free(buf);
+            return 0;
+        }
+        if (c != '\\') {
+            buf[n++] = c;
+            continue;
+        }
+        c = *(*p)++;
+        switch (c) {
+        case '"':  buf[n++] = '"'; break;
+        case '\\': buf[n++] = '\\'; break;
+        case '/':  buf[n++] = '/'; break;
+        case 'b':  buf[n++] = '\b'; break;
+        case 'f':  buf[n++] = '\f'; break;
+        case 'n':  buf[n++] = '\n'; break;
+        case 'r':  buf[n++] = '\r'; break;
+        case 't':  buf[n++] = '\t'; break;
+        case 'u': {
+            uint32_t code;
+            if (!parse_hex4(p, &code)) {
+                free(buf);
+                return 0;
+            }
+            if (code >= 0xD800 && code < 0xDC00 && (*p)[0] == '\\' && (*p)[1] == 'u') {
+                uint32_t low;
+                *p += 2;
+                if (!parse_hex4(p, &low)) {
+                    free(buf);
+                    return 0;
+                }
+                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+            }
+            put_utf8(buf, &n, code);
+            break;
+        }
+        default:
+            free(buf);
+            return 0;
+        }
+    }
+    (*p)++;
+    buf[n] = '\0';
+    *text = buf;
+    *len = n;
+    return 1;
+}
+
+static void free_value(LogValue *value) {
+    free(value->text);
+    for (uint32_t i = 0; i < value->count; i++) free_value(&value->items[i]);
+    free(value->items);
+}
+
+static void free_entry(LogEntry *entry) {
+    free(entry->op);
+    free(entry->error);
+    free_value(&entry->args);
+    free_value(&entry->result);
+}
+
+static int parse_value(const char **p, LogValue *value) {
+    skip_ws(p);
+    memset(value, 0, sizeof(*value));
+    if (**p == '"') {
+        value->kind = VALUE_STRING;
+        return parse_string(p, &value->text, &value->len);
+    }
+    if (**p == '[') {
+        value->kind = VALUE_ARRAY;
+        (*p)++;
+        skip_ws(p);
+        uint32_t capacity = 0;
+        while (**p != ']') {
+            if (value->count > 0) {
+                if (**p != ',') return 0;
+                (*p)++;
+            }
+            if (value->count == capacity) {
+                capacity = capacity ? capacity * 2 : 8;
+                value->items = (LogValue *)realloc(value->items, capacity * sizeof(LogValue));
+                if (!value->items) return 0;
+            }
+            if (!parse_value(p, &value->items[value->count])) return 0;
+            value->count++;
+            skip_ws(p);
+        }
+        (*p)++;
+        return 1;
+    }
+    char *end;
+    value->kind = VALUE_INT;
+    value->num = strtoll(*p, &end, 10);
+    if (end == *p) return 0;
+    *p = end;
+    return 1;
+}
+
+/* Decodes one line of the log, which is a flat JSON object. */
+static int parse_entry(const char *line, LogEntry *entry) {
+    const char *p = line;
+    memset(entry, 0, sizeof(*entry));
+    skip_ws(&p);
+    if (*p++ != '{') return 0;
+    skip_ws(&p);
+    int first = 1;
+    while (*p != '}') {
+        if (!first) {
+            if (*p++ != ',') return 0;
+            skip_ws(&p);
+        }
+        first = 0;
+        char *key;
+        uint32_t key_len;
+        if (!parse_string(&p, &key, &key_len)) return 0;
+        skip_ws(&p);
+        if (*p++ != ':') {
+            free(key);
+            return 0;
+        }
+        LogValue value;
+        int ok = parse_value(&p, &value);
+        if (ok && strcmp(key, "op") == 0 && value.kind == VALUE_STRING) {
+            entry->op = value.text;
+        } else if (ok && strcmp(key, "args") == 0 && value.kind == VALUE_ARRAY) {
+            entry->args = value;
+        } else if (ok && strcmp(key, "result") == 0) {
+            entry->result = value;
+            entry->has_result = 1;
+        } else if (ok && strcmp(key, "error") == 0 && value.kind == VALUE_STRING) {
+            entry->error = value.text;
+        } else if (ok && strcmp(key, "flux_effect_log") == 0 && value.kind == VALUE_INT) {
+            entry->version = value.num;
+        } else {
+            if (ok) free_value(&value);
+            ok = 0;
+        }
+        free(key);
+        if (!ok) return 0;
+        skip_ws(&p);
+    }
+    return 1;
+}
+
+static int64_t log_value_to_flux(const LogValue *value) {
+    switch (value->kind) {
+    case VALUE_STRING:
+        return flux_string_new(value->text, value->len);
+    case VALUE_INT:
+        return flux_tag_int(value->num);
+    default: {
+        int64_t *items = (int64_t *)malloc((value->count + 1) * sizeof(int64_t));
+        for (uint32_t i = 0; i < value->count; i++) {
+            items[i] = log_value_to_flux(&value->items[i]);
+        }
+        int64_t array = flux_array_new(items, (int32_t)value->count);
+        free(items);
+        return array;
+    }
+    }
+}
+
+static int values_match(int64_t value, const LogValue *logged) {
+    switch (logged->kind) {
+    case VALUE_STRING:
+        return is_heap(value, FLUX_OBJ_STRING) && flux_string_len(value) == logged->len
+            && memcmp(flux_string_data(value), logged->text, logged->len) == 0;
+    case VALUE_INT:
+        return flux_is_int(value) && flux_untag_int(value) == logged->num;
+    default:
+        if (!is_heap(value, FLUX_OBJ_ARRAY)
+            || flux_untag_int(flux_array_len(value)) != (int64_t)logged->count) {
+            return 0;
+        }
+        for (uint32_t i = 0; i < logged->count; i++) {
+            if (!values_match(flux_array_get(value, flux_tag_int(i)), &logged->items[i])) {
+                return 0;
+            }
+        }
+        return 1;
+    }
+}
+
+/* ── Opening and closing ────────────────────────────────────────────── */
+
+static void log_fail(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "flux_effect_log: %s: %s\n", log_path, message);
+    exit(1);
+}
+
+static void record_close(void) {
+    if (record_out) fclose(record_out);
+}
+
+/* Reports logged calls the run never made. */
+static void replay_check_end(void) {
+    if (replay_next >= replay_count) return;
+    LogEntry entry;
+    fflush(stdout);
+    fprintf(stderr, "replay diverged: %zu recorded effects were not performed, starting with ",
+            replay_count - replay_next);
+    if (parse_entry(replay_lines[replay_next], &entry) && entry.op) {
+        write_log_call(stderr, &entry);
+    } else {
+        fputs(replay_lines[replay_next], stderr);
+    }
+    fputc('\n', stderr);
+    _Exit(1);
+}
+
+static void replay_load(void) {
+    FILE *f = fopen(log_path, "rb");
+    if (!f) log_fail("cannot read the effect log");
+    fseek(f, 0, SEEK_END);
+    long size = ftell(f);
+    fseek(f, 0, SEEK_SET);
+    replay_text = (char *)malloc((size_t)(size > 0 ? size : 0) + 1);
+    if (!replay_text) log_fail("out of memory");
+    size_t nread = fread(replay_text, 1, (size_t)(size > 0 ? size : 0), f);
+    fclose(f);
+    replay_text[nread] = '\0';
+
+    size_t capacity = 64;
+    replay_lines = (char **)malloc(capacity * sizeof(char *));
+    if (!replay_lines) log_fail("out of memory");
+    for (char *line = replay_text; line && *line;) {
+        char *end = strchr(line, '\n');
+        if (end) *end = '\0';
+        if (*line) {
+            if (replay_count == capacity) {
+                capacity *= 2;
+                replay_lines = (char **)realloc(replay_lines, capacity * sizeof(char *));
+                if (!replay_lines) log_fail("out of memory");
+            }
+            replay_lines[replay_count++] = line;
+        }
+        line = end ? end + 1 : NULL;
+    }
+
+    LogEntry header;
+    if (replay_count == 0 || !parse_entry(replay_lines[0], &header) || header.op
+        || header.version == 0) {
+        log_fail("not a Flux effect log");
+    }
+    if (header.version != EFFECT_LOG_VERSION) {
+        log_fail("unsupported effect log version");
+    }
+    replay_next = 1;
+    atexit(replay_check_end);
+}
+
+static void log_open(void) {
+    if (mode != LOG_CLOSED) return;
+    mode = LOG_OFF;
+    const char *record = getenv("FLUX_EFFECT_RECORD");
+    const char *replay = getenv("FLUX_EFFECT_REPLAY");
+    if (record && *record) {
+        log_path = record;
+        record_out = fopen(record, "w");
+        if (!record_out) log_fail("cannot write the effect log");
+        fprintf(record_out, "{\"flux_effect_log\":%d}\n", EFFECT_LOG_VERSION);
+        fflush(record_out);
+        atexit(record_close);
+        mode = LOG_RECORD;
+    } else if (replay && *replay) {
+        log_path = replay;
+        replay_load();
+        mode = LOG_REPLAY;
+    }
+}
+
+/* ── Runtime interface ──────────────────────────────────────────────── */
+
+static void record_call(const char *op, const int64_t *args, int32_t count) {
+    fprintf(record_out, "{\"op\":\"%s\",\"args\":[", op);
+    for (int32_t i = 0; i < count; i++) {
+        if (i > 0) fputc(',', record_out);
+        write_flux_value(record_out, args[i]);
+    }
+    fputs("],", record_out);
+}
+
+void flux_effect_record(const char *op, const int64_t *args, int32_t count, int64_t result) {
+    log_open();
+    if (mode != LOG_RECORD) return;
+    record_call(op, args, count);
+    fputs("\"result\":", record_out);
+    write_flux_value(record_out, result);
+    fputs("}\n", record_out);
+    fflush(record_out);
+}
+
+void flux_effect_record_error(const char *op, const int64_t *args, int32_t count,
+                              const char *message, uint32_t len) {
+    log_open();
+    if (mode != LOG_RECORD) return;
+    record_call(op, args, count);
+    fputs("\"error\":", record_out);
+    write_json_string(record_out, message, len);
+    fputs("}\n", record_out);
+    fflush(record_out);
+}
+
+int flux_effect_replay(const char *op, const int64_t *args, int32_t count, int64_t *result) {
+    log_open();
+    if (mode != LOG_REPLAY) return 0;
+
+    size_t index = replay_next;
+    if (index >= replay_count) {
+        fflush(stdout);
+        fprintf(stderr, "replay diverged at effect %zu: expected end of log, got ", index);
+        write_flux_call(stderr, op, args, count);
+        fputc('\n', stderr);
+        _Exit(1);
+    }
+    LogEntry entry;
+    if (!parse_entry(replay_lines[index], &entry) || !entry.op
+        || (!entry.has_result && !entry.error)) {
+        log_fail("malformed effect log entry");
+    }
+    int matches = strcmp(entry.op, op) == 0 && entry.args.count == (uint32_t)count;
+    for (int32_t i = 0; matches && i < count; i++) {
+        matches = values_match(args[i], &entry.args.items[i]);
+    }
+    if (!matches) {
+        fflush(stdout);
+        fprintf(stderr, "replay diverged at effect %zu: expected ", index);
+        write_log_call(stderr, &entry);
+        fputs(", got ", stderr);
+        write_flux_call(stderr, op, args, count);
+        fputc('\n', stderr);
+        _Exit(1);
+    }
+    replay_next++;
+    if (entry.error) {
+        int64_t message = flux_string_new(entry.error, (uint32_t)strlen(entry.error));
+        free_entry(&entry);
+        flux_panic(message);
+    }
+    *result = log_value_to_flux(&entry.result);
+    free_entry(&entry);
+    return 1;
+}
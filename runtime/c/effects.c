@@ -60,7 +60,7 @@ int32_t  flux_yield_marker      = 0;   /* target handler's marker */
 int64_t  flux_yield_clause      = 0;   /* operation clause closure */
 int64_t  flux_yield_op_arg      = 0;   /* performed argument (unused for 0-arity) */
 int64_t  flux_yield_op_state    = 0;   /* current handler parameter, or 0 when absent */
-int32_t  flux_yield_op_arity    = 0;   /* user-visible arity of the op (0 or 1) */
+int32_t  flux_yield_op_arity    = 0;   /* user-visible arity of the op */
 int64_t  flux_yield_conts[8];          /* accumulated continuation closures */
 int32_t  flux_yield_conts_count = 0;
 int64_t  flux_yield_evv         = 0;   /* current_evv at yield time (slice 5-tr-fix) */
@@ -204,23 +204,83 @@ static int evv_lookup_by_marker(EvvArray *arr, int32_t marker) {
     return -1;
 }
 
+/*
+ * A handler for a multi-operation effect is installed as an array of
+ * [optag0, clause0, optag1, clause1, ...]; a single-operation handler is the
+ * bare clause closure. Returns the (borrowed) clause for `optag`.
+ */
+static int64_t flux_handler_clause(int64_t handler, int64_t optag) {
+    if (!flux_is_ptr(handler) || flux_obj_tag(flux_untag_ptr(handler)) != FLUX_OBJ_ARRAY) {
+        return handler;
+    }
+    int64_t len = flux_untag_int(flux_array_len(handler));
+    for (int64_t i = 0; i + 1 < len; i += 2) {
+        if (flux_array_get(handler, flux_tag_int(i)) == optag) {
+            return flux_array_get(handler, flux_tag_int(i + 1));
+        }
+    }
+    fprintf(stderr, "flux_handler_clause: handler has no clause for optag=0x%llx\n",
+            (unsigned long long)(uint64_t)optag);
+    abort();
+}
+
+/* Upper bound on resume + op arguments + state passed to a clause. */
+#define FLUX_MAX_CLAUSE_ARGS 18
+
+/*
+ * Call a handler clause as clause(resume, [args...], [state]).
+ *
+ * An op with two or more arguments performs them as one tuple; it is
+ * unpacked here so the clause sees each argument as its own parameter.
+ * An op with more arguments than a clause call can carry aborts rather
+ * than running the clause with some of them missing.
+ */
+static int64_t flux_call_clause(int64_t clause, int64_t resume, int64_t arg,
+                                int64_t arity, int64_t state) {
+    int64_t args[FLUX_MAX_CLAUSE_ARGS];
+    int32_t nargs = 0;
+    args[nargs++] = resume;
+    if (arity == 1) {
+        args[nargs++] = arg;
+    } else if (arity >= 2) {
+        void *tuple = flux_untag_ptr(arg);
+        uint32_t count = *(uint32_t *)((char *)tuple + 4);
+        int64_t *elems = (int64_t *)((char *)tuple + 8);
+        if (count > FLUX_MAX_CLAUSE_ARGS - 2) {
+            fprintf(stderr,
+                    "flux_call_clause: operation takes %u arguments, at most %d are supported\n",
+                    count, FLUX_MAX_CLAUSE_ARGS - 2);
+            abort();
+        }
+        for (uint32_t i = 0; i < count; i++) {
+            flux_dup(elems[i]);
+            args[nargs++] = elems[i];
+        }
+        flux_drop(arg);
+    }
+    if (state != 0) {
+        args[nargs++] = state;
+    }
+    return flux_call_closure_c(clause, args, nargs);
+}
+
 /*
  * Perform an effect: set yield state and return sentinel.
  *
  * htag:  effect tag (tagged int)
- * optag: operation tag (tagged int) — currently unused for single-op dispatch
- * arg:   the performed argument (tagged value)
+ * optag: operation tag (tagged int), selects the clause of a multi-op handler
+ * arg:   the performed argument (tagged value), or a tuple of the arguments
+ *        when the op takes two or more
  * arity: user-visible arity of the op (0 for `() -> T`, 1 for `A -> T`).
  *        Needed so flux_yield_prompt can call the clause with the correct
- *        number of args: `(resume)` when arity=0 (no user arg) or
- *        `(resume, arg)` when arity=1.
+ *        number of args: `(resume)` when arity=0 (no user arg),
+ *        `(resume, arg)` when arity=1, and `(resume, arg0, ..., argN)` when
+ *        the arguments arrive as a tuple.
  *
  * The caller must check flux_yield_yielding after every call and propagate
  * the sentinel + extend continuations as needed.
  */
 int64_t flux_yield_to(int64_t htag, int64_t optag, int64_t arg, int64_t arity) {
-    (void)optag;  /* reserved for multi-op dispatch */
-
     EvvArray *arr = evv_unbox(current_evv);
     int idx = evv_lookup(arr, htag);
 
@@ -232,7 +292,7 @@ int64_t flux_yield_to(int64_t htag, int64_t optag, int64_t arg, int64_t arity) {
 
     int64_t *entry = &arr->data[idx * EVV_ENTRY_WORDS];
     int32_t m = (int32_t)flux_untag_int(entry[EVV_MARKER_OFF]);
-    int64_t clause = entry[EVV_HANDLER_OFF];
+    int64_t clause = flux_handler_clause(entry[EVV_HANDLER_OFF], optag);
 
     flux_yield_yielding    = 1;
     flux_yield_marker      = m;
@@ -251,6 +311,45 @@ int64_t flux_yield_to(int64_t htag, int64_t optag, int64_t arg, int64_t arity) {
     return FLUX_YIELD_SENTINEL;
 }
 
+/*
+ * Call the clause of the pending yield with the composed continuation as its
+ * resume, and return the clause's result.
+ */
+static int64_t flux_run_yield_clause(void) {
+    /* Keep the yield-site evv active for the duration of the clause
+     * call so that any resume → composed-continuation → nested perform
+     * finds the full installed handler chain (slice 5-tr-fix). */
+
+    int64_t clause   = flux_yield_clause;
+    int64_t op_arg   = flux_yield_op_arg;
+    int64_t op_state = flux_yield_op_state;
+    int32_t op_arity = flux_yield_op_arity;
+    int64_t yield_evv = flux_yield_evv;
+
+    /* Build the resume closure from accumulated continuations. */
+    int64_t resume_cont = flux_compose_conts();
+
+    /* Clear yield state so the clause sees a clean slate (it may re-yield). */
+    flux_yield_yielding    = 0;
+    flux_yield_marker      = 0;
+    flux_yield_clause      = 0;
+    flux_yield_op_arg      = 0;
+    flux_yield_op_state    = 0;
+    flux_yield_op_arity    = 0;
+    flux_yield_conts_count = 0;
+    flux_yield_evv         = 0;
+
+    /* Re-install the evv that was active at the yield site so the clause
+     * — including any resume it calls into the composed continuation —
+     * sees the full handler chain the yield was performed under (slice
+     * 5-tr-fix: matters for nested handlers where an inner handle may
+     * have unwound before the outer prompt decides to resume). */
+    current_evv = yield_evv;
+
+    /* Call the handler clause: clause(resume, [args...], [state]). */
+    return flux_call_clause(clause, resume_cont, op_arg, op_arity, op_state);
+}
+
 /*
  * Perform an effect using the direct (tail-resumptive) fast path.
  *
@@ -306,15 +405,22 @@ int32_t flux_direct_resume_marker = 0;
  * next_state aliases *slot — i.e. resume(value, current_state). Reordering
  * to drop-then-dup would risk freeing the new value before the dup if the
  * caller happened to pass the same tagged pointer.
+ *
+ * Every vector on the parent chain that still holds the handler's entry is
+ * updated, so the state survives nested handlers returning.
  */
 static void flux_update_state_for_marker(int32_t marker, int64_t next_state) {
     EvvArray *arr = evv_unbox(current_evv);
     int idx = evv_lookup_by_marker(arr, marker);
-    if (idx >= 0) {
+    while (idx >= 0) {
         int64_t *slot = &arr->data[idx * EVV_ENTRY_WORDS + EVV_STATE_OFF];
         flux_dup(next_state);
         flux_drop(*slot);
         *slot = next_state;
+        /* Handlers installed inside this one hold copies of its entry; the
+         * vector restored when they return is the newest entry's parent. */
+        arr = evv_unbox(arr->data[(arr->count - 1) * EVV_ENTRY_WORDS + EVV_PARENT_OFF]);
+        idx = evv_lookup_by_marker(arr, marker);
     }
 }
 
@@ -329,6 +435,7 @@ static void flux_update_state_for_marker(int32_t marker, int64_t next_state) {
  */
 int64_t flux_resume_mark_called(int64_t value) {
     flux_resume_called += 1;
+    flux_trace_resume(value);
     return value;
 }
 
@@ -365,8 +472,6 @@ int64_t flux_resume_mark_called_closure_entry(int64_t closure_raw, int64_t *args
 }
 
 int64_t flux_perform_direct(int64_t htag, int64_t optag, int64_t arg, int64_t resume, int64_t arity) {
-    (void)optag;  /* reserved for multi-op dispatch */
-
     EvvArray *arr = evv_unbox(current_evv);
     int idx = evv_lookup(arr, htag);
 
@@ -378,7 +483,7 @@ int64_t flux_perform_direct(int64_t htag, int64_t optag, int64_t arg, int64_t re
 
     int64_t *entry = &arr->data[idx * EVV_ENTRY_WORDS];
     int32_t marker = (int32_t)flux_untag_int(entry[EVV_MARKER_OFF]);
-    int64_t clause = entry[EVV_HANDLER_OFF];
+    int64_t clause = flux_handler_clause(entry[EVV_HANDLER_OFF], optag);
     int64_t state = entry[EVV_STATE_OFF];
 
     /* Save & reset the counter so nested performs don't confuse the detector. */
@@ -387,28 +492,22 @@ int64_t flux_perform_direct(int64_t htag, int64_t optag, int64_t arg, int64_t re
     flux_resume_called = 0;
     flux_direct_resume_marker = marker;
 
+    /* Direct call: clause(resume, arg0, ..., argN, [state]). */
+    int64_t result = flux_call_clause(clause, resume, arg, flux_untag_int(arity), state);
+
     /*
-     * Direct call: clause(resume, arg0, ..., argN).
-     *
-     * Today native direct-perform lowering only materializes zero-arg and
-     * one-arg effect operations:
-     *   - arity == 0: clause(resume)
-     *   - arity == 1: clause(resume, arg)
+     * The clause performed an effect handled outside it, e.g. a handler that
+     * forwards to an outer stateful handler. No prompt sits between the
+     * clause and this call, so service the yield here, the same way
+     * flux_yield_prompt services a yield aimed at another handler. The
+     * continuation is the rest of the clause, which ends by resuming us.
      */
-    int64_t argc = flux_untag_int(arity);
-    int64_t result;
-    if (state != 0 && argc <= 0) {
-        int64_t args[2] = { resume, state };
-        result = flux_call_closure_c(clause, args, 2);
-    } else if (state != 0) {
-        int64_t args[3] = { resume, arg, state };
-        result = flux_call_closure_c(clause, args, 3);
-    } else if (argc <= 0) {
-        int64_t args[1] = { resume };
-        result = flux_call_closure_c(clause, args, 1);
-    } else {
-        int64_t args[2] = { resume, arg };
-        result = flux_call_closure_c(clause, args, 2);
+    if (flux_yield_yielding != 0) {
+        int64_t perform_evv = current_evv;
+        while (flux_yield_yielding != 0) {
+            result = flux_run_yield_clause();
+        }
+        current_evv = perform_evv;
     }
 
     /*
@@ -525,6 +624,7 @@ int64_t flux_compose_trampoline_closure_entry(int64_t closure_raw, int64_t *args
 
     int64_t count = flux_untag_int(flux_array_len(conts_arr));
     int64_t result = args_ptr[0];
+    flux_trace_resume(result);
     for (int64_t i = 0; i < count; i++) {
         int64_t cont = flux_array_get(conts_arr, flux_tag_int(i));
         int64_t arg_slot[1] = { result };
@@ -549,7 +649,8 @@ int64_t flux_compose_conts(void) {
     if (flux_yield_conts_count == 0) {
         return flux_make_none();
     }
-    if (flux_yield_conts_count == 1 && flux_yield_op_state == 0) {
+    /* While a traced call runs, keep the trampoline so it can report the resume. */
+    if (flux_yield_conts_count == 1 && flux_yield_op_state == 0 && flux_call_trace_depth == 0) {
         int64_t result = flux_yield_conts[0];
         flux_yield_conts_count = 0;
         return result;
@@ -622,7 +723,6 @@ int64_t flux_yield_prompt(int64_t marker, int64_t saved_evv, int64_t body_result
      * re-yield here and run another iteration. */
     while (flux_yield_yielding != 0) {
         int is_ours = (flux_yield_marker == m);
-        int64_t yield_evv_local = flux_yield_evv;
 
         if (!is_ours) {
             /* Slice 5-tr-nested: the re-yield targets a different handler.
@@ -630,7 +730,7 @@ int64_t flux_yield_prompt(int64_t marker, int64_t saved_evv, int64_t body_result
              * like `handle Inner {} handle Outer {}`. Look up the marker in
              * the yield-site evv: if found, service it inline using its
              * recorded clause and evv. Otherwise propagate. */
-            EvvArray *arr = evv_unbox(yield_evv_local);
+            EvvArray *arr = evv_unbox(flux_yield_evv);
             int idx = evv_lookup_by_marker(arr, flux_yield_marker);
             if (idx < 0) {
                 current_evv = saved_evv;
@@ -642,50 +742,7 @@ int64_t flux_yield_prompt(int64_t marker, int64_t saved_evv, int64_t body_result
              * foreign handler's clause. */
         }
 
-        /* Keep the yield-site evv active for the duration of the clause
-         * call so that any resume → composed-continuation → nested perform
-         * finds the full installed handler chain (slice 5-tr-fix). */
-
-        int64_t clause   = flux_yield_clause;
-        int64_t op_arg   = flux_yield_op_arg;
-        int64_t op_state = flux_yield_op_state;
-        int32_t op_arity = flux_yield_op_arity;
-        int64_t yield_evv = yield_evv_local;
-
-        /* Build the resume closure from accumulated continuations. */
-        int64_t resume_cont = flux_compose_conts();
-
-        /* Clear yield state so the clause sees a clean slate (it may re-yield). */
-        flux_yield_yielding    = 0;
-        flux_yield_marker      = 0;
-        flux_yield_clause      = 0;
-        flux_yield_op_arg      = 0;
-        flux_yield_op_state    = 0;
-        flux_yield_op_arity    = 0;
-        flux_yield_conts_count = 0;
-        flux_yield_evv         = 0;
-
-        /* Re-install the evv that was active at the yield site so the clause
-         * — including any resume it calls into the composed continuation —
-         * sees the full handler chain the yield was performed under (slice
-         * 5-tr-fix: matters for nested handlers where an inner handle may
-         * have unwound before the outer prompt decides to resume). */
-        current_evv = yield_evv;
-
-        /* Call the handler clause: clause(resume, [arg], [state]). */
-        if (op_state != 0 && op_arity <= 0) {
-            int64_t args[2] = { resume_cont, op_state };
-            result = flux_call_closure_c(clause, args, 2);
-        } else if (op_state != 0) {
-            int64_t args[3] = { resume_cont, op_arg, op_state };
-            result = flux_call_closure_c(clause, args, 3);
-        } else if (op_arity <= 0) {
-            int64_t args[1] = { resume_cont };
-            result = flux_call_closure_c(clause, args, 1);
-        } else {
-            int64_t args[2] = { resume_cont, op_arg };
-            result = flux_call_closure_c(clause, args, 2);
-        }
+        result = flux_run_yield_clause();
         /* Loop: if the clause re-yielded (resume inside triggered a nested
          * perform targeting this same handler), iterate. Otherwise exit. */
     }
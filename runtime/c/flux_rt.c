@@ -265,6 +265,8 @@ void flux_print_space(int64_t val) {
 /* ── I/O ────────────────────────────────────────────────────────────── */
 
 int64_t flux_read_line(void) {
+    int64_t result;
+    if (flux_effect_replay("read_stdin", NULL, 0, &result)) return result;
     char buf[4096];
     if (!fgets(buf, sizeof(buf), stdin)) {
         return flux_string_new("", 0);
@@ -274,15 +276,19 @@ int64_t flux_read_line(void) {
     if (len > 0 && buf[len - 1] == '\n') {
         buf[--len] = '\0';
     }
-    return flux_string_new(buf, (uint32_t)len);
+    result = flux_string_new(buf, (uint32_t)len);
+    flux_effect_record("read_stdin", NULL, 0, result);
+    return result;
 }
 
-int64_t flux_read_file(int64_t path) {
+/* Reads the file at `path` for the builtin `op`, which names it in errors. */
+static int64_t read_file_contents(int64_t path, const char *op) {
     const char *path_str = flux_string_data(path);
     uint32_t    path_len = flux_string_len(path);
     char        err_buf[256];
 
-    /* Build a VM-style error message and abort through the runtime panic path. */
+    /* Build a VM-style error message, log it for --record and abort through
+     * the runtime panic path. */
     #define FLUX_READ_FILE_PANIC(fmt, ...)                                           \
         do {                                                                         \
             int written = snprintf(err_buf, sizeof(err_buf), fmt, __VA_ARGS__);      \
@@ -291,13 +297,14 @@ int64_t flux_read_file(int64_t path) {
             }                                                                        \
             size_t msg_len = (size_t)written;                                        \
             if (msg_len >= sizeof(err_buf)) msg_len = sizeof(err_buf) - 1;           \
+            flux_effect_record_error(op, &path, 1, err_buf, (uint32_t)msg_len);      \
             flux_panic(flux_string_new(err_buf, (uint32_t)msg_len));                 \
         } while (0)
 
     /* Null-terminate the path (it may not be). */
     char *cpath = (char *)malloc(path_len + 1);
     if (!cpath) {
-        FLUX_READ_FILE_PANIC("read_file failed for '%.*s': out of memory", (int)path_len, path_str);
+        FLUX_READ_FILE_PANIC("%s failed for '%.*s': out of memory", op, (int)path_len, path_str);
     }
     memcpy(cpath, path_str, path_len);
     cpath[path_len] = '\0';
@@ -306,7 +313,8 @@ int64_t flux_read_file(int64_t path) {
     if (!f) {
         int saved_errno = errno;
         FLUX_READ_FILE_PANIC(
-            "read_file failed for '%s': %s (os error %d)",
+            "%s failed for '%s': %s (os error %d)",
+            op,
             cpath,
             strerror(saved_errno),
             saved_errno
@@ -321,7 +329,8 @@ int64_t flux_read_file(int64_t path) {
         int saved_errno = errno;
         fclose(f);
         FLUX_READ_FILE_PANIC(
-            "read_file failed for '%s': %s (os error %d)",
+            "%s failed for '%s': %s (os error %d)",
+            op,
             cpath,
             strerror(saved_errno),
             saved_errno
@@ -331,7 +340,7 @@ int64_t flux_read_file(int64_t path) {
     char *contents = (char *)malloc((size_t)fsize);
     if (!contents) {
         fclose(f);
-        FLUX_READ_FILE_PANIC("read_file failed for '%s': out of memory", cpath);
+        FLUX_READ_FILE_PANIC("%s failed for '%s': out of memory", op, cpath);
     }
 
     size_t nread = fread(contents, 1, (size_t)fsize, f);
@@ -341,7 +350,8 @@ int64_t flux_read_file(int64_t path) {
     if (nread != (size_t)fsize) {
         free(contents);
         FLUX_READ_FILE_PANIC(
-            "read_file failed for '%s': %s (os error %d)",
+            "%s failed for '%s': %s (os error %d)",
+            op,
             cpath,
             read_error ? strerror(saved_errno) : "short read",
             read_error ? saved_errno : 0
@@ -356,6 +366,14 @@ int64_t flux_read_file(int64_t path) {
     #undef FLUX_READ_FILE_PANIC
 }
 
+int64_t flux_read_file(int64_t path) {
+    int64_t result;
+    if (flux_effect_replay("read_file", &path, 1, &result)) return result;
+    result = read_file_contents(path, "read_file");
+    flux_effect_record("read_file", &path, 1, result);
+    return result;
+}
+
 int64_t flux_write_file(int64_t path, int64_t content) {
     const char *path_str    = flux_string_data(path);
     uint32_t    path_len    = flux_string_len(path);
@@ -1134,6 +1152,8 @@ int64_t flux_assert_throws(int64_t thunk, int64_t expected_msg) {
 }
 
 int64_t flux_clock_now(void) {
+    int64_t logged;
+    if (flux_effect_replay("clock_now", NULL, 0, &logged)) return logged;
 #if defined(_MSC_VER) || defined(_WIN32)
     /* Windows: use QueryPerformanceCounter for monotonic time. */
     LARGE_INTEGER freq, counter;
@@ -1145,7 +1165,9 @@ int64_t flux_clock_now(void) {
     clock_gettime(CLOCK_MONOTONIC, &ts);
     int64_t ms = (int64_t)ts.tv_sec * 1000 + (int64_t)ts.tv_nsec / 1000000;
 #endif
-    return flux_tag_int(ms);
+    int64_t result = flux_tag_int(ms);
+    flux_effect_record("clock_now", NULL, 0, result);
+    return result;
 }
 
 /* ── Extended string/I/O helpers ────────────────────────────────────── */
@@ -1490,7 +1512,9 @@ int64_t flux_to_string_value(int64_t val) {
 
 int64_t flux_read_lines(int64_t path) {
     /* Read file, split on newlines, return as Array (matching VM semantics). */
-    int64_t content = flux_read_file(path);
+    int64_t logged;
+    if (flux_effect_replay("read_lines", &path, 1, &logged)) return logged;
+    int64_t content = read_file_contents(path, "read_lines");
     if (content == FLUX_NONE) {
         return flux_array_new(NULL, 0);
     }
@@ -1524,6 +1548,7 @@ int64_t flux_read_lines(int64_t path) {
 
     int64_t result = flux_array_new(lines, (int32_t)li);
     free(lines);
+    flux_effect_record("read_lines", &path, 1, result);
     return result;
 }
 
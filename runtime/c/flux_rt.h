@@ -171,6 +171,46 @@ int  flux_rc_is_unique(int64_t val);
 size_t flux_gc_allocated(void);
 size_t flux_gc_num_allocs(void);
 
+/* ── Profiling (prof.c) ─────────────────────────────────────────────── */
+/*
+ * Only called by binaries built with `flux --native --prof`: the backend
+ * brackets each Flux function with enter/exit, and allocations are counted
+ * while flux_prof_active is set.
+ */
+
+extern int32_t flux_prof_active;
+void flux_prof_enter(const char *name);
+void flux_prof_exit(void);
+void flux_prof_alloc(int32_t obj_tag);
+
+/* ── Call tracing (trace.c) ─────────────────────────────────────────── */
+/*
+ * Only called by binaries built with `flux --native --trace-calls`: the
+ * backend reports entry, return and tail call of the traced functions and
+ * every effect operation.  `kind` is 0 call, 1 return, 2 tail call,
+ * 3 perform; `raw` marks unboxed worker integers.  Handlers report
+ * resumptions through flux_trace_resume.
+ */
+
+extern int32_t flux_call_trace_depth;
+void flux_trace_event(int32_t kind, const char *label, const int64_t *values,
+                      int32_t count, int32_t raw);
+void flux_trace_resume(int64_t value);
+
+/* ── Effect record/replay (effect_log.c) ────────────────────────────── */
+/*
+ * The builtins that read the outside world call flux_effect_replay first; when
+ * it returns nonzero, *result holds the logged answer.  Otherwise they do the
+ * work and report the outcome with flux_effect_record or
+ * flux_effect_record_error.  All three do nothing unless the binary runs under
+ * `flux --record` or `flux --replay`.
+ */
+
+int flux_effect_replay(const char *op, const int64_t *args, int32_t count, int64_t *result);
+void flux_effect_record(const char *op, const int64_t *args, int32_t count, int64_t result);
+void flux_effect_record_error(const char *op, const int64_t *args, int32_t count,
+                              const char *message, uint32_t len);
+
 /* ── I/O ────────────────────────────────────────────────────────────── */
 
 void    flux_print(int64_t value);
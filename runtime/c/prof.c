@@ -0,0 +1,201 @@
+/*
+ * prof.c — Cost-centre profiling for native binaries (`flux --native --prof`).
+ *
+ * When profiling is requested, the LLVM backend brackets every Flux function
+ * with flux_prof_enter(name) on entry and flux_prof_exit() before it returns
+ * or tail-calls.  The runtime keeps a call tree with one node per distinct
+ * stack of functions, charges elapsed time to the node on top of the stack,
+ * and counts the heap allocations made while it is on top.
+ *
+ * At exit the tree is written to $FLUX_PROF_OUTPUT (default flux-prof.out),
+ * one line per node:
+ *
+ *   <self_ns> <entries> <adts> <closures> <arrays> <cons> <hamt>\t<f1;f2;...>
+ *
+ * which `flux --prof` reads back to render the same reports as the VM.
+ */
+
+// Expose POSIX APIs (clock_gettime, etc.) on Linux/glibc.
+#if !defined(_POSIX_C_SOURCE) && !defined(__APPLE__)
+#define _POSIX_C_SOURCE 199309L
+#endif
+
+#include "flux_rt.h"
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <time.h>
+#if defined(_MSC_VER) || defined(_WIN32)
+#include <windows.h>
+#endif
+
+/* Allocation kinds, in the column order of the output file. */
+enum {
+    PROF_ALLOC_ADT,
+    PROF_ALLOC_CLOSURE,
+    PROF_ALLOC_ARRAY,
+    PROF_ALLOC_CONS,
+    PROF_ALLOC_HAMT,
+    PROF_ALLOC_KINDS
+};
+
+typedef struct {
+    const char *name;     /* NULL for the root */
+    int32_t parent;
+    int32_t first_child;
+    int32_t next_sibling;
+    uint64_t entries;
+    uint64_t self_ns;
+    uint64_t allocs[PROF_ALLOC_KINDS];
+} ProfNode;
+
+int32_t flux_prof_active = 0;
+
+static ProfNode *nodes;
+static size_t node_count;
+static size_t node_capacity;
+
+static int32_t *stack;
+static size_t depth;
+static size_t stack_capacity;
+
+static uint64_t last_ns;
+
+static uint64_t prof_now_ns(void) {
+#if defined(_MSC_VER) || defined(_WIN32)
+    LARGE_INTEGER freq, counter;
+    QueryPerformanceFrequency(&freq);
+    QueryPerformanceCounter(&counter);
+    return (uint64_t)(counter.QuadPart * 1000000000.0 / freq.QuadPart);
+#else
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return (uint64_t)ts.tv_sec * 1000000000u + (uint64_t)ts.tv_nsec;
+#endif
+}
+
+static void *prof_grow(void *items, size_t *capacity, size_t item_size) {
+    size_t next = *capacity ? *capacity * 2 : 256;
+    void *grown = realloc(items, next * item_size);
+    if (!grown) {
+        fprintf(stderr, "flux_prof: out of memory\n");
+        abort();
+    }
+    *capacity = next;
+    return grown;
+}
+
+static int32_t prof_new_node(const char *name, int32_t parent) {
+    if (node_count == node_capacity) {
+        nodes = (ProfNode *)prof_grow(nodes, &node_capacity, sizeof(ProfNode));
+    }
+    int32_t id = (int32_t)node_count++;
+    ProfNode *node = &nodes[id];
+    memset(node, 0, sizeof(*node));
+    node->name = name;
+    node->parent = parent;
+    node->first_child = -1;
+    node->next_sibling = -1;
+    if (parent >= 0) {
+        node->next_sibling = nodes[parent].first_child;
+        nodes[parent].first_child = id;
+    }
+    return id;
+}
+
+static int32_t prof_top(void) {
+    return depth ? stack[depth - 1] : 0;
+}
+
+/* Charges the time since the last event to the node on top of the stack. */
+static void prof_charge(void) {
+    uint64_t now = prof_now_ns();
+    nodes[prof_top()].self_ns += now - last_ns;
+    last_ns = now;
+}
+
+static void prof_write_path(FILE *out, int32_t id) {
+    if (nodes[id].parent > 0) {
+        prof_write_path(out, nodes[id].parent);
+        fputc(';', out);
+    }
+    fputs(nodes[id].name, out);
+}
+
+static void prof_write(void) {
+    if (!flux_prof_active) return;
+    prof_charge();
+    flux_prof_active = 0;
+
+    const char *path = getenv("FLUX_PROF_OUTPUT");
+    if (!path || !*path) path = "flux-prof.out";
+    FILE *out = fopen(path, "w");
+    if (!out) {
+        fprintf(stderr, "flux_prof: cannot write %s\n", path);
+        return;
+    }
+    for (size_t id = 1; id < node_count; id++) {
+        ProfNode *node = &nodes[id];
+        fprintf(out, "%llu %llu", (unsigned long long)node->self_ns,
+                (unsigned long long)node->entries);
+        for (int kind = 0; kind < PROF_ALLOC_KINDS; kind++) {
+            fprintf(out, " %llu", (unsigned long long)node->allocs[kind]);
+        }
+        fputc('\t', out);
+        prof_write_path(out, (int32_t)id);
+        fputc('\n', out);
+    }
+    fclose(out);
+}
+
+static void prof_start(void) {
+    prof_new_node(NULL, -1);
+    last_ns = prof_now_ns();
+    flux_prof_active = 1;
+    atexit(prof_write);
+}
+
+void flux_prof_enter(const char *name) {
+    if (!flux_prof_active) {
+        if (node_count) return; /* already written at exit */
+        prof_start();
+    }
+    prof_charge();
+
+    int32_t parent = prof_top();
+    int32_t child = nodes[parent].first_child;
+    while (child >= 0 && nodes[child].name != name) {
+        child = nodes[child].next_sibling;
+    }
+    if (child < 0) child = prof_new_node(name, parent);
+    nodes[child].entries++;
+
+    if (depth == stack_capacity) {
+        stack = (int32_t *)prof_grow(stack, &stack_capacity, sizeof(int32_t));
+    }
+    stack[depth++] = child;
+}
+
+void flux_prof_exit(void) {
+    if (!flux_prof_active || depth == 0) return;
+    prof_charge();
+    depth--;
+}
+
+/*
+ * Counts one heap allocation against the node on top of the stack.  Cons
+ * cells share FLUX_OBJ_ADT with other constructors, so natively they are
+ * counted as ADT values; HAMT nodes are the untagged allocations.
+ */
+void flux_prof_alloc(int32_t obj_tag) {
+    if (!flux_prof_active) return;
+    int kind;
+    switch (obj_tag) {
+    case FLUX_OBJ_ADT:     kind = PROF_ALLOC_ADT; break;
+    case FLUX_OBJ_CLOSURE: kind = PROF_ALLOC_CLOSURE; break;
+    case FLUX_OBJ_ARRAY:   kind = PROF_ALLOC_ARRAY; break;
+    case 0:                kind = PROF_ALLOC_HAMT; break;
+    default:               return;
+    }
+    nodes[prof_top()].allocs[kind]++;
+}
@@ -128,6 +128,7 @@ void *flux_gc_alloc_header(uint32_t payload_size, uint8_t scan_fsize, uint8_t ob
 
     gc_total_allocated += aligned;
     gc_num_allocs++;
+    if (flux_prof_active) flux_prof_alloc(obj_tag);
 
     return payload;
 }
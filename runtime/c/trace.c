@@ -0,0 +1,210 @@
+/*
+ * trace.c — Call tracing for native binaries (`flux --native --trace-calls`).
+ *
+ * When tracing is requested, the LIR trace pass (src/lir/trace.rs) reports
+ * entry, return and tail call of each selected function and every effect
+ * operation through flux_trace_event.  Handlers report resumptions through
+ * flux_trace_resume.  Like the VM tracer, operations and resumptions are only
+ * logged while a traced call is running.
+ *
+ * Events are written as text to stderr, indented by call depth:
+ *
+ *   → build(2)
+ *     perform Audit.log("bob")
+ *     resume 3
+ *   ← build = [2, 1]
+ *
+ * or, when $FLUX_TRACE_OUTPUT names a file, as JSON lines in that file:
+ *
+ *   {"event":"call","depth":0,"function":"build","args":["2"]}
+ */
+
+#include "flux_rt.h"
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+enum {
+    TRACE_CALL,
+    TRACE_RETURN,
+    TRACE_TAIL_CALL,
+    TRACE_PERFORM,
+    TRACE_RESUME
+};
+
+/* Number of traced calls still running; read by flux_compose_conts. */
+int32_t flux_call_trace_depth = 0;
+
+static const char **active;
+static size_t active_capacity;
+
+static FILE *out;
+static int json;
+
+static void trace_close(void) {
+    if (out) fclose(out);
+}
+
+static void trace_open(void) {
+    if (out) return;
+    const char *path = getenv("FLUX_TRACE_OUTPUT");
+    if (path && *path) {
+        out = fopen(path, "w");
+        if (out) {
+            json = 1;
+            atexit(trace_close);
+            return;
+        }
+        fprintf(stderr, "flux_trace: cannot write %s\n", path);
+    }
+    out = stderr;
+}
+
+/* Writes `text` as a JSON string literal. */
+static void trace_json_string(const char *text, size_t len) {
+    fputc('"', out);
+    for (size_t i = 0; i < len; i++) {
+        unsigned char c = (unsigned char)text[i];
+        switch (c) {
+        case '"':  fputs("\\\"", out); break;
+        case '\\': fputs("\\\\", out); break;
+        case '\n': fputs("\\n", out); break;
+        case '\r': fputs("\\r", out); break;
+        case '\t': fputs("\\t", out); break;
+        default:
+            if (c < 0x20) {
+                fprintf(out, "\\u%04x", c);
+            } else {
+                fputc(c, out);
+            }
+        }
+    }
+    fputc('"', out);
+}
+
+/* Writes one value as Flux renders it, quoted when writing JSON. */
+static void trace_value(int64_t value, int32_t raw) {
+    int64_t text = flux_to_string(raw ? flux_tag_int(value) : value);
+    const char *data = flux_string_data(text);
+    uint32_t len = flux_string_len(text);
+    if (json) {
+        trace_json_string(data, len);
+    } else {
+        fwrite(data, 1, len, out);
+    }
+    flux_drop(text);
+}
+
+static void trace_values(const int64_t *values, int32_t count, int32_t raw) {
+    fputs(json ? "[" : "(", out);
+    for (int32_t i = 0; i < count; i++) {
+        if (i > 0) fputs(json ? "," : ", ", out);
+        trace_value(values[i], raw);
+    }
+    fputs(json ? "]" : ")", out);
+}
+
+static void trace_write(int32_t kind, const char *label, const int64_t *values,
+                        int32_t count, int32_t raw) {
+    static const char *const names[] = {"call", "return", "tail_call", "perform", "resume"};
+    int32_t depth = flux_call_trace_depth;
+    if (json) {
+        fprintf(out, "{\"event\":\"%s\",\"depth\":%d", names[kind], depth);
+        switch (kind) {
+        case TRACE_CALL:
+        case TRACE_RETURN:
+        case TRACE_TAIL_CALL:
+            fputs(",\"function\":", out);
+            trace_json_string(label, strlen(label));
+            break;
+        case TRACE_PERFORM:
+            fputs(",\"operation\":", out);
+            trace_json_string(label, strlen(label));
+            break;
+        }
+        switch (kind) {
+        case TRACE_CALL:
+        case TRACE_PERFORM:
+            fputs(",\"args\":", out);
+            trace_values(values, count, raw);
+            break;
+        case TRACE_RETURN:
+        case TRACE_RESUME:
+            fputs(",\"value\":", out);
+            trace_value(values[0], raw);
+            break;
+        }
+        fputs("}\n", out);
+        return;
+    }
+
+    for (int32_t i = 0; i < depth; i++) fputs("  ", out);
+    switch (kind) {
+    case TRACE_CALL:
+        fprintf(out, "\xe2\x86\x92 %s", label);
+        trace_values(values, count, raw);
+        break;
+    case TRACE_RETURN:
+        fprintf(out, "\xe2\x86\x90 %s = ", label);
+        trace_value(values[0], raw);
+        break;
+    case TRACE_TAIL_CALL:
+        fprintf(out, "\xe2\x86\x90 %s (tail call)", label);
+        break;
+    case TRACE_PERFORM:
+        fprintf(out, "perform %s", label);
+        trace_values(values, count, raw);
+        break;
+    case TRACE_RESUME:
+        fputs("resume ", out);
+        trace_value(values[0], raw);
+        break;
+    }
+    fputc('\n', out);
+}
+
+/*
+ * Closes the innermost running call of `label`.  Calls above it were
+ * abandoned by a handler that did not resume, so they are closed with it.
+ */
+static void trace_leave(const char *label) {
+    for (int32_t i = flux_call_trace_depth - 1; i >= 0; i--) {
+        if (strcmp(active[i], label) == 0) {
+            flux_call_trace_depth = i;
+            return;
+        }
+    }
+}
+
+void flux_trace_event(int32_t kind, const char *label, const int64_t *values,
+                      int32_t count, int32_t raw) {
+    trace_open();
+    switch (kind) {
+    case TRACE_CALL:
+        trace_write(kind, label, values, count, raw);
+        if ((size_t)flux_call_trace_depth == active_capacity) {
+            active_capacity = active_capacity ? active_capacity * 2 : 64;
+            active = (const char **)realloc(active, active_capacity * sizeof(*active));
+            if (!active) {
+                fprintf(stderr, "flux_trace: out of memory\n");
+                abort();
+            }
+        }
+        active[flux_call_trace_depth++] = label;
+        break;
+    case TRACE_RETURN:
+    case TRACE_TAIL_CALL:
+        trace_leave(label);
+        trace_write(kind, label, values, count, raw);
+        break;
+    case TRACE_PERFORM:
+        if (flux_call_trace_depth > 0) trace_write(kind, label, values, count, raw);
+        break;
+    }
+}
+
+void flux_trace_resume(int64_t value) {
+    if (flux_call_trace_depth > 0) {
+        trace_write(TRACE_RESUME, NULL, &value, 1, 0);
+    }
+}
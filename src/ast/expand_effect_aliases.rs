@@ -218,7 +218,8 @@ fn expand_expr(expr: &mut Expression, aliases: &HashMap<Identifier, EffectExpr>)
         | Expression::EmptyList { .. }
         | Expression::None { .. }
         | Expression::NamedConstructor { .. }
-        | Expression::Spread { .. } => {}
+        | Expression::Spread { .. }
+        | Expression::Hole { .. } => {}
     }
 }
 
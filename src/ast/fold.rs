@@ -508,6 +508,7 @@ pub fn fold_expr<F: Folder + ?Sized>(folder: &mut F, expr: Expression) -> Expres
             span,
             id,
         },
+        Expression::Hole { name, span, id } => Expression::Hole { name, span, id },
     }
 }
 
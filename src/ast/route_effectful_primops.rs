@@ -513,7 +513,8 @@ fn route_expr(
         | Expression::String { .. }
         | Expression::Boolean { .. }
         | Expression::EmptyList { .. }
-        | Expression::None { .. } => {}
+        | Expression::None { .. }
+        | Expression::Hole { .. } => {}
     }
     changed
 }
@@ -907,7 +908,8 @@ fn collect_default_effects_expr(
         | Expression::String { .. }
         | Expression::Boolean { .. }
         | Expression::EmptyList { .. }
-        | Expression::None { .. } => {}
+        | Expression::None { .. }
+        | Expression::Hole { .. } => {}
     }
 }
 
@@ -176,6 +176,9 @@ impl<'a> InferCtx<'a> {
                 span,
                 ..
             } => self.infer_spread_expression(base, overrides, *span),
+            Expression::Hole { name, span, .. } => {
+                self.infer_hole_expression(expr_id, *name, *span)
+            }
             // Fallback guards against future Expression variants
             #[allow(unreachable_patterns)]
             _ => self.infer_unsupported_expression(expr),
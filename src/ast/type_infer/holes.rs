@@ -0,0 +1,283 @@
+use crate::{
+    ast::visit::{self, Visitor},
+    diagnostics::compiler_errors::TYPED_HOLE,
+};
+
+use super::*;
+
+/// Maximum number of candidate fillers listed for one typed hole.
+const MAX_HOLE_CANDIDATES: usize = 5;
+
+/// A typed hole met during inference (Proposal 0083).
+///
+/// The hole's type starts as a fresh variable and is only known once the
+/// whole program has been solved, so the diagnostic is built afterwards from
+/// the context captured here.
+#[derive(Debug, Clone)]
+pub(super) struct HoleSite {
+    id: ExprId,
+    name: Option<Identifier>,
+    span: Span,
+    ty: InferType,
+    effects: InferEffectRow,
+    locals: Vec<(Identifier, Scheme)>,
+}
+
+/// Where a candidate filler is bound, in the order candidates are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CandidateScope {
+    /// A parameter or local binding visible at the hole.
+    Local,
+    /// A top-level binding defined in the module being checked.
+    Module,
+    /// A constructor, builtin or imported binding.
+    Other,
+}
+
+/// One in-scope binding whose type unifies with a hole.
+struct HoleCandidate {
+    name: String,
+    rendered: String,
+    exact: bool,
+    scope: CandidateScope,
+    generality: usize,
+}
+
+impl<'a> InferCtx<'a> {
+    /// Infer a typed hole: a fresh type variable that the surrounding
+    /// expression constrains. The site is recorded for [`Self::report_holes`].
+    ///
+    /// Check mode may infer the same expression twice; the second visit
+    /// reuses the first variable so both constraints land on one hole.
+    pub(super) fn infer_hole_expression(
+        &mut self,
+        id: ExprId,
+        name: Option<Identifier>,
+        span: Span,
+    ) -> InferType {
+        if let Some(site) = self.holes.iter().find(|site| site.id == id) {
+            return site.ty.clone();
+        }
+        let ty = self.env.alloc_infer_type_var();
+        let effects = self.current_ambient_effect_row();
+        let locals = self
+            .env
+            .local_bindings()
+            .into_iter()
+            .map(|(name, scheme)| (name, scheme.clone()))
+            .collect();
+        self.holes.push(HoleSite {
+            id,
+            name,
+            span,
+            ty: ty.clone(),
+            effects,
+            locals,
+        });
+        ty
+    }
+
+    /// Emit one `W204` warning per recorded hole, using the final
+    /// substitution for the expected type, effect row and local bindings.
+    pub(super) fn report_holes(&mut self) {
+        let holes = std::mem::take(&mut self.holes);
+        for site in &holes {
+            let diagnostic = self.hole_diagnostic(site);
+            self.errors.push(diagnostic);
+        }
+    }
+
+    /// Build the `W204` warning for one hole: the expected type in the
+    /// message, then the allowed effects, local bindings and candidates.
+    fn hole_diagnostic(&mut self, site: &HoleSite) -> Diagnostic {
+        let expected = site.ty.apply_type_subst(&self.subst);
+        let hole = match site.name {
+            Some(name) => format!("?{}", self.interner.resolve(name)),
+            None => "?".to_string(),
+        };
+        let expected_display = self.display_type(&expected);
+        let mut diagnostic = Diagnostic::make_warning_from_code(
+            &TYPED_HOLE,
+            &[&hole, &expected_display],
+            self.file_path.clone(),
+            site.span,
+        )
+        .with_note(format!(
+            "effects allowed here: {}",
+            self.describe_hole_effects(&site.effects.apply_row_subst(&self.subst))
+        ));
+
+        if !site.locals.is_empty() {
+            let locals = site
+                .locals
+                .iter()
+                .map(|(name, scheme)| {
+                    let ty = scheme.infer_type.apply_type_subst(&self.subst);
+                    format!(
+                        "    {}: {}",
+                        self.interner.resolve(*name),
+                        self.display_type(&ty)
+                    )
+                })
+                .collect::<Vec<_>>();
+            diagnostic = diagnostic.with_note(format!("local bindings:\n{}", locals.join("\n")));
+        }
+
+        let candidates = self.hole_candidates(site, &expected);
+        if !candidates.is_empty() {
+            let lines = candidates
+                .iter()
+                .map(|candidate| format!("    {}: {}", candidate.name, candidate.rendered))
+                .collect::<Vec<_>>();
+            diagnostic = diagnostic.with_help(format!("these fit the hole:\n{}", lines.join("\n")));
+        }
+        diagnostic
+    }
+
+    /// Render the ambient effect row of a hole for its diagnostic.
+    fn describe_hole_effects(&self, row: &InferEffectRow) -> String {
+        let mut names = row
+            .concrete()
+            .iter()
+            .map(|effect| self.interner.resolve(*effect).to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        match (names.is_empty(), row.tail().is_some()) {
+            (true, false) => "none".to_string(),
+            (true, true) => "any (inferred for the enclosing function)".to_string(),
+            (false, false) => names.join(", "),
+            (false, true) => format!(
+                "{}, and any inferred for the enclosing function",
+                names.join(", ")
+            ),
+        }
+    }
+
+    /// Collect up to [`MAX_HOLE_CANDIDATES`] bindings whose types unify with
+    /// the hole. Local bindings come from the hole site; functions and
+    /// constructors come from the final top-level environment.
+    ///
+    /// Exact fits, which leave the expected type unchanged, are listed first,
+    /// then locals and this module's functions, then the least polymorphic
+    /// bindings.
+    fn hole_candidates(&mut self, site: &HoleSite, expected: &InferType) -> Vec<HoleCandidate> {
+        let local_names: HashSet<Identifier> = site.locals.iter().map(|(name, _)| *name).collect();
+        let globals = self
+            .env
+            .visible_bindings()
+            .filter(|(name, _)| !local_names.contains(name))
+            .map(|(name, scheme)| {
+                let scope = if self.env.lookup_span(name).is_some() {
+                    CandidateScope::Module
+                } else {
+                    CandidateScope::Other
+                };
+                (name, scheme.clone(), scope)
+            })
+            .collect::<Vec<_>>();
+        let bindings = site
+            .locals
+            .iter()
+            .map(|(name, scheme)| (*name, scheme.clone(), CandidateScope::Local))
+            .chain(globals);
+
+        let mut candidates = bindings
+            .filter_map(|(name, scheme, scope)| {
+                self.hole_candidate(name, &scheme, scope, expected, site.span)
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| {
+            b.exact
+                .cmp(&a.exact)
+                .then(a.scope.cmp(&b.scope))
+                .then(a.generality.cmp(&b.generality))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        candidates.truncate(MAX_HOLE_CANDIDATES);
+        candidates
+    }
+
+    /// Check one binding against the expected hole type.
+    ///
+    /// When the expected type is still an unconstrained variable every binding
+    /// would unify, so only exact fits are kept.
+    fn hole_candidate(
+        &mut self,
+        name: Identifier,
+        scheme: &Scheme,
+        scope: CandidateScope,
+        expected: &InferType,
+        span: Span,
+    ) -> Option<HoleCandidate> {
+        let display_name = self.interner.resolve(name);
+        if display_name.starts_with("__") {
+            return None;
+        }
+        let scheme = Scheme {
+            forall: scheme.forall.clone(),
+            constraints: scheme.constraints.clone(),
+            infer_type: scheme.infer_type.apply_type_subst(&self.subst),
+        };
+        if scope != CandidateScope::Local && matches!(scheme.infer_type, InferType::Var(_)) {
+            return None;
+        }
+        let (candidate_ty, _, _) = scheme.instantiate(&mut self.env.counter);
+        let mut fresh_row_var = self.env.counter;
+        let subst = unify_core(
+            expected,
+            &candidate_ty,
+            &TypeSubst::empty(),
+            span,
+            &mut fresh_row_var,
+            &HashSet::new(),
+        )
+        .ok()?;
+        self.env.counter = fresh_row_var.max(self.env.counter);
+        let exact = expected
+            .free_type_vars()
+            .iter()
+            .all(|var| matches!(subst.get(*var), None | Some(InferType::Var(_))));
+        if matches!(expected, InferType::Var(_)) && !exact {
+            return None;
+        }
+        let rendered = if scope == CandidateScope::Local {
+            self.display_type(&scheme.infer_type)
+        } else {
+            render_scheme_canonical(self.interner, &scheme)
+        };
+        Some(HoleCandidate {
+            name: display_name.to_string(),
+            rendered,
+            exact,
+            scope,
+            generality: scheme.forall.len(),
+        })
+    }
+}
+
+/// Returns whether `program` contains a typed hole. The drivers never reuse
+/// cached artifacts for such modules, so every run reports the holes again.
+pub fn program_has_typed_holes(program: &Program) -> bool {
+    let mut finder = HoleFinder { found: false };
+    finder.visit_program(program);
+    finder.found
+}
+
+/// Visitor that stops at the first [`Expression::Hole`].
+struct HoleFinder {
+    found: bool,
+}
+
+impl<'ast> Visitor<'ast> for HoleFinder {
+    /// Record a hole, or keep walking until one is found.
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        if self.found {
+            return;
+        }
+        if matches!(expr, Expression::Hole { .. }) {
+            self.found = true;
+            return;
+        }
+        visit::walk_expr(self, expr);
+    }
+}
@@ -47,6 +47,7 @@ mod display;
 mod effects;
 mod expression;
 mod function;
+mod holes;
 mod pattern_coverage;
 mod pattern_coverage_adapter;
 mod solver;
@@ -56,6 +57,7 @@ mod unification;
 
 pub(crate) type BindingSpanKey = (usize, usize, usize, usize);
 pub use display::{display_infer_type, render_scheme_canonical};
+pub use holes::program_has_typed_holes;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Shared type definitions
@@ -187,6 +189,9 @@ struct InferCtx<'a> {
     /// use sites. Surviving unresolved vars from this set are expected to be
     /// resolved by later call-site unification and should not trigger E430.
     instantiated_expr_vars: HashSet<TypeVarId>,
+    /// Typed holes (`?name`) seen so far, reported once inference has
+    /// finished (Proposal 0083).
+    holes: Vec<holes::HoleSite>,
     /// Rigid (skolem) type variables introduced by a declared signature
     /// (Proposal 0159). A skolem cannot be unified with anything other than
     /// itself; `unify_core` enforces this inline via the threaded
@@ -278,6 +283,7 @@ impl<'a> InferCtx<'a> {
             deferred_constraints: Vec::new(),
             fallback_vars: HashSet::new(),
             instantiated_expr_vars: HashSet::new(),
+            holes: Vec::new(),
             skolem_vars: HashSet::new(),
             skolem_names: HashMap::new(),
             class_env: None,
@@ -544,6 +550,7 @@ pub fn infer_program(
     init_class_env(&mut ctx, config.class_env, interner);
     ctx.infer_program(program);
     ctx.solve_deferred_constraints();
+    ctx.report_holes();
     build_infer_result(ctx)
 }
 
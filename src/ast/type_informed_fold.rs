@@ -455,6 +455,7 @@ fn set_expr_span(expr: &mut Expression, span: Span) {
         | Expression::Handle { span: s, .. }
         | Expression::Sealing { span: s, .. }
         | Expression::NamedConstructor { span: s, .. }
-        | Expression::Spread { span: s, .. } => *s = span,
+        | Expression::Spread { span: s, .. }
+        | Expression::Hole { span: s, .. } => *s = span,
     }
 }
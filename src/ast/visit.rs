@@ -408,6 +408,11 @@ pub fn walk_expr<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast E
                 }
             }
         }
+        Expression::Hole {
+            name: _,
+            span: _,
+            id: _,
+        } => {}
     }
 }
 
@@ -31,6 +31,13 @@ pub struct InstructionLocation {
     pub location: Option<Location>,
 }
 
+/// A named local slot, as an offset from the frame's base pointer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalName {
+    pub index: usize,
+    pub name: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct FunctionDebugInfo {
     pub name: Option<String>,
@@ -38,6 +45,10 @@ pub struct FunctionDebugInfo {
     pub locations: Vec<InstructionLocation>,
     pub boundary_location: Option<Location>,
     pub effect_summary: EffectSummary,
+    /// Source names of parameters and `let` bindings; unnamed temporaries are left out.
+    pub locals: Vec<LocalName>,
+    /// Source names of captured variables, in closure free-variable order.
+    pub captures: Vec<String>,
 }
 
 impl FunctionDebugInfo {
@@ -52,6 +63,8 @@ impl FunctionDebugInfo {
             locations,
             boundary_location: None,
             effect_summary: EffectSummary::Unknown,
+            locals: Vec::new(),
+            captures: Vec::new(),
         }
     }
 
@@ -65,6 +78,12 @@ impl FunctionDebugInfo {
         self
     }
 
+    pub fn with_locals(mut self, locals: Vec<LocalName>, captures: Vec<String>) -> Self {
+        self.locals = locals;
+        self.captures = captures;
+        self
+    }
+
     pub fn location_at(&self, ip: usize) -> Option<&Location> {
         match self
             .locations
@@ -209,10 +209,17 @@ pub enum OpCode {
     /// No operands. Emitted immediately before return opcodes so the VM can
     /// observe an in-flight yield while unwinding.
     OpReturnCheck = 105,
+    /// Call tracing: log entry to the current function. Operand: `[label_index: u16]`.
+    /// Only emitted for the functions selected by `--trace-calls`.
+    OpTraceCall = 106,
+    /// Call tracing: log the effect operation about to be performed with the `arity`
+    /// arguments on top of the stack. Operands: `[label_index: u16, arity: u8]`.
+    /// Only emitted when `--trace-calls` is passed.
+    OpTracePerform = 107,
 }
 
 /// Maximum valid opcode value (inclusive). Must be updated when adding new opcodes.
-pub const MAX_OPCODE: u8 = OpCode::OpReturnCheck as u8;
+pub const MAX_OPCODE: u8 = OpCode::OpTracePerform as u8;
 
 impl From<u8> for OpCode {
     fn from(byte: u8) -> Self {
@@ -323,6 +330,8 @@ impl From<u8> for OpCode {
             103 => OpCode::OpTailCall1,
             104 => OpCode::OpEnterCC,
             105 => OpCode::OpReturnCheck,
+            106 => OpCode::OpTraceCall,
+            107 => OpCode::OpTracePerform,
             _ => panic!("Unknown opcode {}", byte),
         }
     }
@@ -395,6 +404,8 @@ pub fn operand_widths(op: OpCode) -> Vec<usize> {
         OpCode::OpCall0 | OpCode::OpCall1 | OpCode::OpCall2 | OpCode::OpTailCall1 => vec![],
         OpCode::OpEnterCC => vec![2], // cc_index: u16
         OpCode::OpReturnCheck => vec![],
+        OpCode::OpTraceCall => vec![2],       // label_index: u16
+        OpCode::OpTracePerform => vec![2, 1], // label_index: u16, arity: u8
         _ => vec![],
     }
 }
@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use crate::{
     ast::free_vars::collect_free_vars,
     diagnostics::position::Span,
-    diagnostics::{Diagnostic, DiagnosticBuilder, DiagnosticPhase, ErrorType},
+    diagnostics::{
+        Diagnostic, DiagnosticBuilder, DiagnosticPhase, ErrorType, compiler_errors::TYPED_HOLE,
+    },
     syntax::{
         Identifier,
         block::Block,
@@ -284,6 +286,7 @@ impl<'a> FunctionLoweringContext<'a> {
             return_type_annotation,
             effects,
             captures,
+            local_names: HashMap::new(),
             body_span,
             ret_type,
             blocks: self.blocks,
@@ -802,6 +805,14 @@ impl<'a> FunctionLoweringContext<'a> {
                      (proposal 0152 Phase 3)"
                 );
             }
+            Expression::Hole { span, .. } => Err(unsupported_lowering(
+                *span,
+                "typed holes are only lowered through Core IR",
+            )
+            .with_code(TYPED_HOLE.code)
+            .with_hint_text(
+                "Fill in the hole, or compile through Core IR where it panics when reached.",
+            )),
         }
     }
 
@@ -3163,6 +3174,29 @@ mod tests {
         assert!(ir.contains("TailCall"));
     }
 
+    #[test]
+    fn typed_hole_reports_a_hole_diagnostic_instead_of_an_internal_error() {
+        let lexer = Lexer::new("fn f(x: Int) -> Int { x + ?todo }");
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+        assert!(
+            parser.errors.is_empty(),
+            "parser errors: {:?}",
+            parser.errors
+        );
+        let interner = parser.take_interner();
+        let mut compiler = Compiler::new_with_interner("<test>", interner);
+        let hm = compiler.infer_expr_types_for_program(&program);
+
+        let diag = lower_program_to_ir(&program, &hm).expect_err("holes do not lower to CFG");
+        assert_eq!(diag.code(), Some("W204"));
+        assert_eq!(
+            diag.message(),
+            Some("typed holes are only lowered through Core IR")
+        );
+        assert!(diag.span().is_some_and(|span| span.start.column > 0));
+    }
+
     #[test]
     fn debug_factorial_ir_structure() {
         let ir =
@@ -635,6 +635,8 @@ pub struct IrFunction {
     pub return_type_annotation: Option<TypeExpr>,
     pub effects: Vec<EffectExpr>,
     pub captures: Vec<Identifier>,
+    /// Source names of `let`- and pattern-bound variables, kept for debug info.
+    pub local_names: HashMap<IrVar, Identifier>,
     pub body_span: Span,
     pub ret_type: IrType,
     pub blocks: Vec<IrBlock>,
@@ -390,6 +390,7 @@ mod tests {
                 return_type_annotation: None,
                 effects: Vec::new(),
                 captures: Vec::new(),
+                local_names: HashMap::new(),
                 body_span: crate::diagnostics::position::Span::default(),
                 ret_type: IrType::Tagged,
                 blocks: vec![IrBlock {
@@ -482,6 +483,7 @@ mod tests {
             return_type_annotation: None,
             effects: Vec::new(),
             captures: Vec::new(),
+            local_names: HashMap::new(),
             body_span: crate::diagnostics::position::Span::default(),
             ret_type: IrType::Tagged,
             blocks: vec![
@@ -504,6 +504,7 @@ mod tests {
                 return_type_annotation: None,
                 effects: Vec::new(),
                 captures: Vec::new(),
+                local_names: HashMap::new(),
                 body_span: crate::diagnostics::position::Span::default(),
                 ret_type: IrType::Tagged,
                 blocks: vec![IrBlock {
@@ -544,6 +545,7 @@ mod tests {
                 return_type_annotation: None,
                 effects: Vec::new(),
                 captures: Vec::new(),
+                local_names: HashMap::new(),
                 body_span: crate::diagnostics::position::Span::default(),
                 ret_type: IrType::Tagged,
                 blocks: vec![IrBlock {
@@ -580,6 +582,7 @@ mod tests {
                 return_type_annotation: None,
                 effects: Vec::new(),
                 captures: Vec::new(),
+                local_names: HashMap::new(),
                 body_span: crate::diagnostics::position::Span::default(),
                 ret_type: IrType::Tagged,
                 blocks: vec![
@@ -632,6 +635,7 @@ mod tests {
                 return_type_annotation: None,
                 effects: Vec::new(),
                 captures: Vec::new(),
+                local_names: HashMap::new(),
                 body_span: crate::diagnostics::position::Span::default(),
                 ret_type: IrType::Tagged,
                 blocks: vec![IrBlock {
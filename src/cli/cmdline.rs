@@ -1,9 +1,16 @@
 //! CLI argument parsing and command selection.
 
-use std::{ffi::OsString, path::Path};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use crate::{
-    cli::render::text::{expected_flx, expected_flxi, fmt_check_usage, fmt_usage, unknown_command},
+    cli::render::text::{
+        bench_usage, debug_usage, doc_usage, expected_flx, expected_flxi, fix_usage,
+        fmt_check_usage, fmt_usage, lsp_usage, repl_usage, unknown_command,
+    },
     cli::shared::{
         ParsedCliFlags, build_driver_flags, extract_cli_flag_groups, extract_cli_value_options,
     },
@@ -29,6 +36,7 @@ pub enum CliCommand {
     Fmt {
         path: String,
         check: bool,
+        max_width: Option<usize>,
     },
     CacheInfo {
         flags: DriverFlags,
@@ -54,6 +62,31 @@ pub enum CliCommand {
     ParityCheck {
         raw_args: Vec<String>,
     },
+    FuzzParity {
+        raw_args: Vec<String>,
+    },
+    Reduce {
+        raw_args: Vec<String>,
+    },
+    Mutate {
+        raw_args: Vec<String>,
+    },
+    Lsp {
+        flags: DriverFlags,
+    },
+    Repl {
+        flags: DriverFlags,
+    },
+    Doc {
+        flags: DriverFlags,
+        path: String,
+        markdown: bool,
+    },
+    Fix {
+        flags: DriverFlags,
+        path: String,
+        dry_run: bool,
+    },
     Help,
 }
 
@@ -73,12 +106,13 @@ pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<CliCommand
     reject_unknown_flag_tokens(&args)?;
 
     let run_mode = run_mode_from_flags(parsed.execution.test_mode);
+    let watch = parsed.execution.watch;
 
-    if let Some(command) = parse_implicit_file_command(&args, flags.clone(), run_mode)? {
+    if let Some(command) = parse_implicit_file_command(&args, flags.clone(), run_mode, watch)? {
         return Ok(command);
     }
 
-    parse_subcommand(&args, &mut flags, run_mode)
+    parse_subcommand(&args, &mut flags, run_mode, watch)
 }
 
 /// Converts raw process arguments into an owned CLI buffer.
@@ -109,9 +143,24 @@ fn has_no_command_or_input(args: &[String]) -> bool {
 
 /// Rejects leftover CLI flags after the known flag-extraction passes complete.
 ///
-/// `parity-check` intentionally forwards raw arguments for its own parser, so its tail is exempt.
+/// `parity-check`, `fuzz-parity`, `reduce` and `mutate` intentionally forward raw arguments for
+/// their own parser, and `fmt`, `doc`, `fix`, `debug` and `bench` parse their own switches, so their
+/// tails are exempt.
 fn reject_unknown_flag_tokens(args: &[String]) -> Result<(), String> {
-    if args.get(1).is_some_and(|arg| arg == "parity-check") {
+    if args.get(1).is_some_and(|arg| {
+        matches!(
+            arg.as_str(),
+            "parity-check"
+                | "fuzz-parity"
+                | "reduce"
+                | "mutate"
+                | "fmt"
+                | "doc"
+                | "fix"
+                | "debug"
+                | "bench"
+        )
+    }) {
         return Ok(());
     }
 
@@ -138,13 +187,14 @@ fn parse_implicit_file_command(
     args: &[String],
     flags: DriverFlags,
     run_mode: RunMode,
+    watch: bool,
 ) -> Result<Option<CliCommand>, String> {
-    if !is_flx_file(&args[1]) {
+    if !is_flx_file(&args[1]) && !is_test_directory(&args[1], run_mode) {
         return Ok(None);
     }
 
-    let path = require_flx_arg(args, 1, "Usage: flux <file.flx>")?;
-    Ok(Some(run_command(flags, path, run_mode)))
+    let path = require_run_target(args, 1, run_mode, watch, "Usage: flux <file.flx>")?;
+    Ok(Some(run_command(flags, path, run_mode, watch)))
 }
 
 /// Parses the explicit subcommand form after flag extraction and validation.
@@ -152,10 +202,15 @@ fn parse_subcommand(
     args: &[String],
     flags: &mut DriverFlags,
     run_mode: RunMode,
+    watch: bool,
 ) -> Result<CliCommand, String> {
     match args[1].as_str() {
         "-h" | "--help" | "help" => Ok(CliCommand::Help),
-        "run" => parse_run_subcommand(args, flags, run_mode),
+        "run" => parse_run_subcommand(args, flags, run_mode, watch),
+        "check" => {
+            let path = require_flx_arg(args, 2, "Usage: flux check <file.flx> [--watch]")?;
+            Ok(run_command(flags.clone(), path, RunMode::Check, watch))
+        }
         "tokens" => {
             parse_flx_subcommand(args, flags, 2, "Usage: flux tokens <file.flx>", |flags| {
                 CliCommand::Tokens { flags }
@@ -166,9 +221,13 @@ fn parse_subcommand(
                 CliCommand::Bytecode { flags }
             })
         }
-        "lint" => parse_flx_subcommand(args, flags, 2, "Usage: flux lint <file.flx>", |flags| {
-            CliCommand::Lint { flags }
-        }),
+        "lint" => parse_flx_subcommand(
+            args,
+            flags,
+            2,
+            "Usage: flux lint <file.flx> [--deny <codes>] [--allow <codes>] [--warn <codes>]",
+            |flags| CliCommand::Lint { flags },
+        ),
         "fmt" => parse_fmt_subcommand(args),
         "cache-info" => parse_flx_subcommand(
             args,
@@ -214,6 +273,23 @@ fn parse_subcommand(
             |flags| CliCommand::AnalyzeTailCalls { flags },
         ),
         "parity-check" => Ok(parity_check_command(args)),
+        "fuzz-parity" => Ok(CliCommand::FuzzParity {
+            raw_args: args[2..].to_vec(),
+        }),
+        "reduce" => Ok(CliCommand::Reduce {
+            raw_args: args[2..].to_vec(),
+        }),
+        "mutate" => Ok(mutate_command(args, flags)),
+        "lsp" => {
+            parse_no_input_subcommand(args, flags, lsp_usage(), |flags| CliCommand::Lsp { flags })
+        }
+        "repl" => parse_no_input_subcommand(args, flags, repl_usage(), |flags| CliCommand::Repl {
+            flags,
+        }),
+        "doc" => parse_doc_subcommand(args, flags),
+        "fix" => parse_fix_subcommand(args, flags),
+        "debug" => parse_debug_subcommand(args, flags),
+        "bench" => parse_bench_subcommand(args, flags),
         other => Err(unknown_command(other)),
     }
 }
@@ -221,10 +297,10 @@ fn parse_subcommand(
 /// Builds a run command from a resolved source path and execution mode.
 ///
 /// Keeping this small constructor separate makes the subcommand match easier to scan.
-fn run_command(flags: DriverFlags, path: String, mode: RunMode) -> CliCommand {
+fn run_command(flags: DriverFlags, path: String, mode: RunMode, watch: bool) -> CliCommand {
     CliCommand::Run {
         flags,
-        target: RunTarget { path, mode },
+        target: RunTarget { path, mode, watch },
     }
 }
 
@@ -233,9 +309,10 @@ fn parse_run_subcommand(
     args: &[String],
     flags: &DriverFlags,
     run_mode: RunMode,
+    watch: bool,
 ) -> Result<CliCommand, String> {
-    let path = require_flx_arg(args, 2, "Usage: flux run <file.flx>")?;
-    Ok(run_command(flags.clone(), path, run_mode))
+    let path = require_run_target(args, 2, run_mode, watch, "Usage: flux run <file.flx>")?;
+    Ok(run_command(flags.clone(), path, run_mode, watch))
 }
 
 /// Builds the `clean` command and attaches an optional `.flx` input path when present.
@@ -251,6 +328,30 @@ fn parity_check_command(args: &[String]) -> CliCommand {
     }
 }
 
+/// Builds the mutate command, restoring the `--root` options taken by the global flag pass
+/// because every mutant run needs them.
+fn mutate_command(args: &[String], flags: &DriverFlags) -> CliCommand {
+    let mut raw_args = args[2..].to_vec();
+    for root in &flags.input.roots {
+        raw_args.push("--root".to_string());
+        raw_args.push(root.to_string_lossy().into_owned());
+    }
+    CliCommand::Mutate { raw_args }
+}
+
+/// Parses a subcommand that takes no positional arguments, such as `lsp`.
+fn parse_no_input_subcommand(
+    args: &[String],
+    flags: &DriverFlags,
+    usage: &str,
+    build: impl FnOnce(DriverFlags) -> CliCommand,
+) -> Result<CliCommand, String> {
+    if args.len() > 2 {
+        return Err(usage.to_string());
+    }
+    Ok(build(flags.clone()))
+}
+
 /// Returns an optional `.flx` positional argument at `index`.
 fn optional_flx_input(args: &[String], index: usize) -> Option<String> {
     args.get(index).filter(|path| is_flx_file(path)).cloned()
@@ -294,13 +395,66 @@ fn parse_path_subcommand(
 
 /// Parses the `fmt` subcommand and returns the path/check-mode command variant.
 fn parse_fmt_subcommand(args: &[String]) -> Result<CliCommand, String> {
-    let (path, check) = parse_fmt_command(args)?;
-    Ok(CliCommand::Fmt { path, check })
+    let mut args = args.to_vec();
+    let max_width = extract_fmt_max_width(&mut args)?;
+    if let Some(flag) = args
+        .iter()
+        .skip(2)
+        .find(|arg| arg.starts_with("--") && *arg != "--check")
+    {
+        return Err(format!("Error: unknown flag `{flag}`."));
+    }
+    let (path, check) = parse_fmt_command(&args)?;
+    Ok(CliCommand::Fmt {
+        path,
+        check,
+        max_width,
+    })
+}
+
+/// Removes `--max-width <n>` (or `--max-width=<n>`) from the `fmt` arguments.
+fn extract_fmt_max_width(args: &mut Vec<String>) -> Result<Option<usize>, String> {
+    let Some(value) = take_subcommand_value(args, "--max-width", fmt_usage())? else {
+        return Ok(None);
+    };
+    match value.parse::<usize>() {
+        Ok(width) if width > 0 => Ok(Some(width)),
+        _ => Err(format!(
+            "Error: --max-width expects a positive integer, got `{value}`."
+        )),
+    }
+}
+
+/// Removes `<flag> <value>` (or `<flag>=<value>`) from subcommand arguments and returns the
+/// value, or `usage` when the value is missing.
+fn take_subcommand_value(
+    args: &mut Vec<String>,
+    flag: &str,
+    usage: &str,
+) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| {
+        arg == flag
+            || arg
+                .strip_prefix(flag)
+                .is_some_and(|rest| rest.starts_with('='))
+    }) else {
+        return Ok(None);
+    };
+    let arg = args.remove(index);
+    match arg
+        .strip_prefix(flag)
+        .and_then(|rest| rest.strip_prefix('='))
+    {
+        Some(value) => Ok(Some(value.to_string())),
+        None if index < args.len() => Ok(Some(args.remove(index))),
+        None => Err(usage.to_string()),
+    }
 }
 
 /// Parses `flux fmt` arguments and returns the target path plus `--check` mode.
 ///
-/// The formatter accepts a single positional `.flx` path with an optional `--check` switch.
+/// The formatter accepts a single positional `.flx` file or directory with an optional
+/// `--check` switch.
 fn parse_fmt_command(args: &[String]) -> Result<(String, bool), String> {
     if args.len() < 3 {
         return Err(fmt_usage().to_string());
@@ -316,7 +470,87 @@ fn parse_fmt_command(args: &[String]) -> Result<(String, bool), String> {
 /// Returns the required formatter path based on whether `--check` is present.
 fn require_fmt_path(args: &[String], check: bool) -> Result<String, String> {
     let index = if check { 3 } else { 2 };
-    require_flx_arg(args, index, fmt_usage())
+    match args.get(index) {
+        Some(path) if Path::new(path).is_dir() => Ok(path.clone()),
+        _ => require_flx_arg(args, index, fmt_usage()),
+    }
+}
+
+/// Parses `flux doc <file.flx|dir> [--markdown]`; the output directory comes from `-o`.
+fn parse_doc_subcommand(args: &[String], flags: &DriverFlags) -> Result<CliCommand, String> {
+    let (path, markdown) = parse_path_with_switch(args, "--markdown", doc_usage())?;
+    Ok(CliCommand::Doc {
+        flags: flags.clone(),
+        path,
+        markdown,
+    })
+}
+
+/// Parses `flux fix <file.flx|dir> [--dry-run]`.
+fn parse_fix_subcommand(args: &[String], flags: &DriverFlags) -> Result<CliCommand, String> {
+    let (path, dry_run) = parse_path_with_switch(args, "--dry-run", fix_usage())?;
+    Ok(CliCommand::Fix {
+        flags: flags.clone(),
+        path,
+        dry_run,
+    })
+}
+
+/// Parses `flux debug <file.flx> --dap`. The adapter only speaks DAP, so `--dap` is required.
+fn parse_debug_subcommand(args: &[String], flags: &DriverFlags) -> Result<CliCommand, String> {
+    let (path, dap) = parse_path_with_switch(args, "--dap", debug_usage())?;
+    if !dap || !is_flx_file(&path) {
+        return Err(debug_usage().to_string());
+    }
+    Ok(run_command(flags.clone(), path, RunMode::Debug, false))
+}
+
+/// Parses `flux bench <file.flx|dir> [--filter <s>] [--baseline <path>] [--time <ms>]` into a
+/// bench run; backend and root flags have already been taken from `args`.
+fn parse_bench_subcommand(args: &[String], flags: &DriverFlags) -> Result<CliCommand, String> {
+    let mut args = args.to_vec();
+    let mut flags = flags.clone();
+    flags.bench.filter = take_subcommand_value(&mut args, "--filter", bench_usage())?;
+    flags.bench.baseline =
+        take_subcommand_value(&mut args, "--baseline", bench_usage())?.map(PathBuf::from);
+    if let Some(value) = take_subcommand_value(&mut args, "--time", bench_usage())? {
+        flags.bench.measurement = match value.parse::<u64>() {
+            Ok(ms) if ms > 0 => Duration::from_millis(ms),
+            _ => {
+                return Err(format!(
+                    "Error: --time expects a positive number of milliseconds, got `{value}`."
+                ));
+            }
+        };
+    }
+    let path = parse_path_arg(&args, bench_usage())?;
+    Ok(run_command(flags, path, RunMode::Bench, false))
+}
+
+/// Parses `<file.flx|dir>` plus one optional boolean `switch` for commands that walk a
+/// file or directory tree. Global flags have already been removed from `args`.
+fn parse_path_with_switch(
+    args: &[String],
+    switch: &str,
+    usage: &str,
+) -> Result<(String, bool), String> {
+    let enabled = args.iter().any(|arg| arg == switch);
+    let rest: Vec<String> = args.iter().filter(|arg| *arg != switch).cloned().collect();
+    Ok((parse_path_arg(&rest, usage)?, enabled))
+}
+
+/// Parses the single `<file.flx|dir>` argument of a command that takes no other switches.
+fn parse_path_arg(args: &[String], usage: &str) -> Result<String, String> {
+    if let Some(flag) = args.iter().skip(2).find(|arg| arg.starts_with("--")) {
+        return Err(format!("Error: unknown flag `{flag}`."));
+    }
+    let [path] = &args[2.min(args.len())..] else {
+        return Err(usage.to_string());
+    };
+    if !Path::new(path.as_str()).is_dir() && !is_flx_file(path) {
+        return Err(expected_flx(path));
+    }
+    Ok(path.to_string())
 }
 
 /// Returns the required `.flx` argument at `index` or a CLI-formatted error.
@@ -329,6 +563,31 @@ fn require_flx_arg(args: &[String], index: usize, usage: &str) -> Result<String,
     }
 }
 
+/// Returns the run target at `index`: a `.flx` file, or with `--test` also a directory whose
+/// test files run together. `--watch` follows one entry's module graph, so it needs a file.
+fn require_run_target(
+    args: &[String],
+    index: usize,
+    run_mode: RunMode,
+    watch: bool,
+    usage: &str,
+) -> Result<String, String> {
+    match args.get(index) {
+        Some(path) if is_test_directory(path, run_mode) => {
+            if watch {
+                return Err("Error: --watch needs a single `.flx` file, not a directory.".into());
+            }
+            Ok(path.clone())
+        }
+        _ => require_flx_arg(args, index, usage),
+    }
+}
+
+/// Returns whether `path` is a directory given to `--test`.
+fn is_test_directory(path: &str, run_mode: RunMode) -> bool {
+    run_mode == RunMode::Tests && Path::new(path).is_dir()
+}
+
 /// Returns the required `.flxi` argument at `index` or a CLI-formatted error.
 fn require_flxi_arg(args: &[String], index: usize, usage: &str) -> Result<String, String> {
     let path = args.get(index).ok_or_else(|| usage.to_string())?;
@@ -370,6 +629,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_check_and_watch() {
+        let command = parse_args(cli(&[
+            "flux",
+            "check",
+            "examples/guide/arithmetic.flx",
+            "--watch",
+        ]))
+        .unwrap();
+        match command {
+            CliCommand::Run { target, .. } => {
+                assert_eq!(target.mode, RunMode::Check);
+                assert!(target.watch);
+            }
+            other => panic!("expected check mode, got {other:?}"),
+        }
+
+        let command = parse_args(cli(&[
+            "flux",
+            "examples/guide/arithmetic.flx",
+            "--test",
+            "--watch",
+        ]))
+        .unwrap();
+        match command {
+            CliCommand::Run { target, .. } => {
+                assert_eq!(target.mode, RunMode::Tests);
+                assert!(target.watch);
+            }
+            other => panic!("expected test mode, got {other:?}"),
+        }
+
+        assert!(parse_args(cli(&["flux", "check"])).is_err());
+    }
+
     #[test]
     fn parses_dump_modes() {
         let command = parse_args(cli(&[
@@ -673,6 +967,36 @@ mod tests {
         assert!(err.contains("Usage: flux fmt --check"));
     }
 
+    #[test]
+    fn parses_fmt_subcommand_with_max_width_and_directory() {
+        let command = parse_args(cli(&[
+            "flux",
+            "fmt",
+            "--check",
+            "--max-width",
+            "80",
+            "examples",
+        ]))
+        .unwrap();
+        match command {
+            CliCommand::Fmt {
+                path,
+                check,
+                max_width,
+            } => {
+                assert_eq!(path, "examples");
+                assert!(check);
+                assert_eq!(max_width, Some(80));
+            }
+            other => panic!("expected fmt mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "fmt", "--max-width=0", "main.flx"])).unwrap_err();
+        assert!(err.contains("--max-width"));
+        let err = parse_args(cli(&["flux", "fmt", "--wide", "main.flx"])).unwrap_err();
+        assert!(err.contains("--wide"));
+    }
+
     #[test]
     fn run_mode_from_flags_maps_test_switch_to_tests_mode() {
         assert_eq!(run_mode_from_flags(false), RunMode::Program);
@@ -758,6 +1082,159 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_lsp_subcommand_with_roots() {
+        let command = parse_args(cli(&["flux", "lsp", "--root", "lib", "--strict"])).unwrap();
+        match command {
+            CliCommand::Lsp { flags } => {
+                assert_eq!(flags.input.roots, vec![std::path::PathBuf::from("lib")]);
+                assert!(flags.language.strict_mode);
+                assert!(flags.input.input_path.is_none());
+            }
+            other => panic!("expected lsp mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "lsp", "main.flx"])).unwrap_err();
+        assert!(err.contains("Usage: flux lsp"));
+    }
+
+    #[test]
+    fn parses_repl_subcommand() {
+        let command = parse_args(cli(&["flux", "repl", "--root", "examples"])).unwrap();
+        match command {
+            CliCommand::Repl { flags } => {
+                assert_eq!(
+                    flags.input.roots,
+                    vec![std::path::PathBuf::from("examples")]
+                );
+                assert!(flags.input.input_path.is_none());
+            }
+            other => panic!("expected repl mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "repl", "main.flx"])).unwrap_err();
+        assert!(err.contains("Usage: flux repl"));
+    }
+
+    #[test]
+    fn parses_doc_subcommand() {
+        let command = parse_args(cli(&[
+            "flux",
+            "doc",
+            "lib/Flow",
+            "--markdown",
+            "-o",
+            "target/api",
+            "--root",
+            "lib",
+        ]))
+        .unwrap();
+        match command {
+            CliCommand::Doc {
+                flags,
+                path,
+                markdown,
+            } => {
+                assert_eq!(path, "lib/Flow");
+                assert!(markdown);
+                assert_eq!(flags.backend.output_path.as_deref(), Some("target/api"));
+                assert_eq!(flags.input.roots, vec![std::path::PathBuf::from("lib")]);
+            }
+            other => panic!("expected doc mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "doc"])).unwrap_err();
+        assert!(err.contains("Usage: flux doc"));
+        let err = parse_args(cli(&["flux", "doc", "lib/Flow", "--html"])).unwrap_err();
+        assert!(err.contains("--html"));
+    }
+
+    #[test]
+    fn parses_fix_subcommand() {
+        let command = parse_args(cli(&["flux", "fix", "examples", "--dry-run"])).unwrap();
+        match command {
+            CliCommand::Fix { path, dry_run, .. } => {
+                assert_eq!(path, "examples");
+                assert!(dry_run);
+            }
+            other => panic!("expected fix mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "fix"])).unwrap_err();
+        assert!(err.contains("Usage: flux fix"));
+    }
+
+    #[test]
+    fn test_mode_accepts_a_directory_of_test_files() {
+        let command = parse_args(cli(&["flux", "--test", "tests"])).unwrap();
+        match command {
+            CliCommand::Run { target, .. } => {
+                assert_eq!(target.path, "tests");
+                assert_eq!(target.mode, RunMode::Tests);
+            }
+            other => panic!("expected test mode, got {other:?}"),
+        }
+
+        assert!(parse_args(cli(&["flux", "run", "tests"])).is_err());
+        let err = parse_args(cli(&["flux", "--test", "--watch", "tests"])).unwrap_err();
+        assert!(err.contains("--watch"));
+    }
+
+    #[test]
+    fn parses_debug_subcommand() {
+        let command = parse_args(cli(&["flux", "debug", "main.flx", "--dap"])).unwrap();
+        match command {
+            CliCommand::Run { target, .. } => {
+                assert_eq!(target.path, "main.flx");
+                assert_eq!(target.mode, RunMode::Debug);
+            }
+            other => panic!("expected debug mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "debug", "main.flx"])).unwrap_err();
+        assert!(err.contains("Usage: flux debug"));
+        let err = parse_args(cli(&["flux", "debug", "examples", "--dap"])).unwrap_err();
+        assert!(err.contains("Usage: flux debug"));
+    }
+
+    #[test]
+    fn parses_bench_subcommand_with_its_options() {
+        let command = parse_args(cli(&[
+            "flux",
+            "bench",
+            "main.flx",
+            "--filter",
+            "sort",
+            "--baseline=base.json",
+            "--time",
+            "250",
+        ]))
+        .unwrap();
+        match command {
+            CliCommand::Run { flags, target } => {
+                assert_eq!(target.path, "main.flx");
+                assert_eq!(target.mode, RunMode::Bench);
+                assert_eq!(flags.bench.filter.as_deref(), Some("sort"));
+                assert_eq!(
+                    flags.bench.baseline.as_deref(),
+                    Some(std::path::Path::new("base.json"))
+                );
+                assert_eq!(
+                    flags.bench.measurement,
+                    std::time::Duration::from_millis(250)
+                );
+            }
+            other => panic!("expected bench mode, got {other:?}"),
+        }
+
+        let err = parse_args(cli(&["flux", "bench", "main.flx", "--time", "0"])).unwrap_err();
+        assert!(err.contains("--time expects a positive number"));
+        let err = parse_args(cli(&["flux", "bench", "main.flx", "--filter"])).unwrap_err();
+        assert!(err.contains("Usage: flux bench"));
+        let err = parse_args(cli(&["flux", "bench", "main.flx", "--fast"])).unwrap_err();
+        assert!(err.contains("unknown flag `--fast`"));
+    }
+
     #[test]
     fn unknown_command_error_mentions_bad_token() {
         let err = parse_args(cli(&["flux", "wat"])).unwrap_err();
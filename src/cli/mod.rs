@@ -7,8 +7,10 @@ use crate::{
         cmdline::{CliCommand, parse_args},
         render::text::help_text,
     },
-    driver::command::{cache, entry, inspect},
-    parity::cli::run_parity_check,
+    driver::command::{cache, entry, inspect, tooling},
+    parity::{
+        cli::run_parity_check, fuzz::run_fuzz_parity, mutate::run_mutate, reduce::run_reduce,
+    },
 };
 
 pub mod cmdline;
@@ -31,7 +33,11 @@ fn run_command(command: CliCommand) -> ExitCode {
         CliCommand::Tokens { flags } => inspect::show_tokens(&flags),
         CliCommand::Bytecode { flags } => inspect::show_bytecode(&flags),
         CliCommand::Lint { flags } => inspect::lint(&flags),
-        CliCommand::Fmt { path, check } => inspect::fmt(&path, check),
+        CliCommand::Fmt {
+            path,
+            check,
+            max_width,
+        } => inspect::fmt(&path, check, max_width),
         CliCommand::CacheInfo { flags } => cache::show_cache_info(&flags),
         CliCommand::ModuleCacheInfo { flags } => cache::show_module_cache_info(&flags),
         CliCommand::NativeCacheInfo { flags } => cache::show_native_cache_info(&flags),
@@ -40,6 +46,21 @@ fn run_command(command: CliCommand) -> ExitCode {
         CliCommand::AnalyzeFreeVars { flags } => inspect::analyze_free_vars(&flags),
         CliCommand::AnalyzeTailCalls { flags } => inspect::analyze_tail_calls(&flags),
         CliCommand::ParityCheck { raw_args } => run_parity_check(&raw_args),
+        CliCommand::FuzzParity { raw_args } => run_fuzz_parity(&raw_args),
+        CliCommand::Reduce { raw_args } => run_reduce(&raw_args),
+        CliCommand::Mutate { raw_args } => run_mutate(&raw_args),
+        CliCommand::Lsp { flags } => tooling::lsp(&flags),
+        CliCommand::Repl { flags } => tooling::repl(&flags),
+        CliCommand::Doc {
+            flags,
+            path,
+            markdown,
+        } => tooling::doc(&flags, &path, markdown),
+        CliCommand::Fix {
+            flags,
+            path,
+            dry_run,
+        } => tooling::fix(&flags, &path, dry_run),
         CliCommand::Help => show_help(),
     }
     ExitCode::SUCCESS
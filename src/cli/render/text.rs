@@ -8,10 +8,12 @@ Flux CLI
 Usage:
   flux <file.flx>
   flux run <file.flx>
+  flux --test <file.flx|dir>
+  flux check <file.flx>
   flux tokens <file.flx>
   flux bytecode <file.flx>
-  flux lint <file.flx>
-  flux fmt [--check] <file.flx>
+  flux lint <file.flx> [--deny <codes>] [--allow <codes>] [--warn <codes>]
+  flux fmt [--check] [--max-width <n>] <file.flx|dir>
   flux cache-info <file.flx>
   flux module-cache-info <file.flx>
   flux native-cache-info <file.flx>
@@ -20,6 +22,15 @@ Usage:
   flux analyze-free-vars <file.flx>
   flux analyze-tail-calls <file.flx>
   flux parity-check <file-or-dir> [--ways vm,llvm] [--root <path> ...]
+  flux fuzz-parity [--seed <n>] [--count <n>] [--out <dir>] [--emit] [--no-minimize]
+  flux reduce <file.flx> --predicate parity|panic|diag=<code> [--out <path>]
+  flux mutate <file.flx> [--jobs <n>] [--timeout <secs>] [--root <path> ...]
+  flux lsp [--root <path> ...] [--strict]
+  flux repl [--root <path> ...] [--strict]
+  flux doc <file.flx|dir> [--markdown] [-o <dir>] [--root <path> ...]
+  flux fix <file.flx|dir> [--dry-run] [--root <path> ...]
+  flux debug <file.flx> --dap [--root <path> ...]
+  flux bench <file.flx|dir> [--filter <s>] [--baseline <p>] [--time <ms>] [--native]
   flux <file.flx> --root <path> [--root <path> ...]
   flux run <file.flx> --root <path> [--root <path> ...]
 
@@ -29,6 +40,20 @@ Flags:
   --trace-aether     Print Aether report plus backend/execution path, then run
   --test             Run test_* functions and report results
   --test-filter <s>  Only run tests whose names contain <s>
+  --test-format <f>  Test report format: text|junit|tap|json (default: text)
+  --test-output <p>  Write the --test-format report to <p> instead of stdout
+  --test-jobs <n>    Run up to <n> tests at once in isolated runtimes (default: 1)
+  --test-timeout <ms>
+                     Fail any test still running after <ms> milliseconds
+  --update-snapshots
+                     Record or rewrite the FTest.snapshot files of the tests that run
+  --doc-tests        Also run the ```flux examples in /// comments of public items
+  --coverage         Count line, function and branch coverage of a VM run or --test run
+  --coverage-output <p>
+                     Write the --coverage lcov tracefile to <p> (default: lcov.info)
+  --coverage-exclude-stdlib
+                     Leave Flow.* stdlib modules out of the --coverage report
+  --watch            Re-run `run`, `check` or `--test` whenever a module changes
   --leak-detector    Print approximate allocation stats after run
   --no-cache         Disable bytecode cache for this run
   --cache-dir <dir>  Override cache root (default: nearest Cargo.toml target/flux, else .flux/cache)
@@ -40,36 +65,83 @@ Flags:
   --roots-only       Use only explicitly provided --root values
   --stats            Print execution analytics (parse/compile/execute times, module info)
   --prof             Print per-function profiling report (call counts, time, allocations)
+  --prof-format <f>  Profile format: flat|folded|folded-alloc|speedscope (default: flat)
+  --prof-output <p>  Write the --prof report to <p> (default: stderr, or profile.* for files)
+  --trace-calls <p>  Log calls to functions matching the comma-separated globs <p> (repeatable)
+  --trace-output <p> Write the --trace-calls log to <p> as JSON lines (default: text on stderr)
+  --record <log>     Log file reads, stdin and clock readings of the run to <log>
+  --replay <log>     Answer file reads, stdin and clock readings from a --record log
+  --deny <codes>     Report the comma-separated lint codes as errors; `flux lint` then fails
+  --allow <codes>    Do not report the comma-separated lint codes
+  --warn <codes>     Report the comma-separated lint codes as warnings
   --strict           Enable strict type/effect boundary checks
   --all-errors       Show diagnostics from all phases (disable stage-aware filtering)
   --dump-repr        Print the backend representation contract summary and exit
   --dump-cfg         Lower to Flux CFG IR, print a readable dump, and exit
   --dump-core        Lower to Flux Core IR, print a readable dump, and exit
   --dump-core=debug  Lower to Flux Core IR, print a raw debug dump, and exit
+  --core-eval        Run the reference Core IR interpreter after every Core pass and exit
   --dump-aether      Show Aether memory model report (per-function reuse/drop stats)
   --dump-aether=debug
                     Show detailed Aether debug report (borrow signatures, call modes, dup/drop, reuse)
   --native           Compile via Core IR -> LLVM text IR -> native binary (requires LLVM tools)
   --emit-llvm        Emit LLVM IR text (.ll) to stdout (with --native)
   --emit-binary      Compile to native binary via opt + llc + cc (with --native)
-  -o <path>          Output path for --emit-llvm or --emit-binary
+  -o <path>          Output path for --emit-llvm or --emit-binary, or the `doc` output directory
   -h, --help         Show this help message
 
 Optimization & Analysis:
   --optimize         Apply transformations (faster bytecode)
   --analyze          Collect analysis data (free vars, tail calls)
   -O -A              Both optimization and analysis
+
+Projects:
+  `flux run`, `flux check`, `--test` and `flux lint` read the nearest flux.toml
+  above the entry file: its roots and dependency roots are added after --root
+  paths, its [build] settings enable --strict / --optimize, and flux.lock is
+  refreshed. `flux lint` also reads lint levels and thresholds from the
+  nearest .fluxlint.toml, or else from the [lint] table of flux.toml.
 "
 }
 
 /// Returns the `fmt` usage text shown when formatter arguments are missing.
 pub fn fmt_usage() -> &'static str {
-    "Usage: flux fmt [--check] <file.flx>"
+    "Usage: flux fmt [--check] [--max-width <n>] <file.flx|dir>"
 }
 
 /// Returns the `fmt --check` usage text shown when the checked formatter path is missing.
 pub fn fmt_check_usage() -> &'static str {
-    "Usage: flux fmt --check <file.flx>"
+    "Usage: flux fmt --check [--max-width <n>] <file.flx|dir>"
+}
+
+/// Returns the `lsp` usage text shown when the language server receives positional arguments.
+pub fn lsp_usage() -> &'static str {
+    "Usage: flux lsp [--root <path> ...] [--roots-only] [--strict] [--cache-dir <dir>]"
+}
+
+/// Returns the `repl` usage text shown when the REPL receives positional arguments.
+pub fn repl_usage() -> &'static str {
+    "Usage: flux repl [--root <path> ...] [--roots-only] [--strict]"
+}
+
+/// Returns the `doc` usage text shown when the documentation input is missing.
+pub fn doc_usage() -> &'static str {
+    "Usage: flux doc <file.flx|dir> [--markdown] [-o <dir>] [--root <path> ...] [--strict]"
+}
+
+/// Returns the `fix` usage text shown when the input file or directory is missing.
+pub fn fix_usage() -> &'static str {
+    "Usage: flux fix <file.flx|dir> [--dry-run] [--root <path> ...] [--strict]"
+}
+
+/// Returns the `debug` usage text shown when the input file or `--dap` is missing.
+pub fn debug_usage() -> &'static str {
+    "Usage: flux debug <file.flx> --dap [--root <path> ...] [--strict]"
+}
+
+/// Returns the `bench` usage text shown when the input file or directory is missing.
+pub fn bench_usage() -> &'static str {
+    "Usage: flux bench <file.flx|dir> [--filter <s>] [--baseline <path>] [--time <ms>] [--native] [--root <path> ...]"
 }
 
 /// Formats the error shown when a command expects a `.flx` source file.
@@ -4,19 +4,22 @@
 //! and by grouping related flags into compact structs before they are converted into
 //! `DriverFlags`.
 
-use std::{path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use crate::{
     diagnostics::DEFAULT_MAX_ERRORS,
     driver::{
-        AetherDumpMode, CoreDumpMode,
+        AetherDumpMode, CoreDumpMode, ProfileFormat, TestReportFormat,
         backend::Backend,
         flags::{
-            DriverBackendFlags, DriverCacheFlags, DriverDiagnosticFlags, DriverDumpFlags,
-            DriverFlags, DriverInputFlags, DriverLanguageFlags, DriverRuntimeFlags,
+            DriverBackendFlags, DriverBenchFlags, DriverCacheFlags, DriverCallTraceFlags,
+            DriverCoverageFlags, DriverDiagnosticFlags, DriverDumpFlags, DriverEffectLogFlags,
+            DriverFlags, DriverInputFlags, DriverLanguageFlags, DriverLintFlags,
+            DriverProfileFlags, DriverRuntimeFlags, DriverTestFlags,
         },
         mode::DiagnosticOutputFormat,
     },
+    syntax::linter::{LintLevel, is_lint_code},
 };
 
 /// Parsed backend-related CLI flags that affect backend selection or backend outputs.
@@ -36,6 +39,8 @@ pub(crate) struct ParsedCliRuntimeFlags {
     pub(crate) trace_aether: bool,
     pub(crate) show_stats: bool,
     pub(crate) profiling: bool,
+    pub(crate) coverage: bool,
+    pub(crate) coverage_exclude_stdlib: bool,
 }
 
 /// Parsed language-related CLI flags that affect compilation semantics.
@@ -54,6 +59,7 @@ pub(crate) struct ParsedCliDumpFlags {
     pub(crate) dump_aether: AetherDumpMode,
     pub(crate) dump_lir: bool,
     pub(crate) dump_lir_llvm: bool,
+    pub(crate) core_eval: bool,
 }
 
 impl Default for ParsedCliDumpFlags {
@@ -65,6 +71,7 @@ impl Default for ParsedCliDumpFlags {
             dump_aether: AetherDumpMode::None,
             dump_lir: false,
             dump_lir_llvm: false,
+            core_eval: false,
         }
     }
 }
@@ -75,7 +82,10 @@ pub(crate) struct ParsedCliExecutionFlags {
     pub(crate) no_cache: bool,
     pub(crate) roots_only: bool,
     pub(crate) test_mode: bool,
+    pub(crate) update_snapshots: bool,
+    pub(crate) doc_tests: bool,
     pub(crate) all_errors: bool,
+    pub(crate) watch: bool,
 }
 
 /// Grouped boolean and mode-like CLI flags extracted from argv.
@@ -116,6 +126,18 @@ pub(crate) struct CliDiagnosticOptions {
 pub(crate) struct CliCommandValueOptions {
     pub(crate) dump_core: CoreDumpMode,
     pub(crate) test_filter: Option<String>,
+    pub(crate) test_format: TestReportFormat,
+    pub(crate) test_output: Option<PathBuf>,
+    pub(crate) test_jobs: usize,
+    pub(crate) test_timeout: Option<Duration>,
+    pub(crate) coverage_output: Option<PathBuf>,
+    pub(crate) prof_format: ProfileFormat,
+    pub(crate) prof_output: Option<PathBuf>,
+    pub(crate) trace_calls: Vec<String>,
+    pub(crate) trace_output: Option<PathBuf>,
+    pub(crate) record: Option<PathBuf>,
+    pub(crate) replay: Option<PathBuf>,
+    pub(crate) lint_levels: Vec<(String, LintLevel)>,
 }
 
 impl Default for CliDiagnosticOptions {
@@ -134,6 +156,18 @@ impl Default for CliCommandValueOptions {
         Self {
             dump_core: CoreDumpMode::None,
             test_filter: None,
+            test_format: TestReportFormat::Text,
+            test_output: None,
+            test_jobs: 1,
+            test_timeout: None,
+            coverage_output: None,
+            prof_format: ProfileFormat::Flat,
+            prof_output: None,
+            trace_calls: Vec::new(),
+            trace_output: None,
+            record: None,
+            replay: None,
+            lint_levels: Vec::new(),
         }
     }
 }
@@ -160,16 +194,25 @@ pub(crate) fn extract_cli_flag_groups(args: &mut Vec<String>) -> ParsedCliFlags
             "--trace" => flags.runtime.trace = remove_bool_flag(args, i),
             "--trace-aether" => flags.runtime.trace_aether = remove_bool_flag(args, i),
             "--prof" => flags.runtime.profiling = remove_bool_flag(args, i),
+            "--coverage" => flags.runtime.coverage = remove_bool_flag(args, i),
+            "--coverage-exclude-stdlib" => {
+                flags.runtime.coverage_exclude_stdlib = remove_bool_flag(args, i);
+            }
             "--roots-only" => flags.execution.roots_only = remove_bool_flag(args, i),
             "--optimize" | "-O" => flags.language.enable_optimize = remove_bool_flag(args, i),
             "--analyze" | "-A" => flags.language.enable_analyze = remove_bool_flag(args, i),
             "--stats" => flags.runtime.show_stats = remove_bool_flag(args, i),
             "--test" => flags.execution.test_mode = remove_bool_flag(args, i),
+            "--update-snapshots" => {
+                flags.execution.update_snapshots = remove_bool_flag(args, i);
+            }
+            "--doc-tests" => flags.execution.doc_tests = remove_bool_flag(args, i),
             "--strict" => flags.language.strict_mode = remove_bool_flag(args, i),
             "--no-strict" => {
                 args.remove(i);
             }
             "--all-errors" => flags.execution.all_errors = remove_bool_flag(args, i),
+            "--watch" => flags.execution.watch = remove_bool_flag(args, i),
             "--dump-repr" => flags.dumps.dump_repr = remove_bool_flag(args, i),
             "--dump-cfg" => flags.dumps.dump_cfg = remove_bool_flag(args, i),
             "--dump-aether" => {
@@ -182,6 +225,7 @@ pub(crate) fn extract_cli_flag_groups(args: &mut Vec<String>) -> ParsedCliFlags
             }
             "--dump-lir" => flags.dumps.dump_lir = remove_bool_flag(args, i),
             "--dump-lir-llvm" => flags.dumps.dump_lir_llvm = remove_bool_flag(args, i),
+            "--core-eval" => flags.dumps.core_eval = remove_bool_flag(args, i),
             "--native" => flags.backend.use_llvm = remove_bool_flag(args, i),
             "--emit-llvm" => flags.backend.emit_llvm = remove_bool_flag(args, i),
             "--emit-binary" => flags.backend.emit_binary = remove_bool_flag(args, i),
@@ -254,6 +298,113 @@ pub(crate) fn extract_cli_value_options(args: &mut Vec<String>) -> Result<CliVal
             values.command.test_filter = Some(filter);
             continue;
         }
+        if let Some(format) = consume_named_value_option(
+            args,
+            &mut i,
+            "--test-format",
+            "Usage: flux <file.flx|dir> --test --test-format <text|junit|tap|json>",
+            parse_test_format,
+        )? {
+            values.command.test_format = format;
+            continue;
+        }
+        if let Some(path) = take_required_long_option(
+            args,
+            &mut i,
+            "--test-output",
+            "Usage: flux <file.flx|dir> --test --test-format <junit|tap|json> --test-output <path>",
+        )? {
+            values.command.test_output = Some(PathBuf::from(path));
+            continue;
+        }
+        if let Some(jobs) = consume_named_value_option(
+            args,
+            &mut i,
+            "--test-jobs",
+            "Usage: flux <file.flx|dir> --test --test-jobs <n>",
+            parse_test_jobs,
+        )? {
+            values.command.test_jobs = jobs;
+            continue;
+        }
+        if let Some(timeout) = consume_named_value_option(
+            args,
+            &mut i,
+            "--test-timeout",
+            "Usage: flux <file.flx|dir> --test --test-timeout <ms>",
+            parse_test_timeout,
+        )? {
+            values.command.test_timeout = Some(timeout);
+            continue;
+        }
+        if let Some(path) = take_required_long_option(
+            args,
+            &mut i,
+            "--coverage-output",
+            "Usage: flux <file.flx|dir> [--test] --coverage --coverage-output <path>",
+        )? {
+            values.command.coverage_output = Some(PathBuf::from(path));
+            continue;
+        }
+        if let Some(format) = consume_named_value_option(
+            args,
+            &mut i,
+            "--prof-format",
+            "Usage: flux <file.flx> --prof --prof-format <flat|folded|folded-alloc|speedscope>",
+            parse_profile_format,
+        )? {
+            values.command.prof_format = format;
+            continue;
+        }
+        if let Some(path) = take_required_long_option(
+            args,
+            &mut i,
+            "--prof-output",
+            "Usage: flux <file.flx> --prof [--prof-format <format>] --prof-output <path>",
+        )? {
+            values.command.prof_output = Some(PathBuf::from(path));
+            continue;
+        }
+        if let Some(patterns) = take_required_long_option(
+            args,
+            &mut i,
+            "--trace-calls",
+            "Usage: flux <file.flx> --trace-calls <pattern>[,<pattern>...]",
+        )? {
+            values.command.trace_calls.push(patterns);
+            continue;
+        }
+        if let Some(path) = take_required_long_option(
+            args,
+            &mut i,
+            "--trace-output",
+            "Usage: flux <file.flx> --trace-calls <pattern> --trace-output <path>",
+        )? {
+            values.command.trace_output = Some(PathBuf::from(path));
+            continue;
+        }
+        if let Some(path) = take_required_long_option(
+            args,
+            &mut i,
+            "--record",
+            "Usage: flux <file.flx> --record <log>",
+        )? {
+            values.command.record = Some(PathBuf::from(path));
+            continue;
+        }
+        if let Some(path) = take_required_long_option(
+            args,
+            &mut i,
+            "--replay",
+            "Usage: flux <file.flx> --replay <log>",
+        )? {
+            values.command.replay = Some(PathBuf::from(path));
+            continue;
+        }
+        if let Some(level) = consume_lint_level_option(args, &mut i)? {
+            values.command.lint_levels.extend(level);
+            continue;
+        }
         if let Some(root) = take_required_long_option(
             args,
             &mut i,
@@ -299,6 +450,7 @@ pub(crate) fn build_driver_flags(parsed: ParsedCliFlags, values: CliValueOptions
             dump_aether: parsed.dumps.dump_aether,
             dump_lir: parsed.dumps.dump_lir,
             dump_lir_llvm: parsed.dumps.dump_lir_llvm,
+            core_eval: parsed.dumps.core_eval,
         },
         diagnostics: DriverDiagnosticFlags {
             max_errors: values.diagnostics.max_errors,
@@ -307,13 +459,43 @@ pub(crate) fn build_driver_flags(parsed: ParsedCliFlags, values: CliValueOptions
         },
         cache: DriverCacheFlags {
             cache_dir: values.paths.cache_dir,
-            no_cache: parsed.execution.no_cache,
+            // Call tracing instruments compiled code, so it bypasses the cache like `--prof`.
+            no_cache: parsed.execution.no_cache || !values.command.trace_calls.is_empty(),
         },
         language: DriverLanguageFlags {
             enable_optimize: parsed.language.enable_optimize,
             enable_analyze: parsed.language.enable_analyze,
             strict_mode: parsed.language.strict_mode,
         },
+        test: DriverTestFlags {
+            format: values.command.test_format,
+            output: values.command.test_output,
+            jobs: values.command.test_jobs,
+            timeout: values.command.test_timeout,
+            update_snapshots: parsed.execution.update_snapshots,
+            doc_tests: parsed.execution.doc_tests,
+        },
+        coverage: DriverCoverageFlags {
+            enabled: parsed.runtime.coverage,
+            output: values.command.coverage_output,
+            exclude_stdlib: parsed.runtime.coverage_exclude_stdlib,
+        },
+        profile: DriverProfileFlags {
+            format: values.command.prof_format,
+            output: values.command.prof_output,
+        },
+        call_trace: DriverCallTraceFlags {
+            patterns: values.command.trace_calls,
+            output: values.command.trace_output,
+        },
+        effect_log: DriverEffectLogFlags {
+            record: values.command.record,
+            replay: values.command.replay,
+        },
+        lint: DriverLintFlags {
+            levels: values.command.lint_levels,
+        },
+        bench: DriverBenchFlags::default(),
     }
     .finalize_backend()
 }
@@ -416,6 +598,93 @@ fn parse_diagnostic_format(value: String) -> Result<DiagnosticOutputFormat, Stri
     Ok(format)
 }
 
+/// Parses the `--test-format` payload into a test report format.
+fn parse_test_format(value: String) -> Result<TestReportFormat, String> {
+    let format = match value.as_str() {
+        "text" => TestReportFormat::Text,
+        "junit" => TestReportFormat::Junit,
+        "tap" => TestReportFormat::Tap,
+        "json" => TestReportFormat::Json,
+        _ => {
+            return Err("Error: --test-format expects one of: text, junit, tap, json.".to_string());
+        }
+    };
+    Ok(format)
+}
+
+/// Parses the `--prof-format` payload into a profile report format.
+fn parse_profile_format(value: String) -> Result<ProfileFormat, String> {
+    let format = match value.as_str() {
+        "flat" => ProfileFormat::Flat,
+        "folded" => ProfileFormat::Folded,
+        "folded-alloc" => ProfileFormat::FoldedAlloc,
+        "speedscope" => ProfileFormat::Speedscope,
+        _ => {
+            return Err(
+                "Error: --prof-format expects one of: flat, folded, folded-alloc, speedscope."
+                    .to_string(),
+            );
+        }
+    };
+    Ok(format)
+}
+
+/// Parses the `--test-jobs` payload into a positive worker count.
+fn parse_test_jobs(value: String) -> Result<usize, String> {
+    match usize::from_str(&value) {
+        Ok(jobs) if jobs > 0 => Ok(jobs),
+        _ => Err("Error: --test-jobs expects a positive integer.".to_string()),
+    }
+}
+
+/// Consumes `--deny`, `--allow` or `--warn` followed by a comma-separated list of lint codes.
+fn consume_lint_level_option(
+    args: &mut Vec<String>,
+    index: &mut usize,
+) -> Result<Option<Vec<(String, LintLevel)>>, String> {
+    for (flag, level) in [
+        ("--deny", LintLevel::Deny),
+        ("--allow", LintLevel::Allow),
+        ("--warn", LintLevel::Warn),
+    ] {
+        if let Some(codes) = consume_named_value_option(
+            args,
+            index,
+            flag,
+            &format!("Usage: flux lint <file.flx> {flag} <code>[,<code>...]"),
+            parse_lint_codes,
+        )? {
+            return Ok(Some(codes.into_iter().map(|code| (code, level)).collect()));
+        }
+    }
+    Ok(None)
+}
+
+/// Parses a comma-separated list of lint codes such as `W011,W012`.
+fn parse_lint_codes(value: String) -> Result<Vec<String>, String> {
+    value
+        .split(',')
+        .map(|code| {
+            let code = code.trim().to_ascii_uppercase();
+            if is_lint_code(&code) {
+                Ok(code)
+            } else {
+                Err(format!(
+                    "Error: unknown lint `{code}`; lint codes are W001 to W013."
+                ))
+            }
+        })
+        .collect()
+}
+
+/// Parses the `--test-timeout` payload, in milliseconds, into a per-test time limit.
+fn parse_test_timeout(value: String) -> Result<Duration, String> {
+    match u64::from_str(&value) {
+        Ok(ms) if ms > 0 => Ok(Duration::from_millis(ms)),
+        _ => Err("Error: --test-timeout expects a positive number of milliseconds.".to_string()),
+    }
+}
+
 /// Parses the `--max-errors` payload into a concrete error limit.
 fn parse_max_errors(value: String) -> Result<usize, String> {
     usize::from_str(&value)
@@ -432,8 +701,9 @@ mod tests {
     };
     #[cfg(feature = "llvm")]
     use crate::driver::backend::Backend;
-    use crate::driver::{AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat};
-    use std::path::Path;
+    use crate::driver::{AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat, TestReportFormat};
+    use crate::syntax::linter::LintLevel;
+    use std::{path::Path, time::Duration};
 
     #[test]
     fn extract_flags_groups_boolean_switches_and_aliases() {
@@ -564,6 +834,54 @@ mod tests {
         assert!(err.contains("--root"));
     }
 
+    #[test]
+    fn extract_value_options_collects_lint_levels_in_order() {
+        let mut args = vec![
+            "flux".into(),
+            "lint".into(),
+            "file.flx".into(),
+            "--deny".into(),
+            "w011,W012".into(),
+            "--allow=W007".into(),
+            "--warn".into(),
+            "W012".into(),
+        ];
+
+        let values = extract_cli_value_options(&mut args).unwrap();
+
+        assert_eq!(
+            values.command.lint_levels,
+            vec![
+                ("W011".to_string(), LintLevel::Deny),
+                ("W012".to_string(), LintLevel::Deny),
+                ("W007".to_string(), LintLevel::Allow),
+                ("W012".to_string(), LintLevel::Warn),
+            ]
+        );
+        assert_eq!(
+            args,
+            vec![
+                "flux".to_string(),
+                "lint".to_string(),
+                "file.flx".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_value_options_rejects_unknown_lint_code() {
+        let mut args = vec![
+            "flux".into(),
+            "lint".into(),
+            "file.flx".into(),
+            "--deny=W099".into(),
+        ];
+
+        let err = extract_cli_value_options(&mut args).unwrap_err();
+
+        assert!(err.contains("unknown lint `W099`"));
+    }
+
     #[test]
     fn extract_value_options_rejects_missing_test_filter_value() {
         let mut args = vec!["flux".into(), "file.flx".into(), "--test-filter".into()];
@@ -573,6 +891,52 @@ mod tests {
         assert!(err.contains("--test-filter"));
     }
 
+    #[test]
+    fn extract_value_options_parses_test_report_options() {
+        let mut args = vec![
+            "flux".into(),
+            "tests".into(),
+            "--test-format=junit".into(),
+            "--test-output".into(),
+            "report.xml".into(),
+        ];
+
+        let values = extract_cli_value_options(&mut args).unwrap();
+
+        assert_eq!(values.command.test_format, TestReportFormat::Junit);
+        assert_eq!(
+            values.command.test_output.as_deref(),
+            Some(Path::new("report.xml"))
+        );
+
+        let mut args = vec!["flux".into(), "tests".into(), "--test-format=xml".into()];
+        let err = extract_cli_value_options(&mut args).unwrap_err();
+        assert!(err.contains("--test-format"));
+    }
+
+    #[test]
+    fn extract_value_options_parses_test_jobs_and_timeout() {
+        let mut args = vec![
+            "flux".into(),
+            "tests".into(),
+            "--test-jobs".into(),
+            "4".into(),
+            "--test-timeout=250".into(),
+        ];
+
+        let values = extract_cli_value_options(&mut args).unwrap();
+
+        assert_eq!(values.command.test_jobs, 4);
+        assert_eq!(
+            values.command.test_timeout,
+            Some(Duration::from_millis(250))
+        );
+
+        let mut args = vec!["flux".into(), "tests".into(), "--test-jobs=0".into()];
+        let err = extract_cli_value_options(&mut args).unwrap_err();
+        assert!(err.contains("--test-jobs"));
+    }
+
     #[test]
     fn extract_value_options_rejects_missing_output_value() {
         let mut args = vec!["flux".into(), "file.flx".into(), "-o".into()];
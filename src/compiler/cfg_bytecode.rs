@@ -191,21 +191,24 @@ impl Compiler {
             let binding = self.symbol_table.resolve(param.name)?;
             bindings.insert(param.var, binding);
         }
+        let mut define_var = |compiler: &mut Self, var: IrVar| {
+            bindings.entry(var).or_insert_with(|| {
+                let binding = compiler.symbol_table.define_temp();
+                if let Some(name) = function.local_names.get(&var) {
+                    compiler.symbol_table.name_temp(&binding, *name);
+                }
+                binding
+            });
+        };
         for block in &function.blocks {
             for param in &block.params {
-                bindings
-                    .entry(param.var)
-                    .or_insert_with(|| self.symbol_table.define_temp());
+                define_var(self, param.var);
             }
             for instr in &block.instrs {
                 match instr {
                     IrInstr::Assign { dest, .. }
                     | IrInstr::Call { dest, .. }
-                    | IrInstr::HandleScope { dest, .. } => {
-                        bindings
-                            .entry(*dest)
-                            .or_insert_with(|| self.symbol_table.define_temp());
-                    }
+                    | IrInstr::HandleScope { dest, .. } => define_var(self, *dest),
                     IrInstr::AetherDrop { .. } => {}
                 }
             }
@@ -665,6 +668,7 @@ impl Compiler {
                         ))
                     })?);
                 }
+                self.emit_trace_perform(*effect, *operation, args.len());
                 // Create PerformDescriptor constant
                 let effect_name = self.sym(*effect).to_string();
                 let op_name = self.sym(*operation).to_string();
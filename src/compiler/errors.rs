@@ -1,6 +1,7 @@
 use crate::diagnostics::{
-    DUPLICATE_NAME, Diagnostic, DiagnosticBuilder, IMMUTABLE_BINDING, IMPORT_NAME_COLLISION,
-    OUTER_ASSIGNMENT, PRIVATE_MEMBER, UNDEFINED_VARIABLE, position::Span,
+    Applicability, DUPLICATE_NAME, Diagnostic, DiagnosticBuilder, IMMUTABLE_BINDING,
+    IMPORT_NAME_COLLISION, InlineSuggestion, OUTER_ASSIGNMENT, PRIVATE_MEMBER, UNDEFINED_VARIABLE,
+    position::Span,
 };
 
 use super::{CompileResult, Compiler, suggestions::find_similar_names};
@@ -56,6 +57,14 @@ impl Compiler {
                 format!("Did you mean one of: {}?", names)
             };
             diagnostic = diagnostic.with_help(suggestion_text);
+            // A similar name is only a guess, so `flux fix` leaves it to the user.
+            if let [only] = suggestions.as_slice() {
+                diagnostic = diagnostic.with_suggestion(
+                    InlineSuggestion::new(span, only.clone())
+                        .with_message(format!("Replace `{name}` with `{only}`"))
+                        .with_applicability(Applicability::MaybeIncorrect),
+                );
+            }
         }
 
         diagnostic
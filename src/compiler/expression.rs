@@ -43,7 +43,7 @@ use crate::{
     syntax::{
         block::Block,
         effect_expr::EffectExpr,
-        expression::{Expression, HandleArm, MatchArm, Pattern, StringPart},
+        expression::{Expression, HandleArm, MatchArm, Pattern, StringPart, hole_panic_message},
         module_graph::is_valid_module_name,
         statement::Statement,
         symbol::Symbol,
@@ -830,10 +830,12 @@ impl Compiler {
                 // If the callee is a class method with a known argument type,
                 // compile a call to the mangled instance function directly.
                 if let Expression::Identifier { name, .. } = function.as_ref()
-                    && let Some(mangled) = self.try_resolve_class_method_call(*name, arguments)
+                    && let Some(mangled) =
+                        self.try_resolve_class_method_call(*name, expression.expr_id(), arguments)
                 {
                     let mut resolved_args = self.resolve_direct_class_call_dict_args_ast(
                         *name,
+                        expression.expr_id(),
                         arguments,
                         function.span(),
                     );
@@ -862,10 +864,12 @@ impl Compiler {
                 // we only need the method name to find the mangled function.
                 if let Expression::MemberAccess { object, member, .. } = function.as_ref()
                     && self.resolve_module_name_from_expr(object).is_some()
-                    && let Some(mangled) = self.try_resolve_class_method_call(*member, arguments)
+                    && let Some(mangled) =
+                        self.try_resolve_class_method_call(*member, expression.expr_id(), arguments)
                 {
                     let mut resolved_args = self.resolve_direct_class_call_dict_args_ast(
                         *member,
+                        expression.expr_id(),
                         arguments,
                         function.span(),
                     );
@@ -1171,6 +1175,12 @@ impl Compiler {
                      (proposal 0152 Phase 3)"
                 );
             }
+            Expression::Hole { name, .. } => {
+                let message = hole_panic_message(name.map(|name| self.sym(name)));
+                let idx = self.add_constant(Value::String(message.into()));
+                self.emit_constant_index(idx);
+                self.emit(OpCode::OpPrimOp, &[CorePrimOp::Panic.id() as usize, 1]);
+            }
         }
         self.current_span = previous_span;
         Ok(())
@@ -2262,6 +2272,7 @@ impl Compiler {
         }
 
         let num_locals = self.symbol_table.num_definitions;
+        let (local_names, capture_names) = self.local_debug_names();
 
         let (instructions, locations, files, effect_summary) = self.leave_scope();
 
@@ -2307,7 +2318,8 @@ impl Compiler {
                 Some(
                     FunctionDebugInfo::new(None, files, locations)
                         .with_boundary_location(Some(boundary_location))
-                        .with_effect_summary(effect_summary),
+                        .with_effect_summary(effect_summary)
+                        .with_locals(local_names, capture_names),
                 ),
             )
             .with_contract(runtime_contract),
@@ -3509,6 +3521,7 @@ impl Compiler {
             for arg in args {
                 self.compile_non_tail_expression(arg)?;
             }
+            self.emit_trace_perform(effect, op, args.len());
             // Call: arm_closure(resume, arg0, ..., argN)
             self.emit(OpCode::OpCall, &[1 + args.len()]);
         } else {
@@ -3516,6 +3529,7 @@ impl Compiler {
             for arg in args {
                 self.compile_non_tail_expression(arg)?;
             }
+            self.emit_trace_perform(effect, op, args.len());
             if let Some((depth, arm_idx)) = self.resolve_handler_statically(effect, op) {
                 self.emit(
                     OpCode::OpPerformDirectIndexed,
@@ -3849,6 +3863,7 @@ impl Compiler {
             is_direct,
             ops: scope_ops,
             evidence_symbols,
+            function_scope: self.scope_index,
         });
 
         // Compile the handled expression with the effect available in scope.
@@ -4106,7 +4121,8 @@ impl Compiler {
             | Expression::String { .. }
             | Expression::Boolean { .. }
             | Expression::None { .. }
-            | Expression::EmptyList { .. } => {}
+            | Expression::EmptyList { .. }
+            | Expression::Hole { .. } => {}
             Expression::NamedConstructor { fields, .. } => {
                 for field in fields {
                     if let Some(value) = &field.value {
@@ -4265,45 +4281,67 @@ impl Compiler {
     ///
     /// If `name` is a class method and the first argument's HM-inferred type
     /// is concrete, returns the mangled instance function symbol.
-    fn try_resolve_class_method_call(
+    /// Find the instance a direct class-method call resolves to, from the
+    /// first argument's type or, failing that, the call's result type.
+    fn resolve_class_method_call_instance(
         &self,
         name: crate::syntax::Identifier,
+        call_id: crate::syntax::expression::ExprId,
         arguments: &[Expression],
-    ) -> Option<crate::syntax::Identifier> {
-        if self.class_env.classes.is_empty() {
-            return None;
-        }
+    ) -> Option<(
+        crate::syntax::Identifier,
+        &crate::types::class_env::InstanceDef,
+        Vec<InferType>,
+    )> {
         let (class_name, _) = self.class_env.method_to_class(name)?;
-
-        // Try compile-time resolution: if the first argument's type is concrete,
-        // resolve directly to the mangled instance function.
-        if let Some(first_arg) = arguments.first()
-            && let Some(first_arg_type) = self.hm_expr_types.get(&first_arg.expr_id())
-            && let Some((instance, _concrete_type_args)) =
+        arguments
+            .first()
+            .and_then(|first_arg| self.hm_expr_types.get(&first_arg.expr_id()))
+            .and_then(|first_arg_type| {
                 self.class_env.resolve_method_call_instance_from_first_arg(
                     class_name,
                     first_arg_type,
                     &self.interner,
                 )
-        {
-            // Build mangled name from all instance type args (multi-param support).
-            let type_key = instance
-                .type_args
-                .iter()
-                .map(|a| a.display_with(&self.interner))
-                .collect::<Vec<_>>()
-                .join("_");
-            let class_str = self.interner.resolve(class_name);
-            let method_str = self.interner.resolve(name);
-            let mangled = format!("__tc_{class_str}_{type_key}_{method_str}");
-            if let Some(sym) = self.interner.lookup(&mangled) {
-                return Some(sym);
-            }
+            })
+            .or_else(|| {
+                let result_type = self.hm_expr_types.get(&call_id)?;
+                self.class_env.resolve_method_call_instance_from_result(
+                    class_name,
+                    name,
+                    result_type,
+                    &self.interner,
+                )
+            })
+            .map(|(instance, concrete_type_args)| (class_name, instance, concrete_type_args))
+    }
+
+    fn try_resolve_class_method_call(
+        &self,
+        name: crate::syntax::Identifier,
+        call_id: crate::syntax::expression::ExprId,
+        arguments: &[Expression],
+    ) -> Option<crate::syntax::Identifier> {
+        if self.class_env.classes.is_empty() {
+            return None;
         }
 
-        // No compile-time resolution possible — return None.
-        // Dictionary elaboration handles polymorphic calls via dict params.
-        None
+        // Try compile-time resolution; unresolved calls are left to
+        // dictionary elaboration.
+        let (class_name, instance, _concrete_type_args) =
+            self.resolve_class_method_call_instance(name, call_id, arguments)?;
+
+        // Build mangled name from all instance type args (multi-param support).
+        let type_key = instance
+            .type_args
+            .iter()
+            .map(|a| a.display_with(&self.interner))
+            .collect::<Vec<_>>()
+            .join("_");
+        let class_str = self.interner.resolve(class_name);
+        let method_str = self.interner.resolve(name);
+        self.interner
+            .lookup(&format!("__tc_{class_str}_{type_key}_{method_str}"))
     }
 
     fn try_build_dict_class_method_call(
@@ -4320,7 +4358,9 @@ impl Compiler {
         let method_index = self.class_env.method_index(class_name, name)?;
         let class_str = self.interner.resolve(class_name);
         let dict_name = format!("__dict_{class_str}");
-        let dict_sym = self.interner.lookup(&dict_name)?;
+        // dict_elaborate names the parameter after the class itself when
+        // `__dict_{Class}` was never interned.
+        let dict_sym = self.interner.lookup(&dict_name).unwrap_or(class_name);
         self.symbol_table.resolve(dict_sym)?;
 
         Some(Expression::Call {
@@ -4343,22 +4383,12 @@ impl Compiler {
     fn resolve_direct_class_call_dict_args_ast(
         &self,
         method_name: crate::syntax::Identifier,
+        call_id: crate::syntax::expression::ExprId,
         arguments: &[Expression],
         span: Span,
     ) -> Vec<Expression> {
-        let Some((class_name, _)) = self.class_env.method_to_class(method_name) else {
-            return Vec::new();
-        };
-        let Some(first_arg) = arguments.first() else {
-            return Vec::new();
-        };
-        let Some(first_arg_ty) = self.hm_expr_types.get(&first_arg.expr_id()) else {
-            return Vec::new();
-        };
-
-        self.class_env
-            .resolve_method_call_instance_from_first_arg(class_name, first_arg_ty, &self.interner)
-            .and_then(|(_instance, concrete_type_args)| {
+        self.resolve_class_method_call_instance(method_name, call_id, arguments)
+            .and_then(|(class_name, _instance, concrete_type_args)| {
                 self.class_env.resolve_instance_context_dictionaries(
                     class_name,
                     &concrete_type_args,
@@ -4509,6 +4539,19 @@ impl Compiler {
                     .zip(actual_args.iter())
                     .find_map(|(p, a)| Self::match_scheme_constraint_type_var_ast(p, a, target))
             }
+            InferType::Fun(pattern_params, pattern_ret, _) => {
+                let InferType::Fun(actual_params, actual_ret, _) = actual else {
+                    return None;
+                };
+                if pattern_params.len() != actual_params.len() {
+                    return None;
+                }
+                pattern_params
+                    .iter()
+                    .zip(actual_params.iter())
+                    .chain(std::iter::once((pattern_ret.as_ref(), actual_ret.as_ref())))
+                    .find_map(|(p, a)| Self::match_scheme_constraint_type_var_ast(p, a, target))
+            }
             _ => None,
         }
     }
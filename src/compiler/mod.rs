@@ -19,7 +19,7 @@ use crate::{
     bytecode::{
         bytecode::Bytecode,
         bytecode_cache::module_cache::{CachedModuleBinding, CachedModuleBytecode},
-        debug_info::{EffectSummary, FunctionDebugInfo, InstructionLocation},
+        debug_info::{EffectSummary, FunctionDebugInfo, InstructionLocation, LocalName},
         emitted_instruction::EmittedInstruction,
         op_code::{Instructions, OpCode, make},
     },
@@ -73,6 +73,7 @@ pub mod module_interface;
 pub mod module_linker;
 mod passes;
 pub(crate) mod pipeline;
+pub mod repl_state;
 mod statement;
 mod suggestions;
 pub mod symbol_scope;
@@ -610,6 +611,19 @@ fn preload_imported_instance_schemes(
     }
 }
 
+fn same_instance_head(
+    left: &crate::types::class_env::InstanceDef,
+    right: &crate::types::class_env::InstanceDef,
+) -> bool {
+    left.class_id == right.class_id
+        && left.type_args.len() == right.type_args.len()
+        && left
+            .type_args
+            .iter()
+            .zip(right.type_args.iter())
+            .all(|(a, b)| a.structural_eq(b))
+}
+
 fn merge_imported_public_instances(
     env: &mut crate::types::class_env::ClassEnv,
     imported_instances: &[crate::types::class_env::InstanceDef],
@@ -617,15 +631,10 @@ fn merge_imported_public_instances(
     interner: &Interner,
 ) {
     for imported in imported_instances {
-        let duplicate = env.instances.iter().find(|existing| {
-            existing.class_id == imported.class_id
-                && existing.type_args.len() == imported.type_args.len()
-                && existing
-                    .type_args
-                    .iter()
-                    .zip(imported.type_args.iter())
-                    .all(|(a, b)| a.structural_eq(b))
-        });
+        let duplicate = env
+            .instances
+            .iter()
+            .find(|existing| same_instance_head(existing, imported));
         if let Some(existing) = duplicate {
             let display_class = interner.resolve(imported.class_name);
             let display_type: Vec<String> = imported
@@ -953,6 +962,10 @@ pub(super) struct HandlerScope {
     /// → `SymbolScope::Free`). `None` when evidence-passing is not applicable
     /// (non-TR handler).
     pub evidence_symbols: Option<Vec<Symbol>>,
+    /// `scope_index` of the function the handle was compiled in. A perform in a
+    /// nested closure may run after the closure escapes, under other handlers,
+    /// so only performs in this same function resolve against the scope.
+    pub function_scope: usize,
 }
 
 pub struct Compiler {
@@ -1049,10 +1062,17 @@ pub struct Compiler {
     /// When true, run two-phase inference with type-informed optimization
     /// between Phase 1 and Phase 2 (proposal 0077).
     type_optimize: bool,
-    /// When true, emit OpEnterCC at function entry for profiling.
+    /// When true, emit OpEnterCC at function entry for profiling, and instrument
+    /// native modules with the C runtime profiler hooks.
     profiling: bool,
     /// Cost centre metadata accumulated during compilation.
     pub cost_centre_infos: Vec<crate::bytecode::debug_info::CostCentreInfo>,
+    /// Functions selected by `--trace-calls`. When set, selected functions log their entry
+    /// with `OpTraceCall`, every effect operation logs itself with `OpTracePerform`, and
+    /// native modules get the matching LIR trace events.
+    call_trace: Option<crate::runtime::call_trace::CallTraceFilter>,
+    /// Labels referenced by `OpTraceCall` and `OpTracePerform`.
+    pub call_trace_labels: Vec<String>,
     /// Type class environment — populated during collection phase.
     pub(super) class_env: crate::types::class_env::ClassEnv,
     /// Imported `public class` entries reconstructed from preloaded module interfaces.
@@ -1066,6 +1086,12 @@ pub struct Compiler {
     /// Imported monomorphic `__tc_*` schemes rebuilt from public instance metadata.
     imported_instance_method_schemes: HashMap<Symbol, Scheme>,
     imported_instance_method_native_symbols: HashMap<Symbol, String>,
+    /// Schemes for bindings made by earlier REPL inputs. Every input is
+    /// inferred as a fresh program, so HM sees these as preloaded globals.
+    repl_schemes: HashMap<Symbol, Scheme>,
+    /// Inferred effects of functions declared by earlier REPL inputs, seeded
+    /// into effect inference so callers in later inputs see them.
+    repl_function_effects: HashMap<ContractKey, HashSet<Symbol>>,
     /// `ExprId`s of `Perform` nodes that were synthesized from direct user
     /// calls via the 0165 routing pass. Used by E400 / other effect
     /// diagnostics to render the user's call shape instead of the lowered
@@ -1101,6 +1127,20 @@ impl Compiler {
         self.current_module_kind == ModuleKind::FlowStdlib
     }
 
+    /// Flow library files only get instance dispatch functions when they
+    /// declare instances of their own (e.g. `Flow.Gen`'s `Arbitrary`).
+    fn generates_class_dispatch(&self, program: &Program) -> bool {
+        fn declares_instances(statements: &[Statement]) -> bool {
+            statements.iter().any(|statement| match statement {
+                Statement::Instance { .. } => true,
+                Statement::Module { body, .. } => declares_instances(&body.statements),
+                _ => false,
+            })
+        }
+        !self.class_env.classes.is_empty()
+            && (!self.is_flow_library_file() || declares_instances(&program.statements))
+    }
+
     pub(super) fn inject_generated_dispatch_functions(
         &self,
         program: &Program,
@@ -1122,12 +1162,8 @@ impl Compiler {
                 .statements
                 .iter()
                 .cloned()
-                .enumerate()
-                .flat_map(|(idx, stmt)| {
+                .flat_map(|stmt| {
                     let mut emitted = Vec::new();
-                    if idx == 0 {
-                        emitted.extend(top_level_generated.clone());
-                    }
                     match stmt {
                         Statement::Module { name, body, span } => {
                             let mut module_statements = module_generated.clone();
@@ -1140,6 +1176,9 @@ impl Compiler {
                                 },
                                 span,
                             });
+                            // Instance functions follow the module so that
+                            // their bodies can call its (qualified) members.
+                            emitted.extend(top_level_generated.clone());
                         }
                         other => emitted.push(other),
                     }
@@ -1151,8 +1190,16 @@ impl Compiler {
                 span: program.span,
             }
         } else {
-            let mut statements = generated;
-            statements.extend(program.statements.iter().cloned());
+            // Generated functions go after the leading imports so that
+            // instance bodies can call imported functions.
+            let imports_end = program
+                .statements
+                .iter()
+                .position(|stmt| !matches!(stmt, Statement::Import { .. }))
+                .unwrap_or(program.statements.len());
+            let mut statements = program.statements[..imports_end].to_vec();
+            statements.extend(generated);
+            statements.extend(program.statements[imports_end..].iter().cloned());
             Program {
                 statements,
                 span: program.span,
@@ -1235,12 +1282,16 @@ impl Compiler {
             type_optimize: false,
             profiling: false,
             cost_centre_infos: Vec::new(),
+            call_trace: None,
+            call_trace_labels: Vec::new(),
             class_env: crate::types::class_env::ClassEnv::new(),
             imported_public_classes: HashMap::new(),
             imported_public_instances: Vec::new(),
             pending_imported_public_instance_entries: Vec::new(),
             imported_instance_method_schemes: HashMap::new(),
             imported_instance_method_native_symbols: HashMap::new(),
+            repl_schemes: HashMap::new(),
+            repl_function_effects: HashMap::new(),
             routed_call_perform_ids: HashSet::new(),
             #[cfg(test)]
             hm_infer_runs: 0,
@@ -1360,6 +1411,7 @@ impl Compiler {
         program_to_lower: &Program,
         optimize: bool,
         elaborate_dictionaries: bool,
+        observer: Option<&mut crate::core::passes::CorePassObserver<'_>>,
     ) -> Result<crate::core::CoreProgram, Diagnostic> {
         let class_env_ref = if self.class_env.classes.is_empty() {
             None
@@ -1386,6 +1438,17 @@ impl Compiler {
             );
         }
 
+        if let Some(observe) = observer {
+            observe("lowered", &core);
+            crate::core::passes::run_semantic_core_passes_observed(
+                &mut core,
+                &self.interner,
+                optimize,
+                observe,
+            )?;
+            return Ok(core);
+        }
+
         let preloaded_registry = self.build_preloaded_borrow_registry(program_to_lower);
         let _ = preloaded_registry;
         crate::core::passes::run_core_passes_with_interner(&mut core, &self.interner, optimize)?;
@@ -1400,7 +1463,7 @@ impl Compiler {
         elaborate_dictionaries: bool,
     ) -> Result<crate::aether::AetherProgram, Diagnostic> {
         let core =
-            self.lower_core_from_program(program_to_lower, optimize, elaborate_dictionaries)?;
+            self.lower_core_from_program(program_to_lower, optimize, elaborate_dictionaries, None)?;
         let preloaded_registry = self.build_preloaded_borrow_registry(program_to_lower);
         let (aether, _) = crate::aether::lower_core_to_aether_program(
             &core,
@@ -1416,6 +1479,7 @@ impl Compiler {
         program: &Program,
         optimize: bool,
         elaborate_dictionaries: bool,
+        observer: Option<&mut crate::core::passes::CorePassObserver<'_>>,
     ) -> Result<crate::core::CoreProgram, Diagnostic> {
         if optimize {
             use crate::ast::{constant_fold_with_interner, desugar, rename};
@@ -1423,14 +1487,19 @@ impl Compiler {
             let optimized = constant_fold_with_interner(desugared, &self.interner);
             let mut program_to_lower = rename(optimized, HashMap::new());
             self.apply_named_field_desugar(&mut program_to_lower);
-            return self.lower_core_from_program(&program_to_lower, true, elaborate_dictionaries);
+            return self.lower_core_from_program(
+                &program_to_lower,
+                true,
+                elaborate_dictionaries,
+                observer,
+            );
         }
 
         let prepared = self.prepare_program_for_lowering(program);
         self.apply_hm_final(&prepared.hm_final);
         let mut program_to_lower = prepared.effective_program.into_owned();
         self.apply_named_field_desugar(&mut program_to_lower);
-        self.lower_core_from_program(&program_to_lower, false, elaborate_dictionaries)
+        self.lower_core_from_program(&program_to_lower, false, elaborate_dictionaries, observer)
     }
 
     /// Proposal 0152: run the named-field AST desugar in place. No-op when
@@ -1587,7 +1656,7 @@ impl Compiler {
         self.collect_effect_declarations(program);
         self.auto_expose_flow_modules();
 
-        if !self.class_env.classes.is_empty() && !self.is_flow_library_file() {
+        if self.generates_class_dispatch(program) {
             let additional_reserved_names = self
                 .preloaded_imported_globals
                 .iter()
@@ -1688,6 +1757,36 @@ impl Compiler {
         idx
     }
 
+    pub fn set_call_trace(&mut self, filter: Option<crate::runtime::call_trace::CallTraceFilter>) {
+        self.call_trace = filter;
+    }
+
+    /// Returns the index of `label` in [`Compiler::call_trace_labels`], adding it if needed.
+    fn register_trace_label(&mut self, label: String) -> u16 {
+        match self
+            .call_trace_labels
+            .iter()
+            .position(|known| *known == label)
+        {
+            Some(idx) => idx as u16,
+            None => {
+                self.call_trace_labels.push(label);
+                (self.call_trace_labels.len() - 1) as u16
+            }
+        }
+    }
+
+    /// Logs the effect operation about to be performed, with its `arity` arguments on top
+    /// of the stack, when `--trace-calls` is on.
+    pub(super) fn emit_trace_perform(&mut self, effect: Symbol, op: Symbol, arity: usize) {
+        if self.call_trace.is_none() {
+            return;
+        }
+        let label = format!("{}.{}", self.sym(effect), self.sym(op));
+        let label_idx = self.register_trace_label(label);
+        self.emit(OpCode::OpTracePerform, &[label_idx as usize, arity]);
+    }
+
     pub fn set_strict_require_main(&mut self, strict_require_main: bool) {
         self.strict_require_main = strict_require_main;
     }
@@ -1743,6 +1842,21 @@ impl Compiler {
         &self.class_env
     }
 
+    /// Read-only access to the HM expression types of the most recently
+    /// compiled module, keyed by parser-assigned `ExprId`.
+    ///
+    /// Editor tooling uses this to answer hover queries without re-running
+    /// inference after `compile`.
+    pub fn hm_expr_types(&self) -> &HashMap<ExprId, InferType> {
+        &self.hm_expr_types
+    }
+
+    /// Read-only access to the HM type environment of the most recently
+    /// compiled module.
+    pub fn hm_type_env(&self) -> &TypeEnv {
+        &self.type_env
+    }
+
     pub fn preload_module_interface(
         &mut self,
         interface: &crate::types::module_interface::ModuleInterface,
@@ -1884,6 +1998,16 @@ impl Compiler {
         self.preloaded_effect_op_signatures = self.effect_op_signatures.clone();
     }
 
+    /// Replaces the native constructor tags gathered while preloading
+    /// dependencies with a numbering shared by every module of a native build.
+    pub fn set_native_constructor_tags(&mut self, tags: &HashMap<Symbol, i32>) {
+        self.native_constructor_tags
+            .extend(tags.iter().map(|(name, tag)| (*name, *tag)));
+        if let Some(max_tag) = self.native_constructor_tags.values().max() {
+            self.next_native_constructor_tag = self.next_native_constructor_tag.max(max_tag + 1);
+        }
+    }
+
     pub fn build_native_extern_symbols(
         &self,
         program: &Program,
@@ -2849,6 +2973,12 @@ impl Compiler {
                 .or_insert_with(|| scheme.clone());
         }
 
+        // REPL bindings shadow prelude and primop names, like a later
+        // top-level definition would.
+        for (&name, scheme) in &self.repl_schemes {
+            exposed_schemes.insert(name, scheme.clone());
+        }
+
         let class_env = if self.class_env.classes.is_empty() {
             None
         } else {
@@ -3324,14 +3454,17 @@ impl Compiler {
 
         let seeds = self.collect_function_effect_seeds(program);
         if seeds.is_empty() {
-            self.inferred_function_effects.clear();
+            self.inferred_function_effects = self.repl_function_effects.clone();
             return;
         }
 
-        let mut inferred: HashMap<ContractKey, HashSet<Symbol>> = seeds
-            .iter()
-            .map(|seed| (seed.key.clone(), seed.declared_effects.clone()))
-            .collect();
+        let mut inferred: HashMap<ContractKey, HashSet<Symbol>> =
+            self.repl_function_effects.clone();
+        inferred.extend(
+            seeds
+                .iter()
+                .map(|seed| (seed.key.clone(), seed.declared_effects.clone())),
+        );
 
         let mut changed = true;
         while changed {
@@ -3471,7 +3604,8 @@ impl Compiler {
             | Expression::String { .. }
             | Expression::Boolean { .. }
             | Expression::None { .. }
-            | Expression::EmptyList { .. } => HashSet::new(),
+            | Expression::EmptyList { .. }
+            | Expression::Hole { .. } => HashSet::new(),
 
             Expression::InterpolatedString { parts, .. } => {
                 let mut effects = HashSet::new();
@@ -4457,6 +4591,15 @@ impl Compiler {
                 | "Option"
                 | "Either"
         ) || self.adt_registry.lookup_adt(name).is_some()
+            || self.is_imported_adt_name(name)
+    }
+
+    /// Whether `name` is an ADT declared by a module compiled before this file,
+    /// such as `Gen` from `import Flow.Gen exposing (..)`.
+    fn is_imported_adt_name(&self, name: Symbol) -> bool {
+        self.module_adt_constructors
+            .values()
+            .any(|adt| *adt == name)
     }
 
     fn strict_missing_ambient_effects(
@@ -4839,7 +4982,7 @@ impl Compiler {
         optimize: bool,
         mode: crate::core::display::CoreDisplayMode,
     ) -> Result<String, Diagnostic> {
-        let core = self.prepare_core_program(program, optimize, true)?;
+        let core = self.prepare_core_program(program, optimize, true, None)?;
 
         let ir_text = match mode {
             crate::core::display::CoreDisplayMode::Readable => {
@@ -4852,6 +4995,36 @@ impl Compiler {
         Ok(ir_text)
     }
 
+    /// Lower to Core IR and return a snapshot of the program after each named
+    /// semantic pass, starting with the freshly lowered program (`lowered`).
+    ///
+    /// Feeds the reference Core interpreter (`--core-eval`), which evaluates
+    /// every snapshot to find the first pass that changed behavior. Prepared
+    /// like the backend program (with preloaded module types) so the
+    /// snapshots match what the backends execute.
+    #[allow(clippy::result_large_err)]
+    pub fn core_pass_snapshots(
+        &mut self,
+        program: &Program,
+        optimize: bool,
+    ) -> Result<Vec<(String, crate::core::CoreProgram)>, Diagnostic> {
+        let mut snapshots = Vec::new();
+        let mut observe = |stage: &str, core: &crate::core::CoreProgram| {
+            snapshots.push((stage.to_string(), core.clone()));
+        };
+        if optimize {
+            self.prepare_core_program(program, true, true, Some(&mut observe))?;
+            return Ok(snapshots);
+        }
+
+        let prepared = self.prepare_program_for_lowering_with_preloaded(program);
+        self.apply_hm_final(&prepared.hm_final);
+        let mut program_to_lower = prepared.effective_program.into_owned();
+        self.apply_named_field_desugar(&mut program_to_lower);
+        self.lower_core_from_program(&program_to_lower, false, true, Some(&mut observe))?;
+        Ok(snapshots)
+    }
+
     /// Lower to Core IR, then to LIR, and return a human-readable dump.
     #[allow(clippy::result_large_err)]
     pub fn dump_lir(&mut self, program: &Program, optimize: bool) -> Result<String, Diagnostic> {
@@ -4902,12 +5075,19 @@ impl Compiler {
         // Pass None for globals_map so ALL functions are lowered to LIR
         // functions (no GetGlobal). In native mode there's no VM globals
         // table, so every function must be compiled into the LLVM module.
-        let lir = crate::lir::lower::lower_aether_program_with_interner(
+        let mut lir = crate::lir::lower::lower_aether_program_with_interner(
             &aether,
             Some(&self.interner),
             None,
         );
-        Ok(crate::lir::emit_llvm::emit_llvm_module(&lir))
+        if let Some(filter) = &self.call_trace {
+            crate::lir::trace::instrument_call_trace(&mut lir, filter, &self.interner, None);
+        }
+        let mut module = crate::lir::emit_llvm::emit_llvm_module(&lir);
+        if self.profiling {
+            crate::lir::emit_llvm::instrument_for_profiling(&mut module, &lir, None);
+        }
+        Ok(module)
     }
 
     /// Lower a single module through LIR to an LLVM IR module while resolving
@@ -4963,7 +5143,7 @@ impl Compiler {
             .iter()
             .map(|(name, tag)| (self.sym(*name).to_string(), *tag))
             .collect();
-        let lir = crate::lir::lower::lower_aether_program_with_interner_and_externs(
+        let mut lir = crate::lir::lower::lower_aether_program_with_interner_and_externs(
             &aether,
             Some(&self.interner),
             None,
@@ -4972,11 +5152,27 @@ impl Compiler {
             emit_main,
             entry_qualifier.as_deref(),
         );
-        Ok(crate::lir::emit_llvm::emit_llvm_module_with_options(
+        if let Some(filter) = &self.call_trace {
+            crate::lir::trace::instrument_call_trace(
+                &mut lir,
+                filter,
+                &self.interner,
+                entry_qualifier.as_deref(),
+            );
+        }
+        let mut module = crate::lir::emit_llvm::emit_llvm_module_with_options(
             &lir,
             false,
             export_user_ctor_name_helper,
-        ))
+        );
+        if self.profiling {
+            crate::lir::emit_llvm::instrument_for_profiling(
+                &mut module,
+                &lir,
+                entry_qualifier.as_deref(),
+            );
+        }
+        Ok(module)
     }
 
     /// Dump LIR as LLVM IR text (Proposal 0132 Phase 7).
@@ -5413,6 +5609,39 @@ impl Compiler {
         )
     }
 
+    /// Source names of the current function's locals and captures, for its debug info.
+    ///
+    /// Symbols minted by lowering have no interned name; such locals are left out and such
+    /// captures get a positional placeholder.
+    pub(super) fn local_debug_names(&self) -> (Vec<LocalName>, Vec<String>) {
+        let mut locals: Vec<LocalName> = self
+            .symbol_table
+            .local_names
+            .iter()
+            .filter_map(|(index, name)| {
+                Some(LocalName {
+                    index: *index,
+                    name: self.interner.try_resolve(*name)?.to_string(),
+                })
+            })
+            .collect();
+        locals.sort_by_key(|local| local.index);
+        locals.dedup_by_key(|local| local.index);
+        let captures = self
+            .symbol_table
+            .free_symbols
+            .iter()
+            .enumerate()
+            .map(
+                |(index, binding)| match self.interner.try_resolve(binding.name) {
+                    Some(name) => name.to_string(),
+                    None => format!("<capture {index}>"),
+                },
+            )
+            .collect();
+        (locals, captures)
+    }
+
     pub(super) fn enter_block_scope(&mut self) {
         let mut block_table = SymbolTable::new_block(self.symbol_table.clone());
         block_table.num_definitions = self.symbol_table.num_definitions;
@@ -5423,9 +5652,11 @@ impl Compiler {
 
     pub(super) fn leave_block_scope(&mut self) {
         let num_definitions = self.symbol_table.num_definitions;
+        let local_names = std::mem::take(&mut self.symbol_table.local_names);
         if let Some(outer) = self.symbol_table.outer.take() {
             let mut outer = *outer;
             outer.num_definitions = num_definitions;
+            outer.local_names.extend(local_names);
             self.symbol_table = outer;
         }
         let _ = self.static_type_scopes.pop();
@@ -6259,6 +6490,14 @@ impl Compiler {
         result
     }
 
+    /// Handler scopes of the function being compiled, innermost first.
+    fn local_handler_scopes(&self) -> impl Iterator<Item = &HandlerScope> {
+        self.handler_scopes
+            .iter()
+            .rev()
+            .take_while(|scope| scope.function_scope == self.scope_index)
+    }
+
     /// Try to resolve a perform target at compile time.
     ///
     /// Searches the handler scope stack (innermost first) for a tail-resumptive
@@ -6271,7 +6510,7 @@ impl Compiler {
         op: Symbol,
     ) -> Option<(usize, usize)> {
         // Search from innermost handler outward.
-        for (i, scope) in self.handler_scopes.iter().rev().enumerate() {
+        for (i, scope) in self.local_handler_scopes().enumerate() {
             if scope.effect == effect {
                 if !scope.is_direct {
                     // Found the handler but it's not tail-resumptive —
@@ -6303,11 +6542,7 @@ impl Compiler {
         op: Symbol,
     ) -> Option<crate::compiler::binding::Binding> {
         let ev_symbol = {
-            let scope = self
-                .handler_scopes
-                .iter()
-                .rev()
-                .find(|s| s.effect == effect)?;
+            let scope = self.local_handler_scopes().find(|s| s.effect == effect)?;
             let arm_idx = scope.ops.iter().position(|&o| o == op)?;
             scope.evidence_symbols.as_ref()?.get(arm_idx).copied()?
         };
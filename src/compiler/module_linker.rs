@@ -47,6 +47,7 @@ impl VmAssemblyContext {
         let mut constants = artifact.constants.clone();
         for value in &mut constants {
             patch_value(value, constant_base, &global_map)?;
+            self.rebind_effect_symbols(value);
         }
 
         let mut instructions = artifact.instructions.clone();
@@ -70,6 +71,31 @@ impl VmAssemblyContext {
         }
     }
 
+    /// Effect and operation symbols in a cached artifact were interned by the build
+    /// that produced it; resolve them again by name so handlers and performs from
+    /// different builds agree.
+    fn rebind_effect_symbols(&mut self, value: &mut Value) {
+        match value {
+            Value::HandlerDescriptor(descriptor) => {
+                let mut rebound = (**descriptor).clone();
+                rebound.effect = self.interner.intern(&rebound.effect_name);
+                rebound.ops = rebound
+                    .op_names
+                    .iter()
+                    .map(|name| self.interner.intern(name))
+                    .collect();
+                *value = Value::HandlerDescriptor(Rc::new(rebound));
+            }
+            Value::PerformDescriptor(descriptor) => {
+                let mut rebound = (**descriptor).clone();
+                rebound.effect = self.interner.intern(&rebound.effect_name);
+                rebound.op = self.interner.intern(&rebound.op_name);
+                *value = Value::PerformDescriptor(Rc::new(rebound));
+            }
+            _ => {}
+        }
+    }
+
     fn global_map_for(
         &mut self,
         artifact: &CachedModuleBytecode,
@@ -517,4 +543,55 @@ mod tests {
         assert_eq!(b_instructions[3], OpCode::OpCall0 as u8);
         assert_eq!(b_instructions[4], OpCode::OpTailCall1 as u8);
     }
+
+    #[test]
+    fn rebinds_cached_effect_descriptors_by_name() {
+        use crate::{
+            runtime::{
+                handler_descriptor::HandlerDescriptor, perform_descriptor::PerformDescriptor,
+            },
+            syntax::Identifier,
+        };
+
+        // The artifact was built by an interner where the ids were assigned
+        // in another order; the linker's interner decides the final ids.
+        let mut interner = Interner::new();
+        interner.intern("unrelated");
+        let effect = interner.intern("FileSystem");
+        let op = interner.intern("read_file");
+        let mut linker = VmAssemblyContext::new(interner);
+
+        let artifact = module_with(
+            vec![global_def("Mock.run", 0)],
+            vec![
+                Value::HandlerDescriptor(Rc::new(HandlerDescriptor {
+                    effect: Identifier::new(900),
+                    effect_name: "FileSystem".into(),
+                    ops: vec![Identifier::new(901)],
+                    op_names: vec!["read_file".into()],
+                    has_state: false,
+                    is_discard: false,
+                })),
+                Value::PerformDescriptor(Rc::new(PerformDescriptor {
+                    effect: Identifier::new(900),
+                    op: Identifier::new(901),
+                    effect_name: "FileSystem".into(),
+                    op_name: "read_file".into(),
+                })),
+            ],
+            make(OpCode::OpHandle, &[0]),
+        );
+
+        linker.assemble_module(&artifact).expect("assemble module");
+        let constants = linker.finish().bytecode.constants;
+        let Value::HandlerDescriptor(handler) = &constants[0] else {
+            panic!("expected handler descriptor");
+        };
+        assert_eq!(handler.effect, effect);
+        assert_eq!(handler.ops, vec![op]);
+        let Value::PerformDescriptor(perform) = &constants[1] else {
+            panic!("expected perform descriptor");
+        };
+        assert_eq!((perform.effect, perform.op), (effect, op));
+    }
 }
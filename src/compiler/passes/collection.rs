@@ -294,7 +294,8 @@ impl Compiler {
             | Expression::String { .. }
             | Expression::Boolean { .. }
             | Expression::EmptyList { .. }
-            | Expression::None { .. } => {}
+            | Expression::None { .. }
+            | Expression::Hole { .. } => {}
         }
     }
 
@@ -559,7 +560,8 @@ impl Compiler {
             | Expression::String { .. }
             | Expression::Boolean { .. }
             | Expression::EmptyList { .. }
-            | Expression::None { .. } => {}
+            | Expression::None { .. }
+            | Expression::Hole { .. } => {}
         }
     }
 
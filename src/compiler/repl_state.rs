@@ -0,0 +1,116 @@
+//! Compiler state carried between `flux repl` inputs.
+//!
+//! The REPL compiles every input as a fresh entry module on one long-lived compiler. Per-module
+//! state such as the HM type environment, class environment and inferred effect table is rebuilt
+//! on each compile, so whatever a later input must still see is copied into the `repl_*` fields
+//! and the imported-class tables here.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    compiler::{
+        Compiler, ModuleContractTable, build_public_class_method_scheme, contracts::ContractKey,
+        same_instance_head, symbol_table::SymbolTable,
+    },
+    syntax::symbol::Symbol,
+    types::{class_env::ClassEnv, scheme::Scheme},
+};
+
+/// Compiler state a REPL input may change, restored when the input fails to compile.
+pub struct ReplCheckpoint {
+    symbol_table: SymbolTable,
+    module_contracts: ModuleContractTable,
+    repl_schemes: HashMap<Symbol, Scheme>,
+    repl_function_effects: HashMap<ContractKey, HashSet<Symbol>>,
+}
+
+impl Compiler {
+    /// Captures the state [`Self::forget_repl_binding`] and a failed compile can disturb.
+    pub fn repl_checkpoint(&self) -> ReplCheckpoint {
+        ReplCheckpoint {
+            symbol_table: self.symbol_table.clone(),
+            module_contracts: self.module_contracts.clone(),
+            repl_schemes: self.repl_schemes.clone(),
+            repl_function_effects: self.repl_function_effects.clone(),
+        }
+    }
+
+    pub fn restore_repl_checkpoint(&mut self, checkpoint: ReplCheckpoint) {
+        self.symbol_table = checkpoint.symbol_table;
+        self.module_contracts = checkpoint.module_contracts;
+        self.repl_schemes = checkpoint.repl_schemes;
+        self.repl_function_effects = checkpoint.repl_function_effects;
+    }
+
+    /// Drops everything recorded about top-level `name` so the next input can redefine it.
+    ///
+    /// The old global slot stays allocated; code compiled against it keeps using it.
+    pub fn forget_repl_binding(&mut self, name: Symbol) {
+        let is_top_level =
+            |key: &ContractKey| key.module_name.is_none() && key.function_name == name;
+        self.symbol_table.forget(name);
+        self.module_contracts.retain(|key, _| !is_top_level(key));
+        self.repl_function_effects
+            .retain(|key, _| !is_top_level(key));
+        self.repl_schemes.remove(&name);
+    }
+
+    /// Records HM schemes for REPL bindings so later inputs can refer to them.
+    pub fn extend_repl_schemes(&mut self, schemes: impl IntoIterator<Item = (Symbol, Scheme)>) {
+        self.repl_schemes.extend(schemes);
+    }
+
+    /// Effects inferred for a top-level function of the last compiled program, including the
+    /// ones its signature declares.
+    pub fn inferred_effects(
+        &self,
+        function_name: Symbol,
+        arity: usize,
+    ) -> Option<&HashSet<Symbol>> {
+        self.inferred_function_effects.get(&ContractKey {
+            module_name: None,
+            function_name,
+            arity,
+        })
+    }
+
+    /// Carries what the last compiled program declared into later compiles: its classes and
+    /// instances, the same way imported public classes are carried, and the effects inferred
+    /// for its top-level functions.
+    pub fn retain_repl_declarations(&mut self) {
+        let mut builtins = ClassEnv::new();
+        builtins.register_builtins(&mut self.interner);
+        for (class_id, class_def) in &self.class_env.classes {
+            if builtins.classes.contains_key(class_id)
+                || self.imported_public_classes.contains_key(class_id)
+            {
+                continue;
+            }
+            // HM only derives method schemes from class declarations in the program it is
+            // inferring, so later inputs see the methods through their schemes instead.
+            for method in &class_def.methods {
+                let scheme = build_public_class_method_scheme(class_def, method, &self.interner);
+                self.repl_schemes.insert(method.name, scheme);
+            }
+            self.imported_public_classes
+                .insert(*class_id, class_def.clone());
+        }
+        for instance in &self.class_env.instances {
+            let known = builtins
+                .instances
+                .iter()
+                .chain(self.imported_public_instances.iter())
+                .any(|existing| same_instance_head(existing, instance));
+            if !known {
+                self.imported_public_instances.push(instance.clone());
+            }
+        }
+
+        for (key, effects) in &self.inferred_function_effects {
+            if key.module_name.is_none() {
+                self.repl_function_effects
+                    .insert(key.clone(), effects.clone());
+            }
+        }
+    }
+}
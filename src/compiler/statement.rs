@@ -1455,6 +1455,20 @@ impl Compiler {
             self.emit(OpCode::OpEnterCC, &[cc_idx as usize]);
         }
 
+        // Log entry when `--trace-calls` selected this function.
+        if let Some(filter) = &self.call_trace {
+            let fn_name = self.sym(name);
+            // Some module functions are already bound under their qualified name.
+            let label = match self.current_module_prefix {
+                Some(module) if !fn_name.contains('.') => format!("{}.{fn_name}", self.sym(module)),
+                _ => fn_name.to_string(),
+            };
+            if filter.matches(&label) {
+                let label_idx = self.register_trace_label(label);
+                self.emit(OpCode::OpTraceCall, &[label_idx as usize]);
+            }
+        }
+
         // Track the runtime-facing IR parameter count when CFG path succeeds.
         // Dict elaboration may add dictionary parameters that the AST doesn't
         // know about, while lifted function literals also include captures in
@@ -1647,6 +1661,7 @@ impl Compiler {
         }
 
         let num_locals = self.symbol_table.num_definitions;
+        let (local_names, capture_names) = self.local_debug_names();
 
         let (instructions, locations, files, effect_summary) = self.leave_scope();
 
@@ -1694,7 +1709,8 @@ impl Compiler {
                 Some(
                     FunctionDebugInfo::new(Some(self.sym(name).to_string()), files, locations)
                         .with_boundary_location(Some(boundary_location))
-                        .with_effect_summary(effect_summary),
+                        .with_effect_summary(effect_summary)
+                        .with_locals(local_names, capture_names),
                 ),
             )
             .with_contract(runtime_contract),
@@ -1846,6 +1862,7 @@ impl Compiler {
             }
 
             let num_locals = self.symbol_table.num_definitions;
+            let (local_names, capture_names) = self.local_debug_names();
             let (instructions, locations, files, effect_summary) = self.leave_scope();
 
             // Store compiled function in the reserved constant pool slot.
@@ -1856,7 +1873,8 @@ impl Compiler {
                 entry.parameters.len(),
                 Some(
                     FunctionDebugInfo::new(Some(fn_name), files, locations)
-                        .with_effect_summary(effect_summary),
+                        .with_effect_summary(effect_summary)
+                        .with_locals(local_names, capture_names),
                 ),
             );
             self.constants[const_slots[idx]] = Value::Function(Rc::new(compiled));
@@ -10,6 +10,8 @@ pub struct SymbolTable {
     store: HashMap<Symbol, Binding>,
     pub num_definitions: usize,
     pub free_symbols: Vec<Binding>,
+    /// Local slots defined in this table together with their source names, for debug info.
+    pub local_names: Vec<(usize, Symbol)>,
     allow_free: bool,
 }
 
@@ -20,6 +22,7 @@ impl SymbolTable {
             store: HashMap::new(),
             num_definitions: 0,
             free_symbols: Vec::new(),
+            local_names: Vec::new(),
             allow_free: true,
         }
     }
@@ -30,6 +33,7 @@ impl SymbolTable {
             store: HashMap::new(),
             num_definitions: 0,
             free_symbols: Vec::new(),
+            local_names: Vec::new(),
             allow_free: true,
         }
     }
@@ -40,6 +44,7 @@ impl SymbolTable {
             store: HashMap::new(),
             num_definitions: 0,
             free_symbols: Vec::new(),
+            local_names: Vec::new(),
             allow_free: false,
         }
     }
@@ -58,6 +63,9 @@ impl SymbolTable {
 
         let symbol = Binding::new(name, scope, self.num_definitions, span);
         self.store.insert(name, symbol.clone());
+        if scope == SymbolScope::Local {
+            self.local_names.push((symbol.index, name));
+        }
         self.num_definitions += 1;
         symbol
     }
@@ -97,6 +105,13 @@ impl SymbolTable {
         symbol
     }
 
+    /// Records the source name of a local temporary slot created by [`Self::define_temp`].
+    pub fn name_temp(&mut self, binding: &Binding, name: Symbol) {
+        if binding.symbol_scope == SymbolScope::Local {
+            self.local_names.push((binding.index, name));
+        }
+    }
+
     pub fn resolve(&mut self, name: Symbol) -> Option<Binding> {
         match self.store.get(&name) {
             Some(symbol) => Some(symbol.clone()),
@@ -168,6 +183,13 @@ impl SymbolTable {
         bindings
     }
 
+    /// Drops `name` from this scope so a later `define` starts a fresh binding.
+    ///
+    /// Its slot stays allocated; code compiled against the old binding keeps using it.
+    pub fn forget(&mut self, name: Symbol) {
+        self.store.remove(&name);
+    }
+
     pub fn define_global_with_index(
         &mut self,
         name: Symbol,
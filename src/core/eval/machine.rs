@@ -0,0 +1,1051 @@
+//! The evaluation machine of the reference Core interpreter.
+//!
+//! A CEK-style machine: control is either an expression to evaluate in an
+//! environment or a value to return to the topmost frame of an explicit
+//! continuation stack. Keeping the stack explicit means deep recursion never
+//! touches the Rust stack and effect handlers can capture the frames between
+//! a `perform` and its `Handle` as an ordinary multi-shot continuation.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::core::{
+    CoreAlt, CoreBinderId, CoreExpr, CoreHandler, CoreLit, CorePat, CorePrimOp, CoreProgram,
+    CoreTag, CoreTopLevelItem,
+};
+use crate::syntax::Identifier;
+use crate::syntax::interner::Interner;
+
+use super::EvalError;
+use super::primop::{Io, apply_primop};
+use super::value::{Closure, Continuation, Env, Slot, Value};
+
+/// Top-level definitions, indexed for the three ways Core refers to them:
+/// by binder, by bare (external) name, and as `Module.member`.
+pub(super) struct Globals<'a> {
+    program: &'a CoreProgram,
+    interner: &'a Interner,
+    values: Vec<std::cell::OnceCell<Value<'a>>>,
+    by_binder: HashMap<CoreBinderId, usize>,
+    by_name: HashMap<Identifier, Vec<usize>>,
+    module_of: Vec<Option<Identifier>>,
+    modules: HashSet<Identifier>,
+    members: HashMap<(Identifier, Identifier), usize>,
+    /// User ADT constructors by name, with their arity.
+    constructors: HashMap<Identifier, usize>,
+    /// Data type of each user constructor, for class dispatch.
+    data_types: HashMap<Identifier, Identifier>,
+    /// Class method stubs and the `__tc_*` instance method for each
+    /// runtime type head, keyed by `(stub name, type head)`.
+    class_stubs: HashSet<Identifier>,
+    instances: HashMap<(Identifier, String), usize>,
+}
+
+impl<'a> Globals<'a> {
+    pub fn new(program: &'a CoreProgram, interner: &'a Interner) -> Self {
+        let mut decls = Declarations::default();
+        decls.collect(&program.top_level_items, None);
+        let Declarations {
+            items,
+            modules,
+            aliases,
+            constructors,
+            data_types,
+        } = decls;
+
+        // Definitions claim top-level items in order, the same way backend
+        // lowering binds function ids to `Function` items.
+        let mut claimed = vec![false; items.len()];
+        let mut module_of = Vec::with_capacity(program.defs.len());
+        let mut by_binder = HashMap::new();
+        let mut by_name: HashMap<Identifier, Vec<usize>> = HashMap::new();
+        let mut members = HashMap::new();
+        for (index, def) in program.defs.iter().enumerate() {
+            let module = items
+                .iter()
+                .enumerate()
+                .find(|(i, (name, _))| !claimed[*i] && *name == def.name)
+                .and_then(|(i, (_, module))| {
+                    claimed[i] = true;
+                    *module
+                });
+            if let Some(module) = module {
+                members.insert((module, def.name), index);
+            }
+            module_of.push(module);
+            by_binder.insert(def.binder.id, index);
+            by_name.entry(def.name).or_default().push(index);
+        }
+        for (alias, module) in aliases {
+            let aliased: Vec<_> = members
+                .iter()
+                .filter(|((owner, _), _)| *owner == module)
+                .map(|((_, member), index)| ((alias, *member), *index))
+                .collect();
+            members.extend(aliased);
+        }
+        let (class_stubs, instances) = class_instances(program, interner);
+
+        Self {
+            program,
+            interner,
+            values: (0..program.defs.len())
+                .map(|_| std::cell::OnceCell::new())
+                .collect(),
+            by_binder,
+            by_name,
+            module_of,
+            modules,
+            members,
+            constructors,
+            data_types,
+            class_stubs,
+            instances,
+        }
+    }
+
+    /// Bind every function definition, then evaluate value definitions in
+    /// program order.
+    pub fn initialize(&self, io: &mut Io<'_>) -> Result<(), EvalError> {
+        for (index, def) in self.program.defs.iter().enumerate() {
+            if self.class_stubs.contains(&def.name) && class_stub_message(&def.expr).is_some() {
+                let _ = self.values[index].set(Value::Builtin(def.name));
+            } else if let CoreExpr::Lam { params, body, .. } = &def.expr {
+                let _ = self.values[index].set(Value::Closure(Rc::new(Closure {
+                    params,
+                    body,
+                    env: Env::default(),
+                    module: self.module_of[index],
+                })));
+            }
+        }
+        for (index, def) in self.program.defs.iter().enumerate() {
+            if self.values[index].get().is_none() {
+                let mut machine = Machine::new(self, io, self.module_of[index]);
+                let value = machine.run(Control::Eval(&def.expr, Env::default()))?;
+                let _ = self.values[index].set(value);
+            }
+        }
+        Ok(())
+    }
+
+    /// The root-level `main` function, if the program has one.
+    pub fn main(&self) -> Option<Value<'a>> {
+        let index = self.program.defs.iter().enumerate().position(|(i, def)| {
+            self.module_of[i].is_none() && self.interner.resolve(def.name) == "main"
+        })?;
+        self.values[index].get().cloned()
+    }
+
+    fn get(&self, index: usize) -> Result<Value<'a>, EvalError> {
+        self.values[index].get().cloned().ok_or_else(|| {
+            EvalError::Runtime(format!(
+                "global `{}` used before initialization",
+                self.interner.resolve(self.program.defs[index].name)
+            ))
+        })
+    }
+}
+
+/// Declarations gathered from the (nested) top-level items.
+#[derive(Default)]
+struct Declarations {
+    /// `Function`/`Let` items with their enclosing module, in order.
+    items: Vec<(Identifier, Option<Identifier>)>,
+    modules: HashSet<Identifier>,
+    /// `(alias, module)` for each aliased import.
+    aliases: Vec<(Identifier, Identifier)>,
+    constructors: HashMap<Identifier, usize>,
+    data_types: HashMap<Identifier, Identifier>,
+}
+
+impl Declarations {
+    fn collect(&mut self, items: &[CoreTopLevelItem], module: Option<Identifier>) {
+        for item in items {
+            match item {
+                CoreTopLevelItem::Function { name, .. } | CoreTopLevelItem::Let { name, .. } => {
+                    self.items.push((*name, module));
+                }
+                CoreTopLevelItem::Module { name, body, .. } => {
+                    self.modules.insert(*name);
+                    self.collect(body, Some(*name));
+                }
+                CoreTopLevelItem::Import { name, alias, .. } => {
+                    self.modules.insert(*name);
+                    if let Some(alias) = alias {
+                        self.modules.insert(*alias);
+                        self.aliases.push((*alias, *name));
+                    }
+                }
+                CoreTopLevelItem::Data { name, variants, .. } => {
+                    for variant in variants {
+                        self.constructors.insert(variant.name, variant.fields.len());
+                        self.data_types.insert(variant.name, *name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The panic message of a class method stub, if `expr` is one. Class
+/// methods are type-checked against a polymorphic stub whose body panics;
+/// backends never call it because calls are resolved to instances
+/// statically or through dictionaries.
+fn class_stub_message(expr: &CoreExpr) -> Option<&str> {
+    let CoreExpr::Lam { body, .. } = expr else {
+        return None;
+    };
+    let arg = match body.as_ref() {
+        CoreExpr::PrimOp {
+            op: CorePrimOp::Panic,
+            args,
+            ..
+        }
+        | CoreExpr::App { args, .. } => args.first()?,
+        _ => return None,
+    };
+    match arg {
+        CoreExpr::Lit(CoreLit::String(message), _) => message.strip_prefix("No instance of "),
+        _ => None,
+    }
+}
+
+/// Find class method stubs and, for each, the `__tc_{Class}_{Type}_{method}`
+/// instance definitions keyed by type head.
+///
+/// The merged program the interpreter runs is not dictionary-elaborated for
+/// library modules, so class method calls are dispatched on the runtime type
+/// of the first argument instead, as the pre-dictionary backends did.
+fn class_instances(
+    program: &CoreProgram,
+    interner: &Interner,
+) -> (HashSet<Identifier>, HashMap<(Identifier, String), usize>) {
+    let mut stubs = HashSet::new();
+    let mut instances = HashMap::new();
+    for def in &program.defs {
+        let Some(message) = class_stub_message(&def.expr) else {
+            continue;
+        };
+        let Some((class, method)) = message
+            .split_once(' ')
+            .and_then(|(qualified, _)| qualified.split_once('.'))
+        else {
+            continue;
+        };
+        stubs.insert(def.name);
+        let prefix = format!("__tc_{class}_");
+        let suffix = format!("_{method}");
+        for (index, candidate) in program.defs.iter().enumerate() {
+            let name = interner.resolve(candidate.name);
+            if let Some(key) = name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(&suffix))
+            {
+                let head = key.split(['<', '_']).next().unwrap_or(key);
+                instances.insert((def.name, head.to_string()), index);
+            }
+        }
+    }
+    (stubs, instances)
+}
+
+#[derive(Clone)]
+pub(super) enum Frame<'a> {
+    Let {
+        id: CoreBinderId,
+        body: &'a CoreExpr,
+        env: Env<'a>,
+    },
+    LetRec {
+        slot: Rc<Slot<'a>>,
+        body: &'a CoreExpr,
+        env: Env<'a>,
+    },
+    LetRecGroup {
+        rhss: Vec<&'a CoreExpr>,
+        slots: Vec<Rc<Slot<'a>>>,
+        index: usize,
+        body: &'a CoreExpr,
+        env: Env<'a>,
+    },
+    AppFunc {
+        args: &'a [CoreExpr],
+        env: Env<'a>,
+    },
+    Args {
+        pending: Pending<'a>,
+        args: &'a [CoreExpr],
+        done: Vec<Value<'a>>,
+        env: Env<'a>,
+    },
+    Case {
+        alts: &'a [CoreAlt],
+        env: Env<'a>,
+    },
+    Guard {
+        scrutinee: Value<'a>,
+        alts: &'a [CoreAlt],
+        index: usize,
+        env: Env<'a>,
+        alt_env: Env<'a>,
+    },
+    TupleField {
+        index: usize,
+    },
+    Return,
+    /// Function-call boundary: the target of `Return`, restoring the
+    /// caller's module for free-name resolution.
+    Call {
+        module: Option<Identifier>,
+    },
+    HandleParam {
+        handle: &'a CoreExpr,
+        env: Env<'a>,
+    },
+    /// An installed handler; the delimiter `perform` searches for.
+    Handler {
+        effect: Identifier,
+        handlers: &'a [CoreHandler],
+        env: Env<'a>,
+        state: Option<Value<'a>>,
+        module: Option<Identifier>,
+    },
+    Catch {
+        expected: Option<Option<String>>,
+    },
+    Time {
+        start: Instant,
+    },
+}
+
+/// What to do once every argument of a multi-argument node is evaluated.
+#[derive(Clone)]
+pub(super) enum Pending<'a> {
+    Call(Value<'a>),
+    Con(&'a CoreTag),
+    PrimOp(CorePrimOp),
+    Perform(Identifier, Identifier),
+}
+
+pub(super) enum Control<'a> {
+    Eval(&'a CoreExpr, Env<'a>),
+    Value(Value<'a>),
+}
+
+pub(super) struct Machine<'g, 'a, 's> {
+    globals: &'g Globals<'a>,
+    io: &'g mut Io<'s>,
+    stack: Vec<Frame<'a>>,
+    module: Option<Identifier>,
+}
+
+impl<'g, 'a, 's> Machine<'g, 'a, 's> {
+    pub fn new(globals: &'g Globals<'a>, io: &'g mut Io<'s>, module: Option<Identifier>) -> Self {
+        Self {
+            globals,
+            io,
+            stack: Vec::new(),
+            module,
+        }
+    }
+
+    pub fn call_value(
+        &mut self,
+        func: Value<'a>,
+        args: Vec<Value<'a>>,
+    ) -> Result<Value<'a>, EvalError> {
+        let control = self.call(func, args)?;
+        self.run(control)
+    }
+
+    fn run(&mut self, mut control: Control<'a>) -> Result<Value<'a>, EvalError> {
+        loop {
+            let step = match control {
+                Control::Eval(expr, env) => self.eval(expr, env),
+                Control::Value(value) => match self.stack.pop() {
+                    None => return Ok(value),
+                    Some(frame) => self.continue_with(frame, value),
+                },
+            };
+            control = match step {
+                Ok(next) => next,
+                Err(EvalError::Runtime(message)) => self.catch(message)?,
+                Err(other) => return Err(other),
+            };
+        }
+    }
+
+    fn eval(&mut self, expr: &'a CoreExpr, env: Env<'a>) -> Result<Control<'a>, EvalError> {
+        Ok(match expr {
+            CoreExpr::Var { var, .. } => {
+                let value = match var.binder {
+                    Some(id) => match env.lookup(id) {
+                        Some(Some(value)) => value,
+                        Some(None) => {
+                            return Err(EvalError::Runtime(format!(
+                                "`{}` used before its recursive binding was initialized",
+                                self.globals.interner.resolve(var.name)
+                            )));
+                        }
+                        None => match self.globals.by_binder.get(&id) {
+                            Some(index) => self.globals.get(self.same_module_def(*index))?,
+                            None => self.resolve_name(var.name)?,
+                        },
+                    },
+                    None => self.resolve_name(var.name)?,
+                };
+                Control::Value(value)
+            }
+            CoreExpr::Lit(lit, _) => Control::Value(match lit {
+                CoreLit::Int(n) => Value::Int(*n),
+                CoreLit::Float(f) => Value::Float(*f),
+                CoreLit::Bool(b) => Value::Bool(*b),
+                CoreLit::String(s) => Value::string(s.clone()),
+                CoreLit::Unit => Value::None,
+            }),
+            CoreExpr::Lam { params, body, .. } => {
+                Control::Value(Value::Closure(Rc::new(Closure {
+                    params,
+                    body,
+                    env,
+                    module: self.module,
+                })))
+            }
+            CoreExpr::App { func, args, .. } => {
+                self.stack.push(Frame::AppFunc {
+                    args,
+                    env: env.clone(),
+                });
+                Control::Eval(func, env)
+            }
+            CoreExpr::Let { var, rhs, body, .. } => {
+                self.stack.push(Frame::Let {
+                    id: var.id,
+                    body,
+                    env: env.clone(),
+                });
+                Control::Eval(rhs, env)
+            }
+            CoreExpr::LetRec { var, rhs, body, .. } => {
+                let (env, slot) = env.bind_pending(var.id);
+                self.stack.push(Frame::LetRec {
+                    slot,
+                    body,
+                    env: env.clone(),
+                });
+                Control::Eval(rhs, env)
+            }
+            CoreExpr::LetRecGroup { bindings, body, .. } => {
+                let mut env = env;
+                let mut slots = Vec::with_capacity(bindings.len());
+                for (binder, _) in bindings {
+                    let (next, slot) = env.bind_pending(binder.id);
+                    env = next;
+                    slots.push(slot);
+                }
+                let rhss: Vec<&'a CoreExpr> = bindings.iter().map(|(_, rhs)| &**rhs).collect();
+                match rhss.first().copied() {
+                    None => Control::Eval(body, env),
+                    Some(first) => {
+                        self.stack.push(Frame::LetRecGroup {
+                            rhss,
+                            slots,
+                            index: 0,
+                            body,
+                            env: env.clone(),
+                        });
+                        Control::Eval(first, env)
+                    }
+                }
+            }
+            CoreExpr::Case {
+                scrutinee, alts, ..
+            } => {
+                self.stack.push(Frame::Case {
+                    alts,
+                    env: env.clone(),
+                });
+                Control::Eval(scrutinee, env)
+            }
+            CoreExpr::Con { tag, fields, .. } => self.eval_args(Pending::Con(tag), fields, env)?,
+            CoreExpr::PrimOp { op, args, .. } => self.eval_args(Pending::PrimOp(*op), args, env)?,
+            CoreExpr::Perform {
+                effect,
+                operation,
+                args,
+                ..
+            } => self.eval_args(Pending::Perform(*effect, *operation), args, env)?,
+            CoreExpr::MemberAccess { object, member, .. } => match object.as_ref() {
+                CoreExpr::Var { var, .. }
+                    if var.binder.is_none() && self.globals.modules.contains(&var.name) =>
+                {
+                    match self.globals.members.get(&(var.name, *member)) {
+                        Some(index) => Control::Value(self.globals.get(*index)?),
+                        None => Control::Value(Value::Builtin(*member)),
+                    }
+                }
+                _ => {
+                    return Err(EvalError::Unsupported(format!(
+                        "member access `.{}` on a value",
+                        self.globals.interner.resolve(*member)
+                    )));
+                }
+            },
+            CoreExpr::TupleField { object, index, .. } => {
+                self.stack.push(Frame::TupleField { index: *index });
+                Control::Eval(object, env)
+            }
+            CoreExpr::Return { value, .. } => {
+                self.stack.push(Frame::Return);
+                Control::Eval(value, env)
+            }
+            CoreExpr::Handle { parameter, .. } => match parameter {
+                Some(parameter) => {
+                    self.stack.push(Frame::HandleParam {
+                        handle: expr,
+                        env: env.clone(),
+                    });
+                    Control::Eval(parameter, env)
+                }
+                None => self.install_handler(expr, env, None)?,
+            },
+        })
+    }
+
+    fn eval_args(
+        &mut self,
+        pending: Pending<'a>,
+        args: &'a [CoreExpr],
+        env: Env<'a>,
+    ) -> Result<Control<'a>, EvalError> {
+        match args.first() {
+            None => self.finish(pending, Vec::new()),
+            Some(first) => {
+                self.stack.push(Frame::Args {
+                    pending,
+                    args,
+                    done: Vec::with_capacity(args.len()),
+                    env: env.clone(),
+                });
+                Ok(Control::Eval(first, env))
+            }
+        }
+    }
+
+    fn continue_with(
+        &mut self,
+        frame: Frame<'a>,
+        value: Value<'a>,
+    ) -> Result<Control<'a>, EvalError> {
+        Ok(match frame {
+            Frame::Let { id, body, env } => Control::Eval(body, env.bind(id, value)),
+            Frame::LetRec { slot, body, env } => {
+                slot.fill(value);
+                Control::Eval(body, env)
+            }
+            Frame::LetRecGroup {
+                rhss,
+                slots,
+                index,
+                body,
+                env,
+            } => {
+                slots[index].fill(value);
+                match rhss.get(index + 1).copied() {
+                    None => Control::Eval(body, env),
+                    Some(next) => {
+                        self.stack.push(Frame::LetRecGroup {
+                            rhss,
+                            slots,
+                            index: index + 1,
+                            body,
+                            env: env.clone(),
+                        });
+                        Control::Eval(next, env)
+                    }
+                }
+            }
+            Frame::AppFunc { args, env } => self.eval_args(Pending::Call(value), args, env)?,
+            Frame::Args {
+                pending,
+                args,
+                mut done,
+                env,
+            } => {
+                done.push(value);
+                match args.get(done.len()) {
+                    None => self.finish(pending, done)?,
+                    Some(next) => {
+                        self.stack.push(Frame::Args {
+                            pending,
+                            args,
+                            done,
+                            env: env.clone(),
+                        });
+                        Control::Eval(next, env)
+                    }
+                }
+            }
+            Frame::Case { alts, env } => self.select_alt(value, alts, 0, env)?,
+            Frame::Guard {
+                scrutinee,
+                alts,
+                index,
+                env,
+                alt_env,
+            } => {
+                if value.is_truthy() {
+                    Control::Eval(&alts[index].rhs, alt_env)
+                } else {
+                    self.select_alt(scrutinee, alts, index + 1, env)?
+                }
+            }
+            Frame::TupleField { index } => match &value {
+                Value::Tuple(items) => match items.get(index) {
+                    Some(item) => Control::Value(item.clone()),
+                    None => {
+                        return Err(EvalError::Runtime(format!(
+                            "tuple index {index} out of bounds"
+                        )));
+                    }
+                },
+                _ => {
+                    return Err(EvalError::Runtime(format!(
+                        "tuple field access on {}",
+                        value.to_runtime().type_name()
+                    )));
+                }
+            },
+            Frame::Return => {
+                while let Some(frame) = self.stack.pop() {
+                    if let Frame::Call { module } = frame {
+                        self.module = module;
+                        break;
+                    }
+                }
+                Control::Value(value)
+            }
+            Frame::Call { module } => {
+                self.module = module;
+                Control::Value(value)
+            }
+            Frame::HandleParam { handle, env } => self.install_handler(handle, env, Some(value))?,
+            Frame::Handler { module, .. } => {
+                self.module = module;
+                Control::Value(value)
+            }
+            Frame::Catch { expected: None } => {
+                Control::Value(Value::Tuple(Rc::new(vec![Value::string("ok"), value])))
+            }
+            Frame::Catch { expected: Some(_) } => {
+                return Err(EvalError::Runtime(
+                    "assert_throws failed: function completed without error".to_string(),
+                ));
+            }
+            Frame::Time { start } => {
+                let elapsed_ms = self
+                    .io
+                    .read_clock(|| start.elapsed().as_millis().min(i64::MAX as u128) as i64);
+                Control::Value(Value::Int(elapsed_ms))
+            }
+        })
+    }
+
+    fn finish(
+        &mut self,
+        pending: Pending<'a>,
+        args: Vec<Value<'a>>,
+    ) -> Result<Control<'a>, EvalError> {
+        match pending {
+            Pending::Call(func) => self.call(func, args),
+            Pending::Con(tag) => Ok(Control::Value(self.construct(tag, args))),
+            Pending::PrimOp(op) => self.primop(op, args),
+            Pending::Perform(effect, operation) => self.perform(effect, operation, args),
+        }
+    }
+
+    fn construct(&self, tag: &CoreTag, args: Vec<Value<'a>>) -> Value<'a> {
+        let mut fields = args.into_iter();
+        let mut field = || fields.next().unwrap_or(Value::None);
+        match tag {
+            CoreTag::Named(name) => self.adt(*name, fields.collect()),
+            CoreTag::None => Value::None,
+            CoreTag::Some => Value::Some(Rc::new(field())),
+            CoreTag::Left => Value::Left(Rc::new(field())),
+            CoreTag::Right => Value::Right(Rc::new(field())),
+            CoreTag::Nil => Value::EmptyList,
+            CoreTag::Cons => {
+                let head = field();
+                let tail = field();
+                Value::Cons(Rc::new((head, tail)))
+            }
+        }
+    }
+
+    /// The type name class instances are keyed by for a runtime value.
+    fn type_head(&self, value: &Value<'a>) -> &'a str {
+        let interner = self.globals.interner;
+        match value {
+            Value::Int(_) => "Int",
+            Value::Float(_) => "Float",
+            Value::Bool(_) => "Bool",
+            Value::String(_) => "String",
+            Value::None | Value::Some(_) => "Option",
+            Value::Left(_) | Value::Right(_) => "Either",
+            Value::EmptyList | Value::Cons(_) => "List",
+            Value::Tuple(_) => "Tuple",
+            Value::Array(_) => "Array",
+            Value::Map(_) => "Map",
+            Value::Adt(constructor, _) => interner
+                .lookup(constructor)
+                .and_then(|name| self.globals.data_types.get(&name))
+                .map_or("Adt", |data| interner.resolve(*data)),
+            Value::Closure(_) | Value::Continuation(_) | Value::Builtin(_) => "Function",
+        }
+    }
+
+    fn adt(&self, name: Identifier, fields: Vec<Value<'a>>) -> Value<'a> {
+        Value::Adt(
+            Rc::new(self.globals.interner.resolve(name).to_string()),
+            Rc::new(fields),
+        )
+    }
+
+    fn primop(&mut self, op: CorePrimOp, args: Vec<Value<'a>>) -> Result<Control<'a>, EvalError> {
+        let (frame, thunk) = match op {
+            CorePrimOp::Try => (Frame::Catch { expected: None }, &args[0]),
+            CorePrimOp::AssertThrows => {
+                let expected = match args.get(1) {
+                    Some(Value::String(text)) => Some(text.to_string()),
+                    _ => None,
+                };
+                (
+                    Frame::Catch {
+                        expected: Some(expected),
+                    },
+                    &args[0],
+                )
+            }
+            CorePrimOp::Time => (
+                Frame::Time {
+                    start: Instant::now(),
+                },
+                &args[0],
+            ),
+            _ => return Ok(Control::Value(apply_primop(op, args, self.io)?)),
+        };
+        let thunk = thunk.clone();
+        self.stack.push(frame);
+        self.call(thunk, Vec::new())
+    }
+
+    fn call(&mut self, func: Value<'a>, args: Vec<Value<'a>>) -> Result<Control<'a>, EvalError> {
+        match func {
+            Value::Closure(closure) => {
+                if closure.params.len() != args.len() {
+                    return Err(EvalError::Runtime(format!(
+                        "function expects {} arguments, got {}",
+                        closure.params.len(),
+                        args.len()
+                    )));
+                }
+                let env = closure
+                    .params
+                    .iter()
+                    .zip(args)
+                    .fold(closure.env.clone(), |env, (param, arg)| {
+                        env.bind(param.id, arg)
+                    });
+                // A call in tail position reuses the caller's boundary.
+                if !matches!(self.stack.last(), Some(Frame::Call { .. })) {
+                    self.stack.push(Frame::Call {
+                        module: self.module,
+                    });
+                }
+                self.module = closure.module;
+                Ok(Control::Eval(closure.body, env))
+            }
+            Value::Continuation(continuation) => {
+                let mut args = args.into_iter();
+                let value = args.next().unwrap_or(Value::None);
+                let mut frames = continuation.frames.clone();
+                if let (Some(new_state), Some(Frame::Handler { state, .. })) =
+                    (args.next(), frames.first_mut())
+                {
+                    *state = Some(new_state);
+                }
+                self.stack.extend(frames);
+                self.module = continuation.module;
+                Ok(Control::Value(value))
+            }
+            Value::Builtin(name) if self.globals.constructors.contains_key(&name) => {
+                Ok(Control::Value(self.adt(name, args)))
+            }
+            Value::Builtin(name) if self.globals.class_stubs.contains(&name) => {
+                let head = args.first().map_or("Unit", |arg| self.type_head(arg));
+                match self.globals.instances.get(&(name, head.to_string())) {
+                    Some(index) => {
+                        let instance = self.globals.get(*index)?;
+                        self.call(instance, args)
+                    }
+                    None => Err(EvalError::Runtime(format!(
+                        "panic: No instance of {} for {head}",
+                        self.globals.interner.resolve(name)
+                    ))),
+                }
+            }
+            Value::Builtin(name) => {
+                let text = self.globals.interner.resolve(name);
+                let op = crate::core::passes::builtin_primop(text, args.len())
+                    .or_else(|| CorePrimOp::from_name(text, args.len()));
+                match op {
+                    Some(op) => self.primop(op, args),
+                    None => Err(EvalError::Unsupported(format!(
+                        "call to unknown function `{text}`"
+                    ))),
+                }
+            }
+            other => Err(EvalError::Runtime(format!(
+                "value of type {} is not callable",
+                other.to_runtime().type_name()
+            ))),
+        }
+    }
+
+    fn install_handler(
+        &mut self,
+        handle: &'a CoreExpr,
+        env: Env<'a>,
+        state: Option<Value<'a>>,
+    ) -> Result<Control<'a>, EvalError> {
+        let CoreExpr::Handle {
+            body,
+            effect,
+            handlers,
+            ..
+        } = handle
+        else {
+            unreachable!("install_handler expects a Handle node");
+        };
+        self.stack.push(Frame::Handler {
+            effect: *effect,
+            handlers,
+            env: env.clone(),
+            state,
+            module: self.module,
+        });
+        Ok(Control::Eval(body, env))
+    }
+
+    fn perform(
+        &mut self,
+        effect: Identifier,
+        operation: Identifier,
+        args: Vec<Value<'a>>,
+    ) -> Result<Control<'a>, EvalError> {
+        let depth = self.stack.iter().rposition(|frame| {
+            matches!(frame, Frame::Handler { effect: e, handlers, .. }
+                if *e == effect && handlers.iter().any(|h| h.operation == operation))
+        });
+        let Some(depth) = depth else {
+            return Err(EvalError::Runtime(format!(
+                "unhandled effect operation {}.{}",
+                self.globals.interner.resolve(effect),
+                self.globals.interner.resolve(operation)
+            )));
+        };
+        let frames = self.stack.split_off(depth);
+        let Some(Frame::Handler {
+            handlers,
+            env,
+            state,
+            module,
+            ..
+        }) = frames.first()
+        else {
+            unreachable!("perform depth points at a handler frame");
+        };
+        let arm = handlers
+            .iter()
+            .find(|h| h.operation == operation)
+            .expect("handler frame has the operation");
+        if arm.params.len() != args.len() {
+            return Err(EvalError::Runtime(format!(
+                "effect operation {} expects {} arguments, got {}",
+                self.globals.interner.resolve(operation),
+                arm.params.len(),
+                args.len()
+            )));
+        }
+        let mut arm_env = arm
+            .params
+            .iter()
+            .zip(args)
+            .fold(env.clone(), |env, (param, arg)| env.bind(param.id, arg));
+        if let Some(state_binder) = &arm.state {
+            arm_env = arm_env.bind(state_binder.id, state.clone().unwrap_or(Value::None));
+        }
+        let arm_module = *module;
+        let continuation = Continuation {
+            frames,
+            module: self.module,
+        };
+        arm_env = arm_env.bind(arm.resume.id, Value::Continuation(Rc::new(continuation)));
+        self.module = arm_module;
+        Ok(Control::Eval(&arm.body, arm_env))
+    }
+
+    fn select_alt(
+        &mut self,
+        scrutinee: Value<'a>,
+        alts: &'a [CoreAlt],
+        start: usize,
+        env: Env<'a>,
+    ) -> Result<Control<'a>, EvalError> {
+        for (index, alt) in alts.iter().enumerate().skip(start) {
+            let Some(alt_env) = self.match_pat(&alt.pat, &scrutinee, env.clone()) else {
+                continue;
+            };
+            return Ok(match &alt.guard {
+                Some(guard) => {
+                    self.stack.push(Frame::Guard {
+                        scrutinee,
+                        alts,
+                        index,
+                        env,
+                        alt_env: alt_env.clone(),
+                    });
+                    Control::Eval(guard, alt_env)
+                }
+                None => Control::Eval(&alt.rhs, alt_env),
+            });
+        }
+        Err(EvalError::Runtime(format!(
+            "non-exhaustive match on {}",
+            scrutinee.to_runtime()
+        )))
+    }
+
+    fn match_pat(&self, pat: &CorePat, value: &Value<'a>, env: Env<'a>) -> Option<Env<'a>> {
+        match (pat, value) {
+            (CorePat::Wildcard, _) => Some(env),
+            (CorePat::Var(binder), _) => Some(env.bind(binder.id, value.clone())),
+            (CorePat::Lit(lit), _) => {
+                let matches = match (lit, value) {
+                    (CoreLit::Int(a), Value::Int(b)) => a == b,
+                    (CoreLit::Float(a), Value::Float(b)) => a == b,
+                    (CoreLit::Bool(a), Value::Bool(b)) => a == b,
+                    (CoreLit::String(a), Value::String(b)) => a == b.as_str(),
+                    (CoreLit::Unit, Value::None) => true,
+                    _ => false,
+                };
+                matches.then_some(env)
+            }
+            (CorePat::EmptyList, Value::EmptyList | Value::None) => Some(env),
+            (CorePat::Tuple(pats), Value::Tuple(items)) if pats.len() == items.len() => {
+                self.match_all(pats, items.iter(), env)
+            }
+            (CorePat::Con { tag, fields }, _) => match (tag, value) {
+                (CoreTag::Named(name), Value::Adt(constructor, items))
+                    if items.len() == fields.len()
+                        && self.globals.interner.resolve(*name) == constructor.as_str() =>
+                {
+                    self.match_all(fields, items.iter(), env)
+                }
+                (CoreTag::None, Value::None) | (CoreTag::Nil, Value::EmptyList | Value::None) => {
+                    Some(env)
+                }
+                (CoreTag::Some, Value::Some(inner))
+                | (CoreTag::Left, Value::Left(inner))
+                | (CoreTag::Right, Value::Right(inner)) => {
+                    self.match_all(fields, std::iter::once(&**inner), env)
+                }
+                (CoreTag::Cons, Value::Cons(cell)) => {
+                    self.match_all(fields, [&cell.0, &cell.1].into_iter(), env)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn match_all<'v>(
+        &self,
+        pats: &[CorePat],
+        values: impl Iterator<Item = &'v Value<'a>>,
+        env: Env<'a>,
+    ) -> Option<Env<'a>>
+    where
+        'a: 'v,
+    {
+        pats.iter()
+            .zip(values)
+            .try_fold(env, |env, (pat, value)| self.match_pat(pat, value, env))
+    }
+
+    /// In a merged multi-module program Core may bind a reference to a
+    /// same-named definition of another module; backends compile modules
+    /// separately, so a definition of the current module wins.
+    fn same_module_def(&self, index: usize) -> usize {
+        let globals = self.globals;
+        if globals.module_of[index] == self.module {
+            return index;
+        }
+        let name = globals.program.defs[index].name;
+        globals.by_name[&name]
+            .iter()
+            .copied()
+            .find(|i| globals.module_of[*i] == self.module && self.module.is_some())
+            .unwrap_or(index)
+    }
+
+    /// Resolve a free name: a definition in the current module first, then
+    /// a root-level one. Nullary constructors are values; other names
+    /// without a definition are resolved when called.
+    fn resolve_name(&self, name: Identifier) -> Result<Value<'a>, EvalError> {
+        let Some(candidates) = self.globals.by_name.get(&name) else {
+            if self.globals.constructors.get(&name) == Some(&0) {
+                return Ok(self.adt(name, Vec::new()));
+            }
+            return Ok(Value::Builtin(name));
+        };
+        let pick = candidates
+            .iter()
+            .find(|i| self.globals.module_of[**i] == self.module)
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .find(|i| self.globals.module_of[**i].is_none())
+            })
+            .unwrap_or(&candidates[0]);
+        self.globals.get(*pick)
+    }
+
+    /// Unwind to the innermost `try`/`assert_throws` after a runtime error.
+    fn catch(&mut self, mut message: String) -> Result<Control<'a>, EvalError> {
+        while let Some(frame) = self.stack.pop() {
+            match frame {
+                Frame::Call { module } => self.module = module,
+                Frame::Catch { expected: None } => {
+                    return Ok(Control::Value(Value::Tuple(Rc::new(vec![
+                        Value::string("error"),
+                        Value::string(message),
+                    ]))));
+                }
+                Frame::Catch {
+                    expected: Some(Some(expected)),
+                } if !message.contains(&expected) => {
+                    message = format!(
+                        "assert_throws failed\n  expected error containing: {expected}\n  actual error: {message}"
+                    );
+                }
+                Frame::Catch { expected: Some(_) } => return Ok(Control::Value(Value::None)),
+                _ => {}
+            }
+        }
+        Err(EvalError::Runtime(message))
+    }
+}
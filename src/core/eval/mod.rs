@@ -0,0 +1,149 @@
+//! Reference interpreter for Core IR.
+//!
+//! A deliberately simple tree-walking evaluator over `CoreExpr`, independent
+//! of both backends. Running it on the freshly lowered program and again
+//! after every named pass in `core::passes` tells a Core-pass bug (the
+//! observable behavior changes at some stage) apart from a backend bug (all
+//! stages agree but the VM or LLVM output does not). `flux parity` exposes
+//! it as the `core` way; the driver as `--core-eval`.
+//!
+//! Primops share the VM's implementations (see `primop.rs`), so this oracle
+//! checks Core semantics — binding, control flow, pattern matching, effect
+//! handlers — rather than re-deriving library behavior.
+
+mod machine;
+mod primop;
+mod value;
+
+use std::fmt;
+
+use crate::core::CoreProgram;
+use crate::syntax::interner::Interner;
+
+use machine::{Globals, Machine};
+use primop::Io;
+
+/// Why an evaluation stopped early.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// The program failed at runtime (panic, division by zero, ...).
+    Runtime(String),
+    /// The program uses something the interpreter does not model.
+    Unsupported(String),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Runtime(message) => write!(f, "{message}"),
+            EvalError::Unsupported(what) => write!(f, "unsupported: {what}"),
+        }
+    }
+}
+
+/// Observable result of evaluating one program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalOutcome {
+    pub stdout: String,
+    pub stderr: String,
+    pub result: Result<(), EvalError>,
+}
+
+impl EvalOutcome {
+    /// Whether two runs are indistinguishable to `flux parity`: same stdout
+    /// and the same success/failure, ignoring error wording.
+    pub fn same_behavior(&self, other: &EvalOutcome) -> bool {
+        self.stdout == other.stdout
+            && matches!(
+                (&self.result, &other.result),
+                (Ok(()), Ok(())) | (Err(EvalError::Runtime(_)), Err(EvalError::Runtime(_)))
+            )
+    }
+
+    /// One-line description used when reporting a behavior change.
+    pub fn summary(&self) -> String {
+        let lines = self.stdout.lines().count();
+        match &self.result {
+            Ok(()) => format!("exits normally after {lines} line(s) of output"),
+            Err(err) => format!("fails after {lines} line(s) of output: {err}"),
+        }
+    }
+}
+
+/// Evaluate `program`: initialize its top-level values in order, then call
+/// the root-level `main` if there is one. `stdin` supplies what `read_stdin`
+/// returns on its first call; it is only invoked if the program reads.
+pub fn evaluate(
+    program: &CoreProgram,
+    interner: &Interner,
+    stdin: &dyn Fn() -> String,
+) -> EvalOutcome {
+    evaluate_replaying(program, interner, stdin, Vec::new()).0
+}
+
+/// Evaluate `program`, answering clock reads from `clock` while it lasts.
+/// Also returns every clock reading the run made.
+fn evaluate_replaying(
+    program: &CoreProgram,
+    interner: &Interner,
+    stdin: &dyn Fn() -> String,
+    clock: Vec<i64>,
+) -> (EvalOutcome, Vec<i64>) {
+    let mut io = Io::new(stdin, clock);
+    let result = run(program, interner, &mut io);
+    let outcome = EvalOutcome {
+        stdout: io.stdout,
+        stderr: io.stderr,
+        result,
+    };
+    (outcome, io.clock)
+}
+
+fn run(program: &CoreProgram, interner: &Interner, io: &mut Io<'_>) -> Result<(), EvalError> {
+    let globals = Globals::new(program, interner);
+    globals.initialize(io)?;
+    if let Some(main) = globals.main() {
+        Machine::new(&globals, io, None).call_value(main, Vec::new())?;
+    }
+    Ok(())
+}
+
+/// Result of evaluating a sequence of pass snapshots.
+#[derive(Debug, Clone)]
+pub struct StagesReport {
+    /// Outcome of the first (freshly lowered) stage — the reference result.
+    pub reference: EvalOutcome,
+    /// First stage whose behavior differs from the reference, with its
+    /// outcome. Stages the interpreter cannot evaluate are skipped.
+    pub changed_at: Option<(String, EvalOutcome)>,
+}
+
+/// Evaluate each `(stage, program)` snapshot in order and report the first
+/// one that behaves differently from the first snapshot. Later stages see
+/// the clock readings of the first, so timing output does not count as a
+/// difference.
+///
+/// Returns `None` when there are no snapshots.
+pub fn evaluate_stages(
+    stages: &[(String, CoreProgram)],
+    interner: &Interner,
+    stdin: &dyn Fn() -> String,
+) -> Option<StagesReport> {
+    let ((_, first), rest) = stages.split_first()?;
+    let (reference, clock) = evaluate_replaying(first, interner, stdin, Vec::new());
+    if matches!(reference.result, Err(EvalError::Unsupported(_))) {
+        return Some(StagesReport {
+            reference,
+            changed_at: None,
+        });
+    }
+    let changed_at = rest.iter().find_map(|(stage, program)| {
+        let (outcome, _) = evaluate_replaying(program, interner, stdin, clock.clone());
+        let comparable = !matches!(outcome.result, Err(EvalError::Unsupported(_)));
+        (comparable && !outcome.same_behavior(&reference)).then(|| (stage.clone(), outcome))
+    });
+    Some(StagesReport {
+        reference,
+        changed_at,
+    })
+}
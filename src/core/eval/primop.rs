@@ -0,0 +1,280 @@
+//! `CorePrimOp` semantics for the reference Core interpreter.
+//!
+//! Operators the backends lower to generic binary/prefix instructions are
+//! implemented here directly; everything else is delegated to the VM's
+//! `execute_core_primop` so both agree on library behavior by construction.
+//! Higher-order primops (`Try`, `Time`, `AssertThrows`) need the machine and
+//! are handled in `machine.rs`.
+
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use crate::core::CorePrimOp;
+use crate::runtime::RuntimeContext;
+use crate::runtime::hamt;
+use crate::runtime::value::{Value as RuntimeValue, format_value};
+use crate::vm::core_dispatch::execute_core_primop;
+
+use super::EvalError;
+use super::value::Value;
+
+/// Observable effects of one evaluation: captured output, the program's
+/// stdin, which is handed out once like a real stream, and clock readings.
+pub(super) struct Io<'s> {
+    pub stdout: String,
+    pub stderr: String,
+    stdin: Option<&'s dyn Fn() -> String>,
+    /// Every clock reading taken, in order.
+    pub clock: Vec<i64>,
+    /// Readings from an earlier run, returned before the real clock so
+    /// successive stages observe the same times.
+    replay: std::vec::IntoIter<i64>,
+}
+
+impl<'s> Io<'s> {
+    pub fn new(stdin: &'s dyn Fn() -> String, replay: Vec<i64>) -> Self {
+        Self {
+            stdout: String::new(),
+            stderr: String::new(),
+            stdin: Some(stdin),
+            clock: Vec::new(),
+            replay: replay.into_iter(),
+        }
+    }
+
+    pub fn read_clock(&mut self, read: impl FnOnce() -> i64) -> i64 {
+        let value = self.replay.next().unwrap_or_else(read);
+        self.clock.push(value);
+        value
+    }
+}
+
+/// Context for delegated primops; output is captured, callbacks refused.
+struct DelegateContext<'io, 's> {
+    io: &'io mut Io<'s>,
+}
+
+impl RuntimeContext for DelegateContext<'_, '_> {
+    fn invoke_value(
+        &mut self,
+        _callee: RuntimeValue,
+        _args: Vec<RuntimeValue>,
+    ) -> Result<RuntimeValue, String> {
+        Err("callback from delegated primop".to_string())
+    }
+
+    fn invoke_base_function_borrowed(
+        &mut self,
+        _base_fn_index: usize,
+        _args: &[&RuntimeValue],
+    ) -> Result<RuntimeValue, String> {
+        Err("base function call from delegated primop".to_string())
+    }
+
+    fn write_stdout(&mut self, text: &str) {
+        self.io.stdout.push_str(text);
+    }
+}
+
+pub(super) fn apply_primop<'a>(
+    op: CorePrimOp,
+    args: Vec<Value<'a>>,
+    io: &mut Io<'_>,
+) -> Result<Value<'a>, EvalError> {
+    use CorePrimOp::*;
+
+    match op {
+        Add | IAdd | FAdd | Concat => arith(op, &args, "add"),
+        Sub | ISub | FSub => arith(op, &args, "subtract"),
+        Mul | IMul | FMul => arith(op, &args, "multiply"),
+        Div | IDiv | FDiv => arith(op, &args, "divide"),
+        Mod | IMod => arith(op, &args, "modulo"),
+        Eq | ICmpEq | FCmpEq => Ok(Value::Bool(values_equal(&args[0], &args[1]))),
+        NEq | ICmpNe | FCmpNe => Ok(Value::Bool(!values_equal(&args[0], &args[1]))),
+        Lt | ICmpLt | FCmpLt => compare(&args, |o| o.is_lt()),
+        Le | ICmpLe | FCmpLe => compare(&args, |o| o.is_le()),
+        Gt | ICmpGt | FCmpGt => compare(&args, |o| o.is_gt()),
+        Ge | ICmpGe | FCmpGe => compare(&args, |o| o.is_ge()),
+        And => Ok(Value::Bool(args[0].is_truthy() && args[1].is_truthy())),
+        Or => Ok(Value::Bool(args[0].is_truthy() || args[1].is_truthy())),
+        Not => Ok(Value::Bool(!args[0].is_truthy())),
+        Interpolate => Ok(Value::string(
+            args.iter()
+                .map(|part| part.to_runtime().to_string_value())
+                .collect::<String>(),
+        )),
+        MakeList => Ok(Value::list(args)),
+        MakeArray => Ok(Value::Array(Rc::new(args))),
+        MakeTuple => Ok(Value::Tuple(Rc::new(args))),
+        MakeHash => {
+            let mut node = hamt::hamt_empty();
+            for pair in args.chunks(2) {
+                let key = pair[0].to_runtime();
+                let key = key
+                    .to_hash_key()
+                    .ok_or_else(|| runtime(format!("unusable as hash key: {}", key.type_name())))?;
+                node = hamt::hamt_insert(&node, key, pair[1].to_runtime());
+            }
+            Ok(Value::Map(node))
+        }
+        Index => index(&args[0], &args[1]),
+        Print | Println => {
+            // As in the VM's `Print`, every argument is printed, separated by spaces.
+            let line: Vec<String> = args.iter().map(|a| format_value(&a.to_runtime())).collect();
+            io.stdout.push_str(&line.join(" "));
+            io.stdout.push('\n');
+            Ok(Value::None)
+        }
+        DebugTrace => {
+            io.stderr.push_str(&format_value(&args[0].to_runtime()));
+            io.stderr.push('\n');
+            Ok(Value::None)
+        }
+        ClockNow => Ok(Value::Int(io.read_clock(|| {
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |now| now.as_millis() as i64)
+        }))),
+        ReadStdin => Ok(Value::string(
+            io.stdin.take().map(|read| read()).unwrap_or_default(),
+        )),
+        EvvGet | EvvSet | FreshMarker | EvvInsert | YieldTo | YieldExtend | YieldPrompt
+        | IsYielding | PerformDirect => Err(EvalError::Unsupported(format!(
+            "primop {op:?} (native yield model)"
+        ))),
+        Try | Time | AssertThrows => unreachable!("higher-order primop {op:?} handled by machine"),
+        _ => {
+            let runtime_args = args.iter().map(Value::to_runtime).collect();
+            let mut ctx = DelegateContext { io };
+            let result = execute_core_primop(&mut ctx, op, runtime_args).map_err(runtime)?;
+            Value::from_runtime(result)
+        }
+    }
+}
+
+fn runtime(message: impl Into<String>) -> EvalError {
+    EvalError::Runtime(message.into())
+}
+
+fn type_name(value: &Value<'_>) -> &'static str {
+    value.to_runtime().type_name()
+}
+
+fn arith<'a>(op: CorePrimOp, args: &[Value<'a>], verb: &str) -> Result<Value<'a>, EvalError> {
+    use CorePrimOp::*;
+
+    let int_op = |l: i64, r: i64| -> Result<i64, EvalError> {
+        match op {
+            Add | IAdd | FAdd | Concat => Ok(l.wrapping_add(r)),
+            Sub | ISub | FSub => Ok(l.wrapping_sub(r)),
+            Mul | IMul | FMul => Ok(l.wrapping_mul(r)),
+            _ if r == 0 => Err(runtime("division by zero")),
+            Div | IDiv | FDiv => Ok(l.wrapping_div(r)),
+            _ => Ok(l.wrapping_rem(r)),
+        }
+    };
+    let float_op = |l: f64, r: f64| match op {
+        Add | IAdd | FAdd | Concat => l + r,
+        Sub | ISub | FSub => l - r,
+        Mul | IMul | FMul => l * r,
+        Div | IDiv | FDiv => l / r,
+        _ => l % r,
+    };
+
+    match (&args[0], &args[1]) {
+        (Value::Int(l), Value::Int(r)) => Ok(Value::Int(int_op(*l, *r)?)),
+        (Value::Float(l), Value::Float(r)) => Ok(Value::Float(float_op(*l, *r))),
+        (Value::Int(l), Value::Float(r)) => Ok(Value::Float(float_op(*l as f64, *r))),
+        (Value::Float(l), Value::Int(r)) => Ok(Value::Float(float_op(*l, *r as f64))),
+        (Value::String(l), Value::String(r)) if matches!(op, Add | IAdd | Concat) => {
+            Ok(Value::string(format!("{l}{r}")))
+        }
+        (l, r) => Err(runtime(format!(
+            "cannot {verb} {} and {}",
+            type_name(l),
+            type_name(r)
+        ))),
+    }
+}
+
+fn values_equal(left: &Value<'_>, right: &Value<'_>) -> bool {
+    left.to_runtime() == right.to_runtime()
+}
+
+fn compare<'a>(
+    args: &[Value<'a>],
+    accept: impl Fn(std::cmp::Ordering) -> bool,
+) -> Result<Value<'a>, EvalError> {
+    let ordering = match (&args[0], &args[1]) {
+        (Value::Int(l), Value::Int(r)) => Some(l.cmp(r)),
+        (Value::Float(l), Value::Float(r)) => l.partial_cmp(r),
+        (Value::Int(l), Value::Float(r)) => (*l as f64).partial_cmp(r),
+        (Value::Float(l), Value::Int(r)) => l.partial_cmp(&(*r as f64)),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        (l, r) => {
+            return Err(runtime(format!(
+                "cannot compare {} and {}",
+                type_name(l),
+                type_name(r)
+            )));
+        }
+    };
+    Ok(Value::Bool(ordering.is_some_and(accept)))
+}
+
+fn index<'a>(collection: &Value<'a>, key: &Value<'a>) -> Result<Value<'a>, EvalError> {
+    let nth = |items: &[Value<'a>], i: i64| {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| items.get(i))
+            .map_or(Value::None, |v| Value::Some(Rc::new(v.clone())))
+    };
+    match (collection, key) {
+        (Value::Array(items), Value::Int(i)) | (Value::Tuple(items), Value::Int(i)) => {
+            Ok(nth(items, *i))
+        }
+        (Value::Cons(_), Value::Int(i)) => {
+            let mut current = collection;
+            let mut remaining = *i;
+            while let Value::Cons(cell) = current {
+                if remaining == 0 {
+                    return Ok(Value::Some(Rc::new(cell.0.clone())));
+                }
+                remaining -= 1;
+                current = &cell.1;
+            }
+            Ok(Value::None)
+        }
+        (Value::Map(node), key) => {
+            let key = key.to_runtime();
+            let hash_key = key
+                .to_hash_key()
+                .ok_or_else(|| runtime(format!("unusable as hash key: {}", key.type_name())))?;
+            match hamt::hamt_lookup(node, &hash_key) {
+                Some(value) => Ok(Value::Some(Rc::new(Value::from_runtime(value)?))),
+                None => Ok(Value::None),
+            }
+        }
+        (other, _) => Err(runtime(format!(
+            "index operator not supported: {}",
+            type_name(other)
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Io, Value, apply_primop};
+    use crate::core::CorePrimOp;
+
+    #[test]
+    fn print_and_println_write_every_argument_on_one_line() {
+        let stdin = String::new;
+        let mut io = Io::new(&stdin, Vec::new());
+        for op in [CorePrimOp::Print, CorePrimOp::Println] {
+            let args = vec![Value::Int(1), Value::string("a"), Value::Bool(true)];
+            apply_primop(op, args, &mut io).expect("print succeeds");
+        }
+        assert_eq!(io.stdout, "1 \"a\" true\n1 \"a\" true\n");
+    }
+}
@@ -0,0 +1,215 @@
+//! Values and environments of the reference Core interpreter.
+//!
+//! Data values mirror `runtime::value::Value` one-to-one so printing and
+//! equality can reuse the runtime implementations; functions are the
+//! interpreter's own closures and captured continuations.
+
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use crate::core::{CoreBinder, CoreBinderId, CoreExpr};
+use crate::runtime::cons_cell::ConsCell;
+use crate::runtime::hamt::HamtNode;
+use crate::runtime::value::{AdtFields, AdtValue, Value as RuntimeValue};
+use crate::syntax::Identifier;
+
+use super::EvalError;
+use super::machine::Frame;
+
+/// Placeholder a function takes when converted to a runtime value. Prints
+/// like the VM's closures; converting it back is reported as unsupported.
+const FUNCTION_PLACEHOLDER: &str = "<closure>";
+
+#[derive(Clone)]
+pub enum Value<'a> {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(Rc<String>),
+    /// `None`, and the unit value.
+    None,
+    EmptyList,
+    Some(Rc<Value<'a>>),
+    Left(Rc<Value<'a>>),
+    Right(Rc<Value<'a>>),
+    Cons(Rc<(Value<'a>, Value<'a>)>),
+    Tuple(Rc<Vec<Value<'a>>>),
+    Array(Rc<Vec<Value<'a>>>),
+    Adt(Rc<String>, Rc<Vec<Value<'a>>>),
+    /// Maps keep the runtime HAMT so iteration order matches the backends.
+    Map(Rc<HamtNode>),
+    Closure(Rc<Closure<'a>>),
+    Continuation(Rc<Continuation<'a>>),
+    /// A free name with no definition, resolved to a primop when called.
+    Builtin(Identifier),
+}
+
+pub struct Closure<'a> {
+    pub params: &'a [CoreBinder],
+    pub body: &'a CoreExpr,
+    pub env: Env<'a>,
+    /// Module the closure was defined in, used to resolve free names.
+    pub module: Option<Identifier>,
+}
+
+/// A captured delimited continuation: the frames from the handling
+/// `Handle` (inclusive) up to the `perform`.
+pub struct Continuation<'a> {
+    pub frames: Vec<Frame<'a>>,
+    pub module: Option<Identifier>,
+}
+
+impl<'a> Value<'a> {
+    pub fn string(text: impl Into<String>) -> Self {
+        Value::String(Rc::new(text.into()))
+    }
+
+    pub fn list(items: Vec<Value<'a>>) -> Self {
+        items
+            .into_iter()
+            .rev()
+            .fold(Value::EmptyList, |tail, head| {
+                Value::Cons(Rc::new((head, tail)))
+            })
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Bool(false) | Value::None | Value::EmptyList)
+    }
+
+    /// Convert to a runtime value for printing, equality and delegated
+    /// primops. Functions become an opaque placeholder.
+    pub fn to_runtime(&self) -> RuntimeValue {
+        match self {
+            Value::Int(n) => RuntimeValue::Integer(*n),
+            Value::Float(f) => RuntimeValue::Float(*f),
+            Value::Bool(b) => RuntimeValue::Boolean(*b),
+            Value::String(s) => RuntimeValue::String(Rc::clone(s)),
+            Value::None => RuntimeValue::None,
+            Value::EmptyList => RuntimeValue::EmptyList,
+            Value::Some(v) => RuntimeValue::Some(Rc::new(v.to_runtime())),
+            Value::Left(v) => RuntimeValue::Left(Rc::new(v.to_runtime())),
+            Value::Right(v) => RuntimeValue::Right(Rc::new(v.to_runtime())),
+            Value::Cons(cell) => RuntimeValue::Cons(Rc::new(ConsCell::new(
+                cell.0.to_runtime(),
+                cell.1.to_runtime(),
+            ))),
+            Value::Tuple(items) => {
+                RuntimeValue::Tuple(Rc::new(items.iter().map(Value::to_runtime).collect()))
+            }
+            Value::Array(items) => {
+                RuntimeValue::Array(Rc::new(items.iter().map(Value::to_runtime).collect()))
+            }
+            Value::Adt(name, fields) if fields.is_empty() => RuntimeValue::AdtUnit(Rc::clone(name)),
+            Value::Adt(name, fields) => RuntimeValue::Adt(Rc::new(AdtValue {
+                constructor: Rc::clone(name),
+                fields: AdtFields::from_vec(fields.iter().map(Value::to_runtime).collect()),
+            })),
+            Value::Map(node) => RuntimeValue::HashMap(Rc::clone(node)),
+            Value::Closure(_) | Value::Continuation(_) | Value::Builtin(_) => {
+                RuntimeValue::AdtUnit(Rc::new(FUNCTION_PLACEHOLDER.to_string()))
+            }
+        }
+    }
+
+    /// Convert a runtime value produced by a delegated primop back.
+    pub fn from_runtime(value: RuntimeValue) -> Result<Self, EvalError> {
+        Ok(match value {
+            RuntimeValue::Integer(n) => Value::Int(n),
+            RuntimeValue::Float(f) => Value::Float(f),
+            RuntimeValue::Boolean(b) => Value::Bool(b),
+            RuntimeValue::String(s) => Value::String(s),
+            RuntimeValue::None => Value::None,
+            RuntimeValue::EmptyList => Value::EmptyList,
+            RuntimeValue::Some(v) => Value::Some(Rc::new(Self::from_runtime(unwrap_rc(v))?)),
+            RuntimeValue::Left(v) => Value::Left(Rc::new(Self::from_runtime(unwrap_rc(v))?)),
+            RuntimeValue::Right(v) => Value::Right(Rc::new(Self::from_runtime(unwrap_rc(v))?)),
+            RuntimeValue::Cons(cell) => Value::Cons(Rc::new((
+                Self::from_runtime(cell.head.clone())?,
+                Self::from_runtime(cell.tail.clone())?,
+            ))),
+            RuntimeValue::Tuple(items) => Value::Tuple(Rc::new(from_runtime_items(&items)?)),
+            RuntimeValue::Array(items) => Value::Array(Rc::new(from_runtime_items(&items)?)),
+            RuntimeValue::AdtUnit(name) if name.as_str() == FUNCTION_PLACEHOLDER => {
+                return Err(EvalError::Unsupported(
+                    "function stored inside a runtime map".to_string(),
+                ));
+            }
+            RuntimeValue::AdtUnit(name) => Value::Adt(name, Rc::new(Vec::new())),
+            RuntimeValue::Adt(adt) => {
+                let fields = adt
+                    .fields
+                    .iter()
+                    .cloned()
+                    .map(Self::from_runtime)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Value::Adt(Rc::clone(&adt.constructor), Rc::new(fields))
+            }
+            RuntimeValue::HashMap(node) => Value::Map(node),
+            other => {
+                return Err(EvalError::Unsupported(format!(
+                    "runtime value of type {}",
+                    other.type_name()
+                )));
+            }
+        })
+    }
+}
+
+fn unwrap_rc(value: Rc<RuntimeValue>) -> RuntimeValue {
+    Rc::try_unwrap(value).unwrap_or_else(|shared| (*shared).clone())
+}
+
+fn from_runtime_items<'a>(items: &[RuntimeValue]) -> Result<Vec<Value<'a>>, EvalError> {
+    items.iter().cloned().map(Value::from_runtime).collect()
+}
+
+/// Persistent environment: a linked list of binder slots.
+///
+/// Slots are write-once so `letrec` can bind a name before its right-hand
+/// side is evaluated. Recursive closures form `Rc` cycles; the interpreter
+/// is a short-lived oracle, so those are simply leaked.
+#[derive(Clone, Default)]
+pub struct Env<'a>(Option<Rc<Slot<'a>>>);
+
+pub struct Slot<'a> {
+    id: CoreBinderId,
+    value: OnceCell<Value<'a>>,
+    next: Env<'a>,
+}
+
+impl<'a> Env<'a> {
+    pub fn bind(&self, id: CoreBinderId, value: Value<'a>) -> Self {
+        let (env, slot) = self.bind_pending(id);
+        let _ = slot.value.set(value);
+        env
+    }
+
+    /// Bind `id` to a slot that is filled later with [`Slot::fill`].
+    pub fn bind_pending(&self, id: CoreBinderId) -> (Self, Rc<Slot<'a>>) {
+        let slot = Rc::new(Slot {
+            id,
+            value: OnceCell::new(),
+            next: self.clone(),
+        });
+        (Env(Some(Rc::clone(&slot))), slot)
+    }
+
+    /// `Some(None)` means the binder is in scope but not yet initialized.
+    pub fn lookup(&self, id: CoreBinderId) -> Option<Option<Value<'a>>> {
+        let mut current = self.0.as_ref();
+        while let Some(slot) = current {
+            if slot.id == id {
+                return Some(slot.value.get().cloned());
+            }
+            current = slot.next.0.as_ref();
+        }
+        None
+    }
+}
+
+impl<'a> Slot<'a> {
+    pub fn fill(&self, value: Value<'a>) {
+        let _ = self.value.set(value);
+    }
+}
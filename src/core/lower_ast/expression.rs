@@ -2,7 +2,7 @@ use crate::{
     diagnostics::position::Span,
     syntax::{
         Identifier,
-        expression::{ExprId, Expression, NamedFieldInit, StringPart},
+        expression::{ExprId, Expression, NamedFieldInit, StringPart, hole_panic_message},
     },
     types::{infer_type::InferType, type_constructor::TypeConstructor},
 };
@@ -160,18 +160,21 @@ impl<'a> super::AstLowerer<'a> {
                 function,
                 arguments,
                 span,
-                ..
+                id: call_id,
             } => {
                 // Phase 4 Step 5: compile-time class method dispatch.
-                // If the callee is a class method and the argument type is known,
-                // resolve directly to the mangled instance function.
-                if let Some(mangled) = self.try_resolve_class_call_expr(function, arguments) {
+                // If the callee is a class method and the argument or result
+                // type is known, resolve directly to the mangled instance function.
+                if let Some(mangled) =
+                    self.try_resolve_class_call_expr(function, *call_id, arguments)
+                {
                     let method_name = match function.as_ref() {
                         Expression::Identifier { name, .. } => *name,
                         Expression::MemberAccess { member, .. } => *member,
                         _ => unreachable!("class call resolution only succeeds for direct callees"),
                     };
-                    let mut args = self.resolve_direct_class_call_dict_args(method_name, arguments);
+                    let mut args =
+                        self.resolve_direct_class_call_dict_args(method_name, *call_id, arguments);
                     args.extend(arguments.iter().map(|a| self.lower_expr(a)));
                     return CoreExpr::App {
                         func: Box::new(CoreExpr::external_var(mangled, *span)),
@@ -426,6 +429,21 @@ impl<'a> super::AstLowerer<'a> {
                 span,
                 ..
             } => self.lower_spread_expression(base, overrides, *span),
+
+            Expression::Hole { name, span, .. } => {
+                let name = name.map(|name| match self.interner {
+                    Some(interner) => interner.resolve(name).to_string(),
+                    None => name.to_string(),
+                });
+                CoreExpr::PrimOp {
+                    op: CorePrimOp::Panic,
+                    args: vec![CoreExpr::Lit(
+                        CoreLit::String(hole_panic_message(name.as_deref())),
+                        *span,
+                    )],
+                    span: *span,
+                }
+            }
         }
     }
 
@@ -22,7 +22,7 @@ use crate::{
     syntax::{
         Identifier, block::Block, expression::ExprId, program::Program, statement::Statement,
     },
-    types::infer_type::InferType,
+    types::{class_env::InstanceDef, infer_type::InferType},
 };
 
 use super::{
@@ -307,68 +307,92 @@ impl<'a> AstLowerer<'a> {
         self.hm_expr_types.get(&id)
     }
 
-    /// Try to resolve a class method call to a mangled instance function.
+    /// Find the instance a direct class-method call resolves to, with the
+    /// concrete class type arguments.
     ///
-    /// If `name` is a known class method, and the first argument's type is
-    /// known (concrete, not a type variable), resolves to `__tc_{Class}_{Type}_{method}`.
-    /// Returns `None` if resolution fails (unknown type, no instance, no ClassEnv).
-    pub(super) fn try_resolve_class_call(
+    /// The first argument's type decides when it is known; otherwise the
+    /// call's own inferred type is matched against the method's return type,
+    /// which handles methods like `fn arbitrary() -> Gen<a>`.
+    fn resolve_class_call_instance(
         &self,
         name: Identifier,
+        call_id: ExprId,
         arguments: &[crate::syntax::expression::Expression],
-    ) -> Option<Identifier> {
+    ) -> Option<(Identifier, &InstanceDef, Vec<InferType>)> {
         let class_env = self.class_env?;
         let interner = self.interner?;
-
-        // Check if this name is a class method.
         let (class_name, _class_def) = class_env.method_to_class(name)?;
 
-        // Try compile-time resolution: if the first argument's type is concrete,
-        // find the matching instance and build the mangled name from all of
-        // the instance's type args (supporting multi-param classes).
-        if let Some(first_arg) = arguments.first()
-            && let Some(first_arg_type) = self.hm_expr_types.get(&first_arg.expr_id())
-            && let Some((instance, _concrete_type_args)) = class_env
-                .resolve_method_call_instance_from_first_arg(class_name, first_arg_type, interner)
-        {
-            // Build mangled name from the instance head exactly as dispatch
-            // generation does. This preserves higher-kinded heads such as
-            // `Functor<List>` while still allowing first-argument instance
-            // selection for multi-parameter classes like `Convert<Int, String>`.
-            let type_key = instance
-                .type_args
-                .iter()
-                .map(|a| a.display_with(interner))
-                .collect::<Vec<_>>()
-                .join("_");
-            let class_str = interner.resolve(class_name);
-            let method_str = interner.resolve(name);
-            let mangled = format!("__tc_{class_str}_{type_key}_{method_str}");
-            if let Some(sym) = interner.lookup(&mangled) {
-                return Some(sym);
-            }
-        }
+        arguments
+            .first()
+            .and_then(|first_arg| self.hm_expr_types.get(&first_arg.expr_id()))
+            .and_then(|first_arg_type| {
+                class_env.resolve_method_call_instance_from_first_arg(
+                    class_name,
+                    first_arg_type,
+                    interner,
+                )
+            })
+            .or_else(|| {
+                let result_type = self.hm_expr_types.get(&call_id)?;
+                class_env.resolve_method_call_instance_from_result(
+                    class_name,
+                    name,
+                    result_type,
+                    interner,
+                )
+            })
+            .map(|(instance, concrete_type_args)| (class_name, instance, concrete_type_args))
+    }
 
-        // No compile-time resolution possible — return None.
-        // Dictionary elaboration handles polymorphic calls via dict params.
-        None
+    /// Try to resolve a class method call to a mangled instance function.
+    ///
+    /// If `name` is a known class method and the instance is known from the
+    /// first argument or the call's result type, resolves to
+    /// `__tc_{Class}_{Type}_{method}`.
+    /// Returns `None` if resolution fails (unknown type, no instance, no ClassEnv).
+    pub(super) fn try_resolve_class_call(
+        &self,
+        name: Identifier,
+        call_id: ExprId,
+        arguments: &[crate::syntax::expression::Expression],
+    ) -> Option<Identifier> {
+        let interner = self.interner?;
+        // Unresolved calls are left to dictionary elaboration.
+        let (class_name, instance, _concrete_type_args) =
+            self.resolve_class_call_instance(name, call_id, arguments)?;
+
+        // Build mangled name from the instance head exactly as dispatch
+        // generation does. This preserves higher-kinded heads such as
+        // `Functor<List>` while still allowing first-argument instance
+        // selection for multi-parameter classes like `Convert<Int, String>`.
+        let type_key = instance
+            .type_args
+            .iter()
+            .map(|a| a.display_with(interner))
+            .collect::<Vec<_>>()
+            .join("_");
+        let class_str = interner.resolve(class_name);
+        let method_str = interner.resolve(name);
+        interner.lookup(&format!("__tc_{class_str}_{type_key}_{method_str}"))
     }
 
     pub(super) fn try_resolve_class_call_expr(
         &self,
         function: &crate::syntax::expression::Expression,
+        call_id: ExprId,
         arguments: &[crate::syntax::expression::Expression],
     ) -> Option<Identifier> {
         match function {
             crate::syntax::expression::Expression::Identifier { name, .. } => {
-                self.try_resolve_class_call(*name, arguments)
+                self.try_resolve_class_call(*name, call_id, arguments)
             }
             crate::syntax::expression::Expression::MemberAccess { object, member, .. } => {
                 let crate::syntax::expression::Expression::Identifier { .. } = object.as_ref()
                 else {
                     return None;
                 };
-                self.try_resolve_class_call(*member, arguments)
+                self.try_resolve_class_call(*member, call_id, arguments)
             }
             _ => None,
         }
@@ -377,28 +401,20 @@ impl<'a> AstLowerer<'a> {
     pub(super) fn resolve_direct_class_call_dict_args(
         &self,
         method_name: Identifier,
+        call_id: ExprId,
         arguments: &[crate::syntax::expression::Expression],
     ) -> Vec<CoreExpr> {
-        let (class_env, interner) = match (self.class_env, self.interner) {
-            (Some(class_env), Some(interner)) => (class_env, interner),
-            _ => return Vec::new(),
-        };
-        let Some((class_name, _)) = class_env.method_to_class(method_name) else {
-            return Vec::new();
-        };
-        let Some(first_arg) = arguments.first() else {
+        let (Some(class_env), Some(interner)) = (self.class_env, self.interner) else {
             return Vec::new();
         };
-        let Some(first_arg_type) = self.hm_expr_types.get(&first_arg.expr_id()) else {
+        let Some((class_name, _instance, concrete_type_args)) =
+            self.resolve_class_call_instance(method_name, call_id, arguments)
+        else {
             return Vec::new();
         };
 
         class_env
-            .resolve_instance_context_dictionaries(
-                class_name,
-                std::slice::from_ref(first_arg_type),
-                interner,
-            )
+            .resolve_instance_context_dictionaries(class_name, &concrete_type_args, interner)
             .map(|dicts| dicts.iter().map(Self::lower_dictionary_ref).collect())
             .unwrap_or_default()
     }
@@ -558,6 +574,23 @@ impl<'a> AstLowerer<'a> {
                         Self::match_constraint_type_var(pattern_arg, actual_arg, target)
                     })
             }
+            // Function-typed parameters such as `prop: (a) -> Bool` bind the
+            // variable through the argument's parameter or result types.
+            InferType::Fun(pattern_params, pattern_ret, _) => {
+                let InferType::Fun(actual_params, actual_ret, _) = actual else {
+                    return None;
+                };
+                if pattern_params.len() != actual_params.len() {
+                    return None;
+                }
+                pattern_params
+                    .iter()
+                    .zip(actual_params.iter())
+                    .chain(std::iter::once((pattern_ret.as_ref(), actual_ret.as_ref())))
+                    .find_map(|(pattern_ty, actual_ty)| {
+                        Self::match_constraint_type_var(pattern_ty, actual_ty, target)
+                    })
+            }
             _ => None,
         }
     }
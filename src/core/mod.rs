@@ -16,6 +16,7 @@ use crate::{
 };
 
 pub mod display;
+pub mod eval;
 pub mod lower_ast;
 pub mod passes;
 pub mod to_ir;
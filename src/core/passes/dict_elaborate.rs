@@ -925,9 +925,13 @@ fn rewrite_expr(
         // Key case: App where the function is a class method reference.
         // Rewrite: App(Var(eq), args) → App(TupleField(Var(dict), idx), args)
         CoreExpr::App { func, args, span } => {
+            // A call without arguments can only be told apart by its result
+            // type, so inside an instance it means the context's instance
+            // (`gen_list(arbitrary())`), not the instance being defined.
             if let CoreExpr::Var { ref var, .. } = *func
                 && let Some(self_call) = self_call
                 && var.name == self_call.method_name
+                && !args.is_empty()
             {
                 let mut rewritten_args = self_call
                     .dict_args
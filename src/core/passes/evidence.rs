@@ -203,6 +203,8 @@ fn evidence_transform(expr: CoreExpr, next_id: &mut u32, evidence: &EvidenceMap)
         // All other expressions: recurse into children.
         CoreExpr::Var { .. } | CoreExpr::Lit(_, _) => expr,
 
+        // A lambda may be called after it escapes its handler, under another
+        // handler for the same effect, so its performs stay dynamic.
         CoreExpr::Lam {
             params,
             param_types,
@@ -213,7 +215,7 @@ fn evidence_transform(expr: CoreExpr, next_id: &mut u32, evidence: &EvidenceMap)
             params,
             param_types,
             result_ty,
-            body: Box::new(evidence_transform(*body, next_id, evidence)),
+            body: Box::new(evidence_transform(*body, next_id, &EvidenceMap::new())),
             span,
         },
 
@@ -0,0 +1,63 @@
+/// Handle-body thunking.
+///
+/// Rewrites `Handle { body, .. }` whose body is more than a single call into
+/// `Handle { body: App(Lam([], body), []), .. }`.
+///
+/// The native backend delimits a handler at the final call of its body: a
+/// yield raised inside that call returns to the handle's prompt. Work that
+/// sits before or after the call in the same function (`let n = perform
+/// Eff.op(); n + 1`) would otherwise yield straight out of the enclosing
+/// function, past the prompt. Moving the body into a thunk makes every
+/// yield come back through one call that the prompt observes. The VM
+/// captures continuations per frame and is unaffected by the extra frame.
+use crate::core::CoreExpr;
+
+use super::helpers::map_children;
+
+pub fn thunk_handle_bodies(expr: CoreExpr) -> CoreExpr {
+    match map_children(expr, thunk_handle_bodies) {
+        CoreExpr::Handle {
+            body,
+            effect,
+            parameter,
+            handlers,
+            span,
+        } if !is_single_call(&body) => {
+            let body_span = body.span();
+            let thunk = CoreExpr::Lam {
+                params: Vec::new(),
+                param_types: Vec::new(),
+                result_ty: None,
+                body,
+                span: body_span,
+            };
+            CoreExpr::Handle {
+                body: Box::new(CoreExpr::App {
+                    func: Box::new(thunk),
+                    args: Vec::new(),
+                    span: body_span,
+                }),
+                effect,
+                parameter,
+                handlers,
+                span,
+            }
+        }
+        other => other,
+    }
+}
+
+/// A body that is an atom, or one call or perform over atoms, already ends
+/// in the call the prompt observes.
+fn is_single_call(body: &CoreExpr) -> bool {
+    match body {
+        CoreExpr::Var { .. } | CoreExpr::Lit(..) => true,
+        CoreExpr::App { func, args, .. } => is_atom(func) && args.iter().all(is_atom),
+        CoreExpr::Perform { args, .. } => args.iter().all(is_atom),
+        _ => false,
+    }
+}
+
+fn is_atom(expr: &CoreExpr) -> bool {
+    matches!(expr, CoreExpr::Var { .. } | CoreExpr::Lit(..))
+}
@@ -13,6 +13,7 @@ mod dead_let;
 pub mod dict_elaborate;
 mod disciplined_inline;
 mod evidence;
+mod handle_thunk;
 mod helpers;
 mod inline;
 mod inliner;
@@ -33,9 +34,10 @@ pub use dead_let::elim_dead_let;
 pub use dict_elaborate::elaborate_dictionaries;
 pub use disciplined_inline::disciplined_inline;
 pub use evidence::evidence_pass;
+pub use handle_thunk::thunk_handle_bodies;
 pub use inline::inline_trivial_lets;
 pub use inliner::inline_lets;
-pub use primop_promote::promote_builtins;
+pub use primop_promote::{builtin_primop, promote_builtins};
 pub use specialize::specialize_known_shapes;
 
 use crate::core::{CoreExpr, CoreLit, CoreProgram};
@@ -60,7 +62,8 @@ use crate::types::{class_env::ClassEnv, type_env::TypeEnv};
 ///
 /// Normalization passes (run once after simplification):
 /// 6. `evidence_pass`            — rewrite TR Handle/Perform into evidence passing
-/// 7. `anf_normalize`            — flatten nested subexpressions into let-chains
+/// 7. `thunk_handle_bodies`      — move multi-step handle bodies into a thunk
+/// 8. `anf_normalize`            — flatten nested subexpressions into let-chains
 ///
 /// When `optimize` is true, simplification passes run up to 3 rounds (fixed-
 /// point iteration inspired by GHC's Core simplifier).  Each additional round
@@ -134,11 +137,64 @@ pub fn run_core_passes_with_class_env(
 /// Maximum number of simplification rounds when `-O` is enabled.
 const MAX_SIMPLIFIER_ROUNDS: usize = 3;
 
+/// Normalization passes whose intermediate results are observable; the
+/// final `anf_normalize` snapshot is the program itself.
+const NORMALIZATION_PASSES: &[&str] = &["evidence_pass", "thunk_handle_bodies"];
+
+type SimplifierPass = fn(CoreExpr) -> CoreExpr;
+
+/// Simplification passes in the order a single round applies them to each
+/// definition. Every entry is a pure `CoreExpr -> CoreExpr` rewrite.
+const SIMPLIFIER_PASSES: &[(&str, SimplifierPass)] = &[
+    ("beta_reduce", beta_reduce),
+    ("case_of_case", case_of_case),
+    ("case_of_known_constructor", case_of_known_constructor),
+    ("call_and_case_canonicalize", call_and_case_canonicalize),
+    ("specialize_known_shapes", specialize_known_shapes),
+    ("algebraic_simplify", algebraic_simplify),
+    ("constant_fold", constant_fold),
+    ("disciplined_inline", disciplined_inline),
+    ("inline_lets", inline_lets),
+    ("elim_dead_let", elim_dead_let),
+];
+
+/// Callback receiving a whole-program snapshot after each named pass.
+///
+/// The first argument is the stage name: the pass name, suffixed with
+/// `(round N)` for simplification passes when `optimize` iterates them.
+pub type CorePassObserver<'a> = dyn FnMut(&str, &CoreProgram) + 'a;
+
+/// Run the semantic Core pipeline (no Aether lowering), reporting a
+/// snapshot of the program to `observer` after every named pass.
+///
+/// Passes run exactly as in [`run_core_passes_with_interner`]; observing
+/// only clones intermediate definitions. Used by the reference Core
+/// interpreter (`--core-eval`) to find the pass that changed behavior.
+#[allow(clippy::result_large_err)]
+pub fn run_semantic_core_passes_observed(
+    program: &mut CoreProgram,
+    interner: &Interner,
+    optimize: bool,
+    observer: &mut CorePassObserver<'_>,
+) -> Result<Vec<Diagnostic>, Diagnostic> {
+    run_semantic_core_passes_inner(program, Some(interner), optimize, Some(observer))
+}
+
 #[allow(clippy::result_large_err)]
 fn run_semantic_core_passes_with_optional_interner(
     program: &mut CoreProgram,
     interner: Option<&Interner>,
     optimize: bool,
+) -> Result<Vec<Diagnostic>, Diagnostic> {
+    run_semantic_core_passes_inner(program, interner, optimize, None)
+}
+
+#[allow(clippy::result_large_err)]
+fn run_semantic_core_passes_inner(
+    program: &mut CoreProgram,
+    interner: Option<&Interner>,
+    optimize: bool,
+    mut observer: Option<&mut CorePassObserver<'_>>,
 ) -> Result<Vec<Diagnostic>, Diagnostic> {
     let mut warnings: Vec<Diagnostic> = Vec::new();
     // Find the maximum binder ID so passes can allocate fresh IDs above it.
@@ -149,6 +205,9 @@ fn run_semantic_core_passes_with_optional_interner(
     // before simplification (so Core passes see PrimOp, not App).
     if let Some(interner) = interner {
         promote_builtins(program, interner);
+        if let Some(observe) = observer.as_deref_mut() {
+            observe("promote_builtins", program);
+        }
     }
 
     let sentinel = CoreExpr::Lit(CoreLit::Unit, Default::default());
@@ -173,31 +232,40 @@ fn run_semantic_core_passes_with_optional_interner(
             .map(|d| helpers::expr_size(&d.expr))
             .sum();
 
+        // Passes are per-definition, so the program "after pass N" is each
+        // definition's expression after pass N; collect those when observed.
+        let mut snapshots: Vec<Vec<CoreExpr>> = if observer.is_some() {
+            vec![Vec::with_capacity(program.defs.len()); SIMPLIFIER_PASSES.len()]
+        } else {
+            Vec::new()
+        };
+
         for def in &mut program.defs {
-            let e = std::mem::replace(&mut def.expr, sentinel.clone());
-            let e = beta_reduce(e);
-            verify_aether_contract_stage(def, &e, "beta_reduce")?;
-            let e = case_of_case(e);
-            verify_aether_contract_stage(def, &e, "case_of_case")?;
-            let e = case_of_known_constructor(e);
-            verify_aether_contract_stage(def, &e, "case_of_known_constructor")?;
-            let e = call_and_case_canonicalize(e);
-            verify_aether_contract_stage(def, &e, "call_and_case_canonicalize")?;
-            let e = specialize_known_shapes(e);
-            verify_aether_contract_stage(def, &e, "specialize_known_shapes")?;
-            let e = algebraic_simplify(e);
-            verify_aether_contract_stage(def, &e, "algebraic_simplify")?;
-            let e = constant_fold(e);
-            verify_aether_contract_stage(def, &e, "constant_fold")?;
-            let e = disciplined_inline(e);
-            verify_aether_contract_stage(def, &e, "disciplined_inline")?;
-            let e = inline_lets(e);
-            verify_aether_contract_stage(def, &e, "inline_lets")?;
-            let e = elim_dead_let(e);
-            verify_aether_contract_stage(def, &e, "elim_dead_let")?;
+            let mut e = std::mem::replace(&mut def.expr, sentinel.clone());
+            for (index, (name, pass)) in SIMPLIFIER_PASSES.iter().enumerate() {
+                e = pass(e);
+                verify_aether_contract_stage(def, &e, name)?;
+                if let Some(snapshot) = snapshots.get_mut(index) {
+                    snapshot.push(e.clone());
+                }
+            }
             def.expr = e;
         }
 
+        if let Some(observe) = observer.as_deref_mut() {
+            for ((name, _), exprs) in SIMPLIFIER_PASSES.iter().zip(snapshots) {
+                let mut snapshot = program.clone();
+                for (def, expr) in snapshot.defs.iter_mut().zip(exprs) {
+                    def.expr = expr;
+                }
+                if optimize {
+                    observe(&format!("{name} (round {})", round + 1), &snapshot);
+                } else {
+                    observe(name, &snapshot);
+                }
+            }
+        }
+
         // Verify Core invariants after each simplification round.
         core_lint_stage(program, "simplification", interner)?;
 
@@ -241,14 +309,37 @@ fn run_semantic_core_passes_with_optional_interner(
     }
 
     // ── Stage 2: Normalization passes (run once) ─────────────────────────
+    let mut snapshots: Vec<Vec<CoreExpr>> = if observer.is_some() {
+        vec![Vec::with_capacity(program.defs.len()); NORMALIZATION_PASSES.len()]
+    } else {
+        Vec::new()
+    };
     for def in &mut program.defs {
         let e = std::mem::replace(&mut def.expr, sentinel.clone());
         let e = evidence_pass(e, &mut next_id);
         verify_aether_contract_stage(def, &e, "evidence_pass")?;
+        if let Some(snapshot) = snapshots.get_mut(0) {
+            snapshot.push(e.clone());
+        }
+        let e = thunk_handle_bodies(e);
+        verify_aether_contract_stage(def, &e, "thunk_handle_bodies")?;
+        if let Some(snapshot) = snapshots.get_mut(1) {
+            snapshot.push(e.clone());
+        }
         let e = anf_normalize(e, &mut next_id);
         verify_aether_contract_stage(def, &e, "anf_normalize")?;
         def.expr = e;
     }
+    if let Some(observe) = observer {
+        for (name, exprs) in NORMALIZATION_PASSES.iter().zip(snapshots) {
+            let mut snapshot = program.clone();
+            for (def, expr) in snapshot.defs.iter_mut().zip(exprs) {
+                def.expr = expr;
+            }
+            observe(name, &snapshot);
+        }
+        observe("anf_normalize", program);
+    }
 
     // Verify Core invariants after normalization.
     core_lint_stage(program, "normalization", interner)?;
@@ -137,6 +137,12 @@ fn builtin_primop_table() -> HashMap<(&'static str, usize), CorePrimOp> {
     entries.iter().map(|&(n, a, op)| ((n, a), op)).collect()
 }
 
+/// The primop a direct call to the external builtin `name` with `arity`
+/// arguments is promoted to, if any.
+pub fn builtin_primop(name: &str, arity: usize) -> Option<CorePrimOp> {
+    builtin_primop_table().get(&(name, arity)).copied()
+}
+
 /// Run the primop promotion pass on a `CoreProgram`.
 pub fn promote_builtins(program: &mut CoreProgram, interner: &Interner) {
     let table = builtin_primop_table();
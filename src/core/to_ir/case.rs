@@ -359,6 +359,7 @@ impl<'a> super::fn_ctx::FnCtx<'a> {
         match pat {
             CorePat::Wildcard | CorePat::Lit(_) | CorePat::EmptyList => {}
             CorePat::Var(binder) => {
+                self.local_names.insert(var, binder.name);
                 self.env.insert(binder.id, var);
                 self.binder_names.insert(binder.id, binder.name);
             }
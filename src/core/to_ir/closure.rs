@@ -223,6 +223,7 @@ impl<'a> super::fn_ctx::FnCtx<'a> {
                 current_block: 0,
                 env: HashMap::new(),
                 binder_names: HashMap::new(),
+                local_names: HashMap::new(),
                 last_value: None,
                 inferred_param_types: Vec::new(),
                 inferred_return_type: None,
@@ -350,6 +351,7 @@ impl<'a> super::fn_ctx::FnCtx<'a> {
                 current_block: 0,
                 env: HashMap::new(),
                 binder_names: HashMap::new(),
+                local_names: HashMap::new(),
                 last_value: None,
                 inferred_param_types: Vec::new(),
                 inferred_return_type: None,
@@ -492,6 +494,7 @@ impl<'a> super::fn_ctx::FnCtx<'a> {
                 current_block: 0,
                 env: HashMap::new(),
                 binder_names: HashMap::new(),
+                local_names: HashMap::new(),
                 last_value: None,
                 inferred_param_types: Vec::new(),
                 inferred_return_type: None,
@@ -622,6 +625,7 @@ impl<'a> super::fn_ctx::FnCtx<'a> {
                 current_block: 0,
                 env: HashMap::new(),
                 binder_names: HashMap::new(),
+                local_names: HashMap::new(),
                 last_value: None,
                 inferred_param_types: Vec::new(),
                 inferred_return_type: None,
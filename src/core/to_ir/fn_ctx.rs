@@ -29,6 +29,8 @@ pub(super) struct FnCtx<'a> {
     pub(super) current_block: usize,
     pub(super) env: HashMap<CoreBinderId, IrVar>,
     pub(super) binder_names: HashMap<CoreBinderId, Identifier>,
+    /// Source names of `let`- and pattern-bound variables, for debug info.
+    pub(super) local_names: HashMap<IrVar, Identifier>,
     pub(super) last_value: Option<IrVar>,
     pub(super) inferred_param_types: Vec<Option<CoreType>>,
     pub(super) inferred_return_type: Option<CoreType>,
@@ -62,6 +64,7 @@ impl<'a> FnCtx<'a> {
             current_block: 0,
             env: HashMap::new(),
             binder_names: HashMap::new(),
+            local_names: HashMap::new(),
             last_value: None,
             inferred_param_types: Vec::new(),
             inferred_return_type: None,
@@ -100,6 +103,7 @@ impl<'a> FnCtx<'a> {
         ir_var: IrVar,
         f: impl FnOnce(&mut Self) -> T,
     ) -> T {
+        self.local_names.insert(ir_var, name);
         let old_var = self.env.insert(binder_id, ir_var);
         let old_name = self.binder_names.insert(binder_id, name);
         let result = f(self);
@@ -151,6 +155,7 @@ impl<'a> FnCtx<'a> {
             return_type_annotation: s.return_type_annotation,
             effects: s.effects,
             captures: Vec::new(),
+            local_names: s.local_names,
             body_span: span,
             ret_type: s
                 .inferred_return_type
@@ -0,0 +1,47 @@
+//! Flux debug adapter.
+//!
+//! `flux debug <file.flx> --dap` speaks the Debug Adapter Protocol over stdio and runs the
+//! program on the bytecode [`VM`](crate::vm::VM) with a [`DebugHook`](crate::vm::debug::DebugHook)
+//! attached:
+//!
+//! - line breakpoints, resolved against the spans in [`debug_info`](crate::bytecode::debug_info)
+//!   and moved to the next line with code;
+//! - step in, step over and step out across call frames;
+//! - call-stack inspection with function names and source positions;
+//! - local and captured variables, with structured values expandable component by component;
+//! - a stop on runtime errors where they are raised, before the diagnostic is emitted.
+//!
+//! Program output from `print` and `println` is forwarded as `output` events so it cannot
+//! interleave with protocol messages on stdout.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo run -- debug examples/guide/fibonacci.flx --dap
+//! ```
+
+pub mod server;
+
+use std::{io, path::Path, sync::mpsc, thread};
+
+use crate::{bytecode::bytecode::Bytecode, lsp::transport::read_message};
+
+pub use server::{LineTable, serve};
+
+/// Runs a debug session on stdin/stdout until the client disconnects.
+///
+/// Returns the rendered runtime error the program ended with, if any.
+pub fn run_stdio(bytecode: Bytecode, entry: &Path) -> io::Result<Option<String>> {
+    let (sender, messages) = mpsc::channel();
+    // Requests are read on their own thread so `pause` reaches a running program.
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut reader = stdin.lock();
+        while let Ok(Some(message)) = read_message(&mut reader) {
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+    serve(bytecode, entry, messages, io::stdout())
+}
@@ -0,0 +1,545 @@
+//! Request handling for the debug adapter.
+//!
+//! The session answers configuration requests until the client has sent both `launch` and
+//! `configurationDone`, then runs the program on a [`VM`] with itself attached as the
+//! [`DebugHook`]. While the program runs, queued requests are drained each time a new source
+//! line is entered, so `pause` and `setBreakpoints` take effect without stopping. While it is
+//! stopped, the session blocks on the request channel until the client resumes.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::mpsc::{Receiver, TryRecvError},
+};
+
+use serde_json::{Value as Json, json};
+
+use crate::{
+    bytecode::{bytecode::Bytecode, debug_info::FunctionDebugInfo},
+    lsp::transport::write_message,
+    runtime::value::Value,
+    vm::{
+        VM,
+        debug::{DebugFrame, DebugHook, Resume, StopReason, value_children},
+    },
+};
+
+/// Flux programs run on a single thread.
+const THREAD_ID: i64 = 1;
+
+/// Lines that have code in each source file, taken from the bytecode's debug info.
+#[derive(Debug, Default)]
+pub struct LineTable {
+    files: HashMap<PathBuf, BTreeSet<usize>>,
+}
+
+impl LineTable {
+    /// Collects the lines of the entry code and of every function in the constant pool.
+    pub fn from_bytecode(bytecode: &Bytecode) -> Self {
+        let mut table = Self::default();
+        table.add(bytecode.debug_info.as_ref());
+        for constant in &bytecode.constants {
+            if let Value::Function(function) = constant {
+                table.add(function.debug_info.as_ref());
+            }
+        }
+        table
+    }
+
+    fn add(&mut self, info: Option<&FunctionDebugInfo>) {
+        let Some(info) = info else {
+            return;
+        };
+        for entry in &info.locations {
+            let Some(location) = &entry.location else {
+                continue;
+            };
+            let Some(file) = info.file_for(location.file_id) else {
+                continue;
+            };
+            if location.span.start.line > 0 {
+                self.files
+                    .entry(canonical(file))
+                    .or_default()
+                    .insert(location.span.start.line);
+            }
+        }
+    }
+
+    /// Resolves a breakpoint to the first line at or after `line` that has code.
+    pub fn resolve(&self, path: &Path, line: usize) -> Option<usize> {
+        self.files.get(path)?.range(line..).next().copied()
+    }
+}
+
+fn canonical(path: &str) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+/// Ends the adapter process. The debuggee runs inside it, so this also ends the program.
+fn end_session(code: i32) -> ! {
+    std::process::exit(code)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Configuring,
+    Running,
+    Stopped,
+    Terminated,
+}
+
+enum Action {
+    Reply,
+    Launched,
+    Configured,
+    Resume(Resume),
+    Disconnect,
+}
+
+struct Session<W: Write> {
+    messages: Receiver<Json>,
+    writer: W,
+    seq: i64,
+    entry: PathBuf,
+    lines: LineTable,
+    breakpoints: HashMap<PathBuf, BTreeSet<usize>>,
+    canonical_files: HashMap<String, PathBuf>,
+    stop_on_entry: bool,
+    entered: bool,
+    pause_requested: bool,
+    /// Frames captured at the current stop, innermost first.
+    frames: Vec<DebugFrame>,
+    /// Variable containers handed out at the current stop; reference `n` is `handles[n - 1]`.
+    handles: Vec<Vec<(String, Value)>>,
+}
+
+impl<W: Write> Session<W> {
+    fn send(&mut self, mut message: Json) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.writer, &message)
+    }
+
+    fn respond(&mut self, request: &Json, body: Json) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": true,
+            "command": request["command"],
+            "body": body,
+        }))
+    }
+
+    fn respond_error(&mut self, request: &Json, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": false,
+            "command": request["command"],
+            "message": message,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Json) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn canonical_file(&mut self, file: &str) -> PathBuf {
+        self.canonical_files
+            .entry(file.to_string())
+            .or_insert_with(|| canonical(file))
+            .clone()
+    }
+
+    fn handle(&mut self, request: &Json, phase: Phase) -> io::Result<Action> {
+        let command = request["command"].as_str().unwrap_or_default();
+        let args = &request["arguments"];
+        let resume = match command {
+            "continue" => Some(Resume::Continue),
+            "next" => Some(Resume::StepOver),
+            "stepIn" => Some(Resume::StepIn),
+            "stepOut" => Some(Resume::StepOut),
+            _ => None,
+        };
+        match command {
+            "initialize" => {
+                self.respond(
+                    request,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsEvaluateForHovers": true,
+                    }),
+                )?;
+                self.event("initialized", json!({}))?;
+            }
+            "launch" | "attach" => {
+                self.stop_on_entry = args["stopOnEntry"].as_bool().unwrap_or(false);
+                self.respond(request, json!({}))?;
+                return Ok(Action::Launched);
+            }
+            "configurationDone" => {
+                self.respond(request, json!({}))?;
+                return Ok(Action::Configured);
+            }
+            "setBreakpoints" => {
+                let body = self.set_breakpoints(args);
+                self.respond(request, body)?;
+            }
+            "setExceptionBreakpoints" => {
+                self.respond(request, json!({ "breakpoints": [] }))?;
+            }
+            "threads" => {
+                self.respond(
+                    request,
+                    json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+                )?;
+            }
+            "pause" => {
+                if phase == Phase::Running {
+                    self.pause_requested = true;
+                }
+                self.respond(request, json!({}))?;
+            }
+            "disconnect" | "terminate" => {
+                self.respond(request, json!({}))?;
+                return Ok(Action::Disconnect);
+            }
+            "stackTrace" | "scopes" | "variables" | "evaluate" if phase == Phase::Stopped => {
+                match self.inspect(command, args) {
+                    Ok(body) => self.respond(request, body)?,
+                    Err(message) => self.respond_error(request, &message)?,
+                }
+            }
+            _ if resume.is_some() && phase == Phase::Stopped => {
+                self.respond(request, json!({ "allThreadsContinued": true }))?;
+                return Ok(Action::Resume(resume.unwrap_or(Resume::Continue)));
+            }
+            "stackTrace" | "scopes" | "variables" | "evaluate" | "continue" | "next" | "stepIn"
+            | "stepOut" => {
+                self.respond_error(request, &format!("`{command}` needs a stopped program"))?;
+            }
+            _ => {
+                self.respond_error(request, &format!("`{command}` is not supported"))?;
+            }
+        }
+        Ok(Action::Reply)
+    }
+
+    fn set_breakpoints(&mut self, args: &Json) -> Json {
+        let path = canonical(args["source"]["path"].as_str().unwrap_or_default());
+        let requested: Vec<usize> = args["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                    .map(|line| line as usize)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut resolved = BTreeSet::new();
+        let breakpoints: Vec<Json> = requested
+            .iter()
+            .map(|&line| match self.lines.resolve(&path, line) {
+                Some(actual) => {
+                    resolved.insert(actual);
+                    json!({ "verified": true, "line": actual })
+                }
+                None => json!({
+                    "verified": false,
+                    "line": line,
+                    "message": "no code at or after this line",
+                }),
+            })
+            .collect();
+        self.breakpoints.insert(path, resolved);
+        json!({ "breakpoints": breakpoints })
+    }
+
+    fn inspect(&mut self, command: &str, args: &Json) -> Result<Json, String> {
+        match command {
+            "stackTrace" => {
+                let frames: Vec<Json> = self
+                    .frames
+                    .clone()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, frame)| self.stack_frame(index, frame))
+                    .collect();
+                Ok(json!({ "stackFrames": frames, "totalFrames": frames.len() }))
+            }
+            "scopes" => {
+                let frame = self.frame(args["frameId"].as_i64())?.clone();
+                let locals = self.handle_for(frame.locals);
+                let mut scopes = vec![json!({
+                    "name": "Locals",
+                    "presentationHint": "locals",
+                    "variablesReference": locals,
+                    "expensive": false,
+                })];
+                if !frame.captures.is_empty() {
+                    let captures = self.handle_for(frame.captures);
+                    scopes.push(json!({
+                        "name": "Captured",
+                        "variablesReference": captures,
+                        "expensive": false,
+                    }));
+                }
+                Ok(json!({ "scopes": scopes }))
+            }
+            "variables" => {
+                let reference = args["variablesReference"].as_u64().unwrap_or(0) as usize;
+                let entries = reference
+                    .checked_sub(1)
+                    .and_then(|index| self.handles.get(index))
+                    .cloned()
+                    .ok_or_else(|| format!("unknown variables reference {reference}"))?;
+                let variables: Vec<Json> = entries
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let mut variable = self.variable(&value);
+                        variable["name"] = json!(name);
+                        variable
+                    })
+                    .collect();
+                Ok(json!({ "variables": variables }))
+            }
+            "evaluate" => {
+                let expression = args["expression"].as_str().unwrap_or_default().trim();
+                let frame = self.frame(args["frameId"].as_i64().or(Some(1)))?;
+                let value = frame
+                    .locals
+                    .iter()
+                    .chain(&frame.captures)
+                    .find(|(name, _)| name == expression)
+                    .map(|(_, value)| value.clone())
+                    .ok_or_else(|| format!("`{expression}` is not a variable of this frame"))?;
+                let mut result = self.variable(&value);
+                result["result"] = result["value"].take();
+                Ok(result)
+            }
+            _ => Err(format!("`{command}` is not supported")),
+        }
+    }
+
+    fn frame(&self, id: Option<i64>) -> Result<&DebugFrame, String> {
+        id.and_then(|id| usize::try_from(id - 1).ok())
+            .and_then(|index| self.frames.get(index))
+            .ok_or_else(|| "unknown frame".to_string())
+    }
+
+    fn stack_frame(&mut self, index: usize, frame: &DebugFrame) -> Json {
+        let mut json = json!({ "id": index + 1, "name": frame.name, "line": 0, "column": 0 });
+        if let Some((file, span)) = &frame.location {
+            let path = self.canonical_file(file);
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.clone());
+            json["source"] = json!({ "name": name, "path": path.to_string_lossy() });
+            json["line"] = json!(span.start.line);
+            json["column"] = json!(span.start.column + 1);
+        }
+        json
+    }
+
+    /// Renders one value; structured values get a reference that expands to their components.
+    fn variable(&mut self, value: &Value) -> Json {
+        let children = value_children(value);
+        let reference = if children.is_empty() {
+            0
+        } else {
+            self.handle_for(children)
+        };
+        let type_name = match value {
+            Value::Adt(adt) => adt.constructor.to_string(),
+            other => other.type_name().to_string(),
+        };
+        json!({
+            "value": value.to_string(),
+            "type": type_name,
+            "variablesReference": reference,
+        })
+    }
+
+    fn handle_for(&mut self, entries: Vec<(String, Value)>) -> usize {
+        self.handles.push(entries);
+        self.handles.len()
+    }
+
+    /// Answers requests that arrived while the program was running.
+    fn poll(&mut self) {
+        loop {
+            match self.messages.try_recv() {
+                Ok(request) => match self.handle(&request, Phase::Running) {
+                    Ok(Action::Disconnect) => end_session(0),
+                    Ok(_) => {}
+                    Err(_) => end_session(1),
+                },
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => end_session(0),
+            }
+        }
+    }
+}
+
+/// The [`DebugHook`] handed to the VM; shares the session with [`serve`].
+struct Hook<W: Write>(Rc<RefCell<Session<W>>>);
+
+impl<W: Write> DebugHook for Hook<W> {
+    fn should_stop(&mut self, file: &str, line: usize) -> Option<StopReason> {
+        let mut session = self.0.borrow_mut();
+        session.poll();
+        let path = session.canonical_file(file);
+        if !session.entered && path == session.entry {
+            session.entered = true;
+            if session.stop_on_entry {
+                return Some(StopReason::Entry);
+            }
+        }
+        if std::mem::take(&mut session.pause_requested) {
+            return Some(StopReason::Pause);
+        }
+        session
+            .breakpoints
+            .get(&path)
+            .is_some_and(|lines| lines.contains(&line))
+            .then_some(StopReason::Breakpoint)
+    }
+
+    fn stopped(&mut self, vm: &VM, reason: StopReason) -> Resume {
+        let mut session = self.0.borrow_mut();
+        session.frames = vm.debug_frames();
+        session.handles.clear();
+        let mut body = json!({ "threadId": THREAD_ID, "allThreadsStopped": true });
+        body["reason"] = json!(match &reason {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+            StopReason::Pause => "pause",
+            StopReason::Error(_) => "exception",
+        });
+        if let StopReason::Error(message) = &reason {
+            let title = message
+                .lines()
+                .find(|line| line.starts_with("error"))
+                .unwrap_or("runtime error");
+            body["description"] = json!(title);
+            body["text"] = json!(message);
+        }
+        if session.event("stopped", body).is_err() {
+            end_session(1);
+        }
+
+        loop {
+            let Ok(request) = session.messages.recv() else {
+                end_session(0);
+            };
+            match session.handle(&request, Phase::Stopped) {
+                Ok(Action::Resume(resume)) => {
+                    session.frames.clear();
+                    session.handles.clear();
+                    return resume;
+                }
+                Ok(Action::Disconnect) => end_session(0),
+                Ok(_) => {}
+                Err(_) => end_session(1),
+            }
+        }
+    }
+
+    fn output(&mut self, text: &str) {
+        let mut session = self.0.borrow_mut();
+        if session
+            .event("output", json!({ "category": "stdout", "output": text }))
+            .is_err()
+        {
+            end_session(1);
+        }
+    }
+}
+
+/// Runs one debug session for `bytecode`, whose entry file is `entry`.
+///
+/// Requests are read from `messages` and responses and events written to `writer`. Returns the
+/// rendered runtime error the program ended with, if any, once the client disconnects or its
+/// message channel closes.
+pub fn serve<W: Write + 'static>(
+    bytecode: Bytecode,
+    entry: &Path,
+    messages: Receiver<Json>,
+    writer: W,
+) -> io::Result<Option<String>> {
+    let session = Rc::new(RefCell::new(Session {
+        messages,
+        writer,
+        seq: 0,
+        entry: canonical(&entry.to_string_lossy()),
+        lines: LineTable::from_bytecode(&bytecode),
+        breakpoints: HashMap::new(),
+        canonical_files: HashMap::new(),
+        stop_on_entry: false,
+        entered: false,
+        pause_requested: false,
+        frames: Vec::new(),
+        handles: Vec::new(),
+    }));
+
+    let (mut launched, mut configured) = (false, false);
+    while !(launched && configured) {
+        let mut state = session.borrow_mut();
+        let Ok(request) = state.messages.recv() else {
+            return Ok(None);
+        };
+        match state.handle(&request, Phase::Configuring)? {
+            Action::Launched => launched = true,
+            Action::Configured => configured = true,
+            Action::Disconnect => return Ok(None),
+            Action::Reply | Action::Resume(_) => {}
+        }
+    }
+
+    let mut vm = VM::new(bytecode);
+    vm.set_debugger(Box::new(Hook(Rc::clone(&session))));
+    let error = vm.run().err();
+    drop(vm);
+
+    let mut session = session.borrow_mut();
+    let exit_code = i64::from(error.is_some());
+    session.event("exited", json!({ "exitCode": exit_code }))?;
+    session.event("terminated", json!({}))?;
+    while let Ok(request) = session.messages.recv() {
+        if let Action::Disconnect = session.handle(&request, Phase::Terminated)? {
+            break;
+        }
+    }
+    Ok(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeSet, HashMap},
+        path::{Path, PathBuf},
+    };
+
+    use super::LineTable;
+
+    #[test]
+    fn breakpoints_move_to_the_next_line_with_code() {
+        let path = PathBuf::from("/src/main.flx");
+        let table = LineTable {
+            files: HashMap::from([(path.clone(), BTreeSet::from([2, 3, 7]))]),
+        };
+
+        assert_eq!(table.resolve(&path, 3), Some(3));
+        assert_eq!(table.resolve(&path, 4), Some(7));
+        assert_eq!(table.resolve(&path, 8), None);
+        assert_eq!(table.resolve(Path::new("/src/other.flx"), 1), None);
+    }
+}
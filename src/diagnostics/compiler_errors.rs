@@ -5,7 +5,7 @@ use super::quality::{
     TypeMismatchNotes, missing_construct_opener_diagnostic, missing_syntax_token_diagnostic,
     occurs_check_diagnostic, type_mismatch_diagnostic,
 };
-use super::types::{DiagnosticCategory, ErrorCode, ErrorType};
+use super::types::{Applicability, DiagnosticCategory, ErrorCode, ErrorType, InlineSuggestion};
 
 pub const DUPLICATE_NAME: ErrorCode = ErrorCode {
     code: "E001",
@@ -816,6 +816,16 @@ pub const RIGID_VAR_ESCAPE: ErrorCode = ErrorCode {
     ),
 };
 
+/// Proposal 0083: a typed hole (`?name` or `?`). Reported as a warning so the
+/// program still compiles; the hole panics if it is reached at runtime.
+pub const TYPED_HOLE: ErrorCode = ErrorCode {
+    code: "W204",
+    title: "TYPED HOLE",
+    error_type: ErrorType::Compiler,
+    message: "Hole `{}` needs a value of type `{}`.",
+    hint: None,
+};
+
 pub const STRICT_TYPES_ANY_INFERRED: ErrorCode = ErrorCode {
     code: "E430",
     title: "COULD NOT INFER CONCRETE TYPE",
@@ -1149,17 +1159,26 @@ pub fn unknown_keyword(span: Span, keyword: &str, suggestion: Option<(&str, &str
 }
 
 /// Create an alias-style unknown keyword diagnostic (E030).
+///
+/// `applicability` records whether swapping the keyword alone yields valid Flux, which
+/// decides whether `flux fix` rewrites it.
 pub fn unknown_keyword_alias(
     span: Span,
     found: &str,
     replacement: &str,
     context: &str,
+    applicability: Applicability,
 ) -> Diagnostic {
     unknown_keyword(span, found, None)
         .with_message(format!(
             "Unknown keyword `{found}`. Flux uses `{replacement}` for {context}."
         ))
         .with_hint_text(format!("Did you mean `{replacement}`?"))
+        .with_suggestion(
+            InlineSuggestion::new(span, replacement)
+                .with_message(format!("Replace `{found}` with `{replacement}`"))
+                .with_applicability(applicability),
+        )
 }
 
 /// Create an "unexpected token" error
@@ -29,8 +29,9 @@ pub use registry::{ERROR_CODES, diagnostic_for, lookup_error_code};
 pub use rendering::render_diagnostics_json;
 pub use rendering::{render_diagnostics, render_display_path};
 pub use types::{
-    DiagnosticCategory, DiagnosticPhase, ErrorCode, ErrorType, Hint, HintChain, HintKind,
-    InlineSuggestion, Label, LabelStyle, RelatedDiagnostic, RelatedKind, Severity, StackTraceFrame,
+    Applicability, DiagnosticCategory, DiagnosticPhase, ErrorCode, ErrorType, Hint, HintChain,
+    HintKind, InlineSuggestion, Label, LabelStyle, RelatedDiagnostic, RelatedKind, Severity,
+    StackTraceFrame,
 };
 
 pub use compiler_errors::*;
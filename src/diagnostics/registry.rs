@@ -102,6 +102,8 @@ pub const ERROR_CODES: &[ErrorCode] = &[
     INVALID_TYPE_ANNOTATION,
     INVALID_EFFECT_ROW,
     RIGID_VAR_ESCAPE,
+    // Typed holes (W204)
+    TYPED_HOLE,
     // Strict-types errors (E430+)
     STRICT_TYPES_ANY_INFERRED,
     CORE_LINT_FAILURE,
@@ -190,10 +192,9 @@ pub fn default_diagnostic_category(code: &str) -> Option<DiagnosticCategory> {
         "E076" => Some(DiagnosticCategory::ParserDelimiter),
         "E423" => Some(DiagnosticCategory::TypeInference),
         "E426" => Some(DiagnosticCategory::Internal),
-        "E056" | "E300" | "E301" | "E430" | "E440" | "E441" | "E442" | "E443" | "E444" | "E445"
-        | "E446" | "E447" | "E448" | "E449" | "E450" | "E451" | "E452" | "E455" | "E456" => {
-            Some(DiagnosticCategory::TypeInference)
-        }
+        "E056" | "E300" | "E301" | "E430" | "W204" | "E440" | "E441" | "E442" | "E443" | "E444"
+        | "E445" | "E446" | "E447" | "E448" | "E449" | "E450" | "E451" | "E452" | "E455"
+        | "E456" => Some(DiagnosticCategory::TypeInference),
         "E457" | "E458" => Some(DiagnosticCategory::ModuleSystem),
         "E460" | "E461" | "E462" | "E463" | "E464" | "E465" | "E466" | "E467" | "E468" => {
             Some(DiagnosticCategory::TypeInference)
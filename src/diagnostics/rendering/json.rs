@@ -55,6 +55,7 @@ struct JsonHint {
 struct JsonSuggestion {
     replacement: String,
     message: Option<String>,
+    applicability: &'static str,
     span: JsonSpan,
 }
 
@@ -188,6 +189,7 @@ impl JsonSuggestion {
         Self {
             replacement: suggestion.replacement.clone(),
             message: suggestion.message.clone(),
+            applicability: suggestion.applicability.as_str(),
             span: JsonSpan::from_span(suggestion.span),
         }
     }
@@ -22,4 +22,4 @@ pub use label::{Label, LabelStyle};
 pub use related::{RelatedDiagnostic, RelatedKind};
 pub use severity::Severity;
 pub use stack_trace::StackTraceFrame;
-pub use suggestion::InlineSuggestion;
+pub use suggestion::{Applicability, InlineSuggestion};
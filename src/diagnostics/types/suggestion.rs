@@ -2,12 +2,43 @@
 
 use crate::diagnostics::position::Span;
 
+/// How confident a suggestion is that applying it yields the intended program.
+///
+/// `flux fix` only applies [`Applicability::MachineApplicable`] suggestions; the others are
+/// shown to the user and left for them to decide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Applicability {
+    /// The replacement is definitely what the user meant and keeps the program valid.
+    MachineApplicable,
+    /// The replacement is plausible but may not be what the user meant, such as a
+    /// "did you mean" name.
+    MaybeIncorrect,
+    /// The replacement contains placeholders the user has to fill in.
+    HasPlaceholders,
+    /// Applicability has not been assessed.
+    #[default]
+    Unspecified,
+}
+
+impl Applicability {
+    /// Stable name used in JSON diagnostics output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Applicability::MachineApplicable => "machine_applicable",
+            Applicability::MaybeIncorrect => "maybe_incorrect",
+            Applicability::HasPlaceholders => "has_placeholders",
+            Applicability::Unspecified => "unspecified",
+        }
+    }
+}
+
 /// An inline suggestion that shows how to fix the code
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InlineSuggestion {
     pub replacement: String,
     pub span: Span,
     pub message: Option<String>,
+    pub applicability: Applicability,
 }
 
 impl InlineSuggestion {
@@ -17,6 +48,7 @@ impl InlineSuggestion {
             span,
             replacement: replacement.into(),
             message: None,
+            applicability: Applicability::Unspecified,
         }
     }
 
@@ -25,4 +57,15 @@ impl InlineSuggestion {
         self.message = Some(message.into());
         self
     }
+
+    /// Set how safe the suggestion is to apply automatically
+    pub fn with_applicability(mut self, applicability: Applicability) -> Self {
+        self.applicability = applicability;
+        self
+    }
+
+    /// Returns `true` when `flux fix` may apply the suggestion without asking
+    pub fn is_machine_applicable(&self) -> bool {
+        self.applicability == Applicability::MachineApplicable
+    }
 }
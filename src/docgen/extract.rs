@@ -0,0 +1,396 @@
+//! Collects doc comments and declaration headers from module sources.
+//!
+//! The parser discards doc comments, so they are recovered from the token stream: a run of
+//! `///` lines (or a `/** */` block) documents the declaration that starts on the line of the
+//! next token. Declaration headers are copied from the source text up to the body, so the
+//! generated pages show signatures exactly as the author wrote them.
+
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    diagnostics::{Diagnostic, position::Position},
+    docgen::model::{DocItem, DocMember, InstanceHead, ItemKind, ModuleDoc},
+    syntax::{
+        Identifier, block::Block, interner::Interner, lexer::Lexer, parser::Parser,
+        statement::Statement, token_type::TokenType, type_expr::TypeExpr,
+    },
+};
+
+/// Parses `source` and returns the documentation skeleton of the module it declares.
+///
+/// Returns `Ok(None)` for files without a `module` declaration (scripts). Types and effect
+/// rows are filled in later from the module interface.
+pub fn extract_module(path: &Path, source: &str) -> Result<Option<ModuleDoc>, Vec<Diagnostic>> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(std::mem::take(&mut parser.errors));
+    }
+    let interner = parser.take_interner();
+    let extractor = Extractor {
+        interner: &interner,
+        docs: doc_comments_by_line(source),
+        text: SourceText::new(source),
+    };
+
+    Ok(program.statements.iter().find_map(|stmt| match stmt {
+        Statement::Module { name, body, span } => Some(ModuleDoc {
+            name: interner.resolve(*name).to_string(),
+            path: path.to_path_buf(),
+            doc: extractor.doc_at(span.start),
+            items: extractor.items(body),
+        }),
+        _ => None,
+    }))
+}
+
+struct Extractor<'a> {
+    interner: &'a Interner,
+    docs: HashMap<usize, String>,
+    text: SourceText<'a>,
+}
+
+impl Extractor<'_> {
+    fn doc_at(&self, position: Position) -> String {
+        self.docs.get(&position.line).cloned().unwrap_or_default()
+    }
+
+    fn items(&self, body: &Block) -> Vec<DocItem> {
+        body.statements
+            .iter()
+            .filter_map(|stmt| self.item(stmt))
+            .collect()
+    }
+
+    fn item(&self, stmt: &Statement) -> Option<DocItem> {
+        let span = stmt.span();
+        let item = |kind, name: String, declaration: String| DocItem {
+            kind,
+            name,
+            doc: self.doc_at(span.start),
+            declaration,
+            scheme: None,
+            effects: Vec::new(),
+            open_effects: false,
+            instance_of: None,
+            members: Vec::new(),
+            deriving: Vec::new(),
+        };
+
+        match stmt {
+            Statement::Function {
+                is_public: true,
+                name,
+                intrinsic,
+                body,
+                ..
+            } => {
+                let name = self.interner.resolve(*name).to_string();
+                // `__primop_*` intrinsics back synthesized default handlers and are not part
+                // of the user-facing surface.
+                if name.starts_with("__") {
+                    return None;
+                }
+                let declaration = match intrinsic {
+                    Some(_) => strip_primop_binding(&self.text.slice(span.start, span.end)),
+                    None => self.text.slice(span.start, body.span.start),
+                };
+                Some(item(ItemKind::Function, name, declaration))
+            }
+            Statement::Data {
+                is_public: true,
+                name,
+                variants,
+                deriving,
+                ..
+            } => {
+                let mut data = item(
+                    ItemKind::Data,
+                    self.interner.resolve(*name).to_string(),
+                    self.text.header(span.start, span.end),
+                );
+                data.members = variants
+                    .iter()
+                    .map(|variant| self.member(variant.name, variant.span.start, variant.span.end))
+                    .collect();
+                data.deriving = deriving
+                    .iter()
+                    .map(|class| self.interner.resolve(*class).to_string())
+                    .collect();
+                Some(data)
+            }
+            Statement::Class { name, methods, .. } => {
+                let mut class = item(
+                    ItemKind::Class,
+                    self.interner.resolve(*name).to_string(),
+                    self.text.header(span.start, span.end),
+                );
+                class.members = methods
+                    .iter()
+                    .map(|method| {
+                        // Without a default body the method span runs into the next
+                        // token, so a required method ends at its signature.
+                        let end = match &method.default_body {
+                            Some(body) => body.span.start,
+                            None => method
+                                .effects
+                                .last()
+                                .map_or(method.return_type.span().end, |effect| effect.span().end),
+                        };
+                        self.member(method.name, method.span.start, end)
+                    })
+                    .collect();
+                Some(class)
+            }
+            Statement::Instance {
+                class_name,
+                type_args,
+                methods,
+                ..
+            } => {
+                let class_name = self.interner.resolve(*class_name).to_string();
+                let args: Vec<String> = type_args
+                    .iter()
+                    .map(|arg| arg.display_with(self.interner))
+                    .collect();
+                let mut instance = item(
+                    ItemKind::Instance,
+                    format!("{class_name}<{}>", args.join(", ")),
+                    self.text.header(span.start, span.end),
+                );
+                instance.instance_of = type_args.first().map(|head| InstanceHead {
+                    class_name,
+                    head_type: self.head_type(head),
+                });
+                instance.members = methods
+                    .iter()
+                    .map(|method| {
+                        self.member(method.name, method.span.start, method.body.span.start)
+                    })
+                    .collect();
+                Some(instance)
+            }
+            Statement::EffectDecl { name, ops, .. } => {
+                let mut effect = item(
+                    ItemKind::Effect,
+                    self.interner.resolve(*name).to_string(),
+                    self.text.header(span.start, span.end),
+                );
+                effect.members = ops
+                    .iter()
+                    .map(|op| self.member(op.name, op.span.start, op.span.end))
+                    .collect();
+                Some(effect)
+            }
+            _ => None,
+        }
+    }
+
+    fn member(&self, name: Identifier, start: Position, end: Position) -> DocMember {
+        DocMember {
+            name: self.interner.resolve(name).to_string(),
+            declaration: self.text.slice(start, end),
+            doc: self.doc_at(start),
+        }
+    }
+
+    /// The type constructor an instance is indexed under: `List` for `List<a>`.
+    fn head_type(&self, ty: &TypeExpr) -> String {
+        let rendered = ty.display_with(self.interner);
+        rendered
+            .split(['<', ' '])
+            .next()
+            .unwrap_or(&rendered)
+            .to_string()
+    }
+}
+
+/// Maps the line of each documented token to the doc comment text before it.
+fn doc_comments_by_line(source: &str) -> HashMap<usize, String> {
+    let mut docs = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+    for token in Lexer::new(source).tokenize() {
+        if token.token_type == TokenType::DocComment {
+            pending.push(clean_doc_text(token.literal.as_str()));
+        } else if !pending.is_empty() {
+            docs.insert(token.position.line, pending.join("\n").trim().to_string());
+            pending.clear();
+        }
+    }
+    docs
+}
+
+/// Strips the leading `*` gutter that `/** */` blocks conventionally use.
+fn clean_doc_text(text: &str) -> String {
+    if !text.contains('\n') {
+        return text.trim_end().to_string();
+    }
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            match trimmed.strip_prefix('*') {
+                Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                None => line,
+            }
+            .trim_end()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Drops the `= primop Name` binding from an intrinsic declaration.
+fn strip_primop_binding(declaration: &str) -> String {
+    match declaration.rfind("= primop") {
+        Some(index) => declaration[..index].trim_end().to_string(),
+        None => declaration.to_string(),
+    }
+}
+
+/// Source text addressed by parser positions (1-based lines, 0-based char columns).
+struct SourceText<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceText<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(offset, _)| offset + 1));
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    fn offset(&self, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line.saturating_sub(1)) else {
+            return self.source.len();
+        };
+        self.source[line_start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.source.len(), |(offset, _)| line_start + offset)
+    }
+
+    /// The text between two positions with whitespace runs collapsed to single spaces.
+    fn slice(&self, start: Position, end: Position) -> String {
+        let start = self.offset(start);
+        let end = self.offset(end).max(start);
+        normalize_whitespace(&self.source[start..end])
+    }
+
+    /// The declaration text before its `{ ... }` body, or all of it when it has none.
+    fn header(&self, start: Position, end: Position) -> String {
+        let text = self.slice(start, end);
+        match text.find('{') {
+            Some(index) => text[..index].trim_end().to_string(),
+            None => text,
+        }
+    }
+}
+
+/// Collapses whitespace runs, including the line breaks of a wrapped parameter list, so a
+/// header reads as it would on one line.
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(", )", ")")
+        .replace(" )", ")")
+        .trim_end_matches(',')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::extract_module;
+    use crate::docgen::model::ItemKind;
+
+    const SOURCE: &str = r#"/// Geometry helpers.
+module Geo.Shapes {
+    /// A plane figure.
+    public data Shape {
+        /// A circle with its radius.
+        Circle(Float),
+        Square(Float),
+    } deriving (Eq)
+
+    /// The area of `shape`.
+    ///
+    /// Squares and circles only.
+    public fn area(shape: Shape) -> Float {
+        match shape {
+            Circle(r) -> 3.14 * r * r,
+            Square(s) -> s * s,
+        }
+    }
+
+    public fn __internal() -> Int { 0 }
+
+    fn private_helper() -> Int { 1 }
+
+    class Describe<a> {
+        /// A one-line description.
+        fn describe(x: a) -> String
+    }
+
+    instance Describe<Shape> {
+        fn describe(x) { "shape" }
+    }
+}
+"#;
+
+    #[test]
+    fn extracts_public_declarations_with_their_doc_comments() {
+        let module = extract_module(Path::new("Shapes.flx"), SOURCE)
+            .expect("parses")
+            .expect("declares a module");
+        assert_eq!(module.name, "Geo.Shapes");
+        assert_eq!(module.doc, "Geometry helpers.");
+
+        let names: Vec<(ItemKind, &str)> = module
+            .items
+            .iter()
+            .map(|item| (item.kind, item.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (ItemKind::Data, "Shape"),
+                (ItemKind::Function, "area"),
+                (ItemKind::Class, "Describe"),
+                (ItemKind::Instance, "Describe<Shape>"),
+            ]
+        );
+
+        let shape = &module.items[0];
+        assert_eq!(shape.doc, "A plane figure.");
+        assert_eq!(shape.deriving, vec!["Eq".to_string()]);
+        assert_eq!(shape.members[0].name, "Circle");
+        assert_eq!(shape.members[0].doc, "A circle with its radius.");
+
+        let area = &module.items[1];
+        assert_eq!(
+            area.doc,
+            "The area of `shape`.\n\nSquares and circles only."
+        );
+        assert_eq!(area.declaration, "fn area(shape: Shape) -> Float");
+
+        let class = &module.items[2];
+        assert_eq!(class.members[0].declaration, "fn describe(x: a) -> String");
+        assert_eq!(class.members[0].doc, "A one-line description.");
+
+        let instance = module.items[3].instance_of.as_ref().expect("instance head");
+        assert_eq!(instance.class_name, "Describe");
+        assert_eq!(instance.head_type, "Shape");
+    }
+
+    #[test]
+    fn skips_files_without_a_module() {
+        let module = extract_module(Path::new("main.flx"), "fn main() { 1 }\n").expect("parses");
+        assert!(module.is_none());
+    }
+}
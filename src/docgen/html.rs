@@ -0,0 +1,295 @@
+//! Static HTML output: one page per module, an index page and a client-side search.
+//!
+//! Pages need no server: the search index is shipped both as `search-index.json` for tools
+//! and as `search-index.js`, which the search box loads with a plain `<script>` tag so it
+//! also works from `file://`.
+
+use crate::docgen::{
+    DocSet,
+    index::{DocIndex, search_entries, summary},
+    markup::{DocBlock, doc_blocks},
+    model::{DocItem, ItemKind, ModuleDoc},
+};
+
+const STYLE: &str = "\
+body { margin: 0; display: flex; font: 15px/1.5 system-ui, sans-serif; color: #1f2328; }
+nav { width: 16rem; padding: 1rem; border-right: 1px solid #d0d7de; min-height: 100vh; }
+nav ul { list-style: none; padding: 0; }
+main { flex: 1; max-width: 56rem; padding: 1rem 2rem; }
+pre, code { font-family: ui-monospace, monospace; font-size: 13px; }
+pre { background: #f6f8fa; padding: .6rem .8rem; overflow-x: auto; }
+section.item { border-top: 1px solid #d0d7de; margin-top: 1.5rem; }
+dl.facts dt { font-weight: 600; float: left; width: 5rem; }
+dl.facts dd { margin-left: 5rem; }
+#search { width: 100%; box-sizing: border-box; }
+#search-results li { margin: .3rem 0; }
+.summary { color: #57606a; }
+";
+
+const SEARCH_SCRIPT: &str = "\
+(function () {
+  var input = document.getElementById('search');
+  var list = document.getElementById('search-results');
+  if (!input || !list || !window.FLUX_SEARCH_INDEX) { return; }
+  input.addEventListener('input', function () {
+    var query = input.value.trim().toLowerCase();
+    list.innerHTML = '';
+    if (!query) { return; }
+    window.FLUX_SEARCH_INDEX
+      .filter(function (entry) { return entry.name.toLowerCase().indexOf(query) >= 0; })
+      .slice(0, 30)
+      .forEach(function (entry) {
+        var item = document.createElement('li');
+        var link = document.createElement('a');
+        link.href = entry.url;
+        link.textContent = entry.kind + ' ' + entry.module + '.' + entry.name;
+        item.appendChild(link);
+        list.appendChild(item);
+      });
+  });
+})();
+";
+
+/// Renders every page of the HTML output as `(file name, contents)` pairs.
+pub fn render(set: &DocSet) -> Vec<(String, String)> {
+    let renderer = HtmlRenderer {
+        set,
+        index: DocIndex::build(&set.modules),
+    };
+    let entries = search_entries(&set.modules, "html");
+    let json = serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string());
+
+    let mut files = vec![
+        ("index.html".to_string(), renderer.index_page()),
+        ("style.css".to_string(), STYLE.to_string()),
+        ("search.js".to_string(), SEARCH_SCRIPT.to_string()),
+        (
+            "search-index.js".to_string(),
+            format!("window.FLUX_SEARCH_INDEX = {json};\n"),
+        ),
+        ("search-index.json".to_string(), format!("{json}\n")),
+    ];
+    for module in &set.modules {
+        files.push((
+            format!("{}.html", module.name),
+            renderer.module_page(module),
+        ));
+    }
+    files
+}
+
+struct HtmlRenderer<'a> {
+    set: &'a DocSet,
+    index: DocIndex,
+}
+
+impl HtmlRenderer<'_> {
+    fn page(&self, title: &str, body: &str) -> String {
+        let mut modules = String::new();
+        for module in &self.set.modules {
+            modules.push_str(&format!(
+                "<li><a href=\"{0}.html\">{1}</a></li>\n",
+                escape(&module.name),
+                escape(&module.name)
+            ));
+        }
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n\
+             <body>\n<nav>\n<p><a href=\"index.html\">Index</a></p>\n\
+             <input id=\"search\" type=\"search\" placeholder=\"Search\" autocomplete=\"off\">\n\
+             <ul id=\"search-results\"></ul>\n<h3>Modules</h3>\n<ul>\n{modules}</ul>\n</nav>\n\
+             <main>\n{body}</main>\n<script src=\"search-index.js\"></script>\n\
+             <script src=\"search.js\"></script>\n</body>\n</html>\n",
+            title = escape(title),
+        )
+    }
+
+    fn index_page(&self) -> String {
+        let mut body = String::from("<h1>API documentation</h1>\n<h2>Modules</h2>\n<dl>\n");
+        for module in &self.set.modules {
+            body.push_str(&format!(
+                "<dt><a href=\"{0}.html\">{0}</a></dt>\n<dd class=\"summary\">{1}</dd>\n",
+                escape(&module.name),
+                inline(&summary(&module.doc))
+            ));
+        }
+        body.push_str("</dl>\n<h2 id=\"instances\">Instances by type</h2>\n");
+        if self.index.instances.is_empty() {
+            body.push_str("<p>No type-class instances are documented.</p>\n");
+        }
+        for (head_type, instances) in &self.index.instances {
+            body.push_str(&format!(
+                "<h3 id=\"instances.{0}\">{1}</h3>\n<ul>\n",
+                escape(head_type),
+                self.link_types(head_type)
+            ));
+            for instance in instances {
+                let label = match &instance.declaration {
+                    Some(declaration) => escape(declaration),
+                    None => format!("{} (derived)", escape(&instance.class_name)),
+                };
+                body.push_str(&format!(
+                    "<li><a href=\"{}.html#{}\">{label}</a> in {}</li>\n",
+                    escape(&instance.target.module),
+                    escape(&instance.target.anchor),
+                    escape(&instance.target.module)
+                ));
+            }
+            body.push_str("</ul>\n");
+        }
+        self.page("API documentation", &body)
+    }
+
+    fn module_page(&self, module: &ModuleDoc) -> String {
+        let mut body = format!("<h1>module {}</h1>\n", escape(&module.name));
+        body.push_str(&doc_html(&module.doc));
+        for kind in ItemKind::SECTIONS {
+            let items: Vec<&DocItem> = module
+                .items
+                .iter()
+                .filter(|item| item.kind == kind)
+                .collect();
+            if items.is_empty() {
+                continue;
+            }
+            body.push_str(&format!("<h2>{}</h2>\n", kind.section()));
+            for item in items {
+                body.push_str(&self.item(item));
+            }
+        }
+        self.page(&module.name, &body)
+    }
+
+    fn item(&self, item: &DocItem) -> String {
+        let anchor = escape(&item.anchor());
+        let mut out = format!(
+            "<section class=\"item\" id=\"{anchor}\">\n<h3><a href=\"#{anchor}\">{} {}</a></h3>\n",
+            item.kind.label(),
+            escape(&item.name)
+        );
+        out.push_str(&format!(
+            "<pre class=\"declaration\">{}</pre>\n",
+            self.link_types(&item.declaration)
+        ));
+
+        let mut facts = String::new();
+        if let Some(scheme) = &item.scheme {
+            facts.push_str(&format!(
+                "<dt>Type</dt><dd><code>{}</code></dd>\n",
+                self.link_types(scheme)
+            ));
+            facts.push_str(&format!(
+                "<dt>Effects</dt><dd>{}</dd>\n",
+                self.link_types(&item.effect_row())
+            ));
+        }
+        if !item.deriving.is_empty() {
+            facts.push_str(&format!(
+                "<dt>Derives</dt><dd>{}</dd>\n",
+                self.link_types(&item.deriving.join(", "))
+            ));
+        }
+        if item.kind == ItemKind::Data && self.index.instances.contains_key(&item.name) {
+            facts.push_str(&format!(
+                "<dt>Instances</dt><dd><a href=\"index.html#instances.{0}\">all instances for {0}</a></dd>\n",
+                escape(&item.name)
+            ));
+        }
+        if !facts.is_empty() {
+            out.push_str(&format!("<dl class=\"facts\">\n{facts}</dl>\n"));
+        }
+
+        out.push_str(&doc_html(&item.doc));
+        if !item.members.is_empty() {
+            out.push_str("<ul class=\"members\">\n");
+            for member in &item.members {
+                out.push_str(&format!(
+                    "<li id=\"{}\"><code>{}</code>{}</li>\n",
+                    escape(&item.member_anchor(member)),
+                    self.link_types(&member.declaration),
+                    doc_html(&member.doc)
+                ));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</section>\n");
+        out
+    }
+
+    /// Escapes `text` and links every documented type name in it to its declaration.
+    fn link_types(&self, text: &str) -> String {
+        let mut out = String::new();
+        let mut word = String::new();
+        for ch in text.chars().chain(std::iter::once('\0')) {
+            if ch.is_alphanumeric() || ch == '_' {
+                word.push(ch);
+                continue;
+            }
+            if !word.is_empty() {
+                match self.index.types.get(&word) {
+                    Some(target) => out.push_str(&format!(
+                        "<a href=\"{}.html#{}\">{}</a>",
+                        escape(&target.module),
+                        escape(&target.anchor),
+                        escape(&word)
+                    )),
+                    None => out.push_str(&escape(&word)),
+                }
+                word.clear();
+            }
+            if ch != '\0' {
+                out.push_str(&escape(&ch.to_string()));
+            }
+        }
+        out
+    }
+}
+
+fn doc_html(doc: &str) -> String {
+    let mut out = String::new();
+    for block in doc_blocks(doc) {
+        match block {
+            DocBlock::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", inline(&text))),
+            DocBlock::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>\n", inline(&item)));
+                }
+                out.push_str("</ul>\n");
+            }
+            DocBlock::Code(code) => {
+                out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&code)))
+            }
+        }
+    }
+    out
+}
+
+/// Escapes prose and turns `` `code` `` spans into `<code>` elements.
+fn inline(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(index, part)| {
+            if index % 2 == 1 {
+                format!("<code>{}</code>", escape(part))
+            } else {
+                escape(part)
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
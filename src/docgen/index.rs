@@ -0,0 +1,245 @@
+//! Cross-module indexes: type links, type-class instances per type and the search index.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::docgen::model::{ItemKind, ModuleDoc};
+
+/// Where a documented item lives: its module page and anchor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemRef {
+    pub module: String,
+    pub anchor: String,
+}
+
+/// One implemented class in the per-type instance index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceRef {
+    pub class_name: String,
+    /// `None` for instances derived by a `deriving` clause.
+    pub declaration: Option<String>,
+    pub target: ItemRef,
+}
+
+/// One entry of `search-index.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchEntry {
+    pub name: String,
+    pub kind: &'static str,
+    pub module: String,
+    /// Page-relative link, e.g. `Flow.List.html#fn.map`.
+    pub url: String,
+    /// Exported type, or the declaration when there is none.
+    pub signature: String,
+    /// First paragraph of the doc comment.
+    pub summary: String,
+}
+
+/// Indexes shared by every page of one documentation build.
+#[derive(Debug, Default)]
+pub struct DocIndex {
+    /// Types, classes and effects by name, for cross-links inside signatures.
+    pub types: HashMap<String, ItemRef>,
+    /// Instances keyed by the head type they are declared for.
+    pub instances: BTreeMap<String, Vec<InstanceRef>>,
+}
+
+impl DocIndex {
+    pub fn build(modules: &[ModuleDoc]) -> DocIndex {
+        let mut index = DocIndex::default();
+        for module in modules {
+            let item_ref = |anchor: String| ItemRef {
+                module: module.name.clone(),
+                anchor,
+            };
+            for item in &module.items {
+                if matches!(
+                    item.kind,
+                    ItemKind::Data | ItemKind::Class | ItemKind::Effect
+                ) {
+                    index
+                        .types
+                        .entry(item.name.clone())
+                        .or_insert_with(|| item_ref(item.anchor()));
+                }
+                if let Some(head) = &item.instance_of {
+                    index
+                        .instances
+                        .entry(head.head_type.clone())
+                        .or_default()
+                        .push(InstanceRef {
+                            class_name: head.class_name.clone(),
+                            declaration: Some(item.name.clone()),
+                            target: item_ref(item.anchor()),
+                        });
+                }
+                for class_name in &item.deriving {
+                    index
+                        .instances
+                        .entry(item.name.clone())
+                        .or_default()
+                        .push(InstanceRef {
+                            class_name: class_name.clone(),
+                            declaration: None,
+                            target: item_ref(item.anchor()),
+                        });
+                }
+            }
+        }
+        for instances in index.instances.values_mut() {
+            instances.sort_by(|left, right| {
+                (&left.class_name, &left.target.module)
+                    .cmp(&(&right.class_name, &right.target.module))
+            });
+        }
+        index
+    }
+
+    /// Names of documented types that appear as identifiers in `text`, in order of first
+    /// appearance.
+    pub fn referenced_types<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut names = Vec::new();
+        for word in identifiers(text) {
+            if self.types.contains_key(word) && !names.contains(&word) {
+                names.push(word);
+            }
+        }
+        names
+    }
+}
+
+/// Builds the search index; `extension` is the page extension (`html` or `md`).
+pub fn search_entries(modules: &[ModuleDoc], extension: &str) -> Vec<SearchEntry> {
+    let mut entries = Vec::new();
+    for module in modules {
+        entries.push(SearchEntry {
+            name: module.name.clone(),
+            kind: "module",
+            module: module.name.clone(),
+            url: format!("{}.{extension}", module.name),
+            signature: String::new(),
+            summary: summary(&module.doc),
+        });
+        for item in &module.items {
+            entries.push(SearchEntry {
+                name: item.name.clone(),
+                kind: item.kind.label(),
+                module: module.name.clone(),
+                url: format!("{}.{extension}#{}", module.name, item.anchor()),
+                signature: item
+                    .scheme
+                    .clone()
+                    .unwrap_or_else(|| item.declaration.clone()),
+                summary: summary(&item.doc),
+            });
+            for member in &item.members {
+                entries.push(SearchEntry {
+                    name: format!("{}.{}", item.name, member.name),
+                    kind: "member",
+                    module: module.name.clone(),
+                    url: format!("{}.{extension}#{}", module.name, item.member_anchor(member)),
+                    signature: member.declaration.clone(),
+                    summary: summary(&member.doc),
+                });
+            }
+        }
+    }
+    entries
+}
+
+/// The first paragraph of a doc comment, on one line.
+pub fn summary(doc: &str) -> String {
+    doc.split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The capitalized identifiers in `text`: the words that may name a type.
+pub fn identifiers(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+        .filter(|word| word.starts_with(|ch: char| ch.is_uppercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{DocIndex, search_entries, summary};
+    use crate::docgen::model::{DocItem, InstanceHead, ItemKind, ModuleDoc};
+
+    fn item(kind: ItemKind, name: &str) -> DocItem {
+        DocItem {
+            kind,
+            name: name.to_string(),
+            doc: String::new(),
+            declaration: String::new(),
+            scheme: None,
+            effects: Vec::new(),
+            open_effects: false,
+            instance_of: None,
+            members: Vec::new(),
+            deriving: Vec::new(),
+        }
+    }
+
+    fn module(name: &str, items: Vec<DocItem>) -> ModuleDoc {
+        ModuleDoc {
+            name: name.to_string(),
+            path: PathBuf::from(format!("{name}.flx")),
+            doc: String::new(),
+            items,
+        }
+    }
+
+    #[test]
+    fn indexes_declared_and_derived_instances_by_head_type() {
+        let mut shape = item(ItemKind::Data, "Shape");
+        shape.deriving = vec!["Eq".to_string()];
+        let mut show = item(ItemKind::Instance, "Show<Shape>");
+        show.instance_of = Some(InstanceHead {
+            class_name: "Show".to_string(),
+            head_type: "Shape".to_string(),
+        });
+        let modules = vec![
+            module("Geo.Shapes", vec![shape]),
+            module("Geo.Show", vec![show]),
+        ];
+
+        let index = DocIndex::build(&modules);
+        let shape_instances = &index.instances["Shape"];
+        let classes: Vec<&str> = shape_instances
+            .iter()
+            .map(|instance| instance.class_name.as_str())
+            .collect();
+        assert_eq!(classes, vec!["Eq", "Show"]);
+        assert_eq!(shape_instances[1].target.module, "Geo.Show");
+        assert_eq!(index.types["Shape"].anchor, "data.Shape");
+        assert_eq!(
+            index.referenced_types("(List<Shape>, Shape) -> Int"),
+            vec!["Shape"]
+        );
+    }
+
+    #[test]
+    fn search_entries_link_items_and_members() {
+        let mut option = item(ItemKind::Data, "Maybe");
+        option.doc = "An optional value.\n\nMore detail.".to_string();
+        option.members = vec![crate::docgen::model::DocMember {
+            name: "Just".to_string(),
+            declaration: "Just(a)".to_string(),
+            doc: String::new(),
+        }];
+        let entries = search_entries(&[module("Demo", vec![option])], "md");
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].url, "Demo.md#data.Maybe");
+        assert_eq!(entries[1].summary, "An optional value.");
+        assert_eq!(entries[2].name, "Maybe.Just");
+        assert_eq!(entries[2].url, "Demo.md#data.Maybe.Just");
+        assert_eq!(summary("one\ntwo\n\nthree"), "one two");
+    }
+}
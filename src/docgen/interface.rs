@@ -0,0 +1,181 @@
+//! Module interfaces for documented modules.
+//!
+//! Types shown in the documentation are the ones other modules see: the HM schemes and
+//! effect rows recorded in each module's [`ModuleInterface`]. Interfaces are rebuilt from
+//! source with the same per-module compilers the VM pipeline uses, so the output
+//! never depends on the state of the `.flxi` cache.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::type_infer::render_scheme_canonical,
+    bytecode::bytecode_cache::hash_bytes,
+    compiler::module_interface::{build_interface, compute_semantic_config_hash},
+    diagnostics::{Diagnostic, DiagnosticPhase},
+    docgen::model::{ItemKind, ModuleDoc},
+    driver::{
+        frontend::{collect_roots, extract_module_name_and_sym, inject_flow_prelude},
+        module_compile::{
+            build_module_compiler, effective_module_strictness, tag_module_diagnostics,
+        },
+        shared::{sort_stdlib_first, tag_and_attach_file},
+    },
+    syntax::{
+        interner::Interner,
+        lexer::Lexer,
+        module_graph::{ModuleGraph, ModuleNode},
+        parser::Parser,
+    },
+    types::{infer_type::InferType, module_interface::ModuleInterface},
+};
+
+/// Module search configuration for interface builds.
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceOptions {
+    pub roots: Vec<PathBuf>,
+    pub roots_only: bool,
+    pub strict_mode: bool,
+}
+
+/// Interfaces built so far, keyed by canonical source path.
+#[derive(Debug, Default)]
+pub struct InterfaceSet {
+    interfaces: HashMap<PathBuf, ModuleInterface>,
+}
+
+impl InterfaceSet {
+    pub fn get(&self, path: &Path) -> Option<&ModuleInterface> {
+        self.interfaces.get(&canonical(path))
+    }
+
+    /// Compiles `path`, whose contents are `source`, together with every module it imports
+    /// and records the interface of each module that compiled. Returns the diagnostics of
+    /// modules that did not.
+    pub fn build(
+        &mut self,
+        path: &Path,
+        source: &str,
+        options: &InterfaceOptions,
+    ) -> Vec<Diagnostic> {
+        let path_text = path.to_string_lossy().to_string();
+        let mut parser = Parser::new(Lexer::new(source));
+        let mut program = parser.parse_program();
+        let mut diagnostics = std::mem::take(&mut parser.errors);
+        if !diagnostics.is_empty() {
+            tag_and_attach_file(&mut diagnostics, DiagnosticPhase::Parse, &path_text);
+            return diagnostics;
+        }
+        // Flow modules import what they use; the prelude would make them import themselves.
+        let is_flow_module = extract_module_name_and_sym(&program, parser.interner())
+            .is_some_and(|(name, _)| name.starts_with("Flow."));
+        if !is_flow_module {
+            inject_flow_prelude(&mut program, &mut parser, false);
+        }
+        let interner = parser.take_interner();
+
+        let roots = collect_roots(path, &options.roots, options.roots_only);
+        let graph_result =
+            ModuleGraph::build_with_entry_and_roots(path, &program, interner, &roots);
+        diagnostics.extend(graph_result.diagnostics);
+        tag_and_attach_file(&mut diagnostics, DiagnosticPhase::ModuleGraph, &path_text);
+
+        let failed = graph_result.failed_modules;
+        let graph = graph_result.graph;
+        let graph_interner = graph_result.interner;
+        let entry_module_kind = graph.entry_node().map(|node| node.kind).unwrap_or_default();
+        let nodes_by_path: HashMap<PathBuf, ModuleNode> = graph
+            .topo_order()
+            .into_iter()
+            .map(|node| (node.path.clone(), node.clone()))
+            .collect();
+        let mut loaded: HashMap<PathBuf, ModuleInterface> = HashMap::new();
+
+        let mut ordered_nodes = graph.topo_order();
+        sort_stdlib_first(&mut ordered_nodes, |node| node.kind);
+        for node in ordered_nodes {
+            if node.imports.iter().any(|e| failed.contains(&e.target_path)) {
+                continue;
+            }
+            let key = canonical(&node.path);
+            if let Some(interface) = self.interfaces.get(&key) {
+                loaded.insert(node.path.clone(), interface.clone());
+                continue;
+            }
+
+            let mut compiler = build_module_compiler(
+                node,
+                &nodes_by_path,
+                &loaded,
+                &graph_interner,
+                entry_module_kind,
+                options.strict_mode,
+                false,
+            );
+            if let Err(mut diags) = compiler.compile_with_opts(&node.program, false, false) {
+                tag_module_diagnostics(&mut diags, DiagnosticPhase::TypeCheck, &node.path);
+                diagnostics.append(&mut diags);
+                continue;
+            }
+            let Some((module_name, module_sym)) =
+                extract_module_name_and_sym(&node.program, &compiler.interner)
+            else {
+                continue;
+            };
+            let Ok(core) = compiler.lower_aether_report_program(&node.program, false) else {
+                continue;
+            };
+            let strict_mode =
+                effective_module_strictness(node.kind, entry_module_kind, options.strict_mode);
+            let module_source = std::fs::read_to_string(&node.path).unwrap_or_default();
+            let interface = build_interface(
+                &module_name,
+                module_sym,
+                &hash_bytes(module_source.as_bytes()),
+                &compute_semantic_config_hash(strict_mode, false),
+                core.as_core(),
+                compiler.cached_member_schemes(),
+                &compiler.exported_runtime_contracts(),
+                &compiler.module_function_visibility,
+                Some(compiler.class_env()),
+                Vec::new(),
+                &compiler.interner,
+            );
+            loaded.insert(node.path.clone(), interface.clone());
+            self.interfaces.insert(key, interface);
+        }
+        diagnostics
+    }
+}
+
+/// Fills in the exported type scheme and effect row of every documented function.
+pub fn apply_interface(module: &mut ModuleDoc, interface: &ModuleInterface) {
+    let mut interner = Interner::new();
+    let remap = interface.build_symbol_remap(&mut interner);
+    for item in &mut module.items {
+        if item.kind != ItemKind::Function {
+            continue;
+        }
+        let Some(scheme) = interface.schemes.get(&item.name) else {
+            continue;
+        };
+        let scheme = scheme.remap_symbols(&remap);
+        item.scheme = Some(render_scheme_canonical(&interner, &scheme));
+        if let InferType::Fun(_, _, effects) = &scheme.infer_type {
+            let mut names: Vec<String> = effects
+                .concrete()
+                .iter()
+                .map(|effect| interner.resolve(*effect).to_string())
+                .collect();
+            names.sort();
+            item.effects = names;
+            item.open_effects = effects.tail().is_some();
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
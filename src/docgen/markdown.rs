@@ -0,0 +1,173 @@
+//! Markdown output: one file per module plus an `index.md`, suitable for committing next to
+//! the sources or publishing through any Markdown site generator.
+//!
+//! Links cannot live inside code spans, so signatures stay plain code and the documented
+//! types they mention are listed as links underneath.
+
+use crate::docgen::{
+    DocSet,
+    index::{DocIndex, search_entries, summary},
+    markup::{DocBlock, doc_blocks},
+    model::{DocItem, ItemKind, ModuleDoc},
+};
+
+/// Renders every file of the Markdown output as `(file name, contents)` pairs.
+pub fn render(set: &DocSet) -> Vec<(String, String)> {
+    let renderer = MarkdownRenderer {
+        set,
+        index: DocIndex::build(&set.modules),
+    };
+    let entries = search_entries(&set.modules, "md");
+    let json = serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".to_string());
+
+    let mut files = vec![
+        ("index.md".to_string(), renderer.index_page()),
+        ("search-index.json".to_string(), format!("{json}\n")),
+    ];
+    for module in &set.modules {
+        files.push((format!("{}.md", module.name), renderer.module_page(module)));
+    }
+    files
+}
+
+struct MarkdownRenderer<'a> {
+    set: &'a DocSet,
+    index: DocIndex,
+}
+
+impl MarkdownRenderer<'_> {
+    fn index_page(&self) -> String {
+        let mut out = String::from("# API documentation\n\n## Modules\n\n");
+        for module in &self.set.modules {
+            let summary = summary(&module.doc);
+            out.push_str(&format!("- [{0}]({0}.md)", module.name));
+            if !summary.is_empty() {
+                out.push_str(&format!(" — {summary}"));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("\n## Instances by type\n\n");
+        if self.index.instances.is_empty() {
+            out.push_str("No type-class instances are documented.\n");
+        }
+        for (head_type, instances) in &self.index.instances {
+            out.push_str(&format!(
+                "<a id=\"instances.{head_type}\"></a>\n### {head_type}\n\n"
+            ));
+            for instance in instances {
+                let label = match &instance.declaration {
+                    Some(declaration) => format!("`{declaration}`"),
+                    None => format!("`{}` (derived)", instance.class_name),
+                };
+                out.push_str(&format!(
+                    "- [{label}]({}.md#{}) in {}\n",
+                    instance.target.module, instance.target.anchor, instance.target.module
+                ));
+            }
+            out.push('\n');
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+
+    fn module_page(&self, module: &ModuleDoc) -> String {
+        let mut out = format!("# module {}\n\n[Index](index.md)\n\n", module.name);
+        push_doc(&mut out, &module.doc);
+        for kind in ItemKind::SECTIONS {
+            let items: Vec<&DocItem> = module
+                .items
+                .iter()
+                .filter(|item| item.kind == kind)
+                .collect();
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("## {}\n\n", kind.section()));
+            for item in items {
+                self.push_item(&mut out, item);
+            }
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+
+    fn push_item(&self, out: &mut String, item: &DocItem) {
+        out.push_str(&format!(
+            "<a id=\"{}\"></a>\n### {} {}\n\n```flux\n{}\n```\n\n",
+            item.anchor(),
+            item.kind.label(),
+            item.name,
+            item.declaration
+        ));
+        if let Some(scheme) = &item.scheme {
+            out.push_str(&format!("- Type: `{scheme}`\n"));
+            out.push_str(&format!("- Effects: {}\n", item.effect_row()));
+        }
+        if !item.deriving.is_empty() {
+            out.push_str(&format!("- Derives: {}\n", item.deriving.join(", ")));
+        }
+        let mut mentioned = String::new();
+        mentioned.push_str(&item.declaration);
+        if let Some(scheme) = &item.scheme {
+            mentioned.push(' ');
+            mentioned.push_str(scheme);
+        }
+        let links: Vec<String> = self
+            .index
+            .referenced_types(&mentioned)
+            .into_iter()
+            .filter(|name| *name != item.name)
+            .map(|name| {
+                let target = &self.index.types[name];
+                format!("[{name}]({}.md#{})", target.module, target.anchor)
+            })
+            .collect();
+        if !links.is_empty() {
+            out.push_str(&format!("- See: {}\n", links.join(", ")));
+        }
+        if item.kind == ItemKind::Data && self.index.instances.contains_key(&item.name) {
+            out.push_str(&format!(
+                "- Instances: [all instances for {0}](index.md#instances.{0})\n",
+                item.name
+            ));
+        }
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+
+        push_doc(out, &item.doc);
+        for member in &item.members {
+            out.push_str(&format!(
+                "- <a id=\"{}\"></a>`{}`",
+                item.member_anchor(member),
+                member.declaration
+            ));
+            let summary = summary(&member.doc);
+            if !summary.is_empty() {
+                out.push_str(&format!(" — {summary}"));
+            }
+            out.push('\n');
+        }
+        if !item.members.is_empty() {
+            out.push('\n');
+        }
+    }
+}
+
+fn push_doc(out: &mut String, doc: &str) {
+    for block in doc_blocks(doc) {
+        match block {
+            DocBlock::Paragraph(text) => out.push_str(&format!("{text}\n\n")),
+            DocBlock::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {item}\n"));
+                }
+                out.push('\n');
+            }
+            DocBlock::Code(code) => out.push_str(&format!("```flux\n{code}\n```\n\n")),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Block structure of doc comment text.
+//!
+//! Doc comments are Markdown-flavoured prose. Both renderers need the same small subset:
+//! paragraphs, bullet lists, fenced code blocks and indented example lines, which Flow's
+//! own doc comments use for `div(7, 2) == 3`-style examples.
+
+/// One block of a doc comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocBlock {
+    /// Prose lines joined with spaces.
+    Paragraph(String),
+    /// `- item` / `* item` lines.
+    List(Vec<String>),
+    /// A fenced block or a run of indented lines, verbatim with the indentation removed.
+    Code(String),
+}
+
+/// Splits doc comment text into blocks.
+pub fn doc_blocks(text: &str) -> Vec<DocBlock> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            let mut code = Vec::new();
+            for line in lines.by_ref() {
+                if line.trim().starts_with("```") {
+                    break;
+                }
+                code.push(line);
+            }
+            blocks.push(DocBlock::Code(dedent(&code)));
+        } else if let Some(item) = list_item(line) {
+            let mut items = vec![item.to_string()];
+            while let Some(next) = lines.peek() {
+                match list_item(next) {
+                    Some(item) => items.push(item.to_string()),
+                    None if is_indented(next) => {
+                        if let Some(last) = items.last_mut() {
+                            last.push(' ');
+                            last.push_str(next.trim());
+                        }
+                    }
+                    None => break,
+                }
+                lines.next();
+            }
+            blocks.push(DocBlock::List(items));
+        } else if is_indented(line) {
+            let mut code = vec![line];
+            while let Some(next) = lines.peek() {
+                if !is_indented(next) {
+                    break;
+                }
+                code.push(next);
+                lines.next();
+            }
+            blocks.push(DocBlock::Code(dedent(&code)));
+        } else {
+            let mut paragraph = vec![trimmed];
+            while let Some(next) = lines.peek() {
+                if next.trim().is_empty()
+                    || is_indented(next)
+                    || list_item(next).is_some()
+                    || next.trim().starts_with("```")
+                {
+                    break;
+                }
+                paragraph.push(next.trim());
+                lines.next();
+            }
+            blocks.push(DocBlock::Paragraph(paragraph.join(" ")));
+        }
+    }
+    blocks
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 1 {
+        return None;
+    }
+    trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+}
+
+fn is_indented(line: &str) -> bool {
+    line.starts_with("  ") && !line.trim().is_empty()
+}
+
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DocBlock, doc_blocks};
+
+    #[test]
+    fn splits_paragraphs_lists_and_examples() {
+        let text = "Floor division.\nRounds down.\n\nExamples:\n  div(7, 2) == 3\n  div(-7, 2) == -4\n\n- first\n- second\n\n```flux\nlet x = 1\n```";
+        assert_eq!(
+            doc_blocks(text),
+            vec![
+                DocBlock::Paragraph("Floor division. Rounds down.".to_string()),
+                DocBlock::Paragraph("Examples:".to_string()),
+                DocBlock::Code("div(7, 2) == 3\ndiv(-7, 2) == -4".to_string()),
+                DocBlock::List(vec!["first".to_string(), "second".to_string()]),
+                DocBlock::Code("let x = 1".to_string()),
+            ]
+        );
+    }
+}
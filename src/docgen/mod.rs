@@ -0,0 +1,133 @@
+//! API documentation generator for `flux doc`.
+//!
+//! Documentation is assembled from two sources:
+//!
+//! - `///` and `/** */` doc comments attached to `public fn`, `public data`, `class`,
+//!   `instance` and `effect` declarations inside a `module`, with their headers as written
+//!   ([`extract`]);
+//! - the exported HM type schemes and effect rows of each module's
+//!   [`ModuleInterface`](crate::types::module_interface::ModuleInterface), rebuilt from source
+//!   ([`interface`]).
+//!
+//! The result renders to static HTML or Markdown. Both outputs link documented types across
+//! modules, list type-class instances per type on the index page and ship a
+//! `search-index.json` with one entry per module, item and member.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo run -- doc lib/Flow
+//! cargo run -- doc src --markdown -o docs/api
+//! ```
+
+pub mod extract;
+pub mod html;
+pub mod index;
+pub mod interface;
+pub mod markdown;
+pub mod markup;
+pub mod model;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticBuilder, DiagnosticPhase},
+    driver::shared::tag_and_attach_file,
+};
+
+pub use interface::InterfaceOptions;
+pub use model::{DocItem, DocMember, ItemKind, ModuleDoc};
+
+/// Output format of a documentation build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocFormat {
+    #[default]
+    Html,
+    Markdown,
+}
+
+/// Documentation for a set of modules, sorted by module name.
+#[derive(Debug, Clone, Default)]
+pub struct DocSet {
+    pub modules: Vec<ModuleDoc>,
+}
+
+/// Result of collecting documentation: the modules found plus the diagnostics of files that
+/// could not be parsed or compiled. Modules that fail to compile are still documented,
+/// without exported types.
+#[derive(Debug, Default)]
+pub struct DocReport {
+    pub set: DocSet,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Collects documentation for every module declared in `files`.
+///
+/// Files without a `module` declaration are skipped.
+pub fn collect(files: &[PathBuf], options: &InterfaceOptions) -> DocReport {
+    let mut report = DocReport::default();
+    let mut interfaces = interface::InterfaceSet::default();
+    for file in files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(err) => {
+                report.diagnostics.push(
+                    Diagnostic::warning("Unreadable Module")
+                        .with_message(format!("could not read {}: {err}", file.display())),
+                );
+                continue;
+            }
+        };
+        let mut module = match extract::extract_module(file, &source) {
+            Ok(Some(module)) => module,
+            Ok(None) => continue,
+            Err(mut diagnostics) => {
+                tag_and_attach_file(
+                    &mut diagnostics,
+                    DiagnosticPhase::Parse,
+                    &file.to_string_lossy(),
+                );
+                report.diagnostics.append(&mut diagnostics);
+                continue;
+            }
+        };
+
+        let needs_types = module
+            .items
+            .iter()
+            .any(|item| item.kind == ItemKind::Function);
+        if needs_types && interfaces.get(file).is_none() {
+            report
+                .diagnostics
+                .extend(interfaces.build(file, &source, options));
+        }
+        if let Some(interface) = interfaces.get(file) {
+            interface::apply_interface(&mut module, interface);
+        }
+        report.set.modules.push(module);
+    }
+    report
+        .set
+        .modules
+        .sort_by(|left, right| left.name.cmp(&right.name));
+    report
+}
+
+/// Renders `set` in `format` and writes it below `out_dir`, returning the written paths.
+pub fn write(set: &DocSet, format: DocFormat, out_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let files = match format {
+        DocFormat::Html => html::render(set),
+        DocFormat::Markdown => markdown::render(set),
+    };
+    fs::create_dir_all(out_dir)?;
+    let mut written = Vec::with_capacity(files.len());
+    for (name, contents) in files {
+        let path = out_dir.join(name);
+        fs::write(&path, contents)?;
+        written.push(path);
+    }
+    Ok(written)
+}
@@ -0,0 +1,144 @@
+//! Documentation model shared by the extractor and the renderers.
+
+use std::path::PathBuf;
+
+/// Documentation for one source module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleDoc {
+    /// Declared module name, e.g. `Flow.List`.
+    pub name: String,
+    pub path: PathBuf,
+    /// Doc comment attached to the `module` declaration itself.
+    pub doc: String,
+    /// Documented declarations in source order.
+    pub items: Vec<DocItem>,
+}
+
+/// Kind of a documented declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Function,
+    Data,
+    Class,
+    Instance,
+    Effect,
+}
+
+impl ItemKind {
+    /// Order of the sections on a module page.
+    pub const SECTIONS: [ItemKind; 5] = [
+        ItemKind::Data,
+        ItemKind::Class,
+        ItemKind::Effect,
+        ItemKind::Function,
+        ItemKind::Instance,
+    ];
+
+    /// Keyword shown in headings and the search index.
+    pub fn label(self) -> &'static str {
+        match self {
+            ItemKind::Function => "fn",
+            ItemKind::Data => "data",
+            ItemKind::Class => "class",
+            ItemKind::Instance => "instance",
+            ItemKind::Effect => "effect",
+        }
+    }
+
+    /// Section heading used on module pages.
+    pub fn section(self) -> &'static str {
+        match self {
+            ItemKind::Function => "Functions",
+            ItemKind::Data => "Types",
+            ItemKind::Class => "Classes",
+            ItemKind::Instance => "Instances",
+            ItemKind::Effect => "Effects",
+        }
+    }
+}
+
+/// One documented declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocItem {
+    pub kind: ItemKind,
+    /// Declared name; for instances the rendered head, e.g. `Eq<List<a>>`.
+    pub name: String,
+    /// Doc comment text (Markdown), empty when undocumented.
+    pub doc: String,
+    /// Declaration header as written in the source, whitespace-normalized.
+    pub declaration: String,
+    /// Exported HM type scheme from the module interface.
+    pub scheme: Option<String>,
+    /// Concrete effects of the exported scheme's outermost function type, sorted.
+    pub effects: Vec<String>,
+    /// The function is polymorphic in further effects (its effect row has a tail).
+    pub open_effects: bool,
+    /// Class name and head type of an `instance` declaration.
+    pub instance_of: Option<InstanceHead>,
+    /// Variants, methods or effect operations.
+    pub members: Vec<DocMember>,
+    /// Classes named in a `deriving` clause.
+    pub deriving: Vec<String>,
+}
+
+impl DocItem {
+    /// Stable HTML/Markdown anchor for the item.
+    pub fn anchor(&self) -> String {
+        anchor(self.kind.label(), &self.name)
+    }
+
+    /// The effect row of the exported type, e.g. `Console, FileSystem`, or `pure`.
+    pub fn effect_row(&self) -> String {
+        let mut row = self.effects.join(", ");
+        if self.open_effects {
+            row.push_str(if row.is_empty() {
+                "polymorphic"
+            } else {
+                " | ..."
+            });
+        }
+        if row.is_empty() {
+            row.push_str("pure");
+        }
+        row
+    }
+
+    /// Anchor of one of the item's members, e.g. `data.Option.Some`.
+    pub fn member_anchor(&self, member: &DocMember) -> String {
+        format!("{}.{}", self.anchor(), sanitize(&member.name))
+    }
+}
+
+/// The class and head type of an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceHead {
+    pub class_name: String,
+    /// Type constructor the instance is indexed under, e.g. `List` for `Eq<List<a>>`.
+    pub head_type: String,
+}
+
+/// A variant, method or effect operation inside a declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocMember {
+    pub name: String,
+    pub declaration: String,
+    pub doc: String,
+}
+
+/// Builds an anchor such as `fn.map` from a kind label and a name, replacing runs of
+/// characters that are awkward in URLs with a single `-`.
+pub fn anchor(label: &str, name: &str) -> String {
+    format!("{label}.{}", sanitize(name))
+}
+
+fn sanitize(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for ch in name.chars() {
+        if ch.is_alphanumeric() || ch == '_' || ch == '.' {
+            out.push(ch);
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
@@ -1,6 +1,6 @@
 use crate::driver::{
-    AetherDumpMode, CoreDumpMode, backend::Backend, flags::DriverFlags,
-    reporting::report::TraceBackend,
+    AetherDumpMode, CoreDumpMode, ProfileFormat, TestReportFormat, backend::Backend,
+    flags::DriverFlags, reporting::report::TraceBackend,
 };
 
 pub fn has_dump_requests(flags: &DriverFlags) -> bool {
@@ -9,6 +9,7 @@ pub fn has_dump_requests(flags: &DriverFlags) -> bool {
         || flags.dumps.dump_cfg
         || flags.dumps.dump_lir
         || flags.dumps.dump_lir_llvm
+        || flags.dumps.core_eval
         || flags.runtime.trace_aether
         || flags.dumps.dump_repr
 }
@@ -54,6 +55,78 @@ pub fn validate_flags(flags: &DriverFlags, is_test_mode: bool) -> Result<(), &'s
     }
 
     validate_dump_flags(flags)?;
+    validate_test_flags(flags, is_test_mode)?;
+    validate_coverage_flags(flags)?;
+    validate_profile_flags(flags)?;
+    validate_call_trace_flags(flags)?;
+    validate_effect_log_flags(flags, is_test_mode)?;
+    Ok(())
+}
+
+/// Coverage is counted by the bytecode VM, and its options only apply with `--coverage`.
+pub fn validate_coverage_flags(flags: &DriverFlags) -> Result<(), &'static str> {
+    if !flags.coverage.enabled && (flags.coverage.output.is_some() || flags.coverage.exclude_stdlib)
+    {
+        return Err("Error: --coverage-output and --coverage-exclude-stdlib require --coverage.");
+    }
+    if flags.coverage.enabled && flags.backend.selected != Backend::Vm {
+        return Err("Error: --coverage requires the VM backend (drop --native).");
+    }
+    Ok(())
+}
+
+/// The `--prof-*` report options only apply with `--prof`.
+pub fn validate_profile_flags(flags: &DriverFlags) -> Result<(), &'static str> {
+    if !flags.runtime.profiling
+        && (flags.profile.format != ProfileFormat::Flat || flags.profile.output.is_some())
+    {
+        return Err("Error: --prof-format and --prof-output require --prof.");
+    }
+    Ok(())
+}
+
+/// `--trace-output` only applies with `--trace-calls`.
+pub fn validate_call_trace_flags(flags: &DriverFlags) -> Result<(), &'static str> {
+    if !flags.call_trace.enabled() && flags.call_trace.output.is_some() {
+        return Err("Error: --trace-output requires --trace-calls.");
+    }
+    Ok(())
+}
+
+/// A run either records its builtin effects or replays them, and test runs do neither.
+pub fn validate_effect_log_flags(
+    flags: &DriverFlags,
+    is_test_mode: bool,
+) -> Result<(), &'static str> {
+    let effect_log = &flags.effect_log;
+    if effect_log.record.is_some() && effect_log.replay.is_some() {
+        return Err("Error: --record and --replay cannot be used together.");
+    }
+    if is_test_mode && (effect_log.record.is_some() || effect_log.replay.is_some()) {
+        return Err("Error: --record and --replay only support normal program execution.");
+    }
+    Ok(())
+}
+
+/// The `--test-*` options only make sense for `--test`, and a report file needs a
+/// machine-readable format.
+pub fn validate_test_flags(flags: &DriverFlags, is_test_mode: bool) -> Result<(), &'static str> {
+    if !is_test_mode && (flags.test.format != TestReportFormat::Text || flags.test.output.is_some())
+    {
+        return Err("Error: --test-format and --test-output require --test.");
+    }
+    if !is_test_mode && (flags.test.jobs != 1 || flags.test.timeout.is_some()) {
+        return Err("Error: --test-jobs and --test-timeout require --test.");
+    }
+    if !is_test_mode && flags.test.update_snapshots {
+        return Err("Error: --update-snapshots requires --test.");
+    }
+    if !is_test_mode && flags.test.doc_tests {
+        return Err("Error: --doc-tests requires --test.");
+    }
+    if flags.test.output.is_some() && flags.test.format == TestReportFormat::Text {
+        return Err("Error: --test-output requires --test-format=junit, tap or json.");
+    }
     Ok(())
 }
 
@@ -166,6 +239,86 @@ mod tests {
         assert!(validate_flags(&flags, true).is_err());
     }
 
+    #[test]
+    fn validate_flags_requires_test_mode_and_format_for_reports() {
+        let mut flags = base_flags();
+        flags.test.format = TestReportFormat::Junit;
+        assert!(validate_flags(&flags, false).is_err());
+        assert!(validate_flags(&flags, true).is_ok());
+
+        flags.test.format = TestReportFormat::Text;
+        flags.test.output = Some("report.xml".into());
+        assert!(validate_flags(&flags, true).is_err());
+    }
+
+    #[test]
+    fn validate_flags_requires_test_mode_for_jobs_and_timeout() {
+        let mut flags = base_flags();
+        flags.test.jobs = 4;
+        assert!(validate_flags(&flags, false).is_err());
+        assert!(validate_flags(&flags, true).is_ok());
+
+        flags.test.jobs = 1;
+        flags.test.timeout = Some(std::time::Duration::from_millis(100));
+        assert!(validate_flags(&flags, false).is_err());
+        assert!(validate_flags(&flags, true).is_ok());
+
+        flags.test.timeout = None;
+        flags.test.update_snapshots = true;
+        assert!(validate_flags(&flags, false).is_err());
+        assert!(validate_flags(&flags, true).is_ok());
+
+        flags.test.update_snapshots = false;
+        flags.test.doc_tests = true;
+        assert!(validate_flags(&flags, false).is_err());
+        assert!(validate_flags(&flags, true).is_ok());
+    }
+
+    #[test]
+    fn validate_flags_requires_coverage_for_its_options_and_the_vm() {
+        let mut flags = base_flags();
+        flags.coverage.exclude_stdlib = true;
+        assert!(validate_flags(&flags, true).is_err());
+
+        flags.coverage.enabled = true;
+        assert!(validate_flags(&flags, true).is_ok());
+        assert!(validate_flags(&flags, false).is_ok());
+
+        flags.backend.selected = Backend::Native;
+        assert!(validate_flags(&flags, false).is_err());
+    }
+
+    #[test]
+    fn validate_flags_requires_prof_for_its_options() {
+        let mut flags = base_flags();
+        flags.profile.format = ProfileFormat::Speedscope;
+        assert!(validate_flags(&flags, false).is_err());
+
+        flags.runtime.profiling = true;
+        assert!(validate_flags(&flags, false).is_ok());
+    }
+
+    #[test]
+    fn validate_flags_requires_trace_calls_for_trace_output() {
+        let mut flags = base_flags();
+        flags.call_trace.output = Some("trace.jsonl".into());
+        assert!(validate_flags(&flags, false).is_err());
+
+        flags.call_trace.patterns = vec!["main".to_string()];
+        assert!(validate_flags(&flags, false).is_ok());
+    }
+
+    #[test]
+    fn validate_flags_rejects_record_with_replay() {
+        let mut flags = base_flags();
+        flags.effect_log.record = Some("run.log".into());
+        assert!(validate_flags(&flags, false).is_ok());
+        assert!(validate_flags(&flags, true).is_err());
+
+        flags.effect_log.replay = Some("run.log".into());
+        assert!(validate_flags(&flags, false).is_err());
+    }
+
     #[test]
     fn validate_dump_flags_requires_native_backend() {
         let mut flags = base_flags();
@@ -1,8 +1,15 @@
-use crate::driver::{flags::DriverFlags, pipeline, pipeline::RunTarget};
+use crate::driver::{
+    command::shared::apply_project_manifest, flags::DriverFlags, pipeline, pipeline::RunTarget,
+    watch,
+};
 #[cfg(feature = "llvm")]
 use crate::llvm::pipeline::toolchain_info;
 
-pub fn run(flags: DriverFlags, target: RunTarget) {
+pub fn run(mut flags: DriverFlags, target: RunTarget) {
+    apply_project_manifest(&mut flags, &target.path);
+    if target.watch {
+        watch::watch(&flags, &target);
+    }
     pipeline::run_pipeline(flags, target);
 }
 
@@ -1,23 +1,29 @@
 //! Inspection-oriented driver commands.
 
-use std::fs;
+use std::{fs, path::Path};
 
 use crate::{
     ast::{collect_free_vars_in_program, find_tail_calls},
     bytecode::op_code::disassemble,
     compiler::Compiler,
+    diagnostics::{Severity, render_diagnostics},
     driver::{
         command::shared::{
-            ParseCommandConfig, emit_parser_diagnostics, parse_program_for_command,
-            read_command_source, require_input_path,
+            ParseCommandConfig, apply_project_manifest, emit_parser_diagnostics, lint_policy_for,
+            parse_program_for_command, read_command_source, require_input_path,
         },
         flags::DriverFlags,
         mode::DiagnosticOutputFormat,
         support::shared::{DiagnosticRenderRequest, emit_diagnostics},
     },
     runtime::value::Value,
+    shared::source_files::collect_flx_files,
     syntax::{
-        formatter::format_source, lexer::Lexer, linter::Linter, parser::Parser, program::Program,
+        formatter::{DEFAULT_MAX_WIDTH, FormatError, FormatOptions, format_source_with},
+        lexer::Lexer,
+        linter::Linter,
+        parser::Parser,
+        program::Program,
     },
 };
 
@@ -112,8 +118,14 @@ pub fn show_bytecode(flags: &DriverFlags) {
 }
 
 /// Runs the linter for one source file after syntax diagnostics are emitted.
+///
+/// Lint levels and thresholds come from the nearest `.fluxlint.toml` or the project's `[lint]`
+/// table, then `--deny`/`--allow`/`--warn` in command-line order. The command exits non-zero
+/// when a denied lint fires.
 pub fn lint(flags: &DriverFlags) {
     let path = require_input_path(flags, "Usage: flux lint <file.flx>");
+    let mut flags = flags.clone();
+    apply_project_manifest(&mut flags, path);
     let mut parsed = parse_program_for_command(
         path,
         ParseCommandConfig {
@@ -122,8 +134,17 @@ pub fn lint(flags: &DriverFlags) {
             show_file_headers: false,
         },
     );
+    let policy = match lint_policy_for(&flags, Path::new(path)) {
+        Ok(policy) => policy,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+    };
     let interner = parsed.parser.take_interner();
-    let lints = Linter::new(Some(path.to_string()), &interner).lint(&parsed.program);
+    let lints = Linter::new(Some(path.to_string()), &interner)
+        .with_policy(policy)
+        .lint(&parsed.program);
     if !lints.is_empty() {
         emit_diagnostics(DiagnosticRenderRequest {
             diagnostics: &lints,
@@ -136,25 +157,80 @@ pub fn lint(flags: &DriverFlags) {
             text_to_stderr: false,
         });
     }
+    if lints.iter().any(|lint| lint.severity() == Severity::Error) {
+        std::process::exit(1);
+    }
 }
 
-/// Formats one Flux source file in place or reports whether changes are needed.
-pub fn fmt(path: &str, check: bool) {
-    match fs::read_to_string(path) {
-        Ok(source) => {
-            let formatted = format_source(&source);
-            if check {
-                if source.trim() != formatted.trim() {
-                    eprintln!("format: changes needed");
-                    std::process::exit(1);
-                }
-                return;
+/// Formats a Flux source file, or every `.flx` file under a directory, in place.
+///
+/// With `check`, files are left untouched and the command exits non-zero if any of them
+/// would change. Files that do not parse are reported and fail the command when named
+/// directly; a directory walk skips them with a note.
+pub fn fmt(path: &str, check: bool, max_width: Option<usize>) {
+    let options = FormatOptions {
+        max_width: max_width.unwrap_or(DEFAULT_MAX_WIDTH),
+    };
+    let root = Path::new(path);
+    let single_file = !root.is_dir();
+    let files = if single_file {
+        vec![root.to_path_buf()]
+    } else {
+        let mut files = Vec::new();
+        collect_flx_files(root, &mut files);
+        files.sort();
+        files
+    };
+
+    let mut failed = false;
+    let mut unformatted = 0usize;
+    for file in &files {
+        let display = file.display().to_string();
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", display, e);
+                failed = true;
+                continue;
+            }
+        };
+        let formatted = match format_source_with(&source, &options) {
+            Ok(formatted) => formatted,
+            Err(FormatError::Parse(diagnostics)) if single_file => {
+                eprintln!(
+                    "{}",
+                    render_diagnostics(&diagnostics, Some(&source), Some(&display))
+                );
+                failed = true;
+                continue;
             }
-            if let Err(err) = fs::write(path, formatted) {
-                eprintln!("Error writing {}: {}", path, err);
+            Err(FormatError::Parse(_)) => {
+                eprintln!("format: skipped {} (does not parse)", display);
+                continue;
             }
+            Err(FormatError::Changed) => {
+                eprintln!(
+                    "Error: formatting {} would change its meaning; file left unchanged",
+                    display
+                );
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if check {
+            eprintln!("format: changes needed in {}", display);
+            unformatted += 1;
+        } else if let Err(err) = fs::write(file, formatted) {
+            eprintln!("Error writing {}: {}", display, err);
+            failed = true;
         }
-        Err(e) => eprintln!("Error reading {}: {}", path, e),
+    }
+
+    if failed || unformatted > 0 {
+        std::process::exit(1);
     }
 }
 
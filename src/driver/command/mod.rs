@@ -5,3 +5,4 @@ pub(crate) mod cache_support;
 pub mod entry;
 pub(crate) mod inspect;
 pub(crate) mod shared;
+pub(crate) mod tooling;
@@ -3,7 +3,7 @@
 //! These helpers keep command modules focused on command-specific behavior instead of repeating
 //! input-path validation, file loading, and parser diagnostic handling.
 
-use std::fs;
+use std::{fs, path::Path};
 
 use crate::{
     driver::{
@@ -11,7 +11,8 @@ use crate::{
         mode::DiagnosticOutputFormat,
         support::shared::{DiagnosticRenderRequest, emit_diagnostics},
     },
-    syntax::{lexer::Lexer, parser::Parser, program::Program},
+    manifest::{ManifestError, Project, lint},
+    syntax::{lexer::Lexer, linter::LintPolicy, parser::Parser, program::Program},
 };
 
 /// Lightweight parser command settings shared by command handlers that operate on one source file.
@@ -29,6 +30,19 @@ pub(crate) struct ParsedCommandProgram {
     pub(crate) program: Program,
 }
 
+/// Returns the lint policy for `path`: the nearest `.fluxlint.toml` or the project's `[lint]`
+/// table, then `--deny`/`--allow`/`--warn` in command-line order.
+pub(crate) fn lint_policy_for(
+    flags: &DriverFlags,
+    path: &Path,
+) -> Result<LintPolicy, ManifestError> {
+    let mut policy = lint::load_for(path)?.policy();
+    for (code, level) in &flags.lint.levels {
+        policy.set_level(code.as_str(), *level);
+    }
+    Ok(policy)
+}
+
 /// Returns the required CLI input path or terminates the process with the given usage string.
 pub(crate) fn require_input_path<'a>(flags: &'a DriverFlags, usage: &str) -> &'a str {
     flags.input.input_path.as_deref().unwrap_or_else(|| {
@@ -37,6 +51,35 @@ pub(crate) fn require_input_path<'a>(flags: &'a DriverFlags, usage: &str) -> &'a
     })
 }
 
+/// Applies the `flux.toml` found by walking up from `entry_path`, if any, to `flags`.
+///
+/// Project and dependency roots are appended after any `--root` paths, and the manifest's
+/// `[build]` settings turn on the matching switches. `flux.lock` is refreshed; manifest and
+/// resolution errors terminate the process.
+pub(crate) fn apply_project_manifest(flags: &mut DriverFlags, entry_path: &str) {
+    let project = Project::discover(Path::new(entry_path)).and_then(|project| {
+        if let Some(project) = &project {
+            project.write_lock()?;
+        }
+        Ok(project)
+    });
+    let project = match project {
+        Ok(Some(project)) => project,
+        Ok(None) => return,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+    };
+    for root in project.module_roots() {
+        if !flags.input.roots.contains(&root) {
+            flags.input.roots.push(root);
+        }
+    }
+    flags.language.strict_mode |= project.manifest.build.strict;
+    flags.language.enable_optimize |= project.manifest.build.optimize;
+}
+
 /// Reads a source file for command processing and exits on IO failure.
 pub(crate) fn read_command_source(path: &str) -> String {
     fs::read_to_string(path).unwrap_or_else(|err| {
@@ -0,0 +1,202 @@
+//! Editor and developer tooling commands.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diagnostics::Severity,
+    docgen::{self, DocFormat, InterfaceOptions},
+    driver::{
+        command::shared::lint_policy_for,
+        flags::DriverFlags,
+        repl::{self, ReplOptions},
+        support::shared::{DiagnosticRenderRequest, emit_diagnostics},
+    },
+    fix::{self, FixOutcome},
+    lsp::{self, AnalysisOptions},
+    shared::source_files::collect_flx_files,
+};
+
+/// Output directory for `flux doc` when `-o` is not given.
+const DEFAULT_DOC_DIR: &str = "target/doc";
+
+/// Serves the Language Server Protocol over stdio until the client exits.
+pub fn lsp(flags: &DriverFlags) {
+    let options = AnalysisOptions {
+        roots: flags.input.roots.clone(),
+        roots_only: flags.input.roots_only,
+        strict_mode: flags.language.strict_mode,
+        cache_dir: flags.cache.cache_dir.clone(),
+    };
+    match lsp::run_stdio(options) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("flux lsp: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs the interactive REPL on stdin/stdout.
+pub fn repl(flags: &DriverFlags) {
+    let options = ReplOptions {
+        roots: flags.input.roots.clone(),
+        roots_only: flags.input.roots_only,
+        strict_mode: flags.language.strict_mode,
+    };
+    if let Err(err) = repl::run_stdio(options) {
+        eprintln!("flux repl: {err}");
+        std::process::exit(1);
+    }
+}
+
+/// Generates API documentation for a module file or every module under a directory.
+///
+/// Pages are still written when some modules fail to parse or compile; their diagnostics
+/// are reported and the command exits non-zero.
+pub fn doc(flags: &DriverFlags, path: &str, markdown: bool) {
+    let root = Path::new(path);
+    let files = if root.is_dir() {
+        let mut files = Vec::new();
+        collect_flx_files(root, &mut files);
+        files.sort();
+        files
+    } else {
+        vec![root.to_path_buf()]
+    };
+    let options = InterfaceOptions {
+        roots: flags.input.roots.clone(),
+        roots_only: flags.input.roots_only,
+        strict_mode: flags.language.strict_mode,
+    };
+    let report = docgen::collect(&files, &options);
+
+    let has_errors = report
+        .diagnostics
+        .iter()
+        .any(|diag| diag.severity() == Severity::Error);
+    if !report.diagnostics.is_empty() {
+        emit_diagnostics(DiagnosticRenderRequest {
+            diagnostics: &report.diagnostics,
+            default_file: None,
+            default_source: None,
+            show_file_headers: true,
+            max_errors: flags.diagnostics.max_errors,
+            format: flags.diagnostics.diagnostics_format,
+            all_errors: flags.diagnostics.all_errors,
+            text_to_stderr: true,
+        });
+    }
+
+    let format = if markdown {
+        DocFormat::Markdown
+    } else {
+        DocFormat::Html
+    };
+    let out_dir = flags
+        .backend
+        .output_path
+        .as_ref()
+        .map_or_else(|| PathBuf::from(DEFAULT_DOC_DIR), PathBuf::from);
+    match docgen::write(&report.set, format, &out_dir) {
+        Ok(written) => {
+            let index = written.first().map_or(out_dir.clone(), PathBuf::clone);
+            eprintln!(
+                "doc: documented {} module(s) in {}",
+                report.set.modules.len(),
+                index.display()
+            );
+        }
+        Err(err) => {
+            eprintln!("flux doc: could not write {}: {err}", out_dir.display());
+            std::process::exit(1);
+        }
+    }
+    if has_errors {
+        std::process::exit(1);
+    }
+}
+
+/// Applies machine-applicable diagnostic suggestions to a file or every `.flx` file under a
+/// directory.
+///
+/// Lints follow the same configuration as `flux lint`, so allowed lints are not fixed and denied
+/// lints count as errors. With `dry_run`, files are left untouched and the changes are printed as
+/// a unified diff. Fixes that would add errors, or leave a fixed diagnostic in place, are dropped
+/// for that file and fail the command.
+pub fn fix(flags: &DriverFlags, path: &str, dry_run: bool) {
+    let root = Path::new(path);
+    let files = if root.is_dir() {
+        let mut files = Vec::new();
+        collect_flx_files(root, &mut files);
+        files.sort();
+        files
+    } else {
+        vec![root.to_path_buf()]
+    };
+    let options = AnalysisOptions {
+        roots: flags.input.roots.clone(),
+        roots_only: flags.input.roots_only,
+        strict_mode: flags.language.strict_mode,
+        cache_dir: flags.cache.cache_dir.clone(),
+    };
+
+    let mut failed = false;
+    let (mut fixed_files, mut fixes) = (0usize, 0usize);
+    for file in &files {
+        let display = file.display().to_string();
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Error reading {display}: {err}");
+                failed = true;
+                continue;
+            }
+        };
+        let policy = match lint_policy_for(flags, file) {
+            Ok(policy) => policy,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                failed = true;
+                continue;
+            }
+        };
+        match fix::fix_source(file, &source, &options, &policy) {
+            FixOutcome::Unchanged => {}
+            FixOutcome::Fixed { fixed, applied } => {
+                if dry_run {
+                    print!("{}", fix::unified_diff(&display, &source, &fixed));
+                } else if let Err(err) = fs::write(file, &fixed) {
+                    eprintln!("Error writing {display}: {err}");
+                    failed = true;
+                    continue;
+                }
+                fixed_files += 1;
+                fixes += applied;
+            }
+            FixOutcome::Regressed { introduced } => {
+                eprintln!(
+                    "fix: left {display} unchanged; its fixes introduce {}",
+                    introduced.join(", ")
+                );
+                failed = true;
+            }
+            FixOutcome::Unresolved { remaining } => {
+                eprintln!(
+                    "fix: left {display} unchanged; {} still reported after fixing",
+                    remaining.join(", ")
+                );
+                failed = true;
+            }
+        }
+    }
+
+    let verb = if dry_run { "would apply" } else { "applied" };
+    eprintln!("fix: {verb} {fixes} fix(es) in {fixed_files} file(s)");
+    if failed {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,291 @@
+//! Doc-tests for `flux --test --doc-tests`: the fenced `flux` examples of `///` comments.
+//!
+//! Each example on a public item of a `module` becomes a program of its own. The program
+//! imports the module with `exposing (..)` and runs the example as the body of one test
+//! function, which may print. A line ending in `// => value`, or followed by a `// => value`
+//! line, is checked with `assert_eq`. The body keeps the lines of the example, so locations
+//! in the program map back to the doc comment.
+
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    diagnostics::position::{Position, Span},
+    docgen::{ItemKind, extract::extract_module},
+};
+
+/// Name of the test function a doc-test program defines.
+pub(crate) const DOC_TEST_FUNCTION: &str = "test_doc";
+
+/// Line of a doc-test program that holds the first line of the example.
+const FIRST_EXAMPLE_LINE: usize = 4;
+
+/// Marker of an expected value in an example.
+const EXPECTED_MARKER: &str = "// =>";
+
+/// Start of a line checked with `// =>`, inserted before its expression.
+const ASSERTION_START: &str = "assert_eq(";
+
+/// One fenced `flux` example of a doc comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DocTest {
+    /// `doc_<item>` for the first example of an item, then `doc_<item>_2`, `doc_<item>_3`, ...
+    pub(crate) name: String,
+    /// Name of the module that declares the item.
+    pub(crate) module: String,
+    /// The example with its expected values turned into `assert_eq` calls.
+    pub(crate) lines: Vec<String>,
+    /// Source line and column of the start of each example line, and the number of characters
+    /// inserted before its code.
+    origins: Vec<(usize, usize, usize)>,
+}
+
+impl DocTest {
+    /// Source of the program that runs the example.
+    pub(crate) fn program_source(&self) -> String {
+        let mut source = format!(
+            "import {} exposing (..)\n\nfn {DOC_TEST_FUNCTION}() with Console {{\n",
+            self.module
+        );
+        for line in &self.lines {
+            source.push_str("    ");
+            source.push_str(line);
+            source.push('\n');
+        }
+        source.push_str("}\n");
+        source
+    }
+
+    /// Maps a span of the doc-test program to the doc comment, when it lies in the example.
+    pub(crate) fn source_span(&self, span: Span) -> Option<Span> {
+        let map = |position: Position| {
+            let (line, column, inserted) = self
+                .origins
+                .get(position.line.checked_sub(FIRST_EXAMPLE_LINE)?)?;
+            // The program indents each example line by four spaces.
+            let offset = position.column.saturating_sub(4 + inserted);
+            Some(Position::new(*line, column + offset))
+        };
+        let start = map(span.start)?;
+        Some(Span::new(start, map(span.end).unwrap_or(start)))
+    }
+}
+
+/// Collects the doc-tests of the module declared in `source`.
+///
+/// Only examples fenced as ` ```flux ` in the `///` comment right before a public function,
+/// `data`, `class` or `effect` declaration are collected. Files without a `module`
+/// declaration, or that do not parse, have none.
+pub(crate) fn collect_doc_tests(path: &Path, source: &str) -> Vec<DocTest> {
+    let Ok(Some(module)) = extract_module(path, source) else {
+        return Vec::new();
+    };
+    let items: HashSet<&str> = module
+        .items
+        .iter()
+        .filter(|item| item.kind != ItemKind::Instance)
+        .map(|item| item.name.as_str())
+        .collect();
+
+    let mut tests = Vec::new();
+    let mut comment: Vec<(usize, usize, &str)> = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(text) = trimmed.strip_prefix("///") {
+            let text = text.strip_prefix(' ').unwrap_or(text);
+            let column = line.len() - text.len();
+            comment.push((index + 1, column, text));
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") || comment.is_empty() {
+            continue;
+        }
+        if let Some(name) = declared_name(trimmed).filter(|name| items.contains(name)) {
+            for (number, example) in flux_examples(&comment).into_iter().enumerate() {
+                let name = match number {
+                    0 => format!("doc_{name}"),
+                    n => format!("doc_{name}_{}", n + 1),
+                };
+                tests.push(doc_test(name, &module.name, example));
+            }
+        }
+        comment.clear();
+    }
+    tests
+}
+
+/// The name a declaration line declares: `abs` for `public fn abs(n) {`.
+fn declared_name(line: &str) -> Option<&str> {
+    let line = line.strip_prefix("public ").unwrap_or(line);
+    let rest = ["fn ", "data ", "class ", "effect "]
+        .iter()
+        .find_map(|keyword| line.strip_prefix(keyword))?
+        .trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+/// The ` ```flux ` blocks of a doc comment, as `(line, column, text)` lines.
+fn flux_examples<'a>(comment: &[(usize, usize, &'a str)]) -> Vec<Vec<(usize, usize, &'a str)>> {
+    let mut examples = Vec::new();
+    let mut lines = comment.iter();
+    while let Some((_, _, text)) = lines.next() {
+        let Some(info) = text.trim().strip_prefix("```") else {
+            continue;
+        };
+        let mut example = Vec::new();
+        for &line in lines.by_ref() {
+            if line.2.trim().starts_with("```") {
+                break;
+            }
+            example.push(line);
+        }
+        if info.trim() == "flux" {
+            examples.push(example);
+        }
+    }
+    examples
+}
+
+fn doc_test(name: String, module: &str, example: Vec<(usize, usize, &str)>) -> DocTest {
+    let indent = example
+        .iter()
+        .filter(|(_, _, text)| !text.trim().is_empty())
+        .map(|(_, _, text)| text.len() - text.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut lines: Vec<String> = Vec::with_capacity(example.len());
+    let mut origins = Vec::with_capacity(example.len());
+    // Index of the last code line, which a `// => value` line on its own checks.
+    let mut last_code: Option<usize> = None;
+    for (line, column, text) in example {
+        let text = text.get(indent..).unwrap_or("").trim_end();
+        origins.push((line, column + indent, 0));
+        match text.split_once(EXPECTED_MARKER) {
+            Some((code, expected)) if !code.trim().is_empty() => {
+                lines.push(assertion(code, expected));
+                origins[lines.len() - 1].2 = ASSERTION_START.len();
+                last_code = None;
+            }
+            Some((_, expected)) => match last_code.take() {
+                Some(index) => {
+                    lines[index] = assertion(&lines[index], expected);
+                    origins[index].2 = ASSERTION_START.len();
+                    lines.push(String::new());
+                }
+                None => lines.push(text.to_string()),
+            },
+            None => {
+                let trimmed = text.trim_start();
+                if !trimmed.is_empty() && !trimmed.starts_with("//") {
+                    last_code = Some(lines.len());
+                }
+                lines.push(text.to_string());
+            }
+        }
+    }
+    DocTest {
+        name,
+        module: module.to_string(),
+        lines,
+        origins,
+    }
+}
+
+/// `assert_eq(<code>, <expected>)`, keeping the indentation of `code`.
+fn assertion(code: &str, expected: &str) -> String {
+    let expression = code.trim();
+    let indent = &code[..code.len() - code.trim_start().len()];
+    format!(
+        "{indent}{ASSERTION_START}{expression}, {})",
+        expected.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::collect_doc_tests;
+    use crate::diagnostics::position::{Position, Span};
+
+    const SOURCE: &str = r#"module Shapes {
+    /// Absolute value.
+    ///
+    /// ```flux
+    /// abs(-3) // => 3
+    /// let n = 4
+    /// abs(n)
+    /// // => 4
+    /// ```
+    ///
+    /// ```text
+    /// not run
+    /// ```
+    ///
+    /// ```flux
+    /// assert_eq(abs(0), 0)
+    /// ```
+    public fn abs(n) {
+        if n < 0 { -n } else { n }
+    }
+
+    /// ```flux
+    /// hidden(1)
+    /// ```
+    fn hidden(n) {
+        n
+    }
+}
+"#;
+
+    #[test]
+    fn collects_the_flux_examples_of_public_items() {
+        let tests = collect_doc_tests(Path::new("Shapes.flx"), SOURCE);
+
+        let names: Vec<&str> = tests.iter().map(|test| test.name.as_str()).collect();
+        assert_eq!(names, ["doc_abs", "doc_abs_2"]);
+        assert_eq!(tests[0].module, "Shapes");
+        assert_eq!(
+            tests[0].lines,
+            [
+                "assert_eq(abs(-3), 3)",
+                "let n = 4",
+                "assert_eq(abs(n), 4)",
+                ""
+            ]
+        );
+        assert_eq!(tests[1].lines, ["assert_eq(abs(0), 0)"]);
+    }
+
+    #[test]
+    fn program_lines_map_back_to_the_doc_comment() {
+        let tests = collect_doc_tests(Path::new("Shapes.flx"), SOURCE);
+        let program = tests[0].program_source();
+
+        assert!(
+            program.starts_with("import Shapes exposing (..)\n\nfn test_doc() with Console {\n")
+        );
+        assert_eq!(program.lines().nth(5), Some("    assert_eq(abs(n), 4)"));
+        assert_eq!(
+            tests[0].source_span(Span::new(Position::new(5, 4), Position::new(5, 13))),
+            Some(Span::new(Position::new(6, 8), Position::new(6, 17)))
+        );
+        assert_eq!(
+            tests[0].source_span(Span::new(Position::new(6, 14), Position::new(6, 20))),
+            Some(Span::new(Position::new(7, 8), Position::new(7, 14)))
+        );
+        assert_eq!(
+            tests[0].source_span(Span::new(Position::new(1, 0), Position::new(1, 4))),
+            None
+        );
+    }
+
+    #[test]
+    fn scripts_have_no_doc_tests() {
+        let source = "/// ```flux\n/// main()\n/// ```\npublic fn main() {}\n";
+
+        assert!(collect_doc_tests(Path::new("script.flx"), source).is_empty());
+    }
+}
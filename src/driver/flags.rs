@@ -1,9 +1,14 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
-use crate::driver::{
-    backend::Backend,
-    backend_policy,
-    mode::{AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat},
+use crate::{
+    driver::{
+        backend::Backend,
+        backend_policy,
+        mode::{
+            AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat, ProfileFormat, TestReportFormat,
+        },
+    },
+    syntax::linter::LintLevel,
 };
 
 /// Backend-selection and backend-output switches for a driver invocation.
@@ -45,6 +50,7 @@ pub struct DriverDumpFlags {
     pub dump_aether: AetherDumpMode,
     pub dump_lir: bool,
     pub dump_lir_llvm: bool,
+    pub core_eval: bool,
 }
 
 /// Diagnostic rendering and reporting options shared across driver modes.
@@ -70,6 +76,105 @@ pub struct DriverLanguageFlags {
     pub strict_mode: bool,
 }
 
+/// Execution and reporting options for `--test` runs.
+#[derive(Debug, Clone)]
+pub struct DriverTestFlags {
+    pub format: TestReportFormat,
+    /// Where to write the machine-readable report; stdout when unset.
+    pub output: Option<PathBuf>,
+    /// Number of tests run at once (`--test-jobs`); `1` runs them in order in one VM.
+    pub jobs: usize,
+    /// Wall-clock limit for each test (`--test-timeout`).
+    pub timeout: Option<Duration>,
+    /// Rewrite `FTest.snapshot` files instead of comparing against them (`--update-snapshots`).
+    pub update_snapshots: bool,
+    /// Also run the `flux` examples in `///` comments of public items (`--doc-tests`).
+    pub doc_tests: bool,
+}
+
+/// Source coverage options for VM runs (`--coverage`).
+#[derive(Debug, Clone)]
+pub struct DriverCoverageFlags {
+    pub enabled: bool,
+    /// Where the lcov report is written (`--coverage-output`); `lcov.info` when unset.
+    pub output: Option<PathBuf>,
+    /// Leave `Flow.*` stdlib modules out of the report (`--coverage-exclude-stdlib`).
+    pub exclude_stdlib: bool,
+}
+
+/// Report options of `--prof` runs.
+#[derive(Debug, Clone)]
+pub struct DriverProfileFlags {
+    /// Report format (`--prof-format`); the flat table when unset.
+    pub format: ProfileFormat,
+    /// Where the report is written (`--prof-output`); stderr for the flat table and a
+    /// `profile.*` file in the working directory for the other formats when unset.
+    pub output: Option<PathBuf>,
+}
+
+impl Default for DriverProfileFlags {
+    fn default() -> Self {
+        Self {
+            format: ProfileFormat::Flat,
+            output: None,
+        }
+    }
+}
+
+/// Call tracing options (`--trace-calls`).
+#[derive(Debug, Clone, Default)]
+pub struct DriverCallTraceFlags {
+    /// Name globs of the functions to trace; tracing is off when empty.
+    pub patterns: Vec<String>,
+    /// Where JSON lines are written (`--trace-output`); text on stderr when unset.
+    pub output: Option<PathBuf>,
+}
+
+impl DriverCallTraceFlags {
+    pub fn enabled(&self) -> bool {
+        !self.patterns.is_empty()
+    }
+}
+
+/// Builtin effect log options (`--record`, `--replay`).
+#[derive(Debug, Clone, Default)]
+pub struct DriverEffectLogFlags {
+    /// Log every builtin effect of the run to this file (`--record`).
+    pub record: Option<PathBuf>,
+    /// Answer builtin effects from this earlier recording (`--replay`).
+    pub replay: Option<PathBuf>,
+}
+
+/// Lint level overrides of `flux lint` (`--deny`, `--allow`, `--warn`).
+#[derive(Debug, Clone, Default)]
+pub struct DriverLintFlags {
+    /// Codes and levels in command-line order; they apply after the lint configuration, and a
+    /// later entry for the same code wins.
+    pub levels: Vec<(String, LintLevel)>,
+}
+
+/// Options of `flux bench`.
+#[derive(Debug, Clone)]
+pub struct DriverBenchFlags {
+    /// Only run benchmarks whose name contains this text (`--filter`).
+    pub filter: Option<String>,
+    /// Baseline file to compare against and update (`--baseline`); under the cache
+    /// directory when unset.
+    pub baseline: Option<PathBuf>,
+    /// Measurement time of each benchmark (`--time`), after a warmup of a third of it.
+    pub measurement: Duration,
+}
+
+impl Default for DriverBenchFlags {
+    fn default() -> Self {
+        Self {
+            filter: None,
+            baseline: None,
+            measurement: Duration::from_secs(1),
+        }
+    }
+}
+
 /// All per-invocation driver options, grouped by concern.
 ///
 /// This keeps command parsing explicit while avoiding a single flat "bag of flags"
@@ -83,6 +188,13 @@ pub struct DriverFlags {
     pub diagnostics: DriverDiagnosticFlags,
     pub cache: DriverCacheFlags,
     pub language: DriverLanguageFlags,
+    pub test: DriverTestFlags,
+    pub coverage: DriverCoverageFlags,
+    pub profile: DriverProfileFlags,
+    pub call_trace: DriverCallTraceFlags,
+    pub effect_log: DriverEffectLogFlags,
+    pub lint: DriverLintFlags,
+    pub bench: DriverBenchFlags,
 }
 
 impl DriverFlags {
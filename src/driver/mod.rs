@@ -7,18 +7,25 @@
 pub mod backend;
 pub mod backend_policy;
 pub mod command;
+pub(crate) mod doc_tests;
 pub mod flags;
 pub(crate) mod frontend;
 pub mod mode;
 pub(crate) mod module_compile;
 pub mod pipeline;
+pub(crate) mod repl;
 pub(crate) mod reporting;
+pub(crate) mod run_bench;
 pub(crate) mod run_program;
 pub(crate) mod run_tests;
 pub mod session;
 pub(crate) mod shared;
 pub(crate) mod support;
+pub(crate) mod test_snapshots;
 #[cfg(test)]
 pub(crate) mod test_support;
+pub(crate) mod watch;
 
-pub use mode::{AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat, RunMode};
+pub use mode::{
+    AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat, ProfileFormat, RunMode, TestReportFormat,
+};
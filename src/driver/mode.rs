@@ -5,6 +5,28 @@ pub enum DiagnosticOutputFormat {
     JsonCompact,
 }
 
+/// Report format of a `--test` run (`--test-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestReportFormat {
+    Text,
+    Junit,
+    Tap,
+    Json,
+}
+
+/// Output format of a `--prof` run (`--prof-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// Flat cost-centre table on stderr.
+    Flat,
+    /// Folded stacks weighted by self time in nanoseconds.
+    Folded,
+    /// Folded stacks weighted by allocation count.
+    FoldedAlloc,
+    /// speedscope JSON with a time and an allocation profile.
+    Speedscope,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CoreDumpMode {
     None,
@@ -23,4 +45,10 @@ pub enum AetherDumpMode {
 pub enum RunMode {
     Program,
     Tests,
+    /// Type-check and validate without executing (`flux check`).
+    Check,
+    /// Serve a Debug Adapter Protocol session for the program (`flux debug --dap`).
+    Debug,
+    /// Measure the `bench_*` functions (`flux bench`).
+    Bench,
 }
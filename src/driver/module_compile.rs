@@ -1,7 +1,7 @@
 #![cfg_attr(not(feature = "llvm"), allow(dead_code))]
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
@@ -13,6 +13,7 @@ use crate::{
         interner::Interner,
         module_graph::{ModuleKind, ModuleNode},
         program::Program,
+        symbol::Symbol,
     },
     types::module_interface::ModuleInterface,
 };
@@ -53,6 +54,93 @@ pub(crate) fn program_has_user_adt_declarations(program: &Program) -> bool {
         .any(statement_has_user_adt_declarations)
 }
 
+/// First tag available to user constructors; lower tags are reserved for the
+/// runtime's built-in constructors.
+const FIRST_USER_CONSTRUCTOR_TAG: i32 = 5;
+
+fn collect_declared_constructors(
+    statements: &[flux::syntax::statement::Statement],
+    out: &mut Vec<Symbol>,
+) {
+    for statement in statements {
+        match statement {
+            flux::syntax::statement::Statement::Data { variants, .. } => {
+                out.extend(variants.iter().map(|variant| variant.name));
+            }
+            flux::syntax::statement::Statement::Module { body, .. } => {
+                collect_declared_constructors(&body.statements, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Numbers every ADT constructor of a native build once, Flow library modules
+/// first, so that each per-module compile agrees on the tags and the
+/// constructor-name helper can name all of them.
+pub(crate) fn graph_constructor_tags(order: &[&ModuleNode]) -> HashMap<Symbol, i32> {
+    let mut constructors = Vec::new();
+    for node in order
+        .iter()
+        .filter(|node| node.kind == ModuleKind::FlowStdlib)
+    {
+        collect_declared_constructors(&node.program.statements, &mut constructors);
+    }
+    for node in order
+        .iter()
+        .filter(|node| node.kind != ModuleKind::FlowStdlib)
+    {
+        collect_declared_constructors(&node.program.statements, &mut constructors);
+    }
+    let mut tags = HashMap::new();
+    for name in constructors {
+        let next = FIRST_USER_CONSTRUCTOR_TAG + tags.len() as i32;
+        tags.entry(name).or_insert(next);
+    }
+    tags
+}
+
+/// Stable text of the constructor tags a module's object code depends on: the
+/// ones declared by the module and its transitive imports, or every tag when
+/// the module carries the constructor-name helper.
+pub(crate) fn constructor_tag_fingerprint(
+    node: &ModuleNode,
+    nodes_by_path: &HashMap<PathBuf, ModuleNode>,
+    tags: &HashMap<Symbol, i32>,
+    interner: &Interner,
+    include_all: bool,
+) -> String {
+    let mut constructors = Vec::new();
+    if include_all {
+        constructors.extend(tags.keys().copied());
+    } else {
+        let mut seen = HashSet::new();
+        let mut pending = vec![node];
+        while let Some(current) = pending.pop() {
+            if !seen.insert(current.path.clone()) {
+                continue;
+            }
+            collect_declared_constructors(&current.program.statements, &mut constructors);
+            pending.extend(
+                current
+                    .imports
+                    .iter()
+                    .filter_map(|dep| nodes_by_path.get(&dep.target_path)),
+            );
+        }
+    }
+    let mut entries: Vec<String> = constructors
+        .into_iter()
+        .filter_map(|name| {
+            tags.get(&name)
+                .map(|tag| format!("{}={tag}", interner.resolve(name)))
+        })
+        .collect();
+    entries.sort();
+    entries.dedup();
+    entries.join(";")
+}
+
 pub(crate) fn tag_module_diagnostics(
     diags: &mut Vec<Diagnostic>,
     phase: DiagnosticPhase,
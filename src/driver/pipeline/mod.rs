@@ -8,7 +8,8 @@ pub(crate) mod vm;
 use crate::driver::{
     flags::DriverFlags,
     mode::RunMode,
-    pipeline::program::{RunProgramRequest, run_file},
+    pipeline::program::{RunProgramRequest, check_file, debug_file, run_file},
+    run_bench::run_bench_target,
     run_tests::{TestRunRequest, run_test_file},
     session::DriverSession,
 };
@@ -18,6 +19,8 @@ use crate::driver::{
 pub struct RunTarget {
     pub path: String,
     pub mode: RunMode,
+    /// Re-run whenever a module of the program changes (`--watch`).
+    pub watch: bool,
 }
 
 /// Dispatches a driver invocation to the program or test pipeline.
@@ -36,6 +39,23 @@ pub fn run_pipeline(flags: DriverFlags, target: RunTarget) {
                 session: &session,
             },
         ),
+        RunMode::Check => check_file(RunProgramRequest {
+            path: &target.path,
+            flags: &flags,
+            session: &session,
+        }),
+        RunMode::Debug => debug_file(RunProgramRequest {
+            path: &target.path,
+            flags: &flags,
+            session: &session,
+        }),
+        RunMode::Bench => run_bench_target(
+            &target.path,
+            TestRunRequest {
+                flags: &flags,
+                session: &session,
+            },
+        ),
     }
 }
 
@@ -49,6 +69,7 @@ mod tests {
         let target = RunTarget {
             path: "examples/guide/arithmetic.flx".to_string(),
             mode: RunMode::Program,
+            watch: false,
         };
 
         let cloned = target.clone();
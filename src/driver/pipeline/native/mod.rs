@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 
 use crate::{
     diagnostics::Diagnostic,
+    runtime::call_trace::CallTraceFilter,
     shared::cache_paths::CacheLayout,
     syntax::{interner::Interner, module_graph::ModuleGraph},
 };
@@ -124,6 +125,10 @@ pub(crate) struct NativeParallelCompileRequest<'a> {
     pub(crate) enable_optimize: bool,
     pub(crate) enable_analyze: bool,
     pub(crate) verbose: bool,
+    /// Instrument modules for `--prof`.
+    pub(crate) profiling: bool,
+    /// Instrument modules for `--trace-calls`.
+    pub(crate) call_trace: Option<&'a CallTraceFilter>,
     pub(crate) base_interner: &'a Interner,
 }
 
@@ -16,10 +16,12 @@ use crate::{
         pipeline::compile_ir_to_object,
         render_module, target,
     },
+    runtime::call_trace::CallTraceFilter,
     shared::cache_paths::{self, CacheLayout},
     syntax::{
         interner::Interner,
         module_graph::{ModuleGraph, ModuleKind, ModuleNode},
+        symbol::Symbol,
     },
     types::module_interface::ModuleInterface,
 };
@@ -29,8 +31,8 @@ use super::{NativeParallelCompileRequest, native_temp_dir};
 use crate::driver::{
     frontend::extract_module_name_and_sym,
     module_compile::{
-        build_module_compiler, effective_module_strictness, program_has_user_adt_declarations,
-        tag_module_diagnostics,
+        build_module_compiler, constructor_tag_fingerprint, effective_module_strictness,
+        graph_constructor_tags, program_has_user_adt_declarations, tag_module_diagnostics,
     },
     pipeline::parallel_shared::{
         ParallelReplayRequest, collect_dependency_fingerprints, dependency_changed_paths,
@@ -84,7 +86,10 @@ struct NativeModuleCompileRequest<'a> {
     strict_mode: bool,
     enable_optimize: bool,
     enable_analyze: bool,
+    profiling: bool,
+    call_trace: Option<&'a CallTraceFilter>,
     base_interner: &'a Interner,
+    constructor_tags: &'a HashMap<Symbol, i32>,
     export_user_ctor_name_helper: bool,
 }
 
@@ -97,6 +102,14 @@ fn compile_parallel_native_module(
     let strict_mode =
         effective_module_strictness(node.kind, request.entry_module_kind, request.strict_mode);
     let semantic_config_hash = compute_semantic_config_hash(strict_mode, request.enable_optimize);
+    let constructor_tags = constructor_tag_fingerprint(
+        node,
+        request.nodes_by_path,
+        request.constructor_tags,
+        request.base_interner,
+        request.export_user_ctor_name_helper,
+    );
+    let source_hash = hash_bytes(&[&source_hash[..], constructor_tags.as_bytes()].concat());
     let cache_key = compute_native_cache_key(&source_hash, &semantic_config_hash);
 
     let native_miss_reason = if !request.no_cache && !request.force_rebuild {
@@ -138,6 +151,9 @@ fn compile_parallel_native_module(
         request.strict_mode,
         false,
     );
+    compiler.set_native_constructor_tags(request.constructor_tags);
+    compiler.set_profiling(request.profiling);
+    compiler.set_call_trace(request.call_trace.cloned());
     compiler.set_file_path(node.path.to_string_lossy().to_string());
 
     let compile_result = compiler.compile_with_opts(
@@ -449,6 +465,8 @@ pub(crate) fn compile_native_modules_parallel(
         enable_optimize,
         enable_analyze,
         verbose,
+        profiling,
+        call_trace,
         base_interner,
     } = request;
     let entry_path = graph
@@ -461,11 +479,14 @@ pub(crate) fn compile_native_modules_parallel(
         .into_iter()
         .map(|node| (node.path.clone(), node.clone()))
         .collect();
+    let constructor_tags = graph_constructor_tags(&graph.topo_order());
+    // The entry module sees the whole constructor numbering, so it carries
+    // the helper that `to_string` uses to name user constructors.
     let user_ctor_helper_owner = graph
         .topo_order()
         .into_iter()
-        .find(|node| program_has_user_adt_declarations(&node.program))
-        .map(|node| node.path.clone());
+        .any(|node| program_has_user_adt_declarations(&node.program))
+        .then(|| entry_path.clone());
     let mut build_state = NativeParallelBuildState::new(graph, cache_layout, no_cache);
 
     for level in graph.topo_levels() {
@@ -499,7 +520,10 @@ pub(crate) fn compile_native_modules_parallel(
                         strict_mode,
                         enable_optimize,
                         enable_analyze,
+                        profiling,
+                        call_trace,
                         base_interner,
+                        constructor_tags: &constructor_tags,
                         export_user_ctor_name_helper: user_ctor_helper_owner
                             .as_ref()
                             .is_some_and(|owner| owner == &node.path),
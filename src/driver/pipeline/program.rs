@@ -34,11 +34,17 @@ use crate::driver::{
 #[cfg(feature = "llvm")]
 use flux::llvm::pipeline::toolchain_info;
 use flux::{
+    ast::fold::{self, Folder},
     bytecode::bytecode_cache::hash_bytes,
     compiler::Compiler,
     diagnostics::{Diagnostic, Severity},
+    runtime::call_trace::CallTraceFilter,
     shared::cache_paths::CacheLayout,
-    syntax::{module_graph::ModuleGraph, program::Program},
+    syntax::{
+        expression::{ExprId, ExprIdGen, Expression},
+        module_graph::ModuleGraph,
+        program::Program,
+    },
 };
 
 #[derive(Clone, Copy)]
@@ -74,18 +80,42 @@ fn should_build_merged_program(flags: &DriverFlags, is_multimodule: bool) -> boo
             || !matches!(flags.dumps.dump_core, CoreDumpMode::None)
             || flags.dumps.dump_cfg
             || flags.dumps.dump_lir
-            || flags.dumps.dump_lir_llvm)
+            || flags.dumps.dump_lir_llvm
+            || flags.dumps.core_eval)
 }
 
 /// Concatenates module programs in topological order for dump-only surfaces.
+///
+/// Each module's parser numbers expressions from 1, so ids are shifted past
+/// the preceding modules' to keep HM types keyed by `ExprId` unambiguous.
 fn merge_programs<'a>(programs: impl IntoIterator<Item = &'a Program>) -> Program {
     let mut merged = Program::new();
+    let mut shift = ExprIdShift { offset: 0 };
     for program in programs {
-        merged.statements.extend(program.statements.clone());
+        let next_offset = ExprIdGen::resuming_past_program(program).counter() - 1;
+        let shifted = shift.fold_program(program.clone());
+        merged.statements.extend(shifted.statements);
+        shift.offset += next_offset;
     }
     merged
 }
 
+/// Adds a fixed offset to every parser-assigned [`ExprId`].
+struct ExprIdShift {
+    offset: u32,
+}
+
+impl Folder for ExprIdShift {
+    fn fold_expr(&mut self, expr: Expression) -> Expression {
+        let mut expr = fold::fold_expr(self, expr);
+        let id = expr.expr_id_mut();
+        if *id != ExprId::UNSET {
+            id.0 += self.offset;
+        }
+        expr
+    }
+}
+
 /// Returns whether the cached parallel VM fast-path is eligible for this run.
 fn should_try_parallel_vm_fast_path(flags: &DriverFlags, is_multimodule: bool) -> bool {
     is_multimodule && flags.allow_vm_cache() && !flags.cache.no_cache
@@ -148,6 +178,11 @@ fn prepare_run_context(request: RunProgramRequest<'_>) -> Result<RunContext, Str
     if request.flags.runtime.profiling {
         compiler.set_profiling(true);
     }
+    if request.flags.call_trace.enabled() {
+        compiler.set_call_trace(Some(CallTraceFilter::new(
+            &request.flags.call_trace.patterns,
+        )));
+    }
 
     Ok(RunContext {
         source,
@@ -251,6 +286,7 @@ fn handle_dump_requests(
         dump_lir: request.flags.dumps.dump_lir,
         dump_cfg: request.flags.dumps.dump_cfg,
         dump_lir_llvm: request.flags.dumps.dump_lir_llvm,
+        core_eval: request.flags.dumps.core_eval,
     })
 }
 
@@ -284,6 +320,9 @@ fn dispatch_backend(ctx: &mut RunContext, request: RunProgramRequest<'_>) {
                 compile_backend_label: compile_backend_label(request.flags),
                 execute_backend_label: execute_backend_label(request.flags),
             },
+            profile: request.flags.profile.clone(),
+            call_trace: request.flags.call_trace.clone(),
+            effect_log: request.flags.effect_log.clone(),
         });
         return;
     }
@@ -292,6 +331,7 @@ fn dispatch_backend(ctx: &mut RunContext, request: RunProgramRequest<'_>) {
     run_vm(VmRunRequest {
         compiler: &mut ctx.compiler,
         program: &ctx.program,
+        graph: &ctx.graph,
         path: request.path,
         source: ctx.source.as_str(),
         is_multimodule: ctx.is_multimodule,
@@ -352,6 +392,94 @@ pub(crate) fn run_file(request: RunProgramRequest<'_>) {
     }
 }
 
+/// Lowers the entry program through the Aether passes so `check` reports contract violations
+/// for entry files that are not themselves modules and were never lowered by
+/// `compile_modules`.
+fn verify_entry_aether(ctx: &mut RunContext, request: RunProgramRequest<'_>) {
+    if has_error_diagnostics(&ctx.all_diagnostics) {
+        return;
+    }
+    ctx.compiler.set_file_path(request.path.to_string());
+    if let Err(mut diagnostic) = ctx
+        .compiler
+        .lower_aether_report_program(&ctx.program, request.session.enable_optimize)
+    {
+        if diagnostic.file().is_none() {
+            diagnostic.set_file(request.path.to_string());
+        }
+        ctx.all_diagnostics.push(diagnostic);
+    }
+}
+
+/// Runs the pipeline up to type inference, static validation and the Aether verify passes,
+/// reports diagnostics and stops without executing the program.
+pub(crate) fn check_file(request: RunProgramRequest<'_>) {
+    let mut ctx = match prepare_run_context(request) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    if has_error_diagnostics(&ctx.all_diagnostics) {
+        emit_compile_diagnostics_or_exit(&ctx, request);
+    }
+
+    compile_modules_for_run(&mut ctx, request);
+    verify_entry_aether(&mut ctx, request);
+    emit_compile_diagnostics_or_exit(&ctx, request);
+
+    let check_ms = ctx.compile_start.elapsed().as_secs_f64() * 1000.0;
+    eprintln!(
+        "Checked {} ({} module{}) in {:.1}ms: no errors",
+        request.path,
+        ctx.module_count,
+        if ctx.module_count == 1 { "" } else { "s" },
+        ctx.parse_ms + check_ms
+    );
+}
+
+/// Compiles the program for the VM and serves a Debug Adapter Protocol session for it on
+/// stdio (`flux debug --dap`). Module caches are bypassed because cached bytecode does not
+/// carry local variable names.
+pub(crate) fn debug_file(request: RunProgramRequest<'_>) {
+    if request.flags.is_native_backend() {
+        eprintln!("Error: flux debug runs on the VM; remove --native.");
+        std::process::exit(1);
+    }
+    let mut flags = request.flags.clone();
+    flags.cache.no_cache = true;
+    let request = RunProgramRequest {
+        flags: &flags,
+        ..request
+    };
+
+    let mut ctx = match prepare_run_context(request) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    if has_error_diagnostics(&ctx.all_diagnostics) {
+        emit_compile_diagnostics_or_exit(&ctx, request);
+    }
+    compile_modules_for_run(&mut ctx, request);
+    emit_compile_diagnostics_or_exit(&ctx, request);
+
+    match flux::dap::run_stdio(ctx.compiler.bytecode(), &ctx.entry_path) {
+        Ok(None) => {}
+        Ok(Some(err)) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: debug adapter I/O failed: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
@@ -366,6 +494,7 @@ mod tests {
             test_support::base_flags,
         },
         syntax::{
+            expression::{ExprId, Expression},
             program::Program,
             statement::{ImportExposing, Statement},
             symbol::Symbol,
@@ -514,4 +643,32 @@ mod tests {
             other => panic!("expected import statement, got {other:?}"),
         }
     }
+
+    #[test]
+    fn merge_programs_keeps_expression_ids_distinct_across_modules() {
+        let module = |value| Program {
+            statements: vec![Statement::Expression {
+                expression: Expression::Integer {
+                    value,
+                    span: Span::default(),
+                    id: ExprId(1),
+                },
+                has_semicolon: false,
+                span: Span::default(),
+            }],
+            span: Span::default(),
+        };
+
+        let merged = merge_programs([&module(1), &module(2)]);
+
+        let ids: Vec<ExprId> = merged
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Expression { expression, .. } => expression.expr_id(),
+                other => panic!("expected expression statement, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(ids, vec![ExprId(1), ExprId(2)]);
+    }
 }
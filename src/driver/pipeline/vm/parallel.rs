@@ -6,6 +6,7 @@ use std::{
 };
 
 use crate::{
+    ast::type_infer::program_has_typed_holes,
     bytecode::bytecode_cache::{hash_bytes, hash_cache_key, module_cache::ModuleBytecodeCache},
     compiler::{
         module_interface::{
@@ -124,7 +125,8 @@ fn compile_parallel_module(
         current_interface.is_some(),
         is_entry,
         has_cached_artifact,
-    ) {
+    ) && !program_has_typed_holes(&node.program)
+    {
         return ParallelModuleResult {
             path: node.path.clone(),
             needs_serial_warning_replay: false,
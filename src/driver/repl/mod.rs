@@ -0,0 +1,215 @@
+//! Interactive `flux repl` loop.
+//!
+//! Input lines are read until their brackets balance, then handed to a [`ReplSession`]. Lines
+//! starting with `:` are session commands rather than Flux source.
+
+mod session;
+
+use std::io::{self, BufRead, Write};
+
+pub(crate) use session::{ReplOptions, ReplSession};
+
+use crate::syntax::{lexer::Lexer, token_type::TokenType};
+
+const PROMPT: &str = "flux> ";
+const CONTINUATION_PROMPT: &str = "....> ";
+
+const HELP: &str = "\
+Commands:
+  <expr>            evaluate an expression and print `value : Type`
+  let x = <expr>    bind a value for later inputs
+  fn f(..) { .. }   declare functions, data types, classes, instances and effects
+  :type <expr>      show the inferred type without running
+  :effects <expr>   show the effects the expression performs
+  :load <path>      compile a file into the session (its `fn main` is skipped)
+  :reload           reload every file brought in with :load
+  :help             show this help
+  :quit             leave the REPL";
+
+/// Runs the REPL on stdin/stdout until end of input or `:quit`.
+pub(crate) fn run_stdio(options: ReplOptions) -> io::Result<()> {
+    let mut session = ReplSession::new(options);
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    writeln!(
+        stdout,
+        "Flux {} REPL. Type :help for commands.",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    run_loop(&mut session, &mut stdin.lock(), &mut stdout, true)
+}
+
+/// Reads inputs from `reader` and writes results and errors to `out`.
+///
+/// Prompts are written only when `interactive` is set, so scripted sessions produce just the
+/// results.
+pub(crate) fn run_loop<R: BufRead, W: Write>(
+    session: &mut ReplSession,
+    reader: &mut R,
+    out: &mut W,
+    interactive: bool,
+) -> io::Result<()> {
+    let mut pending = String::new();
+    loop {
+        if interactive {
+            let prompt = if pending.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            write!(out, "{prompt}")?;
+            out.flush()?;
+        }
+
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            if !pending.trim().is_empty() {
+                respond(out, session.eval(&pending))?;
+            }
+            return Ok(());
+        }
+
+        if pending.is_empty() && line.trim_start().starts_with(':') {
+            match run_command(session, line.trim()) {
+                Some(response) => respond(out, response)?,
+                None => return Ok(()),
+            }
+            continue;
+        }
+
+        pending.push_str(&line);
+        if pending.trim().is_empty() {
+            pending.clear();
+            continue;
+        }
+        if bracket_depth(&pending) > 0 {
+            continue;
+        }
+        let input = std::mem::take(&mut pending);
+        respond(out, session.eval(&input))?;
+    }
+}
+
+/// Runs a `:command`. Returns `None` when the session should end.
+fn run_command(session: &mut ReplSession, line: &str) -> Option<Result<Option<String>, String>> {
+    let (command, argument) = match line.split_once(char::is_whitespace) {
+        Some((command, argument)) => (command, argument.trim()),
+        None => (line, ""),
+    };
+    let response = match command {
+        ":q" | ":quit" => return None,
+        ":h" | ":help" => Ok(Some(HELP.to_string())),
+        ":t" | ":type" => session
+            .type_of(argument)
+            .map(|ty| Some(format!("{argument} : {ty}"))),
+        ":e" | ":effects" => session.effects_of(argument).map(Some),
+        ":l" | ":load" if argument.is_empty() => Err("usage: :load <path>".to_string()),
+        ":l" | ":load" => session.load(argument.as_ref()).map(Some),
+        ":r" | ":reload" => session.reload().map(Some),
+        _ => Err(format!(
+            "unknown command `{command}`; type :help for commands"
+        )),
+    };
+    Some(response)
+}
+
+fn respond<W: Write>(out: &mut W, response: Result<Option<String>, String>) -> io::Result<()> {
+    match response {
+        Ok(Some(text)) => writeln!(out, "{text}"),
+        Ok(None) => Ok(()),
+        Err(message) => writeln!(out, "{}", message.trim_end()),
+    }
+}
+
+/// Net count of open brackets in `source`; positive while an input is still incomplete.
+fn bracket_depth(source: &str) -> i32 {
+    let mut lexer = Lexer::new(source);
+    let mut depth = 0;
+    loop {
+        match lexer.next_token().token_type {
+            TokenType::LParen | TokenType::LBrace | TokenType::LBracket => depth += 1,
+            TokenType::RParen | TokenType::RBrace | TokenType::RBracket => depth -= 1,
+            TokenType::Eof => return depth,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{ReplOptions, ReplSession, bracket_depth, run_loop};
+
+    fn run_script(script: &str) -> String {
+        let mut session = ReplSession::new(ReplOptions::default());
+        let mut out = Vec::new();
+        run_loop(&mut session, &mut Cursor::new(script), &mut out, false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn bracket_depth_tracks_open_blocks() {
+        assert_eq!(bracket_depth("fn f(x) {"), 1);
+        assert_eq!(bracket_depth("fn f(x) { x }"), 0);
+        assert_eq!(bracket_depth("\"{\""), 0);
+    }
+
+    #[test]
+    fn globals_persist_across_inputs() {
+        let output = run_script("let x = 40\nfn inc(n) { n + 1 }\ninc(inc(x))\n");
+        assert_eq!(output, "x : Int\ninc : (Int) -> Int\n42 : Int\n");
+    }
+
+    #[test]
+    fn multi_line_declarations_and_data_types_carry_over() {
+        let output = run_script(
+            "data Shape { Circle(Int), Square(Int) }\n\
+             fn area(s) {\n  match s {\n    Circle(r) -> r * r * 3,\n    Square(w) -> w * w\n  }\n}\n\
+             area(Square(4))\n",
+        );
+        assert_eq!(output, "area : (Shape) -> Int\n16 : Int\n");
+    }
+
+    #[test]
+    fn redefinition_shadows_and_failed_inputs_are_rolled_back() {
+        let output = run_script(
+            "fn f(n) { n + 1 }\nfn g(n) { f(n) }\nfn f(n) { n + 2 }\nfn f(n) { missing }\nf(1)\ng(1)\n",
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[..3],
+            ["f : (Int) -> Int", "g : (Int) -> Int", "f : (Int) -> Int"]
+        );
+        assert!(output.contains("missing"));
+        assert_eq!(lines[lines.len() - 2..], ["3 : Int", "2 : Int"]);
+    }
+
+    #[test]
+    fn type_and_effects_commands_do_not_run_the_expression() {
+        let output =
+            run_script(":type [1, 2]\n:effects println(\"side effect\")\n:effects 1 + 1\n");
+        assert_eq!(output, "[1, 2] : List<Int>\nConsole\npure\n");
+    }
+
+    #[test]
+    fn load_skips_main_and_reload_picks_up_edits() {
+        let path = std::env::temp_dir().join(format!("flux_repl_load_{}.flx", std::process::id()));
+        std::fs::write(&path, "fn triple(x) { x * 3 }\nfn main() { print(0) }\n").unwrap();
+
+        let mut session = ReplSession::new(ReplOptions::default());
+        assert!(session.load(&path).is_ok());
+        assert_eq!(
+            session.eval("triple(5)").unwrap().as_deref(),
+            Some("15 : Int")
+        );
+
+        std::fs::write(&path, "fn triple(x) { x * 30 }\n").unwrap();
+        assert!(session.reload().is_ok());
+        assert_eq!(
+            session.eval("triple(5)").unwrap().as_deref(),
+            Some("150 : Int")
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
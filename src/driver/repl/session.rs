@@ -0,0 +1,550 @@
+//! Incremental compile-and-run session behind `flux repl`.
+//!
+//! One [`Compiler`] and one [`VM`] live for the whole session. Every input is parsed, given the
+//! Flow prelude imports, and compiled as a fresh entry module on top of the accumulated compiler
+//! state: the global symbol table, constant pool and main-scope instructions keep growing, and
+//! HM sees earlier bindings through [`Compiler::extend_repl_schemes`]. The VM then runs only the
+//! instructions appended since the previous input, so its globals persist.
+//!
+//! Executable input is wrapped in a synthetic `fn main() { ... }`. That gives it the same
+//! default effect handlers as a program entry point and lets the compiler emit the trailing
+//! `main()` call whose popped value is the result.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::type_infer::render_scheme_canonical,
+    compiler::Compiler,
+    diagnostics::{
+        Diagnostic, DiagnosticCategory, DiagnosticPhase, DiagnosticsAggregator, Severity,
+        position::Span,
+    },
+    driver::{
+        frontend::{collect_roots, inject_flow_prelude},
+        module_compile::{effective_module_strictness, tag_module_diagnostics},
+        shared::{sort_stdlib_first, tag_and_attach_file},
+    },
+    syntax::{
+        Identifier,
+        block::Block,
+        expression::{ExprIdGen, Expression},
+        lexer::Lexer,
+        module_graph::ModuleGraph,
+        parser::Parser,
+        program::Program,
+        statement::Statement,
+    },
+    types::{infer_type::InferType, scheme::Scheme, type_constructor::TypeConstructor},
+    vm::VM,
+};
+
+/// Display name of the virtual entry file every input is compiled as.
+pub(crate) const REPL_FILE: &str = "<repl>";
+
+/// Name of the wrapper function used by `:type` and `:effects`. It is not an entry point, so
+/// its inferred row keeps the effects the expression performs.
+const PROBE_FN: &str = "__repl_probe";
+
+/// Module search options for a REPL session.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReplOptions {
+    pub(crate) roots: Vec<PathBuf>,
+    pub(crate) roots_only: bool,
+    pub(crate) strict_mode: bool,
+}
+
+/// How an input is turned into a compilable entry program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputMode {
+    /// Declarations are compiled as-is; executable statements run inside `fn main`.
+    Eval,
+    /// The expression is wrapped in [`PROBE_FN`] and never executed.
+    Probe,
+    /// A whole file; its own `fn main` is dropped so loading only defines things.
+    Load,
+}
+
+/// What a successfully compiled input produced.
+struct CompiledInput {
+    /// Top-level functions declared by the input.
+    functions: Vec<Identifier>,
+    /// Binding introduced by a single `let` input.
+    let_binding: Option<Identifier>,
+    /// Whether executable statements were wrapped in `fn main`.
+    has_main: bool,
+}
+
+/// Long-lived compiler and VM state for one REPL session.
+pub(crate) struct ReplSession {
+    options: ReplOptions,
+    compiler: Compiler,
+    vm: VM,
+    /// Length of the main-scope instruction stream that has already run.
+    executed: usize,
+    /// Imported modules that have been compiled into the session.
+    compiled_modules: HashSet<PathBuf>,
+    /// Files brought in with `:load`, in load order, for `:reload`.
+    loaded_files: Vec<PathBuf>,
+    /// Imports, data types and effect declarations from earlier inputs. They carry no code, so
+    /// they are replayed in front of every input for the collection passes and HM to see.
+    replayed: Vec<Statement>,
+}
+
+impl ReplSession {
+    pub(crate) fn new(options: ReplOptions) -> Self {
+        let compiler = Compiler::new_with_interner(REPL_FILE, Default::default());
+        let vm = VM::new(compiler.bytecode());
+        Self {
+            options,
+            compiler,
+            vm,
+            executed: 0,
+            compiled_modules: HashSet::new(),
+            loaded_files: Vec::new(),
+            replayed: Vec::new(),
+        }
+    }
+
+    /// Compiles and runs one input.
+    ///
+    /// Returns the line to echo back: `value : Type` for expressions, `name : Type` for a
+    /// `let` or a function declaration, and `None` for `Unit` results and other declarations.
+    pub(crate) fn eval(&mut self, source: &str) -> Result<Option<String>, String> {
+        let compiled = self.compile_input(Path::new(REPL_FILE), source, InputMode::Eval)?;
+        self.run_pending()?;
+
+        let mut lines: Vec<String> = compiled
+            .functions
+            .iter()
+            .filter_map(|&name| self.describe_binding(name))
+            .collect();
+        if compiled.has_main {
+            let main = self.compiler.interner.intern("main");
+            let result = self.fn_return_scheme(main);
+            let value = self.vm.last_popped_stack_elem();
+            match compiled.let_binding {
+                Some(name) => {
+                    let binding = self.compiler.symbol_table.define(name, Default::default());
+                    self.vm.set_global_value(binding.index, value);
+                    if let Some(scheme) = result {
+                        lines.push(self.describe_scheme(name, &scheme));
+                        self.compiler.extend_repl_schemes([(name, scheme)]);
+                    }
+                }
+                None => match result {
+                    Some(scheme) if is_unit(&scheme.infer_type) => {}
+                    Some(scheme) => lines.push(format!(
+                        "{value} : {}",
+                        render_scheme_canonical(&self.compiler.interner, &scheme)
+                    )),
+                    None => lines.push(value.to_string()),
+                },
+            }
+        }
+
+        Ok((!lines.is_empty()).then(|| lines.join("\n")))
+    }
+
+    /// Infers the type of an expression without running it.
+    pub(crate) fn type_of(&mut self, source: &str) -> Result<String, String> {
+        self.compile_input(Path::new(REPL_FILE), source, InputMode::Probe)?;
+        let probe = self.compiler.interner.intern(PROBE_FN);
+        self.fn_return_scheme(probe)
+            .map(|scheme| render_scheme_canonical(&self.compiler.interner, &scheme))
+            .ok_or_else(|| "could not infer a type for this expression".to_string())
+    }
+
+    /// Infers the effect row an expression performs without running it.
+    pub(crate) fn effects_of(&mut self, source: &str) -> Result<String, String> {
+        self.compile_input(Path::new(REPL_FILE), source, InputMode::Probe)?;
+        let probe = self.compiler.interner.intern(PROBE_FN);
+        let Some(InferType::Fun(_, _, row)) = self
+            .compiler
+            .hm_type_env()
+            .lookup(probe)
+            .map(|scheme| scheme.infer_type.clone())
+        else {
+            return Err("could not infer effects for this expression".to_string());
+        };
+        // HM rows only carry effects that signatures mention; the compiler's effect inference
+        // also covers unannotated functions.
+        let inferred = self.compiler.inferred_effects(probe, 0).cloned();
+        let mut effects: Vec<String> = row
+            .concrete()
+            .iter()
+            .chain(inferred.iter().flatten())
+            .map(|effect| self.compiler.interner.resolve(*effect).to_string())
+            .collect();
+        effects.sort();
+        effects.dedup();
+        if row.tail().is_some() {
+            effects.push("|_".to_string());
+        }
+        if effects.is_empty() {
+            Ok("pure".to_string())
+        } else {
+            Ok(effects.join(", "))
+        }
+    }
+
+    /// Compiles a source file into the session and runs its top level.
+    ///
+    /// The file's own `fn main` is skipped, so loading a program only brings its
+    /// declarations into scope.
+    pub(crate) fn load(&mut self, path: &Path) -> Result<String, String> {
+        let source = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        let compiled = self.compile_input(path, &source, InputMode::Load)?;
+        self.run_pending()?;
+        if !self.loaded_files.iter().any(|loaded| loaded == path) {
+            self.loaded_files.push(path.to_path_buf());
+        }
+        Ok(format!(
+            "loaded {} ({} {})",
+            path.display(),
+            compiled.functions.len(),
+            if compiled.functions.len() == 1 {
+                "function"
+            } else {
+                "functions"
+            }
+        ))
+    }
+
+    /// Re-reads and recompiles every file brought in with `:load`.
+    ///
+    /// Modules those files import were compiled once and are not reloaded.
+    pub(crate) fn reload(&mut self) -> Result<String, String> {
+        if self.loaded_files.is_empty() {
+            return Err("no files loaded; use `:load <path>` first".to_string());
+        }
+        let files = self.loaded_files.clone();
+        let mut lines = Vec::new();
+        for path in files {
+            lines.push(self.load(&path)?);
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn compile_input(
+        &mut self,
+        entry_path: &Path,
+        source: &str,
+        mode: InputMode,
+    ) -> Result<CompiledInput, String> {
+        let entry_file = entry_path.to_string_lossy().to_string();
+        let interner = std::mem::take(&mut self.compiler.interner);
+        let mut parser = Parser::new(Lexer::new_with_interner(source, interner));
+        let program = parser.parse_program();
+        // A bare `Some(x)` typed at the prompt is the input's result, not an orphaned pattern.
+        parser.errors.retain(|diag| {
+            !(diag.code() == Some("E034")
+                && diag.category() == Some(DiagnosticCategory::ParserPattern))
+        });
+        if !parser.errors.is_empty() {
+            let mut errors = std::mem::take(&mut parser.errors);
+            self.compiler.interner = parser.take_interner();
+            tag_and_attach_file(&mut errors, DiagnosticPhase::Parse, &entry_file);
+            return Err(render(&errors, &entry_file, source));
+        }
+
+        let mut interner = parser.take_interner();
+        let main = interner.intern("main");
+        let probe = interner.intern(PROBE_FN);
+        parser.restore_interner(interner);
+        let (mut program, compiled) = match shape_program(program, mode, main, probe) {
+            Ok(shaped) => shaped,
+            Err(message) => {
+                self.compiler.interner = parser.take_interner();
+                return Err(message);
+            }
+        };
+        let declared_here: Vec<Statement> = program
+            .statements
+            .iter()
+            .filter(|stmt| is_replayed(stmt))
+            .cloned()
+            .collect();
+        let mut statements: Vec<Statement> = self
+            .replayed
+            .iter()
+            .filter(|old| !declared_here.iter().any(|new| same_declaration(old, new)))
+            .cloned()
+            .collect();
+        statements.append(&mut program.statements);
+        program.statements = statements;
+        inject_flow_prelude(&mut program, &mut parser, false);
+
+        // Redefinitions shadow: a fresh global slot is bound, and code compiled against the old
+        // one keeps using it. Everything is restored if the input fails to compile.
+        let checkpoint = self.compiler.repl_checkpoint();
+        for name in compiled.functions.iter().chain([&main, &probe]) {
+            self.compiler.forget_repl_binding(*name);
+        }
+
+        let roots = collect_roots(entry_path, &self.options.roots, self.options.roots_only);
+        let interner = parser.take_interner();
+        let graph_result =
+            ModuleGraph::build_with_entry_and_roots(entry_path, &program, interner, &roots);
+        self.compiler.interner = graph_result.interner;
+
+        let mut diagnostics = graph_result.diagnostics;
+        tag_and_attach_file(&mut diagnostics, DiagnosticPhase::ModuleGraph, &entry_file);
+        let graph = graph_result.graph;
+        let failed = graph_result.failed_modules;
+        let entry_kind = graph.entry_node().map(|node| node.kind).unwrap_or_default();
+        let entry_node_path = graph.entry_node().map(|node| node.path.clone());
+
+        let mut ordered_nodes = graph.topo_order();
+        sort_stdlib_first(&mut ordered_nodes, |node| node.kind);
+        for node in ordered_nodes {
+            let is_entry = Some(&node.path) == entry_node_path.as_ref();
+            if !is_entry && self.compiled_modules.contains(&node.path) {
+                continue;
+            }
+            if node.imports.iter().any(|e| failed.contains(&e.target_path)) {
+                continue;
+            }
+            let file_path = if is_entry {
+                entry_file.clone()
+            } else {
+                node.path.to_string_lossy().to_string()
+            };
+            self.compiler.set_file_path(file_path);
+            self.compiler.set_current_module_kind(node.kind);
+            self.compiler.set_strict_mode(effective_module_strictness(
+                node.kind,
+                entry_kind,
+                self.options.strict_mode,
+            ));
+            self.compiler.set_strict_require_main(false);
+            let result = self.compiler.compile_with_opts(&node.program, false, false);
+            // Warnings are not echoed; only errors interrupt an input.
+            let _ = self.compiler.take_warnings();
+            match result {
+                Ok(()) if !is_entry => {
+                    self.compiled_modules.insert(node.path.clone());
+                }
+                Ok(()) => {}
+                Err(mut diags) => {
+                    if is_entry {
+                        tag_and_attach_file(&mut diags, DiagnosticPhase::TypeCheck, &entry_file);
+                    } else {
+                        tag_module_diagnostics(&mut diags, DiagnosticPhase::TypeCheck, &node.path);
+                    }
+                    diagnostics.append(&mut diags);
+                }
+            }
+        }
+
+        let has_errors = diagnostics
+            .iter()
+            .any(|diag| diag.severity() == Severity::Error);
+        if has_errors || mode == InputMode::Probe {
+            // Nothing compiled for this input may run later: skip past it.
+            self.executed = self.instructions_len();
+        }
+        if has_errors {
+            self.compiler.restore_repl_checkpoint(checkpoint);
+            return Err(render(&diagnostics, &entry_file, source));
+        }
+        self.replayed
+            .retain(|old| !declared_here.iter().any(|new| same_declaration(old, new)));
+        self.replayed.extend(declared_here);
+        self.compiler.retain_repl_declarations();
+        self.record_function_schemes(&compiled.functions);
+        Ok(compiled)
+    }
+
+    /// Runs the instructions appended since the previous input.
+    fn run_pending(&mut self) -> Result<(), String> {
+        let start = self.executed;
+        self.executed = self.instructions_len();
+        self.vm.run_from(self.compiler.bytecode(), start)
+    }
+
+    fn instructions_len(&self) -> usize {
+        self.compiler.bytecode().instructions.len()
+    }
+
+    fn record_function_schemes(&mut self, functions: &[Identifier]) {
+        let schemes: Vec<(Identifier, Scheme)> = functions
+            .iter()
+            .filter_map(|&name| {
+                self.compiler
+                    .hm_type_env()
+                    .lookup(name)
+                    .map(|scheme| (name, scheme.clone()))
+            })
+            .collect();
+        self.compiler.extend_repl_schemes(schemes);
+    }
+
+    /// Renders `name : scheme` for a function the last input declared.
+    fn describe_binding(&self, name: Identifier) -> Option<String> {
+        let scheme = self.compiler.hm_type_env().lookup(name)?;
+        Some(self.describe_scheme(name, scheme))
+    }
+
+    fn describe_scheme(&self, name: Identifier, scheme: &Scheme) -> String {
+        format!(
+            "{} : {}",
+            self.compiler.interner.resolve(name),
+            render_scheme_canonical(&self.compiler.interner, scheme)
+        )
+    }
+
+    /// Returns the scheme of a zero-argument wrapper function's result type.
+    fn fn_return_scheme(&self, name: Identifier) -> Option<Scheme> {
+        let scheme = self.compiler.hm_type_env().lookup(name)?;
+        let InferType::Fun(_, ret, _) = &scheme.infer_type else {
+            return None;
+        };
+        Some(Scheme {
+            forall: scheme.forall.clone(),
+            constraints: scheme.constraints.clone(),
+            infer_type: (**ret).clone(),
+        })
+    }
+}
+
+/// Splits an input into declarations and executable statements and wraps the latter.
+fn shape_program(
+    program: Program,
+    mode: InputMode,
+    main: Identifier,
+    probe: Identifier,
+) -> Result<(Program, CompiledInput), String> {
+    let mut ids = ExprIdGen::resuming_past_program(&program);
+    let (mut declarations, mut body): (Vec<Statement>, Vec<Statement>) =
+        program.statements.into_iter().partition(is_declaration);
+
+    if mode == InputMode::Load {
+        declarations
+            .retain(|stmt| !matches!(stmt, Statement::Function { name, .. } if *name == main));
+        declarations.append(&mut body);
+    }
+    let functions = declarations
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Function { name, .. } => Some(*name),
+            _ => None,
+        })
+        .collect();
+
+    let mut let_binding = None;
+    let has_main = mode != InputMode::Load && !body.is_empty();
+    if has_main {
+        if mode == InputMode::Probe && !declarations.is_empty() {
+            return Err("expected a single expression".to_string());
+        }
+        if let [Statement::Let { name, span, .. }] = body.as_slice() {
+            let (name, span) = (*name, *span);
+            let_binding = Some(name);
+            body.push(Statement::Expression {
+                expression: Expression::Identifier {
+                    name,
+                    span,
+                    id: ids.next_id(),
+                },
+                has_semicolon: false,
+                span,
+            });
+        }
+        let span = Span::new(body[0].span().start, body[body.len() - 1].span().end);
+        declarations.push(Statement::Function {
+            is_public: false,
+            fip: None,
+            intrinsic: None,
+            name: if mode == InputMode::Probe {
+                probe
+            } else {
+                main
+            },
+            type_params: Vec::new(),
+            parameters: Vec::new(),
+            parameter_types: Vec::new(),
+            return_type: None,
+            effects: Vec::new(),
+            body: Block {
+                statements: body,
+                span,
+            },
+            span,
+        });
+    } else if mode == InputMode::Probe {
+        return Err("expected an expression".to_string());
+    }
+
+    Ok((
+        Program {
+            statements: declarations,
+            ..program
+        },
+        CompiledInput {
+            functions,
+            let_binding,
+            has_main,
+        },
+    ))
+}
+
+fn is_declaration(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::Function { .. }
+            | Statement::Data { .. }
+            | Statement::Class { .. }
+            | Statement::Instance { .. }
+            | Statement::EffectDecl { .. }
+            | Statement::EffectAlias { .. }
+            | Statement::Import { .. }
+            | Statement::Module { .. }
+    )
+}
+
+fn is_replayed(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::Import { .. }
+            | Statement::Data { .. }
+            | Statement::EffectDecl { .. }
+            | Statement::EffectAlias { .. }
+    )
+}
+
+/// Whether `new` redeclares what `old` declared, so only the newer one is replayed.
+fn same_declaration(old: &Statement, new: &Statement) -> bool {
+    match (old, new) {
+        (
+            Statement::Import {
+                name: a, alias: x, ..
+            },
+            Statement::Import {
+                name: b, alias: y, ..
+            },
+        ) => a == b && x == y,
+        (Statement::Data { name: a, .. }, Statement::Data { name: b, .. })
+        | (Statement::EffectDecl { name: a, .. }, Statement::EffectDecl { name: b, .. })
+        | (Statement::EffectAlias { name: a, .. }, Statement::EffectAlias { name: b, .. }) => {
+            a == b
+        }
+        _ => false,
+    }
+}
+
+fn is_unit(ty: &InferType) -> bool {
+    matches!(ty, InferType::Con(TypeConstructor::Unit))
+}
+
+fn render(diagnostics: &[Diagnostic], file: &str, source: &str) -> String {
+    DiagnosticsAggregator::new(diagnostics)
+        .with_default_source(file, source)
+        .with_file_headers(false)
+        .with_stage_filtering(true)
+        .render()
+}
@@ -0,0 +1,283 @@
+//! Statistics, text report and baseline file of `flux bench`.
+//!
+//! Every benchmark is summarised by the mean, median and standard deviation of its samples,
+//! with outliers classified by Tukey's fences. The summaries are written to a baseline JSON
+//! file keyed by `<file>::<benchmark>`; the next run compares its means against it and reports
+//! each benchmark as regressed, improved or unchanged.
+
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// Relative change of the mean within which a benchmark counts as unchanged.
+pub(crate) const NOISE_THRESHOLD: f64 = 0.05;
+
+/// Summary statistics of the samples of one benchmark, in nanoseconds per iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BenchStats {
+    pub(crate) mean_ns: f64,
+    pub(crate) median_ns: f64,
+    pub(crate) std_dev_ns: f64,
+    pub(crate) samples: usize,
+    /// Samples between 1.5 and 3 interquartile ranges outside the quartiles.
+    pub(crate) mild_outliers: usize,
+    /// Samples more than 3 interquartile ranges outside the quartiles.
+    pub(crate) severe_outliers: usize,
+}
+
+impl BenchStats {
+    /// Summarises `samples`; `None` when there are none.
+    pub(crate) fn of(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len() as f64;
+        let mean_ns = sorted.iter().sum::<f64>() / n;
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|x| (x - mean_ns).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+        let iqr = q3 - q1;
+        let outside = |fence: f64| {
+            sorted
+                .iter()
+                .filter(|&&x| x < q1 - fence * iqr || x > q3 + fence * iqr)
+                .count()
+        };
+        let severe_outliers = outside(3.0);
+        Some(Self {
+            mean_ns,
+            median_ns: quantile(&sorted, 0.5),
+            std_dev_ns: variance.sqrt(),
+            samples: sorted.len(),
+            mild_outliers: outside(1.5) - severe_outliers,
+            severe_outliers,
+        })
+    }
+}
+
+/// The `q` quantile of sorted samples, interpolating between neighbours.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (position - low as f64)
+}
+
+/// How a benchmark's mean compares to its baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BenchChange {
+    /// The benchmark is not in the baseline.
+    New,
+    Unchanged {
+        ratio: f64,
+    },
+    Regressed {
+        ratio: f64,
+    },
+    Improved {
+        ratio: f64,
+    },
+}
+
+impl BenchChange {
+    /// Compares `current` to the baseline entry `previous`, if any.
+    pub(crate) fn between(previous: Option<&BenchStats>, current: &BenchStats) -> Self {
+        let Some(previous) = previous.filter(|previous| previous.mean_ns > 0.0) else {
+            return Self::New;
+        };
+        let ratio = current.mean_ns / previous.mean_ns - 1.0;
+        if ratio > NOISE_THRESHOLD {
+            Self::Regressed { ratio }
+        } else if ratio < -NOISE_THRESHOLD {
+            Self::Improved { ratio }
+        } else {
+            Self::Unchanged { ratio }
+        }
+    }
+}
+
+/// One measured benchmark of a run.
+#[derive(Debug, Clone)]
+pub(crate) struct BenchReport {
+    /// `<file>::<benchmark>`, the baseline key.
+    pub(crate) key: String,
+    pub(crate) name: String,
+    pub(crate) iterations_per_sample: u64,
+    pub(crate) stats: BenchStats,
+    pub(crate) baseline: Option<BenchStats>,
+}
+
+impl BenchReport {
+    pub(crate) fn change(&self) -> BenchChange {
+        BenchChange::between(self.baseline.as_ref(), &self.stats)
+    }
+}
+
+/// Saved results of earlier `flux bench` runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct BenchBaseline {
+    pub(crate) benchmarks: BTreeMap<String, BenchStats>,
+}
+
+impl BenchBaseline {
+    /// Reads the baseline at `path`; a missing or unreadable file is an empty baseline.
+    pub(crate) fn load(path: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            eprintln!(
+                "warning: ignoring unreadable bench baseline {}: {e}",
+                path.display()
+            );
+            Self::default()
+        })
+    }
+
+    /// Records the results of `reports`, keeping the entries of benchmarks that did not run.
+    pub(crate) fn update(&mut self, reports: &[BenchReport]) {
+        for report in reports {
+            self.benchmarks.insert(report.key.clone(), report.stats);
+        }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        json.push('\n');
+        fs::write(path, json)
+    }
+}
+
+/// Renders the result of one benchmark.
+pub(crate) fn render_bench_result(report: &BenchReport) -> String {
+    let stats = &report.stats;
+    let mut out = format!("\n{}\n", report.name);
+    let _ = writeln!(
+        out,
+        "  mean {}   median {}   std dev {}",
+        format_duration(stats.mean_ns),
+        format_duration(stats.median_ns),
+        format_duration(stats.std_dev_ns)
+    );
+    let _ = write!(
+        out,
+        "  {} samples x {} iterations",
+        stats.samples, report.iterations_per_sample
+    );
+    match stats.mild_outliers + stats.severe_outliers {
+        0 => out.push('\n'),
+        outliers => {
+            let _ = writeln!(
+                out,
+                ", {outliers} outliers ({} mild, {} severe)",
+                stats.mild_outliers, stats.severe_outliers
+            );
+        }
+    }
+    let _ = writeln!(out, "  {}", render_change(report));
+    out
+}
+
+fn render_change(report: &BenchReport) -> String {
+    let was = |ratio: f64, verdict: &str| {
+        let previous = report.baseline.map_or(0.0, |baseline| baseline.mean_ns);
+        format!(
+            "{verdict} {:+.2}% (baseline {})",
+            ratio * 100.0,
+            format_duration(previous)
+        )
+    };
+    match report.change() {
+        BenchChange::New => "no baseline".to_string(),
+        BenchChange::Unchanged { ratio } => was(ratio, "no change"),
+        BenchChange::Regressed { ratio } => was(ratio, "regressed"),
+        BenchChange::Improved { ratio } => was(ratio, "improved"),
+    }
+}
+
+/// Formats nanoseconds with the largest unit that keeps the value at least 1.
+pub(crate) fn format_duration(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{ns:.2} ns")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchBaseline, BenchChange, BenchReport, BenchStats, format_duration};
+
+    #[test]
+    fn stats_summarise_samples_and_classify_outliers() {
+        let mut samples = vec![10.0, 11.0, 12.0, 10.0, 11.0, 12.0, 10.0, 11.0, 12.0];
+        samples.push(16.0);
+        samples.push(40.0);
+        let stats = BenchStats::of(&samples).unwrap();
+
+        assert_eq!(stats.samples, 11);
+        assert!((stats.mean_ns - 155.0 / 11.0).abs() < 1e-9);
+        assert_eq!(stats.median_ns, 11.0);
+        assert_eq!((stats.mild_outliers, stats.severe_outliers), (1, 1));
+        assert!(stats.std_dev_ns > 8.0 && stats.std_dev_ns < 9.0);
+        assert!(BenchStats::of(&[]).is_none());
+    }
+
+    #[test]
+    fn changes_beyond_the_noise_threshold_are_regressions_or_improvements() {
+        let stats = |mean_ns| BenchStats::of(&[mean_ns]).unwrap();
+
+        assert_eq!(BenchChange::between(None, &stats(100.0)), BenchChange::New);
+        assert!(matches!(
+            BenchChange::between(Some(&stats(100.0)), &stats(104.0)),
+            BenchChange::Unchanged { .. }
+        ));
+        assert!(matches!(
+            BenchChange::between(Some(&stats(100.0)), &stats(120.0)),
+            BenchChange::Regressed { ratio } if (ratio - 0.2).abs() < 1e-9
+        ));
+        assert!(matches!(
+            BenchChange::between(Some(&stats(100.0)), &stats(50.0)),
+            BenchChange::Improved { .. }
+        ));
+    }
+
+    #[test]
+    fn baseline_keeps_benchmarks_that_did_not_run() {
+        let stats = BenchStats::of(&[5.0]).unwrap();
+        let report = |key: &str| BenchReport {
+            key: key.to_string(),
+            name: key.to_string(),
+            iterations_per_sample: 1,
+            stats,
+            baseline: None,
+        };
+        let mut baseline = BenchBaseline::default();
+        baseline.update(&[report("a.flx::bench_a"), report("a.flx::bench_b")]);
+        baseline.update(&[report("a.flx::bench_a")]);
+
+        let json = serde_json::to_string(&baseline).unwrap();
+        let reloaded: BenchBaseline = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.benchmarks.len(), 2);
+    }
+
+    #[test]
+    fn durations_use_the_largest_fitting_unit() {
+        assert_eq!(format_duration(12.0), "12.00 ns");
+        assert_eq!(format_duration(1_500.0), "1.50 µs");
+        assert_eq!(format_duration(2_000_000.0), "2.00 ms");
+        assert_eq!(format_duration(3e9), "3.00 s");
+    }
+}
@@ -0,0 +1,291 @@
+//! Coverage reports for `--coverage`: an lcov tracefile and a per-function text summary.
+//!
+//! The lcov file has one record per source file with `FN`/`FNDA` for functions, `BRDA` for
+//! the two outcomes of each conditional jump and `DA` for every line that starts a span.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write as _,
+    fs,
+    path::Path,
+};
+
+use crate::{
+    driver::flags::DriverCoverageFlags,
+    syntax::module_graph::{ModuleGraph, ModuleKind},
+    vm::coverage::{CoverageData, FileCoverage, FunctionCoverage},
+};
+
+/// Default path of the lcov tracefile.
+const DEFAULT_LCOV_PATH: &str = "lcov.info";
+
+/// Drops `Flow.*` stdlib modules of `graph` from `data` when `--coverage-exclude-stdlib` is on.
+pub(crate) fn apply_coverage_filters(
+    data: &mut CoverageData,
+    graph: &ModuleGraph,
+    flags: &DriverCoverageFlags,
+) {
+    if !flags.exclude_stdlib {
+        return;
+    }
+    let stdlib: HashSet<String> = graph
+        .topo_order()
+        .into_iter()
+        .filter(|node| node.kind == ModuleKind::FlowStdlib)
+        .map(|node| node.path.to_string_lossy().into_owned())
+        .collect();
+    data.files.retain(|path, _| !stdlib.contains(path));
+}
+
+/// Writes the lcov tracefile and prints the per-function summary to stderr.
+pub(crate) fn write_coverage_report(data: &CoverageData, flags: &DriverCoverageFlags) {
+    let output = flags
+        .output
+        .as_deref()
+        .unwrap_or(Path::new(DEFAULT_LCOV_PATH));
+    eprint!("{}", render_coverage_summary(data));
+    match fs::write(output, render_lcov(data)) {
+        Ok(()) => eprintln!("  coverage written to {}", output.display()),
+        Err(e) => {
+            eprintln!("Error writing coverage report {}: {}", output.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Renders `data` as an lcov tracefile.
+pub(crate) fn render_lcov(data: &CoverageData) -> String {
+    let mut out = String::new();
+    for (path, file) in &data.files {
+        let _ = writeln!(out, "TN:");
+        let _ = writeln!(out, "SF:{path}");
+        for (key, function) in &file.functions {
+            let _ = writeln!(out, "FN:{},{}", key.line, key.name);
+            let _ = writeln!(out, "FNDA:{},{}", function.calls, key.name);
+        }
+        let _ = writeln!(out, "FNF:{}", file.functions.len());
+        let called = file.functions.values().filter(|f| f.calls > 0).count();
+        let _ = writeln!(out, "FNH:{called}");
+
+        // Each jump is a block of two branches; blocks are numbered per line.
+        let mut block = 0;
+        let mut block_line = 0;
+        let mut branches_hit = 0;
+        for (key, counts) in &file.branches {
+            if key.line != block_line {
+                block_line = key.line;
+                block = 0;
+            }
+            let ran = counts.fallthrough + counts.jumped > 0;
+            for (branch, taken) in [counts.fallthrough, counts.jumped].into_iter().enumerate() {
+                let taken = if ran {
+                    taken.to_string()
+                } else {
+                    "-".to_string()
+                };
+                let _ = writeln!(out, "BRDA:{},{block},{branch},{taken}", key.line);
+            }
+            branches_hit += usize::from(counts.fallthrough > 0) + usize::from(counts.jumped > 0);
+            block += 1;
+        }
+        let _ = writeln!(out, "BRF:{}", file.branches.len() * 2);
+        let _ = writeln!(out, "BRH:{branches_hit}");
+
+        let lines = file.line_counts();
+        for (line, count) in &lines {
+            let _ = writeln!(out, "DA:{line},{count}");
+        }
+        let _ = writeln!(out, "LF:{}", lines.len());
+        let _ = writeln!(out, "LH:{}", lines.values().filter(|&&c| c > 0).count());
+        let _ = writeln!(out, "end_of_record");
+    }
+    out
+}
+
+/// Renders the per-function summary: calls, and covered lines and branch outcomes of each
+/// function's own body, then the totals of each file.
+pub(crate) fn render_coverage_summary(data: &CoverageData) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "  ── Flux Coverage Report ──────────────────────────────────────"
+    );
+    let _ = writeln!(
+        out,
+        "  {:<28} {:>8} {:>14} {:>14}",
+        "FUNCTION", "calls", "lines", "branches"
+    );
+    for (path, file) in &data.files {
+        let lines = file.line_counts();
+        let _ = writeln!(out, "  {path}");
+        for (key, function) in &file.functions {
+            let (lines_hit, lines_found) = function_lines(function, &lines);
+            let (branches_hit, branches_found) = function_branches(function, file);
+            let _ = writeln!(
+                out,
+                "    {:<26} {:>8} {:>14} {:>14}",
+                truncate(&format!("{}:{}", key.name, key.line), 26),
+                function.calls,
+                ratio(lines_hit, lines_found),
+                ratio(branches_hit, branches_found),
+            );
+        }
+        let lines_hit = lines.values().filter(|&&c| c > 0).count();
+        let (branches_hit, branches_found) = file_branches(file);
+        let _ = writeln!(
+            out,
+            "    {:<26} {:>8} {:>14} {:>14}",
+            "(file)",
+            "",
+            ratio(lines_hit, lines.len()),
+            ratio(branches_hit, branches_found),
+        );
+    }
+    let _ = writeln!(
+        out,
+        "  ──────────────────────────────────────────────────────────────"
+    );
+    out
+}
+
+fn function_lines(function: &FunctionCoverage, lines: &BTreeMap<usize, u64>) -> (usize, usize) {
+    let hit = function
+        .lines
+        .iter()
+        .filter(|line| lines.get(line).is_some_and(|&count| count > 0))
+        .count();
+    (hit, function.lines.len())
+}
+
+/// Covered and total branch outcomes of a function; each jump has two.
+fn function_branches(function: &FunctionCoverage, file: &FileCoverage) -> (usize, usize) {
+    let hit = function
+        .branches
+        .iter()
+        .filter_map(|key| file.branches.get(key))
+        .map(|counts| usize::from(counts.fallthrough > 0) + usize::from(counts.jumped > 0))
+        .sum();
+    (hit, function.branches.len() * 2)
+}
+
+fn file_branches(file: &FileCoverage) -> (usize, usize) {
+    let hit = file
+        .branches
+        .values()
+        .map(|counts| usize::from(counts.fallthrough > 0) + usize::from(counts.jumped > 0))
+        .sum();
+    (hit, file.branches.len() * 2)
+}
+
+fn ratio(hit: usize, found: usize) -> String {
+    if found == 0 {
+        return "-".to_string();
+    }
+    format!("{hit}/{found} {:>3.0}%", hit as f64 * 100.0 / found as f64)
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let kept: String = s.chars().take(max - 1).collect();
+        format!("{kept}…")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::{render_coverage_summary, render_lcov};
+    use crate::vm::coverage::{
+        BranchCounts, BranchKey, CoverageData, FileCoverage, FunctionCoverage, FunctionKey, SpanKey,
+    };
+
+    fn span(line: usize, column: usize) -> SpanKey {
+        SpanKey {
+            start: (line, column),
+            end: (line, column + 4),
+        }
+    }
+
+    fn sample(calls: u64, line_2: u64, jumped: u64) -> CoverageData {
+        let branch = BranchKey {
+            line: 2,
+            column: 4,
+            index: 0,
+        };
+        let file = FileCoverage {
+            spans: BTreeMap::from([(span(2, 4), line_2), (span(2, 10), 1), (span(3, 8), 0)]),
+            branches: BTreeMap::from([(
+                branch,
+                BranchCounts {
+                    fallthrough: calls - jumped,
+                    jumped,
+                },
+            )]),
+            functions: BTreeMap::from([(
+                FunctionKey {
+                    line: 1,
+                    name: "abs".into(),
+                },
+                FunctionCoverage {
+                    calls,
+                    lines: BTreeSet::from([2, 3]),
+                    branches: BTreeSet::from([branch]),
+                },
+            )]),
+        };
+        CoverageData {
+            files: BTreeMap::from([("src/math.flx".to_string(), file)]),
+        }
+    }
+
+    #[test]
+    fn lcov_lists_functions_branches_and_lines() {
+        let lcov = render_lcov(&sample(3, 3, 0));
+
+        assert_eq!(
+            lcov,
+            "TN:\nSF:src/math.flx\nFN:1,abs\nFNDA:3,abs\nFNF:1\nFNH:1\n\
+             BRDA:2,0,0,3\nBRDA:2,0,1,0\nBRF:2\nBRH:1\n\
+             DA:2,3\nDA:3,0\nLF:2\nLH:1\nend_of_record\n"
+        );
+    }
+
+    #[test]
+    fn merged_runs_add_counts() {
+        let mut data = sample(3, 3, 0);
+        data.merge(sample(2, 2, 2));
+
+        let file = &data.files["src/math.flx"];
+        assert_eq!(file.line_counts()[&2], 5);
+        assert_eq!(
+            file.branches.values().next(),
+            Some(&BranchCounts {
+                fallthrough: 3,
+                jumped: 2
+            })
+        );
+        assert!(render_lcov(&data).contains("BRH:2\n"));
+        assert!(render_lcov(&data).contains("FNDA:5,abs\n"));
+    }
+
+    #[test]
+    fn unexecuted_branches_are_marked_with_a_dash() {
+        let lcov = render_lcov(&sample(0, 0, 0));
+
+        assert!(lcov.contains("BRDA:2,0,0,-\nBRDA:2,0,1,-\n"));
+        assert!(lcov.contains("FNH:0\n"));
+    }
+
+    #[test]
+    fn summary_reports_each_function() {
+        let summary = render_coverage_summary(&sample(3, 3, 0));
+
+        assert!(summary.contains("src/math.flx"));
+        assert!(summary.contains("abs:1"));
+        assert!(summary.contains("1/2  50%"));
+    }
+}
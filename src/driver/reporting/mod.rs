@@ -3,5 +3,9 @@
 //! These modules format backend contracts, runtime analytics, trace banners, and runtime error
 //! rendering used by the driver. They do not own compile semantics or backend execution.
 
+pub(crate) mod bench_report;
+pub(crate) mod coverage_report;
+pub(crate) mod profile_report;
 pub(crate) mod report;
 pub(crate) mod runtime_errors;
+pub(crate) mod test_report;
@@ -0,0 +1,350 @@
+//! Profile reports for `--prof`: the flat cost-centre table, folded stacks and speedscope JSON.
+//!
+//! Every format renders the same [`Profile`], whether the VM built it from its cost-centre
+//! stacks or it was read back from the file a profiled native binary writes at exit. Folded
+//! output has one `outer;inner weight` line per stack, as consumed by `flamegraph.pl` and
+//! `inferno-flamegraph`; the speedscope file holds a time and an allocation profile.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs,
+    path::Path,
+};
+
+use serde_json::json;
+
+use crate::{
+    driver::{flags::DriverProfileFlags, mode::ProfileFormat},
+    runtime::alloc_profile::{AllocCounts, AllocKind},
+    vm::profiling::{Profile, ProfileFrame, ProfileStack},
+};
+
+/// Default output path of each file format.
+const DEFAULT_FOLDED_PATH: &str = "profile.folded";
+const DEFAULT_FOLDED_ALLOC_PATH: &str = "profile.alloc.folded";
+const DEFAULT_SPEEDSCOPE_PATH: &str = "profile.speedscope.json";
+
+/// Renders `profile` in the format selected by `flags`. The flat table goes to stderr unless
+/// `--prof-output` names a file; the other formats are always written to a file.
+pub(crate) fn write_profile_report(profile: &Profile, program: &str, flags: &DriverProfileFlags) {
+    let (rendered, default_path) = match flags.format {
+        ProfileFormat::Flat => (render_flat(profile), None),
+        ProfileFormat::Folded => (
+            render_folded(profile, |stack| stack.self_ns),
+            Some(DEFAULT_FOLDED_PATH),
+        ),
+        ProfileFormat::FoldedAlloc => (
+            render_folded(profile, |stack| stack.allocs.total()),
+            Some(DEFAULT_FOLDED_ALLOC_PATH),
+        ),
+        ProfileFormat::Speedscope => (
+            render_speedscope(profile, program),
+            Some(DEFAULT_SPEEDSCOPE_PATH),
+        ),
+    };
+    let Some(output) = flags.output.as_deref().or(default_path.map(Path::new)) else {
+        eprint!("{rendered}");
+        return;
+    };
+    match fs::write(output, rendered) {
+        Ok(()) => eprintln!("  profile written to {}", output.display()),
+        Err(e) => {
+            eprintln!("Error writing profile {}: {}", output.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Renders the GHC-style flat table: one row per cost centre with its entries and the share
+/// of time and allocations charged while it was running, most expensive first.
+pub(crate) fn render_flat(profile: &Profile) -> String {
+    let mut out = String::new();
+    let mut centres: BTreeMap<usize, FlatRow> = BTreeMap::new();
+    for stack in &profile.stacks {
+        let Some(&frame) = stack.frames.last() else {
+            continue;
+        };
+        let row = centres.entry(frame).or_default();
+        row.entries += stack.entries;
+        row.self_ns += stack.self_ns;
+        row.allocs += stack.allocs;
+    }
+    if centres.is_empty() || profile.total_ns == 0 {
+        return out;
+    }
+    let total_allocs = profile.total_allocs();
+
+    let mut rows: Vec<(usize, FlatRow)> = centres.into_iter().collect();
+    rows.sort_by(|a, b| b.1.self_ns.cmp(&a.1.self_ns).then(a.0.cmp(&b.0)));
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "  ── Flux Profiling Report ─────────────────────────────────────"
+    );
+    let _ = writeln!(
+        out,
+        "  {:<24} {:<16} {:>8} {:>8} {:>8}",
+        "COST CENTRE", "MODULE", "entries", "%time", "%alloc"
+    );
+    let _ = writeln!(
+        out,
+        "  {:<24} {:<16} {:>8} {:>8} {:>8}",
+        "───────────", "──────", "───────", "─────", "──────"
+    );
+    for (frame, row) in &rows {
+        let frame = &profile.frames[*frame];
+        let _ = writeln!(
+            out,
+            "  {:<24} {:<16} {:>8} {:>7.1}% {:>8}",
+            truncate(&frame.name, 24),
+            truncate(&frame.module, 16),
+            row.entries,
+            percent(row.self_ns, profile.total_ns),
+            alloc_share(row.allocs.total(), total_allocs.total()),
+        );
+    }
+    let _ = writeln!(
+        out,
+        "  ──────────────────────────────────────────────────────────────"
+    );
+    let by_kind: Vec<String> = AllocKind::ALL
+        .iter()
+        .map(|&kind| format!("{} {}", kind.label(), total_allocs.get(kind)))
+        .collect();
+    let _ = writeln!(
+        out,
+        "  allocations: {} ({})",
+        total_allocs.total(),
+        by_kind.join(", ")
+    );
+    out
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FlatRow {
+    entries: u64,
+    self_ns: u64,
+    allocs: AllocCounts,
+}
+
+/// Renders one `outer;...;inner weight` line per stack with a non-zero `weight`.
+pub(crate) fn render_folded(profile: &Profile, weight: impl Fn(&ProfileStack) -> u64) -> String {
+    let mut lines: BTreeMap<String, u64> = BTreeMap::new();
+    for stack in &profile.stacks {
+        let weight = weight(stack);
+        if stack.frames.is_empty() || weight == 0 {
+            continue;
+        }
+        *lines.entry(profile.stack_label(stack)).or_default() += weight;
+    }
+    let mut out = String::new();
+    for (stack, weight) in lines {
+        let _ = writeln!(out, "{stack} {weight}");
+    }
+    out
+}
+
+/// Renders a speedscope file with two sampled profiles over the same frames: self time in
+/// nanoseconds and allocation counts.
+pub(crate) fn render_speedscope(profile: &Profile, name: &str) -> String {
+    let frames: Vec<_> = profile
+        .frames
+        .iter()
+        .map(|frame| json!({ "name": frame.label() }))
+        .collect();
+    let sampled = |profile_name: &str, unit: &str, weight: &dyn Fn(&ProfileStack) -> u64| {
+        let stacks: Vec<&ProfileStack> = profile
+            .stacks
+            .iter()
+            .filter(|stack| !stack.frames.is_empty() && weight(stack) > 0)
+            .collect();
+        let weights: Vec<u64> = stacks.iter().map(|stack| weight(stack)).collect();
+        json!({
+            "type": "sampled",
+            "name": profile_name,
+            "unit": unit,
+            "startValue": 0,
+            "endValue": weights.iter().sum::<u64>(),
+            "samples": stacks.iter().map(|stack| &stack.frames).collect::<Vec<_>>(),
+            "weights": weights,
+        })
+    };
+    let report = json!({
+        "$schema": "https://www.speedscope.app/file-format-schema.json",
+        "name": name,
+        "exporter": "flux",
+        "activeProfileIndex": 0,
+        "shared": { "frames": frames },
+        "profiles": [
+            sampled("time", "nanoseconds", &|stack| stack.self_ns),
+            sampled("allocations", "none", &|stack| stack.allocs.total()),
+        ],
+    });
+    let mut out = serde_json::to_string_pretty(&report).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// Reads the call tree a profiled native binary writes at exit (see `runtime/c/prof.c`):
+/// one line per stack, `self_ns entries adt closure array cons hamt`, a tab, then the
+/// `;`-separated function names.
+#[cfg_attr(not(feature = "llvm"), allow(dead_code))]
+pub(crate) fn parse_native_profile(text: &str) -> Result<Profile, String> {
+    let mut profile = Profile::default();
+    let mut frame_ids: HashMap<String, usize> = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let malformed = || format!("malformed native profile line {}: {line}", index + 1);
+        let (counts, path) = line.split_once('\t').ok_or_else(malformed)?;
+        let counts = counts
+            .split(' ')
+            .map(|count| count.parse::<u64>().map_err(|_| malformed()))
+            .collect::<Result<Vec<u64>, String>>()?;
+        let [self_ns, entries, allocs @ ..] = counts.as_slice() else {
+            return Err(malformed());
+        };
+        let allocs: [u64; 5] = allocs.try_into().map_err(|_| malformed())?;
+        let frames = path
+            .split(';')
+            .map(|symbol| {
+                *frame_ids.entry(symbol.to_string()).or_insert_with(|| {
+                    // Native names are already module-qualified (`Flow_List_map`).
+                    profile.frames.push(ProfileFrame {
+                        name: symbol.to_string(),
+                        module: String::new(),
+                    });
+                    profile.frames.len() - 1
+                })
+            })
+            .collect();
+        profile.total_ns += self_ns;
+        profile.stacks.push(ProfileStack {
+            frames,
+            entries: *entries,
+            self_ns: *self_ns,
+            allocs: AllocCounts(allocs),
+        });
+    }
+    Ok(profile)
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    part as f64 * 100.0 / total as f64
+}
+
+fn alloc_share(part: u64, total: u64) -> String {
+    if total == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", percent(part, total))
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let kept: String = s.chars().take(max - 1).collect();
+        format!("{kept}…")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_native_profile, render_flat, render_folded, render_speedscope};
+    use crate::{
+        runtime::alloc_profile::AllocCounts,
+        vm::profiling::{Profile, ProfileFrame, ProfileStack},
+    };
+
+    fn frame(name: &str, module: &str) -> ProfileFrame {
+        ProfileFrame {
+            name: name.into(),
+            module: module.into(),
+        }
+    }
+
+    fn stack(frames: &[usize], entries: u64, self_ns: u64, allocs: [u64; 5]) -> ProfileStack {
+        ProfileStack {
+            frames: frames.to_vec(),
+            entries,
+            self_ns,
+            allocs: AllocCounts(allocs),
+        }
+    }
+
+    fn sample() -> Profile {
+        Profile {
+            frames: vec![
+                frame("main", "<main>"),
+                frame("sum", "Math"),
+                frame("go", "<main>"),
+            ],
+            stacks: vec![
+                stack(&[], 0, 10, [0; 5]),
+                stack(&[0], 1, 20, [0, 0, 1, 0, 0]),
+                stack(&[0, 1], 2, 50, [0, 0, 0, 3, 0]),
+                stack(&[0, 2], 1, 15, [0; 5]),
+                stack(&[0, 2, 1], 4, 5, [1, 0, 0, 0, 0]),
+            ],
+            total_ns: 100,
+        }
+    }
+
+    #[test]
+    fn folded_stacks_weigh_self_time_or_allocations() {
+        let profile = sample();
+
+        assert_eq!(
+            render_folded(&profile, |stack| stack.self_ns),
+            "main 20\nmain;Math.sum 50\nmain;go 15\nmain;go;Math.sum 5\n"
+        );
+        assert_eq!(
+            render_folded(&profile, |stack| stack.allocs.total()),
+            "main 1\nmain;Math.sum 3\nmain;go;Math.sum 1\n"
+        );
+    }
+
+    #[test]
+    fn flat_table_merges_stacks_by_cost_centre() {
+        let table = render_flat(&sample());
+        let rows: Vec<&str> = table.lines().skip(4).take(3).collect();
+
+        assert!(
+            rows[0].contains("sum") && rows[0].contains("Math"),
+            "{table}"
+        );
+        assert!(rows[0].contains("       6    55.0%    80.0%"), "{table}");
+        assert!(rows[1].starts_with("  main"), "{table}");
+        assert!(
+            table.contains("allocations: 5 (adt 1, closure 0, array 1, cons 3, hamt 0)"),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn speedscope_shares_frames_between_time_and_allocation_profiles() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_speedscope(&sample(), "main.flx")).unwrap();
+
+        assert_eq!(json["shared"]["frames"][1]["name"], "Math.sum");
+        assert_eq!(json["profiles"][0]["unit"], "nanoseconds");
+        assert_eq!(json["profiles"][0]["endValue"], 90);
+        assert_eq!(json["profiles"][0]["samples"][1], serde_json::json!([0, 1]));
+        assert_eq!(json["profiles"][1]["weights"], serde_json::json!([1, 3, 1]));
+    }
+
+    #[test]
+    fn native_profiles_parse_into_shared_frames() {
+        let profile = parse_native_profile(
+            "20 1 0 0 1 0 0\tmain\n50 2 0 0 0 3 0\tmain;Math_sum\n5 4 1 0 0 0 0\tmain;go;Math_sum\n",
+        )
+        .unwrap();
+
+        assert_eq!(profile.frames.len(), 3);
+        assert_eq!(profile.frames[1].label(), "Math_sum");
+        assert_eq!(profile.stacks[2].frames, vec![0, 2, 1]);
+        assert_eq!(profile.total_ns, 75);
+        assert!(parse_native_profile("1 2 3\tmain\n").is_err());
+    }
+}
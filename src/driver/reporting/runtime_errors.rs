@@ -13,11 +13,18 @@ struct NativeTraceFrame {
     line: Option<usize>,
 }
 
+/// The message of the first `panic: ` line in native stderr. A multi-line message
+/// continues up to the blank line that precedes the stack trace.
 #[cfg(feature = "llvm")]
 pub(crate) fn split_native_panic_message(stderr: &str) -> Option<&str> {
-    stderr
-        .lines()
-        .find_map(|line| line.strip_prefix("panic: ").map(str::trim))
+    let start = if stderr.starts_with("panic: ") {
+        0
+    } else {
+        stderr.find("\npanic: ")? + 1
+    };
+    let rest = &stderr[start + "panic: ".len()..];
+    let end = rest.find("\n\n").unwrap_or(rest.len());
+    Some(rest[..end].trim())
 }
 
 #[cfg(feature = "llvm")]
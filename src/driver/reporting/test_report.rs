@@ -0,0 +1,377 @@
+//! Machine-readable test reports for `--test-format=junit|tap|json`.
+//!
+//! Every format serializes the same [`TestResult`] fields: name, module, elapsed time, and for
+//! failures the message and the source span of the failing assertion. Results from all test
+//! files of one invocation go into a single report.
+
+use std::fmt::Write as _;
+
+use serde_json::{Value, json};
+
+use crate::{
+    diagnostics::position::Span,
+    driver::{mode::TestReportFormat, test_snapshots::SnapshotReport},
+    vm::{
+        coverage::CoverageData,
+        strip_ansi,
+        test_runner::{TestOutcome, TestResult},
+    },
+};
+
+/// Results of one test file.
+pub(crate) struct TestFileReport {
+    pub(crate) path: String,
+    pub(crate) results: Vec<TestResult>,
+    pub(crate) snapshots: SnapshotReport,
+    /// Counted with `--coverage`, after the stdlib filter; empty otherwise.
+    pub(crate) coverage: CoverageData,
+}
+
+/// Pass/fail counts across every file of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct TestTotals {
+    pub(crate) passed: usize,
+    pub(crate) failed: usize,
+}
+
+impl TestTotals {
+    pub(crate) fn of(reports: &[TestFileReport]) -> Self {
+        let mut totals = Self::default();
+        for result in reports.iter().flat_map(|report| &report.results) {
+            match result.outcome {
+                TestOutcome::Pass => totals.passed += 1,
+                TestOutcome::Fail(_) => totals.failed += 1,
+            }
+        }
+        totals
+    }
+
+    pub(crate) fn total(self) -> usize {
+        self.passed + self.failed
+    }
+}
+
+/// Renders `reports` in a machine-readable `format`; `Text` has no aggregate rendering.
+pub(crate) fn render_test_report(format: TestReportFormat, reports: &[TestFileReport]) -> String {
+    match format {
+        TestReportFormat::Text => String::new(),
+        TestReportFormat::Junit => render_junit(reports),
+        TestReportFormat::Tap => render_tap(reports),
+        TestReportFormat::Json => render_json(reports),
+    }
+}
+
+fn render_junit(reports: &[TestFileReport]) -> String {
+    let totals = TestTotals::of(reports);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"flux\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+        totals.total(),
+        totals.failed,
+        seconds(elapsed_ms(
+            reports.iter().flat_map(|report| &report.results)
+        ))
+    );
+    for report in reports {
+        let suite = TestTotals::of(std::slice::from_ref(report));
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+            xml_escape(&report.path),
+            suite.total(),
+            suite.failed,
+            seconds(elapsed_ms(&report.results))
+        );
+        for result in &report.results {
+            let (qualifier, name) = split_test_name(&result.name);
+            let mut classname = module_classname(&result.module);
+            if let Some(qualifier) = qualifier {
+                classname.push('.');
+                classname.push_str(qualifier);
+            }
+            let _ = write!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\" file=\"{}\"",
+                xml_escape(name),
+                xml_escape(&classname),
+                seconds(result.elapsed_ms),
+                xml_escape(&result.module)
+            );
+            if let TestOutcome::Fail(failure) = &result.outcome
+                && let Some((_, span)) = &failure.location
+            {
+                let _ = write!(out, " line=\"{}\"", span.start.line);
+            }
+            let has_body =
+                matches!(result.outcome, TestOutcome::Fail(_)) || !result.output.is_empty();
+            if !has_body {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            if let TestOutcome::Fail(failure) = &result.outcome {
+                let message = strip_ansi(&failure.message);
+                let title = message.lines().next().unwrap_or_default();
+                let mut body = message.clone();
+                if let Some(location) = &failure.location {
+                    let _ = write!(body, "\nat {}", render_location(location));
+                }
+                let _ = writeln!(
+                    out,
+                    "      <failure message=\"{}\" type=\"failure\">{}</failure>",
+                    xml_escape(title),
+                    xml_escape(&body)
+                );
+            }
+            if !result.output.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "      <system-out>{}</system-out>",
+                    xml_escape(&result.output)
+                );
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn render_tap(reports: &[TestFileReport]) -> String {
+    let mut out = String::from("TAP version 13\n");
+    let _ = writeln!(out, "1..{}", TestTotals::of(reports).total());
+    let results = reports.iter().flat_map(|report| &report.results);
+    for (index, result) in results.enumerate() {
+        let status = match result.outcome {
+            TestOutcome::Pass => "ok",
+            TestOutcome::Fail(_) => "not ok",
+        };
+        let _ = writeln!(
+            out,
+            "{status} {} - {} {}",
+            index + 1,
+            result.module,
+            result.name
+        );
+        let mut fields = vec![format!("duration_ms: {:.3}", result.elapsed_ms)];
+        if let TestOutcome::Fail(failure) = &result.outcome {
+            fields.push(yaml_block("message", &strip_ansi(&failure.message)));
+            if let Some(location) = &failure.location {
+                fields.push(format!("at: {}", yaml_string(&render_location(location))));
+            }
+        }
+        if !result.output.is_empty() {
+            fields.push(yaml_block("output", &result.output));
+        }
+        out.push_str("  ---\n");
+        for field in fields {
+            for line in field.lines() {
+                let _ = writeln!(out, "  {line}");
+            }
+        }
+        out.push_str("  ...\n");
+    }
+    out
+}
+
+fn render_json(reports: &[TestFileReport]) -> String {
+    let totals = TestTotals::of(reports);
+    let tests: Vec<Value> = reports
+        .iter()
+        .flat_map(|report| &report.results)
+        .map(|result| {
+            let mut test = json!({
+                "name": result.name,
+                "module": result.module,
+                "outcome": match result.outcome {
+                    TestOutcome::Pass => "pass",
+                    TestOutcome::Fail(_) => "fail",
+                },
+                "elapsed_ms": result.elapsed_ms,
+            });
+            if let TestOutcome::Fail(failure) = &result.outcome {
+                test["message"] = json!(strip_ansi(&failure.message));
+                test["location"] = failure
+                    .location
+                    .as_ref()
+                    .map_or(Value::Null, |(file, span)| json_location(file, *span));
+            }
+            if !result.output.is_empty() {
+                test["output"] = json!(result.output);
+            }
+            test
+        })
+        .collect();
+    let report = json!({
+        "files": reports.len(),
+        "tests": totals.total(),
+        "passed": totals.passed,
+        "failed": totals.failed,
+        "elapsed_ms": elapsed_ms(reports.iter().flat_map(|report| &report.results)),
+        "results": tests,
+    });
+    let mut out = serde_json::to_string_pretty(&report).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn json_location(file: &str, span: Span) -> Value {
+    json!({
+        "file": file,
+        "line": span.start.line,
+        "column": span.start.column + 1,
+        "end_line": span.end.line,
+        "end_column": span.end.column + 1,
+    })
+}
+
+fn elapsed_ms<'a>(results: impl IntoIterator<Item = &'a TestResult>) -> f64 {
+    results.into_iter().map(|result| result.elapsed_ms).sum()
+}
+
+fn seconds(ms: f64) -> String {
+    format!("{:.3}", ms / 1000.0)
+}
+
+fn render_location((file, span): &(String, Span)) -> String {
+    format!("{file}:{}:{}", span.start.line, span.start.column + 1)
+}
+
+/// Splits `Tests.test_x` into its module qualifier and bare name.
+fn split_test_name(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once('.') {
+        Some((qualifier, name)) => (Some(qualifier), name),
+        None => (None, name),
+    }
+}
+
+/// JUnit class name of a test file: its path without extension, dot-separated.
+fn module_classname(path: &str) -> String {
+    let path = path.strip_suffix(".flx").unwrap_or(path);
+    path.trim_start_matches("./")
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' | '\r' | '\t' => out.push(ch),
+            ch if (ch as u32) < 0x20 => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn yaml_block(key: &str, text: &str) -> String {
+    let mut out = format!("{key}: |-\n");
+    for line in text.trim_end().lines() {
+        let _ = writeln!(out, "  {line}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SnapshotReport, TestFileReport, module_classname, render_json, render_junit, render_tap,
+    };
+    use crate::{
+        diagnostics::position::{Position, Span},
+        vm::{
+            coverage::CoverageData,
+            test_runner::{TestFailure, TestOutcome, TestResult},
+        },
+    };
+
+    fn sample() -> Vec<TestFileReport> {
+        let span = Span::new(Position::new(4, 2), Position::new(4, 20));
+        vec![TestFileReport {
+            path: "tests/math.flx".to_string(),
+            results: vec![
+                TestResult {
+                    name: "test_add".to_string(),
+                    module: "tests/math.flx".to_string(),
+                    elapsed_ms: 1.5,
+                    outcome: TestOutcome::Pass,
+                    output: String::new(),
+                },
+                TestResult {
+                    name: "Tests.test_div".to_string(),
+                    module: "tests/math.flx".to_string(),
+                    elapsed_ms: 2.0,
+                    outcome: TestOutcome::Fail(TestFailure {
+                        message: "assert_eq failed <1 & 2>".to_string(),
+                        location: Some(("tests/math.flx".to_string(), span)),
+                    }),
+                    output: "ASSERTION FAILED: assert_eq\n".to_string(),
+                },
+            ],
+            snapshots: SnapshotReport::default(),
+            coverage: CoverageData::default(),
+        }]
+    }
+
+    #[test]
+    fn junit_report_escapes_and_locates_failures() {
+        let xml = render_junit(&sample());
+
+        assert!(
+            xml.contains("<testsuites name=\"flux\" tests=\"2\" failures=\"1\" time=\"0.004\">")
+        );
+        assert!(xml.contains(
+            "<testcase name=\"test_add\" classname=\"tests.math\" time=\"0.002\" file=\"tests/math.flx\"/>"
+        ));
+        assert!(xml.contains("name=\"test_div\" classname=\"tests.math.Tests\""));
+        assert!(xml.contains("line=\"4\""));
+        assert!(xml.contains("message=\"assert_eq failed &lt;1 &amp; 2&gt;\""));
+        assert!(xml.contains("at tests/math.flx:4:3</failure>"));
+        assert!(xml.contains("<system-out>ASSERTION FAILED: assert_eq\n</system-out>"));
+    }
+
+    #[test]
+    fn tap_report_numbers_tests_and_adds_yaml_diagnostics() {
+        let tap = render_tap(&sample());
+
+        assert!(tap.starts_with("TAP version 13\n1..2\n"));
+        assert!(tap.contains("ok 1 - tests/math.flx test_add\n"));
+        assert!(tap.contains("not ok 2 - tests/math.flx Tests.test_div\n"));
+        assert!(tap.contains("  message: |-\n    assert_eq failed <1 & 2>\n"));
+        assert!(tap.contains("  at: \"tests/math.flx:4:3\"\n"));
+    }
+
+    #[test]
+    fn json_report_aggregates_counts() {
+        let report: serde_json::Value = serde_json::from_str(&render_json(&sample())).unwrap();
+
+        assert_eq!(report["tests"], 2);
+        assert_eq!(report["failed"], 1);
+        let failure = &report["results"][1];
+        assert_eq!(failure["outcome"], "fail");
+        assert_eq!(failure["module"], "tests/math.flx");
+        assert_eq!(failure["location"]["line"], 4);
+        assert_eq!(failure["location"]["column"], 3);
+        assert!(report["results"][0].get("message").is_none());
+    }
+
+    #[test]
+    fn module_classname_drops_extension_and_separators() {
+        assert_eq!(module_classname("./tests/flux/math.flx"), "tests.flux.math");
+        assert_eq!(module_classname("math.flx"), "math");
+    }
+}
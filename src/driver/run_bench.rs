@@ -0,0 +1,401 @@
+//! `flux bench`: measures the `bench_*` functions of a file or a directory of files.
+//!
+//! Benchmarks are compiled like tests. On the VM they run in the process, measured by
+//! [`measure_bench`]; with `--native` each one runs in a harness binary that warms up,
+//! calibrates and times itself, printing its samples. The results are compared against the
+//! baseline file, which is then updated with them.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    reporting::bench_report::{
+        BenchBaseline, BenchChange, BenchReport, BenchStats, render_bench_result,
+    },
+    run_tests::{
+        CompiledTestProgram, ParsedTestFile, TestRunRequest, compile_test_program,
+        emit_parse_diagnostics_or_exit, load_test_file,
+    },
+    shared::{DriverDiagnosticConfig, emit_diagnostics_or_exit},
+};
+use crate::{
+    shared::{cache_paths::resolve_cache_root, source_files::collect_flx_files},
+    vm::{
+        VM,
+        bench_runner::{BenchConfig, BenchSamples, measure_bench},
+        test_runner::collect_bench_functions,
+    },
+};
+
+/// Path of the baseline file under the cache directory, when `--baseline` is not given.
+const DEFAULT_BASELINE_FILE: &str = "bench/baseline.json";
+
+/// Runs the benchmarks of `path`, a file or a directory, and updates the baseline.
+/// Exits with a failure status when a benchmark fails.
+pub(crate) fn run_bench_target(path: &str, request: TestRunRequest<'_>) {
+    let in_directory = Path::new(path).is_dir();
+    let files = if in_directory {
+        discover_bench_files(Path::new(path))
+    } else {
+        vec![PathBuf::from(path)]
+    };
+    if files.is_empty() {
+        println!("No benchmark files found under {}.", path);
+        return;
+    }
+
+    let baseline_path = request.flags.bench.baseline.clone().unwrap_or_else(|| {
+        resolve_cache_root(Path::new(path), request.flags.cache.cache_dir.as_deref())
+            .join(DEFAULT_BASELINE_FILE)
+    });
+    let mut baseline = BenchBaseline::load(&baseline_path);
+    let mut reports = Vec::new();
+    let mut failed = 0usize;
+    for file in &files {
+        let file = file.to_string_lossy();
+        failed += run_bench_file(&file, &request, &baseline, in_directory, &mut reports);
+    }
+    if reports.is_empty() && failed == 0 {
+        return;
+    }
+
+    println!("\n{}", summary_line(&reports, failed));
+    baseline.update(&reports);
+    match baseline.save(&baseline_path) {
+        Ok(()) => println!("baseline written to {}", baseline_path.display()),
+        Err(e) => {
+            eprintln!(
+                "Error writing bench baseline {}: {}",
+                baseline_path.display(),
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Collects the `.flx` files under `dir` that define a `bench_` function, in path order.
+fn discover_bench_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_flx_files(dir, &mut files);
+    files.sort();
+    files.retain(|file| fs::read_to_string(file).is_ok_and(|source| source.contains("fn bench_")));
+    files
+}
+
+/// Compiles one file, measures its benchmarks and prints each result as it completes.
+/// Appends the results to `reports` and returns how many benchmarks failed.
+fn run_bench_file(
+    path: &str,
+    request: &TestRunRequest<'_>,
+    baseline: &BenchBaseline,
+    in_directory: bool,
+    reports: &mut Vec<BenchReport>,
+) -> usize {
+    let ParsedTestFile {
+        source,
+        roots,
+        mut parser,
+        program,
+    } = load_test_file(path, request);
+    let mut all_diagnostics = Vec::new();
+    emit_parse_diagnostics_or_exit(
+        path,
+        &source,
+        &mut parser,
+        request.session,
+        &mut all_diagnostics,
+    );
+    let CompiledTestProgram {
+        compiler,
+        graph,
+        diagnostics,
+    } = compile_test_program(path, program, parser, &roots, request);
+    all_diagnostics.extend(diagnostics);
+    emit_diagnostics_or_exit(
+        &all_diagnostics,
+        path,
+        source.as_str(),
+        graph.module_count() > 1,
+        DriverDiagnosticConfig::from(request.session),
+    );
+
+    let mut benches = collect_bench_functions(&compiler.symbol_table, &compiler.interner);
+    if let Some(filter) = request.flags.bench.filter.as_deref() {
+        benches.retain(|(name, _)| name.contains(filter));
+    }
+    if benches.is_empty() {
+        if !in_directory {
+            println!("Running benchmarks in {}\n", path);
+            println!("No benchmark functions found (define functions named `bench_*`).");
+        }
+        return 0;
+    }
+
+    if !reports.is_empty() {
+        println!();
+    }
+    println!("Running benchmarks in {}", path);
+    let measurement = request.flags.bench.measurement;
+    let config = BenchConfig {
+        warmup: measurement / 3,
+        measurement,
+    };
+    let mut record = |name: &str, measured: Result<BenchSamples, String>| -> bool {
+        let key = format!("{path}::{name}");
+        let measured = measured.and_then(|samples| {
+            BenchStats::of(&samples.samples)
+                .map(|stats| (samples.iterations_per_sample, stats))
+                .ok_or_else(|| "no samples were taken".to_string())
+        });
+        match measured {
+            Ok((iterations_per_sample, stats)) => {
+                let report = BenchReport {
+                    baseline: baseline.benchmarks.get(&key).copied(),
+                    key,
+                    name: name.to_string(),
+                    iterations_per_sample,
+                    stats,
+                };
+                print!("{}", render_bench_result(&report));
+                reports.push(report);
+                true
+            }
+            Err(message) => {
+                println!("\n{name}\n  FAILED: {message}");
+                false
+            }
+        }
+    };
+
+    let mut failed = 0;
+    #[cfg(feature = "llvm")]
+    if request.flags.is_native_backend() {
+        for (name, _) in &benches {
+            let measured = native::measure_native_bench(path, &source, &roots, name, request);
+            failed += usize::from(!record(name, measured));
+        }
+        return failed;
+    }
+
+    let mut vm = VM::new(compiler.bytecode());
+    if let Err(err) = vm.run() {
+        eprintln!("Error during benchmark setup: {}", err);
+        std::process::exit(1);
+    }
+    for (name, idx) in &benches {
+        let measured = measure_bench(&mut vm, *idx, config);
+        failed += usize::from(!record(name, measured));
+    }
+    failed
+}
+
+/// One line counting the benchmarks of the run by how they compare to the baseline.
+fn summary_line(reports: &[BenchReport], failed: usize) -> String {
+    let (mut regressed, mut improved, mut unchanged, mut new) = (0, 0, 0, 0);
+    for report in reports {
+        match report.change() {
+            BenchChange::Regressed { .. } => regressed += 1,
+            BenchChange::Improved { .. } => improved += 1,
+            BenchChange::Unchanged { .. } => unchanged += 1,
+            BenchChange::New => new += 1,
+        }
+    }
+    let mut line = format!(
+        "{} benchmarks: {regressed} regressed, {improved} improved, {unchanged} unchanged, {new} new",
+        reports.len() + failed
+    );
+    if failed > 0 {
+        line.push_str(&format!(", {failed} failed"));
+    }
+    line
+}
+
+/// Benchmarks compiled to native harness binaries (`flux bench --native`).
+#[cfg(feature = "llvm")]
+mod native {
+    use std::{
+        path::{Path, PathBuf},
+        process::Command,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use crate::{
+        driver::run_tests::{
+            NativeTestHarnessSource, NativeTestRunConfig, TestExecution, TestRunRequest,
+            append_native_test_command_args, build_native_harness_source, run_native_test_binary,
+        },
+        vm::{
+            bench_runner::{BenchSamples, MAX_SAMPLES, MIN_SAMPLES},
+            test_runner::TestOutcome,
+        },
+    };
+
+    /// Marker of the line on which the harness prints its iterations per sample.
+    const ITERATIONS_MARKER: &str = "__flux_bench_iterations ";
+
+    /// Marker of the lines on which the harness prints the milliseconds of each sample.
+    const SAMPLE_MARKER: &str = "__flux_bench_sample ";
+
+    /// Shortest sample the harness aims for, since its clock counts milliseconds.
+    const MIN_SAMPLE_MS: u128 = 10;
+
+    /// Source of the harness `main` for benchmark `name`.
+    ///
+    /// The harness doubles the iterations of a batch until one batch lasts a sample, keeps
+    /// running batches until the warmup time has passed, then prints the time of each sample.
+    /// Its functions are unannotated, so they take on the effects of the benchmark.
+    fn harness_main(name: &str, samples: usize, sample_ms: u128, warmup_ms: u128) -> String {
+        format!(
+            "\
+fn __flux_bench_repeat(n) {{
+    if n <= 0 {{ 0 }} else {{
+        let _result = {name}()
+        __flux_bench_repeat(n - 1)
+    }}
+}}
+
+fn __flux_bench_time(n) {{
+    let start = clock_now()
+    let _done = __flux_bench_repeat(n)
+    clock_now() - start
+}}
+
+fn __flux_bench_calibrate(n) {{
+    if __flux_bench_time(n) >= {sample_ms} {{ n }} else {{ __flux_bench_calibrate(n * 2) }}
+}}
+
+fn __flux_bench_warm_up(n, until) {{
+    if clock_now() >= until {{ 0 }} else {{
+        let _took = __flux_bench_time(n)
+        __flux_bench_warm_up(n, until)
+    }}
+}}
+
+fn __flux_bench_samples(n, left) {{
+    if left > 0 {{
+        println(\"{SAMPLE_MARKER}\" + to_string(__flux_bench_time(n)))
+        __flux_bench_samples(n, left - 1)
+    }}
+}}
+
+fn main() {{
+    let n = __flux_bench_calibrate(1)
+    let _warm = __flux_bench_warm_up(n, clock_now() + {warmup_ms})
+    println(\"{ITERATIONS_MARKER}\" + to_string(n))
+    __flux_bench_samples(n, {samples})
+}}"
+        )
+    }
+
+    /// Compiles and runs the harness of benchmark `name` of `path`, and reads its samples.
+    pub(super) fn measure_native_bench(
+        path: &str,
+        source: &str,
+        roots: &[PathBuf],
+        name: &str,
+        request: &TestRunRequest<'_>,
+    ) -> Result<BenchSamples, String> {
+        let measurement_ms = request.flags.bench.measurement.as_millis();
+        let samples = ((measurement_ms / MIN_SAMPLE_MS) as usize).clamp(MIN_SAMPLES, MAX_SAMPLES);
+        let sample_ms = (measurement_ms / samples as u128).max(MIN_SAMPLE_MS);
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let harness_main = harness_main(name, samples, sample_ms, measurement_ms / 3);
+        let hidden_main_name = format!("__flux_bench_user_main_{unique}");
+        let NativeTestHarnessSource::Generated(harness) =
+            build_native_harness_source(source, &harness_main, &hidden_main_name)?
+        else {
+            return Err(
+                "files with several top-level `main` functions cannot be benchmarked natively"
+                    .to_string(),
+            );
+        };
+
+        let harness_path = std::env::temp_dir().join(format!(
+            "flux_native_bench_{}_{}.flx",
+            std::process::id(),
+            unique
+        ));
+        let binary_path = harness_path.with_extension(std::env::consts::EXE_EXTENSION);
+        std::fs::write(&harness_path, harness)
+            .map_err(|e| format!("failed to write the native bench harness: {e}"))?;
+        let measured = compile_and_run(path, source, roots, request, &harness_path, &binary_path);
+        let _ = std::fs::remove_file(&harness_path);
+        let _ = std::fs::remove_file(&binary_path);
+        parse_samples(&measured?)
+    }
+
+    /// Compiles the harness at `harness_path` in a child `flux` and returns the stdout of
+    /// running it.
+    fn compile_and_run(
+        path: &str,
+        source: &str,
+        roots: &[PathBuf],
+        request: &TestRunRequest<'_>,
+        harness_path: &Path,
+        binary_path: &Path,
+    ) -> Result<String, String> {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("failed to locate the flux executable: {e}"))?;
+        let config = NativeTestRunConfig {
+            source_path: path,
+            source,
+            roots,
+            roots_only: request.session.roots_only,
+            tests: &[],
+            enable_optimize: request.session.enable_optimize,
+            enable_analyze: request.session.enable_analyze,
+            strict_mode: request.session.strict_mode,
+            use_native: true,
+            execution: TestExecution::from_flags(request.flags),
+        };
+        let mut cmd = Command::new(exe);
+        append_native_test_command_args(&mut cmd, &config, harness_path, binary_path);
+        cmd.env("NO_COLOR", "1");
+        let compile = cmd
+            .output()
+            .map_err(|e| format!("failed to compile the native bench harness: {e}"))?;
+        if !compile.status.success() {
+            let mut text = String::from_utf8_lossy(&compile.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&compile.stderr));
+            return Err(text.trim().to_string());
+        }
+        match run_native_test_binary(binary_path, &harness_path.to_string_lossy(), None) {
+            (TestOutcome::Pass, stdout) => Ok(stdout),
+            (TestOutcome::Fail(failure), _) => Err(failure.message),
+        }
+    }
+
+    /// Reads the iterations per sample and the per-iteration time of each sample, in
+    /// nanoseconds, from the harness output.
+    fn parse_samples(stdout: &str) -> Result<BenchSamples, String> {
+        let mut iterations = None;
+        let mut sample_ms = Vec::new();
+        for line in stdout.lines() {
+            let line = line.trim().trim_matches('"');
+            if let Some(n) = line.strip_prefix(ITERATIONS_MARKER) {
+                iterations = n.trim().parse::<u64>().ok();
+            } else if let Some(ms) = line.strip_prefix(SAMPLE_MARKER) {
+                sample_ms.extend(ms.trim().parse::<f64>().ok());
+            }
+        }
+        let iterations = iterations
+            .filter(|&n| n > 0)
+            .ok_or_else(|| "the native bench harness printed no iteration count".to_string())?;
+        Ok(BenchSamples {
+            iterations_per_sample: iterations,
+            samples: sample_ms
+                .into_iter()
+                .map(|ms| ms * 1e6 / iterations as f64)
+                .collect(),
+        })
+    }
+}
@@ -3,9 +3,6 @@ use std::path::PathBuf;
 use std::time::Instant;
 
 use crate as flux;
-use crate::driver::shared::{
-    DriverCacheConfig, DriverCompileConfig, DriverDiagnosticConfig, DriverRuntimeConfig,
-};
 #[cfg(feature = "llvm")]
 use crate::driver::{
     backend_policy::{
@@ -16,6 +13,7 @@ use crate::driver::{
         compile_native_support_object, locate_runtime_lib_dir,
     },
     reporting::{
+        profile_report::{parse_native_profile, write_profile_report},
         report::{
             AetherTraceContext, ArtifactStats, CompileStats, ExecuteStats, RunStats, TraceBackend,
             print_aether_trace, print_stats,
@@ -25,6 +23,12 @@ use crate::driver::{
     shared::emit_diagnostics_or_exit,
     support::shared::{DiagnosticRenderRequest, emit_diagnostics},
 };
+use crate::driver::{
+    flags::{DriverCallTraceFlags, DriverEffectLogFlags, DriverProfileFlags},
+    shared::{DriverCacheConfig, DriverCompileConfig, DriverDiagnosticConfig, DriverRuntimeConfig},
+};
+#[cfg(feature = "llvm")]
+use flux::runtime::call_trace::CallTraceFilter;
 #[cfg(feature = "llvm")]
 use flux::syntax::{module_graph::ModuleKind, program::Program};
 use flux::{diagnostics::Diagnostic, syntax::module_graph::ModuleGraph};
@@ -56,7 +60,7 @@ fn merged_native_program(graph: &ModuleGraph) -> Program {
 
 #[cfg(feature = "llvm")]
 /// Formats a native child-process termination when no Flux runtime panic text is available.
-fn native_exit_summary(status: &std::process::ExitStatus) -> String {
+pub(crate) fn native_exit_summary(status: &std::process::ExitStatus) -> String {
     #[cfg(unix)]
     {
         if let Some(signal) = status.signal() {
@@ -164,6 +168,9 @@ pub(crate) struct NativeRunRequest<'a> {
     pub(crate) runtime: DriverRuntimeConfig,
     pub(crate) output: NativeOutputConfig,
     pub(crate) report: NativeReportConfig,
+    pub(crate) profile: DriverProfileFlags,
+    pub(crate) call_trace: DriverCallTraceFlags,
+    pub(crate) effect_log: DriverEffectLogFlags,
 }
 
 #[cfg(feature = "llvm")]
@@ -289,6 +296,10 @@ pub(crate) fn run_native_backend(request: NativeRunRequest<'_>) {
         }
         eprintln!("{}", native_module_lowering_banner());
         let native_modules_start = Instant::now();
+        let call_trace = request
+            .call_trace
+            .enabled()
+            .then(|| CallTraceFilter::new(&request.call_trace.patterns));
         let (mut object_paths, any_native_recompiled) = match compile_native_modules_parallel(
             NativeParallelCompileRequest {
                 graph: request.program.graph,
@@ -298,6 +309,8 @@ pub(crate) fn run_native_backend(request: NativeRunRequest<'_>) {
                 enable_optimize: request.compile.enable_optimize,
                 enable_analyze: request.compile.enable_analyze,
                 verbose: request.runtime.verbose,
+                profiling: request.runtime.profiling,
+                call_trace: call_trace.as_ref(),
                 base_interner: &request.program.compiler.interner,
             },
             request.program.all_diagnostics,
@@ -455,8 +468,27 @@ pub(crate) fn run_native_backend(request: NativeRunRequest<'_>) {
             return;
         }
 
+        // A profiled binary writes its call tree here at exit (see runtime/c/prof.c).
+        let profile_path = out.with_extension("prof");
+        let mut command = std::process::Command::new(&out);
+        if request.runtime.profiling {
+            command.env("FLUX_PROF_OUTPUT", &profile_path);
+        }
+        // A traced binary writes JSON lines here instead of text on stderr (see runtime/c/trace.c).
+        if let Some(trace_output) = &request.call_trace.output {
+            command.env("FLUX_TRACE_OUTPUT", trace_output);
+        }
+        // Builtin effects are logged or replayed by the binary itself (see runtime/c/effect_log.c).
+        if let Some(record) = &request.effect_log.record {
+            command.env("FLUX_EFFECT_RECORD", record);
+        }
+        if let Some(replay) = &request.effect_log.replay {
+            command.env("FLUX_EFFECT_REPLAY", replay);
+        }
+        // The program reads the terminal's stdin, as it does on the VM.
+        command.stdin(std::process::Stdio::inherit());
         let exec_start = Instant::now();
-        match std::process::Command::new(&out).output() {
+        match command.output() {
             Ok(output) => {
                 let exit_code = output.status.code().unwrap_or(1);
                 let execute_ms = exec_start.elapsed().as_secs_f64() * 1000.0;
@@ -505,6 +537,9 @@ pub(crate) fn run_native_backend(request: NativeRunRequest<'_>) {
                         },
                     });
                 }
+                if request.runtime.profiling && exit_code == 0 {
+                    emit_native_profile(&profile_path, request.program.path, &request.profile);
+                }
                 if request.cache.no_cache || request.output.emit_binary {
                     let _ = std::fs::remove_file(&out);
                 }
@@ -525,6 +560,23 @@ pub(crate) fn run_native_backend(request: NativeRunRequest<'_>) {
     }
 }
 
+#[cfg(feature = "llvm")]
+/// Reads the call tree a profiled native run wrote to `profile_path` and renders the report.
+fn emit_native_profile(profile_path: &std::path::Path, program: &str, flags: &DriverProfileFlags) {
+    let text = std::fs::read_to_string(profile_path);
+    let _ = std::fs::remove_file(profile_path);
+    match text
+        .map_err(|e| e.to_string())
+        .and_then(|text| parse_native_profile(&text))
+    {
+        Ok(profile) => write_profile_report(&profile, program, flags),
+        Err(err) => eprintln!(
+            "Error reading native profile {}: {err}",
+            profile_path.display()
+        ),
+    }
+}
+
 #[cfg(feature = "llvm")]
 /// Allocates a unique temporary output directory for uncached native runs.
 fn native_temp_dir() -> PathBuf {
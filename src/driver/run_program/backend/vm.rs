@@ -3,11 +3,15 @@ use std::{path::PathBuf, time::Instant};
 use crate as flux;
 use crate::driver::{
     backend_policy::{compile_backend_label, execute_backend_label, vm_run_banner},
-    flags::DriverFlags,
+    flags::{DriverCallTraceFlags, DriverEffectLogFlags, DriverFlags},
     pipeline::vm::{VmCompileRequest, compile_vm_modules_parallel},
-    reporting::report::{
-        AetherTraceContext, ArtifactStats, CompileStats, ExecuteStats, RunStats, TraceBackend,
-        count_bytecode_functions, print_aether_trace, print_leak_stats, print_stats,
+    reporting::{
+        coverage_report::{apply_coverage_filters, write_coverage_report},
+        profile_report::write_profile_report,
+        report::{
+            AetherTraceContext, ArtifactStats, CompileStats, ExecuteStats, RunStats, TraceBackend,
+            count_bytecode_functions, print_aether_trace, print_leak_stats, print_stats,
+        },
     },
     shared::{
         DriverCacheConfig, DriverCompileConfig, DriverDiagnosticConfig, DriverRuntimeConfig,
@@ -16,6 +20,7 @@ use crate::driver::{
 };
 use flux::{
     diagnostics::Diagnostic,
+    runtime::{call_trace::TraceWriter, effect_log::EffectLog},
     syntax::{module_graph::ModuleGraph, program::Program},
     vm::VM,
 };
@@ -60,6 +65,48 @@ fn vm_run_stats(request: VmRunStatsRequest<'_>) -> RunStats {
     }
 }
 
+/// Filters and writes the coverage of a finished run when `--coverage` is on.
+fn emit_vm_coverage(vm: &VM, graph: &ModuleGraph, flags: &DriverFlags) {
+    if let Some(mut coverage) = vm.coverage() {
+        apply_coverage_filters(&mut coverage, graph, &flags.coverage);
+        write_coverage_report(&coverage, &flags.coverage);
+    }
+}
+
+/// Opens the destination of `--trace-calls` output, exiting when the file can't be created.
+fn call_trace_writer(flags: &DriverCallTraceFlags) -> TraceWriter {
+    let Some(path) = flags.output.as_deref() else {
+        return TraceWriter::stderr();
+    };
+    TraceWriter::json_file(path).unwrap_or_else(|err| {
+        eprintln!("Error writing call trace {}: {}", path.display(), err);
+        std::process::exit(1);
+    })
+}
+
+/// Opens the `--record` or `--replay` log, exiting when it can't be opened.
+fn effect_log(flags: &DriverEffectLogFlags) -> Option<EffectLog> {
+    if let Some(path) = flags.record.as_deref() {
+        return Some(EffectLog::record(path).unwrap_or_else(|err| {
+            eprintln!("Error writing effect log {}: {}", path.display(), err);
+            std::process::exit(1);
+        }));
+    }
+    let path = flags.replay.as_deref()?;
+    Some(EffectLog::replay(path).unwrap_or_else(|err| {
+        eprintln!("Error reading effect log {}: {}", path.display(), err);
+        std::process::exit(1);
+    }))
+}
+
+/// Ends the effect log of a finished run, exiting when a replay diverged from its log.
+fn finish_effect_log(vm: &mut VM) {
+    if let Err(err) = vm.finish_effect_log() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
 /// Emits the VM Aether trace if the current run requested it.
 fn emit_vm_aether_trace(request: &mut VmRunRequest<'_>) {
     match request.compiler.render_aether_report(
@@ -144,11 +191,18 @@ pub(crate) fn try_run_parallel_vm(request: ParallelVmRunRequest<'_>) -> bool {
     eprintln!("{}", vm_run_banner());
     let mut vm = VM::new(bytecode);
     vm.set_trace(request.runtime.trace);
+    vm.set_coverage(request.flags.coverage.enabled);
+    if let Some(log) = effect_log(&request.flags.effect_log) {
+        vm.set_effect_log(log);
+    }
     let exec_start = Instant::now();
-    if let Err(err) = vm.run() {
+    let result = vm.run();
+    emit_vm_coverage(&vm, request.graph, request.flags);
+    if let Err(err) = result {
         eprintln!("{err}");
         std::process::exit(1);
     }
+    finish_effect_log(&mut vm);
     let execute_ms = exec_start.elapsed().as_secs_f64() * 1000.0;
     if request.runtime.leak_detector {
         print_leak_stats();
@@ -175,6 +229,7 @@ pub(crate) fn try_run_parallel_vm(request: ParallelVmRunRequest<'_>) -> bool {
 pub(crate) struct VmRunRequest<'a> {
     pub(crate) compiler: &'a mut flux::compiler::Compiler,
     pub(crate) program: &'a Program,
+    pub(crate) graph: &'a ModuleGraph,
     pub(crate) path: &'a str,
     pub(crate) source: &'a str,
     pub(crate) is_multimodule: bool,
@@ -203,15 +258,33 @@ pub(crate) fn run_vm(mut request: VmRunRequest<'_>) {
     if request.runtime.profiling {
         vm.set_profiling(true, request.compiler.cost_centre_infos.clone());
     }
+    if request.flags.call_trace.enabled() {
+        vm.set_call_trace(
+            request.compiler.call_trace_labels.clone(),
+            call_trace_writer(&request.flags.call_trace),
+        );
+    }
+    vm.set_coverage(request.flags.coverage.enabled);
+    if let Some(log) = effect_log(&request.flags.effect_log) {
+        vm.set_effect_log(log);
+    }
     let exec_start = Instant::now();
-    if let Err(err) = vm.run() {
+    let result = vm.run();
+    vm.flush_call_trace();
+    emit_vm_coverage(&vm, request.graph, request.flags);
+    if let Err(err) = result {
         eprintln!("{err}");
         std::process::exit(1);
     }
+    finish_effect_log(&mut vm);
     let execute_ns = exec_start.elapsed().as_nanos() as u64;
     let execute_ms = execute_ns as f64 / 1_000_000.0;
     if request.runtime.profiling {
-        vm.print_profile_report(execute_ns);
+        write_profile_report(
+            &vm.profile(execute_ns),
+            request.path,
+            &request.flags.profile,
+        );
     }
     if request.runtime.leak_detector {
         print_leak_stats();
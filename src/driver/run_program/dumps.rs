@@ -19,6 +19,7 @@ pub(crate) struct DumpRequest<'a> {
     pub(crate) dump_cfg: bool,
     #[cfg_attr(not(feature = "llvm"), allow(unused))]
     pub(crate) dump_lir_llvm: bool,
+    pub(crate) core_eval: bool,
 }
 
 pub(crate) fn handle_dumps(request: DumpRequest<'_>) -> bool {
@@ -121,6 +122,30 @@ pub(crate) fn handle_dumps(request: DumpRequest<'_>) -> bool {
         return true;
     }
 
+    if request.core_eval {
+        let stages = match request
+            .compiler
+            .core_pass_snapshots(request.merged_program, request.enable_optimize)
+        {
+            Ok(stages) => stages,
+            Err(diag) => {
+                emit_diagnostics(DiagnosticRenderRequest {
+                    diagnostics: &[diag],
+                    default_file: Some(request.path),
+                    default_source: Some(request.source),
+                    show_file_headers: request.is_multimodule,
+                    max_errors: request.max_errors,
+                    format: request.diagnostics_format,
+                    all_errors: request.all_errors,
+                    text_to_stderr: true,
+                });
+                std::process::exit(1);
+            }
+        };
+        report_core_eval(&stages, &request.compiler.interner);
+        return true;
+    }
+
     #[cfg(feature = "llvm")]
     if request.dump_lir_llvm {
         match request
@@ -147,3 +172,50 @@ pub(crate) fn handle_dumps(request: DumpRequest<'_>) -> bool {
 
     false
 }
+
+/// Runs the reference Core interpreter over every pass snapshot and reports
+/// like a normal run: program output on stdout, runtime errors on stderr
+/// with exit 1. Unsupported programs exit 2 so `flux parity` can skip them.
+fn report_core_eval(
+    stages: &[(String, flux::core::CoreProgram)],
+    interner: &flux::syntax::interner::Interner,
+) {
+    use flux::core::eval::{EvalError, evaluate_stages};
+    use std::io::Read;
+
+    let stdin = std::cell::OnceCell::new();
+    let read_stdin = || {
+        stdin
+            .get_or_init(|| {
+                let mut input = String::new();
+                let _ = std::io::stdin().read_to_string(&mut input);
+                input
+            })
+            .clone()
+    };
+    let Some(report) = evaluate_stages(stages, interner, &read_stdin) else {
+        return;
+    };
+
+    print!("{}", report.reference.stdout);
+    eprint!("{}", report.reference.stderr);
+    if let Some((stage, outcome)) = &report.changed_at {
+        eprintln!(
+            "core-eval: behavior changed after `{stage}`: {} (lowered Core {})",
+            outcome.summary(),
+            report.reference.summary()
+        );
+    }
+    match report.reference.result {
+        Ok(()) if report.changed_at.is_some() => std::process::exit(1),
+        Ok(()) => {}
+        Err(EvalError::Runtime(message)) => {
+            eprintln!("runtime error: {message}");
+            std::process::exit(1);
+        }
+        Err(err @ EvalError::Unsupported(_)) => {
+            eprintln!("core-eval: {err}");
+            std::process::exit(2);
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crate::driver::{
     support::shared::{module_display_name, progress_line, short_hash, tag_diagnostics},
 };
 use flux::{
+    ast::type_infer::program_has_typed_holes,
     bytecode::bytecode_cache::{hash_bytes, hash_cache_key, module_cache::ModuleBytecodeCache},
     diagnostics::{Diagnostic, DiagnosticPhase, quality::module_skipped_note, render_display_path},
     shared::cache_paths::cache_key_filename,
@@ -265,7 +266,11 @@ pub(crate) fn compile_modules(request: CompileModulesRequest<'_>) {
             && !request.cache.no_cache
             && llvm_entry_marker.exists();
 
-        if has_vm_cache || skip_for_llvm || has_vm_cache_entry || skip_llvm_entry {
+        let has_typed_holes = program_has_typed_holes(&node.program);
+
+        if !has_typed_holes
+            && (has_vm_cache || skip_for_llvm || has_vm_cache_entry || skip_llvm_entry)
+        {
             if let Some(interface) = current_interface.as_ref() {
                 request.compiler.preload_module_interface(interface);
                 loaded_interfaces.insert(node.path.clone(), interface.clone());
@@ -1,25 +1,47 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{fs, path::Path};
 
+use rayon::prelude::*;
+
 #[cfg(feature = "llvm")]
 use super::backend_policy::should_run_tests_native;
 use super::{
+    doc_tests::{DOC_TEST_FUNCTION, DocTest, collect_doc_tests},
     flags::DriverFlags,
-    frontend::{collect_roots, inject_flow_prelude, validate_no_primops_import},
+    frontend::{
+        collect_roots, extract_module_name_and_sym, inject_flow_prelude, validate_no_primops_import,
+    },
+    mode::TestReportFormat,
     module_compile::{effective_module_strictness, tag_module_diagnostics},
+    reporting::{
+        coverage_report::{apply_coverage_filters, write_coverage_report},
+        test_report::{TestFileReport, TestTotals, render_test_report},
+    },
     session::DriverSession,
     shared::{
         DriverDiagnosticConfig, emit_diagnostics_or_exit, sort_stdlib_first, tag_and_attach_file,
     },
     support::shared::{DiagnosticRenderRequest, emit_diagnostics},
+    test_snapshots::{SnapshotOptions, SnapshotReport, check_snapshots},
 };
 use crate as flux;
 use flux::{
-    compiler::Compiler,
-    diagnostics::{Diagnostic, DiagnosticPhase},
-    syntax::{lexer::Lexer, module_graph::ModuleGraph, parser::Parser},
+    bytecode::{
+        bytecode::Bytecode,
+        bytecode_cache::{BytecodeCache, hash_bytes},
+    },
+    compiler::{Compiler, module_interface::build_interface},
+    core::CoreProgram,
+    diagnostics::{Diagnostic, DiagnosticPhase, Severity, position::Span},
+    shared::source_files::collect_flx_files,
+    syntax::{lexer::Lexer, module_graph::ModuleGraph, parser::Parser, program::Program},
     vm::VM,
-    vm::test_runner::{collect_test_functions, print_test_report, run_tests},
+    vm::coverage::CoverageData,
+    vm::test_runner::{
+        TestFailure, TestOutcome, TestResult, collect_test_functions, print_test_report, run_tests,
+    },
 };
 #[cfg(any(feature = "llvm", test))]
 use flux::{
@@ -33,15 +55,15 @@ pub(crate) struct TestRunRequest<'a> {
 }
 
 /// Parsed source file plus module graph roots for a test run.
-struct ParsedTestFile {
-    source: String,
-    roots: Vec<PathBuf>,
-    parser: Parser,
-    program: flux::syntax::program::Program,
+pub(crate) struct ParsedTestFile {
+    pub(crate) source: String,
+    pub(crate) roots: Vec<PathBuf>,
+    pub(crate) parser: Parser,
+    pub(crate) program: Program,
 }
 
 /// Loads and parses a test file before graph construction.
-fn load_test_file(path: &str, request: &TestRunRequest<'_>) -> ParsedTestFile {
+pub(crate) fn load_test_file(path: &str, request: &TestRunRequest<'_>) -> ParsedTestFile {
     let source = match fs::read_to_string(path) {
         Ok(s) => s,
         Err(e) => {
@@ -68,7 +90,7 @@ fn load_test_file(path: &str, request: &TestRunRequest<'_>) -> ParsedTestFile {
 }
 
 /// Emits parse diagnostics for the initial test file and exits on parse errors.
-fn emit_parse_diagnostics_or_exit(
+pub(crate) fn emit_parse_diagnostics_or_exit(
     path: &str,
     source: &str,
     parser: &mut Parser,
@@ -95,16 +117,224 @@ fn emit_parse_diagnostics_or_exit(
     }
 }
 
-/// Runs the discovered tests on the VM backend.
-fn run_tests_vm(file_name: &str, compiler: &Compiler, tests: Vec<(String, usize)>) -> bool {
+/// How the tests of a file are executed.
+///
+/// Program output is always captured into the results: snapshot lines are taken out of it,
+/// and a text run prints the rest before the report.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TestExecution {
+    jobs: usize,
+    timeout: Option<Duration>,
+    /// Count VM coverage (`--coverage`).
+    coverage: bool,
+}
+
+impl TestExecution {
+    pub(crate) fn from_flags(flags: &DriverFlags) -> Self {
+        Self {
+            jobs: flags.test.jobs,
+            timeout: flags.test.timeout,
+            coverage: flags.coverage.enabled,
+        }
+    }
+
+    /// Builds the worker pool for `--test-jobs`, or `None` when tests run one at a time.
+    fn thread_pool(self, test_count: usize) -> Option<rayon::ThreadPool> {
+        if self.jobs <= 1 || test_count <= 1 {
+            return None;
+        }
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.jobs)
+            .build()
+            .inspect_err(|e| eprintln!("warning: running tests one at a time: {e}"))
+            .ok()
+    }
+}
+
+/// Runs the discovered tests on the VM backend and returns their results with the coverage
+/// they counted, which is empty without `--coverage`.
+///
+/// Tests run in order in one VM, or with `--test-jobs` each in a fresh VM on a worker thread.
+fn run_tests_vm(
+    path: &str,
+    compiler: &Compiler,
+    tests: Vec<(String, usize)>,
+    execution: TestExecution,
+) -> (Vec<TestResult>, CoverageData) {
     let bytecode = compiler.bytecode();
+    if let Some(pool) = execution.thread_pool(tests.len()) {
+        match run_tests_vm_parallel(path, &bytecode, &tests, execution, &pool) {
+            Ok(run) => return run,
+            Err(e) => eprintln!("warning: running tests one at a time: {e}"),
+        }
+    }
+
     let mut vm = VM::new(bytecode);
+    vm.set_coverage(execution.coverage);
     if let Err(err) = vm.run() {
         eprintln!("Error during test setup: {}", err);
         std::process::exit(1);
     }
-    let results = run_tests(&mut vm, tests);
-    print_test_report(file_name, &results)
+    vm.set_capture_output(true);
+    let results = run_tests(&mut vm, path, tests, execution.timeout);
+    (results, vm.coverage().unwrap_or_default())
+}
+
+/// Runs every test in its own VM on `pool`.
+///
+/// VM values are reference-counted and stay on the thread that created them, so the workers
+/// do not share the compiled program: it is written once in the bytecode cache format and
+/// each test loads its own copy. The copy lives in a per-process temporary directory that is
+/// removed once the tests finish.
+fn run_tests_vm_parallel(
+    path: &str,
+    bytecode: &Bytecode,
+    tests: &[(String, usize)],
+    execution: TestExecution,
+    pool: &rayon::ThreadPool,
+) -> std::io::Result<(Vec<TestResult>, CoverageData)> {
+    let jobs_dir = std::env::temp_dir().join(format!("flux_test_jobs_{}", std::process::id()));
+    let cache = BytecodeCache::new(jobs_dir.clone());
+    let key = hash_bytes(path.as_bytes());
+    if let Err(e) = cache.store(
+        Path::new(path),
+        &key,
+        env!("CARGO_PKG_VERSION"),
+        bytecode,
+        &[],
+    ) {
+        let _ = fs::remove_dir_all(&jobs_dir);
+        return Err(e);
+    }
+    let program = cache.cache_path(Path::new(path), &key);
+
+    let runs: Vec<(TestResult, CoverageData)> = pool.install(|| {
+        tests
+            .par_iter()
+            .map(|test| run_isolated_vm_test(path, &cache, &program, test.clone(), execution))
+            .collect()
+    });
+    let _ = fs::remove_dir_all(&jobs_dir);
+    let mut coverage = CoverageData::default();
+    let mut results = Vec::with_capacity(runs.len());
+    for (result, test_coverage) in runs {
+        results.push(result);
+        coverage.merge(test_coverage);
+    }
+    Ok((results, coverage))
+}
+
+/// Runs one test in a VM of its own, loaded from the serialized `program`.
+fn run_isolated_vm_test(
+    path: &str,
+    cache: &BytecodeCache,
+    program: &Path,
+    test: (String, usize),
+    execution: TestExecution,
+) -> (TestResult, CoverageData) {
+    let name = test.0.clone();
+    let setup_failure = |message: String| {
+        let result = TestResult {
+            name: name.clone(),
+            module: path.to_string(),
+            elapsed_ms: 0.0,
+            outcome: TestOutcome::Fail(TestFailure::new(message)),
+            output: String::new(),
+        };
+        (result, CoverageData::default())
+    };
+    let Some(bytecode) = cache.load_file(program) else {
+        return setup_failure(format!(
+            "failed to load the compiled test program {}",
+            program.display()
+        ));
+    };
+    let mut vm = VM::new(bytecode);
+    vm.set_coverage(execution.coverage);
+    if let Err(err) = vm.run() {
+        return setup_failure(format!("Error during test setup: {err}"));
+    }
+    vm.set_capture_output(true);
+    match run_tests(&mut vm, path, vec![test], execution.timeout).pop() {
+        Some(result) => (result, vm.coverage().unwrap_or_default()),
+        None => setup_failure(format!("test {name} did not run")),
+    }
+}
+
+/// Returns whether the human-readable report is printed: always, unless a machine-readable
+/// report takes over stdout.
+fn shows_text_report(flags: &DriverFlags) -> bool {
+    flags.test.format == TestReportFormat::Text || flags.test.output.is_some()
+}
+
+/// Returns whether program output stays in the results instead of being printed, which
+/// every machine-readable report does.
+fn captures_test_output(flags: &DriverFlags) -> bool {
+    flags.test.format != TestReportFormat::Text
+}
+
+/// Collects the `.flx` files under `dir` that define tests, in path order.
+///
+/// Files without a `test_` function, or with `--doc-tests` a ` ```flux ` example, are skipped
+/// before compiling, so helper modules and programs next to the tests are not built on their
+/// own.
+fn discover_test_files(dir: &Path, doc_tests: bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_flx_files(dir, &mut files);
+    files.sort();
+    files.retain(|file| {
+        fs::read_to_string(file).is_ok_and(|source| {
+            source.contains("fn test_") || (doc_tests && source.contains("```flux"))
+        })
+    });
+    files
+}
+
+/// Prints the cross-file summary and writes the machine-readable report, then exits with a
+/// failure status when any test failed.
+fn finish_test_run(reports: &[TestFileReport], flags: &DriverFlags) {
+    let totals = TestTotals::of(reports);
+    if shows_text_report(flags) && reports.len() > 1 {
+        println!(
+            "\n{} files, {} tests: {} passed, {} failed",
+            reports.len(),
+            totals.total(),
+            totals.passed,
+            totals.failed
+        );
+        let mut snapshots = SnapshotReport::default();
+        for report in reports {
+            snapshots.add(&report.snapshots);
+        }
+        for line in snapshots.summary_lines() {
+            println!("{line}");
+        }
+    }
+
+    if flags.coverage.enabled {
+        let mut coverage = CoverageData::default();
+        for report in reports {
+            coverage.merge(report.coverage.clone());
+        }
+        write_coverage_report(&coverage, &flags.coverage);
+    }
+
+    if flags.test.format != TestReportFormat::Text {
+        let report = render_test_report(flags.test.format, reports);
+        match &flags.test.output {
+            Some(output) => {
+                if let Err(e) = fs::write(output, report) {
+                    eprintln!("Error writing test report {}: {}", output.display(), e);
+                    std::process::exit(1);
+                }
+            }
+            None => print!("{report}"),
+        }
+    }
+
+    if totals.failed > 0 {
+        std::process::exit(1);
+    }
 }
 
 /// Prints the empty-test discovery message for the current file.
@@ -145,7 +375,7 @@ fn filter_tests_by_name(
 
 #[cfg(any(feature = "llvm", test))]
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum NativeTestHarnessSource {
+pub(crate) enum NativeTestHarnessSource {
     Generated(String),
     OriginalSource,
 }
@@ -162,11 +392,26 @@ fn build_native_test_harness_source(
     source: &str,
     test_name: &str,
     hidden_main_name: &str,
+) -> Result<NativeTestHarnessSource, String> {
+    build_native_harness_source(
+        source,
+        &format!("fn main() {{ {test_name}(); }}"),
+        hidden_main_name,
+    )
+}
+
+/// Appends `harness_main` to `source`, renaming a top-level `main` of the source to
+/// `hidden_main_name` first. A source with several top-level `main` functions is kept as is.
+#[cfg(any(feature = "llvm", test))]
+pub(crate) fn build_native_harness_source(
+    source: &str,
+    harness_main: &str,
+    hidden_main_name: &str,
 ) -> Result<NativeTestHarnessSource, String> {
     let analysis = analyze_top_level_main_usage(source)?;
     match analysis {
         TopLevelMainAnalysis::NoMain => Ok(NativeTestHarnessSource::Generated(
-            synthetic_test_harness_source(source, test_name),
+            synthetic_harness_source(source, harness_main),
         )),
         TopLevelMainAnalysis::SingleMain {
             main_name_range,
@@ -179,7 +424,7 @@ fn build_native_test_harness_source(
             }
             let rewritten = rewrite_source_range(source, main_name_range, hidden_main_name);
             Ok(NativeTestHarnessSource::Generated(
-                synthetic_test_harness_source(&rewritten, test_name),
+                synthetic_harness_source(&rewritten, harness_main),
             ))
         }
         TopLevelMainAnalysis::MultipleMains => Ok(NativeTestHarnessSource::OriginalSource),
@@ -251,9 +496,9 @@ fn analyze_top_level_main_usage(source: &str) -> Result<TopLevelMainAnalysis, St
 }
 
 #[cfg(any(feature = "llvm", test))]
-fn synthetic_test_harness_source(source: &str, test_name: &str) -> String {
+fn synthetic_harness_source(source: &str, harness_main: &str) -> String {
     let source = source.trim_end_matches('\n');
-    format!("{source}\n\nfn main() {{ {test_name}(); }}\n")
+    format!("{source}\n\n{harness_main}\n")
 }
 
 #[cfg(any(feature = "llvm", test))]
@@ -315,24 +560,43 @@ fn position_to_byte_offset(source: &str, position: Position) -> Result<usize, St
     ))
 }
 
+/// Runs the tests of `path`, a test file or a directory of test files, and reports the
+/// aggregated results.
 pub(crate) fn run_test_file(path: &str, request: TestRunRequest<'_>) {
-    let ParsedTestFile {
-        source,
-        roots,
-        mut parser,
-        mut program,
-    } = load_test_file(path, &request);
-    let entry_path = Path::new(path);
+    let reports: Vec<TestFileReport> = if Path::new(path).is_dir() {
+        let files = discover_test_files(Path::new(path), request.flags.test.doc_tests);
+        if files.is_empty() && shows_text_report(request.flags) {
+            println!("No test files found under {}.", path);
+        }
+        files
+            .iter()
+            .filter_map(|file| run_single_test_file(&file.to_string_lossy(), &request, true))
+            .collect()
+    } else {
+        run_single_test_file(path, &request, false)
+            .into_iter()
+            .collect()
+    };
+    finish_test_run(&reports, request.flags);
+}
 
-    let mut all_diagnostics: Vec<Diagnostic> = Vec::new();
-    emit_parse_diagnostics_or_exit(
-        path,
-        &source,
-        &mut parser,
-        request.session,
-        &mut all_diagnostics,
-    );
+/// A test program compiled together with the modules it imports.
+pub(crate) struct CompiledTestProgram {
+    pub(crate) compiler: Compiler,
+    pub(crate) graph: ModuleGraph,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+}
 
+/// Builds the module graph of a parsed test program and compiles every module of it,
+/// stdlib first. Compile errors are returned with the warnings in `diagnostics`.
+pub(crate) fn compile_test_program(
+    path: &str,
+    mut program: Program,
+    mut parser: Parser,
+    roots: &[PathBuf],
+    request: &TestRunRequest<'_>,
+) -> CompiledTestProgram {
+    let mut all_diagnostics = Vec::new();
     let mut primops_import_diags = validate_no_primops_import(&program, parser.interner(), path);
     if !primops_import_diags.is_empty() {
         tag_and_attach_file(&mut primops_import_diags, DiagnosticPhase::Parse, path);
@@ -341,14 +605,12 @@ pub(crate) fn run_test_file(path: &str, request: TestRunRequest<'_>) {
     inject_flow_prelude(&mut program, &mut parser, request.flags.is_native_backend());
     let interner = parser.take_interner();
     let graph_result =
-        ModuleGraph::build_with_entry_and_roots(entry_path, &program, interner, &roots);
+        ModuleGraph::build_with_entry_and_roots(Path::new(path), &program, interner, roots);
     let mut graph_diags = graph_result.diagnostics;
     tag_and_attach_file(&mut graph_diags, DiagnosticPhase::ModuleGraph, path);
     all_diagnostics.extend(graph_diags);
 
     let failed = graph_result.failed_modules;
-    let module_count = graph_result.graph.module_count();
-    let is_multimodule = module_count > 1;
     let graph = graph_result.graph;
     let entry_module_kind = graph.entry_node().map(|node| node.kind).unwrap_or_default();
 
@@ -381,17 +643,84 @@ pub(crate) fn run_test_file(path: &str, request: TestRunRequest<'_>) {
         );
         all_diagnostics.append(&mut compiler_warnings);
 
-        if let Err(mut diags) = compile_result {
-            tag_module_diagnostics(&mut diags, DiagnosticPhase::TypeCheck, &node.path);
-            all_diagnostics.append(&mut diags);
+        match compile_result {
+            Ok(()) => preload_public_classes(&mut compiler, &node.program),
+            Err(mut diags) => {
+                tag_module_diagnostics(&mut diags, DiagnosticPhase::TypeCheck, &node.path);
+                all_diagnostics.append(&mut diags);
+            }
         }
     }
 
+    CompiledTestProgram {
+        compiler,
+        graph,
+        diagnostics: all_diagnostics,
+    }
+}
+
+/// Test runs compile the whole graph with one compiler and write no interface
+/// files, so a module's public classes and instances are handed on to the
+/// modules compiled after it the way `run` preloads them from its interface.
+fn preload_public_classes(compiler: &mut Compiler, program: &Program) {
+    let Some((module_name, module_sym)) = extract_module_name_and_sym(program, &compiler.interner)
+    else {
+        return;
+    };
+    let interface = build_interface(
+        &module_name,
+        module_sym,
+        &[0; 32],
+        &[0; 32],
+        &CoreProgram {
+            defs: Vec::new(),
+            top_level_items: Vec::new(),
+        },
+        &HashMap::new(),
+        &HashMap::new(),
+        &HashMap::new(),
+        Some(compiler.class_env()),
+        Vec::new(),
+        &compiler.interner,
+    );
+    compiler.preload_module_interface(&interface);
+}
+
+/// Compiles and runs one test file. Returns `None` for a file of a directory run that has no
+/// (matching) tests, which is left out of the report.
+fn run_single_test_file(
+    path: &str,
+    request: &TestRunRequest<'_>,
+    in_directory: bool,
+) -> Option<TestFileReport> {
+    let ParsedTestFile {
+        source,
+        roots,
+        mut parser,
+        program,
+    } = load_test_file(path, request);
+    let entry_path = Path::new(path);
+
+    let mut all_diagnostics: Vec<Diagnostic> = Vec::new();
+    emit_parse_diagnostics_or_exit(
+        path,
+        &source,
+        &mut parser,
+        request.session,
+        &mut all_diagnostics,
+    );
+
+    let CompiledTestProgram {
+        compiler,
+        graph,
+        diagnostics,
+    } = compile_test_program(path, program, parser, &roots, request);
+    all_diagnostics.extend(diagnostics);
     emit_diagnostics_or_exit(
         &all_diagnostics,
         path,
         source.as_str(),
-        is_multimodule,
+        graph.module_count() > 1,
         DriverDiagnosticConfig::from(request.session),
     );
 
@@ -400,65 +729,268 @@ pub(crate) fn run_test_file(path: &str, request: TestRunRequest<'_>) {
         request.flags.input.test_filter.as_deref(),
     );
 
-    if tests.is_empty() {
-        let file_name = entry_path
+    // Directory runs name each file by its path; a single file keeps its short name.
+    let file_name = if in_directory {
+        path
+    } else {
+        entry_path
             .file_name()
             .and_then(|n| n.to_str())
-            .unwrap_or(path);
-        print_no_tests_message(file_name, request.flags.input.test_filter.as_deref());
-        return;
-    }
+            .unwrap_or(path)
+    };
 
-    let file_name = entry_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(path);
+    let doc_tests: Vec<DocTest> = if request.flags.test.doc_tests {
+        let filter = request.flags.input.test_filter.as_deref();
+        collect_doc_tests(entry_path, &source)
+            .into_iter()
+            .filter(|doc_test| filter.is_none_or(|filter| doc_test.name.contains(filter)))
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-    #[cfg(feature = "llvm")]
-    let all_passed = if should_use_native_test_backend(request.flags) {
-        run_tests_native(NativeTestRunConfig {
-            file_name,
-            source_path: path,
+    if tests.is_empty() && doc_tests.is_empty() {
+        if in_directory {
+            return None;
+        }
+        if shows_text_report(request.flags) {
+            print_no_tests_message(file_name, request.flags.input.test_filter.as_deref());
+        }
+        return Some(TestFileReport {
+            path: path.to_string(),
+            results: Vec::new(),
+            snapshots: SnapshotReport::default(),
+            coverage: CoverageData::default(),
+        });
+    }
+
+    let execution = TestExecution::from_flags(request.flags);
+    let (mut results, mut coverage) = if tests.is_empty() {
+        (Vec::new(), CoverageData::default())
+    } else {
+        run_test_functions(TestFunctionRun {
+            path,
             source: &source,
             roots: &roots,
-            roots_only: request.session.roots_only,
-            tests: &tests,
-            enable_optimize: request.session.enable_optimize,
-            enable_analyze: request.session.enable_analyze,
-            strict_mode: request.session.strict_mode,
-            use_native: should_use_native_test_backend(request.flags),
+            compiler: &compiler,
+            tests,
+            request,
+            execution,
         })
+    };
+    apply_coverage_filters(&mut coverage, &graph, &request.flags.coverage);
+    for doc_test in &doc_tests {
+        let (result, doc_coverage) = run_doc_test(path, &roots, doc_test, request, execution);
+        results.push(result);
+        coverage.merge(doc_coverage);
+    }
+
+    let snapshots = check_snapshots(
+        entry_path,
+        &mut results,
+        SnapshotOptions {
+            update: request.flags.test.update_snapshots,
+            all_tests_ran: request.flags.input.test_filter.is_none(),
+        },
+    );
+    if !captures_test_output(request.flags) {
+        for result in &mut results {
+            print!("{}", std::mem::take(&mut result.output));
+        }
+    }
+    if shows_text_report(request.flags) {
+        print_test_report(file_name, &results, &snapshots.summary_lines());
+    }
+    Some(TestFileReport {
+        path: path.to_string(),
+        results,
+        snapshots,
+        coverage,
+    })
+}
+
+/// The `test_*` functions of a compiled test file and how to run them.
+struct TestFunctionRun<'a> {
+    path: &'a str,
+    #[cfg_attr(not(feature = "llvm"), allow(dead_code))]
+    source: &'a str,
+    #[cfg_attr(not(feature = "llvm"), allow(dead_code))]
+    roots: &'a [PathBuf],
+    compiler: &'a Compiler,
+    tests: Vec<(String, usize)>,
+    #[cfg_attr(not(feature = "llvm"), allow(dead_code))]
+    request: &'a TestRunRequest<'a>,
+    execution: TestExecution,
+}
+
+/// Runs the `test_*` functions of a file on the selected backend.
+fn run_test_functions(run: TestFunctionRun<'_>) -> (Vec<TestResult>, CoverageData) {
+    #[cfg(feature = "llvm")]
+    if should_use_native_test_backend(run.request.flags) {
+        let results = run_tests_native(NativeTestRunConfig {
+            source_path: run.path,
+            source: run.source,
+            roots: run.roots,
+            roots_only: run.request.session.roots_only,
+            tests: &run.tests,
+            enable_optimize: run.request.session.enable_optimize,
+            enable_analyze: run.request.session.enable_analyze,
+            strict_mode: run.request.session.strict_mode,
+            use_native: should_use_native_test_backend(run.request.flags),
+            execution: run.execution,
+        });
+        return (results, CoverageData::default());
+    }
+
+    run_tests_vm(run.path, run.compiler, run.tests, run.execution)
+}
+
+/// Compiles and runs one doc-test of the module in `path` on the VM.
+///
+/// The program is compiled as a file next to the module, with the module's root added so the
+/// module can be imported. An example that does not compile fails its test; locations inside
+/// the example point into the doc comment.
+fn run_doc_test(
+    path: &str,
+    roots: &[PathBuf],
+    doc_test: &DocTest,
+    request: &TestRunRequest<'_>,
+    execution: TestExecution,
+) -> (TestResult, CoverageData) {
+    let module_path = Path::new(path);
+    let program_path = module_path
+        .with_extension(format!("{}.flx", doc_test.name))
+        .to_string_lossy()
+        .into_owned();
+    let mut roots = roots.to_vec();
+    roots.extend(module_root(module_path, &doc_test.module));
+    let location = |file: &str, span: Span| {
+        if file == program_path {
+            doc_test
+                .source_span(span)
+                .map(|span| (path.to_string(), span))
+        } else {
+            Some((file.to_string(), span))
+        }
+    };
+    let failure = |message: String, location: Option<(String, Span)>| {
+        let result = TestResult {
+            name: doc_test.name.clone(),
+            module: path.to_string(),
+            elapsed_ms: 0.0,
+            outcome: TestOutcome::Fail(TestFailure { message, location }),
+            output: String::new(),
+        };
+        (result, CoverageData::default())
+    };
+
+    let source = doc_test.program_source();
+    let mut parser = Parser::new(Lexer::new(&source));
+    let program = parser.parse_program();
+    let compiled = if parser.errors.is_empty() {
+        let compiled = compile_test_program(&program_path, program, parser, &roots, request);
+        let errors = compiled
+            .diagnostics
+            .iter()
+            .find(|diag| diag.severity() == Severity::Error)
+            .cloned();
+        errors.map_or(Ok(compiled), Err)
     } else {
-        run_tests_vm(file_name, &compiler, tests)
+        Err(parser.errors.remove(0))
+    };
+    let compiled = match compiled {
+        Ok(compiled) => compiled,
+        Err(error) => {
+            let file = error.file().unwrap_or(&program_path).to_string();
+            return failure(
+                format!(
+                    "doc example does not compile: {}",
+                    describe_diagnostic(&error)
+                ),
+                error.span().and_then(|span| location(&file, span)),
+            );
+        }
     };
 
-    #[cfg(not(feature = "llvm"))]
-    let all_passed = run_tests_vm(file_name, &compiler, tests);
+    let Some(test) =
+        collect_test_functions(&compiled.compiler.symbol_table, &compiled.compiler.interner)
+            .into_iter()
+            .find(|(name, _)| name == DOC_TEST_FUNCTION)
+    else {
+        return failure(
+            format!("doc example did not define {DOC_TEST_FUNCTION}"),
+            None,
+        );
+    };
+    let execution = TestExecution {
+        jobs: 1,
+        ..execution
+    };
+    let (results, mut coverage) =
+        run_tests_vm(&program_path, &compiled.compiler, vec![test], execution);
+    apply_coverage_filters(&mut coverage, &compiled.graph, &request.flags.coverage);
+    coverage.files.remove(&program_path);
+    let Some(mut result) = results.into_iter().next() else {
+        return failure(format!("doc test {} did not run", doc_test.name), None);
+    };
+    result.name = doc_test.name.clone();
+    result.module = path.to_string();
+    if let TestOutcome::Fail(failure) = &mut result.outcome {
+        failure.location = failure
+            .location
+            .take()
+            .and_then(|(file, span)| location(&file, span));
+    }
+    (result, coverage)
+}
 
-    if !all_passed {
-        std::process::exit(1);
+/// The module root of `path` that declares `module`: `src` for `src/Data/Shapes.flx` and
+/// `Data.Shapes`.
+fn module_root(path: &Path, module: &str) -> Option<PathBuf> {
+    let mut root = path.parent()?;
+    for _ in 1..module.split('.').count() {
+        root = root.parent()?;
     }
+    Some(root.to_path_buf())
+}
+
+/// One-line form of a diagnostic for a test failure: `error[E004]: title: message`.
+fn describe_diagnostic(diagnostic: &Diagnostic) -> String {
+    let title = diagnostic
+        .display_title()
+        .unwrap_or_else(|| diagnostic.title());
+    let mut text = match diagnostic.code() {
+        Some(code) => format!("error[{code}]: {title}"),
+        None => format!("error: {title}"),
+    };
+    if let Some(message) = diagnostic.message().and_then(|m| m.lines().next()) {
+        text.push_str(": ");
+        text.push_str(message);
+    }
+    text
 }
 
 #[cfg(feature = "llvm")]
-struct NativeTestRunConfig<'a> {
-    file_name: &'a str,
-    source_path: &'a str,
-    source: &'a str,
-    roots: &'a [PathBuf],
-    roots_only: bool,
-    tests: &'a [(String, usize)],
-    enable_optimize: bool,
-    enable_analyze: bool,
-    strict_mode: bool,
-    use_native: bool,
+pub(crate) struct NativeTestRunConfig<'a> {
+    pub(crate) source_path: &'a str,
+    pub(crate) source: &'a str,
+    pub(crate) roots: &'a [PathBuf],
+    pub(crate) roots_only: bool,
+    pub(crate) tests: &'a [(String, usize)],
+    pub(crate) enable_optimize: bool,
+    pub(crate) enable_analyze: bool,
+    pub(crate) strict_mode: bool,
+    pub(crate) use_native: bool,
+    pub(crate) execution: TestExecution,
 }
 
+/// Adds the arguments that compile a test harness to the native binary `binary_path`.
 #[cfg(feature = "llvm")]
-fn append_native_test_command_args(
+pub(crate) fn append_native_test_command_args(
     cmd: &mut std::process::Command,
     config: &NativeTestRunConfig<'_>,
     source_path: &Path,
+    binary_path: &Path,
 ) {
     if config.use_native {
         cmd.arg("--native");
@@ -479,102 +1011,243 @@ fn append_native_test_command_args(
     for root in config.roots {
         cmd.arg("--root").arg(root);
     }
+    cmd.arg("--emit-binary").arg("-o").arg(binary_path);
     cmd.arg(source_path);
 }
 
+/// Runs each test as its own native binary, on `--test-jobs` workers.
+///
+/// A child `flux` process compiles the harness for a test, then the binary runs in a process
+/// of its own. A crash or a `--test-timeout` kill fails only that test.
 #[cfg(feature = "llvm")]
-fn run_tests_native(config: NativeTestRunConfig<'_>) -> bool {
-    use flux::vm::test_runner::{TestOutcome, TestResult};
-    use std::process::Command;
-    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+fn run_tests_native(config: NativeTestRunConfig<'_>) -> Vec<TestResult> {
+    use std::time::{SystemTime, UNIX_EPOCH};
 
     let exe = std::env::current_exe().unwrap_or_else(|e| {
         eprintln!("Failed to locate current executable for native test mode: {e}");
         std::process::exit(1);
     });
-    let mut results = Vec::new();
     let unique = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_nanos())
         .unwrap_or(0);
+    let run = |(idx, (name, _)): (usize, &(String, usize))| {
+        run_native_test(&config, &exe, unique, idx, name)
+    };
 
-    for (idx, (name, _)) in config.tests.iter().enumerate() {
-        let start = Instant::now();
-        let hidden_main_name = format!("__flux_test_user_main_{}_{}", unique, idx);
-        let harness_source =
-            match build_native_test_harness_source(config.source, name, &hidden_main_name) {
-                Ok(harness_source) => harness_source,
-                Err(err) => {
-                    results.push(TestResult {
-                        name: name.clone(),
-                        elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
-                        outcome: TestOutcome::Fail(err),
-                    });
-                    continue;
-                }
-            };
-        let harness_path = std::env::temp_dir().join(format!(
-            "flux_native_test_{}_{}_{}.flx",
-            std::process::id(),
-            unique,
-            idx
-        ));
-        let mut cmd = Command::new(&exe);
-        let generated_harness = matches!(harness_source, NativeTestHarnessSource::Generated(_));
-        let child_source_path = if generated_harness {
-            harness_path.as_path()
-        } else {
-            Path::new(config.source_path)
+    match config.execution.thread_pool(config.tests.len()) {
+        Some(pool) => pool.install(|| config.tests.par_iter().enumerate().map(run).collect()),
+        None => config.tests.iter().enumerate().map(run).collect(),
+    }
+}
+
+/// Compiles and runs the native harness of one test.
+#[cfg(feature = "llvm")]
+fn run_native_test(
+    config: &NativeTestRunConfig<'_>,
+    exe: &Path,
+    unique: u128,
+    idx: usize,
+    name: &str,
+) -> TestResult {
+    use std::process::Command;
+    use std::time::Instant;
+
+    let start = Instant::now();
+    let result = |outcome: TestOutcome, output: String| TestResult {
+        name: name.to_string(),
+        module: config.source_path.to_string(),
+        elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+        outcome,
+        output,
+    };
+    let hidden_main_name = format!("__flux_test_user_main_{}_{}", unique, idx);
+    let harness_source =
+        match build_native_test_harness_source(config.source, name, &hidden_main_name) {
+            Ok(harness_source) => harness_source,
+            Err(err) => return result(TestOutcome::Fail(TestFailure::new(err)), String::new()),
         };
-        if let NativeTestHarnessSource::Generated(ref source_text) = harness_source
-            && let Err(e) = std::fs::write(&harness_path, source_text)
-        {
-            eprintln!(
-                "Failed to write native test harness {}: {e}",
-                harness_path.display()
-            );
-            std::process::exit(1);
+    let harness_path = std::env::temp_dir().join(format!(
+        "flux_native_test_{}_{}_{}.flx",
+        std::process::id(),
+        unique,
+        idx
+    ));
+    let binary_path = harness_path.with_extension(std::env::consts::EXE_EXTENSION);
+    let generated_harness = matches!(harness_source, NativeTestHarnessSource::Generated(_));
+    let child_source_path = if generated_harness {
+        harness_path.as_path()
+    } else {
+        Path::new(config.source_path)
+    };
+    if let NativeTestHarnessSource::Generated(ref source_text) = harness_source
+        && let Err(e) = std::fs::write(&harness_path, source_text)
+    {
+        eprintln!(
+            "Failed to write native test harness {}: {e}",
+            harness_path.display()
+        );
+        std::process::exit(1);
+    }
+
+    let mut cmd = Command::new(exe);
+    append_native_test_command_args(&mut cmd, config, child_source_path, &binary_path);
+    cmd.env("NO_COLOR", "1");
+    let child_source = child_source_path.to_string_lossy();
+    let (outcome, output) = match cmd.output() {
+        Ok(compile) if compile.status.success() => {
+            run_native_test_binary(&binary_path, &child_source, config.execution.timeout)
         }
-        append_native_test_command_args(&mut cmd, &config, child_source_path);
-        cmd.env("NO_COLOR", "1");
-        let output = cmd.output();
-        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
-
-        let outcome = match output {
-            Ok(output) if output.status.success() => TestOutcome::Pass,
-            Ok(output) => {
-                let mut text = String::new();
-                text.push_str(&String::from_utf8_lossy(&output.stdout));
-                text.push_str(&String::from_utf8_lossy(&output.stderr));
-                TestOutcome::Fail(text.trim().to_string())
-            }
-            Err(err) => TestOutcome::Fail(format!(
+        Ok(compile) => {
+            let mut text = String::from_utf8_lossy(&compile.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&compile.stderr));
+            (native_test_failure(&text, &child_source), String::new())
+        }
+        Err(err) => (
+            TestOutcome::Fail(TestFailure::new(format!(
                 "failed to run native test harness for {} (from {}): {}",
                 name, config.source_path, err
-            )),
-        };
+            ))),
+            String::new(),
+        ),
+    };
 
-        if generated_harness {
-            let _ = std::fs::remove_file(&harness_path);
-        }
-        results.push(TestResult {
-            name: name.clone(),
-            elapsed_ms,
-            outcome,
-        });
+    if generated_harness {
+        let _ = std::fs::remove_file(&harness_path);
     }
+    let _ = std::fs::remove_file(&binary_path);
+    let outcome = match outcome {
+        TestOutcome::Fail(mut failure) => {
+            // The harness keeps the source's line layout, so its diagnostics apply to the
+            // original file once they name it.
+            failure.message = failure.message.replace(&*child_source, config.source_path);
+            failure.location = failure
+                .location
+                .map(|(_, span)| (config.source_path.to_string(), span));
+            TestOutcome::Fail(failure)
+        }
+        pass => pass,
+    };
+    result(outcome, output)
+}
 
-    print_test_report(config.file_name, &results)
+/// Runs a compiled test binary in its own process, killing it once `timeout` passes.
+/// Returns the outcome and the binary's stdout.
+#[cfg(feature = "llvm")]
+pub(crate) fn run_native_test_binary(
+    binary_path: &Path,
+    harness_path: &str,
+    timeout: Option<Duration>,
+) -> (TestOutcome, String) {
+    use super::{
+        reporting::runtime_errors::render_native_runtime_error,
+        run_program::backend::native::native_exit_summary,
+    };
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+    use std::time::Instant;
+
+    let mut child = match Command::new(binary_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            let message = format!("failed to run native test binary: {err}");
+            return (TestOutcome::Fail(TestFailure::new(message)), String::new());
+        }
+    };
+    // Drain both pipes while waiting, so a chatty test cannot block on a full pipe.
+    let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut bytes);
+            }
+            String::from_utf8_lossy(&bytes).into_owned()
+        })
+    };
+    let stdout = read_pipe(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr = read_pipe(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(2)),
+            Err(_) => break child.wait().ok(),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let outcome = match status {
+        Some(status) if status.success() => TestOutcome::Pass,
+        Some(status) => {
+            let mut message = render_native_runtime_error(harness_path, &stderr)
+                .unwrap_or_else(|| stderr.trim().to_string());
+            // A crash has no Flux panic text; name the signal after whatever stderr it left.
+            if message.is_empty() || status.code().is_none() {
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(&native_exit_summary(&status));
+            }
+            native_test_failure(&message, harness_path)
+        }
+        None => TestOutcome::Fail(TestFailure::new(format!(
+            "timed out after {}ms",
+            timeout.unwrap_or_default().as_millis()
+        ))),
+    };
+    (outcome, stdout)
+}
+
+/// A failing native test whose location is read from its diagnostics.
+#[cfg(feature = "llvm")]
+fn native_test_failure(text: &str, harness_path: &str) -> TestOutcome {
+    TestOutcome::Fail(TestFailure {
+        message: text.trim().to_string(),
+        location: native_failure_location(text, harness_path)
+            .map(|span| (harness_path.to_string(), span)),
+    })
+}
+
+/// Finds the first `<source_path>:<line>:<column>` in a native test's output: the location of
+/// the failing assertion in the test's own file. The harness keeps the source's line layout,
+/// so the span applies to the original file.
+#[cfg(any(feature = "llvm", test))]
+fn native_failure_location(output: &str, source_path: &str) -> Option<Span> {
+    let prefix = format!("{source_path}:");
+    output.match_indices(&prefix).find_map(|(start, _)| {
+        let rest = &output[start + prefix.len()..];
+        let mut parts = rest.splitn(3, ':');
+        let line = parts.next()?.parse::<usize>().ok()?;
+        let column: String = parts
+            .next()?
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        let column = column.parse::<usize>().ok()?.saturating_sub(1);
+        let position = Position::new(line, column);
+        Some(Span::new(position, position))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
         NativeTestHarnessSource, build_native_test_harness_source, filter_tests_by_name,
-        should_use_native_test_backend,
+        native_failure_location, should_use_native_test_backend,
     };
     #[cfg(feature = "llvm")]
-    use super::{NativeTestRunConfig, append_native_test_command_args};
+    use super::{NativeTestRunConfig, TestExecution, append_native_test_command_args};
     use crate::driver::{backend::Backend, test_support::base_flags};
     #[cfg(feature = "llvm")]
     use std::path::{Path, PathBuf};
@@ -619,6 +1292,16 @@ mod tests {
         assert!(filtered.is_empty());
     }
 
+    #[test]
+    fn native_failure_location_reads_the_test_file_position() {
+        let output = "error[E1009]: assertion failed\n  --> lib/Flow/Assert.flx:3:9\n  --> /tmp/harness.flx:12:5\n";
+
+        let span = native_failure_location(output, "/tmp/harness.flx").unwrap();
+
+        assert_eq!((span.start.line, span.start.column), (12, 4));
+        assert!(native_failure_location(output, "/tmp/other.flx").is_none());
+    }
+
     #[test]
     fn harness_builder_appends_synthetic_main_when_source_has_no_main() {
         let harness =
@@ -699,7 +1382,6 @@ mod tests {
         let tests = vec![("test_ok".to_string(), 0)];
         let roots = vec![PathBuf::from("tests"), PathBuf::from("lib")];
         let config = NativeTestRunConfig {
-            file_name: "sample.flx",
             source_path: "sample.flx",
             source: "fn test_ok() { 0 }",
             roots: &roots,
@@ -709,10 +1391,20 @@ mod tests {
             enable_analyze: true,
             strict_mode: true,
             use_native: true,
+            execution: TestExecution {
+                jobs: 1,
+                timeout: None,
+                coverage: false,
+            },
         };
         let mut cmd = std::process::Command::new("flux");
 
-        append_native_test_command_args(&mut cmd, &config, Path::new("rewritten.flx"));
+        append_native_test_command_args(
+            &mut cmd,
+            &config,
+            Path::new("rewritten.flx"),
+            Path::new("rewritten"),
+        );
 
         let args: Vec<_> = cmd
             .get_args()
@@ -732,6 +1424,9 @@ mod tests {
                 "tests",
                 "--root",
                 "lib",
+                "--emit-binary",
+                "-o",
+                "rewritten",
                 "rewritten.flx",
             ]
         );
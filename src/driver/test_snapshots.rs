@@ -0,0 +1,315 @@
+//! Snapshot files for `FTest.snapshot`.
+//!
+//! `FTest.snapshot(name, value)` prints one marker line holding the rendered value. The
+//! runner takes those lines out of each test's captured output, the same way for VM and
+//! native runs, and checks them against `__snapshots__/<file stem>__<name>.snap` next to the
+//! test file.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::vm::test_runner::{TestFailure, TestOutcome, TestResult};
+
+/// Start of the output line printed by `FTest.snapshot`, followed by the escaped name, a tab
+/// and the escaped value.
+const SNAPSHOT_MARKER: &str = "@@flux-snapshot\t";
+
+const SNAPSHOT_DIR: &str = "__snapshots__";
+
+/// How snapshots are checked for one test file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SnapshotOptions {
+    /// Record missing snapshots, rewrite changed ones and delete obsolete ones.
+    pub(crate) update: bool,
+    /// Every test of the file ran, so snapshot files nobody took are obsolete.
+    pub(crate) all_tests_ran: bool,
+}
+
+/// Snapshot outcomes of one test file, or of a whole run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SnapshotReport {
+    pub(crate) passed: usize,
+    /// Recorded or rewritten by `--update-snapshots`.
+    pub(crate) written: usize,
+    /// Taken by a test but never recorded.
+    pub(crate) pending: usize,
+    /// Different from the recorded file.
+    pub(crate) failed: usize,
+    /// Obsolete files deleted by `--update-snapshots`.
+    pub(crate) removed: usize,
+    /// Snapshot files no test took.
+    pub(crate) obsolete: Vec<PathBuf>,
+}
+
+impl SnapshotReport {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub(crate) fn add(&mut self, other: &Self) {
+        self.passed += other.passed;
+        self.written += other.written;
+        self.pending += other.pending;
+        self.failed += other.failed;
+        self.removed += other.removed;
+        self.obsolete.extend(other.obsolete.iter().cloned());
+    }
+
+    /// The summary lines printed under the test counts: the counts that are not zero, then
+    /// each obsolete file.
+    pub(crate) fn summary_lines(&self) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let counts = [
+            (self.passed, "passed"),
+            (self.failed, "failed"),
+            (self.written, "written"),
+            (self.pending, "pending"),
+            (self.obsolete.len(), "obsolete"),
+            (self.removed, "removed"),
+        ];
+        let counts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{count} {label}"))
+            .collect();
+        let mut lines = vec![format!("snapshots: {}", counts.join(", "))];
+        lines.extend(
+            self.obsolete
+                .iter()
+                .map(|path| format!("  obsolete snapshot {}", path.display())),
+        );
+        if self.pending > 0 || !self.obsolete.is_empty() {
+            lines.push("  run with --update-snapshots to record or remove them".to_string());
+        }
+        lines
+    }
+}
+
+/// Checks the snapshots taken by `results`, which ran from `test_path`.
+///
+/// Marker lines are removed from each result's output. A missing or different snapshot fails
+/// its test unless `options.update` rewrites the file.
+pub(crate) fn check_snapshots(
+    test_path: &Path,
+    results: &mut [TestResult],
+    options: SnapshotOptions,
+) -> SnapshotReport {
+    let dir = test_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(SNAPSHOT_DIR);
+    let stem = test_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut report = SnapshotReport::default();
+    let mut taken: HashSet<String> = HashSet::new();
+
+    for result in results.iter_mut() {
+        let (output, snapshots) = take_snapshot_lines(&result.output);
+        result.output = output;
+        let mut errors = Vec::new();
+        for (name, value) in snapshots {
+            let file_name = format!("{stem}__{}.snap", sanitize_name(&name));
+            let shown = Path::new(SNAPSHOT_DIR).join(&file_name);
+            if !taken.insert(file_name.clone()) {
+                errors.push(format!(
+                    "snapshot `{name}` is taken more than once in this file"
+                ));
+                continue;
+            }
+            let path = dir.join(&file_name);
+            let recorded = fs::read_to_string(&path).ok();
+            let recorded = recorded
+                .as_deref()
+                .map(|text| text.strip_suffix('\n').unwrap_or(text));
+            if recorded == Some(value.as_str()) {
+                report.passed += 1;
+            } else if options.update {
+                match write_snapshot(&dir, &path, &value) {
+                    Ok(()) => report.written += 1,
+                    Err(e) => errors.push(format!("cannot write {}: {e}", shown.display())),
+                }
+            } else if let Some(recorded) = recorded {
+                report.failed += 1;
+                errors.push(format!(
+                    "snapshot `{name}` does not match {}\n{}",
+                    shown.display(),
+                    render_diff(recorded, &value)
+                ));
+            } else {
+                report.pending += 1;
+                errors.push(format!(
+                    "snapshot `{name}` is not recorded in {}\n{}",
+                    shown.display(),
+                    render_diff("", &value)
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            fail_result(result, errors.join("\n"));
+        }
+    }
+
+    if options.all_tests_ran {
+        let any_failed = results
+            .iter()
+            .any(|result| matches!(result.outcome, TestOutcome::Fail(_)));
+        for file_name in obsolete_snapshot_files(&dir, &stem, &taken) {
+            // A failing test may have stopped before taking its snapshots, so they are only
+            // deleted once every test passes.
+            if options.update && !any_failed && fs::remove_file(dir.join(&file_name)).is_ok() {
+                report.removed += 1;
+            } else {
+                report
+                    .obsolete
+                    .push(Path::new(SNAPSHOT_DIR).join(file_name));
+            }
+        }
+    }
+    report
+}
+
+/// Splits captured output into the text without marker lines and the snapshots they hold.
+fn take_snapshot_lines(output: &str) -> (String, Vec<(String, String)>) {
+    let mut rest = String::new();
+    let mut snapshots = Vec::new();
+    for line in output.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        // `println` shows a string in quotes.
+        let text = text
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
+            .unwrap_or(text);
+        let snapshot = text
+            .strip_prefix(SNAPSHOT_MARKER)
+            .and_then(|payload| payload.split_once('\t'));
+        match snapshot {
+            Some((name, value)) => snapshots.push((unescape(name), unescape(value))),
+            None => rest.push_str(line),
+        }
+    }
+    (rest, snapshots)
+}
+
+/// Undoes the escaping `FTest.snapshot` applies to keep a snapshot on one line.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Keeps ASCII letters, digits, `-` and `_` so a snapshot name is a portable file name.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_snapshot(dir: &Path, path: &Path, value: &str) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(path, format!("{value}\n"))
+}
+
+/// Snapshot files of this test file that no test took, in name order.
+fn obsolete_snapshot_files(dir: &Path, stem: &str, taken: &HashSet<String>) -> Vec<String> {
+    let prefix = format!("{stem}__");
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| {
+            name.starts_with(&prefix) && name.ends_with(".snap") && !taken.contains(name)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Shows the lines that differ between the recorded and the new snapshot, `-` for recorded
+/// and `+` for new, keeping the common leading and trailing lines out.
+fn render_diff(recorded: &str, actual: &str) -> String {
+    let old: Vec<&str> = if recorded.is_empty() {
+        Vec::new()
+    } else {
+        recorded.lines().collect()
+    };
+    let new: Vec<&str> = actual.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let removed = old[prefix..old.len() - suffix]
+        .iter()
+        .map(|line| format!("  - {line}"));
+    let added = new[prefix..new.len() - suffix]
+        .iter()
+        .map(|line| format!("  + {line}"));
+    removed.chain(added).collect::<Vec<_>>().join("\n")
+}
+
+fn fail_result(result: &mut TestResult, message: String) {
+    match &mut result.outcome {
+        TestOutcome::Fail(failure) => {
+            failure.message.push('\n');
+            failure.message.push_str(&message);
+        }
+        TestOutcome::Pass => result.outcome = TestOutcome::Fail(TestFailure::new(message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_diff, sanitize_name, take_snapshot_lines};
+
+    #[test]
+    fn snapshot_lines_are_taken_out_of_the_output() {
+        let output = "\"before\"\n\"@@flux-snapshot\tshape\tCircle(2)\\nRect(1, 2)\"\nafter\n";
+        let (rest, snapshots) = take_snapshot_lines(output);
+        assert_eq!(rest, "\"before\"\nafter\n");
+        assert_eq!(
+            snapshots,
+            vec![("shape".to_string(), "Circle(2)\nRect(1, 2)".to_string())]
+        );
+    }
+
+    #[test]
+    fn snapshot_names_become_portable_file_names() {
+        assert_eq!(sanitize_name("area of/shape 1"), "area_of_shape_1");
+        assert_eq!(sanitize_name("ok-name_2"), "ok-name_2");
+    }
+
+    #[test]
+    fn diff_shows_only_the_changed_lines() {
+        assert_eq!(render_diff("a\nb\nc", "a\nx\nc"), "  - b\n  + x");
+        assert_eq!(render_diff("", "new"), "  + new");
+    }
+}
@@ -1,9 +1,11 @@
 use crate::driver::{
-    AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat,
+    AetherDumpMode, CoreDumpMode, DiagnosticOutputFormat, TestReportFormat,
     backend::Backend,
     flags::{
-        DriverBackendFlags, DriverCacheFlags, DriverDiagnosticFlags, DriverDumpFlags, DriverFlags,
-        DriverInputFlags, DriverLanguageFlags, DriverRuntimeFlags,
+        DriverBackendFlags, DriverBenchFlags, DriverCacheFlags, DriverCallTraceFlags,
+        DriverCoverageFlags, DriverDiagnosticFlags, DriverDumpFlags, DriverEffectLogFlags,
+        DriverFlags, DriverInputFlags, DriverLanguageFlags, DriverLintFlags, DriverProfileFlags,
+        DriverRuntimeFlags, DriverTestFlags,
     },
     session::DriverSession,
 };
@@ -38,6 +40,7 @@ pub(crate) fn base_flags() -> DriverFlags {
             dump_aether: AetherDumpMode::None,
             dump_lir: false,
             dump_lir_llvm: false,
+            core_eval: false,
         },
         diagnostics: DriverDiagnosticFlags {
             max_errors: 1,
@@ -53,6 +56,24 @@ pub(crate) fn base_flags() -> DriverFlags {
             enable_analyze: false,
             strict_mode: false,
         },
+        test: DriverTestFlags {
+            format: TestReportFormat::Text,
+            output: None,
+            jobs: 1,
+            timeout: None,
+            update_snapshots: false,
+            doc_tests: false,
+        },
+        coverage: DriverCoverageFlags {
+            enabled: false,
+            output: None,
+            exclude_stdlib: false,
+        },
+        profile: DriverProfileFlags::default(),
+        call_trace: DriverCallTraceFlags::default(),
+        effect_log: DriverEffectLogFlags::default(),
+        lint: DriverLintFlags::default(),
+        bench: DriverBenchFlags::default(),
     }
 }
 
@@ -0,0 +1,294 @@
+//! `--watch` for `check`, `run` and `test`.
+//!
+//! The watcher polls the modification times of every module in the entry's [`ModuleGraph`].
+//! A module whose mtime moved is re-hashed, so saving a file without changing it does not
+//! trigger a rerun. Each rerun is a child process of the same executable without `--watch`:
+//! a failed check or a program that exits does not end the session, and the child goes through
+//! the module interface cache, which recompiles a module only when its source hash or one of
+//! its [`DependencyFingerprint`]s changed.
+//!
+//! After every rerun the watcher compares the dependency fingerprints recorded in the cached
+//! interfaces before and after, and names the dependents that had to be recompiled because an
+//! edited module's interface changed.
+//!
+//! [`ModuleGraph`]: crate::syntax::module_graph::ModuleGraph
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    bytecode::bytecode_cache::hash_file,
+    compiler::module_interface::load_cached_interface,
+    driver::{
+        flags::DriverFlags, pipeline::RunTarget, run_program::frontend::build_program_context,
+        support::shared::module_display_name,
+    },
+    shared::cache_paths::resolve_cache_layout,
+    types::module_interface::DependencyFingerprint,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Source and interface state of one module at the time of a snapshot.
+#[derive(Debug, Clone)]
+struct WatchedModule {
+    mtime: Option<SystemTime>,
+    source_hash: Option<[u8; 32]>,
+    /// Dependency fingerprints from the cached interface; empty without one.
+    dependencies: Vec<DependencyFingerprint>,
+}
+
+impl WatchedModule {
+    fn read(path: &Path, dependencies: Vec<DependencyFingerprint>) -> Self {
+        Self {
+            mtime: modified_time(path),
+            source_hash: hash_file(path).ok(),
+            dependencies,
+        }
+    }
+}
+
+/// Every module reachable from the entry file, keyed by canonical path.
+#[derive(Debug, Default)]
+struct WatchSnapshot {
+    modules: BTreeMap<PathBuf, WatchedModule>,
+}
+
+impl WatchSnapshot {
+    /// Rebuilds the module graph of `entry` and records the state of each module.
+    ///
+    /// The entry itself is always watched, even when it does not parse.
+    fn capture(entry: &str, flags: &DriverFlags, cache_root: &Path) -> Self {
+        let mut modules = BTreeMap::new();
+        if let Ok(context) = build_program_context(
+            entry,
+            &flags.input.roots,
+            flags.input.roots_only,
+            flags.cache.cache_dir.as_deref(),
+            false,
+            flags.is_native_backend(),
+        ) {
+            for node in context.graph_result.graph.topo_order() {
+                let dependencies = load_cached_interface(cache_root, &node.path)
+                    .map(|interface| interface.dependency_fingerprints)
+                    .unwrap_or_default();
+                modules.insert(
+                    node.path.clone(),
+                    WatchedModule::read(&node.path, dependencies),
+                );
+            }
+        }
+        let entry = std::fs::canonicalize(entry).unwrap_or_else(|_| PathBuf::from(entry));
+        modules
+            .entry(entry.clone())
+            .or_insert_with(|| WatchedModule::read(&entry, Vec::new()));
+        Self { modules }
+    }
+
+    /// Returns the modules whose source changed since the snapshot.
+    ///
+    /// Only files with a new mtime are re-hashed; their recorded mtime is updated so an
+    /// unchanged file is not hashed again on the next poll.
+    fn poll_changes(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, module) in &mut self.modules {
+            let mtime = modified_time(path);
+            if mtime == module.mtime {
+                continue;
+            }
+            module.mtime = mtime;
+            let source_hash = hash_file(path).ok();
+            if source_hash != module.source_hash {
+                module.source_hash = source_hash;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+
+    /// Names the modules that were recompiled because a dependency's interface fingerprint
+    /// changed between `self` and `after`, leaving out the edited modules themselves.
+    fn rebuilt_dependents(&self, after: &WatchSnapshot, changed: &[PathBuf]) -> Vec<PathBuf> {
+        after
+            .modules
+            .iter()
+            .filter(|(path, _)| !changed.contains(path))
+            .filter(|(path, module)| {
+                self.modules.get(*path).is_some_and(|before| {
+                    !module.dependencies.is_empty()
+                        && fingerprints(&before.dependencies) != fingerprints(&module.dependencies)
+                })
+            })
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+fn fingerprints(dependencies: &[DependencyFingerprint]) -> Vec<(&str, &str)> {
+    let mut pairs: Vec<(&str, &str)> = dependencies
+        .iter()
+        .map(|dep| (dep.source_path.as_str(), dep.interface_fingerprint.as_str()))
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+fn module_names(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| module_display_name(path))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Runs the current command once more in a child process, without `--watch`.
+fn run_once() -> Option<ExitStatus> {
+    let exe = std::env::current_exe().ok()?;
+    let args = std::env::args_os().skip(1).filter(|arg| arg != "--watch");
+    match Command::new(exe).args(args).status() {
+        Ok(status) => Some(status),
+        Err(err) => {
+            eprintln!("[watch] could not start compiler: {err}");
+            None
+        }
+    }
+}
+
+/// Runs `target` and then again on every source change, until interrupted.
+pub(crate) fn watch(flags: &DriverFlags, target: &RunTarget) -> ! {
+    let cache_root =
+        resolve_cache_layout(Path::new(&target.path), flags.cache.cache_dir.as_deref())
+            .root()
+            .to_path_buf();
+    let mut before = WatchSnapshot::default();
+    let mut changed: Vec<PathBuf> = Vec::new();
+    loop {
+        if let Some(status) = run_once()
+            && !status.success()
+        {
+            eprintln!("[watch] exited with {status}");
+        }
+        let mut snapshot = WatchSnapshot::capture(&target.path, flags, &cache_root);
+        let rebuilt = before.rebuilt_dependents(&snapshot, &changed);
+        if !rebuilt.is_empty() {
+            eprintln!(
+                "[watch] interface changed; recompiled dependents: {}",
+                module_names(&rebuilt)
+            );
+        }
+        eprintln!(
+            "[watch] watching {} module{} for changes",
+            snapshot.modules.len(),
+            if snapshot.modules.len() == 1 { "" } else { "s" }
+        );
+
+        changed = loop {
+            thread::sleep(POLL_INTERVAL);
+            let changed = snapshot.poll_changes();
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        eprintln!();
+        eprintln!("[watch] changed: {}", module_names(&changed));
+        before = snapshot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime},
+    };
+
+    use super::{WatchSnapshot, WatchedModule};
+    use crate::types::module_interface::DependencyFingerprint;
+
+    fn dependency(path: &str, fingerprint: &str) -> DependencyFingerprint {
+        DependencyFingerprint {
+            module_name: "Dep".to_string(),
+            source_path: path.to_string(),
+            interface_fingerprint: fingerprint.to_string(),
+        }
+    }
+
+    fn bump_mtime(path: &Path) {
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+    }
+
+    #[test]
+    fn poll_reports_content_changes_but_not_touches() {
+        let dir = std::env::temp_dir().join(format!("flux_watch_poll_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Main.flx");
+        fs::write(&path, "fn main() { 1 }\n").unwrap();
+
+        let mut snapshot = WatchSnapshot::default();
+        snapshot
+            .modules
+            .insert(path.clone(), WatchedModule::read(&path, Vec::new()));
+        assert!(snapshot.poll_changes().is_empty());
+
+        bump_mtime(&path);
+        assert!(snapshot.poll_changes().is_empty(), "touch is not a change");
+
+        fs::write(&path, "fn main() { 2 }\n").unwrap();
+        bump_mtime(&path);
+        assert_eq!(snapshot.poll_changes(), vec![path.clone()]);
+        assert!(snapshot.poll_changes().is_empty());
+    }
+
+    #[test]
+    fn rebuilt_dependents_follow_dependency_fingerprints() {
+        let module = |deps: Vec<DependencyFingerprint>| WatchedModule {
+            mtime: None,
+            source_hash: None,
+            dependencies: deps,
+        };
+        let snapshot = |entries: Vec<(&str, WatchedModule)>| WatchSnapshot {
+            modules: entries
+                .into_iter()
+                .map(|(path, module)| (PathBuf::from(path), module))
+                .collect(),
+        };
+        let before = snapshot(vec![
+            ("/p/Lib.flx", module(Vec::new())),
+            ("/p/Main.flx", module(vec![dependency("/p/Lib.flx", "aaa")])),
+            (
+                "/p/Other.flx",
+                module(vec![dependency("/p/Util.flx", "ccc")]),
+            ),
+        ]);
+        let after = snapshot(vec![
+            ("/p/Lib.flx", module(Vec::new())),
+            ("/p/Main.flx", module(vec![dependency("/p/Lib.flx", "bbb")])),
+            (
+                "/p/Other.flx",
+                module(vec![dependency("/p/Util.flx", "ccc")]),
+            ),
+        ]);
+        let changed = [PathBuf::from("/p/Lib.flx")];
+
+        assert_eq!(
+            before.rebuilt_dependents(&after, &changed),
+            vec![PathBuf::from("/p/Main.flx")]
+        );
+        assert!(after.rebuilt_dependents(&after, &changed).is_empty());
+    }
+}
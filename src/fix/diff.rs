@@ -0,0 +1,162 @@
+//! Unified diffs for `flux fix --dry-run`.
+
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Renders the changes from `before` to `after` as a unified diff with three lines of
+/// context, labelled `a/<path>` and `b/<path>`. Returns an empty string when the texts are
+/// equal.
+pub fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    if before == after {
+        return String::new();
+    }
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let lines = diff_lines(&old, &new);
+
+    let mut out = format!("--- a/{path}\n+++ b/{path}\n");
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut start = 0;
+    while start < changed.len() {
+        // Group changes whose context windows touch into one hunk.
+        let mut end = start;
+        while end + 1 < changed.len() && changed[end + 1] - changed[end] <= 2 * CONTEXT {
+            end += 1;
+        }
+        let first = changed[start].saturating_sub(CONTEXT);
+        let last = (changed[end] + CONTEXT + 1).min(lines.len());
+        push_hunk(&mut out, &lines, first, last);
+        start = end + 1;
+    }
+    out
+}
+
+fn push_hunk(out: &mut String, lines: &[Line<'_>], first: usize, last: usize) {
+    let (mut old_start, mut new_start) = (1, 1);
+    for line in &lines[..first] {
+        match line {
+            Line::Same(_) => {
+                old_start += 1;
+                new_start += 1;
+            }
+            Line::Removed(_) => old_start += 1,
+            Line::Added(_) => new_start += 1,
+        }
+    }
+    let hunk = &lines[first..last];
+    let old_len = hunk
+        .iter()
+        .filter(|line| !matches!(line, Line::Added(_)))
+        .count();
+    let new_len = hunk
+        .iter()
+        .filter(|line| !matches!(line, Line::Removed(_)))
+        .count();
+    // An empty side is addressed by the line before it, as in `diff -u`.
+    let old_start = if old_len == 0 {
+        old_start - 1
+    } else {
+        old_start
+    };
+    let new_start = if new_len == 0 {
+        new_start - 1
+    } else {
+        new_start
+    };
+
+    out.push_str(&format!(
+        "@@ -{old_start},{old_len} +{new_start},{new_len} @@\n"
+    ));
+    for line in hunk {
+        let (marker, text) = match line {
+            Line::Same(text) => (' ', text),
+            Line::Removed(text) => ('-', text),
+            Line::Added(text) => ('+', text),
+        };
+        out.push(marker);
+        out.push_str(text);
+        out.push('\n');
+    }
+}
+
+/// Line diff via the longest common subsequence of the lines between the common prefix and
+/// suffix, which keeps the table small for the local edits `flux fix` makes.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j]: length of the LCS of old_mid[i..] and new_mid[j..].
+    let mut lcs = vec![vec![0usize; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<Line<'a>> = old[..prefix].iter().map(|line| Line::Same(line)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            lines.push(Line::Same(old_mid[i]));
+            i += 1;
+            j += 1;
+        } else if i < old_mid.len() && (j == new_mid.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(Line::Removed(old_mid[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new_mid[j]));
+            j += 1;
+        }
+    }
+    lines.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| Line::Same(line)),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unified_diff;
+
+    #[test]
+    fn renders_hunks_with_context() {
+        let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+        let after = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nL\nm\n";
+        assert_eq!(
+            unified_diff("x.flx", before, after),
+            "--- a/x.flx\n+++ b/x.flx\n\
+             @@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n\
+             @@ -9,5 +9,5 @@\n i\n j\n k\n-l\n+L\n m\n"
+        );
+        assert_eq!(unified_diff("x.flx", before, before), "");
+    }
+}
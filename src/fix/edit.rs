@@ -0,0 +1,200 @@
+//! Text edits built from inline suggestions.
+
+use crate::diagnostics::{
+    InlineSuggestion,
+    position::{Position, Span},
+};
+
+/// A replacement of the byte range `start..end` of a source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    /// Resolves the span of `suggestion` against `source`. Returns `None` when the span does
+    /// not fit the text, which happens when a diagnostic points into another file.
+    pub fn from_suggestion(source: &str, suggestion: &InlineSuggestion) -> Option<TextEdit> {
+        Self::from_span(source, suggestion.span, suggestion.replacement.clone())
+    }
+
+    /// Resolves `span` against `source` into an edit replacing it with `replacement`.
+    pub fn from_span(source: &str, span: Span, replacement: String) -> Option<TextEdit> {
+        let start = byte_offset(source, span.start)?;
+        let end = byte_offset(source, span.end)?;
+        (start <= end).then_some(TextEdit {
+            start,
+            end,
+            replacement,
+        })
+    }
+}
+
+/// Applies `edits` to `source`, skipping every edit that overlaps one already taken.
+///
+/// Edits are taken in source order, so when two suggestions touch the same text the earlier
+/// one wins; the other one is reported again on the next pass if it still applies. Returns
+/// the new text and the edits that were applied.
+pub fn apply_edits(source: &str, mut edits: Vec<TextEdit>) -> (String, Vec<TextEdit>) {
+    edits.sort_by_key(|edit| (edit.start, edit.end));
+    edits.dedup();
+
+    let mut applied: Vec<TextEdit> = Vec::new();
+    for edit in edits {
+        let overlaps = applied
+            .last()
+            .is_some_and(|last| edit.start < last.end || edit.start == last.start);
+        if !overlaps {
+            applied.push(edit);
+        }
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in &applied {
+        out.push_str(&source[cursor..edit.start]);
+        out.push_str(&edit.replacement);
+        cursor = edit.end;
+    }
+    out.push_str(&source[cursor..]);
+    (out, applied)
+}
+
+/// Maps `span`, a range of `before`, to the same text in `after`, the result of applying the
+/// `applied` edits returned by [`apply_edits`]. An end point inside an edited range moves to the
+/// matching end of the replacement. Returns `None` when the span does not fit `before`.
+pub fn map_span(before: &str, applied: &[TextEdit], after: &str, span: Span) -> Option<Span> {
+    let start = map_offset(byte_offset(before, span.start)?, applied, false);
+    let end = map_offset(byte_offset(before, span.end)?, applied, true);
+    Some(Span::new(
+        position_at(after, start),
+        position_at(after, end),
+    ))
+}
+
+/// Shifts a byte offset of the original text past the edits before it.
+fn map_offset(offset: usize, applied: &[TextEdit], is_end: bool) -> usize {
+    let mut shift = 0isize;
+    for edit in applied {
+        let before_edit = if is_end {
+            offset < edit.start || (offset == edit.start && edit.start < edit.end)
+        } else {
+            offset <= edit.start
+        };
+        if before_edit {
+            break;
+        }
+        if offset < edit.end {
+            let replacement_start = edit.start.saturating_add_signed(shift);
+            return if is_end {
+                replacement_start + edit.replacement.len()
+            } else {
+                replacement_start
+            };
+        }
+        shift += edit.replacement.len() as isize - (edit.end - edit.start) as isize;
+    }
+    offset.saturating_add_signed(shift)
+}
+
+/// Converts a byte offset into a position (1-based line, 0-based char column).
+fn position_at(source: &str, offset: usize) -> Position {
+    let offset = offset.min(source.len());
+    let line_start = source[..offset]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    Position::new(
+        source[..offset].matches('\n').count() + 1,
+        source[line_start..offset].chars().count(),
+    )
+}
+
+/// Converts a position (1-based line, 0-based char column) into a byte offset. A column past
+/// the end of the line, such as the end-of-line sentinel, resolves to the line end.
+fn byte_offset(source: &str, position: Position) -> Option<usize> {
+    let line_start = if position.line <= 1 {
+        0
+    } else {
+        source
+            .match_indices('\n')
+            .nth(position.line - 2)
+            .map(|(offset, _)| offset + 1)?
+    };
+    let line = source[line_start..].split('\n').next().unwrap_or_default();
+    let column = line
+        .char_indices()
+        .nth(position.column)
+        .map_or(line.len(), |(offset, _)| offset);
+    Some(line_start + column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextEdit, apply_edits, map_span};
+    use crate::diagnostics::{
+        InlineSuggestion,
+        position::{Position, Span},
+    };
+
+    fn edit(start: usize, end: usize, replacement: &str) -> TextEdit {
+        TextEdit {
+            start,
+            end,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn applies_edits_in_order_and_skips_overlaps() {
+        let source = "def f() { var x = 1 }";
+        let (fixed, applied) = apply_edits(
+            source,
+            vec![
+                edit(10, 13, "let"),
+                edit(0, 3, "fn"),
+                edit(1, 2, "zz"),
+                edit(0, 3, "fn"),
+            ],
+        );
+        assert_eq!(fixed, "fn f() { let x = 1 }");
+        assert_eq!(applied.len(), 2);
+    }
+
+    #[test]
+    fn resolves_suggestion_spans_to_byte_offsets() {
+        let source = "fn é() {}\nvar x = 1\n";
+        let suggestion =
+            InlineSuggestion::new(Span::new(Position::new(2, 0), Position::new(2, 3)), "let");
+        let edit = TextEdit::from_suggestion(source, &suggestion).unwrap();
+        assert_eq!(&source[edit.start..edit.end], "var");
+
+        let past_end =
+            InlineSuggestion::new(Span::new(Position::new(9, 0), Position::new(9, 1)), "x");
+        assert!(TextEdit::from_suggestion(source, &past_end).is_none());
+    }
+
+    #[test]
+    fn maps_spans_across_applied_edits() {
+        let source = "def f() {\n    var x = 1\n    x\n}\n";
+        let (fixed, applied) = apply_edits(source, vec![edit(0, 3, "fn"), edit(14, 17, "let x:")]);
+        let span = |sl, sc, el, ec| Span::new(Position::new(sl, sc), Position::new(el, ec));
+
+        // Untouched text shifts with the edits before it.
+        assert_eq!(
+            map_span(source, &applied, &fixed, span(1, 4, 1, 5)),
+            Some(span(1, 3, 1, 4))
+        );
+        assert_eq!(
+            map_span(source, &applied, &fixed, span(3, 4, 3, 5)),
+            Some(span(3, 4, 3, 5))
+        );
+        // A span over an edit covers its replacement.
+        assert_eq!(
+            map_span(source, &applied, &fixed, span(2, 4, 2, 7)),
+            Some(span(2, 4, 2, 10))
+        );
+        assert_eq!(map_span(source, &applied, &fixed, span(9, 0, 9, 1)), None);
+    }
+}
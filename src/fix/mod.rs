@@ -0,0 +1,261 @@
+//! Automatic application of diagnostic suggestions for `flux fix`.
+//!
+//! A file is compiled through the same pipeline the language server uses and linted under the
+//! caller's [`LintPolicy`], so allowed lints are not fixed and denied lints count as errors. Every
+//! [`InlineSuggestion`](crate::diagnostics::InlineSuggestion) marked
+//! [`Applicability::MachineApplicable`](crate::diagnostics::Applicability) is turned into a
+//! text edit, non-overlapping edits are applied, and the file is checked again. Fixes can
+//! expose new suggestions (a repaired keyword lets the file type-check), so this repeats for
+//! a few passes. Errors are identified by code and span, carried through each pass's edits;
+//! the result is discarded if a pass reports an error that was not there before, or still
+//! reports a diagnostic whose suggestion it applied.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo run -- fix examples/guide/print.flx
+//! cargo run -- fix src --dry-run
+//! ```
+
+pub mod diff;
+pub mod edit;
+
+use std::path::Path;
+
+use crate::{
+    diagnostics::{Diagnostic, Severity, position::Span},
+    lsp::{Analysis, AnalysisOptions},
+    syntax::{
+        lexer::Lexer,
+        linter::{LintPolicy, Linter},
+        parser::Parser,
+    },
+};
+
+pub use diff::unified_diff;
+pub use edit::{TextEdit, apply_edits, map_span};
+
+/// Passes after which fixing stops even if new suggestions keep appearing.
+const MAX_PASSES: usize = 4;
+
+/// The outcome of fixing one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixOutcome {
+    /// No machine-applicable suggestion applied.
+    Unchanged,
+    /// `fixed` reports no error the original did not, and none of the fixed diagnostics.
+    Fixed { fixed: String, applied: usize },
+    /// Applying the fixes reported errors the original did not, such as `E002 at 3:5`.
+    Regressed { introduced: Vec<String> },
+    /// A diagnostic was still reported after its suggestion was applied.
+    Unresolved { remaining: Vec<String> },
+}
+
+/// A diagnostic identified by its code and where it is reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiagnosticKey {
+    code: Option<String>,
+    file: Option<String>,
+    span: Option<Span>,
+}
+
+impl DiagnosticKey {
+    fn of(diag: &Diagnostic) -> Self {
+        DiagnosticKey {
+            code: diag.code().map(str::to_string),
+            file: diag.file().map(str::to_string),
+            span: diag.span(),
+        }
+    }
+
+    /// The key after `applied` turned `before`, the contents of `path`, into `after`.
+    fn mapped(&self, path: &Path, before: &str, applied: &[TextEdit], after: &str) -> Self {
+        let in_path = self
+            .file
+            .as_deref()
+            .is_none_or(|file| Path::new(file) == path);
+        let span = match self.span {
+            Some(span) if in_path => map_span(before, applied, after, span).or(Some(span)),
+            span => span,
+        };
+        DiagnosticKey {
+            span,
+            ..self.clone()
+        }
+    }
+
+    fn describe(&self) -> String {
+        let code = self.code.as_deref().unwrap_or("error");
+        match self.span {
+            Some(span) => format!("{code} at {}:{}", span.start.line, span.start.column + 1),
+            None => code.to_string(),
+        }
+    }
+}
+
+/// Collects the machine-applicable suggestions for `source`, which holds the contents of
+/// `path`, applies them and checks the result.
+pub fn fix_source(
+    path: &Path,
+    source: &str,
+    options: &AnalysisOptions,
+    policy: &LintPolicy,
+) -> FixOutcome {
+    let mut diagnostics = check(path, source, options, policy);
+    let mut original_errors = error_keys(&diagnostics);
+    let mut current = source.to_string();
+    let mut applied = 0;
+    for _ in 0..MAX_PASSES {
+        let candidates: Vec<(TextEdit, DiagnosticKey)> = diagnostics
+            .iter()
+            .flat_map(|diag| {
+                let current = &current;
+                diag.suggestions()
+                    .iter()
+                    .filter(|suggestion| suggestion.is_machine_applicable())
+                    .filter_map(move |suggestion| TextEdit::from_suggestion(current, suggestion))
+                    .map(|edit| (edit, DiagnosticKey::of(diag)))
+            })
+            .collect();
+        let edits = candidates.iter().map(|(edit, _)| edit.clone()).collect();
+        let (next, taken) = apply_edits(&current, edits);
+        if taken.is_empty() || next == current {
+            break;
+        }
+        let targeted: Vec<DiagnosticKey> = candidates
+            .iter()
+            .filter(|(edit, _)| taken.contains(edit))
+            .map(|(_, key)| key.mapped(path, &current, &taken, &next))
+            .collect();
+        original_errors = original_errors
+            .iter()
+            .map(|key| key.mapped(path, &current, &taken, &next))
+            .collect();
+        applied += taken.len();
+        current = next;
+        diagnostics = check(path, &current, options, policy);
+
+        let reported: Vec<DiagnosticKey> = diagnostics.iter().map(DiagnosticKey::of).collect();
+        let remaining = describe_all(targeted.iter().filter(|key| reported.contains(key)));
+        if !remaining.is_empty() {
+            return FixOutcome::Unresolved { remaining };
+        }
+    }
+
+    if applied == 0 {
+        return FixOutcome::Unchanged;
+    }
+    // Checked after the last pass: an earlier fix can uncover an error a later one repairs.
+    let errors = error_keys(&diagnostics);
+    let introduced = describe_all(errors.iter().filter(|key| !original_errors.contains(key)));
+    if !introduced.is_empty() {
+        return FixOutcome::Regressed { introduced };
+    }
+    FixOutcome::Fixed {
+        fixed: current,
+        applied,
+    }
+}
+
+/// Compile and lint diagnostics for `source` as the contents of `path`.
+pub fn check(
+    path: &Path,
+    source: &str,
+    options: &AnalysisOptions,
+    policy: &LintPolicy,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Analysis::new(path, source, options).diagnostics().to_vec();
+
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    if parser.errors.is_empty() {
+        let interner = parser.take_interner();
+        let file = path.to_string_lossy().to_string();
+        diagnostics.extend(
+            Linter::new(Some(file), &interner)
+                .with_policy(policy.clone())
+                .lint(&program),
+        );
+    }
+    diagnostics
+}
+
+fn error_keys(diagnostics: &[Diagnostic]) -> Vec<DiagnosticKey> {
+    diagnostics
+        .iter()
+        .filter(|diag| diag.severity() == Severity::Error)
+        .map(DiagnosticKey::of)
+        .collect()
+}
+
+fn describe_all<'a>(keys: impl Iterator<Item = &'a DiagnosticKey>) -> Vec<String> {
+    let mut described: Vec<String> = keys.map(DiagnosticKey::describe).collect();
+    described.dedup();
+    described
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FixOutcome, fix_source};
+    use crate::{
+        lsp::AnalysisOptions,
+        syntax::linter::{LintLevel, LintPolicy},
+    };
+
+    fn fix(name: &str, source: &str) -> FixOutcome {
+        fix_with_policy(name, source, &LintPolicy::default())
+    }
+
+    fn fix_with_policy(name: &str, source: &str, policy: &LintPolicy) -> FixOutcome {
+        let dir = std::env::temp_dir().join(format!("flux_fix_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, source).unwrap();
+        fix_source(&path, source, &AnalysisOptions::default(), policy)
+    }
+
+    #[test]
+    fn applies_keyword_and_effect_row_fixes_across_passes() {
+        let source = "def main() with Console + Clock {\n    var x = 1\n    print(x)\n}\n";
+        let FixOutcome::Fixed { fixed, applied } = fix("passes.flx", source) else {
+            panic!("expected fixes");
+        };
+        assert_eq!(
+            fixed,
+            "fn main() with Console, Clock {\n    let x = 1\n    print(x)\n}\n"
+        );
+        assert_eq!(applied, 3);
+    }
+
+    #[test]
+    fn leaves_guesses_alone() {
+        let source = "fn main() {\n    let count = 1\n    print(cuont)\n}\n";
+        assert_eq!(fix("guess.flx", source), FixOutcome::Unchanged);
+    }
+
+    #[test]
+    fn allowed_lints_are_not_fixed() {
+        let source = "fn main() with Console + Clock {\n    print(1)\n}\n";
+        let mut policy = LintPolicy::default();
+        policy.set_level("W013", LintLevel::Allow);
+        assert_eq!(
+            fix_with_policy("allowed.flx", source, &policy),
+            FixOutcome::Unchanged
+        );
+        assert!(matches!(
+            fix("allowed.flx", source),
+            FixOutcome::Fixed { applied: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn rejects_a_fix_that_trades_one_error_for_another() {
+        let source = "fn main() {\n    var x = 1\n    x = 2\n    print(x)\n}\n";
+        assert_eq!(
+            fix("trade.flx", source),
+            FixOutcome::Regressed {
+                introduced: vec!["E002 at 3:5".to_string()]
+            }
+        );
+    }
+}
@@ -5,11 +5,16 @@ pub mod cfg;
 pub mod cli;
 pub mod compiler;
 pub mod core;
+pub mod dap;
 pub mod diagnostics;
+pub mod docgen;
 pub mod driver;
+pub mod fix;
 pub mod lir;
 #[cfg(feature = "llvm")]
 pub mod llvm;
+pub mod lsp;
+pub mod manifest;
 pub mod parity;
 pub mod runtime;
 pub mod shared;
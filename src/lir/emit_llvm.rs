@@ -1082,6 +1082,12 @@ impl<'a> FnEmitter<'a> {
                 });
             }
 
+            LirInstr::Trace {
+                kind,
+                label,
+                values,
+            } => self.emit_trace(*kind, label, values),
+
             LirInstr::GetGlobal { dst, global_idx } => {
                 // Call C runtime to load from globals table.
                 self.call_c(
@@ -2006,6 +2012,58 @@ impl<'a> FnEmitter<'a> {
         }
     }
 
+    /// Lower a trace event to `flux_trace_event(kind, label, values, count, raw)`.
+    /// Worker variants pass their integers unboxed, which `raw` tells the runtime.
+    fn emit_trace(&mut self, kind: TraceKind, label: &str, values: &[LirVar]) {
+        let idx = self.string_globals.len();
+        let gid = GlobalId(format!("flux.str.{}", idx));
+        self.string_globals
+            .push((gid.clone(), format!("{label}\0")));
+        let array = if values.is_empty() {
+            LlvmOperand::Const(LlvmConst::Null)
+        } else {
+            let arr = self.tmp();
+            self.emit(LlvmInstr::Alloca {
+                dst: arr.clone(),
+                ty: LlvmType::Array {
+                    len: values.len() as u64,
+                    element: Box::new(LlvmType::i64()),
+                },
+                count: None,
+                align: Some(8),
+            });
+            for (i, value) in values.iter().enumerate() {
+                let gep = self.tmp();
+                self.emit(LlvmInstr::GetElementPtr {
+                    dst: gep.clone(),
+                    inbounds: true,
+                    element_ty: LlvmType::i64(),
+                    base: LlvmOperand::Local(arr.clone()),
+                    indices: vec![(LlvmType::i32(), self.i32_const(i as i32))],
+                });
+                self.emit(LlvmInstr::Store {
+                    ty: LlvmType::i64(),
+                    value: self.var(*value),
+                    ptr: LlvmOperand::Local(gep),
+                    align: Some(8),
+                });
+            }
+            LlvmOperand::Local(arr)
+        };
+        self.call_c(
+            None,
+            "flux_trace_event",
+            vec![
+                (LlvmType::i32(), self.i32_const(kind as i32)),
+                (LlvmType::Ptr, LlvmOperand::Global(gid)),
+                (LlvmType::Ptr, array),
+                (LlvmType::i32(), self.i32_const(values.len() as i32)),
+                (LlvmType::i32(), self.i32_const(i32::from(self.worker_mode))),
+            ],
+            LlvmType::Void,
+        );
+    }
+
     fn emit_interpolate(&mut self, dst: LirVar, parts: &[LirVar]) {
         // String interpolation: use interpolation-friendly formatting for each part.
         if parts.is_empty() {
@@ -3435,6 +3493,16 @@ fn known_c_decl(name: &str) -> Option<LlvmDecl> {
             (LlvmType::i64(), vec![LlvmType::i64()])
         }
         "flux_panic" => (LlvmType::Void, vec![LlvmType::i64()]),
+        "flux_trace_event" => (
+            LlvmType::Void,
+            vec![
+                LlvmType::i32(),
+                LlvmType::Ptr,
+                LlvmType::Ptr,
+                LlvmType::i32(),
+                LlvmType::i32(),
+            ],
+        ),
         "flux_make_cons" => (LlvmType::i64(), vec![LlvmType::i64(), LlvmType::i64()]),
         "flux_make_array" | "flux_make_tuple" | "flux_make_hash" | "flux_interpolate" => {
             (LlvmType::i64(), vec![LlvmType::Ptr, LlvmType::i32()])
@@ -3584,6 +3652,148 @@ fn guess_arity(name: &str) -> usize {
     }
 }
 
+// ── Profiling instrumentation ───────────────────────────────────────────────
+
+/// Instrument an emitted module for `flux --native --prof`.
+///
+/// Every Flux function (direct, worker and direct-capture variants) calls
+/// `flux_prof_enter(name)` on entry and `flux_prof_exit()` before it returns.
+/// When a block returns the result of a tail call, the exit goes before the
+/// call so the callee is charged as a sibling, as in the VM, and the call
+/// stays in tail position. The inline bump allocator reports each allocation
+/// with `flux_prof_alloc(tag)`; see `runtime/c/prof.c`.
+///
+/// Functions are named by qualified name, with `entry_qualifier` stripped so
+/// entry-file functions keep their source names.
+pub fn instrument_for_profiling(
+    module: &mut LlvmModule,
+    program: &LirProgram,
+    entry_qualifier: Option<&str>,
+) {
+    let mut name_globals: std::collections::HashMap<String, GlobalId> =
+        std::collections::HashMap::new();
+    for (index, func) in program.functions.iter().enumerate() {
+        let global = GlobalId(format!("flux_prof_name.{index}"));
+        let symbols = if func.qualified_name == "main" {
+            vec!["flux_main".to_string()]
+        } else {
+            let sanitized = sanitize_llvm_symbol_fragment(&func.qualified_name);
+            vec![
+                format!("flux_{sanitized}"),
+                format!("flux_{sanitized}$w"),
+                format!("flux_{sanitized}$dc"),
+            ]
+        };
+        for symbol in symbols {
+            name_globals.insert(symbol, global.clone());
+        }
+        let name = entry_qualifier
+            .and_then(|qualifier| func.qualified_name.strip_prefix(qualifier))
+            .and_then(|name| name.strip_prefix('_'))
+            .unwrap_or(&func.qualified_name);
+        let mut bytes = name.as_bytes().to_vec();
+        bytes.push(0);
+        module.globals.push(LlvmGlobal {
+            linkage: Linkage::Private,
+            name: global,
+            ty: LlvmType::Array {
+                len: bytes.len() as u64,
+                element: Box::new(LlvmType::i8()),
+            },
+            is_constant: true,
+            value: Some(LlvmConst::Array {
+                element_ty: LlvmType::i8(),
+                elements: bytes
+                    .into_iter()
+                    .map(|b| LlvmConst::Int {
+                        bits: 8,
+                        value: b as i128,
+                    })
+                    .collect(),
+            }),
+            attrs: Vec::new(),
+        });
+    }
+
+    for function in &mut module.functions {
+        if function.name.0 == "flux_bump_alloc_inline" {
+            if let Some(entry) = function.blocks.first_mut() {
+                entry.instrs.insert(
+                    0,
+                    prof_call(
+                        "flux_prof_alloc",
+                        vec![(LlvmType::i32(), LlvmOperand::Local(LlvmLocal("tag".into())))],
+                    ),
+                );
+            }
+            continue;
+        }
+        let Some(global) = name_globals.get(&function.name.0) else {
+            continue;
+        };
+        for block in &mut function.blocks {
+            let returned = match &block.term {
+                LlvmTerminator::Ret { value, .. } => Some(value),
+                LlvmTerminator::RetVoid => None,
+                _ => continue,
+            };
+            let at = match block.instrs.last() {
+                Some(LlvmInstr::Call {
+                    dst: Some(dst),
+                    tail: true,
+                    ..
+                }) if returned == Some(&LlvmOperand::Local(dst.clone())) => block.instrs.len() - 1,
+                _ => block.instrs.len(),
+            };
+            block
+                .instrs
+                .insert(at, prof_call("flux_prof_exit", Vec::new()));
+        }
+        if let Some(entry) = function.blocks.first_mut() {
+            entry.instrs.insert(
+                0,
+                prof_call(
+                    "flux_prof_enter",
+                    vec![(LlvmType::Ptr, LlvmOperand::Global(global.clone()))],
+                ),
+            );
+        }
+    }
+
+    for (name, param) in [
+        ("flux_prof_enter", Some(LlvmType::Ptr)),
+        ("flux_prof_exit", None),
+        ("flux_prof_alloc", Some(LlvmType::i32())),
+    ] {
+        if module.declarations.iter().any(|d| d.name.0 == name) {
+            continue;
+        }
+        module.declarations.push(LlvmDecl {
+            linkage: Linkage::External,
+            name: GlobalId(name.to_string()),
+            sig: LlvmFunctionSig {
+                ret: LlvmType::Void,
+                params: param.into_iter().collect(),
+                varargs: false,
+                call_conv: CallConv::Ccc,
+            },
+            attrs: vec!["nounwind".to_string()],
+        });
+    }
+}
+
+fn prof_call(name: &str, args: Vec<(LlvmType, LlvmOperand)>) -> LlvmInstr {
+    LlvmInstr::Call {
+        dst: None,
+        tail: false,
+        call_conv: Some(CallConv::Ccc),
+        ret_ty: LlvmType::Void,
+        callee: LlvmOperand::Global(GlobalId(name.to_string())),
+        args,
+        attrs: Vec::new(),
+    }
+}
+
 /// Generate an empty `flux_main` stub function: `define i64 @flux_main() { ret i64 0 }`.
 /// Used when compiling module-only .flx files with `--native` that have no `fn main()`.
 pub fn flux_main_stub() -> LlvmFunction {
@@ -245,7 +245,8 @@ fn instr_def(instr: &LirInstr) -> Option<LirVar> {
         LirInstr::Store { .. }
         | LirInstr::StoreI32 { .. }
         | LirInstr::Dup { .. }
-        | LirInstr::Drop { .. } => None,
+        | LirInstr::Drop { .. }
+        | LirInstr::Trace { .. } => None,
     }
 }
 
@@ -292,6 +293,7 @@ fn instr_uses(instr: &LirInstr) -> Vec<LirVar> {
         LirInstr::MakeCtor { fields, .. } => fields.clone(),
         LirInstr::Copy { src, .. } => vec![*src],
         LirInstr::Const { .. } | LirInstr::GetGlobal { .. } => Vec::new(),
+        LirInstr::Trace { values, .. } => values.clone(),
     }
 }
 
@@ -469,9 +469,11 @@ pub fn lower_program_with_interner_and_externs(
         &mut lir.constructor_tags,
         interner,
     );
+    // The compiler's numbering is shared by every module of a native build,
+    // so it wins over the module-local one.
     if let Some(imported) = imported_constructor_tags {
         for (name, tag) in imported {
-            lir.constructor_tags.entry(name.clone()).or_insert(*tag);
+            lir.constructor_tags.insert(name.clone(), *tag);
         }
     }
 
@@ -605,9 +607,11 @@ pub fn lower_aether_program_with_interner_and_externs(
 
     let qualified_names = build_qualified_names(core, interner, entry_qualifier);
     collect_constructor_tags(&core.top_level_items, &mut lir.constructor_tags, interner);
+    // The compiler's numbering is shared by every module of a native build,
+    // so it wins over the module-local one.
     if let Some(imported) = imported_constructor_tags {
         for (name, tag) in imported {
-            lir.constructor_tags.entry(name.clone()).or_insert(*tag);
+            lir.constructor_tags.insert(name.clone(), *tag);
         }
     }
 
@@ -2776,15 +2780,13 @@ impl<'a> FnLower<'a> {
         // 2. Build handler clause closure.
         //    For single-operation effects (the common case), the handler
         //    closure IS the clause: fn(resume, param0, ...) -> handler_body.
-        //    For multi-operation effects, we'd need a dispatch closure, but
-        //    for now we support single-operation effects.
-        let handler_closure = if handlers.len() == 1 {
-            self.lower_handler_clause(&handlers[0])
-        } else {
-            // Multi-operation: build the first handler for now.
-            // TODO: dispatch closure for multi-op effects.
-            self.lower_handler_clause(&handlers[0])
-        };
+        //    Multi-operation effects install one clause per operation, see
+        //    `handler_evidence`.
+        let clauses: Vec<(Identifier, LirVar)> = handlers
+            .iter()
+            .map(|handler| (handler.operation, self.lower_handler_clause(handler)))
+            .collect();
+        let handler_closure = self.handler_evidence(clauses);
 
         // 3. Create fresh marker + insert evidence.
         let marker = self.fresh_var();
@@ -2861,7 +2863,11 @@ impl<'a> FnLower<'a> {
             args: vec![],
         });
 
-        let handler_closure = self.lower_handler_clause_aether(&handlers[0]);
+        let clauses: Vec<(Identifier, LirVar)> = handlers
+            .iter()
+            .map(|handler| (handler.operation, self.lower_handler_clause_aether(handler)))
+            .collect();
+        let handler_closure = self.handler_evidence(clauses);
 
         let marker = self.fresh_var();
         self.emit(LirInstr::PrimCall {
@@ -2917,6 +2923,53 @@ impl<'a> FnLower<'a> {
         result
     }
 
+    /// The value a handle installs as its evidence: the clause itself for a
+    /// single-operation effect, otherwise an array of operation tags and
+    /// clauses that the runtime searches with the performed operation's tag.
+    fn handler_evidence(&mut self, mut clauses: Vec<(Identifier, LirVar)>) -> LirVar {
+        if clauses.len() == 1 {
+            return clauses.remove(0).1;
+        }
+        let mut elements = Vec::with_capacity(clauses.len() * 2);
+        for (operation, clause) in clauses {
+            let optag = self.fresh_var();
+            self.emit(LirInstr::Const {
+                dst: optag,
+                value: LirConst::Tagged(crate::lir::nanbox_tag_int(operation.as_u32() as i64)),
+            });
+            elements.push(optag);
+            elements.push(clause);
+        }
+        let dst = self.fresh_var();
+        self.emit(LirInstr::MakeArray { dst, elements });
+        dst
+    }
+
+    /// The argument a perform hands to the runtime: nothing for a nullary
+    /// operation, the value itself for a unary one, and a tuple of the
+    /// arguments otherwise, which the runtime unpacks into clause parameters.
+    fn perform_argument(&mut self, mut args: Vec<LirVar>) -> LirVar {
+        match args.len() {
+            0 => {
+                let none = self.fresh_var();
+                self.emit(LirInstr::Const {
+                    dst: none,
+                    value: LirConst::None,
+                });
+                none
+            }
+            1 => args.remove(0),
+            _ => {
+                let dst = self.fresh_var();
+                self.emit(LirInstr::MakeTuple {
+                    dst,
+                    elements: args,
+                });
+                dst
+            }
+        }
+    }
+
     fn lower_call_expr(
         &mut self,
         func: &CoreExpr,
@@ -3599,17 +3652,8 @@ impl<'a> FnLower<'a> {
     ) -> LirVar {
         use crate::core::CorePrimOp;
 
-        // Lower the argument (currently single-arg effects).
-        let arg = if args.is_empty() {
-            let none = self.fresh_var();
-            self.emit(LirInstr::Const {
-                dst: none,
-                value: LirConst::None,
-            });
-            none
-        } else {
-            self.lower_expr(&args[0])
-        };
+        let arg_vars: Vec<LirVar> = args.iter().map(|arg| self.lower_expr(arg)).collect();
+        let arg = self.perform_argument(arg_vars);
 
         // Effect tag and operation tag as NaN-boxed integers.
         let htag = self.fresh_var();
@@ -3692,16 +3736,8 @@ impl<'a> FnLower<'a> {
     ) -> LirVar {
         use crate::core::CorePrimOp;
 
-        let arg = if args.is_empty() {
-            let none = self.fresh_var();
-            self.emit(LirInstr::Const {
-                dst: none,
-                value: LirConst::None,
-            });
-            none
-        } else {
-            self.lower_expr_aether(&args[0])
-        };
+        let arg_vars: Vec<LirVar> = args.iter().map(|arg| self.lower_expr_aether(arg)).collect();
+        let arg = self.perform_argument(arg_vars);
 
         let htag = self.fresh_var();
         self.emit(LirInstr::Const {
@@ -5442,6 +5478,14 @@ fn display_instr(instr: &LirInstr) -> String {
         LirInstr::Copy { dst, src } => format!("{dst} = copy {src}"),
         LirInstr::Const { dst, value } => format!("{dst} = const {value:?}"),
         LirInstr::TupleGet { dst, tuple, index } => format!("{dst} = tuple_get({tuple}, {index})"),
+        LirInstr::Trace {
+            kind,
+            label,
+            values,
+        } => {
+            let vs: Vec<String> = values.iter().map(|v| format!("{v}")).collect();
+            format!("trace {kind:?} {label}([{}])", vs.join(", "))
+        }
         LirInstr::GetGlobal { dst, global_idx } => format!("{dst} = get_global({global_idx})"),
     }
 }
@@ -17,6 +17,7 @@ pub mod cont_split;
 pub mod emit_llvm;
 pub mod liveness;
 pub mod lower;
+pub mod trace;
 
 use std::collections::HashMap;
 use std::fmt;
@@ -271,6 +272,29 @@ pub enum LirInstr {
     /// Used for imported/prelude functions that were compiled by the
     /// regular CFG pipeline and stored as globals.
     GetGlobal { dst: LirVar, global_idx: usize },
+
+    // ── Instrumentation ─────────────────────────────────────────────
+    /// Report a call trace event (`--trace-calls`) with rendered `values`.
+    /// Inserted by `lir::trace` after lowering; see `runtime/c/trace.c`.
+    Trace {
+        kind: TraceKind,
+        label: String,
+        values: Vec<LirVar>,
+    },
+}
+
+/// The event reported by a `LirInstr::Trace`. The discriminants are the
+/// `kind` codes understood by `flux_trace_event`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKind {
+    /// Entry to a traced function; values are its parameters.
+    Call = 0,
+    /// Return from a traced function; the value is the result.
+    Return = 1,
+    /// A traced function leaving through a tail call.
+    TailCall = 2,
+    /// An effect operation; values are its arguments.
+    Perform = 3,
 }
 
 // ── Block terminators ────────────────────────────────────────────────────────
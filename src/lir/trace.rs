@@ -0,0 +1,126 @@
+//! Call tracing instrumentation for native binaries (`flux --native --trace-calls`).
+//!
+//! Runs on lowered LIR, before continuation splitting, and inserts
+//! `LirInstr::Trace` events: entry, return and tail call of every function
+//! selected by the [`CallTraceFilter`], and every effect operation. The LLVM
+//! emitter turns each event into a `flux_trace_event` call; the C runtime
+//! (`runtime/c/trace.c`) keeps the call depth, reports handler resumptions and
+//! drops operations performed outside traced calls, as the VM does.
+//!
+//! Functions are matched by symbol name (`Flow_List_map`), with the entry
+//! qualifier stripped so entry-file functions keep their source names. Lifted
+//! lambdas are never traced.
+
+use std::collections::HashMap;
+
+use crate::core::CorePrimOp;
+use crate::lir::{LirConst, LirFunction, LirInstr, LirProgram, LirTerminator, LirVar, TraceKind};
+use crate::runtime::call_trace::CallTraceFilter;
+use crate::syntax::{interner::Interner, symbol::Symbol};
+
+/// Instrument `program` for call tracing.
+pub fn instrument_call_trace(
+    program: &mut LirProgram,
+    filter: &CallTraceFilter,
+    interner: &Interner,
+    entry_qualifier: Option<&str>,
+) {
+    for func in &mut program.functions {
+        instrument_performs(func, interner);
+        let name = entry_qualifier
+            .and_then(|qualifier| func.qualified_name.strip_prefix(qualifier))
+            .and_then(|name| name.strip_prefix('_'))
+            .unwrap_or(&func.qualified_name)
+            .to_string();
+        if !name.contains("_lambda_") && filter.matches_symbol(&name) {
+            instrument_calls(func, name);
+        }
+    }
+}
+
+fn instrument_calls(func: &mut LirFunction, label: String) {
+    for block in &mut func.blocks {
+        let event = match &block.terminator {
+            LirTerminator::Return(value) => (TraceKind::Return, vec![*value]),
+            LirTerminator::TailCall { .. } => (TraceKind::TailCall, Vec::new()),
+            _ => continue,
+        };
+        block.instrs.push(LirInstr::Trace {
+            kind: event.0,
+            label: label.clone(),
+            values: event.1,
+        });
+    }
+    let params = func.params.clone();
+    if let Some(entry) = func.blocks.first_mut() {
+        entry.instrs.insert(
+            0,
+            LirInstr::Trace {
+                kind: TraceKind::Call,
+                label,
+                values: params,
+            },
+        );
+    }
+}
+
+/// Report each `flux_yield_to` / `flux_perform_direct` call with the operation
+/// name and its arguments, recovered from the constants and the argument tuple
+/// that `lower_perform` builds in front of it.
+fn instrument_performs(func: &mut LirFunction, interner: &Interner) {
+    let mut defs: HashMap<LirVar, &LirInstr> = HashMap::new();
+    for instr in func.blocks.iter().flat_map(|block| &block.instrs) {
+        if let LirInstr::Const { dst, .. } | LirInstr::MakeTuple { dst, .. } = instr {
+            defs.insert(*dst, instr);
+        }
+    }
+    let tagged_name = |var: LirVar| match defs.get(&var) {
+        Some(LirInstr::Const {
+            value: LirConst::Tagged(n),
+            ..
+        }) => interner.try_resolve(Symbol::new((n >> 1) as u32)),
+        _ => None,
+    };
+
+    let mut inserts = Vec::new();
+    for (block_idx, block) in func.blocks.iter().enumerate() {
+        for (instr_idx, instr) in block.instrs.iter().enumerate() {
+            let LirInstr::PrimCall { op, args, .. } = instr else {
+                continue;
+            };
+            let arity_var = match op {
+                CorePrimOp::YieldTo => args.get(3),
+                CorePrimOp::PerformDirect => args.get(4),
+                _ => continue,
+            };
+            let (Some(effect), Some(operation)) = (tagged_name(args[0]), tagged_name(args[1]))
+            else {
+                continue;
+            };
+            let arity = match arity_var.and_then(|var| defs.get(var)) {
+                Some(LirInstr::Const {
+                    value: LirConst::Int(arity),
+                    ..
+                }) => *arity,
+                _ => continue,
+            };
+            let values = match (arity, defs.get(&args[2])) {
+                (0, _) => Vec::new(),
+                (2.., Some(LirInstr::MakeTuple { elements, .. })) => elements.clone(),
+                _ => vec![args[2]],
+            };
+            inserts.push((
+                block_idx,
+                instr_idx,
+                LirInstr::Trace {
+                    kind: TraceKind::Perform,
+                    label: format!("{effect}.{operation}"),
+                    values,
+                },
+            ));
+        }
+    }
+    for (block_idx, instr_idx, trace) in inserts.into_iter().rev() {
+        func.blocks[block_idx].instrs.insert(instr_idx, trace);
+    }
+}
@@ -583,6 +583,9 @@ pub fn ensure_runtime_lib(runtime_c_dir: &Path) -> Result<(), PipelineError> {
         "hamt.c",
         "effects.c",
         "array.c",
+        "prof.c",
+        "trace.c",
+        "effect_log.c",
     ];
     let mut obj_files = Vec::new();
 
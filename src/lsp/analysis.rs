@@ -0,0 +1,541 @@
+//! Per-document analysis backing the language server queries.
+//!
+//! An [`Analysis`] is a snapshot of one open document: the diagnostics produced by the same
+//! parse → module graph → compile pipeline used by `flux run`, the HM expression types of the
+//! entry module, a [`SymbolIndex`] resolved against the compiler's global bindings, and the
+//! module graph used to follow references into other files. Every edit rebuilds the snapshot
+//! from scratch.
+
+use std::{
+    collections::{HashMap, HashSet},
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::{
+        type_infer::{display_infer_type, render_scheme_canonical},
+        visit::{self, Visitor},
+    },
+    compiler::{Compiler, module_interface::load_cached_interface},
+    diagnostics::{
+        Diagnostic, DiagnosticBuilder, DiagnosticPhase, DiagnosticsAggregator,
+        position::{Position, Span},
+    },
+    driver::{
+        frontend::{collect_roots, extract_module_name_and_sym, inject_flow_prelude},
+        module_compile::{effective_module_strictness, tag_module_diagnostics},
+        shared::{sort_stdlib_first, tag_and_attach_file},
+    },
+    lsp::{
+        range::{LineIndex, span_contains, span_encloses},
+        symbols::{Globals, Occurrence, SymbolIndex, Target},
+    },
+    shared::cache_paths::resolve_cache_layout,
+    syntax::{
+        Identifier,
+        expression::{ExprId, Expression},
+        interner::Interner,
+        lexer::Lexer,
+        module_graph::ModuleGraph,
+        parser::Parser,
+        program::Program,
+        statement::Statement,
+    },
+    types::{infer_type::InferType, type_env::TypeEnv},
+};
+
+/// Options shared by every analysis in one server session.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// Extra module roots, as passed with `--root`.
+    pub roots: Vec<PathBuf>,
+    pub roots_only: bool,
+    pub strict_mode: bool,
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// A resolved source location in some file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub span: Span,
+}
+
+/// One completion candidate for a module member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberCompletion {
+    pub name: String,
+    /// Rendered type scheme when the module interface is available.
+    pub detail: Option<String>,
+}
+
+/// Analysis snapshot of one document.
+pub struct Analysis {
+    path: PathBuf,
+    line_index: LineIndex,
+    diagnostics: Vec<Diagnostic>,
+    program: Option<Program>,
+    interner: Interner,
+    symbols: SymbolIndex,
+    expr_types: HashMap<ExprId, InferType>,
+    type_env: Option<TypeEnv>,
+    graph: Option<ModuleGraph>,
+    globals: Globals,
+    cache_root: PathBuf,
+}
+
+impl Analysis {
+    /// Parses and compiles `source` as if it were the contents of `path`.
+    pub fn new(path: &Path, source: &str, options: &AnalysisOptions) -> Self {
+        let cache_root = resolve_cache_layout(path, options.cache_dir.as_deref())
+            .root()
+            .to_path_buf();
+        let mut analysis = Self {
+            path: path.to_path_buf(),
+            line_index: LineIndex::new(source),
+            diagnostics: Vec::new(),
+            program: None,
+            interner: Interner::new(),
+            symbols: SymbolIndex::default(),
+            expr_types: HashMap::new(),
+            type_env: None,
+            graph: None,
+            globals: Globals::default(),
+            cache_root,
+        };
+
+        let mut parser = Parser::new(Lexer::new(source));
+        let mut program = parser.parse_program();
+        let path_text = path.to_string_lossy().to_string();
+        let mut diagnostics = parser.take_warnings();
+        if !parser.errors.is_empty() {
+            diagnostics.append(&mut parser.errors);
+            tag_and_attach_file(&mut diagnostics, DiagnosticPhase::Parse, &path_text);
+            analysis.symbols =
+                SymbolIndex::build(&program, source, parser.interner(), &Globals::default());
+            analysis.interner = parser.take_interner();
+            analysis.diagnostics = analysis.finish_diagnostics(diagnostics);
+            analysis.program = Some(program);
+            return analysis;
+        }
+        tag_and_attach_file(&mut diagnostics, DiagnosticPhase::Parse, &path_text);
+
+        inject_flow_prelude(&mut program, &mut parser, false);
+        let interner = parser.take_interner();
+        let parsed_interner = interner.clone();
+        let roots = collect_roots(path, &options.roots, options.roots_only);
+
+        let compiled = catch_unwind(AssertUnwindSafe(|| {
+            compile_document(path, &program, interner, &roots, options.strict_mode)
+        }));
+        match compiled {
+            Ok(result) => {
+                diagnostics.extend(result.diagnostics);
+                analysis.interner = result.interner;
+                analysis.expr_types = result.expr_types;
+                analysis.type_env = result.type_env;
+                analysis.graph = Some(result.graph);
+                analysis.globals = result.globals;
+            }
+            Err(_) => {
+                diagnostics.push(Diagnostic::warning("Analysis Aborted").with_message(
+                    "The compiler panicked while analyzing this file; results may be incomplete.",
+                ));
+                tag_and_attach_file(&mut diagnostics, DiagnosticPhase::TypeCheck, &path_text);
+                analysis.interner = parsed_interner;
+            }
+        }
+        analysis.symbols =
+            SymbolIndex::build(&program, source, &analysis.interner, &analysis.globals);
+        analysis.program = Some(program);
+        analysis.diagnostics = analysis.finish_diagnostics(diagnostics);
+        analysis
+    }
+
+    /// Keeps only diagnostics that belong to this document, deduplicated and ordered the same
+    /// way the CLI renders them.
+    fn finish_diagnostics(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let own: Vec<Diagnostic> = diagnostics
+            .into_iter()
+            .filter(|diag| {
+                diag.file()
+                    .is_none_or(|file| same_file(Path::new(file), &self.path))
+            })
+            .collect();
+        DiagnosticsAggregator::new(&own)
+            .with_stage_filtering(true)
+            .processed_diagnostics()
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    /// The parsed document, including the injected prelude imports.
+    pub fn program(&self) -> Option<&Program> {
+        self.program.as_ref()
+    }
+
+    /// Renders the inferred type of `expr`, an expression of [`Self::program`].
+    pub fn expr_type(&self, expr: &Expression) -> Option<String> {
+        let ty = self.expr_types.get(&expr.expr_id())?;
+        Some(display_infer_type(ty, &self.interner))
+    }
+
+    /// Returns the hover text for `position`: the inferred type of the innermost typed
+    /// expression, or the declared scheme when the cursor sits on a binder.
+    pub fn hover(&self, position: Position) -> Option<(String, Span)> {
+        let program = self.program.as_ref()?;
+        let occurrence = self.symbols.occurrence_at(position);
+        if let Some(occurrence) = occurrence
+            && occurrence.is_definition
+        {
+            if let Some(scheme) = self.scheme_of(&occurrence.name) {
+                return Some((format!("{} : {}", occurrence.name, scheme), occurrence.span));
+            }
+            // Local binders have no expression of their own; borrow the type of a use site.
+            let (_, ty) = self
+                .symbols
+                .references_to(&occurrence.target)
+                .into_iter()
+                .filter(|other| !other.is_definition)
+                .find_map(|other| self.expr_type_at(program, other.span.start))?;
+            return Some((format!("{} : {}", occurrence.name, ty), occurrence.span));
+        }
+
+        let (span, ty) = self.expr_type_at(program, position)?;
+        let label = occurrence
+            .filter(|occurrence| span_encloses(span, occurrence.span))
+            .map(|occurrence| format!("{} : {}", occurrence.name, ty))
+            .unwrap_or(ty);
+        Some((label, span))
+    }
+
+    /// Renders the type of the smallest typed expression around `position`.
+    fn expr_type_at(&self, program: &Program, position: Position) -> Option<(Span, String)> {
+        let mut finder = TypedExprFinder {
+            position,
+            types: &self.expr_types,
+            best: None,
+        };
+        finder.visit_program(program);
+        let (span, id) = finder.best?;
+        let ty = display_infer_type(self.expr_types.get(&id)?, &self.interner);
+        Some((span, ty))
+    }
+
+    fn scheme_of(&self, name: &str) -> Option<String> {
+        let env = self.type_env.as_ref()?;
+        let symbol = self.interner.lookup(name)?;
+        env.lookup(symbol)
+            .map(|scheme| render_scheme_canonical(&self.interner, scheme))
+    }
+
+    /// Resolves the binder referenced at `position`.
+    pub fn definition(&self, position: Position) -> Option<Location> {
+        let occurrence = self.symbols.occurrence_at(position)?;
+        match &occurrence.target {
+            Target::Local(span) => Some(Location {
+                path: self.path.clone(),
+                span: *span,
+            }),
+            Target::Global(symbol) => self
+                .global_occurrences(*symbol, true)
+                .into_iter()
+                .find_map(|(location, is_definition)| is_definition.then_some(location)),
+            Target::Unresolved(_) => None,
+        }
+    }
+
+    /// Returns every occurrence that resolves to the same binder as the identifier at
+    /// `position`. Top-level bindings are searched for in every module of the graph.
+    pub fn references(&self, position: Position, include_declaration: bool) -> Vec<Location> {
+        let Some(occurrence) = self.symbols.occurrence_at(position) else {
+            return Vec::new();
+        };
+        if let Target::Global(symbol) = occurrence.target {
+            return self
+                .global_occurrences(symbol, include_declaration)
+                .into_iter()
+                .map(|(location, _)| location)
+                .collect();
+        }
+        self.symbols
+            .references_to(&occurrence.target)
+            .into_iter()
+            .filter(|other: &&Occurrence| include_declaration || !other.is_definition)
+            .map(|other| Location {
+                path: self.path.clone(),
+                span: other.span,
+            })
+            .collect()
+    }
+
+    /// Qualified name of the top-level binding referenced at `position`, used to look the
+    /// binding up in the analyses of other open documents.
+    pub fn global_name_at(&self, position: Position) -> Option<String> {
+        match self.symbols.occurrence_at(position)?.target {
+            Target::Global(symbol) => Some(self.interner.resolve(symbol).to_string()),
+            _ => None,
+        }
+    }
+
+    /// Returns the occurrences of the top-level binding `qualified` in the modules of this
+    /// analysis.
+    pub fn global_references(&self, qualified: &str, include_declaration: bool) -> Vec<Location> {
+        let Some(symbol) = self
+            .interner
+            .lookup(qualified)
+            .filter(|symbol| self.globals.sites.contains_key(symbol))
+        else {
+            return Vec::new();
+        };
+        self.global_occurrences(symbol, include_declaration)
+            .into_iter()
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    /// Collects the occurrences of a global in the document and in every other module of the
+    /// graph, each paired with whether it is the binder. Modules are read from disk and only
+    /// indexed when their text mentions the name at all.
+    fn global_occurrences(
+        &self,
+        symbol: Identifier,
+        include_declaration: bool,
+    ) -> Vec<(Location, bool)> {
+        let target = Target::Global(symbol);
+        let collect = |path: &Path, index: &SymbolIndex| -> Vec<(Location, bool)> {
+            index
+                .references_to(&target)
+                .into_iter()
+                .filter(|occurrence| include_declaration || !occurrence.is_definition)
+                .map(|occurrence| {
+                    let location = Location {
+                        path: path.to_path_buf(),
+                        span: occurrence.span,
+                    };
+                    (location, occurrence.is_definition)
+                })
+                .collect()
+        };
+
+        let mut found = collect(&self.path, &self.symbols);
+        let Some(graph) = self.graph.as_ref() else {
+            return found;
+        };
+        let spelled = self.interner.resolve(symbol);
+        let member = spelled.rsplit_once('.').map_or(spelled, |(_, last)| last);
+        for node in graph.topo_order() {
+            if same_file(&node.path, &self.path) {
+                continue;
+            }
+            let Ok(source) = std::fs::read_to_string(&node.path) else {
+                continue;
+            };
+            if !source.contains(member) {
+                continue;
+            }
+            let index = SymbolIndex::build(&node.program, &source, &self.interner, &self.globals);
+            found.extend(collect(&node.path, &index));
+        }
+        found
+    }
+
+    fn find_module_path(&self, module: &str) -> Option<PathBuf> {
+        self.graph
+            .as_ref()?
+            .topo_order()
+            .into_iter()
+            .find_map(|node| {
+                extract_module_name_and_sym(&node.program, &self.interner)
+                    .filter(|(name, _)| name == module)
+                    .map(|_| node.path.clone())
+            })
+    }
+
+    /// Lists the public members of the module named by `qualifier` (a module name or import
+    /// alias). Types come from the cached `.flxi` interface when one exists.
+    pub fn module_members(&self, qualifier: &str) -> Vec<MemberCompletion> {
+        let module = self
+            .symbols
+            .resolve_module(qualifier)
+            .unwrap_or(qualifier)
+            .to_string();
+        let Some(path) = self.find_module_path(&module) else {
+            return Vec::new();
+        };
+
+        let mut members = match load_cached_interface(&self.cache_root, &path) {
+            Ok(interface) => {
+                let mut interner = Interner::new();
+                let remap = interface.build_symbol_remap(&mut interner);
+                interface
+                    .schemes
+                    .iter()
+                    .map(|(name, scheme)| MemberCompletion {
+                        name: name.clone(),
+                        detail: Some(render_scheme_canonical(
+                            &interner,
+                            &scheme.remap_symbols(&remap),
+                        )),
+                    })
+                    .collect()
+            }
+            Err(_) => self.ast_public_members(&module),
+        };
+        members.sort_by(|left, right| left.name.cmp(&right.name));
+        members.dedup_by(|left, right| left.name == right.name);
+        members
+    }
+
+    fn ast_public_members(&self, module: &str) -> Vec<MemberCompletion> {
+        let Some(graph) = self.graph.as_ref() else {
+            return Vec::new();
+        };
+        let Some(node) = graph.topo_order().into_iter().find(|node| {
+            extract_module_name_and_sym(&node.program, &self.interner)
+                .is_some_and(|(name, _)| name == module)
+        }) else {
+            return Vec::new();
+        };
+        let mut members = Vec::new();
+        for stmt in &node.program.statements {
+            if let Statement::Module { body, .. } = stmt {
+                for item in &body.statements {
+                    if let Statement::Function {
+                        is_public: true,
+                        name,
+                        ..
+                    } = item
+                    {
+                        members.push(MemberCompletion {
+                            name: self.interner.resolve(*name).to_string(),
+                            detail: None,
+                        });
+                    }
+                }
+            }
+        }
+        members
+    }
+}
+
+/// Output of compiling the document and its dependencies.
+struct CompiledDocument {
+    graph: ModuleGraph,
+    interner: Interner,
+    diagnostics: Vec<Diagnostic>,
+    expr_types: HashMap<ExprId, InferType>,
+    type_env: Option<TypeEnv>,
+    globals: Globals,
+}
+
+fn compile_document(
+    path: &Path,
+    program: &Program,
+    interner: Interner,
+    roots: &[PathBuf],
+    strict_mode: bool,
+) -> CompiledDocument {
+    let path_text = path.to_string_lossy().to_string();
+    let graph_result = ModuleGraph::build_with_entry_and_roots(path, program, interner, roots);
+    let mut diagnostics = graph_result.diagnostics;
+    tag_and_attach_file(&mut diagnostics, DiagnosticPhase::ModuleGraph, &path_text);
+
+    let failed = graph_result.failed_modules;
+    let graph = graph_result.graph;
+    let entry_module_kind = graph.entry_node().map(|node| node.kind).unwrap_or_default();
+    let entry_path = graph.entry_node().map(|node| node.path.clone());
+
+    let mut compiler = Compiler::new_with_interner(&path_text, graph_result.interner);
+    let mut expr_types = HashMap::new();
+    let mut type_env = None;
+    let mut sites = HashMap::new();
+
+    let mut ordered_nodes = graph.topo_order();
+    sort_stdlib_first(&mut ordered_nodes, |node| node.kind);
+    for node in ordered_nodes {
+        if node.imports.iter().any(|e| failed.contains(&e.target_path)) {
+            continue;
+        }
+        compiler.set_file_path(node.path.to_string_lossy().to_string());
+        compiler.set_current_module_kind(node.kind);
+        compiler.set_strict_mode(effective_module_strictness(
+            node.kind,
+            entry_module_kind,
+            strict_mode,
+        ));
+        compiler.set_strict_require_main(false);
+        let defined_before: HashSet<_> = compiler
+            .symbol_table
+            .global_definitions()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let compile_result = compiler.compile_with_opts(&node.program, false, false);
+        for (name, _) in compiler.symbol_table.global_definitions() {
+            if !defined_before.contains(&name) {
+                sites.insert(name, node.path.clone());
+            }
+        }
+        let mut warnings = compiler.take_warnings();
+        tag_module_diagnostics(&mut warnings, DiagnosticPhase::Validation, &node.path);
+        diagnostics.append(&mut warnings);
+        if let Err(mut diags) = compile_result {
+            tag_module_diagnostics(&mut diags, DiagnosticPhase::TypeCheck, &node.path);
+            diagnostics.append(&mut diags);
+        }
+        if entry_path.as_ref() == Some(&node.path) {
+            expr_types = compiler.hm_expr_types().clone();
+            type_env = Some(compiler.hm_type_env().clone());
+        }
+    }
+
+    CompiledDocument {
+        globals: Globals {
+            table: compiler.symbol_table.clone(),
+            sites,
+        },
+        interner: std::mem::take(&mut compiler.interner),
+        graph,
+        diagnostics,
+        expr_types,
+        type_env,
+    }
+}
+
+pub(crate) fn same_file(left: &Path, right: &Path) -> bool {
+    left == right
+        || match (left.canonicalize(), right.canonicalize()) {
+            (Ok(left), Ok(right)) => left == right,
+            _ => false,
+        }
+}
+
+/// Finds the smallest expression around a position that has an inferred type.
+struct TypedExprFinder<'a> {
+    position: Position,
+    types: &'a HashMap<ExprId, InferType>,
+    best: Option<(Span, ExprId)>,
+}
+
+impl<'ast> Visitor<'ast> for TypedExprFinder<'_> {
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        let span = expr.span();
+        if !span_contains(span, self.position) {
+            return;
+        }
+        if self.types.contains_key(&expr.expr_id())
+            && self.best.is_none_or(|(best, _)| span_encloses(best, span))
+        {
+            self.best = Some((span, expr.expr_id()));
+        }
+        visit::walk_expr(self, expr);
+    }
+}
@@ -0,0 +1,41 @@
+//! Flux language server (Proposal 0163).
+//!
+//! `flux lsp` speaks the Language Server Protocol over stdio. The server reuses the regular
+//! compiler pipeline for every open document and answers editor queries from the result:
+//!
+//! - diagnostics from parsing, module graph construction and type checking, deduplicated with
+//!   the same [`DiagnosticsAggregator`](crate::diagnostics::DiagnosticsAggregator) rules as the
+//!   CLI;
+//! - hover with the HM type of the expression under the cursor;
+//! - go-to-definition and find-references resolved through the compiler's symbol table, following
+//!   top-level bindings into the other modules of the
+//!   [`ModuleGraph`](crate::syntax::module_graph::ModuleGraph) and into other open documents;
+//! - module-member completion after `Module.`, typed from cached `.flxi` interfaces.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo run -- lsp
+//! cargo run -- lsp --root lib --strict
+//! ```
+
+pub mod analysis;
+pub mod range;
+pub mod server;
+pub mod symbols;
+pub mod transport;
+
+use std::io;
+
+pub use analysis::{Analysis, AnalysisOptions};
+pub use server::Server;
+
+/// Runs the language server on stdin/stdout until the client exits.
+///
+/// Returns `Ok(true)` when the client performed a clean `shutdown`/`exit` handshake.
+pub fn run_stdio(options: AnalysisOptions) -> io::Result<bool> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    Server::new(stdout.lock(), options).serve(&mut reader)
+}
@@ -0,0 +1,185 @@
+//! Conversions between Flux source positions and LSP positions and URIs.
+//!
+//! Flux positions use 1-based lines and 0-based columns counted in chars. LSP positions use
+//! 0-based lines and columns counted in UTF-16 code units, so every conversion goes through the
+//! text of the affected line.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::diagnostics::position::{Position, Span};
+
+/// Line-oriented view of one document used for position conversions.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    lines: Vec<String>,
+}
+
+impl LineIndex {
+    /// Builds a line index for the given document text.
+    pub fn new(text: &str) -> Self {
+        Self {
+            lines: text.split('\n').map(|line| line.to_string()).collect(),
+        }
+    }
+
+    /// Returns the text of a 1-based line, or `None` when the line is out of range.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        line.checked_sub(1)
+            .and_then(|idx| self.lines.get(idx))
+            .map(String::as_str)
+    }
+
+    /// Converts a Flux position into an LSP `{ line, character }` object.
+    pub fn to_lsp_position(&self, position: Position) -> Value {
+        let line = position.line.saturating_sub(1);
+        let character = self
+            .line(position.line)
+            .map(|text| {
+                text.chars()
+                    .take(position.column)
+                    .map(char::len_utf16)
+                    .sum::<usize>()
+            })
+            .unwrap_or(position.column);
+        json!({ "line": line, "character": character })
+    }
+
+    /// Converts a Flux span into an LSP `{ start, end }` range object.
+    pub fn to_lsp_range(&self, span: Span) -> Value {
+        json!({
+            "start": self.to_lsp_position(span.start),
+            "end": self.to_lsp_position(span.end),
+        })
+    }
+
+    /// Converts an LSP `{ line, character }` object into a Flux position.
+    pub fn from_lsp_position(&self, value: &Value) -> Option<Position> {
+        let line = value.get("line")?.as_u64()? as usize + 1;
+        let character = value.get("character")?.as_u64()? as usize;
+        let text = self.line(line).unwrap_or("");
+        let mut units = 0usize;
+        let mut column = 0usize;
+        for ch in text.chars() {
+            if units >= character {
+                break;
+            }
+            units += ch.len_utf16();
+            column += 1;
+        }
+        Some(Position::new(line, column))
+    }
+}
+
+/// Returns whether `position` lies inside `span`, treating the end as inclusive so a cursor
+/// placed right after an identifier still selects it.
+pub fn span_contains(span: Span, position: Position) -> bool {
+    position_le(span.start, position) && position_le(position, span.end)
+}
+
+/// Returns whether `inner` is nested inside `outer`.
+pub fn span_encloses(outer: Span, inner: Span) -> bool {
+    position_le(outer.start, inner.start) && position_le(inner.end, outer.end)
+}
+
+fn position_le(left: Position, right: Position) -> bool {
+    left.line < right.line || (left.line == right.line && left.column <= right.column)
+}
+
+/// Converts a `file://` URI into a filesystem path.
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // `file:///C:/x` on Windows carries an extra slash before the drive letter.
+    let rest = match rest.as_bytes() {
+        [b'/', _, b':', ..] => &rest[1..],
+        _ => rest,
+    };
+    Some(PathBuf::from(percent_decode(rest)))
+}
+
+/// Converts a filesystem path into a `file://` URI.
+pub fn path_to_uri(path: &Path) -> String {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    let text = absolute.to_string_lossy().replace('\\', "/");
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    if encoded.starts_with('/') {
+        format!("file://{encoded}")
+    } else {
+        format!("file:///{encoded}")
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%'
+            && idx + 2 < bytes.len()
+            && let Ok(hex) = std::str::from_utf8(&bytes[idx + 1..idx + 3])
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            idx += 3;
+            continue;
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineIndex, path_to_uri, span_contains, uri_to_path};
+    use crate::diagnostics::position::{Position, Span};
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn positions_convert_through_utf16_columns() {
+        let index = LineIndex::new("let a = 1\nlet é𝄞 = 2\n");
+
+        let lsp = index.to_lsp_position(Position::new(2, 6));
+        assert_eq!(lsp, json!({"line": 1, "character": 7}));
+
+        let back = index.from_lsp_position(&lsp).unwrap();
+        assert_eq!(back, Position::new(2, 6));
+    }
+
+    #[test]
+    fn span_contains_treats_end_as_inclusive() {
+        let span = Span::new(Position::new(1, 4), Position::new(1, 8));
+
+        assert!(span_contains(span, Position::new(1, 8)));
+        assert!(!span_contains(span, Position::new(1, 9)));
+        assert!(!span_contains(span, Position::new(2, 4)));
+    }
+
+    #[test]
+    fn uris_round_trip_with_percent_encoding() {
+        let uri = path_to_uri(Path::new("/tmp/my project/main.flx"));
+
+        assert_eq!(uri, "file:///tmp/my%20project/main.flx");
+        assert_eq!(
+            uri_to_path(&uri).unwrap(),
+            Path::new("/tmp/my project/main.flx")
+        );
+        assert!(uri_to_path("untitled:Untitled-1").is_none());
+    }
+}
@@ -0,0 +1,392 @@
+//! Request dispatch for the language server.
+//!
+//! The server is a synchronous loop: it reads one message, updates the document store or
+//! answers from the latest [`Analysis`], and writes the response before reading the next
+//! message. Documents are re-analyzed on open, change and save.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    diagnostics::{Diagnostic, types::Severity},
+    lsp::{
+        analysis::{Analysis, AnalysisOptions, Location, same_file},
+        range::{LineIndex, path_to_uri, uri_to_path},
+        transport::{read_message, write_message},
+    },
+};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// LSP `CompletionItemKind.Function`.
+const COMPLETION_KIND_FUNCTION: u64 = 3;
+
+struct Document {
+    path: PathBuf,
+    text: String,
+    analysis: Analysis,
+}
+
+/// Language server state for one client connection.
+pub struct Server<W: Write> {
+    writer: W,
+    options: AnalysisOptions,
+    documents: HashMap<String, Document>,
+    initialized: bool,
+    shutdown_requested: bool,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(writer: W, options: AnalysisOptions) -> Self {
+        Self {
+            writer,
+            options,
+            documents: HashMap::new(),
+            initialized: false,
+            shutdown_requested: false,
+        }
+    }
+
+    /// Serves messages until `exit` or end of input.
+    ///
+    /// Returns `true` when the client shut the server down cleanly (`shutdown` followed by
+    /// `exit`), which maps to exit status 0.
+    pub fn serve(&mut self, reader: &mut impl BufRead) -> io::Result<bool> {
+        while let Some(message) = read_message(reader)? {
+            let method = message.get("method").and_then(Value::as_str);
+            if method == Some("exit") {
+                return Ok(self.shutdown_requested);
+            }
+            self.handle(&message)?;
+        }
+        Ok(self.shutdown_requested)
+    }
+
+    fn handle(&mut self, message: &Value) -> io::Result<()> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to server-initiated requests are not used.
+            return Ok(());
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let id = message.get("id").cloned();
+
+        if !self.initialized && method != "initialize" {
+            if let Some(id) = id {
+                return self.respond_error(id, SERVER_NOT_INITIALIZED, "server not initialized");
+            }
+            return Ok(());
+        }
+
+        match (method, id) {
+            ("initialize", Some(id)) => {
+                self.initialized = true;
+                self.respond(id, initialize_result())
+            }
+            ("shutdown", Some(id)) => {
+                self.shutdown_requested = true;
+                self.respond(id, Value::Null)
+            }
+            ("textDocument/didOpen", None) => {
+                let document = &params["textDocument"];
+                match (document["uri"].as_str(), document["text"].as_str()) {
+                    (Some(uri), Some(text)) => self.update_document(uri, text.to_string()),
+                    _ => Ok(()),
+                }
+            }
+            ("textDocument/didChange", None) => {
+                let uri = params["textDocument"]["uri"].as_str();
+                // Full-document sync: the last change carries the whole text.
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.update_document(uri, text.to_string()),
+                    _ => Ok(()),
+                }
+            }
+            ("textDocument/didSave", None) => {
+                let Some(uri) = params["textDocument"]["uri"].as_str() else {
+                    return Ok(());
+                };
+                let text = params["text"]
+                    .as_str()
+                    .map(str::to_string)
+                    .or_else(|| self.documents.get(uri).map(|doc| doc.text.clone()));
+                match text {
+                    Some(text) => self.update_document(uri, text),
+                    None => Ok(()),
+                }
+            }
+            ("textDocument/didClose", None) => {
+                let Some(uri) = params["textDocument"]["uri"].as_str() else {
+                    return Ok(());
+                };
+                self.documents.remove(uri);
+                self.notify(
+                    "textDocument/publishDiagnostics",
+                    json!({ "uri": uri, "diagnostics": [] }),
+                )
+            }
+            ("textDocument/hover", Some(id)) => {
+                let result = self.hover(&params);
+                self.respond(id, result)
+            }
+            ("textDocument/definition", Some(id)) => {
+                let result = self.definition(&params);
+                self.respond(id, result)
+            }
+            ("textDocument/references", Some(id)) => {
+                let result = self.references(&params);
+                self.respond(id, result)
+            }
+            ("textDocument/completion", Some(id)) => {
+                let result = self.completion(&params);
+                self.respond(id, result)
+            }
+            (_, Some(id)) => {
+                self.respond_error(id, METHOD_NOT_FOUND, &format!("unknown method `{method}`"))
+            }
+            (_, None) => Ok(()),
+        }
+    }
+
+    fn update_document(&mut self, uri: &str, text: String) -> io::Result<()> {
+        let path = uri_to_path(uri).unwrap_or_else(|| PathBuf::from(uri));
+        let analysis = Analysis::new(&path, &text, &self.options);
+        let diagnostics: Vec<Value> = analysis
+            .diagnostics()
+            .iter()
+            .map(|diag| diagnostic_to_lsp(diag, analysis.line_index()))
+            .collect();
+        self.documents.insert(
+            uri.to_string(),
+            Document {
+                path,
+                text,
+                analysis,
+            },
+        );
+        self.notify(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    /// Looks up the document and cursor position named by a text-document-position request.
+    fn document_position(
+        &self,
+        params: &Value,
+    ) -> Option<(&Document, crate::diagnostics::position::Position)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let document = self.documents.get(uri)?;
+        let position = document
+            .analysis
+            .line_index()
+            .from_lsp_position(&params["position"])?;
+        Some((document, position))
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some((document, position)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        match document.analysis.hover(position) {
+            Some((label, span)) => json!({
+                "contents": { "kind": "markdown", "value": format!("```flux\n{label}\n```") },
+                "range": document.analysis.line_index().to_lsp_range(span),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        let Some((document, position)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        match document.analysis.definition(position) {
+            Some(location) => self.location_to_lsp(&location),
+            None => Value::Null,
+        }
+    }
+
+    /// Answers find-references from the document's own analysis and, for top-level bindings,
+    /// from every other open document. A file that is open is only searched through its own
+    /// analysis, so unsaved edits win over the copy on disk.
+    fn references(&self, params: &Value) -> Value {
+        let Some((document, position)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        let include_declaration = params["context"]["includeDeclaration"]
+            .as_bool()
+            .unwrap_or(true);
+        let mut found = vec![(
+            document,
+            document.analysis.references(position, include_declaration),
+        )];
+        if let Some(name) = document.analysis.global_name_at(position) {
+            for other in self.documents.values() {
+                if !std::ptr::eq(other, document) {
+                    let locations = other.analysis.global_references(&name, include_declaration);
+                    found.push((other, locations));
+                }
+            }
+        }
+
+        let mut locations: Vec<Location> = Vec::new();
+        for (searched, results) in found {
+            for location in results {
+                let owner = self
+                    .documents
+                    .values()
+                    .find(|open| same_file(&open.path, &location.path));
+                let stale = owner.is_some_and(|owner| !std::ptr::eq(owner, searched));
+                if !stale && !locations.contains(&location) {
+                    locations.push(location);
+                }
+            }
+        }
+        Value::Array(
+            locations
+                .iter()
+                .map(|location| self.location_to_lsp(location))
+                .collect(),
+        )
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let Some((document, position)) = self.document_position(params) else {
+            return Value::Null;
+        };
+        let line = document
+            .text
+            .split('\n')
+            .nth(position.line - 1)
+            .unwrap_or("");
+        let before: String = line.chars().take(position.column).collect();
+        let Some(qualifier) = completion_qualifier(&before) else {
+            return json!({ "isIncomplete": false, "items": [] });
+        };
+        let items: Vec<Value> = document
+            .analysis
+            .module_members(qualifier)
+            .into_iter()
+            .map(|member| {
+                let mut item = json!({ "label": member.name, "kind": COMPLETION_KIND_FUNCTION });
+                if let Some(detail) = member.detail {
+                    item["detail"] = Value::String(detail);
+                }
+                item
+            })
+            .collect();
+        json!({ "isIncomplete": false, "items": items })
+    }
+
+    fn location_to_lsp(&self, location: &Location) -> Value {
+        let uri = path_to_uri(&location.path);
+        let range = match self.documents.get(&uri) {
+            Some(document) => document.analysis.line_index().to_lsp_range(location.span),
+            None => {
+                let text = std::fs::read_to_string(&location.path).unwrap_or_default();
+                LineIndex::new(&text).to_lsp_range(location.span)
+            }
+        };
+        json!({ "uri": uri, "range": range })
+    }
+
+    fn respond(&mut self, id: Value, result: Value) -> io::Result<()> {
+        write_message(
+            &mut self.writer,
+            &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        )
+    }
+
+    fn respond_error(&mut self, id: Value, code: i64, message: &str) -> io::Result<()> {
+        write_message(
+            &mut self.writer,
+            &json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        )
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        write_message(
+            &mut self.writer,
+            &json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+        )
+    }
+}
+
+fn initialize_result() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": { "openClose": true, "change": 1, "save": { "includeText": true } },
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "referencesProvider": true,
+            "completionProvider": { "triggerCharacters": ["."] },
+        },
+        "serverInfo": { "name": "flux", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// Extracts `Module.Name` from text such as `List.ma` or `Flow.List.` ending at the cursor.
+fn completion_qualifier(before: &str) -> Option<&str> {
+    let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let partial_start = before.trim_end_matches(is_ident).len();
+    let head = before[..partial_start].strip_suffix('.')?;
+    let qualifier_start = head
+        .trim_end_matches(|ch: char| is_ident(ch) || ch == '.')
+        .len();
+    let qualifier = head[qualifier_start..].trim_start_matches('.');
+    (!qualifier.is_empty()).then_some(qualifier)
+}
+
+fn diagnostic_to_lsp(diag: &Diagnostic, line_index: &LineIndex) -> Value {
+    let range = diag
+        .span()
+        .map(|span| line_index.to_lsp_range(span))
+        .unwrap_or_else(|| json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } }));
+    let severity = match diag.severity() {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+        Severity::Help => 4,
+    };
+    let message = match diag.message() {
+        Some(message) if !message.is_empty() => format!("{}: {}", diag.title(), message),
+        _ => diag.title().to_string(),
+    };
+    let mut value = json!({
+        "range": range,
+        "severity": severity,
+        "source": "flux",
+        "message": message,
+    });
+    if let Some(code) = diag.code() {
+        value["code"] = Value::String(code.to_string());
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::completion_qualifier;
+
+    #[test]
+    fn completion_qualifier_handles_partial_members() {
+        assert_eq!(completion_qualifier("  List."), Some("List"));
+        assert_eq!(completion_qualifier("x = Flow.List.ma"), Some("Flow.List"));
+        assert_eq!(completion_qualifier("foo"), None);
+        assert_eq!(completion_qualifier("."), None);
+    }
+}
@@ -0,0 +1,728 @@
+//! Name resolution for one document.
+//!
+//! Names resolve the way the compiler resolves them: binders of the document go through a
+//! [`SymbolTable`] scope chain, and everything else is looked up among the top-level bindings
+//! the compiler defined while compiling the module graph ([`Globals`]), following the same
+//! module prefix, `exposing` and alias rules as code generation. The parser stores some binders
+//! without a span (function names, parameters, handler arguments), so their name tokens are
+//! located by scanning identifier tokens forward from the start of the enclosing node.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{
+    compiler::{binding::Binding, symbol_scope::SymbolScope, symbol_table::SymbolTable},
+    diagnostics::position::{Position, Span},
+    lsp::range::span_contains,
+    syntax::{
+        Identifier,
+        block::Block,
+        expression::{Expression, HandleArm, MatchArm, Pattern, StringPart},
+        interner::Interner,
+        lexer::Lexer,
+        program::Program,
+        statement::{ImportExposing, Statement},
+        token_type::TokenType,
+    },
+};
+
+/// What an identifier occurrence refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// A binder in the same document, identified by the span of its name.
+    Local(Span),
+    /// A top-level binding of the module graph, by its qualified name (`Flow.List.map`).
+    ///
+    /// Every module that mentions the binding resolves it to the same target, which is what
+    /// lets find-references follow it across files.
+    Global(Identifier),
+    /// A name the compiler could not resolve either (builtins, typos, unparsed modules).
+    Unresolved(String),
+}
+
+/// Top-level bindings of the compiled module graph.
+#[derive(Debug, Clone, Default)]
+pub struct Globals {
+    /// The compiler's global scope after compiling every module.
+    pub table: SymbolTable,
+    /// File of the module that declared each global, including the document itself.
+    pub sites: HashMap<Identifier, PathBuf>,
+}
+
+/// One identifier occurrence in the document.
+#[derive(Debug, Clone)]
+pub struct Occurrence {
+    pub name: String,
+    pub span: Span,
+    pub target: Target,
+    /// `true` when this occurrence is the binder itself.
+    pub is_definition: bool,
+}
+
+/// Identifier occurrences and import aliases of one document.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    occurrences: Vec<Occurrence>,
+    /// Import alias (or the module name itself) to the full module name.
+    imports: HashMap<String, String>,
+    /// Modules imported with `exposing`, used to resolve bare names.
+    exposing: Vec<String>,
+}
+
+impl SymbolIndex {
+    /// Builds the index for a parsed program and its source text. `interner` must be the one
+    /// `globals` was compiled with.
+    pub fn build(program: &Program, source: &str, interner: &Interner, globals: &Globals) -> Self {
+        let tokens = Lexer::new(source)
+            .tokenize()
+            .into_iter()
+            .filter(|token| token.token_type == TokenType::Ident)
+            .map(|token| (token.literal.as_str().to_string(), token.span()))
+            .collect();
+        let mut walker = Walker {
+            interner,
+            globals,
+            tokens,
+            table: SymbolTable::new_enclosed(globals.table.clone()),
+            module_prefix: None,
+            depth: 0,
+            index: SymbolIndex::default(),
+        };
+        walker.declare_items(&program.statements, true);
+        for stmt in &program.statements {
+            walker.walk_stmt(stmt);
+        }
+        walker.index
+    }
+
+    /// Returns the innermost occurrence under `position`.
+    pub fn occurrence_at(&self, position: Position) -> Option<&Occurrence> {
+        self.occurrences
+            .iter()
+            .find(|occurrence| span_contains(occurrence.span, position))
+    }
+
+    /// Returns every occurrence that resolves to the same binder as `target`.
+    pub fn references_to(&self, target: &Target) -> Vec<&Occurrence> {
+        self.occurrences
+            .iter()
+            .filter(|occurrence| &occurrence.target == target)
+            .collect()
+    }
+
+    /// Resolves an import alias or module name to the full module name.
+    pub fn resolve_module(&self, qualifier: &str) -> Option<&str> {
+        self.imports.get(qualifier).map(String::as_str)
+    }
+}
+
+struct Walker<'a> {
+    interner: &'a Interner,
+    globals: &'a Globals,
+    tokens: Vec<(String, Span)>,
+    table: SymbolTable,
+    /// Name of the `module` body being walked; its members resolve unqualified.
+    module_prefix: Option<String>,
+    /// Number of scopes entered below the top level.
+    depth: usize,
+    index: SymbolIndex,
+}
+
+impl Walker<'_> {
+    fn name(&self, symbol: Identifier) -> String {
+        self.interner.resolve(symbol).to_string()
+    }
+
+    /// Finds the first identifier token spelled `name` at or after `from`.
+    fn locate(&self, name: &str, from: Position) -> Option<Span> {
+        self.tokens
+            .iter()
+            .find(|(literal, span)| literal == name && !position_lt(span.start, from))
+            .map(|(_, span)| *span)
+    }
+
+    fn bind(&mut self, name: Identifier, span: Span) {
+        self.table.define(name, span);
+        self.index.occurrences.push(Occurrence {
+            name: self.name(name),
+            span,
+            target: Target::Local(span),
+            is_definition: true,
+        });
+    }
+
+    /// Binds a span-less name found at or after `from`; returns the end of the token so
+    /// successive binders can be located in order.
+    fn bind_located(&mut self, name: Identifier, from: Position) -> Position {
+        match self.locate(self.interner.resolve(name), from) {
+            Some(span) => {
+                self.bind(name, span);
+                span.end
+            }
+            None => from,
+        }
+    }
+
+    fn reference(&mut self, name: Identifier, span: Span) {
+        let target = self.resolve(name);
+        self.index.occurrences.push(Occurrence {
+            name: self.name(name),
+            span,
+            target,
+            is_definition: false,
+        });
+    }
+
+    /// Resolves a bare name: document binders first, then globals, then members of the
+    /// enclosing module and of `exposing` imports.
+    fn resolve(&mut self, name: Identifier) -> Target {
+        if let Some(binding) = self.table.resolve(name) {
+            if binding.symbol_scope != SymbolScope::Global {
+                return Target::Local(binding.span);
+            }
+            if let Some(target) = self.global_target(&binding) {
+                return target;
+            }
+        }
+        let spelled = self.name(name);
+        let candidates: Vec<String> = self
+            .module_prefix
+            .iter()
+            .chain(&self.index.exposing)
+            .map(|module| format!("{module}.{spelled}"))
+            .collect();
+        candidates
+            .iter()
+            .find_map(|qualified| self.resolve_global(qualified))
+            .unwrap_or(Target::Unresolved(spelled))
+    }
+
+    /// Resolves a qualified name such as `Flow.List.map` among the compiled globals.
+    fn resolve_global(&mut self, qualified: &str) -> Option<Target> {
+        let symbol = self.interner.lookup(qualified)?;
+        let binding = self
+            .table
+            .resolve(symbol)
+            .filter(|binding| binding.symbol_scope == SymbolScope::Global)?;
+        self.global_target(&binding)
+    }
+
+    /// Maps a global binding to its target when some module of the graph declared it.
+    fn global_target(&self, binding: &Binding) -> Option<Target> {
+        self.globals
+            .sites
+            .contains_key(&binding.name)
+            .then_some(Target::Global(binding.name))
+    }
+
+    /// Records the binder of a top-level declaration. Names the compiler defined as globals
+    /// resolve through [`Globals`]; anything else (a document that failed to compile) falls
+    /// back to a document-local binder.
+    fn declare_top_level(&mut self, name: Identifier, def: Span) {
+        let spelled = self.name(name);
+        let qualified = match &self.module_prefix {
+            Some(module) => format!("{module}.{spelled}"),
+            None => spelled.clone(),
+        };
+        let global = self
+            .interner
+            .lookup(&qualified)
+            .filter(|symbol| self.globals.sites.contains_key(symbol));
+        match global {
+            Some(symbol) => self.index.occurrences.push(Occurrence {
+                name: spelled,
+                span: def,
+                target: Target::Global(symbol),
+                is_definition: true,
+            }),
+            None => self.bind(name, def),
+        }
+    }
+
+    fn is_bound(&mut self, name: &str) -> bool {
+        self.interner
+            .lookup(name)
+            .is_some_and(|symbol| self.table.resolve(symbol).is_some())
+    }
+
+    /// Runs `f` in a nested scope: `enclosed` for function bodies, which capture free
+    /// variables, and a plain block scope otherwise.
+    fn with_scope(&mut self, enclosed: bool, f: impl FnOnce(&mut Self)) {
+        let outer = std::mem::take(&mut self.table);
+        self.table = if enclosed {
+            SymbolTable::new_enclosed(outer)
+        } else {
+            SymbolTable::new_block(outer)
+        };
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+        let inner = std::mem::take(&mut self.table);
+        if let Some(outer) = inner.outer {
+            self.table = *outer;
+        }
+    }
+
+    /// Pre-declares functions and constructors of a statement list so that forward and
+    /// mutually recursive references resolve.
+    fn declare_items(&mut self, statements: &[Statement], top_level: bool) {
+        for stmt in statements {
+            match stmt {
+                Statement::Function { name, span, .. } => {
+                    if let Some(def) = self.locate(self.interner.resolve(*name), span.start) {
+                        if top_level {
+                            self.declare_top_level(*name, def);
+                        } else {
+                            self.bind(*name, def);
+                        }
+                    }
+                }
+                Statement::Data {
+                    name,
+                    variants,
+                    span,
+                    ..
+                } => {
+                    if top_level
+                        && let Some(def) = self.locate(self.interner.resolve(*name), span.start)
+                    {
+                        self.index.occurrences.push(Occurrence {
+                            name: self.name(*name),
+                            span: def,
+                            target: Target::Local(def),
+                            is_definition: true,
+                        });
+                    }
+                    for variant in variants {
+                        let ctor = self.interner.resolve(variant.name);
+                        if let Some(def) = self.locate(ctor, variant.span.start) {
+                            self.bind(variant.name, def);
+                        }
+                    }
+                }
+                Statement::Module { name, body, .. } if top_level => {
+                    let outer = self.module_prefix.replace(self.name(*name));
+                    self.declare_items(&body.statements, true);
+                    self.module_prefix = outer;
+                }
+                Statement::Import {
+                    name,
+                    alias,
+                    exposing,
+                    ..
+                } if top_level => {
+                    let module = self.name(*name);
+                    let key = alias.map(|alias| self.name(alias)).unwrap_or_else(|| {
+                        module
+                            .rsplit_once('.')
+                            .map(|(_, last)| last.to_string())
+                            .unwrap_or_else(|| module.clone())
+                    });
+                    self.index.imports.insert(key, module.clone());
+                    self.index.imports.insert(module.clone(), module.clone());
+                    if !matches!(exposing, ImportExposing::None) {
+                        self.index.exposing.push(module);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn walk_block(&mut self, block: &Block) {
+        self.with_scope(false, |walker| {
+            walker.declare_items(&block.statements, false);
+            for stmt in &block.statements {
+                walker.walk_stmt(stmt);
+            }
+        });
+    }
+
+    /// Walks a function-like body whose parameters are located in order from `from`.
+    fn walk_function(&mut self, params: &[Identifier], from: Position, body: &Block) {
+        self.with_scope(true, |walker| {
+            let mut cursor = from;
+            for param in params {
+                cursor = walker.bind_located(*param, cursor);
+            }
+            walker.walk_block(body);
+        });
+    }
+
+    fn walk_stmt(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let {
+                name, value, span, ..
+            } => {
+                self.walk_expr(value);
+                match self.locate(self.interner.resolve(*name), span.start) {
+                    Some(def) if self.depth == 0 => self.declare_top_level(*name, def),
+                    Some(def) => self.bind(*name, def),
+                    None => {}
+                }
+            }
+            Statement::LetDestructure { pattern, value, .. } => {
+                self.walk_expr(value);
+                self.bind_pattern(pattern);
+            }
+            Statement::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.walk_expr(value);
+                }
+            }
+            Statement::Expression { expression, .. } => self.walk_expr(expression),
+            Statement::Function {
+                name,
+                parameters,
+                body,
+                span,
+                ..
+            } => {
+                let after_name = self
+                    .locate(self.interner.resolve(*name), span.start)
+                    .map(|def| def.end)
+                    .unwrap_or(span.start);
+                self.walk_function(parameters, after_name, body);
+            }
+            Statement::Assign { name, value, span } => {
+                if let Some(at) = self.locate(self.interner.resolve(*name), span.start) {
+                    self.reference(*name, at);
+                }
+                self.walk_expr(value);
+            }
+            Statement::Module { name, body, .. } => {
+                let outer = self.module_prefix.replace(self.name(*name));
+                for stmt in &body.statements {
+                    self.walk_stmt(stmt);
+                }
+                self.module_prefix = outer;
+            }
+            Statement::Class { methods, .. } => {
+                for method in methods {
+                    if let Some(body) = &method.default_body {
+                        self.walk_function(&method.params, method.span.start, body);
+                    }
+                }
+            }
+            Statement::Instance { methods, .. } => {
+                for method in methods {
+                    self.walk_function(&method.params, method.span.start, &method.body);
+                }
+            }
+            Statement::Import { .. }
+            | Statement::Data { .. }
+            | Statement::EffectDecl { .. }
+            | Statement::EffectAlias { .. } => {}
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier { name, span } => self.bind(*name, *span),
+            Pattern::Literal { expression, .. } => self.walk_expr(expression),
+            Pattern::Some { pattern, .. }
+            | Pattern::Left { pattern, .. }
+            | Pattern::Right { pattern, .. } => self.bind_pattern(pattern),
+            Pattern::Cons { head, tail, .. } => {
+                self.bind_pattern(head);
+                self.bind_pattern(tail);
+            }
+            Pattern::Tuple { elements, .. } => {
+                for element in elements {
+                    self.bind_pattern(element);
+                }
+            }
+            Pattern::Constructor { name, fields, span } => {
+                if let Some(at) = self.locate(self.interner.resolve(*name), span.start) {
+                    self.reference(*name, at);
+                }
+                for field in fields {
+                    self.bind_pattern(field);
+                }
+            }
+            Pattern::NamedConstructor {
+                name, fields, span, ..
+            } => {
+                if let Some(at) = self.locate(self.interner.resolve(*name), span.start) {
+                    self.reference(*name, at);
+                }
+                for field in fields {
+                    match &field.pattern {
+                        Some(pattern) => self.bind_pattern(pattern),
+                        None => {
+                            self.bind_located(field.name, field.span.start);
+                        }
+                    }
+                }
+            }
+            Pattern::Wildcard { .. } | Pattern::None { .. } | Pattern::EmptyList { .. } => {}
+        }
+    }
+
+    fn walk_match_arm(&mut self, arm: &MatchArm) {
+        self.with_scope(false, |walker| {
+            walker.bind_pattern(&arm.pattern);
+            if let Some(guard) = &arm.guard {
+                walker.walk_expr(guard);
+            }
+            walker.walk_expr(&arm.body);
+        });
+    }
+
+    fn walk_handle_arm(&mut self, arm: &HandleArm) {
+        let mut cursor = self
+            .locate(self.interner.resolve(arm.operation_name), arm.span.start)
+            .map(|span| span.end)
+            .unwrap_or(arm.span.start);
+        let binders: Vec<Identifier> = std::iter::once(arm.resume_param)
+            .chain(arm.params.iter().copied())
+            .collect();
+        self.with_scope(true, |walker| {
+            for binder in binders {
+                cursor = walker.bind_located(binder, cursor);
+            }
+            walker.walk_expr(&arm.body);
+        });
+    }
+
+    fn walk_expr(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier { name, span, .. } => self.reference(*name, *span),
+            Expression::MemberAccess {
+                object,
+                member,
+                span,
+                ..
+            } => {
+                let member = self.name(*member);
+                let member_span = self
+                    .tokens
+                    .iter()
+                    .rev()
+                    .find(|(literal, at)| {
+                        *literal == member
+                            && !position_lt(at.start, span.start)
+                            && !position_lt(span.end, at.end)
+                    })
+                    .map(|(_, at)| *at);
+                match qualified_path(object, self.interner) {
+                    Some(qualifier) if !self.is_bound(root_segment(&qualifier)) => {
+                        if let Some(at) = member_span {
+                            let module = self
+                                .index
+                                .imports
+                                .get(&qualifier)
+                                .cloned()
+                                .unwrap_or_else(|| qualifier.clone());
+                            let target = self
+                                .resolve_global(&format!("{module}.{member}"))
+                                .unwrap_or_else(|| {
+                                    Target::Unresolved(format!("{qualifier}.{member}"))
+                                });
+                            self.index.occurrences.push(Occurrence {
+                                name: member,
+                                span: at,
+                                target,
+                                is_definition: false,
+                            });
+                        }
+                    }
+                    _ => self.walk_expr(object),
+                }
+            }
+            Expression::Function {
+                parameters,
+                body,
+                span,
+                ..
+            } => self.walk_function(parameters, span.start, body),
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.walk_expr(scrutinee);
+                for arm in arms {
+                    self.walk_match_arm(arm);
+                }
+            }
+            Expression::Handle {
+                expr,
+                parameter,
+                arms,
+                ..
+            } => {
+                self.walk_expr(expr);
+                if let Some(parameter) = parameter {
+                    self.walk_expr(parameter);
+                }
+                for arm in arms {
+                    self.walk_handle_arm(arm);
+                }
+            }
+            Expression::InterpolatedString { parts, .. } => {
+                for part in parts {
+                    if let StringPart::Interpolation(expr) = part {
+                        self.walk_expr(expr);
+                    }
+                }
+            }
+            Expression::Prefix { right, .. } => self.walk_expr(right),
+            Expression::Infix { left, right, .. } => {
+                self.walk_expr(left);
+                self.walk_expr(right);
+            }
+            Expression::If {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                self.walk_expr(condition);
+                self.walk_block(consequence);
+                if let Some(alternative) = alternative {
+                    self.walk_block(alternative);
+                }
+            }
+            Expression::DoBlock { block, .. } => self.walk_block(block),
+            Expression::Call {
+                function,
+                arguments,
+                ..
+            } => {
+                self.walk_expr(function);
+                for argument in arguments {
+                    self.walk_expr(argument);
+                }
+            }
+            Expression::ListLiteral { elements, .. }
+            | Expression::ArrayLiteral { elements, .. }
+            | Expression::TupleLiteral { elements, .. } => {
+                for element in elements {
+                    self.walk_expr(element);
+                }
+            }
+            Expression::Index { left, index, .. } => {
+                self.walk_expr(left);
+                self.walk_expr(index);
+            }
+            Expression::Hash { pairs, .. } => {
+                for (key, value) in pairs {
+                    self.walk_expr(key);
+                    self.walk_expr(value);
+                }
+            }
+            Expression::TupleFieldAccess { object, .. } => self.walk_expr(object),
+            Expression::Some { value, .. }
+            | Expression::Left { value, .. }
+            | Expression::Right { value, .. } => self.walk_expr(value),
+            Expression::Cons { head, tail, .. } => {
+                self.walk_expr(head);
+                self.walk_expr(tail);
+            }
+            Expression::Perform { args, .. } => {
+                for arg in args {
+                    self.walk_expr(arg);
+                }
+            }
+            Expression::Sealing { expr, .. } => self.walk_expr(expr),
+            Expression::NamedConstructor { fields, .. } => {
+                for field in fields {
+                    if let Some(value) = &field.value {
+                        self.walk_expr(value);
+                    }
+                }
+            }
+            Expression::Spread {
+                base, overrides, ..
+            } => {
+                self.walk_expr(base);
+                for field in overrides {
+                    if let Some(value) = &field.value {
+                        self.walk_expr(value);
+                    }
+                }
+            }
+            Expression::Integer { .. }
+            | Expression::Float { .. }
+            | Expression::String { .. }
+            | Expression::Boolean { .. }
+            | Expression::EmptyList { .. }
+            | Expression::None { .. }
+            | Expression::Hole { .. } => {}
+        }
+    }
+}
+
+/// Flattens an `A.B.c` identifier / member-access chain into its dotted spelling.
+pub fn qualified_path(expr: &Expression, interner: &Interner) -> Option<String> {
+    match expr {
+        Expression::Identifier { name, .. } => Some(interner.resolve(*name).to_string()),
+        Expression::MemberAccess { object, member, .. } => {
+            let mut path = qualified_path(object, interner)?;
+            path.push('.');
+            path.push_str(interner.resolve(*member));
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+fn root_segment(path: &str) -> &str {
+    path.split('.').next().unwrap_or(path)
+}
+
+fn position_lt(left: Position, right: Position) -> bool {
+    left.line < right.line || (left.line == right.line && left.column < right.column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Globals, SymbolIndex, Target};
+    use crate::{
+        diagnostics::position::Position,
+        syntax::{lexer::Lexer, parser::Parser},
+    };
+
+    fn index(source: &str) -> SymbolIndex {
+        let mut parser = Parser::new(Lexer::new(source));
+        let program = parser.parse_program();
+        assert!(
+            parser.errors.is_empty(),
+            "parse errors: {:?}",
+            parser.errors
+        );
+        let interner = parser.take_interner();
+        SymbolIndex::build(&program, source, &interner, &Globals::default())
+    }
+
+    #[test]
+    fn locals_resolve_to_their_innermost_binder() {
+        let source = "fn f(x) {\n    let y = x + 1\n    \\x -> x + y\n}\n";
+        let index = index(source);
+
+        let outer_use = index.occurrence_at(Position::new(2, 12)).unwrap();
+        assert_eq!(outer_use.name, "x");
+        assert_eq!(
+            outer_use.target,
+            Target::Local(index.occurrence_at(Position::new(1, 5)).unwrap().span)
+        );
+
+        let lambda_use = index.occurrence_at(Position::new(3, 11)).unwrap();
+        let lambda_param = index.occurrence_at(Position::new(3, 5)).unwrap();
+        assert!(lambda_param.is_definition);
+        assert_eq!(lambda_use.target, Target::Local(lambda_param.span));
+        assert_eq!(index.references_to(&lambda_use.target).len(), 2);
+    }
+
+    #[test]
+    fn forward_references_resolve_and_unknown_members_stay_unresolved() {
+        let source = "import Flow.List as L\n\nfn main() { helper(L.map) }\nfn helper(f) { f }\n";
+        let index = index(source);
+
+        let call = index.occurrence_at(Position::new(3, 13)).unwrap();
+        assert_eq!(call.name, "helper");
+        assert!(matches!(call.target, Target::Local(span) if span.start.line == 4));
+
+        let member = index.occurrence_at(Position::new(3, 22)).unwrap();
+        assert_eq!(member.target, Target::Unresolved("L.map".to_string()));
+        assert_eq!(index.resolve_module("L"), Some("Flow.List"));
+    }
+}
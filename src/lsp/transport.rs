@@ -0,0 +1,89 @@
+//! JSON-RPC message framing for the language server stdio transport.
+//!
+//! LSP frames every message with a `Content-Length` header followed by a blank line and the
+//! UTF-8 JSON payload. Reading and writing are generic over `BufRead`/`Write` so the server can be
+//! driven headlessly from in-memory buffers in tests.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Reads one framed JSON-RPC message.
+///
+/// Returns `Ok(None)` when the input stream is closed before a new header starts.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+    let mut saw_header = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            if saw_header {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream closed inside message header",
+                ));
+            }
+            return Ok(None);
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if saw_header {
+                break;
+            }
+            continue;
+        }
+        saw_header = true;
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = Some(value.trim().parse().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid Content-Length header")
+            })?);
+        }
+    }
+
+    let length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Writes one framed JSON-RPC message and flushes the writer.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_message, write_message};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    #[test]
+    fn framed_messages_round_trip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({"jsonrpc": "2.0", "id": 1})).unwrap();
+        write_message(&mut buffer, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        let first = read_message(&mut reader).unwrap().unwrap();
+        let second = read_message(&mut reader).unwrap().unwrap();
+
+        assert_eq!(first["id"], 1);
+        assert_eq!(second["method"], "exit");
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn missing_content_length_is_rejected() {
+        let mut reader = Cursor::new(b"Content-Type: x\r\n\r\n{}".to_vec());
+
+        assert!(read_message(&mut reader).is_err());
+    }
+}
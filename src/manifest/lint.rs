@@ -0,0 +1,213 @@
+//! Lint configuration: the `[lint]` table of `flux.toml` and `.fluxlint.toml` files.
+//!
+//! A `.fluxlint.toml` holds the same keys at its top level. `flux lint` uses the nearest one
+//! above the entry file and otherwise falls back to the `[lint]` table of the project manifest.
+//!
+//! ```toml
+//! deny = ["W011"]
+//! allow = ["W007"]
+//! max_cyclomatic_complexity = 15
+//! max_nesting_depth = 5
+//! max_function_lines = 80
+//! max_function_params = 6
+//! ```
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::syntax::linter::{LintLevel, LintPolicy, is_lint_code};
+
+use super::{Manifest, ManifestError};
+
+/// File name of a standalone lint configuration.
+pub const LINT_FILE: &str = ".fluxlint.toml";
+
+/// Lint levels and complexity thresholds, as written in a lint configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintSettings {
+    /// Codes that are not reported.
+    pub allow: Vec<String>,
+    /// Codes reported as warnings, which is the default level.
+    pub warn: Vec<String>,
+    /// Codes reported as errors, failing `flux lint`.
+    pub deny: Vec<String>,
+    /// W009 limit on lines in a function body.
+    pub max_function_lines: Option<usize>,
+    /// W010 limit on parameters of a function.
+    pub max_function_params: Option<usize>,
+    /// W011 limit on cyclomatic complexity.
+    pub max_cyclomatic_complexity: Option<usize>,
+    /// W012 limit on nesting depth.
+    pub max_nesting_depth: Option<usize>,
+}
+
+impl LintSettings {
+    /// Parses and validates `.fluxlint.toml` text; `path` is only used for error messages.
+    pub fn parse(text: &str, path: &Path) -> Result<LintSettings, ManifestError> {
+        let settings: LintSettings =
+            toml::from_str(text).map_err(|err| ManifestError::Invalid {
+                path: path.to_path_buf(),
+                message: err.message().to_string(),
+            })?;
+        settings.validate(path)?;
+        Ok(settings)
+    }
+
+    /// Reads and parses the `.fluxlint.toml` at `path`.
+    pub fn load(path: &Path) -> Result<LintSettings, ManifestError> {
+        let text = fs::read_to_string(path).map_err(|error| ManifestError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        LintSettings::parse(&text, path)
+    }
+
+    /// Rejects unknown codes, codes given two levels and zero thresholds.
+    pub(crate) fn validate(&self, path: &Path) -> Result<(), ManifestError> {
+        let invalid = |message: String| ManifestError::Invalid {
+            path: path.to_path_buf(),
+            message,
+        };
+        let mut seen: Vec<&str> = Vec::new();
+        for code in self.allow.iter().chain(&self.warn).chain(&self.deny) {
+            if !is_lint_code(code) {
+                return Err(invalid(format!(
+                    "unknown lint `{code}`; lint codes are W001 to W013"
+                )));
+            }
+            if seen.contains(&code.as_str()) {
+                return Err(invalid(format!(
+                    "lint `{code}` is given more than one level"
+                )));
+            }
+            seen.push(code);
+        }
+        for (key, value) in [
+            ("max_function_lines", self.max_function_lines),
+            ("max_function_params", self.max_function_params),
+            ("max_cyclomatic_complexity", self.max_cyclomatic_complexity),
+            ("max_nesting_depth", self.max_nesting_depth),
+        ] {
+            if value == Some(0) {
+                return Err(invalid(format!("`{key}` must be at least 1")));
+            }
+        }
+        Ok(())
+    }
+
+    /// The linter policy these settings describe; unset thresholds keep their defaults.
+    pub fn policy(&self) -> LintPolicy {
+        let mut policy = LintPolicy::default();
+        for (codes, level) in [
+            (&self.allow, LintLevel::Allow),
+            (&self.warn, LintLevel::Warn),
+            (&self.deny, LintLevel::Deny),
+        ] {
+            for code in codes {
+                policy.set_level(code.as_str(), level);
+            }
+        }
+        let thresholds = &mut policy.thresholds;
+        if let Some(max) = self.max_function_lines {
+            thresholds.max_function_lines = max;
+        }
+        if let Some(max) = self.max_function_params {
+            thresholds.max_function_params = max;
+        }
+        if let Some(max) = self.max_cyclomatic_complexity {
+            thresholds.max_cyclomatic_complexity = max;
+        }
+        if let Some(max) = self.max_nesting_depth {
+            thresholds.max_nesting_depth = max;
+        }
+        policy
+    }
+}
+
+/// Returns the nearest `.fluxlint.toml` in the directory of `entry` or one of its ancestors.
+pub fn discover(entry: &Path) -> Option<PathBuf> {
+    let entry = if entry.is_absolute() {
+        entry.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(entry)
+    };
+    let start = if entry.is_dir() {
+        entry.as_path()
+    } else {
+        entry.parent()?
+    };
+    start
+        .ancestors()
+        .map(|dir| dir.join(LINT_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Loads the lint configuration that applies to `entry`: the nearest `.fluxlint.toml`, else the
+/// `[lint]` table of the nearest `flux.toml`, else the defaults.
+pub fn load_for(entry: &Path) -> Result<LintSettings, ManifestError> {
+    if let Some(path) = discover(entry) {
+        return LintSettings::load(&path);
+    }
+    match super::discover(entry) {
+        Some(manifest_path) => Ok(Manifest::load(&manifest_path)?.lint),
+        None => Ok(LintSettings::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::syntax::linter::LintLevel;
+
+    use super::LintSettings;
+
+    fn parse(text: &str) -> Result<LintSettings, String> {
+        LintSettings::parse(text, Path::new(".fluxlint.toml")).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn builds_policy_from_levels_and_thresholds() {
+        let settings = parse(
+            r#"
+deny = ["W011"]
+allow = ["W007"]
+max_cyclomatic_complexity = 15
+max_function_params = 7
+"#,
+        )
+        .unwrap();
+        let policy = settings.policy();
+
+        assert_eq!(policy.level("W011"), LintLevel::Deny);
+        assert_eq!(policy.level("W007"), LintLevel::Allow);
+        assert_eq!(policy.level("W001"), LintLevel::Warn);
+        assert_eq!(policy.thresholds.max_cyclomatic_complexity, 15);
+        assert_eq!(policy.thresholds.max_function_params, 7);
+        assert_eq!(policy.thresholds.max_function_lines, 50);
+        assert_eq!(policy.thresholds.max_nesting_depth, 4);
+    }
+
+    #[test]
+    fn rejects_unknown_codes_conflicts_and_zero_thresholds() {
+        let err = parse(r#"deny = ["W099"]"#).unwrap_err();
+        assert!(err.contains("unknown lint `W099`"), "{err}");
+
+        let err = parse("deny = [\"W011\"]\nallow = [\"W011\"]").unwrap_err();
+        assert!(err.contains("`W011` is given more than one level"), "{err}");
+
+        let err = parse("max_nesting_depth = 0").unwrap_err();
+        assert!(
+            err.contains("`max_nesting_depth` must be at least 1"),
+            "{err}"
+        );
+
+        let err = parse("max_depth = 3").unwrap_err();
+        assert!(err.contains("unknown field `max_depth`"), "{err}");
+    }
+}
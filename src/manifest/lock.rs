@@ -0,0 +1,232 @@
+//! `flux.lock`: the resolved package graph with content hashes.
+//!
+//! Each dependency is recorded with a `sha256:` checksum over its `flux.toml` and the `.flx`
+//! files under its roots. Vendored registry packages are expected to stay as vendored, so a
+//! registry package whose checksum no longer matches the lockfile is an error; path
+//! dependencies are edited alongside the project and their checksums are simply refreshed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ManifestError, PackageSource, Resolution, ResolvedPackage, Version};
+use crate::{
+    bytecode::bytecode_cache::{hash_bytes, hash_file},
+    shared::source_files::collect_flx_files,
+};
+
+/// Format version written to the `version` key.
+pub const LOCK_VERSION: u32 = 1;
+
+const HEADER: &str = "# This file is generated by Flux from flux.toml. Do not edit it by hand.\n";
+
+/// A parsed or generated `flux.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(rename = "package", default)]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// One `[[package]]` entry of `flux.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    /// `root`, `registry` or `path+<dir>` with `<dir>` relative to the project.
+    pub source: String,
+    /// Content hash; absent for the project itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    /// Builds the lockfile for `resolution`, hashing every dependency.
+    pub fn from_resolution(resolution: &Resolution) -> Result<Lockfile, ManifestError> {
+        let packages = resolution
+            .packages
+            .iter()
+            .map(|package| {
+                let checksum = match package.source {
+                    PackageSource::Root => None,
+                    _ => Some(package_checksum(package)?),
+                };
+                Ok(LockedPackage {
+                    name: package.name.clone(),
+                    version: package.version,
+                    source: source_label(&package.source),
+                    checksum,
+                    dependencies: package.dependencies.clone(),
+                })
+            })
+            .collect::<Result<_, ManifestError>>()?;
+        Ok(Lockfile {
+            version: LOCK_VERSION,
+            packages,
+        })
+    }
+
+    /// Parses lockfile text. Returns `None` for unreadable or foreign content, which is then
+    /// regenerated.
+    pub fn parse(text: &str) -> Option<Lockfile> {
+        toml::from_str(text)
+            .ok()
+            .filter(|lock: &Lockfile| lock.version == LOCK_VERSION)
+    }
+
+    /// Renders the lockfile as TOML with a generated-file header.
+    pub fn render(&self) -> String {
+        let body = toml::to_string(self).expect("lockfile serializes to TOML");
+        format!("{HEADER}\n{body}")
+    }
+
+    fn find(&self, name: &str, version: Version) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .find(|package| package.name == name && package.version == version)
+    }
+}
+
+/// Writes the lockfile for `resolution` to `lock_path` unless it is already up to date.
+///
+/// Returns whether the file was written. Fails with
+/// [`ManifestError::ChecksumMismatch`] when a vendored registry package changed since it
+/// was locked.
+pub fn write_lockfile(lock_path: &Path, resolution: &Resolution) -> Result<bool, ManifestError> {
+    let lock = Lockfile::from_resolution(resolution)?;
+    let existing = fs::read_to_string(lock_path).ok();
+
+    if let Some(previous) = existing.as_deref().and_then(Lockfile::parse) {
+        for package in &lock.packages {
+            if package.source != source_label(&PackageSource::Registry) {
+                continue;
+            }
+            if let Some(locked) = previous.find(&package.name, package.version)
+                && locked.source == package.source
+                && locked.checksum != package.checksum
+            {
+                return Err(ManifestError::ChecksumMismatch {
+                    name: package.name.clone(),
+                    version: package.version,
+                });
+            }
+        }
+    }
+
+    let rendered = lock.render();
+    if existing.as_deref() == Some(rendered.as_str()) {
+        return Ok(false);
+    }
+    fs::write(lock_path, rendered).map_err(|error| ManifestError::Io {
+        path: lock_path.to_path_buf(),
+        error,
+    })?;
+    Ok(true)
+}
+
+/// Hashes a package's `flux.toml` and the `.flx` files under its roots.
+///
+/// Each file contributes its path relative to the package directory and its SHA-256, in path
+/// order, so the checksum does not depend on where the package is checked out.
+pub fn package_checksum(package: &ResolvedPackage) -> Result<String, ManifestError> {
+    let mut files = vec![package.dir.join(super::MANIFEST_FILE)];
+    for root in &package.roots {
+        collect_flx_files(root, &mut files);
+    }
+    let mut entries: Vec<(String, PathBuf)> = files
+        .into_iter()
+        .map(|file| {
+            let relative = file.strip_prefix(&package.dir).unwrap_or(&file);
+            (relative.to_string_lossy().replace('\\', "/"), file)
+        })
+        .collect();
+    entries.sort();
+    entries.dedup_by(|left, right| left.0 == right.0);
+
+    let mut buffer = Vec::new();
+    for (relative, file) in &entries {
+        let hash = hash_file(file).map_err(|error| ManifestError::Io {
+            path: file.clone(),
+            error,
+        })?;
+        buffer.extend_from_slice(relative.as_bytes());
+        buffer.push(0);
+        buffer.extend_from_slice(&hash);
+    }
+    let digest = hash_bytes(&buffer);
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    Ok(format!("sha256:{hex}"))
+}
+
+fn source_label(source: &PackageSource) -> String {
+    match source {
+        PackageSource::Root => "root".to_string(),
+        PackageSource::Registry => "registry".to_string(),
+        PackageSource::Path(path) => format!("path+{}", path.to_string_lossy().replace('\\', "/")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Lockfile, write_lockfile};
+    use crate::manifest::{ManifestError, Project};
+
+    #[test]
+    fn writes_lockfile_and_detects_changed_vendored_packages() {
+        let dir = std::env::temp_dir().join(format!("flux_lockfile_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let vendored = dir.join("vendor/json/1.2.0");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(vendored.join("src")).unwrap();
+        fs::write(
+            dir.join("flux.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n\
+             [dependencies]\njson = { version = \"1.2.0\" }\n\n[registry]\npath = \"vendor\"\n",
+        )
+        .unwrap();
+        fs::write(
+            vendored.join("flux.toml"),
+            "[package]\nname = \"json\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        fs::write(vendored.join("src/Json.flx"), "module Json { }\n").unwrap();
+
+        let project = Project::load(&dir.join("flux.toml")).unwrap();
+        assert!(project.write_lock().unwrap());
+        assert!(
+            !project.write_lock().unwrap(),
+            "unchanged lock is not rewritten"
+        );
+
+        let text = fs::read_to_string(project.lock_path()).unwrap();
+        let lock = Lockfile::parse(&text).unwrap();
+        assert_eq!(lock.packages.len(), 2);
+        assert_eq!(lock.packages[0].source, "root");
+        assert_eq!(lock.packages[0].dependencies, ["json"]);
+        assert_eq!(lock.packages[1].source, "registry");
+        assert!(
+            lock.packages[1]
+                .checksum
+                .as_deref()
+                .is_some_and(|sum| sum.starts_with("sha256:") && sum.len() == 71)
+        );
+
+        fs::write(
+            vendored.join("src/Json.flx"),
+            "module Json { fn x() { 1 } }\n",
+        )
+        .unwrap();
+        let err = write_lockfile(&project.lock_path(), &project.resolution).unwrap_err();
+        assert!(
+            matches!(err, ManifestError::ChecksumMismatch { .. }),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,406 @@
+//! Project manifests (`flux.toml`) and local dependency resolution.
+//!
+//! A manifest names the package, lists its source roots, sets default compiler switches and
+//! declares dependencies. A dependency is either a local path or an exact version from a
+//! vendored directory registry laid out as `<registry>/<name>/<version>/flux.toml`.
+//!
+//! [`resolve`] walks the dependency graph, rejects two versions of the same package and yields
+//! the module roots handed to the [`ModuleGraph`](crate::syntax::module_graph::ModuleGraph).
+//! [`lock`] records the resolved graph with content hashes in `flux.lock` next to the manifest.
+//!
+//! ```toml
+//! [package]
+//! name = "app"
+//! version = "0.1.0"
+//! roots = ["src"]
+//!
+//! [build]
+//! strict = true
+//! optimize = true
+//!
+//! [dependencies]
+//! utils = { path = "../utils" }
+//! json = { version = "1.2.0" }
+//!
+//! [registry]
+//! path = "vendor"
+//!
+//! [lint]
+//! deny = ["W011"]
+//! max_cyclomatic_complexity = 15
+//! ```
+
+pub mod lint;
+pub mod lock;
+pub mod resolve;
+pub mod version;
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+pub use lint::LintSettings;
+pub use lock::{LockedPackage, Lockfile};
+pub use resolve::{PackageSource, Resolution, ResolvedPackage, resolve};
+pub use version::Version;
+
+/// File name of a project manifest.
+pub const MANIFEST_FILE: &str = "flux.toml";
+/// File name of the lockfile written next to a manifest.
+pub const LOCK_FILE: &str = "flux.lock";
+
+/// A parsed `flux.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub package: Package,
+    #[serde(default)]
+    pub build: BuildSettings,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+    pub registry: Option<Registry>,
+    #[serde(default)]
+    pub lint: LintSettings,
+}
+
+/// The `[package]` table.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Package {
+    pub name: String,
+    pub version: Version,
+    /// Module roots, relative to the manifest directory.
+    #[serde(default = "default_roots")]
+    pub roots: Vec<PathBuf>,
+}
+
+/// The `[build]` table: defaults for the matching CLI switches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildSettings {
+    pub strict: bool,
+    pub optimize: bool,
+}
+
+/// One entry of `[dependencies]`.
+///
+/// `path` points at a directory holding the dependency's own `flux.toml`. Without `path`,
+/// `version` selects the package from the `[registry]` directory; with `path`, it is checked
+/// against the version the dependency declares.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dependency {
+    pub path: Option<PathBuf>,
+    pub version: Option<Version>,
+}
+
+/// The `[registry]` table: a vendored directory registry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Registry {
+    /// Registry directory, relative to the manifest directory.
+    pub path: PathBuf,
+}
+
+fn default_roots() -> Vec<PathBuf> {
+    vec![PathBuf::from("src")]
+}
+
+/// Errors from loading manifests, resolving dependencies or writing the lockfile.
+#[derive(Debug)]
+pub enum ManifestError {
+    /// A manifest or lockfile could not be read or written.
+    Io { path: PathBuf, error: io::Error },
+    /// A manifest is not valid TOML or does not follow the manifest schema.
+    Invalid { path: PathBuf, message: String },
+    /// A dependency does not point at a directory with a `flux.toml`.
+    MissingDependency {
+        package: String,
+        name: String,
+        path: PathBuf,
+    },
+    /// A registry dependency was declared but the root manifest has no `[registry]`.
+    NoRegistry { package: String, name: String },
+    /// The manifest a dependency points at declares another package name.
+    NameMismatch {
+        name: String,
+        found: String,
+        path: PathBuf,
+    },
+    /// A path dependency declares a different version than the one required.
+    VersionMismatch {
+        name: String,
+        required: Version,
+        found: Version,
+    },
+    /// Two packages require different versions of the same package.
+    VersionConflict {
+        name: String,
+        first: (Version, String),
+        second: (Version, String),
+    },
+    /// The same package version is provided by two different directories.
+    SourceConflict {
+        name: String,
+        version: Version,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    /// A vendored registry package no longer matches the checksum recorded in `flux.lock`.
+    ChecksumMismatch { name: String, version: Version },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            ManifestError::Invalid { path, message } => {
+                write!(f, "invalid manifest {}: {message}", path.display())
+            }
+            ManifestError::MissingDependency {
+                package,
+                name,
+                path,
+            } => write!(
+                f,
+                "dependency `{name}` of `{package}` not found: no {MANIFEST_FILE} in {}",
+                path.display()
+            ),
+            ManifestError::NoRegistry { package, name } => write!(
+                f,
+                "dependency `{name}` of `{package}` has no `path`, and the project manifest has \
+                 no [registry] to look it up in"
+            ),
+            ManifestError::NameMismatch { name, found, path } => write!(
+                f,
+                "dependency `{name}` points at {}, which declares package `{found}`",
+                path.display()
+            ),
+            ManifestError::VersionMismatch {
+                name,
+                required,
+                found,
+            } => write!(
+                f,
+                "dependency `{name}` requires version {required}, but its manifest declares {found}"
+            ),
+            ManifestError::VersionConflict {
+                name,
+                first: (first, first_by),
+                second: (second, second_by),
+            } => write!(
+                f,
+                "version conflict for `{name}`: `{first_by}` uses {first}, `{second_by}` uses \
+                 {second}"
+            ),
+            ManifestError::SourceConflict {
+                name,
+                version,
+                first,
+                second,
+            } => write!(
+                f,
+                "package `{name} {version}` is provided by both {} and {}",
+                first.display(),
+                second.display()
+            ),
+            ManifestError::ChecksumMismatch { name, version } => write!(
+                f,
+                "vendored package `{name} {version}` does not match its checksum in {LOCK_FILE}; \
+                 restore it or delete the entry from {LOCK_FILE}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl Manifest {
+    /// Parses and validates manifest text; `path` is only used for error messages.
+    pub fn parse(text: &str, path: &Path) -> Result<Manifest, ManifestError> {
+        let invalid = |message: String| ManifestError::Invalid {
+            path: path.to_path_buf(),
+            message,
+        };
+        let manifest: Manifest =
+            toml::from_str(text).map_err(|err| invalid(err.message().to_string()))?;
+
+        if !is_package_name(&manifest.package.name) {
+            return Err(invalid(format!(
+                "package name `{}` must be non-empty and use only letters, digits, `_` and `-`",
+                manifest.package.name
+            )));
+        }
+        if manifest.package.roots.is_empty() {
+            return Err(invalid(
+                "`package.roots` must list at least one root".into(),
+            ));
+        }
+        for (name, dependency) in &manifest.dependencies {
+            if !is_package_name(name) {
+                return Err(invalid(format!("invalid dependency name `{name}`")));
+            }
+            if dependency.path.is_none() && dependency.version.is_none() {
+                return Err(invalid(format!(
+                    "dependency `{name}` needs a `path` or a `version`"
+                )));
+            }
+        }
+        manifest.lint.validate(path)?;
+        Ok(manifest)
+    }
+
+    /// Reads and parses the manifest at `path`.
+    pub fn load(path: &Path) -> Result<Manifest, ManifestError> {
+        let text = fs::read_to_string(path).map_err(|error| ManifestError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        Manifest::parse(&text, path)
+    }
+}
+
+fn is_package_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+}
+
+/// Returns the nearest `flux.toml` in the directory of `entry` or one of its ancestors.
+pub fn discover(entry: &Path) -> Option<PathBuf> {
+    let entry = if entry.is_absolute() {
+        entry.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(entry)
+    };
+    let start = if entry.is_dir() {
+        entry.as_path()
+    } else {
+        entry.parent()?
+    };
+    start
+        .ancestors()
+        .map(|dir| dir.join(MANIFEST_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// A loaded manifest together with its resolved dependency graph.
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub manifest_path: PathBuf,
+    pub manifest: Manifest,
+    pub resolution: Resolution,
+}
+
+impl Project {
+    /// Loads the manifest at `manifest_path` and resolves its dependencies.
+    pub fn load(manifest_path: &Path) -> Result<Project, ManifestError> {
+        let manifest = Manifest::load(manifest_path)?;
+        let resolution = resolve(manifest_path, &manifest)?;
+        Ok(Project {
+            manifest_path: manifest_path.to_path_buf(),
+            manifest,
+            resolution,
+        })
+    }
+
+    /// Loads the project whose manifest [`discover`] finds for `entry`, if any.
+    pub fn discover(entry: &Path) -> Result<Option<Project>, ManifestError> {
+        discover(entry)
+            .map(|manifest_path| Project::load(&manifest_path))
+            .transpose()
+    }
+
+    /// Path of the lockfile next to the manifest.
+    pub fn lock_path(&self) -> PathBuf {
+        self.manifest_path.with_file_name(LOCK_FILE)
+    }
+
+    /// Writes `flux.lock` for the resolved graph. Returns `false` when the file was already
+    /// up to date.
+    pub fn write_lock(&self) -> Result<bool, ManifestError> {
+        lock::write_lockfile(&self.lock_path(), &self.resolution)
+    }
+
+    /// Module roots of the project followed by those of its dependencies.
+    pub fn module_roots(&self) -> Vec<PathBuf> {
+        self.resolution.module_roots()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{Manifest, ManifestError, Version};
+
+    fn parse(text: &str) -> Result<Manifest, ManifestError> {
+        Manifest::parse(text, Path::new("flux.toml"))
+    }
+
+    #[test]
+    fn parses_full_manifest_and_defaults() {
+        let manifest = parse(
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+
+            [build]
+            strict = true
+
+            [dependencies]
+            utils = { path = "../utils" }
+            json = { version = "1.2.0" }
+
+            [registry]
+            path = "vendor"
+
+            [lint]
+            deny = ["W011"]
+            max_nesting_depth = 6
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.package.version, Version::new(0, 1, 0));
+        assert_eq!(manifest.package.roots, vec![PathBuf::from("src")]);
+        assert!(manifest.build.strict);
+        assert!(!manifest.build.optimize);
+        assert_eq!(
+            manifest.dependencies["utils"].path.as_deref(),
+            Some(Path::new("../utils"))
+        );
+        assert_eq!(
+            manifest.dependencies["json"].version,
+            Some(Version::new(1, 2, 0))
+        );
+        assert_eq!(manifest.registry.unwrap().path, PathBuf::from("vendor"));
+        assert_eq!(manifest.lint.deny, vec!["W011".to_string()]);
+        assert_eq!(manifest.lint.max_nesting_depth, Some(6));
+    }
+
+    #[test]
+    fn rejects_invalid_manifests() {
+        let cases = [
+            "[package]\nname = \"app\"\n",
+            "[package]\nname = \"a b\"\nversion = \"0.1.0\"\n",
+            "[package]\nname = \"app\"\nversion = \"0.1\"\n",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nroots = []\n",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n[dependencies]\nx = {}\n",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2024\"\n",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n[lint]\nallow = [\"W100\"]\n",
+        ];
+        for text in cases {
+            assert!(
+                matches!(parse(text), Err(ManifestError::Invalid { .. })),
+                "accepted:\n{text}"
+            );
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Dependency resolution for a project manifest.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use super::{MANIFEST_FILE, Manifest, ManifestError, Version};
+
+/// Where a resolved package comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    /// The project itself.
+    Root,
+    /// A path dependency, relative to the project directory.
+    Path(PathBuf),
+    /// A package from the vendored registry.
+    Registry,
+}
+
+/// One package of a resolved project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPackage {
+    pub name: String,
+    pub version: Version,
+    pub source: PackageSource,
+    /// Canonical directory holding the package's `flux.toml`.
+    pub dir: PathBuf,
+    /// Module roots, resolved against `dir`.
+    pub roots: Vec<PathBuf>,
+    /// Names of direct dependencies, sorted.
+    pub dependencies: Vec<String>,
+}
+
+/// The packages of a project: the project itself first, then its dependencies by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub packages: Vec<ResolvedPackage>,
+}
+
+impl Resolution {
+    /// The project package.
+    pub fn root(&self) -> &ResolvedPackage {
+        &self.packages[0]
+    }
+
+    /// Module roots of every package, project roots first.
+    pub fn module_roots(&self) -> Vec<PathBuf> {
+        self.packages
+            .iter()
+            .flat_map(|package| package.roots.iter().cloned())
+            .collect()
+    }
+}
+
+/// Resolves the dependency graph of `manifest`, read from `manifest_path`.
+///
+/// Every package may appear in one version only; a second version, or the same version from a
+/// second directory, is an error. Registry dependencies are looked up in the project
+/// manifest's `[registry]`, including those of transitive dependencies.
+pub fn resolve(manifest_path: &Path, manifest: &Manifest) -> Result<Resolution, ManifestError> {
+    let root_dir = package_dir(manifest_path)?;
+    let registry = manifest
+        .registry
+        .as_ref()
+        .map(|registry| root_dir.join(&registry.path));
+
+    let mut packages = vec![resolved(manifest, PackageSource::Root, root_dir.clone())];
+    // Package name -> (index into `packages`, name of the package that first required it).
+    let mut seen: HashMap<String, (usize, String)> = HashMap::new();
+    seen.insert(manifest.package.name.clone(), (0, String::new()));
+
+    let mut pending = VecDeque::from([(0usize, manifest.clone())]);
+    while let Some((index, current)) = pending.pop_front() {
+        let dir = packages[index].dir.clone();
+        for (name, dependency) in &current.dependencies {
+            let (dep_dir, source) = match &dependency.path {
+                Some(path) => (dir.join(path), None),
+                None => {
+                    let version = dependency.version.expect("validated by Manifest::parse");
+                    let registry = registry.as_ref().ok_or_else(|| ManifestError::NoRegistry {
+                        package: current.package.name.clone(),
+                        name: name.clone(),
+                    })?;
+                    let dir = registry.join(name).join(version.to_string());
+                    (dir, Some(PackageSource::Registry))
+                }
+            };
+            let dep_manifest_path = dep_dir.join(MANIFEST_FILE);
+            if !dep_manifest_path.is_file() {
+                return Err(ManifestError::MissingDependency {
+                    package: current.package.name.clone(),
+                    name: name.clone(),
+                    path: dep_dir,
+                });
+            }
+            let dep_manifest = Manifest::load(&dep_manifest_path)?;
+            let dep_dir = package_dir(&dep_manifest_path)?;
+            let found = dep_manifest.package.version;
+            if dep_manifest.package.name != *name {
+                return Err(ManifestError::NameMismatch {
+                    name: name.clone(),
+                    found: dep_manifest.package.name,
+                    path: dep_dir,
+                });
+            }
+            if let Some(required) = dependency.version
+                && required != found
+            {
+                return Err(ManifestError::VersionMismatch {
+                    name: name.clone(),
+                    required,
+                    found,
+                });
+            }
+
+            if let Some((existing, required_by)) = seen.get(name) {
+                let existing = &packages[*existing];
+                if existing.version != found {
+                    return Err(ManifestError::VersionConflict {
+                        name: name.clone(),
+                        first: (existing.version, display_requirer(required_by, &packages)),
+                        second: (found, current.package.name.clone()),
+                    });
+                }
+                if existing.dir != dep_dir {
+                    return Err(ManifestError::SourceConflict {
+                        name: name.clone(),
+                        version: found,
+                        first: existing.dir.clone(),
+                        second: dep_dir,
+                    });
+                }
+            } else {
+                let source = source
+                    .unwrap_or_else(|| PackageSource::Path(relative_path(&root_dir, &dep_dir)));
+                seen.insert(name.clone(), (packages.len(), current.package.name.clone()));
+                pending.push_back((packages.len(), dep_manifest.clone()));
+                packages.push(resolved(&dep_manifest, source, dep_dir));
+            }
+            packages[index].dependencies.push(name.clone());
+        }
+        packages[index].dependencies.sort();
+    }
+
+    packages[1..].sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(Resolution { packages })
+}
+
+fn resolved(manifest: &Manifest, source: PackageSource, dir: PathBuf) -> ResolvedPackage {
+    ResolvedPackage {
+        name: manifest.package.name.clone(),
+        version: manifest.package.version,
+        source,
+        roots: manifest
+            .package
+            .roots
+            .iter()
+            .map(|root| dir.join(root))
+            .collect(),
+        dir,
+        dependencies: Vec::new(),
+    }
+}
+
+/// The root package is recorded with an empty requirer; name it for error messages.
+fn display_requirer(required_by: &str, packages: &[ResolvedPackage]) -> String {
+    if required_by.is_empty() {
+        packages[0].name.clone()
+    } else {
+        required_by.to_string()
+    }
+}
+
+fn package_dir(manifest_path: &Path) -> Result<PathBuf, ManifestError> {
+    let dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    fs::canonicalize(dir).map_err(|error| ManifestError::Io {
+        path: dir.to_path_buf(),
+        error,
+    })
+}
+
+/// Expresses `target` relative to `base`; both are canonical paths.
+fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base
+        .iter()
+        .zip(&target)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let mut relative: PathBuf = base[common..].iter().map(|_| "..").collect();
+    relative.extend(&target[common..]);
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use super::{PackageSource, relative_path};
+    use crate::manifest::{ManifestError, Project, Version};
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flux_manifest_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_package(dir: &Path, name: &str, version: &str, dependencies: &str) {
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("flux.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"{version}\"\n\n\
+                 [dependencies]\n{dependencies}\n[registry]\npath = \"vendor\"\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn resolves_path_and_registry_dependencies() {
+        let dir = temp_project("resolve");
+        write_package(
+            &dir.join("app"),
+            "app",
+            "0.1.0",
+            "utils = { path = \"../utils\" }\njson = { version = \"1.2.0\" }\n",
+        );
+        write_package(
+            &dir.join("utils"),
+            "utils",
+            "0.3.0",
+            "json = { version = \"1.2.0\" }\n",
+        );
+        write_package(&dir.join("app/vendor/json/1.2.0"), "json", "1.2.0", "");
+
+        let project = Project::load(&dir.join("app/flux.toml")).unwrap();
+        let packages = &project.resolution.packages;
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["app", "json", "utils"]);
+        assert_eq!(packages[0].dependencies, ["json", "utils"]);
+        assert_eq!(packages[1].source, PackageSource::Registry);
+        assert_eq!(
+            packages[2].source,
+            PackageSource::Path(PathBuf::from("../utils"))
+        );
+        assert_eq!(packages[2].version, Version::new(0, 3, 0));
+        assert!(project.module_roots()[0].ends_with("app/src"));
+        assert_eq!(project.module_roots().len(), 3);
+    }
+
+    #[test]
+    fn rejects_conflicting_versions() {
+        let dir = temp_project("conflict");
+        write_package(
+            &dir.join("app"),
+            "app",
+            "0.1.0",
+            "json = { version = \"1.2.0\" }\nutils = { path = \"../utils\" }\n",
+        );
+        write_package(
+            &dir.join("utils"),
+            "utils",
+            "0.3.0",
+            "json = { path = \"../json\" }\n",
+        );
+        write_package(&dir.join("app/vendor/json/1.2.0"), "json", "1.2.0", "");
+        write_package(&dir.join("json"), "json", "2.0.0", "");
+
+        let err = Project::load(&dir.join("app/flux.toml")).unwrap_err();
+        assert!(
+            matches!(&err, ManifestError::VersionConflict { name, .. } if name == "json"),
+            "{err}"
+        );
+        assert!(
+            err.to_string()
+                .contains("`app` uses 1.2.0, `utils` uses 2.0.0")
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_misnamed_dependencies() {
+        let dir = temp_project("missing");
+        write_package(&dir, "app", "0.1.0", "utils = { path = \"utils\" }\n");
+        let err = Project::load(&dir.join("flux.toml")).unwrap_err();
+        assert!(
+            matches!(err, ManifestError::MissingDependency { .. }),
+            "{err}"
+        );
+
+        write_package(&dir.join("utils"), "tools", "0.1.0", "");
+        let err = Project::load(&dir.join("flux.toml")).unwrap_err();
+        assert!(matches!(err, ManifestError::NameMismatch { .. }), "{err}");
+    }
+
+    #[test]
+    fn relative_paths_climb_to_common_ancestor() {
+        assert_eq!(
+            relative_path(Path::new("/a/b/app"), Path::new("/a/utils")),
+            PathBuf::from("../../utils")
+        );
+        assert_eq!(
+            relative_path(Path::new("/a"), Path::new("/a/vendor/x")),
+            PathBuf::from("vendor/x")
+        );
+    }
+}
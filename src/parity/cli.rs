@@ -23,21 +23,42 @@ use super::report::{
 };
 use super::runner::{
     DEFAULT_TIMEOUT_SECS, capture_dump_aether, capture_dump_cfg, capture_dump_core,
-    capture_dump_lir, capture_dump_repr, compile_fixture, is_native_skip, run_way,
+    capture_dump_lir, capture_dump_repr, compile_fixture, core_pass_change, is_core_eval_skip,
+    is_native_skip, run_way,
 };
 use super::{
     BackendId, DebugArtifacts, ExitKind, MismatchDetail, ParityResult, SurfaceKind, Verdict, Way,
     backend_spec,
 };
 
-fn default_vm_binary() -> PathBuf {
+pub(crate) fn default_vm_binary() -> PathBuf {
     default_parity_binary("target/parity_vm/debug")
 }
 
-fn default_llvm_binary() -> PathBuf {
+pub(crate) fn default_llvm_binary() -> PathBuf {
     default_parity_binary("target/parity_native/debug")
 }
 
+/// Exits with build instructions when either parity binary is missing.
+pub(crate) fn require_parity_binaries(vm_binary: &Path, llvm_binary: &Path) {
+    if !vm_binary.exists() {
+        eprintln!(
+            "Error: VM binary not found at {}\n\
+             Build with: CARGO_TARGET_DIR=target/parity_vm cargo build",
+            vm_binary.display()
+        );
+        std::process::exit(1);
+    }
+    if !llvm_binary.exists() {
+        eprintln!(
+            "Error: LLVM binary not found at {}\n\
+             Build with: CARGO_TARGET_DIR=target/parity_native cargo build --features llvm",
+            llvm_binary.display()
+        );
+        std::process::exit(1);
+    }
+}
+
 fn default_parity_binary(debug_dir: &str) -> PathBuf {
     let mut path = PathBuf::from(debug_dir);
     path.push(format!("flux{}", std::env::consts::EXE_SUFFIX));
@@ -58,23 +79,7 @@ pub fn run_parity_check(args: &[String]) {
 
     let binary_statuses = ensure_parity_binaries(&config);
 
-    // Validate binaries exist
-    if !config.vm_binary.exists() {
-        eprintln!(
-            "Error: VM binary not found at {}\n\
-             Build with: CARGO_TARGET_DIR=target/parity_vm cargo build",
-            config.vm_binary.display()
-        );
-        std::process::exit(1);
-    }
-    if !config.llvm_binary.exists() {
-        eprintln!(
-            "Error: LLVM binary not found at {}\n\
-             Build with: CARGO_TARGET_DIR=target/parity_native cargo build --features llvm",
-            config.llvm_binary.display()
-        );
-        std::process::exit(1);
-    }
+    require_parity_binaries(&config.vm_binary, &config.llvm_binary);
 
     print_run_context(&config, &binary_statuses);
 
@@ -236,6 +241,10 @@ fn check_file(file: &Path, opts: &CheckOpts<'_>) -> ParityResult {
         run_results.push(result);
     }
 
+    // The Core interpreter only models part of the language; drop its run
+    // rather than skipping the whole fixture.
+    run_results.retain(|result| is_core_eval_skip(result).is_none());
+
     // Check for native skip
     for result in &run_results {
         if let Some(reason) = is_native_skip(result) {
@@ -544,7 +553,7 @@ fn collect_diagnostic_code_details(
         .collect()
 }
 
-fn diagnostic_codes(stderr: &str) -> Vec<String> {
+pub(crate) fn diagnostic_codes(stderr: &str) -> Vec<String> {
     let mut codes = Vec::new();
     let mut rest = stderr;
     while let Some(start) = rest.find("error[E") {
@@ -663,11 +672,40 @@ fn collect_mismatch_details(
             )
         });
         for other in &run_results[1..] {
+            // The Core interpreter reports runtime errors in its own words, so
+            // only exit kind and stdout are compared against it.
+            let core_run = [base, other].into_iter().find(|run| run.way == Way::Core);
+            let stderr_diverged = core_run.is_none()
+                && (base.exit_kind != ExitKind::Success || other.exit_kind != ExitKind::Success)
+                && base.normalized_stderr != other.normalized_stderr;
             let runtime_diverged = base.exit_kind != other.exit_kind
                 || base.normalized_stdout != other.normalized_stdout
-                || ((base.exit_kind != ExitKind::Success || other.exit_kind != ExitKind::Success)
-                    && base.normalized_stderr != other.normalized_stderr);
-            if runtime_diverged && !has_shared_mismatch {
+                || stderr_diverged;
+            if runtime_diverged
+                && !has_shared_mismatch
+                && let Some(core_run) = core_run
+            {
+                let backend_run = if core_run.way == base.way {
+                    other
+                } else {
+                    base
+                };
+                match core_pass_change(core_run) {
+                    Some((pass, summary)) => details.push(MismatchDetail::CorePassMismatch {
+                        way: core_run.way,
+                        pass,
+                        summary,
+                    }),
+                    None => details.push(MismatchDetail::BackendRuntimeMismatch {
+                        baseline_way: core_run.way,
+                        backend: backend_run.way.backend_id(),
+                        summary: format!(
+                            "{} diverged from the Core reference; every Core pass agrees",
+                            backend_run.way
+                        ),
+                    }),
+                }
+            } else if runtime_diverged && !has_shared_mismatch {
                 let backend = other.way.backend_id();
                 details.push(MismatchDetail::BackendIrMismatch {
                     baseline_way: base.way,
@@ -703,9 +741,7 @@ fn collect_mismatch_details(
                 });
             }
 
-            if (base.exit_kind != ExitKind::Success || other.exit_kind != ExitKind::Success)
-                && base.normalized_stderr != other.normalized_stderr
-            {
+            if stderr_diverged {
                 details.push(MismatchDetail::Stderr {
                     left_way: base.way,
                     left: base.normalized_stderr.clone(),
@@ -756,6 +792,7 @@ fn run_compile_phase(files: &[PathBuf], config: &Config) -> bool {
             Way::LlvmCached => 3,
             Way::VmStrict => 4,
             Way::LlvmStrict => 5,
+            Way::Core => 6,
         });
         compile_ways.dedup();
         let mut expected_failure_stderr: Vec<(Way, String)> = Vec::new();
@@ -1882,6 +1919,7 @@ fn mismatch_detail_label(detail: &MismatchDetail) -> String {
         MismatchDetail::BackendRuntimeMismatch { backend, .. } => {
             format!("backend_runtime: {backend}")
         }
+        MismatchDetail::CorePassMismatch { pass, .. } => format!("core_pass: {pass}"),
         MismatchDetail::CacheMismatch {
             fresh_way,
             cached_way,
@@ -0,0 +1,111 @@
+//! Comparison of the `vm` and `llvm` ways on one program, shared by `flux fuzz-parity` and
+//! `flux reduce`.
+
+use std::path::Path;
+use std::time::Duration;
+
+use super::cli::diagnostic_codes;
+use super::runner::{is_native_skip, run_way};
+use super::{ExitKind, RunResult, Way};
+
+/// How the VM and native runs of a program compare.
+pub enum Outcome {
+    Agree,
+    /// The native backend does not support the program.
+    Skip(String),
+    Diverge(Divergence),
+}
+
+/// Observable behavior of the two ways on a divergent program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub vm: Observed,
+    pub llvm: Observed,
+}
+
+/// What one way's run shows: how it exited, its output and its diagnostic codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observed {
+    pub exit_kind: ExitKind,
+    pub stdout: String,
+    pub codes: Vec<String>,
+}
+
+impl Observed {
+    pub fn of(result: &RunResult) -> Self {
+        Self {
+            exit_kind: result.exit_kind,
+            stdout: result.normalized_stdout.clone(),
+            codes: diagnostic_codes(&result.stderr),
+        }
+    }
+}
+
+impl Divergence {
+    pub fn summary(&self) -> String {
+        if self.vm.exit_kind != self.llvm.exit_kind {
+            format!("vm {} vs llvm {}", self.vm.exit_kind, self.llvm.exit_kind)
+        } else if self.vm.codes != self.llvm.codes {
+            format!(
+                "error codes differ: vm [{}] vs llvm [{}]",
+                self.vm.codes.join(", "),
+                self.llvm.codes.join(", ")
+            )
+        } else {
+            "stdout differs".to_string()
+        }
+    }
+
+    /// Whether `other` is the same kind of divergence: the same exit kinds, and a
+    /// difference in the same observation. Minimizers keep only candidates for which this
+    /// holds, so they cannot drift to an unrelated failure.
+    pub fn same_shape(&self, other: &Self) -> bool {
+        self.vm.exit_kind == other.vm.exit_kind
+            && self.llvm.exit_kind == other.llvm.exit_kind
+            && (self.vm.codes != self.llvm.codes) == (other.vm.codes != other.llvm.codes)
+            && (self.vm.stdout != self.llvm.stdout) == (other.vm.stdout != other.llvm.stdout)
+    }
+}
+
+/// Runs `path` under `vm` and `llvm` and compares exit kind, normalized stdout and diagnostic
+/// codes. Programs the native backend does not support, and runs that could not start, are
+/// skipped.
+pub fn compare_vm_llvm(
+    vm_binary: &Path,
+    llvm_binary: &Path,
+    path: &Path,
+    extra_args: &[String],
+    timeout: Duration,
+) -> Outcome {
+    let run = |way| run_way(vm_binary, llvm_binary, path, way, extra_args, timeout);
+    let vm = run(Way::Vm);
+    let llvm = run(Way::Llvm);
+    if let Some(reason) = is_native_skip(&llvm) {
+        return Outcome::Skip(reason);
+    }
+    if vm.exit_kind == ExitKind::ToolFailure || llvm.exit_kind == ExitKind::ToolFailure {
+        let failed = if vm.exit_kind == ExitKind::ToolFailure {
+            &vm
+        } else {
+            &llvm
+        };
+        return Outcome::Skip(
+            failed
+                .stderr
+                .lines()
+                .next()
+                .unwrap_or("tool failure")
+                .to_string(),
+        );
+    }
+
+    let divergence = Divergence {
+        vm: Observed::of(&vm),
+        llvm: Observed::of(&llvm),
+    };
+    if divergence.vm == divergence.llvm {
+        Outcome::Agree
+    } else {
+        Outcome::Diverge(divergence)
+    }
+}
@@ -0,0 +1,259 @@
+//! CLI entry point for `flux fuzz-parity`.
+//!
+//! Usage:
+//!   flux fuzz-parity [options]
+//!
+//! Generates random well-typed programs (see [`super::generate`]), runs each under
+//! `Way::Vm` and `Way::Llvm`, and compares exit kind, normalized stdout and diagnostic
+//! codes. Divergent programs are saved with a parity fixture header, then minimized with
+//! delta debugging into a second file that keeps the same divergence.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::ExitKind;
+use super::cli::{default_llvm_binary, default_vm_binary, require_parity_binaries};
+use super::divergence::{Divergence, Outcome, compare_vm_llvm};
+use super::generate::{FuzzProgram, ProgramPart, render_parts};
+use super::minimize::ddmin;
+use super::runner::DEFAULT_TIMEOUT_SECS;
+
+const DEFAULT_COUNT: u64 = 100;
+const DEFAULT_OUT_DIR: &str = "target/fuzz-parity";
+
+/// Entry point called from `main.rs`.
+pub fn run_fuzz_parity(args: &[String]) {
+    let config = match parse_args(args) {
+        Ok(c) => c,
+        Err(msg) => {
+            eprintln!("Error: {msg}");
+            eprintln!();
+            print_usage();
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(err) = fs::create_dir_all(&config.out_dir) {
+        eprintln!("Error: cannot create {}: {err}", config.out_dir.display());
+        std::process::exit(1);
+    }
+
+    if config.emit_only {
+        for seed in config.seeds() {
+            let program = FuzzProgram::generate(seed);
+            let path = config.out_dir.join(format!("seed-{seed}.flx"));
+            if let Err(err) = fs::write(&path, program.render()) {
+                eprintln!("Error: cannot write {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        }
+        println!(
+            "fuzz-parity: wrote {} programs to {}",
+            config.count,
+            config.out_dir.display()
+        );
+        return;
+    }
+
+    require_parity_binaries(&config.vm_binary, &config.llvm_binary);
+
+    println!(
+        "fuzz-parity: seeds {}..{} ({} programs)",
+        config.seed,
+        config.seed + config.count,
+        config.count
+    );
+
+    let mut divergent = 0;
+    let mut skipped = 0;
+    for seed in config.seeds() {
+        let program = FuzzProgram::generate(seed);
+        let path = config.out_dir.join(format!("seed-{seed}.flx"));
+        match check_program(&config, &path, &program.parts) {
+            Outcome::Agree => {}
+            Outcome::Skip(reason) => {
+                skipped += 1;
+                println!("  skip  seed {seed}: {reason}");
+            }
+            Outcome::Diverge(divergence) => {
+                divergent += 1;
+                println!("  FAIL  seed {seed}: {}", divergence.summary());
+                save_program(&path, &program.parts, &divergence);
+                println!("        saved {}", path.display());
+                if config.minimize {
+                    let min_path = config.out_dir.join(format!("seed-{seed}.min.flx"));
+                    let minimal = minimize(&config, &min_path, &program.parts, &divergence);
+                    save_program(&min_path, &minimal, &divergence);
+                    println!(
+                        "        minimized {} -> {} parts: {}",
+                        program.parts.len(),
+                        minimal.len(),
+                        min_path.display()
+                    );
+                }
+                continue;
+            }
+        }
+        // Agreeing and skipped programs are not worth keeping.
+        let _ = fs::remove_file(&path);
+    }
+
+    println!(
+        "\nfuzz-parity: {} programs, {divergent} divergent, {skipped} skipped",
+        config.count
+    );
+    if divergent > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Writes `parts` to `path`, runs both ways and compares them.
+fn check_program(config: &Config, path: &Path, parts: &[ProgramPart]) -> Outcome {
+    if let Err(err) = fs::write(path, render_parts(parts)) {
+        return Outcome::Skip(format!("cannot write {}: {err}", path.display()));
+    }
+    compare_vm_llvm(
+        &config.vm_binary,
+        &config.llvm_binary,
+        path,
+        &[],
+        config.timeout,
+    )
+}
+
+/// Shrinks `parts` while the program keeps diverging the way `original` does.
+fn minimize(
+    config: &Config,
+    path: &Path,
+    parts: &[ProgramPart],
+    original: &Divergence,
+) -> Vec<ProgramPart> {
+    ddmin(parts, |candidate| {
+        matches!(
+            check_program(config, path, candidate),
+            Outcome::Diverge(divergence) if divergence.same_shape(original)
+        )
+    })
+}
+
+fn save_program(path: &Path, parts: &[ProgramPart], divergence: &Divergence) {
+    let header = format!(
+        "// parity: vm, llvm\n// expect: {}\n// bug: fuzz-parity divergence: {}\n\n",
+        match divergence.vm.exit_kind {
+            ExitKind::CompileError => "compile_error",
+            ExitKind::RuntimeError => "runtime_error",
+            _ => "success",
+        },
+        divergence.summary()
+    );
+    if let Err(err) = fs::write(path, header + &render_parts(parts)) {
+        eprintln!("[fuzz-parity] failed to write {}: {err}", path.display());
+    }
+}
+
+// ── Argument parsing ───────────────────────────────────────────────────────
+
+struct Config {
+    /// First seed; programs use `seed..seed + count`.
+    seed: u64,
+    count: u64,
+    out_dir: PathBuf,
+    /// When true, only write the generated programs.
+    emit_only: bool,
+    minimize: bool,
+    vm_binary: PathBuf,
+    llvm_binary: PathBuf,
+    timeout: Duration,
+}
+
+impl Config {
+    fn seeds(&self) -> std::ops::Range<u64> {
+        self.seed..self.seed + self.count
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut seed: Option<u64> = None;
+    let mut count = DEFAULT_COUNT;
+    let mut out_dir = PathBuf::from(DEFAULT_OUT_DIR);
+    let mut emit_only = false;
+    let mut minimize = true;
+    let mut vm_binary = default_vm_binary();
+    let mut llvm_binary = default_llvm_binary();
+    let mut timeout_secs = DEFAULT_TIMEOUT_SECS;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        let mut value = |name: &str| -> Result<String, String> {
+            i += 1;
+            args.get(i)
+                .cloned()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match arg {
+            "--seed" => {
+                let raw = value("--seed")?;
+                seed = Some(raw.parse().map_err(|_| format!("invalid seed: {raw}"))?);
+            }
+            "--count" => {
+                let raw = value("--count")?;
+                count = raw.parse().map_err(|_| format!("invalid count: {raw}"))?;
+            }
+            "--out" => out_dir = PathBuf::from(value("--out")?),
+            "--emit" => emit_only = true,
+            "--no-minimize" => minimize = false,
+            "--vm-binary" => vm_binary = PathBuf::from(value("--vm-binary")?),
+            "--llvm-binary" => llvm_binary = PathBuf::from(value("--llvm-binary")?),
+            "--timeout" => {
+                let raw = value("--timeout")?;
+                timeout_secs = raw.parse().map_err(|_| format!("invalid timeout: {raw}"))?;
+            }
+            other => return Err(format!("unknown option: {other}")),
+        }
+        i += 1;
+    }
+
+    // Without --seed, start from the clock; the seed is printed so the run can be repeated.
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    });
+    if seed.checked_add(count).is_none() {
+        return Err(format!("seed {seed} + count {count} overflows"));
+    }
+
+    Ok(Config {
+        seed,
+        count,
+        out_dir,
+        emit_only,
+        minimize,
+        vm_binary,
+        llvm_binary,
+        timeout: Duration::from_secs(timeout_secs),
+    })
+}
+
+fn print_usage() {
+    eprintln!(
+        "\
+Usage:
+  flux fuzz-parity [options]
+
+Options:
+  --seed <n>             First seed (default: current time); seeds n..n+count are run
+  --count <n>            Number of programs (default: {DEFAULT_COUNT})
+  --out <dir>            Where divergent programs are saved (default: {DEFAULT_OUT_DIR})
+  --emit                 Only write the generated programs, without running them
+  --no-minimize          Save divergent programs without minimizing them
+  --vm-binary <path>     Path to VM binary (default: {})
+  --llvm-binary <path>   Path to native binary (default: {})
+  --timeout <secs>       Timeout per program per way (default: {DEFAULT_TIMEOUT_SECS})",
+        default_vm_binary().display(),
+        default_llvm_binary().display()
+    );
+}
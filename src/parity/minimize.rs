@@ -0,0 +1,77 @@
+//! Delta debugging (Zeller's `ddmin`) over a list of program units.
+//!
+//! Used by `flux fuzz-parity` to shrink a divergent program to a small reproducer, and usable
+//! by any caller that can re-check a candidate.
+
+/// Shrinks `units` to a 1-minimal subsequence for which `still_fails` holds: removing any
+/// single remaining unit makes the failure go away.
+///
+/// `still_fails` is assumed to hold for `units`; it is never called on the full input.
+pub fn ddmin<T: Clone>(units: &[T], mut still_fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut current = units.to_vec();
+    let mut granularity = 2;
+    while current.len() >= 2 {
+        let chunk = current.len().div_ceil(granularity);
+        let chunks: Vec<(usize, usize)> = (0..current.len())
+            .step_by(chunk)
+            .map(|start| (start, (start + chunk).min(current.len())))
+            .collect();
+
+        // Try each chunk alone, then each complement.
+        let subset = chunks
+            .iter()
+            .map(|&(start, end)| current[start..end].to_vec())
+            .find(|candidate| candidate.len() < current.len() && still_fails(candidate));
+        if let Some(subset) = subset {
+            current = subset;
+            granularity = 2;
+            continue;
+        }
+        let complement = chunks
+            .iter()
+            .map(|&(start, end)| {
+                let mut rest = current[..start].to_vec();
+                rest.extend_from_slice(&current[end..]);
+                rest
+            })
+            .find(|candidate| !candidate.is_empty() && still_fails(candidate));
+        if let Some(complement) = complement {
+            current = complement;
+            granularity = (granularity - 1).max(2);
+            continue;
+        }
+
+        if granularity >= current.len() {
+            break;
+        }
+        granularity = (granularity * 2).min(current.len());
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ddmin;
+
+    #[test]
+    fn ddmin_keeps_only_the_units_the_failure_needs() {
+        let units: Vec<u32> = (0..40).collect();
+        let minimal = ddmin(&units, |candidate| {
+            candidate.contains(&7) && candidate.contains(&31)
+        });
+
+        assert_eq!(minimal, vec![7, 31]);
+    }
+
+    #[test]
+    fn ddmin_preserves_the_order_of_units() {
+        let units = ["c", "a", "b", "d"];
+        let minimal = ddmin(&units, |candidate| {
+            let a = candidate.iter().position(|u| *u == "a");
+            let d = candidate.iter().position(|u| *u == "d");
+            matches!((a, d), (Some(a), Some(d)) if a < d)
+        });
+
+        assert_eq!(minimal, vec!["a", "d"]);
+    }
+}
@@ -21,6 +21,7 @@
 //! cargo run -- parity-check tests/parity --ways vm,vm_strict
 //! cargo run -- parity-check tests/parity --ways llvm,llvm_strict
 //! cargo run -- parity-check tests/parity --ways vm,llvm,vm_cached,llvm_cached,vm_strict,llvm_strict
+//! cargo run -- parity-check tests/parity --ways vm,llvm,core
 //! cargo run -- parity-check tests/parity --capture-core
 //! cargo run -- parity-check tests/parity --capture-aether
 //! cargo run -- parity-check tests/parity --capture-repr
@@ -33,10 +34,17 @@
 //! ```
 
 pub mod cli;
+pub mod divergence;
 pub mod fixture;
+pub mod fuzz;
+pub mod generate;
+pub mod minimize;
+pub mod mutate;
 pub mod normalize;
+pub mod reduce;
 pub mod report;
 pub mod runner;
+pub mod shrink;
 
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -77,6 +85,8 @@ pub enum Way {
     LlvmCached,
     VmStrict,
     LlvmStrict,
+    /// Reference Core IR interpreter, run after every Core pass.
+    Core,
 }
 
 impl fmt::Display for Way {
@@ -88,6 +98,7 @@ impl fmt::Display for Way {
             Self::LlvmCached => write!(f, "llvm_cached"),
             Self::VmStrict => write!(f, "vm_strict"),
             Self::LlvmStrict => write!(f, "llvm_strict"),
+            Self::Core => write!(f, "core"),
         }
     }
 }
@@ -101,6 +112,7 @@ impl Way {
             "llvm_cached" => Some(Self::LlvmCached),
             "vm_strict" => Some(Self::VmStrict),
             "llvm_strict" => Some(Self::LlvmStrict),
+            "core" => Some(Self::Core),
             _ => None,
         }
     }
@@ -137,6 +149,7 @@ impl Way {
         match self {
             Self::Vm | Self::VmCached | Self::VmStrict => BackendId::Vm,
             Self::Llvm | Self::LlvmCached | Self::LlvmStrict => BackendId::Llvm,
+            Self::Core => BackendId::Core,
         }
     }
 }
@@ -145,6 +158,7 @@ impl Way {
 pub enum BackendId {
     Vm,
     Llvm,
+    Core,
 }
 
 impl fmt::Display for BackendId {
@@ -152,6 +166,7 @@ impl fmt::Display for BackendId {
         match self {
             Self::Vm => write!(f, "vm"),
             Self::Llvm => write!(f, "llvm"),
+            Self::Core => write!(f, "core"),
         }
     }
 }
@@ -210,6 +225,13 @@ pub fn backend_spec(id: BackendId) -> BackendSpec {
                 "runtime/c/hamt.c",
             ],
         },
+        BackendId::Core => BackendSpec {
+            id,
+            display_label: "Core IR reference interpreter",
+            ir_surface: "core",
+            lowering_files: &["src/core/passes/"],
+            runtime_files: &["src/core/eval/"],
+        },
     }
 }
 
@@ -374,6 +396,13 @@ pub enum MismatchDetail {
         backend: BackendId,
         summary: String,
     },
+    /// The reference interpreter saw observable behavior change after a
+    /// named Core pass.
+    CorePassMismatch {
+        way: Way,
+        pass: String,
+        summary: String,
+    },
     CacheMismatch {
         fresh_way: Way,
         cached_way: Way,
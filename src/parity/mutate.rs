@@ -0,0 +1,633 @@
+//! CLI entry point for `flux mutate`.
+//!
+//! Usage:
+//!   flux mutate <file.flx> [options]
+//!
+//! Measures how much the `test_*` functions of a file check the rest of it. Every other
+//! function is mutated one small change at a time, each mutant is run against the tests
+//! with `flux --test`, and a mutant the tests still pass on has *survived*: the tests cannot
+//! tell the changed code from the original. Mutants run in parallel, one child process each,
+//! so a mutant that no longer terminates only costs its timeout.
+//!
+//! Mutations are text edits computed from the spans of the parsed program, like the
+//! [`super::shrink`] steps of `flux reduce`. They are not type-directed: a mutant the
+//! compiler rejects (a literal of the wrong type, an unbound variable after swapping option
+//! patterns, a match the pattern coverage checker reports as non-exhaustive after dropping
+//! an arm) is skipped and does not count against the score.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rayon::prelude::*;
+
+use crate::{
+    ast::visit::{self, Visitor},
+    diagnostics::position::Span,
+    fix::{TextEdit, apply_edits},
+    syntax::{
+        expression::{Expression, MatchArm, Pattern},
+        interner::Interner,
+        lexer::Lexer,
+        parser::Parser,
+        statement::Statement,
+    },
+};
+
+use super::ExitKind;
+use super::runner::{DEFAULT_TIMEOUT_SECS, run_exit_kind};
+use super::shrink::widen_deletion;
+
+/// Entry point called from `main.rs`.
+pub fn run_mutate(args: &[String]) {
+    let config = match parse_args(args) {
+        Ok(c) => c,
+        Err(msg) => {
+            eprintln!("Error: {msg}");
+            eprintln!();
+            print_usage();
+            std::process::exit(1);
+        }
+    };
+    let source = match fs::read_to_string(&config.path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("Error: cannot read {}: {err}", config.path.display());
+            std::process::exit(1);
+        }
+    };
+    if !source.contains("fn test_") {
+        eprintln!(
+            "Error: {} has no `test_*` functions to run mutants against.",
+            config.path.display()
+        );
+        std::process::exit(1);
+    }
+    let binary = match std::env::current_exe() {
+        Ok(binary) => binary,
+        Err(err) => {
+            eprintln!("Error: failed to locate the flux executable: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    match run_tests(&binary, &config.path, &config) {
+        ExitKind::Success => {}
+        other => {
+            eprintln!(
+                "Error: the tests of {} do not pass before mutation ({other}); fix them first.",
+                config.path.display()
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let mutants = mutants(&source);
+    println!(
+        "mutate: {}: {} mutants",
+        config.path.display(),
+        mutants.len()
+    );
+    let pool = match rayon::ThreadPoolBuilder::new()
+        .num_threads(config.jobs)
+        .build()
+    {
+        Ok(pool) => pool,
+        Err(err) => {
+            eprintln!("Error: failed to start the mutant workers: {err}");
+            std::process::exit(1);
+        }
+    };
+    let verdicts: Vec<Verdict> = pool.install(|| {
+        mutants
+            .par_iter()
+            .enumerate()
+            .map(|(index, mutant)| run_mutant(&binary, &config, &source, index, mutant))
+            .collect()
+    });
+
+    let report = Report::new(&verdicts);
+    for (mutant, verdict) in mutants.iter().zip(&verdicts) {
+        if *verdict == Verdict::Survived {
+            println!(
+                "  survived  {}:{}:{}  {}: {}",
+                config.path.display(),
+                mutant.span.start.line,
+                mutant.span.start.column + 1,
+                mutant.kind.label(),
+                mutant.description
+            );
+        }
+    }
+    if report.errors > 0 {
+        eprintln!(
+            "Error: {} mutants could not be written next to {} or run.",
+            report.errors,
+            config.path.display()
+        );
+        std::process::exit(1);
+    }
+    println!("{report}");
+    if report.survived > 0 {
+        std::process::exit(1);
+    }
+}
+
+// ── Mutations ──────────────────────────────────────────────────────────────
+
+/// What a mutation changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationKind {
+    /// `<` becomes `>=`, `==` becomes `!=`, and so on.
+    FlipComparison,
+    /// An integer or float gains one, a boolean is negated, a string is emptied or filled.
+    ReplaceConstant,
+    /// One arm of a match is removed.
+    DropArm,
+    /// `Some(x)` becomes `None`, or the `Some` and `None` patterns of a match trade arms.
+    SwapOption,
+    /// The guard of a match arm is negated.
+    NegateGuard,
+}
+
+impl MutationKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::FlipComparison => "flip comparison",
+            Self::ReplaceConstant => "replace constant",
+            Self::DropArm => "drop arm",
+            Self::SwapOption => "swap Some/None",
+            Self::NegateGuard => "negate guard",
+        }
+    }
+}
+
+/// One mutant: edits applied together to the original source.
+#[derive(Debug, Clone)]
+pub struct Mutant {
+    pub kind: MutationKind,
+    /// Where the mutation is, for reporting.
+    pub span: Span,
+    /// The change, e.g. ``` `<` -> `>=` ```.
+    pub description: String,
+    pub edits: Vec<TextEdit>,
+}
+
+impl Mutant {
+    pub fn apply(&self, source: &str) -> String {
+        apply_edits(source, self.edits.clone()).0
+    }
+}
+
+/// Every mutant of `source`, in source order. Functions named `test_*` or `bench_*` are
+/// left alone; they are what the mutants are checked with.
+pub fn mutants(source: &str) -> Vec<Mutant> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Vec::new();
+    }
+    let mut collector = Collector {
+        source,
+        interner: parser.interner(),
+        mutants: Vec::new(),
+    };
+    collector.visit_program(&program);
+    let mut mutants = collector.mutants;
+    mutants.sort_by_key(|mutant| (mutant.span.start.line, mutant.span.start.column));
+    mutants
+}
+
+struct Collector<'a> {
+    source: &'a str,
+    interner: &'a Interner,
+    mutants: Vec<Mutant>,
+}
+
+impl Collector<'_> {
+    fn text(&self, span: Span) -> Option<&str> {
+        let edit = TextEdit::from_span(self.source, span, String::new())?;
+        let text = &self.source[edit.start..edit.end];
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn push(&mut self, kind: MutationKind, span: Span, description: String, edits: Vec<TextEdit>) {
+        self.mutants.push(Mutant {
+            kind,
+            span,
+            description,
+            edits,
+        });
+    }
+
+    /// Replaces the text of `span` by `replacement`.
+    fn replace(&mut self, kind: MutationKind, span: Span, replacement: String) {
+        let Some(original) = self.text(span).map(str::to_string) else {
+            return;
+        };
+        let Some(edit) = TextEdit::from_span(self.source, span, replacement.clone()) else {
+            return;
+        };
+        let description = format!("`{}` -> `{replacement}`", snippet(&original));
+        self.push(kind, span, description, vec![edit]);
+    }
+
+    /// Replaces the operator of a comparison, found between the spans of its operands.
+    fn flip_comparison(&mut self, left: &Expression, operator: &str, right: &Expression) {
+        let Some(flipped) = flipped_comparison(operator) else {
+            return;
+        };
+        let gap = Span::new(left.span().end, right.span().start);
+        let Some(gap) = TextEdit::from_span(self.source, gap, String::new()) else {
+            return;
+        };
+        let Some(offset) = self.source[gap.start..gap.end].find(operator) else {
+            return;
+        };
+        let start = gap.start + offset;
+        let edit = TextEdit {
+            start,
+            end: start + operator.len(),
+            replacement: flipped.to_string(),
+        };
+        let span = Span::new(left.span().start, right.span().end);
+        let description = format!("`{operator}` -> `{flipped}`");
+        self.push(MutationKind::FlipComparison, span, description, vec![edit]);
+    }
+
+    fn replace_constant(&mut self, expr: &Expression) {
+        let replacement = match expr {
+            Expression::Integer { value, .. } => value.wrapping_add(1).to_string(),
+            Expression::Float { value, .. } => format!("{:?}", value + 1.0),
+            Expression::Boolean { value, .. } => (!value).to_string(),
+            Expression::String { value, .. } if value.is_empty() => "\"mutant\"".to_string(),
+            Expression::String { .. } => "\"\"".to_string(),
+            _ => return,
+        };
+        self.replace(MutationKind::ReplaceConstant, expr.span(), replacement);
+    }
+
+    fn mutate_arms(&mut self, arms: &[MatchArm]) {
+        if arms.len() > 1 {
+            for arm in arms {
+                if let Some(edit) = TextEdit::from_span(self.source, arm.span, String::new())
+                    && let Some(text) = self.text(arm.span)
+                {
+                    let description = format!("removed `{}`", snippet(text));
+                    let deletion = widen_deletion(self.source, edit.start, edit.end);
+                    self.push(MutationKind::DropArm, arm.span, description, vec![deletion]);
+                }
+            }
+        }
+
+        for arm in arms {
+            if let Some(guard) = &arm.guard
+                && let Some(text) = self.text(guard.span()).map(str::to_string)
+            {
+                self.replace(
+                    MutationKind::NegateGuard,
+                    guard.span(),
+                    format!("!({text})"),
+                );
+            }
+        }
+
+        let some = arms
+            .iter()
+            .find(|arm| matches!(arm.pattern, Pattern::Some { .. }));
+        let none = arms
+            .iter()
+            .find(|arm| matches!(arm.pattern, Pattern::None { .. }));
+        if let (Some(some), Some(none)) = (some, none)
+            && let (Some(some_text), Some(none_text)) = (
+                self.text(some.pattern.span()),
+                self.text(none.pattern.span()),
+            )
+            && let (Some(to_none), Some(to_some)) = (
+                TextEdit::from_span(self.source, some.pattern.span(), none_text.to_string()),
+                TextEdit::from_span(self.source, none.pattern.span(), some_text.to_string()),
+            )
+        {
+            let description = format!(
+                "swapped `{}` and `{none_text}` patterns",
+                snippet(some_text)
+            );
+            let span = earlier(some.span, none.span);
+            self.push(
+                MutationKind::SwapOption,
+                span,
+                description,
+                vec![to_none, to_some],
+            );
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for Collector<'_> {
+    fn visit_stmt(&mut self, stmt: &'ast Statement) {
+        if let Statement::Function { name, .. } = stmt {
+            let name = self.interner.resolve(*name);
+            if name.starts_with("test_") || name.starts_with("bench_") {
+                return;
+            }
+        }
+        visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        match expr {
+            Expression::Infix {
+                left,
+                operator,
+                right,
+                ..
+            } => self.flip_comparison(left, operator, right),
+            Expression::Some { span, .. } => {
+                self.replace(MutationKind::SwapOption, *span, "None".to_string())
+            }
+            Expression::Match { arms, .. } => self.mutate_arms(arms),
+            _ => self.replace_constant(expr),
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+fn flipped_comparison(operator: &str) -> Option<&'static str> {
+    Some(match operator {
+        "==" => "!=",
+        "!=" => "==",
+        "<" => ">=",
+        ">=" => "<",
+        ">" => "<=",
+        "<=" => ">",
+        _ => return None,
+    })
+}
+
+/// The first line of `text`, shortened for a one-line report.
+fn snippet(text: &str) -> String {
+    const MAX: usize = 40;
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > MAX || text.trim().contains('\n') {
+        let short: String = line.chars().take(MAX).collect();
+        format!("{short}...")
+    } else {
+        line.to_string()
+    }
+}
+
+/// Whichever of the two spans starts first.
+fn earlier(a: Span, b: Span) -> Span {
+    let key = |span: &Span| (span.start.line, span.start.column);
+    if key(&b) < key(&a) { b } else { a }
+}
+
+// ── Running mutants ────────────────────────────────────────────────────────
+
+/// How a mutant fared against the tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    /// A test failed or the run timed out.
+    Killed,
+    /// Every test passed.
+    Survived,
+    /// The mutant does not compile.
+    Skipped,
+    /// The mutant could not be written or run.
+    Error,
+}
+
+fn run_tests(binary: &Path, file: &Path, config: &Config) -> ExitKind {
+    let mut args = vec![
+        file.to_string_lossy().into_owned(),
+        "--test".to_string(),
+        "--no-cache".to_string(),
+    ];
+    args.extend(config.extra_args.iter().cloned());
+    run_exit_kind(binary, &args, config.timeout)
+}
+
+/// Writes mutant `index` next to the input, so sibling modules still resolve, and runs the
+/// tests on it.
+fn run_mutant(
+    binary: &Path,
+    config: &Config,
+    source: &str,
+    index: usize,
+    mutant: &Mutant,
+) -> Verdict {
+    let stem = config
+        .path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("program");
+    let file = config
+        .path
+        .with_file_name(format!("{stem}_mutant{index}.flx"));
+    if fs::write(&file, mutant.apply(source)).is_err() {
+        return Verdict::Error;
+    }
+    let exit = run_tests(binary, &file, config);
+    let _ = fs::remove_file(&file);
+    match exit {
+        ExitKind::Success => Verdict::Survived,
+        ExitKind::CompileError => Verdict::Skipped,
+        ExitKind::RuntimeError | ExitKind::Timeout => Verdict::Killed,
+        ExitKind::ToolFailure => Verdict::Error,
+    }
+}
+
+/// Counts of one run, printed as its last line.
+struct Report {
+    killed: usize,
+    survived: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+impl Report {
+    fn new(verdicts: &[Verdict]) -> Self {
+        let count = |wanted: Verdict| verdicts.iter().filter(|v| **v == wanted).count();
+        Self {
+            killed: count(Verdict::Killed),
+            survived: count(Verdict::Survived),
+            skipped: count(Verdict::Skipped),
+            errors: count(Verdict::Error),
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scored = self.killed + self.survived;
+        if scored == 0 {
+            write!(f, "mutation score: no mutants compiled")?;
+        } else {
+            write!(
+                f,
+                "mutation score: {}/{scored} killed ({:.1}%), {} survived",
+                self.killed,
+                self.killed as f64 * 100.0 / scored as f64,
+                self.survived
+            )?;
+        }
+        write!(f, ", {} skipped (do not compile)", self.skipped)
+    }
+}
+
+// ── Argument parsing ───────────────────────────────────────────────────────
+
+struct Config {
+    path: PathBuf,
+    /// `--root` arguments, forwarded to every run.
+    extra_args: Vec<String>,
+    jobs: usize,
+    timeout: Duration,
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut path: Option<PathBuf> = None;
+    let mut roots = Vec::new();
+    let mut jobs = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut timeout_secs = DEFAULT_TIMEOUT_SECS;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        let mut value = |name: &str| -> Result<String, String> {
+            i += 1;
+            args.get(i)
+                .cloned()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match arg {
+            "--root" => roots.push(value("--root")?),
+            "--jobs" => {
+                let raw = value("--jobs")?;
+                jobs = raw
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("invalid job count: {raw}"))?;
+            }
+            "--timeout" => {
+                let raw = value("--timeout")?;
+                timeout_secs = raw.parse().map_err(|_| format!("invalid timeout: {raw}"))?;
+            }
+            arg if !arg.starts_with('-') => {
+                if path.is_some() {
+                    return Err(format!("unexpected argument: {arg}"));
+                }
+                path = Some(PathBuf::from(arg));
+            }
+            other => return Err(format!("unknown option: {other}")),
+        }
+        i += 1;
+    }
+
+    let path = path.ok_or("missing file argument")?;
+    let extra_args = roots
+        .into_iter()
+        .flat_map(|root| ["--root".to_string(), root])
+        .collect();
+    Ok(Config {
+        path,
+        extra_args,
+        jobs,
+        timeout: Duration::from_secs(timeout_secs),
+    })
+}
+
+fn print_usage() {
+    eprintln!(
+        "\
+Usage:
+  flux mutate <file.flx> [options]
+
+Runs the `test_*` functions of <file.flx> against mutants of its other functions and
+reports the mutants no test fails on.
+
+Options:
+  --jobs <n>             Mutants run at once (default: available CPUs)
+  --root <path>          Module root (forwarded to flux, can repeat)
+  --timeout <secs>       Timeout per mutant; a mutant that times out is killed (default: {DEFAULT_TIMEOUT_SECS})"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MutationKind, mutants};
+
+    fn results(source: &str, kind: MutationKind) -> Vec<String> {
+        mutants(source)
+            .into_iter()
+            .filter(|mutant| mutant.kind == kind)
+            .map(|mutant| mutant.apply(source))
+            .collect()
+    }
+
+    #[test]
+    fn comparisons_are_flipped() {
+        let source = "fn small(x) { x < 10 }\n";
+
+        assert_eq!(
+            results(source, MutationKind::FlipComparison),
+            vec!["fn small(x) { x >= 10 }\n".to_string()]
+        );
+        assert_eq!(
+            results(source, MutationKind::ReplaceConstant),
+            vec!["fn small(x) { x < 11 }\n".to_string()]
+        );
+    }
+
+    #[test]
+    fn tests_are_not_mutated() {
+        let source = "fn f() { 1 }\n\nfn test_f() {\n    assert_eq(f(), 1)\n}\n";
+
+        let mutated: Vec<String> = mutants(source)
+            .into_iter()
+            .map(|mutant| mutant.apply(source))
+            .collect();
+        assert_eq!(
+            mutated,
+            vec!["fn f() { 2 }\n\nfn test_f() {\n    assert_eq(f(), 1)\n}\n".to_string()]
+        );
+    }
+
+    #[test]
+    fn match_arms_are_dropped_swapped_and_guards_negated() {
+        let source = "fn f(o) {\n    match o {\n        Some(x) if x > 0 -> x,\n        None -> 0,\n        _ -> 1,\n    }\n}\n";
+
+        assert!(
+            results(source, MutationKind::DropArm).contains(
+                &"fn f(o) {\n    match o {\n        Some(x) if x > 0 -> x,\n        _ -> 1,\n    }\n}\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            results(source, MutationKind::NegateGuard),
+            vec![
+                "fn f(o) {\n    match o {\n        Some(x) if !(x > 0) -> x,\n        None -> 0,\n        _ -> 1,\n    }\n}\n"
+                    .to_string()
+            ]
+        );
+        assert_eq!(
+            results(source, MutationKind::SwapOption),
+            vec![
+                "fn f(o) {\n    match o {\n        None if x > 0 -> x,\n        Some(x) -> 0,\n        _ -> 1,\n    }\n}\n"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn some_becomes_none() {
+        let source = "fn f(x) { Some(x) }\n";
+
+        assert_eq!(
+            results(source, MutationKind::SwapOption),
+            vec!["fn f(x) { None }\n".to_string()]
+        );
+    }
+}
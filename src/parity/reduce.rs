@@ -0,0 +1,491 @@
+//! CLI entry point for `flux reduce`.
+//!
+//! Usage:
+//!   flux reduce <file.flx> --predicate parity|panic|diag=<code> [options]
+//!
+//! Shrinks a failing program while it keeps failing the same way. Top-level items are
+//! removed first with delta debugging, then the [`super::shrink`] steps (statement and
+//! match-arm deletion, let inlining, replacing expressions by a subexpression or literal of
+//! the same type) are applied greedily until none applies. Every candidate is re-checked:
+//!
+//! - `parity` runs `vm` and `llvm` through [`super::runner::run_way`] and needs the same
+//!   divergence as the original;
+//! - `panic` runs the program with this binary and needs a panic at the same location;
+//! - `diag=<code>` runs `flux check` and needs an error with `<code>`, and no error (by code
+//!   and title) the original did not report.
+
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::{
+    fix::apply_edits,
+    lsp::analysis::{Analysis, AnalysisOptions},
+    syntax::{formatter::format_source, lexer::Lexer, parser::Parser},
+};
+
+use super::Way;
+use super::cli::{default_llvm_binary, default_vm_binary, require_parity_binaries};
+use super::divergence::{Divergence, Outcome, compare_vm_llvm};
+use super::minimize::ddmin;
+use super::runner::{DEFAULT_TIMEOUT_SECS, run_way};
+use super::shrink::{item_deletions, shrinks};
+
+/// Entry point called from `main.rs`.
+pub fn run_reduce(args: &[String]) {
+    let config = match parse_args(args) {
+        Ok(c) => c,
+        Err(msg) => {
+            eprintln!("Error: {msg}");
+            eprintln!();
+            print_usage();
+            std::process::exit(1);
+        }
+    };
+    if config.predicate == Predicate::Parity {
+        require_parity_binaries(&config.vm_binary, &config.llvm_binary);
+    }
+    let source = match fs::read_to_string(&config.path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("Error: cannot read {}: {err}", config.path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let mut reducer = Reducer {
+        config: &config,
+        original: None,
+        parses: parses(&source),
+        rejected: HashSet::new(),
+        checks: 0,
+    };
+    let Some(original) = reducer.observe(&source) else {
+        eprintln!(
+            "Error: {} does not fail the `{}` predicate; nothing to reduce.",
+            config.path.display(),
+            config.predicate
+        );
+        let _ = fs::remove_file(&config.out);
+        std::process::exit(1);
+    };
+    println!(
+        "reduce: {} fails `{}`: {}",
+        config.path.display(),
+        config.predicate,
+        original.describe()
+    );
+    reducer.original = Some(original);
+
+    let reduced = reducer.reduce(source.clone());
+    if let Err(err) = fs::write(&config.out, &reduced) {
+        eprintln!("Error: cannot write {}: {err}", config.out.display());
+        std::process::exit(1);
+    }
+    println!(
+        "reduce: {} -> {} bytes in {} checks, wrote {}",
+        source.len(),
+        reduced.len(),
+        reducer.checks,
+        config.out.display()
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    Parity,
+    Panic,
+    Diagnostic(String),
+}
+
+impl std::fmt::Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parity => write!(f, "parity"),
+            Self::Panic => write!(f, "panic"),
+            Self::Diagnostic(code) => write!(f, "diag={code}"),
+        }
+    }
+}
+
+impl Predicate {
+    fn parse(raw: &str) -> Result<Self, String> {
+        match raw {
+            "parity" => Ok(Self::Parity),
+            "panic" => Ok(Self::Panic),
+            _ => {
+                let code = raw
+                    .strip_prefix("diag=")
+                    .filter(|code| {
+                        code.len() > 1
+                            && code.starts_with('E')
+                            && code[1..].chars().all(|c| c.is_ascii_digit())
+                    })
+                    .ok_or_else(|| {
+                        format!("unknown predicate: {raw} (expected parity, panic or diag=E<code>)")
+                    })?;
+                Ok(Self::Diagnostic(code.to_string()))
+            }
+        }
+    }
+}
+
+/// How a program fails, as far as the predicate is concerned.
+#[derive(Debug, Clone)]
+enum Failure {
+    Parity(Divergence),
+    /// Location of the panic, e.g. `src/core/passes/inline.rs:120:9`.
+    Panic(String),
+    /// Every error reported as `<code> <title>`, one of them with the predicate's code.
+    Diagnostic(Vec<String>),
+}
+
+impl Failure {
+    fn describe(&self) -> String {
+        match self {
+            Self::Parity(divergence) => divergence.summary(),
+            Self::Panic(location) => format!("panicked at {location}"),
+            Self::Diagnostic(codes) => format!("reports {}", codes.join(", ")),
+        }
+    }
+
+    /// Whether `candidate` is the same failure as `self`.
+    fn reproduced_by(&self, candidate: &Failure) -> bool {
+        match (self, candidate) {
+            (Self::Parity(original), Self::Parity(candidate)) => original.same_shape(candidate),
+            (Self::Panic(original), Self::Panic(candidate)) => original == candidate,
+            (Self::Diagnostic(original), Self::Diagnostic(candidate)) => {
+                candidate.iter().all(|error| original.contains(error))
+            }
+            _ => false,
+        }
+    }
+}
+
+struct Reducer<'a> {
+    config: &'a Config,
+    original: Option<Failure>,
+    /// Whether the original parses; candidates that stop parsing are then skipped unchecked.
+    parses: bool,
+    /// Hashes of candidates that were checked and did not fail.
+    rejected: HashSet<u64>,
+    checks: usize,
+}
+
+impl Reducer<'_> {
+    fn reduce(&mut self, mut best: String) -> String {
+        // Top-level items first: delta debugging finds the items the failure needs in far
+        // fewer checks than deleting them one at a time.
+        let program = Parser::new(Lexer::new(&best)).parse_program();
+        let deletions = item_deletions(&best, &program);
+        if deletions.len() > 1 {
+            let indices: Vec<usize> = (0..deletions.len()).collect();
+            let source = best.clone();
+            let without = |kept: &[usize]| {
+                let removed = (0..deletions.len())
+                    .filter(|index| !kept.contains(index))
+                    .map(|index| deletions[index].clone())
+                    .collect();
+                apply_edits(&source, removed).0
+            };
+            let kept = ddmin(&indices, |kept| self.still_fails(&without(kept)));
+            if kept.len() < indices.len() {
+                let reduced = without(&kept);
+                self.report("delete items", &best, &reduced);
+                best = reduced;
+            }
+        }
+
+        // Then the finer steps, restarting from the largest after every success.
+        'progress: loop {
+            let analysis = quiet_analysis(&self.config.out, &best, &self.config.roots);
+            for shrink in shrinks(&best, &analysis) {
+                let candidate = shrink.apply(&best);
+                if candidate.len() < best.len() && self.still_fails(&candidate) {
+                    self.report(shrink.kind.label(), &best, &candidate);
+                    best = candidate;
+                    continue 'progress;
+                }
+            }
+            break;
+        }
+
+        let formatted = format_source(&best);
+        if formatted != best && self.still_fails(&formatted) {
+            self.report("format", &best, &formatted);
+            best = formatted;
+        }
+        best
+    }
+
+    fn report(&self, step: &str, before: &str, after: &str) {
+        println!("  {step:<17} {} -> {} bytes", before.len(), after.len());
+    }
+
+    fn still_fails(&mut self, candidate: &str) -> bool {
+        let key = hash(candidate);
+        if self.rejected.contains(&key) {
+            return false;
+        }
+        let fails = (!self.parses || parses(candidate))
+            && self
+                .observe(candidate)
+                .zip(self.original.as_ref())
+                .is_some_and(|(failure, original)| original.reproduced_by(&failure));
+        if !fails {
+            self.rejected.insert(key);
+        }
+        fails
+    }
+
+    /// Writes `source` to the output path and checks it against the predicate.
+    fn observe(&mut self, source: &str) -> Option<Failure> {
+        self.checks += 1;
+        let config = self.config;
+        fs::write(&config.out, source).ok()?;
+        match &config.predicate {
+            Predicate::Parity => match compare_vm_llvm(
+                &config.vm_binary,
+                &config.llvm_binary,
+                &config.out,
+                &config.extra_args,
+                config.timeout,
+            ) {
+                Outcome::Diverge(divergence) => Some(Failure::Parity(divergence)),
+                Outcome::Agree | Outcome::Skip(_) => None,
+            },
+            Predicate::Panic => {
+                let binary = std::env::current_exe().ok()?;
+                let result = run_way(
+                    &binary,
+                    &binary,
+                    &config.out,
+                    Way::Vm,
+                    &config.extra_args,
+                    config.timeout,
+                );
+                panic_location(&result.stderr).map(Failure::Panic)
+            }
+            Predicate::Diagnostic(code) => {
+                let output = Command::new(std::env::current_exe().ok()?)
+                    .arg("check")
+                    .arg(&config.out)
+                    .arg("--no-cache")
+                    .args(&config.extra_args)
+                    .env("NO_COLOR", "1")
+                    .output()
+                    .ok()?;
+                let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                let errors = error_headlines(&text);
+                errors
+                    .iter()
+                    .any(|error| error.split_once(' ').is_some_and(|(c, _)| c == code))
+                    .then_some(Failure::Diagnostic(errors))
+            }
+        }
+    }
+}
+
+/// Analyzes `source` for its inferred types. A compiler panic is the failure being reduced
+/// under `--predicate panic`, so panic output is silenced while analyzing.
+fn quiet_analysis(path: &Path, source: &str, roots: &[PathBuf]) -> Analysis {
+    let options = AnalysisOptions {
+        roots: roots.to_vec(),
+        ..AnalysisOptions::default()
+    };
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let analysis = Analysis::new(path, source, &options);
+    panic::set_hook(hook);
+    analysis
+}
+
+fn parses(source: &str) -> bool {
+    let mut parser = Parser::new(Lexer::new(source));
+    parser.parse_program();
+    parser.errors.is_empty()
+}
+
+fn hash(source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The `<code> <title>` of every error in rendered diagnostics, e.g. `E300 Type Mismatch`,
+/// sorted and deduplicated. The title tells apart different errors that share a code.
+fn error_headlines(text: &str) -> Vec<String> {
+    let mut errors: Vec<String> = text
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once("error[")?;
+            let (code, title) = rest.split_once("]:")?;
+            Some(format!("{code} {}", title.trim()))
+        })
+        .collect();
+    errors.sort();
+    errors.dedup();
+    errors
+}
+
+/// The location of the first panic in `stderr`, from a line like
+/// `thread 'main' panicked at src/x.rs:1:2:`.
+fn panic_location(stderr: &str) -> Option<String> {
+    stderr.lines().find_map(|line| {
+        let (_, location) = line.split_once("panicked at ")?;
+        Some(location.trim_end_matches(':').to_string())
+    })
+}
+
+// ── Argument parsing ───────────────────────────────────────────────────────
+
+struct Config {
+    path: PathBuf,
+    predicate: Predicate,
+    /// Where candidates are written while checking, and the result at the end.
+    out: PathBuf,
+    /// `--root` arguments, forwarded to every run.
+    roots: Vec<PathBuf>,
+    extra_args: Vec<String>,
+    vm_binary: PathBuf,
+    llvm_binary: PathBuf,
+    timeout: Duration,
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut path: Option<PathBuf> = None;
+    let mut predicate: Option<Predicate> = None;
+    let mut out: Option<PathBuf> = None;
+    let mut roots = Vec::new();
+    let mut vm_binary = default_vm_binary();
+    let mut llvm_binary = default_llvm_binary();
+    let mut timeout_secs = DEFAULT_TIMEOUT_SECS;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        let mut value = |name: &str| -> Result<String, String> {
+            i += 1;
+            args.get(i)
+                .cloned()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match arg {
+            "--predicate" => predicate = Some(Predicate::parse(&value("--predicate")?)?),
+            "--out" => out = Some(PathBuf::from(value("--out")?)),
+            "--root" => roots.push(PathBuf::from(value("--root")?)),
+            "--vm-binary" => vm_binary = PathBuf::from(value("--vm-binary")?),
+            "--llvm-binary" => llvm_binary = PathBuf::from(value("--llvm-binary")?),
+            "--timeout" => {
+                let raw = value("--timeout")?;
+                timeout_secs = raw.parse().map_err(|_| format!("invalid timeout: {raw}"))?;
+            }
+            arg if !arg.starts_with('-') => {
+                if path.is_some() {
+                    return Err(format!("unexpected argument: {arg}"));
+                }
+                path = Some(PathBuf::from(arg));
+            }
+            other => return Err(format!("unknown option: {other}")),
+        }
+        i += 1;
+    }
+
+    let path = path.ok_or("missing file argument")?;
+    let predicate = predicate.ok_or("missing --predicate")?;
+    // Candidates are checked next to the original, so sibling modules still resolve.
+    let out = out.unwrap_or_else(|| {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("program");
+        path.with_file_name(format!("{stem}_reduced.flx"))
+    });
+    if out == path {
+        return Err("--out must differ from the input file".to_string());
+    }
+    let extra_args = roots
+        .iter()
+        .flat_map(|root| ["--root".to_string(), root.to_string_lossy().into_owned()])
+        .collect();
+
+    Ok(Config {
+        path,
+        predicate,
+        out,
+        roots,
+        extra_args,
+        vm_binary,
+        llvm_binary,
+        timeout: Duration::from_secs(timeout_secs),
+    })
+}
+
+fn print_usage() {
+    eprintln!(
+        "\
+Usage:
+  flux reduce <file.flx> --predicate <predicate> [options]
+
+Predicates:
+  parity                 vm and llvm still diverge the same way
+  panic                  running the program still panics at the same location
+  diag=<code>            `flux check` still reports <code>, e.g. diag=E300
+
+Options:
+  --out <path>           Result file (default: <file>_reduced.flx next to the input)
+  --root <path>          Module root (forwarded to flux, can repeat)
+  --vm-binary <path>     Path to VM binary for parity (default: {})
+  --llvm-binary <path>   Path to native binary for parity (default: {})
+  --timeout <secs>       Timeout per run (default: {DEFAULT_TIMEOUT_SECS})",
+        default_vm_binary().display(),
+        default_llvm_binary().display()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Failure, Predicate, error_headlines, panic_location};
+
+    #[test]
+    fn parses_predicates() {
+        assert_eq!(Predicate::parse("parity"), Ok(Predicate::Parity));
+        assert_eq!(Predicate::parse("panic"), Ok(Predicate::Panic));
+        assert_eq!(
+            Predicate::parse("diag=E300"),
+            Ok(Predicate::Diagnostic("E300".to_string()))
+        );
+        assert!(Predicate::parse("diag=300").is_err());
+        assert!(Predicate::parse("crash").is_err());
+    }
+
+    #[test]
+    fn finds_the_panic_location() {
+        let stderr = "note: x\nthread 'main' panicked at src/core/lower.rs:12:5:\nboom\n";
+        assert_eq!(
+            panic_location(stderr).as_deref(),
+            Some("src/core/lower.rs:12:5")
+        );
+        assert_eq!(panic_location("error[E300]: Type Error"), None);
+    }
+
+    #[test]
+    fn diagnostic_failures_may_lose_errors_but_not_gain_them() {
+        let text = "error[E300]: Type Mismatch\n  |\nerror[E004]: Unknown Name\n";
+        let original = Failure::Diagnostic(error_headlines(text));
+
+        assert!(original.reproduced_by(&Failure::Diagnostic(error_headlines(
+            "error[E300]: Type Mismatch\n"
+        ))));
+        assert!(
+            !original.reproduced_by(&Failure::Diagnostic(error_headlines(
+                "error[E300]: Return Type Mismatch\n"
+            )))
+        );
+    }
+}
@@ -238,6 +238,9 @@ pub fn diagnose_mismatch(details: &[MismatchDetail]) -> Option<&'static str> {
     let has_repr = details
         .iter()
         .any(|d| matches!(d, MismatchDetail::RepresentationMismatch { .. }));
+    let has_core_pass = details
+        .iter()
+        .any(|d| matches!(d, MismatchDetail::CorePassMismatch { .. }));
     let has_backend_ir = details
         .iter()
         .any(|d| matches!(d, MismatchDetail::BackendIrMismatch { .. }));
@@ -282,6 +285,9 @@ pub fn diagnose_mismatch(details: &[MismatchDetail]) -> Option<&'static str> {
     if has_strict {
         return Some("strict-mode behavior diverged from non-strict behavior");
     }
+    if has_core_pass {
+        return Some("a Core pass changed observable behavior; see core_pass_mismatch");
+    }
     if has_core {
         return Some("likely frontend/Core lowering divergence before backend execution");
     }
@@ -328,6 +334,7 @@ pub fn cargo_run_for_way(way: Way, file: &str) -> String {
         Way::LlvmStrict => {
             format!("cargo run --features llvm -- {file} --native --strict --no-cache")
         }
+        Way::Core => format!("cargo run -- {file} --core-eval --no-cache"),
     }
 }
 
@@ -446,6 +453,13 @@ fn print_mismatch_detail(detail: &MismatchDetail) {
             );
             println!("    {summary}");
         }
+        MismatchDetail::CorePassMismatch { way, pass, summary } => {
+            println!(
+                "  {} {way} saw behavior change after Core pass `{pass}`",
+                cyan("core_pass_mismatch:")
+            );
+            println!("    {summary}");
+        }
         MismatchDetail::CacheMismatch {
             fresh_way,
             cached_way,
@@ -623,6 +637,9 @@ fn likely_rust_files(details: &[MismatchDetail], symbols: &[String]) -> Vec<Stri
             MismatchDetail::AetherMismatch { .. } => {
                 files.insert("src/aether/".to_string());
             }
+            MismatchDetail::CorePassMismatch { .. } => {
+                files.insert("src/core/passes/".to_string());
+            }
             MismatchDetail::RepresentationMismatch { .. } => {
                 files.insert("src/bytecode/vm/".to_string());
                 files.insert("src/lir/emit_llvm.rs".to_string());
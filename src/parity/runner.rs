@@ -205,6 +205,7 @@ fn build_way_args<'a>(
         Way::Llvm | Way::LlvmCached | Way::LlvmStrict => {
             (llvm_binary, vec!["--native".to_string()])
         }
+        Way::Core => (vm_binary, vec!["--core-eval".to_string()]),
     };
     if way.is_strict() {
         args.push("--strict".to_string());
@@ -348,6 +349,7 @@ fn parity_cache_dir(file: &Path, way: Way) -> PathBuf {
     let backend = match way {
         Way::Vm | Way::VmCached | Way::VmStrict => "vm",
         Way::Llvm | Way::LlvmCached | Way::LlvmStrict => "llvm",
+        Way::Core => "core",
     };
     project_root
         .join("target")
@@ -433,6 +435,34 @@ pub fn is_native_skip(result: &RunResult) -> Option<String> {
     None
 }
 
+/// Check if the reference Core interpreter could not model the program.
+///
+/// Unlike a native skip this only drops the `core` way: the remaining ways
+/// are still compared.
+pub fn is_core_eval_skip(result: &RunResult) -> Option<String> {
+    if result.way != Way::Core {
+        return None;
+    }
+    result
+        .stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("core-eval: unsupported: "))
+        .map(str::to_string)
+}
+
+/// Extract the pass after which `--core-eval` saw behavior change, with the
+/// rest of its report.
+pub fn core_pass_change(result: &RunResult) -> Option<(String, String)> {
+    if result.way != Way::Core {
+        return None;
+    }
+    result.stderr.lines().find_map(|line| {
+        let rest = line.strip_prefix("core-eval: behavior changed after `")?;
+        let (pass, summary) = rest.split_once("`: ")?;
+        Some((pass.to_string(), summary.to_string()))
+    })
+}
+
 /// Capture `--dump-core` output for a fixture under a given way.
 ///
 /// Returns `DebugArtifacts` with the Core IR dump. Both VM and LLVM binaries
@@ -447,7 +477,7 @@ pub fn capture_dump_core(
     timeout: Duration,
 ) -> DebugArtifacts {
     let binary = match way {
-        Way::Vm | Way::VmCached | Way::VmStrict => vm_binary,
+        Way::Vm | Way::VmCached | Way::VmStrict | Way::Core => vm_binary,
         Way::Llvm | Way::LlvmCached | Way::LlvmStrict => llvm_binary,
     };
 
@@ -489,7 +519,7 @@ pub fn capture_dump_aether(
     timeout: Duration,
 ) -> DebugArtifacts {
     let binary = match way {
-        Way::Vm | Way::VmCached | Way::VmStrict => vm_binary,
+        Way::Vm | Way::VmCached | Way::VmStrict | Way::Core => vm_binary,
         Way::Llvm | Way::LlvmCached | Way::LlvmStrict => llvm_binary,
     };
 
@@ -532,7 +562,7 @@ pub fn capture_dump_repr(
     timeout: Duration,
 ) -> DebugArtifacts {
     let binary = match way {
-        Way::Vm | Way::VmCached | Way::VmStrict => vm_binary,
+        Way::Vm | Way::VmCached | Way::VmStrict | Way::Core => vm_binary,
         Way::Llvm | Way::LlvmCached | Way::LlvmStrict => llvm_binary,
     };
 
@@ -634,6 +664,18 @@ pub fn capture_dump_lir(
     }
 }
 
+/// Run `binary` with `args` and classify how it exited, for tools that only need the
+/// exit kind of a run rather than a way's full result.
+pub fn run_exit_kind(binary: &Path, args: &[String], timeout: Duration) -> ExitKind {
+    match spawn_with_timeout(binary, args, timeout) {
+        SpawnResult::Completed {
+            exit_code, stderr, ..
+        } => classify_exit(exit_code, &stderr),
+        SpawnResult::Timeout => ExitKind::Timeout,
+        SpawnResult::SpawnError(_) => ExitKind::ToolFailure,
+    }
+}
+
 // ── Subprocess management ──────────────────────────────────────────────────
 
 enum SpawnResult {
@@ -0,0 +1,409 @@
+//! AST-aware shrinking steps for `flux reduce`.
+//!
+//! Every step is a set of text edits computed from the spans of the parsed program, so the
+//! rest of the source, comments included, is kept verbatim. Steps that would replace an
+//! expression use the inferred types of the document analysis, so they only swap in a
+//! subexpression or literal of the same type and the program stays type-correct.
+
+use crate::{
+    ast::visit::{self, Visitor},
+    diagnostics::position::Span,
+    fix::{TextEdit, apply_edits},
+    lsp::analysis::Analysis,
+    syntax::{
+        Identifier, block::Block, expression::Expression, program::Program, statement::Statement,
+    },
+};
+
+/// What a shrinking step does, in the order steps are tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShrinkKind {
+    DeleteItem,
+    DeleteStatement,
+    RemoveArm,
+    InlineLet,
+    Hoist,
+    Literal,
+}
+
+impl ShrinkKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DeleteItem => "delete item",
+            Self::DeleteStatement => "delete statement",
+            Self::RemoveArm => "remove arm",
+            Self::InlineLet => "inline let",
+            Self::Hoist => "hoist",
+            Self::Literal => "literal",
+        }
+    }
+}
+
+/// One candidate step: edits that are applied together.
+#[derive(Debug, Clone)]
+pub struct Shrink {
+    pub kind: ShrinkKind,
+    pub edits: Vec<TextEdit>,
+}
+
+impl Shrink {
+    pub fn apply(&self, source: &str) -> String {
+        apply_edits(source, self.edits.clone()).0
+    }
+
+    /// Bytes the step removes, used to try the largest steps first.
+    fn removed(&self) -> isize {
+        self.edits
+            .iter()
+            .map(|edit| (edit.end - edit.start) as isize - edit.replacement.len() as isize)
+            .sum()
+    }
+}
+
+/// Deletions of the top-level items of `source`, one per item, in source order.
+pub fn item_deletions(source: &str, program: &Program) -> Vec<TextEdit> {
+    program
+        .statements
+        .iter()
+        .filter_map(|statement| statement_deletion(source, statement))
+        .collect()
+}
+
+/// Every shrinking step of `source`, largest first within each [`ShrinkKind`].
+pub fn shrinks(source: &str, analysis: &Analysis) -> Vec<Shrink> {
+    let Some(program) = analysis.program() else {
+        return Vec::new();
+    };
+    let mut collector = Collector {
+        source,
+        analysis,
+        shrinks: Vec::new(),
+    };
+    collector.visit_program(program);
+    let mut shrinks = collector.shrinks;
+    shrinks.retain(|shrink| shrink.removed() > 0);
+    shrinks.sort_by_key(|shrink| (shrink.kind, -shrink.removed()));
+    shrinks
+}
+
+struct Collector<'a> {
+    source: &'a str,
+    analysis: &'a Analysis,
+    shrinks: Vec<Shrink>,
+}
+
+impl Collector<'_> {
+    fn push(&mut self, kind: ShrinkKind, edits: Vec<TextEdit>) {
+        self.shrinks.push(Shrink { kind, edits });
+    }
+
+    fn text(&self, expr: &Expression) -> Option<&str> {
+        let edit = TextEdit::from_span(self.source, expr.span(), String::new())?;
+        let text = &self.source[edit.start..edit.end];
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn replace(&self, expr: &Expression, replacement: String) -> Option<TextEdit> {
+        TextEdit::from_span(self.source, expr.span(), replacement)
+            .filter(|edit| edit.start < edit.end)
+    }
+
+    /// `let x = value` followed by a single use of `x` in the rest of the block: the use is
+    /// replaced by the value and the binding deleted. Values that are a bare name or literal
+    /// are inlined into any number of uses.
+    fn inline_let(&mut self, statement: &Statement, rest: &[Statement]) {
+        let Statement::Let { name, value, .. } = statement else {
+            return;
+        };
+        let Some(value_text) = self.text(value) else {
+            return;
+        };
+        let mut uses = IdentifierUses {
+            name: *name,
+            uses: Vec::new(),
+        };
+        for later in rest {
+            uses.visit_stmt(later);
+        }
+        if uses.uses.is_empty() || (uses.uses.len() > 1 && !is_atomic(value)) {
+            return;
+        }
+        let replacement = parenthesized(value, value_text);
+        let Some(mut edits) = uses
+            .uses
+            .iter()
+            .map(|used| self.replace(used, replacement.clone()))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        let Some(deletion) = statement_deletion(self.source, statement) else {
+            return;
+        };
+        edits.push(deletion);
+        self.push(ShrinkKind::InlineLet, edits);
+    }
+
+    /// Replaces `expr` by one of its direct subexpressions of the same type, or by a literal
+    /// of its type.
+    fn simplify(&mut self, expr: &Expression) {
+        let ty = self.analysis.expr_type(expr);
+        let mut children = Children(Vec::new());
+        visit::walk_expr(&mut children, expr);
+        for child in children.0 {
+            let same_type = match (&ty, self.analysis.expr_type(child)) {
+                (Some(ty), Some(child_ty)) => *ty == child_ty,
+                _ => true,
+            };
+            if !same_type {
+                continue;
+            }
+            if let Some(text) = self.text(child)
+                && let Some(edit) = self.replace(expr, parenthesized(child, text))
+            {
+                self.push(ShrinkKind::Hoist, vec![edit]);
+            }
+        }
+
+        if is_literal(expr) {
+            return;
+        }
+        if let Some(literal) = ty.as_deref().and_then(literal_of)
+            && let Some(edit) = self.replace(expr, literal.to_string())
+        {
+            self.push(ShrinkKind::Literal, vec![edit]);
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for Collector<'_> {
+    fn visit_program(&mut self, program: &'ast Program) {
+        for (index, statement) in program.statements.iter().enumerate() {
+            if let Some(deletion) = statement_deletion(self.source, statement) {
+                self.push(ShrinkKind::DeleteItem, vec![deletion]);
+            }
+            self.inline_let(statement, &program.statements[index + 1..]);
+        }
+        visit::walk_program(self, program);
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        for (index, statement) in block.statements.iter().enumerate() {
+            if let Some(deletion) = statement_deletion(self.source, statement) {
+                self.push(ShrinkKind::DeleteStatement, vec![deletion]);
+            }
+            self.inline_let(statement, &block.statements[index + 1..]);
+        }
+        visit::walk_block(self, block);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        if let Expression::Match { arms, .. } = expr
+            && arms.len() > 1
+        {
+            for arm in arms {
+                if let Some(edit) = TextEdit::from_span(self.source, arm.span, String::new()) {
+                    let deletion = widen_deletion(self.source, edit.start, edit.end);
+                    self.push(ShrinkKind::RemoveArm, vec![deletion]);
+                }
+            }
+        }
+        self.simplify(expr);
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Collects the direct subexpressions of an expression, including the expressions of the
+/// statements of its blocks.
+struct Children<'ast>(Vec<&'ast Expression>);
+
+impl<'ast> Visitor<'ast> for Children<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        self.0.push(expr);
+    }
+}
+
+/// Collects the identifier expressions that name `name`.
+struct IdentifierUses<'ast> {
+    name: Identifier,
+    uses: Vec<&'ast Expression>,
+}
+
+impl<'ast> Visitor<'ast> for IdentifierUses<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        if let Expression::Identifier { name, .. } = expr
+            && *name == self.name
+        {
+            self.uses.push(expr);
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Deletes `statement` with the `;` or `,` after it, and its whole line when nothing else
+/// is on it. Statements without a source span (injected imports) cannot be deleted.
+fn statement_deletion(source: &str, statement: &Statement) -> Option<TextEdit> {
+    let edit = TextEdit::from_span(source, statement_extent(statement), String::new())?;
+    (edit.start < edit.end).then(|| widen_deletion(source, edit.start, edit.end))
+}
+
+/// The source extent of `statement`. Function and module spans stop at the signature, so
+/// their bodies are added.
+fn statement_extent(statement: &Statement) -> Span {
+    match statement {
+        Statement::Function { span, body, .. } | Statement::Module { span, body, .. } => {
+            Span::new(span.start, body.span.end)
+        }
+        other => other.span(),
+    }
+}
+
+pub(super) fn widen_deletion(source: &str, mut start: usize, mut end: usize) -> TextEdit {
+    let rest = &source[end..];
+    let trimmed = rest.trim_start_matches([' ', '\t']);
+    if trimmed.starts_with([';', ',']) {
+        let after = &trimmed[1..];
+        end += rest.len() - after.trim_start_matches([' ', '\t']).len();
+    }
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |i| end + i + 1);
+    if source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty() {
+        start = line_start;
+        end = line_end;
+    }
+    TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+    }
+}
+
+/// `text` of `expr`, parenthesized when moving it could change how it parses.
+fn parenthesized(expr: &Expression, text: &str) -> String {
+    let needs_parens = matches!(
+        expr,
+        Expression::Infix { .. }
+            | Expression::Prefix { .. }
+            | Expression::If { .. }
+            | Expression::Match { .. }
+            | Expression::Function { .. }
+            | Expression::Handle { .. }
+            | Expression::Sealing { .. }
+            | Expression::Cons { .. }
+    );
+    if needs_parens && !text.starts_with('(') {
+        format!("({text})")
+    } else {
+        text.to_string()
+    }
+}
+
+fn is_literal(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Integer { .. }
+            | Expression::Float { .. }
+            | Expression::String { .. }
+            | Expression::Boolean { .. }
+            | Expression::None { .. }
+            | Expression::EmptyList { .. }
+    )
+}
+
+fn is_atomic(expr: &Expression) -> bool {
+    is_literal(expr) || matches!(expr, Expression::Identifier { .. })
+}
+
+/// The smallest literal of a rendered type, when the type has one.
+fn literal_of(ty: &str) -> Option<&'static str> {
+    match ty {
+        "Int" => Some("0"),
+        "Float" => Some("0.0"),
+        "Bool" => Some("false"),
+        "String" => Some("\"\""),
+        _ if ty.starts_with("Option<") => Some("None"),
+        _ if ty.starts_with("List<") => Some("[]"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{ShrinkKind, item_deletions, shrinks};
+    use crate::{
+        fix::apply_edits,
+        lsp::analysis::{Analysis, AnalysisOptions},
+        syntax::{lexer::Lexer, parser::Parser},
+    };
+
+    fn analyze(source: &str) -> Analysis {
+        let dir = std::env::temp_dir().join(format!("flux_shrink_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path: PathBuf = dir.join("main.flx");
+        std::fs::write(&path, source).unwrap();
+        Analysis::new(&path, source, &AnalysisOptions::default())
+    }
+
+    fn results(source: &str, kind: ShrinkKind) -> Vec<String> {
+        shrinks(source, &analyze(source))
+            .into_iter()
+            .filter(|shrink| shrink.kind == kind)
+            .map(|shrink| shrink.apply(source))
+            .collect()
+    }
+
+    #[test]
+    fn item_deletions_remove_whole_lines() {
+        let source = "fn f() { 1 }\n\nfn main() with IO {\n    print(f())\n}\n";
+        let program = Parser::new(Lexer::new(source)).parse_program();
+        let deletions = item_deletions(source, &program);
+
+        assert_eq!(deletions.len(), 2);
+        let (without_f, _) = apply_edits(source, vec![deletions[0].clone()]);
+        assert_eq!(without_f, "\nfn main() with IO {\n    print(f())\n}\n");
+    }
+
+    #[test]
+    fn statements_and_match_arms_are_deleted() {
+        let source =
+            "fn main() with IO {\n    print(1)\n    print(match 2 { 0 -> 3, _ -> 4 })\n}\n";
+
+        assert!(results(source, ShrinkKind::DeleteStatement).contains(
+            &"fn main() with IO {\n    print(match 2 { 0 -> 3, _ -> 4 })\n}\n".to_string()
+        ));
+        assert!(results(source, ShrinkKind::RemoveArm).contains(
+            &"fn main() with IO {\n    print(1)\n    print(match 2 { _ -> 4 })\n}\n".to_string()
+        ));
+    }
+
+    #[test]
+    fn single_use_lets_are_inlined() {
+        let source = "fn main() with IO {\n    let x = 1 + 2\n    print(x * 3)\n}\n";
+
+        assert_eq!(
+            results(source, ShrinkKind::InlineLet),
+            vec!["fn main() with IO {\n    print((1 + 2) * 3)\n}\n".to_string()]
+        );
+    }
+
+    #[test]
+    fn replacements_keep_the_expression_type() {
+        let source = "fn main() with IO {\n    print(len(\"abc\") + 40)\n}\n";
+
+        let literals = results(source, ShrinkKind::Literal);
+        assert!(literals.contains(&"fn main() with IO {\n    print(0 + 40)\n}\n".to_string()));
+        let hoists = results(source, ShrinkKind::Hoist);
+        assert!(hoists.contains(&"fn main() with IO {\n    print(len(\"abc\"))\n}\n".to_string()));
+        // `"abc"` is a String, so it never replaces the Int call around it.
+        assert!(
+            !hoists
+                .iter()
+                .any(|hoisted| hoisted.contains("print((\"abc\") + 40)")
+                    || hoisted.contains("print(\"abc\" + 40)"))
+        );
+    }
+}
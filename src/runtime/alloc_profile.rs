@@ -0,0 +1,133 @@
+//! Heap allocation counters for the cost-centre profiler (`--prof`).
+//!
+//! Allocation sites call [`record`], which is a single thread-local read unless profiling
+//! switched counting on with [`set_enabled`]. The VM reads [`snapshot`] whenever the
+//! cost centre on top of its stack changes and charges the difference to the centre
+//! that was running.
+//!
+//! Counters are per thread, like the VM values they count, so VMs running on other threads
+//! (`--test-jobs` workers) do not leak allocations into each other's profiles. Enabling
+//! counting starts from zero, so every profiled run gets its own totals.
+
+use std::cell::Cell;
+use std::ops::{AddAssign, Sub};
+
+/// The kinds of heap object the profiler attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocKind {
+    Adt,
+    Closure,
+    Array,
+    Cons,
+    HamtNode,
+}
+
+impl AllocKind {
+    pub const ALL: [AllocKind; 5] = [
+        AllocKind::Adt,
+        AllocKind::Closure,
+        AllocKind::Array,
+        AllocKind::Cons,
+        AllocKind::HamtNode,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AllocKind::Adt => "adt",
+            AllocKind::Closure => "closure",
+            AllocKind::Array => "array",
+            AllocKind::Cons => "cons",
+            AllocKind::HamtNode => "hamt",
+        }
+    }
+}
+
+/// Allocation counts by kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocCounts(pub [u64; 5]);
+
+impl AllocCounts {
+    pub fn get(&self, kind: AllocKind) -> u64 {
+        self.0[kind as usize]
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+}
+
+impl AddAssign for AllocCounts {
+    fn add_assign(&mut self, other: Self) {
+        for (count, more) in self.0.iter_mut().zip(other.0) {
+            *count += more;
+        }
+    }
+}
+
+impl Sub for AllocCounts {
+    type Output = Self;
+
+    fn sub(self, earlier: Self) -> Self {
+        let mut counts = self;
+        for (count, before) in counts.0.iter_mut().zip(earlier.0) {
+            *count = count.saturating_sub(before);
+        }
+        counts
+    }
+}
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static COUNTS: [Cell<u64>; 5] = const { [const { Cell::new(0) }; 5] };
+}
+
+/// Switches counting on or off for the current thread. Switching it on clears the counters.
+pub fn set_enabled(enabled: bool) {
+    if enabled {
+        COUNTS.with(|counts| counts.iter().for_each(|count| count.set(0)));
+    }
+    ENABLED.with(|flag| flag.set(enabled));
+}
+
+#[inline]
+pub fn record(kind: AllocKind) {
+    if ENABLED.with(Cell::get) {
+        COUNTS.with(|counts| {
+            let count = &counts[kind as usize];
+            count.set(count.get() + 1);
+        });
+    }
+}
+
+pub fn snapshot() -> AllocCounts {
+    COUNTS.with(|counts| AllocCounts(std::array::from_fn(|i| counts[i].get())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllocKind, record, set_enabled, snapshot};
+
+    #[test]
+    fn counts_are_per_thread_and_restart_when_enabled() {
+        set_enabled(true);
+        record(AllocKind::Cons);
+        record(AllocKind::Cons);
+
+        std::thread::spawn(|| {
+            set_enabled(true);
+            record(AllocKind::Closure);
+            assert_eq!(snapshot().total(), 1);
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(snapshot().get(AllocKind::Cons), 2);
+        assert_eq!(snapshot().get(AllocKind::Closure), 0);
+
+        set_enabled(true);
+        assert_eq!(snapshot().total(), 0);
+        set_enabled(false);
+        record(AllocKind::Adt);
+        assert_eq!(snapshot().total(), 0);
+    }
+}
@@ -0,0 +1,214 @@
+//! Function selection and event rendering for the call tracer (`--trace-calls`).
+//!
+//! A [`CallTraceFilter`] picks the functions to trace by name glob, where `*` matches any
+//! run of characters and `?` a single one. The VM matches module-qualified names such as
+//! `Flow.List.map`; functions of the main module are matched by their bare name.
+//!
+//! [`TraceWriter`] renders [`TraceEvent`]s as text indented by call depth, or as JSON lines.
+//! The native runtime (`runtime/c/trace.c`) writes the same two formats.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// The functions selected by `--trace-calls`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallTraceFilter {
+    patterns: Vec<String>,
+}
+
+impl CallTraceFilter {
+    /// Builds a filter from `--trace-calls` values, each holding one or more
+    /// comma-separated patterns.
+    pub fn new(values: &[String]) -> Self {
+        let patterns = values
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), name.as_bytes()))
+    }
+
+    /// Matches a native symbol name such as `Flow_List_map`, in which module separators
+    /// have become underscores.
+    pub fn matches_symbol(&self, name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(pattern.replace('.', "_").as_bytes(), name.as_bytes()))
+    }
+}
+
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// One line of a call trace. Values are already rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent<'a> {
+    /// A traced function was entered.
+    Call {
+        function: &'a str,
+        args: &'a [String],
+    },
+    /// A traced function returned.
+    Return { function: &'a str, value: &'a str },
+    /// A traced function left through a tail call.
+    TailCall { function: &'a str },
+    /// An effect operation was performed inside a traced call.
+    Perform {
+        operation: &'a str,
+        args: &'a [String],
+    },
+    /// A handler resumed the continuation of an operation.
+    Resume { value: &'a str },
+}
+
+/// Renders an event as a text line, indented two spaces per level of `depth`.
+pub fn render_text(depth: usize, event: &TraceEvent<'_>) -> String {
+    let indent = "  ".repeat(depth);
+    match event {
+        TraceEvent::Call { function, args } => format!("{indent}→ {function}({})", args.join(", ")),
+        TraceEvent::Return { function, value } => format!("{indent}← {function} = {value}"),
+        TraceEvent::TailCall { function } => format!("{indent}← {function} (tail call)"),
+        TraceEvent::Perform { operation, args } => {
+            format!("{indent}perform {operation}({})", args.join(", "))
+        }
+        TraceEvent::Resume { value } => format!("{indent}resume {value}"),
+    }
+}
+
+/// Renders an event as one JSON object, with `event` and `depth` first.
+pub fn render_json(depth: usize, event: &TraceEvent<'_>) -> String {
+    let quote = |text: &str| serde_json::Value::from(text).to_string();
+    let list = |items: &[String]| serde_json::Value::from(items).to_string();
+    match event {
+        TraceEvent::Call { function, args } => format!(
+            r#"{{"event":"call","depth":{depth},"function":{},"args":{}}}"#,
+            quote(function),
+            list(args)
+        ),
+        TraceEvent::Return { function, value } => format!(
+            r#"{{"event":"return","depth":{depth},"function":{},"value":{}}}"#,
+            quote(function),
+            quote(value)
+        ),
+        TraceEvent::TailCall { function } => format!(
+            r#"{{"event":"tail_call","depth":{depth},"function":{}}}"#,
+            quote(function)
+        ),
+        TraceEvent::Perform { operation, args } => format!(
+            r#"{{"event":"perform","depth":{depth},"operation":{},"args":{}}}"#,
+            quote(operation),
+            list(args)
+        ),
+        TraceEvent::Resume { value } => format!(
+            r#"{{"event":"resume","depth":{depth},"value":{}}}"#,
+            quote(value)
+        ),
+    }
+}
+
+/// Where trace events go: text on stderr, or JSON lines in a file.
+pub struct TraceWriter {
+    out: Box<dyn Write>,
+    json: bool,
+}
+
+impl TraceWriter {
+    pub fn stderr() -> Self {
+        Self {
+            out: Box::new(io::stderr()),
+            json: false,
+        }
+    }
+
+    pub fn json_file(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            out: Box::new(BufWriter::new(File::create(path)?)),
+            json: true,
+        })
+    }
+
+    pub fn write(&mut self, depth: usize, event: &TraceEvent<'_>) {
+        let line = if self.json {
+            render_json(depth, event)
+        } else {
+            render_text(depth, event)
+        };
+        let _ = writeln!(self.out, "{line}");
+    }
+
+    pub fn flush(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_matches_globs_and_comma_separated_patterns() {
+        let filter = CallTraceFilter::new(&["build,Flow.List.*".to_string(), "t?tal".to_string()]);
+        assert!(filter.matches("build"));
+        assert!(filter.matches("Flow.List.map"));
+        assert!(filter.matches("total"));
+        assert!(!filter.matches("builder"));
+        assert!(!filter.matches("Flow.Array.map"));
+        assert!(filter.matches_symbol("Flow_List_map"));
+        assert!(CallTraceFilter::new(&["*".to_string()]).matches("anything"));
+    }
+
+    #[test]
+    fn events_render_as_text_and_json() {
+        let args = vec!["3".to_string(), "\"a\"".to_string()];
+        let call = TraceEvent::Call {
+            function: "build",
+            args: &args,
+        };
+        assert_eq!(render_text(1, &call), "  → build(3, \"a\")");
+        assert_eq!(
+            render_json(1, &call),
+            r#"{"event":"call","depth":1,"function":"build","args":["3","\"a\""]}"#
+        );
+        let ret = TraceEvent::Return {
+            function: "build",
+            value: "[1]",
+        };
+        assert_eq!(render_text(0, &ret), "← build = [1]");
+        assert_eq!(
+            render_text(2, &TraceEvent::Resume { value: "5" }),
+            "    resume 5"
+        );
+    }
+}
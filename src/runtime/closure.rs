@@ -1,6 +1,11 @@
 use std::rc::Rc;
 
-use crate::runtime::{compiled_function::CompiledFunction, leak_detector, value::Value};
+use crate::runtime::{
+    alloc_profile::{self, AllocKind},
+    compiled_function::CompiledFunction,
+    leak_detector,
+    value::Value,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Closure {
@@ -11,6 +16,7 @@ pub struct Closure {
 impl Closure {
     pub fn new(function: Rc<CompiledFunction>, free: Vec<Value>) -> Self {
         leak_detector::record_closure();
+        alloc_profile::record(AllocKind::Closure);
         Self { function, free }
     }
 }
@@ -1,6 +1,9 @@
 use std::rc::Rc;
 
-use crate::runtime::value::Value;
+use crate::runtime::{
+    alloc_profile::{self, AllocKind},
+    value::Value,
+};
 
 /// Rc-based cons cell for persistent linked lists.
 ///
@@ -19,6 +22,7 @@ impl ConsCell {
 
     /// Construct a `Value::Cons` from head and tail.
     pub fn cons(head: Value, tail: Value) -> Value {
+        alloc_profile::record(AllocKind::Cons);
         Value::Cons(Rc::new(Self { head, tail }))
     }
 }
@@ -45,7 +45,10 @@ impl Continuation {
     ///
     /// `pieces` are expected innermost-first, matching `YieldState.conts`.
     /// The composed result stores frames and stack outermost-first so
-    /// `execute_resume` can restore them in one shot.
+    /// `execute_resume` can restore them in one shot. Each piece keeps its
+    /// absolute position: the callee slot between a caller's piece and its
+    /// callee's frame is padded, since the restored frames keep their base
+    /// pointers.
     pub fn compose(
         pieces: &[Value],
         inner_handlers: Vec<HandlerFrame>,
@@ -73,6 +76,11 @@ impl Continuation {
             if outermost.is_none() {
                 outermost = Some(cont.clone());
             }
+            let outermost_entry_sp = outermost.as_ref().map_or(cont.entry_sp, |c| c.entry_sp);
+            let offset = cont.entry_sp.saturating_sub(outermost_entry_sp);
+            if composed_stack.len() < offset {
+                composed_stack.resize(offset, Value::None);
+            }
             innermost_sp = cont.sp;
             composed_frames.extend(cont.frames.clone());
             composed_stack.extend(cont.stack.clone());
@@ -150,6 +158,8 @@ mod tests {
         assert_eq!(cont.entry_frame_index, 0);
         assert_eq!(cont.sp, 22);
         assert_eq!(cont.frames.len(), 2);
-        assert_eq!(cont.stack.len(), 3);
+        // The inner piece stays at its absolute slots 20..22.
+        assert_eq!(cont.stack.len(), 12);
+        assert_eq!(cont.stack[10], Value::Integer(1));
     }
 }
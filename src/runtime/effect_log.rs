@@ -0,0 +1,294 @@
+//! Record and replay of builtin effects (`--record`, `--replay`).
+//!
+//! The builtin operations that read the outside world (`read_file`, `read_lines`,
+//! `read_stdin` and `clock_now`) go through [`EffectLog::perform`] when a log is attached.
+//! Recording appends every call to the log with its arguments and its result or error.
+//! Replaying answers every call from the log instead, and fails on the first call that differs
+//! from it: another operation, other arguments, or a call past the end of the log.
+//! [`EffectLog::finish`] reports logged calls the run never made.
+//!
+//! The log is a JSON lines file: a `{"flux_effect_log":1}` header, then one object per call.
+//! The native runtime (`runtime/c/effect_log.c`) reads and writes the same format, so a log
+//! recorded on one backend replays on the other.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+
+use crate::runtime::value::Value;
+
+/// Version written in the log header and required on replay.
+pub const EFFECT_LOG_VERSION: u64 = 1;
+
+/// One logged call, with either its result or its error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoggedEffect {
+    op: String,
+    args: Vec<Json>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Json>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LogHeader {
+    flux_effect_log: u64,
+}
+
+enum Mode {
+    Record(BufWriter<File>),
+    Replay {
+        effects: Vec<LoggedEffect>,
+        next: usize,
+    },
+}
+
+pub struct EffectLog {
+    mode: Mode,
+    /// The first divergence found on replay, kept so it is reported even when the program
+    /// caught the error.
+    divergence: Option<String>,
+}
+
+impl EffectLog {
+    /// Starts recording to `path`.
+    pub fn record(path: &Path) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, r#"{{"flux_effect_log":{EFFECT_LOG_VERSION}}}"#)?;
+        out.flush()?;
+        Ok(Self {
+            mode: Mode::Record(out),
+            divergence: None,
+        })
+    }
+
+    /// Loads the log at `path` for replay.
+    pub fn replay(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header: LogHeader = lines
+            .next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .ok_or("not a Flux effect log")?;
+        if header.flux_effect_log != EFFECT_LOG_VERSION {
+            return Err(format!(
+                "unsupported effect log version {}",
+                header.flux_effect_log
+            ));
+        }
+        let effects = lines
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str::<LoggedEffect>(line)
+                    .ok()
+                    .filter(|effect| effect.result.is_some() != effect.error.is_some())
+                    .ok_or_else(|| format!("malformed entry for effect {}", index + 1))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            mode: Mode::Replay { effects, next: 0 },
+            divergence: None,
+        })
+    }
+
+    /// Runs the builtin `op` through the log: `run` performs it for real when recording, and
+    /// the logged outcome stands in for it when replaying.
+    pub fn perform(
+        &mut self,
+        op: &str,
+        args: &[Value],
+        run: impl FnOnce() -> Result<Value, String>,
+    ) -> Result<Value, String> {
+        let args: Vec<Json> = args.iter().map(value_to_json).collect();
+        match &mut self.mode {
+            Mode::Record(out) => {
+                let outcome = run();
+                let effect = LoggedEffect {
+                    op: op.to_string(),
+                    args,
+                    result: outcome.as_ref().ok().map(value_to_json),
+                    error: outcome.as_ref().err().cloned(),
+                };
+                let line = serde_json::to_string(&effect).map_err(|err| err.to_string())?;
+                writeln!(out, "{line}")
+                    .and_then(|()| out.flush())
+                    .map_err(|err| format!("cannot write effect log: {err}"))?;
+                outcome
+            }
+            Mode::Replay { effects, next } => {
+                let index = *next + 1;
+                let logged = match effects.get(*next) {
+                    Some(logged) if logged.op == op && logged.args == args => logged,
+                    Some(logged) => {
+                        let message = format!(
+                            "replay diverged at effect {index}: expected {}, got {}",
+                            render_call(&logged.op, &logged.args),
+                            render_call(op, &args)
+                        );
+                        return Err(self.diverge(message));
+                    }
+                    None => {
+                        let message = format!(
+                            "replay diverged at effect {index}: expected end of log, got {}",
+                            render_call(op, &args)
+                        );
+                        return Err(self.diverge(message));
+                    }
+                };
+                *next += 1;
+                match (&logged.result, &logged.error) {
+                    (_, Some(error)) => Err(error.clone()),
+                    (Some(result), None) => json_to_value(result).ok_or_else(|| {
+                        format!("effect log: unsupported result for effect {index}")
+                    }),
+                    (None, None) => unreachable!("replay entries are validated on load"),
+                }
+            }
+        }
+    }
+
+    /// Ends the run: flushes a recording, or reports the first divergence of a replay and any
+    /// logged calls the run never made.
+    pub fn finish(&mut self) -> Result<(), String> {
+        if let Some(divergence) = &self.divergence {
+            return Err(divergence.clone());
+        }
+        match &mut self.mode {
+            Mode::Record(out) => out
+                .flush()
+                .map_err(|err| format!("cannot write effect log: {err}")),
+            Mode::Replay { effects, next } => match effects.get(*next) {
+                Some(missing) => Err(format!(
+                    "replay diverged: {} recorded effects were not performed, starting with {}",
+                    effects.len() - *next,
+                    render_call(&missing.op, &missing.args)
+                )),
+                None => Ok(()),
+            },
+        }
+    }
+
+    fn diverge(&mut self, message: String) -> String {
+        self.divergence.get_or_insert_with(|| message.clone());
+        message
+    }
+}
+
+/// Renders a call as `op(arg, ...)` with JSON arguments, as the native runtime does.
+fn render_call(op: &str, args: &[Json]) -> String {
+    let args: Vec<String> = args.iter().map(Json::to_string).collect();
+    format!("{op}({})", args.join(", "))
+}
+
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::Integer(n) => Json::from(*n),
+        Value::Float(f) => Json::from(*f),
+        Value::Boolean(b) => Json::from(*b),
+        Value::String(s) => Json::from(s.as_str()),
+        Value::None => Json::Null,
+        Value::Array(items) => Json::Array(items.iter().map(value_to_json).collect()),
+        other => Json::from(other.to_string_value()),
+    }
+}
+
+fn json_to_value(json: &Json) -> Option<Value> {
+    match json {
+        Json::Null => Some(Value::None),
+        Json::Bool(b) => Some(Value::Boolean(*b)),
+        Json::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| n.as_f64().map(Value::Float)),
+        Json::String(s) => Some(Value::String(Rc::new(s.clone()))),
+        Json::Array(items) => items
+            .iter()
+            .map(json_to_value)
+            .collect::<Option<Vec<_>>>()
+            .map(|items| Value::Array(Rc::new(items))),
+        Json::Object(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Value {
+        Value::String(Rc::new(s.to_string()))
+    }
+
+    fn log_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("flux_effect_log_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn replay_answers_recorded_calls() {
+        let path = log_path("round_trip");
+        let mut log = EffectLog::record(&path).unwrap();
+        let read = log.perform("read_file", &[text("in.txt")], || Ok(text("hello\n")));
+        assert_eq!(read, Ok(text("hello\n")));
+        let missing = log.perform("read_file", &[text("gone.txt")], || Err("no".into()));
+        assert_eq!(missing, Err("no".to_string()));
+        log.perform("clock_now", &[], || Ok(Value::Integer(42)))
+            .unwrap();
+        log.finish().unwrap();
+
+        let mut log = EffectLog::replay(&path).unwrap();
+        let unreachable = || -> Result<Value, String> { panic!("replay ran the builtin") };
+        assert_eq!(
+            log.perform("read_file", &[text("in.txt")], unreachable),
+            Ok(text("hello\n"))
+        );
+        assert_eq!(
+            log.perform("read_file", &[text("gone.txt")], unreachable),
+            Err("no".to_string())
+        );
+        assert_eq!(
+            log.perform("clock_now", &[], unreachable),
+            Ok(Value::Integer(42))
+        );
+        assert_eq!(log.finish(), Ok(()));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn replay_reports_divergence() {
+        let path = log_path("divergence");
+        let mut log = EffectLog::record(&path).unwrap();
+        log.perform("read_file", &[text("a.txt")], || Ok(text("a")))
+            .unwrap();
+        log.perform("clock_now", &[], || Ok(Value::Integer(1)))
+            .unwrap();
+        log.finish().unwrap();
+
+        let mut log = EffectLog::replay(&path).unwrap();
+        let error = log
+            .perform("read_file", &[text("b.txt")], || Ok(text("b")))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            r#"replay diverged at effect 1: expected read_file("a.txt"), got read_file("b.txt")"#
+        );
+        assert_eq!(log.finish(), Err(error));
+
+        let mut log = EffectLog::replay(&path).unwrap();
+        log.perform("read_file", &[text("a.txt")], || Ok(text("a")))
+            .unwrap();
+        assert_eq!(
+            log.finish(),
+            Err(
+                "replay diverged: 1 recorded effects were not performed, starting with clock_now()"
+                    .to_string()
+            )
+        );
+        let _ = fs::remove_file(path);
+    }
+}
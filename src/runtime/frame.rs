@@ -8,6 +8,12 @@ pub struct Frame {
     pub ip: usize,
     pub base_pointer: usize,
     pub return_slot: usize,
+    /// The function and call-site offset this frame first tail-called out of, kept only when
+    /// the VM records tail callers (test runs) so failures still point at the call site.
+    pub tail_caller: Option<(Rc<Closure>, usize)>,
+    /// The call-trace label logged when this frame was entered, when `--trace-calls`
+    /// selected its function.
+    pub traced: Option<u16>,
 }
 
 impl Frame {
@@ -25,6 +31,8 @@ impl Frame {
             ip: 0,
             base_pointer,
             return_slot,
+            tail_caller: None,
+            traced: None,
         }
     }
 
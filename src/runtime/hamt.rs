@@ -6,7 +6,11 @@
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::rc::Rc;
 
-use crate::runtime::{hash_key::HashKey, value::Value};
+use crate::runtime::{
+    alloc_profile::{self, AllocKind},
+    hash_key::HashKey,
+    value::Value,
+};
 
 /// Bits consumed per HAMT level.
 const BITS_PER_LEVEL: u32 = 5;
@@ -48,12 +52,15 @@ fn compressed_index(bitmap: u32, slot: u32) -> usize {
     (bitmap & ((1 << slot) - 1)).count_ones() as usize
 }
 
+/// Allocates a node, counting it for `--prof`.
+fn new_node(bitmap: u32, children: Vec<HamtEntry>) -> Rc<HamtNode> {
+    alloc_profile::record(AllocKind::HamtNode);
+    Rc::new(HamtNode { bitmap, children })
+}
+
 /// Creates an empty HAMT root node.
 pub fn hamt_empty() -> Rc<HamtNode> {
-    Rc::new(HamtNode {
-        bitmap: 0,
-        children: Vec::new(),
-    })
+    new_node(0, Vec::new())
 }
 
 /// Looks up a key in a HAMT.
@@ -117,10 +124,7 @@ fn hamt_insert_at(
         // Slot is empty -- insert a new leaf
         let mut new_children = node.children.clone();
         new_children.insert(idx, HamtEntry::Leaf(key, value));
-        Rc::new(HamtNode {
-            bitmap: node.bitmap | bit,
-            children: new_children,
-        })
+        new_node(node.bitmap | bit, new_children)
     } else {
         // Slot is occupied
         let mut new_children = node.children.clone();
@@ -130,10 +134,7 @@ fn hamt_insert_at(
                 if *existing_key == key {
                     // Update existing key
                     new_children[idx] = HamtEntry::Leaf(key, value);
-                    Rc::new(HamtNode {
-                        bitmap: node.bitmap,
-                        children: new_children,
-                    })
+                    new_node(node.bitmap, new_children)
                 } else {
                     // Hash collision at this level -- push down
                     let existing_hash = hash_key(existing_key);
@@ -147,33 +148,21 @@ fn hamt_insert_at(
                             entries: vec![(ek, ev), (key, value)],
                         });
                         new_children[idx] = HamtEntry::Collision(col);
-                        Rc::new(HamtNode {
-                            bitmap: node.bitmap,
-                            children: new_children,
-                        })
+                        new_node(node.bitmap, new_children)
                     } else {
                         // Create a sub-node and insert both entries
-                        let empty = Rc::new(HamtNode {
-                            bitmap: 0,
-                            children: Vec::new(),
-                        });
+                        let empty = new_node(0, Vec::new());
                         let sub = hamt_insert_at(&empty, ek, ev, existing_hash, depth + 1);
                         let sub = hamt_insert_at(&sub, key, value, hash, depth + 1);
                         new_children[idx] = HamtEntry::Node(sub);
-                        Rc::new(HamtNode {
-                            bitmap: node.bitmap,
-                            children: new_children,
-                        })
+                        new_node(node.bitmap, new_children)
                     }
                 }
             }
             HamtEntry::Node(child) => {
                 let new_child = hamt_insert_at(child, key, value, hash, depth + 1);
                 new_children[idx] = HamtEntry::Node(new_child);
-                Rc::new(HamtNode {
-                    bitmap: node.bitmap,
-                    children: new_children,
-                })
+                new_node(node.bitmap, new_children)
             }
             HamtEntry::Collision(col) => {
                 let mut entries = col.entries.clone();
@@ -187,10 +176,7 @@ fn hamt_insert_at(
                     entries,
                 });
                 new_children[idx] = HamtEntry::Collision(new_col);
-                Rc::new(HamtNode {
-                    bitmap: node.bitmap,
-                    children: new_children,
-                })
+                new_node(node.bitmap, new_children)
             }
         }
     }
@@ -221,10 +207,7 @@ fn hamt_delete_at(node: &Rc<HamtNode>, key: &HashKey, hash: u64, depth: u32) ->
             // Remove this leaf
             new_children.remove(idx);
             let new_bitmap = node.bitmap & !bit;
-            Rc::new(HamtNode {
-                bitmap: new_bitmap,
-                children: new_children,
-            })
+            new_node(new_bitmap, new_children)
         }
         HamtEntry::Node(child) => {
             let new_child = hamt_delete_at(child, key, hash, depth + 1);
@@ -232,24 +215,15 @@ fn hamt_delete_at(node: &Rc<HamtNode>, key: &HashKey, hash: u64, depth: u32) ->
                 // Child is empty, remove the slot
                 new_children.remove(idx);
                 let new_bitmap = node.bitmap & !bit;
-                Rc::new(HamtNode {
-                    bitmap: new_bitmap,
-                    children: new_children,
-                })
+                new_node(new_bitmap, new_children)
             } else if new_child.children.len() == 1 {
                 // Child has single entry, pull it up
                 let entry = new_child.children[0].clone();
                 new_children[idx] = entry;
-                Rc::new(HamtNode {
-                    bitmap: node.bitmap,
-                    children: new_children,
-                })
+                new_node(node.bitmap, new_children)
             } else {
                 new_children[idx] = HamtEntry::Node(new_child);
-                Rc::new(HamtNode {
-                    bitmap: node.bitmap,
-                    children: new_children,
-                })
+                new_node(node.bitmap, new_children)
             }
         }
         HamtEntry::Collision(col) => {
@@ -260,20 +234,14 @@ fn hamt_delete_at(node: &Rc<HamtNode>, key: &HashKey, hash: u64, depth: u32) ->
                     // Convert back to leaf
                     let (k, v) = entries.remove(0);
                     new_children[idx] = HamtEntry::Leaf(k, v);
-                    Rc::new(HamtNode {
-                        bitmap: node.bitmap,
-                        children: new_children,
-                    })
+                    new_node(node.bitmap, new_children)
                 } else {
                     let new_col = Rc::new(HamtCollision {
                         hash: col.hash,
                         entries,
                     });
                     new_children[idx] = HamtEntry::Collision(new_col);
-                    Rc::new(HamtNode {
-                        bitmap: node.bitmap,
-                        children: new_children,
-                    })
+                    new_node(node.bitmap, new_children)
                 }
             } else {
                 Rc::clone(node) // Key not in collision
@@ -15,10 +15,13 @@
 //! Future cyclic data features must use cycle-aware memory management.
 use crate::runtime::value::Value;
 
+pub mod alloc_profile;
+pub mod call_trace;
 pub mod closure;
 pub mod compiled_function;
 pub mod cons_cell;
 pub mod continuation;
+pub mod effect_log;
 pub mod evidence;
 pub mod frame;
 pub mod function_contract;
@@ -59,4 +62,12 @@ pub trait RuntimeContext {
     ) -> Option<&'a function_contract::FunctionContract> {
         None
     }
+    /// Writes program output from `print` and `println`.
+    fn write_stdout(&mut self, text: &str) {
+        print!("{text}");
+    }
+    /// The `--record`/`--replay` log that builtin effects go through, when one is attached.
+    fn effect_log(&mut self) -> Option<&mut effect_log::EffectLog> {
+        None
+    }
 }
@@ -1,3 +1,4 @@
 //! Shared infrastructure used across multiple compiler and driver subsystems.
 
 pub mod cache_paths;
+pub mod source_files;
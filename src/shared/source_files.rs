@@ -0,0 +1,24 @@
+//! Discovery of Flux source files on disk.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Collects `.flx` files under `dir` recursively.
+///
+/// Unreadable directories are skipped; the order follows `read_dir`, so callers that need a
+/// stable order sort the result.
+pub fn collect_flx_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_flx_files(&path, files);
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("flx") {
+            files.push(path);
+        }
+    }
+}
@@ -396,6 +396,14 @@ pub enum Expression {
         span: Span,
         id: ExprId,
     },
+    /// `?name` or `?` — a typed hole standing in for a missing expression
+    /// (proposal 0083). Type inference reports what belongs there; reaching
+    /// it at runtime panics.
+    Hole {
+        name: Option<Identifier>,
+        span: Span,
+        id: ExprId,
+    },
 }
 
 /// One field in a named-field constructor or spread override.
@@ -410,6 +418,14 @@ pub struct NamedFieldInit {
     pub span: Span,
 }
 
+/// Panic message for a typed hole (`?name` or `?`) reached at runtime.
+pub fn hole_panic_message(name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("typed hole ?{name} reached"),
+        None => "typed hole ? reached".to_string(),
+    }
+}
+
 impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -629,6 +645,10 @@ impl fmt::Display for Expression {
                 }
                 write!(f, " }}")
             }
+            Expression::Hole { name, .. } => match name {
+                Some(name) => write!(f, "?{}", name),
+                None => write!(f, "?"),
+            },
         }
     }
 }
@@ -667,7 +687,46 @@ impl Expression {
             | Expression::Handle { id, .. }
             | Expression::Sealing { id, .. }
             | Expression::NamedConstructor { id, .. }
-            | Expression::Spread { id, .. } => *id,
+            | Expression::Spread { id, .. }
+            | Expression::Hole { id, .. } => *id,
+        }
+    }
+
+    /// Mutable access to [`Self::expr_id`], for passes that renumber nodes.
+    pub fn expr_id_mut(&mut self) -> &mut ExprId {
+        match self {
+            Expression::Identifier { id, .. }
+            | Expression::Integer { id, .. }
+            | Expression::Float { id, .. }
+            | Expression::String { id, .. }
+            | Expression::InterpolatedString { id, .. }
+            | Expression::Boolean { id, .. }
+            | Expression::Prefix { id, .. }
+            | Expression::Infix { id, .. }
+            | Expression::If { id, .. }
+            | Expression::DoBlock { id, .. }
+            | Expression::Function { id, .. }
+            | Expression::Call { id, .. }
+            | Expression::ListLiteral { id, .. }
+            | Expression::ArrayLiteral { id, .. }
+            | Expression::TupleLiteral { id, .. }
+            | Expression::EmptyList { id, .. }
+            | Expression::Index { id, .. }
+            | Expression::Hash { id, .. }
+            | Expression::MemberAccess { id, .. }
+            | Expression::TupleFieldAccess { id, .. }
+            | Expression::Match { id, .. }
+            | Expression::None { id, .. }
+            | Expression::Some { id, .. }
+            | Expression::Left { id, .. }
+            | Expression::Right { id, .. }
+            | Expression::Cons { id, .. }
+            | Expression::Perform { id, .. }
+            | Expression::Handle { id, .. }
+            | Expression::Sealing { id, .. }
+            | Expression::NamedConstructor { id, .. }
+            | Expression::Spread { id, .. }
+            | Expression::Hole { id, .. } => id,
         }
     }
 
@@ -704,6 +763,7 @@ impl Expression {
             Expression::Sealing { span, .. } => *span,
             Expression::NamedConstructor { span, .. } => *span,
             Expression::Spread { span, .. } => *span,
+            Expression::Hole { span, .. } => *span,
         }
     }
 }
@@ -994,6 +1054,10 @@ impl Expression {
                 out.push_str(" }");
                 out
             }
+            Expression::Hole { name, .. } => match name {
+                Some(name) => format!("?{}", interner.resolve(*name)),
+                None => "?".to_string(),
+            },
         }
     }
 }
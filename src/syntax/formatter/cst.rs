@@ -0,0 +1,409 @@
+//! Lossless token tree for the formatter.
+//!
+//! The parser drops comments and desugars surface syntax (`|>`, list comprehensions,
+//! `else if`), so the printer works from the token stream instead: tokens are grouped by
+//! bracket pairs and every comment is attached to a neighbouring token as trivia. String
+//! literals, including interpolations, stay single atoms copied verbatim from the source.
+
+use crate::{
+    diagnostics::position::Position,
+    syntax::{lexer::Lexer, token::Token, token_type::TokenType},
+};
+
+/// A source comment carried as trivia.
+#[derive(Debug, Clone)]
+pub(super) struct Comment {
+    pub text: String,
+    pub source_column: usize,
+    /// At least one blank line precedes the comment.
+    pub blank_before: bool,
+    /// A newline follows the comment. Always true for `//` comments.
+    pub newline_after: bool,
+}
+
+impl Comment {
+    fn is_line_comment(&self) -> bool {
+        self.text.starts_with("//")
+    }
+}
+
+/// One printable token (or a whole string literal) with its attached comments.
+#[derive(Debug, Clone)]
+pub(super) struct Atom {
+    pub kind: TokenType,
+    pub text: String,
+    pub start: Position,
+    pub end: Position,
+    /// Comments on the lines before this atom.
+    pub leading: Vec<Comment>,
+    /// Comments after this atom on the same line.
+    pub trailing: Vec<Comment>,
+    /// A blank line separates the atom from what precedes it (after its leading comments).
+    pub blank_before: bool,
+}
+
+impl Atom {
+    pub(super) fn is(&self, kind: TokenType) -> bool {
+        self.kind == kind
+    }
+
+    /// Whether any comment on the atom forces the surrounding layout onto several lines.
+    pub(super) fn has_comments(&self) -> bool {
+        !self.leading.is_empty() || !self.trailing.is_empty()
+    }
+}
+
+/// A bracket pair and everything between it.
+#[derive(Debug, Clone)]
+pub(super) struct Group {
+    pub open: Atom,
+    pub children: Vec<Node>,
+    pub close: Atom,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Node {
+    Atom(Atom),
+    Group(Group),
+}
+
+impl Node {
+    pub(super) fn first_atom(&self) -> &Atom {
+        match self {
+            Node::Atom(atom) => atom,
+            Node::Group(group) => &group.open,
+        }
+    }
+
+    pub(super) fn last_atom(&self) -> &Atom {
+        match self {
+            Node::Atom(atom) => atom,
+            Node::Group(group) => &group.close,
+        }
+    }
+
+    pub(super) fn start(&self) -> Position {
+        self.first_atom().start
+    }
+
+    pub(super) fn end(&self) -> Position {
+        self.last_atom().end
+    }
+
+    pub(super) fn as_atom(&self) -> Option<&Atom> {
+        match self {
+            Node::Atom(atom) => Some(atom),
+            Node::Group(_) => None,
+        }
+    }
+
+    pub(super) fn is_atom(&self, kind: TokenType) -> bool {
+        self.as_atom().is_some_and(|atom| atom.is(kind))
+    }
+
+    /// Whether the node or anything inside it carries comments.
+    pub(super) fn has_comments(&self) -> bool {
+        match self {
+            Node::Atom(atom) => atom.has_comments(),
+            Node::Group(group) => {
+                group.open.has_comments()
+                    || group.close.has_comments()
+                    || group.children.iter().any(Node::has_comments)
+            }
+        }
+    }
+}
+
+/// The whole file as a sequence of top-level nodes.
+#[derive(Debug, Clone)]
+pub(super) struct SourceTree {
+    pub nodes: Vec<Node>,
+    /// Comments after the last token of the file.
+    pub trailing_comments: Vec<Comment>,
+    /// Start position and kind of every atom, in source order, for AST lookups.
+    pub atoms: Vec<(Position, TokenType)>,
+}
+
+enum Piece {
+    Atom(Atom),
+    Comment(Comment),
+}
+
+impl SourceTree {
+    /// Builds the tree for a source that parsed without errors.
+    pub(super) fn build(source: &str) -> SourceTree {
+        let index = LineIndex::new(source);
+        let pieces = collect_pieces(source, &index);
+        let (atoms, trailing_comments) = attach_comments(pieces);
+        let positions = atoms.iter().map(|atom| (atom.start, atom.kind)).collect();
+
+        let mut stack: Vec<(Atom, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        for atom in atoms {
+            if is_opener(&atom.text) {
+                stack.push((atom, std::mem::take(&mut nodes)));
+            } else if is_closer(&atom.text) && !stack.is_empty() {
+                let (open, parent) = stack.pop().expect("checked non-empty");
+                let children = std::mem::replace(&mut nodes, parent);
+                nodes.push(Node::Group(Group {
+                    open,
+                    children,
+                    close: atom,
+                }));
+            } else {
+                nodes.push(Node::Atom(atom));
+            }
+        }
+        // Unbalanced input never reaches the printer (it fails to parse), but keep every
+        // token if it does.
+        while let Some((open, parent)) = stack.pop() {
+            let children = std::mem::replace(&mut nodes, parent);
+            nodes.push(Node::Atom(open));
+            nodes.extend(children);
+        }
+
+        SourceTree {
+            nodes,
+            trailing_comments,
+            atoms: positions,
+        }
+    }
+}
+
+fn is_opener(text: &str) -> bool {
+    matches!(text, "(" | "[" | "{" | "[|")
+}
+
+fn is_closer(text: &str) -> bool {
+    matches!(text, ")" | "]" | "}" | "|]")
+}
+
+/// Lexes `source` into atoms and comments, each with the number of newlines before it.
+fn collect_pieces(source: &str, index: &LineIndex) -> Vec<(Piece, usize)> {
+    let tokens: Vec<Token> = Lexer::new(source)
+        .tokenize()
+        .into_iter()
+        .filter(|token| token.token_type != TokenType::Eof)
+        .collect();
+
+    let mut pieces = Vec::new();
+    let mut cursor = 0usize;
+    let mut i = 0usize;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let start = index.offset(token.position);
+        let mut last = i;
+        let mut text = None;
+
+        match token.token_type {
+            TokenType::String | TokenType::InterpolationStart
+                if source[start..].starts_with('"') =>
+            {
+                last = string_end(&tokens, i, source, index);
+            }
+            // `[| ... |]` array delimiters and the empty array `[||]`.
+            TokenType::LBracket => match (kind_at(&tokens, i + 1), kind_at(&tokens, i + 2)) {
+                (Some(TokenType::Bar), _) => {
+                    last = i + 1;
+                    text = Some("[|");
+                }
+                (Some(TokenType::Or), Some(TokenType::RBracket)) => {
+                    last = i + 2;
+                    text = Some("[||]");
+                }
+                _ => {}
+            },
+            TokenType::Bar if kind_at(&tokens, i + 1) == Some(TokenType::RBracket) => {
+                last = i + 1;
+                text = Some("|]");
+            }
+            _ => {}
+        }
+
+        let end = index.offset(tokens[last].end_position).max(start);
+        let newlines = scan_gap(source, index, cursor, start, &mut pieces);
+        let raw = &source[start..end];
+
+        if token.token_type == TokenType::DocComment {
+            pieces.push((
+                Piece::Comment(Comment {
+                    text: raw.trim_end().to_string(),
+                    source_column: token.position.column,
+                    blank_before: newlines > 1,
+                    newline_after: true,
+                }),
+                newlines,
+            ));
+        } else {
+            pieces.push((
+                Piece::Atom(Atom {
+                    kind: if last > i && text.is_none() {
+                        TokenType::String
+                    } else {
+                        token.token_type
+                    },
+                    text: text.map_or_else(|| raw.to_string(), str::to_string),
+                    start: token.position,
+                    end: tokens[last].end_position,
+                    leading: Vec::new(),
+                    trailing: Vec::new(),
+                    blank_before: newlines > 1,
+                }),
+                newlines,
+            ));
+        }
+        cursor = end;
+        i = last + 1;
+    }
+    scan_gap(source, index, cursor, source.len(), &mut pieces);
+    pieces
+}
+
+fn kind_at(tokens: &[Token], index: usize) -> Option<TokenType> {
+    tokens.get(index).map(|token| token.token_type)
+}
+
+/// Returns the index of the last token of the string literal starting at `first`, skipping
+/// over the tokens of any interpolations inside it.
+fn string_end(tokens: &[Token], first: usize, source: &str, index: &LineIndex) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(first) {
+        let opens_literal = source[index.offset(token.position)..].starts_with('"');
+        match token.token_type {
+            TokenType::InterpolationStart if opens_literal => depth += 1,
+            TokenType::StringEnd => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 {
+            return i;
+        }
+    }
+    tokens.len() - 1
+}
+
+/// Pushes the comments found in `source[from..to]`, which holds only whitespace and
+/// comments, and returns the number of newlines after the last of them.
+fn scan_gap(
+    source: &str,
+    index: &LineIndex,
+    from: usize,
+    to: usize,
+    pieces: &mut Vec<(Piece, usize)>,
+) -> usize {
+    let gap = &source[from..to];
+    let bytes = gap.as_bytes();
+    let mut newlines = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let end = match (bytes[i], bytes.get(i + 1)) {
+            (b'\n', _) => {
+                newlines += 1;
+                i += 1;
+                continue;
+            }
+            (b'/', Some(b'/')) => gap[i..].find('\n').map_or(gap.len(), |offset| i + offset),
+            (b'/', Some(b'*')) => i + block_comment_len(&gap[i..]),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        pieces.push((
+            Piece::Comment(Comment {
+                text: gap[i..end].trim_end().to_string(),
+                source_column: index.column_of(from + i),
+                blank_before: newlines > 1,
+                newline_after: true,
+            }),
+            newlines,
+        ));
+        newlines = 0;
+        i = end;
+    }
+    newlines
+}
+
+/// Byte length of the (possibly nested) block comment at the start of `text`.
+fn block_comment_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        match (bytes[i], bytes[i + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    text.len()
+}
+
+/// Turns the flat piece list into atoms, attaching each comment as trailing trivia of the
+/// atom before it (same line) or leading trivia of the atom after it.
+fn attach_comments(pieces: Vec<(Piece, usize)>) -> (Vec<Atom>, Vec<Comment>) {
+    let mut atoms: Vec<Atom> = Vec::new();
+    let mut pending: Vec<Comment> = Vec::new();
+    let newline_follows: Vec<bool> = (0..pieces.len())
+        .map(|i| pieces.get(i + 1).is_none_or(|(_, newlines)| *newlines > 0))
+        .collect();
+
+    for (i, (piece, newlines)) in pieces.into_iter().enumerate() {
+        match piece {
+            Piece::Atom(mut atom) => {
+                atom.leading = std::mem::take(&mut pending);
+                atoms.push(atom);
+            }
+            Piece::Comment(mut comment) => {
+                comment.newline_after = comment.is_line_comment() || newline_follows[i];
+                match atoms.last_mut() {
+                    Some(atom) if newlines == 0 && pending.is_empty() => {
+                        atom.trailing.push(comment)
+                    }
+                    _ => pending.push(comment),
+                }
+            }
+        }
+    }
+    (atoms, pending)
+}
+
+/// Maps the lexer's line/column positions (1-based lines, char columns) to byte offsets.
+struct LineIndex {
+    line_starts: Vec<usize>,
+    source: String,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(offset, _)| offset + 1));
+        LineIndex {
+            line_starts,
+            source: source.to_string(),
+        }
+    }
+
+    fn offset(&self, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line.saturating_sub(1)) else {
+            return self.source.len();
+        };
+        self.source[line_start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.source.len(), |(offset, _)| line_start + offset)
+    }
+
+    fn column_of(&self, offset: usize) -> usize {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        self.source[self.line_starts[line]..offset].chars().count()
+    }
+}
@@ -0,0 +1,288 @@
+//! Wadler-style document algebra and its width-aware renderer.
+//!
+//! The printer builds a [`Doc`] tree once; [`render`] then decides, group by group, whether
+//! the group fits on the remaining line (flat mode) or must break its `Line`s into newlines.
+
+const INDENT_WIDTH: usize = 4;
+
+#[derive(Debug, Clone)]
+pub(super) enum Doc {
+    Nil,
+    /// Literal text. Text containing newlines (multi-line strings) is emitted verbatim.
+    Text(String),
+    /// A source comment whose continuation lines are re-indented by how far its first line
+    /// moved from `source_column`.
+    Comment {
+        text: String,
+        source_column: usize,
+    },
+    /// `flat` in flat mode, a newline plus indentation in break mode.
+    Line {
+        flat: &'static str,
+    },
+    /// Always a newline; any enclosing group is forced to break.
+    HardLine,
+    /// Forces every enclosing group to break without printing anything.
+    BreakParent,
+    /// Text deferred to the end of the current line (trailing line comments).
+    LineSuffix(String),
+    Concat(Vec<Doc>),
+    Nest(Box<Doc>),
+    Group(Box<Doc>),
+}
+
+impl Doc {
+    pub(super) fn text(text: impl Into<String>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    /// A break that is a single space when flat.
+    pub(super) fn line() -> Doc {
+        Doc::Line { flat: " " }
+    }
+
+    /// A break that disappears when flat.
+    pub(super) fn softline() -> Doc {
+        Doc::Line { flat: "" }
+    }
+
+    pub(super) fn nest(doc: Doc) -> Doc {
+        Doc::Nest(Box::new(doc))
+    }
+
+    pub(super) fn group(doc: Doc) -> Doc {
+        Doc::Group(Box::new(doc))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Renders `doc` so that lines stay within `width` wherever a group can break.
+pub(super) fn render(doc: &Doc, width: usize) -> String {
+    let mut out = String::new();
+    let mut column = 0usize;
+    let mut suffix = Vec::new();
+    let mut stack = vec![(0usize, Mode::Break, doc)];
+
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Nil | Doc::BreakParent => {}
+            Doc::Text(text) => push_text(&mut out, &mut column, text),
+            Doc::Comment {
+                text,
+                source_column,
+            } => {
+                let reindented = reindent_comment(text, *source_column, column);
+                push_text(&mut out, &mut column, &reindented);
+            }
+            Doc::Line { flat } if mode == Mode::Flat => {
+                out.push_str(flat);
+                column += flat.len();
+            }
+            Doc::Line { .. } | Doc::HardLine => {
+                newline(&mut out, &mut suffix, indent);
+                column = indent;
+            }
+            Doc::LineSuffix(text) => suffix.push(text.clone()),
+            Doc::Concat(parts) => {
+                for part in parts.iter().rev() {
+                    stack.push((indent, mode, part));
+                }
+            }
+            Doc::Nest(inner) => stack.push((indent + INDENT_WIDTH, mode, inner)),
+            Doc::Group(inner) => {
+                let fits = mode == Mode::Flat
+                    || fits(
+                        width.saturating_sub(column),
+                        (indent, Mode::Flat, inner),
+                        &stack,
+                    );
+                let mode = if fits { Mode::Flat } else { Mode::Break };
+                stack.push((indent, mode, inner));
+            }
+        }
+    }
+
+    flush_suffix(&mut out, &mut suffix);
+    out
+}
+
+/// Checks whether `next`, printed flat, plus whatever follows it up to the next line break
+/// fits in `remaining` columns. What follows `next` is measured in the mode it is already
+/// printed in: its first `Line` in break mode ends the line, including one inside a later
+/// group, which can still break on its own. So a call followed by a long trailing argument
+/// list stays flat and the argument list breaks instead.
+///
+/// Text spanning several lines (multi-line strings and comments) ends the measured line,
+/// but `next` must still contain no forced break.
+fn fits(mut remaining: usize, next: (usize, Mode, &Doc), rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut stack = vec![next];
+    let mut rest_index = rest.len();
+    let mut in_rest = false;
+    let mut line_ended = false;
+
+    loop {
+        let (indent, mode, doc) = match stack.pop() {
+            Some(entry) => entry,
+            None if line_ended || rest_index == 0 => return true,
+            None => {
+                in_rest = true;
+                rest_index -= 1;
+                rest[rest_index]
+            }
+        };
+
+        match doc {
+            Doc::Concat(parts) => {
+                for part in parts.iter().rev() {
+                    stack.push((indent, mode, part));
+                }
+            }
+            Doc::Nest(inner) => stack.push((indent + INDENT_WIDTH, mode, inner)),
+            Doc::Group(inner) => stack.push((indent, mode, inner)),
+            Doc::HardLine | Doc::BreakParent => return in_rest,
+            _ if line_ended => {}
+            Doc::Nil | Doc::LineSuffix(_) => {}
+            Doc::Text(text) | Doc::Comment { text, .. } => {
+                let first_line = text.split('\n').next().unwrap_or_default();
+                let width = first_line.chars().count();
+                if width > remaining {
+                    return false;
+                }
+                remaining -= width;
+                if text.contains('\n') {
+                    if in_rest {
+                        return true;
+                    }
+                    line_ended = true;
+                }
+            }
+            Doc::Line { .. } if mode == Mode::Break => return true,
+            Doc::Line { flat } => {
+                if flat.len() > remaining {
+                    return false;
+                }
+                remaining -= flat.len();
+            }
+        }
+    }
+}
+
+fn push_text(out: &mut String, column: &mut usize, text: &str) {
+    out.push_str(text);
+    match text.rfind('\n') {
+        Some(index) => *column = text[index + 1..].chars().count(),
+        None => *column += text.chars().count(),
+    }
+}
+
+fn newline(out: &mut String, suffix: &mut Vec<String>, indent: usize) {
+    flush_suffix(out, suffix);
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    out.push('\n');
+    out.extend(std::iter::repeat_n(' ', indent));
+}
+
+fn flush_suffix(out: &mut String, suffix: &mut Vec<String>) {
+    for text in suffix.drain(..) {
+        out.push_str(&text);
+    }
+}
+
+/// Shifts the continuation lines of a multi-line comment by the distance its first line
+/// moved, so `/* ... */` blocks keep their internal alignment when re-indented.
+fn reindent_comment(text: &str, source_column: usize, column: usize) -> String {
+    if !text.contains('\n') || source_column == column {
+        return text.to_string();
+    }
+    let mut lines = text.split('\n');
+    let mut out = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        out.push('\n');
+        if column > source_column {
+            if !line.is_empty() {
+                out.extend(std::iter::repeat_n(' ', column - source_column));
+            }
+            out.push_str(line);
+        } else {
+            let shift = source_column - column;
+            let removable = line.len() - line.trim_start_matches(' ').len();
+            out.push_str(&line[shift.min(removable)..]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Doc, render};
+
+    fn call(args: &[&str]) -> Doc {
+        let mut items = Vec::new();
+        for (index, arg) in args.iter().enumerate() {
+            if index > 0 {
+                items.push(Doc::text(","));
+                items.push(Doc::line());
+            }
+            items.push(Doc::text(*arg));
+        }
+        Doc::group(Doc::Concat(vec![
+            Doc::text("f("),
+            Doc::nest(Doc::Concat(vec![Doc::softline(), Doc::Concat(items)])),
+            Doc::softline(),
+            Doc::text(")"),
+        ]))
+    }
+
+    #[test]
+    fn groups_stay_flat_when_they_fit() {
+        assert_eq!(render(&call(&["a", "b"]), 20), "f(a, b)");
+    }
+
+    #[test]
+    fn groups_break_every_line_when_too_wide() {
+        assert_eq!(
+            render(&call(&["alpha", "beta"]), 10),
+            "f(\n    alpha,\n    beta\n)"
+        );
+    }
+
+    #[test]
+    fn hard_lines_and_line_suffixes_force_breaks() {
+        let doc = Doc::group(Doc::Concat(vec![
+            Doc::text("a"),
+            Doc::LineSuffix(" // note".to_string()),
+            Doc::BreakParent,
+            Doc::line(),
+            Doc::text("b"),
+        ]));
+        assert_eq!(render(&doc, 80), "a // note\nb");
+    }
+
+    #[test]
+    fn comments_keep_their_internal_alignment() {
+        let doc = Doc::nest(Doc::Concat(vec![
+            Doc::HardLine,
+            Doc::Comment {
+                text: "/*\n * a\n */".to_string(),
+                source_column: 0,
+            },
+        ]));
+        assert_eq!(render(&doc, 80), "\n    /*\n     * a\n     */");
+    }
+
+    #[test]
+    fn a_later_group_breaks_before_an_earlier_one_that_fits() {
+        let doc = Doc::Concat(vec![
+            call(&["a"]),
+            Doc::text(" + "),
+            call(&["alpha", "beta"]),
+        ]);
+        assert_eq!(render(&doc, 12), "f(a) + f(\n    alpha,\n    beta\n)");
+    }
+}
@@ -0,0 +1,274 @@
+//! Layout facts recovered from the parsed `Program`.
+//!
+//! The token tree alone cannot tell a block from a record literal, or `a < b` from
+//! `List<a>`. This pass walks the AST once and records, by source position, how each brace
+//! group should be laid out and which tokens are binary operators.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::visit::{Visitor, walk_block, walk_expr, walk_stmt},
+    diagnostics::position::{Position, Span},
+    syntax::{
+        block::Block, expression::Expression, program::Program, statement::Statement,
+        token_type::TokenType,
+    },
+};
+
+/// Source position as a hashable `(line, column)` pair.
+pub(super) type Key = (usize, usize);
+
+pub(super) fn key(position: Position) -> Key {
+    (position.line, position.column)
+}
+
+/// How the contents of one `{ ... }` group are laid out.
+#[derive(Debug, Clone)]
+pub(super) enum BraceLayout {
+    /// A statement block. `starts` holds where each statement begins, in source order.
+    Block { starts: Vec<Key>, force_break: bool },
+    /// Declaration members or match/handle arms, one per line when broken.
+    Items { starts: Vec<Key>, force_break: bool },
+}
+
+#[derive(Debug, Default)]
+pub(super) struct LayoutHints {
+    /// Keyed by the position of the opening `{`.
+    pub braces: HashMap<Key, BraceLayout>,
+    /// Positions of `<`, `>` and `-` tokens used as binary operators.
+    pub binary_operators: HashSet<Key>,
+    /// Operator chains (`a + b + c`) that may break before their operators, keyed by the
+    /// chain's start with its end as the value.
+    pub operator_chains: HashMap<Key, Key>,
+    /// Operators a chain breaks before: those of the chain's outermost operator kind.
+    pub chain_operators: HashSet<Key>,
+    /// Opening braces of `if`/`else` blocks. They break together with the expression
+    /// around them instead of one at a time.
+    pub branch_blocks: HashSet<Key>,
+    /// Opening brackets inside function return types. They stay on one line, so a long
+    /// signature breaks its parameter list instead.
+    pub return_type_groups: HashSet<Key>,
+}
+
+impl LayoutHints {
+    pub(super) fn collect(program: &Program, atoms: &[(Position, TokenType)]) -> LayoutHints {
+        let mut collector = HintCollector {
+            atoms: atoms.iter().map(|(pos, kind)| (key(*pos), *kind)).collect(),
+            hints: LayoutHints::default(),
+            in_infix: false,
+        };
+        for statement in &program.statements {
+            collector.visit_stmt(statement);
+        }
+        collector.hints
+    }
+
+    /// Statement starts of a top-level program, in source order.
+    pub(super) fn program_starts(program: &Program) -> Vec<Key> {
+        sorted_starts(program.statements.iter().map(|stmt| stmt.span().start))
+    }
+}
+
+struct HintCollector {
+    atoms: Vec<(Key, TokenType)>,
+    hints: LayoutHints,
+    /// The expression being visited is an operand of an infix expression.
+    in_infix: bool,
+}
+
+impl HintCollector {
+    fn atom_index_at_or_after(&self, position: Key) -> usize {
+        self.atoms.partition_point(|(pos, _)| *pos < position)
+    }
+
+    fn add_block(&mut self, block: &Block, force_break: bool) {
+        let open = key(block.span.start);
+        let index = self.atom_index_at_or_after(open);
+        if self.atoms.get(index) != Some(&(open, TokenType::LBrace)) {
+            // Blocks synthesized by the parser (`else if`, lambda expression bodies) have
+            // no braces of their own.
+            return;
+        }
+        let starts = sorted_starts(block.statements.iter().map(|stmt| stmt.span().start));
+        if starts.first().is_some_and(|first| *first <= open) {
+            return;
+        }
+        let layout = self.hints.braces.entry(open).or_insert(BraceLayout::Block {
+            starts,
+            force_break,
+        });
+        if let BraceLayout::Block {
+            force_break: existing,
+            ..
+        } = layout
+        {
+            *existing |= force_break;
+        }
+    }
+
+    /// Registers the brace that encloses items starting at `starts`: the last `{` before
+    /// the first item.
+    fn add_items(&mut self, starts: impl Iterator<Item = Position>, force_break: bool) {
+        let starts = sorted_starts(starts);
+        let Some(first) = starts.first() else {
+            return;
+        };
+        let index = self.atom_index_at_or_after(*first);
+        let Some(open) = self.atoms[..index]
+            .iter()
+            .rev()
+            .find(|(_, kind)| *kind == TokenType::LBrace)
+            .map(|(pos, _)| *pos)
+        else {
+            return;
+        };
+        self.hints.braces.insert(
+            open,
+            BraceLayout::Items {
+                starts,
+                force_break,
+            },
+        );
+    }
+
+    fn add_return_type(&mut self, span: Span) {
+        let (start, end) = (key(span.start), key(span.end));
+        let from = self.atom_index_at_or_after(start);
+        let groups = self.atoms[from..]
+            .iter()
+            .take_while(|(pos, _)| *pos < end)
+            .filter(|(_, kind)| matches!(kind, TokenType::LParen | TokenType::LBracket))
+            .map(|(pos, _)| *pos);
+        self.hints.return_type_groups.extend(groups);
+    }
+
+    /// Marks the operator token in front of `right`, skipping any `(` that opens it.
+    fn add_binary_operator(&mut self, right: Position) {
+        if let Some(pos) = self.operator_before(right) {
+            self.hints.binary_operators.insert(pos);
+        }
+    }
+
+    fn operator_before(&self, right: Position) -> Option<Key> {
+        let index = self.atom_index_at_or_after(key(right));
+        self.atoms[..index]
+            .iter()
+            .rev()
+            .find(|(_, kind)| *kind != TokenType::LParen)
+            .map(|(pos, _)| *pos)
+    }
+
+    /// Records an outermost infix expression as a breakable chain. It breaks before each
+    /// operator that repeats the outermost one, so `a + b * c + d` breaks only at `+`.
+    fn add_operator_chain(&mut self, expr: &Expression) {
+        let Expression::Infix { operator, span, .. } = expr else {
+            return;
+        };
+        self.hints
+            .operator_chains
+            .insert(key(span.start), key(span.end));
+        self.add_chain_operators(expr, operator);
+    }
+
+    fn add_chain_operators(&mut self, expr: &Expression, chain_operator: &str) {
+        if let Expression::Infix {
+            left,
+            operator,
+            right,
+            ..
+        } = expr
+            && operator == chain_operator
+        {
+            self.add_chain_operators(left, chain_operator);
+            if let Some(pos) = self.operator_before(right.span().start) {
+                self.hints.chain_operators.insert(pos);
+            }
+            self.add_chain_operators(right, chain_operator);
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for HintCollector {
+    fn visit_block(&mut self, block: &'ast Block) {
+        self.add_block(block, false);
+        walk_block(self, block);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Statement) {
+        match stmt {
+            Statement::Function {
+                body, return_type, ..
+            } => {
+                self.add_block(body, true);
+                if let Some(return_type) = return_type {
+                    self.add_return_type(return_type.span());
+                }
+            }
+            Statement::Module { body, .. } => {
+                self.add_block(body, true);
+            }
+            Statement::Class { methods, .. } => {
+                for method in methods {
+                    self.add_return_type(method.return_type.span());
+                    if let Some(body) = &method.default_body {
+                        self.add_block(body, true);
+                    }
+                }
+                self.add_items(methods.iter().map(|method| method.span.start), true);
+            }
+            Statement::Instance { methods, .. } => {
+                for method in methods {
+                    self.add_block(&method.body, true);
+                }
+                self.add_items(methods.iter().map(|method| method.span.start), true);
+            }
+            Statement::Data { variants, .. } => {
+                self.add_items(variants.iter().map(|variant| variant.span.start), false);
+            }
+            Statement::EffectDecl { ops, .. } => {
+                self.add_items(ops.iter().map(|op| op.span.start), false);
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expression) {
+        let is_infix = matches!(expr, Expression::Infix { .. });
+        let operand = std::mem::replace(&mut self.in_infix, is_infix);
+        match expr {
+            Expression::Infix { right, .. } => {
+                self.add_binary_operator(right.span().start);
+                if !operand {
+                    self.add_operator_chain(expr);
+                }
+            }
+            Expression::If {
+                consequence,
+                alternative,
+                ..
+            } => {
+                self.hints.branch_blocks.insert(key(consequence.span.start));
+                if let Some(alternative) = alternative {
+                    self.hints.branch_blocks.insert(key(alternative.span.start));
+                }
+            }
+            Expression::Match { arms, .. } => {
+                self.add_items(arms.iter().map(|arm| arm.span.start), true);
+            }
+            Expression::Handle { arms, .. } => {
+                self.add_items(arms.iter().map(|arm| arm.span.start), true);
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+        self.in_infix = operand;
+    }
+}
+
+fn sorted_starts(starts: impl Iterator<Item = Position>) -> Vec<Key> {
+    let mut starts: Vec<Key> = starts.map(key).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    starts
+}
@@ -0,0 +1,223 @@
+//! Source formatter for `flux fmt`.
+//!
+//! Formatting is driven by the parsed [`Program`]: the AST decides what is a block, an arm
+//! list or a binary operator, and a lossless token tree ([`cst::SourceTree`]) supplies the
+//! exact tokens and comments to print. The printer builds a Wadler-style document that is
+//! rendered against a maximum line width, so short constructs stay on one line and long
+//! calls, lists and `|>` pipelines break one element per line.
+//!
+//! Every result is re-parsed and compared with the original program before it is returned,
+//! so formatting can never change what a file means.
+
+mod cst;
+mod doc;
+mod layout;
+mod printer;
+
+use crate::{
+    diagnostics::Diagnostic,
+    syntax::{lexer::Lexer, parser::Parser, program::Program},
+};
+
+use self::{cst::SourceTree, layout::LayoutHints, printer::Printer};
+
+/// Default maximum line width for formatted output.
+pub const DEFAULT_MAX_WIDTH: usize = 100;
+
+/// Options controlling formatter output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Lines are broken wherever possible to stay within this many columns.
+    pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            max_width: DEFAULT_MAX_WIDTH,
+        }
+    }
+}
+
+/// Why a source could not be formatted.
+#[derive(Debug)]
+pub enum FormatError {
+    /// The source does not parse; formatting needs a valid program.
+    Parse(Vec<Diagnostic>),
+    /// The formatted output no longer parses to the same program. This is a formatter bug;
+    /// the source is left untouched.
+    Changed,
+}
+
+/// Formats `source` with default options, returning it unchanged if it cannot be formatted.
+pub fn format_source(source: &str) -> String {
+    format_source_with(source, &FormatOptions::default()).unwrap_or_else(|_| source.to_string())
+}
+
+/// Formats `source`, failing if it does not parse.
+pub fn format_source_with(source: &str, options: &FormatOptions) -> Result<String, FormatError> {
+    let (program, rendered) = parse(source).map_err(FormatError::Parse)?;
+
+    let tree = SourceTree::build(source);
+    let hints = LayoutHints::collect(&program, &tree.atoms);
+    let starts = LayoutHints::program_starts(&program);
+    let doc = Printer::new(&hints).program(&tree, &starts);
+    let formatted = doc::render(&doc, options.max_width);
+
+    match parse(&formatted) {
+        Ok((_, reformatted)) if reformatted == rendered => Ok(formatted),
+        _ => Err(FormatError::Changed),
+    }
+}
+
+/// Parses `source` and returns the program with its canonical rendering, used to confirm
+/// that formatting preserved meaning.
+fn parse(source: &str) -> Result<(Program, String), Vec<Diagnostic>> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(std::mem::take(&mut parser.errors));
+    }
+    let interner = parser.take_interner();
+    let rendered = program.display_with(&interner);
+    Ok((program, rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatOptions, format_source, format_source_with};
+
+    fn fmt(source: &str) -> String {
+        format_source_with(source, &FormatOptions::default()).expect("source should format")
+    }
+
+    fn fmt_width(source: &str, max_width: usize) -> String {
+        format_source_with(source, &FormatOptions { max_width }).expect("source should format")
+    }
+
+    #[test]
+    fn removes_blank_line_before_function_close() {
+        let source = "fn main() with IO {\n    print(\"x\")\n\n}\n";
+        let formatted = format_source(source);
+        assert_eq!(formatted, "fn main() with IO {\n    print(\"x\")\n}\n");
+    }
+
+    #[test]
+    fn preserves_indentation_inside_block_comments() {
+        let source = "fn main() {\n/**\n * hello\n * world\n */\nprint(1)\n}\n";
+        let formatted = format_source(source);
+        assert_eq!(
+            formatted,
+            "fn main() {\n    /**\n     * hello\n     * world\n     */\n    print(1)\n}\n"
+        );
+    }
+
+    #[test]
+    fn normalizes_operator_and_comma_spacing() {
+        assert_eq!(
+            fmt("fn f(a:Int,b:Int)->Int{a+b*2-(a-1)}\n"),
+            "fn f(a: Int, b: Int) -> Int {\n    a + b * 2 - (a - 1)\n}\n"
+        );
+        assert_eq!(
+            fmt("let xs=[1,2,3]\nlet n=-xs[0]\n"),
+            "let xs = [1, 2, 3]\nlet n = -xs[0]\n"
+        );
+    }
+
+    #[test]
+    fn keeps_comments_and_collapses_blank_lines() {
+        let source = "// header\n\n\n\nlet a = 1 // one\n/* two */\nlet b = 2\n\n// tail\n";
+        assert_eq!(
+            fmt(source),
+            "// header\n\nlet a = 1 // one\n/* two */\nlet b = 2\n\n// tail\n"
+        );
+    }
+
+    #[test]
+    fn wraps_long_calls_one_argument_per_line() {
+        let source = "let total = combine(first_argument, second_argument, third_argument)\n";
+        assert_eq!(
+            fmt_width(source, 40),
+            "let total = combine(\n    first_argument,\n    second_argument,\n    third_argument\n)\n"
+        );
+        assert_eq!(fmt_width(source, 100), source);
+    }
+
+    #[test]
+    fn trailing_comma_keeps_a_list_broken() {
+        let source = "let xs = [\n    1,\n    2,\n]\n";
+        assert_eq!(fmt(source), source);
+        assert_eq!(fmt("let xs = [1, 2,]\n"), "let xs = [\n    1,\n    2,\n]\n");
+    }
+
+    #[test]
+    fn puts_match_arms_on_their_own_lines() {
+        let source = "fn f(x) { match x { 0 -> \"zero\", _ -> \"many\" } }\n";
+        assert_eq!(
+            fmt(source),
+            "fn f(x) {\n    match x {\n        0 -> \"zero\",\n        _ -> \"many\"\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn reflows_pipelines_that_do_not_fit() {
+        let source = "let result = items |> map(\\x -> x * 2) |> filter(\\x -> x > 10) |> sum\n";
+        assert_eq!(fmt_width(source, 100), source);
+        assert_eq!(
+            fmt_width(source, 40),
+            "let result = items\n    |> map(\\x -> x * 2)\n    |> filter(\\x -> x > 10)\n    |> sum\n"
+        );
+        assert_eq!(
+            fmt_width("let r = xs\n|> map(f)\n", 100),
+            "let r = xs |> map(f)\n"
+        );
+    }
+
+    #[test]
+    fn hugs_trailing_lambda_arguments() {
+        let source = "let ys = map(xs, \\x -> {\n    let y = x + 1\n    y * 2\n})\n";
+        assert_eq!(fmt(source), source);
+    }
+
+    #[test]
+    fn trailing_lambda_breaks_before_earlier_short_calls() {
+        let source = "let g = map2_gen(choice(n + 1), choice(2), \\(m, neg) -> if neg == 1 { 0 - m } else { m })\n";
+        assert_eq!(
+            fmt_width(source, 70),
+            "let g = map2_gen(choice(n + 1), choice(2), \\(m, neg) -> if neg == 1 {\n    0 - m\n} else {\n    m\n})\n"
+        );
+    }
+
+    #[test]
+    fn trailing_argument_list_breaks_before_an_earlier_pattern() {
+        let source = "fn f(x) { match x { Some(value) -> combine(first_argument, second_argument), _ -> 0 } }\n";
+        assert_eq!(
+            fmt_width(source, 40),
+            "fn f(x) {\n    match x {\n        Some(value) -> combine(\n            first_argument,\n            second_argument\n        ),\n        _ -> 0\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn long_signatures_break_parameters_before_the_return_type() {
+        let source = "fn pair(alpha: Int, beta: Int) -> (Int, Int) { (alpha, beta) }\n";
+        assert_eq!(
+            fmt_width(source, 40),
+            "fn pair(\n    alpha: Int,\n    beta: Int\n) -> (Int, Int) {\n    (alpha, beta)\n}\n"
+        );
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let source = "module Demo {\n  // helpers\n  public fn twice(f,x){f(f(x))}\n\n\n  fn pick(x) { if x > 0 { \"pos\" } else { \"neg\" } }\n}\n";
+        let once = fmt(source);
+        assert_eq!(fmt(&once), once);
+        assert_eq!(fmt_width(&fmt_width(source, 30), 30), fmt_width(source, 30));
+    }
+
+    #[test]
+    fn leaves_unparsable_sources_untouched() {
+        let source = "fn main( {\n";
+        assert!(format_source_with(source, &FormatOptions::default()).is_err());
+        assert_eq!(format_source(source), source);
+    }
+}
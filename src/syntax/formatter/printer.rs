@@ -0,0 +1,588 @@
+//! Builds the [`Doc`] for a [`SourceTree`] using the layout facts from the AST.
+
+use crate::syntax::token_type::TokenType;
+
+use super::{
+    cst::{Atom, Comment, Group, Node, SourceTree},
+    doc::Doc,
+    layout::{BraceLayout, Key, LayoutHints, key},
+};
+
+pub(super) struct Printer<'a> {
+    hints: &'a LayoutHints,
+}
+
+impl<'a> Printer<'a> {
+    pub(super) fn new(hints: &'a LayoutHints) -> Self {
+        Self { hints }
+    }
+
+    /// Lays out a whole file: top-level statements one per line, then end-of-file comments.
+    pub(super) fn program(&self, tree: &SourceTree, starts: &[Key]) -> Doc {
+        let chunks = split_at_starts(&tree.nodes, starts);
+        let mut parts = vec![self.sequence(&chunks)];
+        for (index, comment) in tree.trailing_comments.iter().enumerate() {
+            if index > 0 || !tree.nodes.is_empty() {
+                parts.push(Doc::HardLine);
+                if comment.blank_before {
+                    parts.push(Doc::HardLine);
+                }
+            }
+            parts.push(comment_doc(comment));
+        }
+        parts.push(Doc::HardLine);
+        Doc::Concat(parts)
+    }
+
+    /// Statements or items, one per line, keeping a single blank line where the source had
+    /// one or more.
+    fn sequence(&self, chunks: &[&[Node]]) -> Doc {
+        let mut parts = Vec::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            if index > 0 {
+                parts.push(Doc::HardLine);
+                if blank_before(&chunk[0]) {
+                    parts.push(Doc::HardLine);
+                }
+            }
+            parts.push(self.run(chunk));
+        }
+        Doc::Concat(parts)
+    }
+
+    /// One statement, item or list element. The run breaks before `|>` when it does not
+    /// fit; comments trailing its last token are printed after it.
+    fn run(&self, nodes: &[Node]) -> Doc {
+        let Some(last) = nodes.last() else {
+            return Doc::Nil;
+        };
+        let mut segments: Vec<&[Node]> = Vec::new();
+        let mut segment_start = 0;
+        for (index, node) in nodes.iter().enumerate() {
+            if index > 0 && node.is_atom(TokenType::Pipe) {
+                segments.push(&nodes[segment_start..index]);
+                segment_start = index;
+            }
+        }
+        segments.push(&nodes[segment_start..]);
+
+        let last_index = nodes.len() - 1;
+        let mut offset = 0;
+        let mut head = Doc::Nil;
+        let mut tail = Vec::new();
+        for (segment_index, segment) in segments.iter().enumerate() {
+            let doc = self.join(segment, offset, last_index, segment_index > 0);
+            if segment_index == 0 {
+                head = doc;
+            } else {
+                tail.push(Doc::line());
+                tail.push(doc);
+            }
+            offset += segment.len();
+        }
+        let body = if tail.is_empty() {
+            head
+        } else {
+            Doc::Concat(vec![head, Doc::nest(Doc::Concat(tail))])
+        };
+        Doc::Concat(vec![
+            leading_doc(nodes[0].first_atom(), true),
+            Doc::group(body),
+            trailing_doc(&last.last_atom().trailing),
+        ])
+    }
+
+    /// Concatenates nodes with the spacing their token kinds call for. `offset` is the
+    /// index of `nodes[0]` within its run.
+    fn join(&self, nodes: &[Node], offset: usize, last_index: usize, line_start: bool) -> Doc {
+        let mut parts = Vec::new();
+        let mut index = 0;
+        while index < nodes.len() {
+            if index > 0
+                && self.space_between(nodes[index - 1].last_atom(), nodes[index].first_atom())
+            {
+                parts.push(Doc::text(" "));
+            }
+            let chain_len = self.chain_len(&nodes[index..]);
+            if chain_len > 1 {
+                let chain = &nodes[index..index + chain_len];
+                parts.push(self.chain(chain, offset + index, last_index, index == 0 && line_start));
+                index += chain_len;
+            } else {
+                let at_line_start = index == 0 && line_start;
+                parts.push(self.run_node(&nodes[index], offset + index, last_index, at_line_start));
+                index += 1;
+            }
+        }
+        Doc::Concat(parts)
+    }
+
+    /// Number of nodes in the operator chain starting at `nodes[0]`, or 0.
+    fn chain_len(&self, nodes: &[Node]) -> usize {
+        let Some(end) = self.hints.operator_chains.get(&key(nodes[0].start())) else {
+            return 0;
+        };
+        nodes.partition_point(|node| key(node.start()) < *end)
+    }
+
+    /// An operator chain: one line when it fits, otherwise broken before each of its
+    /// outermost operators with the continuation lines indented.
+    fn chain(&self, nodes: &[Node], offset: usize, last_index: usize, line_start: bool) -> Doc {
+        let mut parts = Vec::new();
+        let mut split = None;
+        for (index, node) in nodes.iter().enumerate() {
+            if index > 0 {
+                if node.as_atom().is_some_and(|atom| self.breaks_before(atom)) {
+                    split.get_or_insert(parts.len());
+                    parts.push(Doc::line());
+                } else if self.space_between(nodes[index - 1].last_atom(), node.first_atom()) {
+                    parts.push(Doc::text(" "));
+                }
+            }
+            let at_line_start = index == 0 && line_start;
+            parts.push(self.run_node(node, offset + index, last_index, at_line_start));
+        }
+        match split {
+            Some(at) => {
+                let tail = parts.split_off(at);
+                Doc::group(Doc::Concat(vec![
+                    Doc::Concat(parts),
+                    Doc::nest(Doc::Concat(tail)),
+                ]))
+            }
+            None => Doc::Concat(parts),
+        }
+    }
+
+    fn breaks_before(&self, atom: &Atom) -> bool {
+        self.hints.chain_operators.contains(&key(atom.start))
+    }
+
+    /// A node at `position` within its run. The run's first node leaves its leading
+    /// comments, and its last node its trailing comments, to [`Printer::run`].
+    fn run_node(&self, node: &Node, position: usize, last_index: usize, line_start: bool) -> Doc {
+        let with_trailing = position != last_index;
+        if position == 0 {
+            self.node_body(node, with_trailing)
+        } else {
+            self.node(node, line_start, with_trailing)
+        }
+    }
+
+    fn node(&self, node: &Node, line_start: bool, with_trailing: bool) -> Doc {
+        Doc::Concat(vec![
+            leading_doc(node.first_atom(), line_start),
+            self.node_body(node, with_trailing),
+        ])
+    }
+
+    /// A node without its leading comments.
+    fn node_body(&self, node: &Node, with_trailing: bool) -> Doc {
+        match node {
+            Node::Atom(atom) => Doc::Concat(vec![
+                Doc::text(atom.text.clone()),
+                if with_trailing {
+                    trailing_doc(&atom.trailing)
+                } else {
+                    Doc::Nil
+                },
+            ]),
+            Node::Group(group) => Doc::Concat(vec![
+                self.group(group),
+                if with_trailing {
+                    trailing_doc(&group.close.trailing)
+                } else {
+                    Doc::Nil
+                },
+            ]),
+        }
+    }
+
+    fn group(&self, group: &Group) -> Doc {
+        let layout = match group.open.text.as_str() {
+            "{" => self.hints.braces.get(&key(group.open.start)),
+            _ => None,
+        };
+        match layout {
+            Some(BraceLayout::Block {
+                starts,
+                force_break,
+            }) => {
+                let chunks = split_at_starts(&group.children, starts);
+                self.lines(group, &chunks, *force_break)
+            }
+            Some(BraceLayout::Items {
+                starts,
+                force_break,
+            }) => {
+                let chunks = split_at_starts(&group.children, starts);
+                let comma_separated = chunks.len() > 1
+                    && chunks[..chunks.len() - 1]
+                        .iter()
+                        .all(|chunk| chunk.last().is_some_and(|n| n.is_atom(TokenType::Comma)));
+                if !force_break && (comma_separated || chunks.len() == 1) {
+                    self.list(group)
+                } else {
+                    self.lines(group, &chunks, true)
+                }
+            }
+            None => self.list(group),
+        }
+    }
+
+    /// A block or member list: `{`, one chunk per line, `}`. Collapses to one line when it
+    /// fits and `force_break` is off.
+    fn lines(&self, group: &Group, chunks: &[&[Node]], force_break: bool) -> Doc {
+        if chunks.is_empty() && !group.close.has_comments() {
+            return self.empty(group);
+        }
+        let mut inner = vec![Doc::line(), self.sequence(chunks)];
+        inner.push(dangling_doc(&group.close, !chunks.is_empty()));
+        let doc = Doc::Concat(vec![
+            Doc::text(group.open.text.clone()),
+            trailing_doc(&group.open.trailing),
+            if force_break {
+                Doc::BreakParent
+            } else {
+                Doc::Nil
+            },
+            Doc::nest(Doc::Concat(inner)),
+            Doc::line(),
+            Doc::text(group.close.text.clone()),
+        ]);
+        if self.hints.branch_blocks.contains(&key(group.open.start)) {
+            // Left to the enclosing run's group so `then` and `else` break together.
+            doc
+        } else {
+            Doc::group(doc)
+        }
+    }
+
+    /// A comma-separated list: arguments, parameters, literals, record fields.
+    fn list(&self, group: &Group) -> Doc {
+        let items = self.split_items(&group.children);
+        if items.is_empty() && !group.close.has_comments() {
+            return self.empty(group);
+        }
+        let pad = if group.open.text == "{" { " " } else { "" };
+        let trailing_comma = items.last().is_some_and(|item| item.1.is_some());
+
+        if let Some(doc) = self.hugged(group, &items, trailing_comma) {
+            return doc;
+        }
+        if self
+            .hints
+            .return_type_groups
+            .contains(&key(group.open.start))
+            && !trailing_comma
+            && !group.open.has_comments()
+            && !group.close.has_comments()
+            && !group.children.iter().any(Node::has_comments)
+        {
+            return self.inline(group, &items);
+        }
+
+        let mut inner = vec![Doc::Line { flat: pad }];
+        for (index, (nodes, comma)) in items.iter().enumerate() {
+            inner.push(self.run(nodes));
+            if let Some(comma) = comma {
+                inner.push(self.node(&Node::Atom((*comma).clone()), false, true));
+            }
+            if index + 1 < items.len() {
+                inner.push(Doc::line());
+            }
+        }
+        inner.push(dangling_doc(&group.close, !items.is_empty()));
+
+        Doc::group(Doc::Concat(vec![
+            Doc::text(group.open.text.clone()),
+            trailing_doc(&group.open.trailing),
+            if trailing_comma && (items.len() > 1 || group.open.text == "{") {
+                Doc::BreakParent
+            } else {
+                Doc::Nil
+            },
+            Doc::nest(Doc::Concat(inner)),
+            Doc::Line { flat: pad },
+            Doc::text(group.close.text.clone()),
+        ]))
+    }
+
+    /// `f(a, \x -> { ... })`: keeps the leading arguments on the call line and lets only the
+    /// final lambda, block or record break.
+    fn hugged(
+        &self,
+        group: &Group,
+        items: &[(&[Node], Option<&Atom>)],
+        trailing_comma: bool,
+    ) -> Option<Doc> {
+        let (last, _) = items.last()?;
+        let hug = group.open.text == "("
+            && !trailing_comma
+            && !group.open.has_comments()
+            && !group.close.has_comments()
+            && items[..items.len() - 1].iter().all(|(nodes, comma)| {
+                !nodes.iter().any(Node::has_comments)
+                    && comma.is_none_or(|comma| !comma.has_comments())
+            })
+            && ends_in_hug_target(last);
+        if !hug {
+            return None;
+        }
+        Some(self.inline(group, items))
+    }
+
+    /// The list on one line, with no breaks of its own.
+    fn inline(&self, group: &Group, items: &[(&[Node], Option<&Atom>)]) -> Doc {
+        let mut parts = vec![Doc::text(group.open.text.clone())];
+        for (nodes, comma) in items {
+            parts.push(self.run(nodes));
+            if comma.is_some() {
+                parts.push(Doc::text(", "));
+            }
+        }
+        parts.push(Doc::text(group.close.text.clone()));
+        Doc::Concat(parts)
+    }
+
+    /// Splits a list body at top-level commas, skipping those inside type arguments such as
+    /// `Map<k, v>`. Each item keeps the comma that ends it.
+    fn split_items<'n>(&self, nodes: &'n [Node]) -> Vec<(&'n [Node], Option<&'n Atom>)> {
+        let mut items = Vec::new();
+        let mut start = 0;
+        let mut type_depth = 0usize;
+        for (index, node) in nodes.iter().enumerate() {
+            let Some(atom) = node.as_atom() else {
+                continue;
+            };
+            if self.is_type_bracket(atom, TokenType::Lt) {
+                type_depth += 1;
+            } else if self.is_type_bracket(atom, TokenType::Gt) {
+                type_depth = type_depth.saturating_sub(1);
+            } else if atom.is(TokenType::Comma) && type_depth == 0 {
+                items.push((&nodes[start..index], Some(atom)));
+                start = index + 1;
+            }
+        }
+        if start < nodes.len() {
+            items.push((&nodes[start..], None));
+        }
+        items
+    }
+
+    fn empty(&self, group: &Group) -> Doc {
+        Doc::Concat(vec![
+            Doc::text(group.open.text.clone()),
+            trailing_doc(&group.open.trailing),
+            Doc::text(group.close.text.clone()),
+        ])
+    }
+
+    fn space_between(&self, prev: &Atom, next: &Atom) -> bool {
+        use TokenType as T;
+
+        if matches!(prev.kind, T::Comma | T::Semicolon) {
+            return true;
+        }
+        if matches!(next.kind, T::Comma | T::Semicolon | T::Dot | T::Colon) {
+            return false;
+        }
+        if matches!(
+            prev.kind,
+            T::Dot | T::DotDotDot | T::Backslash | T::At | T::Hash | T::Bang
+        ) || (prev.is(T::Minus) && !self.is_binary(prev))
+            || self.is_type_bracket(prev, T::Lt)
+            || self.is_type_bracket(next, T::Gt)
+        {
+            return false;
+        }
+        if prev.is(T::Question) && next.is(T::Ident) {
+            // `?name` is one hole; `? name` is a hole followed by a name.
+            return prev.end != next.start;
+        }
+        if self.is_type_bracket(next, T::Lt) {
+            return !prev.is(T::Ident);
+        }
+        match next.text.as_str() {
+            "(" => !self.is_callee(prev),
+            "[" => {
+                !matches!(prev.text.as_str(), ")" | "]")
+                    && !prev.is(T::Ident)
+                    && !prev.is(T::String)
+            }
+            _ => true,
+        }
+    }
+
+    fn is_binary(&self, atom: &Atom) -> bool {
+        self.hints.binary_operators.contains(&key(atom.start))
+    }
+
+    /// `<`/`>` delimiting type arguments rather than comparing.
+    fn is_type_bracket(&self, atom: &Atom, kind: TokenType) -> bool {
+        atom.is(kind) && !self.is_binary(atom)
+    }
+
+    /// Whether `(` right after `prev` is a call or parameter list rather than a group.
+    fn is_callee(&self, prev: &Atom) -> bool {
+        use TokenType as T;
+        match prev.kind {
+            T::Ident => !matches!(prev.text.as_str(), "exposing" | "except"),
+            T::Some | T::None | T::Left | T::Right | T::Fn => true,
+            T::Gt => !self.is_binary(prev),
+            _ => matches!(prev.text.as_str(), ")" | "]"),
+        }
+    }
+}
+
+/// Splits `nodes` into chunks beginning at each of `starts`. A chunk also takes the
+/// modifiers the AST spans leave out (`public`, `intrinsic`, `fn`, `@fip`).
+fn split_at_starts<'n>(nodes: &'n [Node], starts: &[Key]) -> Vec<&'n [Node]> {
+    let mut boundaries = vec![0];
+    for start in starts.iter().skip(1) {
+        let mut index = nodes.partition_point(|node| key(node.end()) <= *start);
+        let floor = *boundaries.last().expect("starts with 0");
+        while index > floor + 1 {
+            if is_modifier(&nodes[index - 1]) {
+                index -= 1;
+            } else if index > floor + 2
+                && nodes[index - 1].is_atom(TokenType::Ident)
+                && nodes[index - 2].is_atom(TokenType::At)
+            {
+                index -= 2;
+            } else {
+                break;
+            }
+        }
+        if index > floor && index < nodes.len() {
+            boundaries.push(index);
+        }
+    }
+    boundaries.push(nodes.len());
+    boundaries
+        .windows(2)
+        .map(|pair| &nodes[pair[0]..pair[1]])
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+fn is_modifier(node: &Node) -> bool {
+    node.is_atom(TokenType::Public)
+        || node.is_atom(TokenType::Intrinsic)
+        || node.is_atom(TokenType::Fn)
+}
+
+/// Whether the last call argument is a lambda, function literal or block-like expression
+/// that can break on its own.
+fn ends_in_hug_target(nodes: &[Node]) -> bool {
+    let Some(first) = nodes.first() else {
+        return false;
+    };
+    let opens_block = nodes
+        .last()
+        .is_some_and(|node| matches!(node, Node::Group(group) if group.open.text == "{"));
+    opens_block
+        && (first.is_atom(TokenType::Backslash)
+            || first.is_atom(TokenType::Fn)
+            || first.is_atom(TokenType::Do)
+            || first.is_atom(TokenType::Match)
+            || first.is_atom(TokenType::If)
+            || nodes.len() == 1)
+}
+
+fn blank_before(node: &Node) -> bool {
+    let atom = node.first_atom();
+    match atom.leading.first() {
+        Some(comment) => comment.blank_before,
+        None => atom.blank_before,
+    }
+}
+
+/// Comments on the lines before an atom. At a line start they are printed in place; in
+/// the middle of a run they move the atom onto its own indented line.
+fn leading_doc(atom: &Atom, line_start: bool) -> Doc {
+    if atom.leading.is_empty() {
+        return Doc::Nil;
+    }
+    let mut parts = Vec::new();
+    if !line_start {
+        parts.push(Doc::HardLine);
+    }
+    for (index, comment) in atom.leading.iter().enumerate() {
+        if index > 0 {
+            let previous = &atom.leading[index - 1];
+            if previous.newline_after {
+                parts.push(Doc::HardLine);
+                if comment.blank_before {
+                    parts.push(Doc::HardLine);
+                }
+            } else {
+                parts.push(Doc::text(" "));
+            }
+        }
+        parts.push(comment_doc(comment));
+    }
+    let last = atom.leading.last().expect("checked non-empty");
+    if last.newline_after {
+        parts.push(Doc::HardLine);
+        if atom.blank_before {
+            parts.push(Doc::HardLine);
+        }
+    } else {
+        parts.push(Doc::text(" "));
+    }
+    if line_start {
+        Doc::Concat(parts)
+    } else {
+        Doc::nest(Doc::Concat(parts))
+    }
+}
+
+/// Comments after an atom on the same line. Line comments are deferred to the end of the
+/// output line and force the enclosing groups to break.
+fn trailing_doc(comments: &[Comment]) -> Doc {
+    let mut parts = Vec::new();
+    for comment in comments {
+        if comment.newline_after {
+            parts.push(Doc::LineSuffix(format!(" {}", comment.text)));
+            parts.push(Doc::BreakParent);
+        } else {
+            parts.push(Doc::text(format!(" {}", comment.text)));
+        }
+    }
+    Doc::Concat(parts)
+}
+
+/// Comments before a closing bracket, printed inside the indented body.
+fn dangling_doc(close: &Atom, after_content: bool) -> Doc {
+    if close.leading.is_empty() {
+        return Doc::Nil;
+    }
+    let mut parts = Vec::new();
+    if after_content {
+        parts.push(Doc::HardLine);
+        if close.leading[0].blank_before {
+            parts.push(Doc::HardLine);
+        }
+    }
+    for (index, comment) in close.leading.iter().enumerate() {
+        if index > 0 {
+            parts.push(Doc::HardLine);
+            if comment.blank_before {
+                parts.push(Doc::HardLine);
+            }
+        }
+        parts.push(comment_doc(comment));
+    }
+    if close.leading.iter().any(|comment| comment.newline_after) {
+        parts.push(Doc::BreakParent);
+    }
+    Doc::Concat(parts)
+}
+
+fn comment_doc(comment: &Comment) -> Doc {
+    Doc::Comment {
+        text: comment.text.clone(),
+        source_column: comment.source_column,
+    }
+}
@@ -447,6 +447,7 @@ impl Lexer {
             b'.' => (TokenType::Dot, "."),
             b'#' => (TokenType::Hash, "#"),
             b'@' => (TokenType::At, "@"),
+            b'?' => (TokenType::Question, "?"),
             b'\\' => (TokenType::Backslash, "\\"),
             b'|' => (TokenType::Bar, "|"),
             _ => return None,
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use crate::{
     ast::{Visitor, complexity::analyze_complexity, visit},
     diagnostics::{
-        Diagnostic, DiagnosticBuilder,
+        Applicability, Diagnostic, DiagnosticBuilder, InlineSuggestion, Severity,
         position::{Position, Span},
     },
     syntax::{
@@ -34,18 +34,81 @@ struct BindingInfo {
     kind: BindingKind,
 }
 
+/// Every code the linter can report.
+pub const LINT_CODES: &[&str] = &[
+    "W001", "W002", "W003", "W004", "W005", "W006", "W007", "W008", "W009", "W010", "W011", "W012",
+    "W013",
+];
+
+/// How a lint is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LintLevel {
+    /// The lint is not reported.
+    Allow,
+    /// The lint is reported as a warning.
+    #[default]
+    Warn,
+    /// The lint is reported as an error, and `flux lint` fails.
+    Deny,
+}
+
+/// Limits for the complexity lints W009 to W012. A function is reported
+/// when it exceeds the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LintThresholds {
+    /// W009: lines in a function body.
+    pub max_function_lines: usize,
+    /// W010: parameters of a function.
+    pub max_function_params: usize,
+    /// W011: cyclomatic complexity of a function.
+    pub max_cyclomatic_complexity: usize,
+    /// W012: nesting depth of a function body.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for LintThresholds {
+    fn default() -> Self {
+        Self {
+            max_function_lines: 50,
+            max_function_params: 5,
+            max_cyclomatic_complexity: 10,
+            max_nesting_depth: 4,
+        }
+    }
+}
+
+/// Per-code lint levels and complexity thresholds applied by [`Linter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintPolicy {
+    levels: HashMap<String, LintLevel>,
+    pub thresholds: LintThresholds,
+}
+
+impl LintPolicy {
+    /// Sets the level of one lint code; a later call for the same code wins.
+    pub fn set_level(&mut self, code: impl Into<String>, level: LintLevel) {
+        self.levels.insert(code.into(), level);
+    }
+
+    /// Returns the level of a lint code, [`LintLevel::Warn`] unless configured.
+    pub fn level(&self, code: &str) -> LintLevel {
+        self.levels.get(code).copied().unwrap_or_default()
+    }
+}
+
+/// Returns whether `code` is one of [`LINT_CODES`].
+pub fn is_lint_code(code: &str) -> bool {
+    LINT_CODES.contains(&code)
+}
+
 pub struct Linter<'a> {
     scopes: Vec<HashMap<Symbol, BindingInfo>>,
     warnings: Vec<Diagnostic>,
     file: Option<String>,
     interner: &'a Interner,
+    policy: LintPolicy,
 }
 
-const MAX_FUNCTION_LINES: usize = 50;
-const MAX_FUNCTION_PARAMS: usize = 5;
-const MAX_CYCLOMATIC_COMPLEXITY: usize = 10;
-const MAX_NESTING_DEPTH: usize = 4;
-
 impl<'a> Linter<'a> {
     pub fn new(file: Option<String>, interner: &'a Interner) -> Self {
         Self {
@@ -53,9 +116,16 @@ impl<'a> Linter<'a> {
             warnings: Vec::new(),
             file,
             interner,
+            policy: LintPolicy::default(),
         }
     }
 
+    /// Applies lint levels and thresholds instead of the defaults.
+    pub fn with_policy(mut self, policy: LintPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     pub fn lint(mut self, program: &Program) -> Vec<Diagnostic> {
         // Analyze complexity metrics for all functions
         let complexity_metrics = analyze_complexity(program);
@@ -222,6 +292,22 @@ impl<'a> Linter<'a> {
     }
 
     fn push_warning(&mut self, title: &str, code: &str, position: Position, message: String) {
+        let diag = self.warning(title, code, position, message);
+        self.report(diag);
+    }
+
+    /// Records a lint at the level the policy gives its code: dropped when
+    /// allowed and turned into an error when denied.
+    fn report(&mut self, mut diag: Diagnostic) {
+        match self.policy.level(diag.code().unwrap_or_default()) {
+            LintLevel::Allow => return,
+            LintLevel::Warn => {}
+            LintLevel::Deny => diag.severity = Severity::Error,
+        }
+        self.warnings.push(diag);
+    }
+
+    fn warning(&self, title: &str, code: &str, position: Position, message: String) -> Diagnostic {
         let mut diag = Diagnostic::warning(title)
             .with_code(code)
             .with_message(message)
@@ -229,7 +315,7 @@ impl<'a> Linter<'a> {
         if let Some(file) = &self.file {
             diag = diag.with_file(file.clone());
         }
-        self.warnings.push(diag);
+        diag
     }
 
     /// W013: Warn when a `with` clause uses `+` to separate concrete atoms
@@ -239,17 +325,26 @@ impl<'a> Linter<'a> {
     fn check_effect_separator_style(&mut self, effects: &[EffectExpr]) {
         for effect in effects {
             if effect.contains_add() && !effect.contains_subtract() && !effect.is_open() {
-                self.push_warning(
-                    "EFFECT ROW SEPARATOR STYLE",
-                    "W013",
-                    effect.span().start,
-                    format!(
-                        "Effect row `{}` uses `+` without subtraction; use `,` to separate \
-                         effects in `with` clauses. Reserve `+`/`-` for row arithmetic \
-                         (e.g. `A + B - B` or `A + B | e`).",
-                        effect.display_with(self.interner)
-                    ),
-                );
+                // Only named atoms joined by `+` reach here, so the comma list is
+                // the same row.
+                let row = effect.display_with(self.interner);
+                let diag = self
+                    .warning(
+                        "EFFECT ROW SEPARATOR STYLE",
+                        "W013",
+                        effect.span().start,
+                        format!(
+                            "Effect row `{row}` uses `+` without subtraction; use `,` to separate \
+                             effects in `with` clauses. Reserve `+`/`-` for row arithmetic \
+                             (e.g. `A + B - B` or `A + B | e`)."
+                        ),
+                    )
+                    .with_suggestion(
+                        InlineSuggestion::new(effect.span(), row.replace(" + ", ", "))
+                            .with_message("Separate effects with `,`")
+                            .with_applicability(Applicability::MachineApplicable),
+                    );
+                self.report(diag);
             }
         }
     }
@@ -291,28 +386,30 @@ impl<'a> Linter<'a> {
             "<anonymous>".to_string()
         };
 
+        let thresholds = self.policy.thresholds;
+
         // W011: High cyclomatic complexity
-        if metrics.cyclomatic_complexity > MAX_CYCLOMATIC_COMPLEXITY {
+        if metrics.cyclomatic_complexity > thresholds.max_cyclomatic_complexity {
             self.push_warning(
                 "HIGH CYCLOMATIC COMPLEXITY",
                 "W011",
                 metrics.span.start,
                 format!(
                     "Function `{}` has cyclomatic complexity {} (max {}).",
-                    name_str, metrics.cyclomatic_complexity, MAX_CYCLOMATIC_COMPLEXITY
+                    name_str, metrics.cyclomatic_complexity, thresholds.max_cyclomatic_complexity
                 ),
             );
         }
 
         // W012: Deep nesting
-        if metrics.max_nesting_depth > MAX_NESTING_DEPTH {
+        if metrics.max_nesting_depth > thresholds.max_nesting_depth {
             self.push_warning(
                 "DEEP NESTING",
                 "W012",
                 metrics.span.start,
                 format!(
                     "Function `{}` has nesting depth {} (max {}).",
-                    name_str, metrics.max_nesting_depth, MAX_NESTING_DEPTH
+                    name_str, metrics.max_nesting_depth, thresholds.max_nesting_depth
                 ),
             );
         }
@@ -325,7 +422,8 @@ impl<'a> Linter<'a> {
         body: &crate::syntax::block::Block,
         position: Position,
     ) {
-        if parameters.len() > MAX_FUNCTION_PARAMS {
+        let thresholds = self.policy.thresholds;
+        if parameters.len() > thresholds.max_function_params {
             let label = name.map(|n| self.sym(n)).unwrap_or("<anonymous>");
             self.push_warning(
                 "TOO MANY PARAMETERS",
@@ -335,13 +433,13 @@ impl<'a> Linter<'a> {
                     "Function `{}` has {} parameters (max {}).",
                     label,
                     parameters.len(),
-                    MAX_FUNCTION_PARAMS
+                    thresholds.max_function_params
                 ),
             );
         }
 
         let line_count = span_line_count(body.span());
-        if line_count > MAX_FUNCTION_LINES {
+        if line_count > thresholds.max_function_lines {
             let label = name.map(|n| self.sym(n)).unwrap_or("<anonymous>");
             self.push_warning(
                 "FUNCTION TOO LONG",
@@ -349,7 +447,7 @@ impl<'a> Linter<'a> {
                 position,
                 format!(
                     "Function `{}` is {} lines long (max {}).",
-                    label, line_count, MAX_FUNCTION_LINES
+                    label, line_count, thresholds.max_function_lines
                 ),
             );
         }
@@ -1,9 +1,9 @@
 use crate::{
     diagnostics::{
-        DiagnosticBuilder, DiagnosticCategory, invalid_pattern, lambda_syntax_error,
-        match_fat_arrow, match_pipe_separator, missing_array_close_bracket,
-        missing_comprehension_close_bracket, missing_do_block_brace, missing_hash_close_brace,
-        missing_lambda_arrow, missing_match_arrow, pipe_target_error,
+        Applicability, DiagnosticBuilder, DiagnosticCategory, invalid_pattern, match_fat_arrow,
+        match_pipe_separator, missing_array_close_bracket, missing_comprehension_close_bracket,
+        missing_do_block_brace, missing_hash_close_brace, missing_lambda_arrow,
+        missing_match_arrow, pipe_target_error,
         position::{Position, Span},
         quality::missing_syntax_token_diagnostic_with_origin,
         unclosed_delimiter, unexpected_token, unexpected_token_with_details, unknown_keyword_alias,
@@ -215,6 +215,7 @@ impl Parser {
             TokenType::Do => self.parse_do_block_expression(),
             TokenType::Fn => self.parse_function_literal(),
             TokenType::Backslash => self.parse_lambda(),
+            TokenType::Question => self.parse_hole(),
             token if prefix_op(token).is_some() => self.parse_prefix_expression(),
             _ => {
                 self.no_prefix_parse_error();
@@ -1294,6 +1295,7 @@ impl Parser {
                     &self.peek_token.literal,
                     "else if",
                     "chained conditionals",
+                    Applicability::MachineApplicable,
                 )
                 .with_hint_text("Replace `elif`/`elsif` with `else if`."),
             );
@@ -2172,7 +2174,7 @@ impl Parser {
         })
     }
 
-    /// Parse a lambda expression: \x -> expr, \(x, y) -> expr, \() -> expr
+    /// Parse a lambda expression: \x -> expr, \(x, y) -> expr, \() -> expr, \-> expr
     pub(super) fn parse_lambda(&mut self) -> Option<Expression> {
         debug_assert!(self.is_current_token(TokenType::Backslash));
         let _lambda_context = self.enter_parser_context(ParserContext::Lambda);
@@ -2186,11 +2188,8 @@ impl Parser {
             // Parenthesized parameters: \() -> or \(x) -> or \(x, y) ->
             self.parse_typed_function_parameters(ParameterListContext::Lambda)?
         } else if self.is_current_token(TokenType::Arrow) {
-            self.emit_parser_diagnostic(lambda_syntax_error(
-                self.current_token.span(),
-                "Expected parameter or `(` after `\\`.",
-            ));
-            return None;
+            // Parameterless shorthand: \-> expr is \() -> expr
+            (Vec::new(), Vec::new())
         } else {
             // Single unparenthesized parameter: \x ->
             // Validate using the same identifier checks used by parenthesized
@@ -2264,6 +2263,24 @@ impl Parser {
         })
     }
 
+    /// Parse a typed hole: `?name`, or `?` alone. The name must follow the
+    /// `?` directly, so `? x` is an anonymous hole followed by `x`.
+    pub(super) fn parse_hole(&mut self) -> Option<Expression> {
+        let start = self.current_token.position;
+        let mut name = None;
+        if self.is_peek_token(TokenType::Ident)
+            && self.peek_token.position == self.current_token.end_position
+        {
+            self.next_token();
+            name = self.current_token.symbol;
+        }
+        Some(Expression::Hole {
+            name,
+            span: Span::new(start, self.current_token.end_position),
+            id: self.next_expr_id(),
+        })
+    }
+
     // Option/Either expressions
     pub(super) fn parse_none(&mut self) -> Option<Expression> {
         let start = self.current_token.position;
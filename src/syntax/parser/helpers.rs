@@ -313,6 +313,7 @@ impl Parser {
                 | TokenType::Fn
                 | TokenType::Match
                 | TokenType::Backslash
+                | TokenType::Question
         )
     }
 
@@ -1,10 +1,11 @@
 use crate::{
     core::CorePrimOp,
     diagnostics::{
-        DiagnosticBuilder, DiagnosticCategory, compiler_errors::DATA_MIXED_FIELD_FORMS,
-        diagnostic_for, missing_fn_param_list, missing_function_body_brace, missing_let_assign,
-        orphan_constructor_pattern, position::Span, unexpected_end_keyword, unexpected_token,
-        unexpected_token_with_details, unknown_keyword, unknown_keyword_alias,
+        Applicability, DiagnosticBuilder, DiagnosticCategory, InlineSuggestion,
+        compiler_errors::DATA_MIXED_FIELD_FORMS, diagnostic_for, missing_fn_param_list,
+        missing_function_body_brace, missing_let_assign, orphan_constructor_pattern,
+        position::Span, unexpected_end_keyword, unexpected_token, unexpected_token_with_details,
+        unknown_keyword, unknown_keyword_alias,
     },
     syntax::{
         data_variant::DataVariant,
@@ -134,6 +135,7 @@ impl Parser {
                     "def",
                     "fn",
                     "function declarations",
+                    Applicability::MachineApplicable,
                 ));
                 None
             }
@@ -146,6 +148,7 @@ impl Parser {
                     &self.current_token.literal,
                     "let",
                     "bindings",
+                    Applicability::MachineApplicable,
                 ));
                 None
             }
@@ -160,6 +163,7 @@ impl Parser {
                     &self.current_token.literal,
                     "match",
                     "pattern matching",
+                    Applicability::MaybeIncorrect,
                 ));
                 None
             }
@@ -175,7 +179,15 @@ impl Parser {
                             "Unknown keyword `{}`. Flux uses `fn` for function declarations.",
                             self.current_token.literal
                         ))
-                        .with_hint_text("Did you mean `fn`?"),
+                        .with_hint_text("Did you mean `fn`?")
+                        .with_suggestion(
+                            InlineSuggestion::new(self.current_token.span(), "fn")
+                                .with_message(format!(
+                                    "Replace `{}` with `fn`",
+                                    self.current_token.literal
+                                ))
+                                .with_applicability(Applicability::MachineApplicable),
+                        ),
                 );
                 None
             }
@@ -229,6 +241,7 @@ impl Parser {
                         &ident_name,
                         "else if",
                         "chained conditionals",
+                        Applicability::MachineApplicable,
                     )
                     .with_hint_text("Replace `elif`/`elsif` with `else if`."),
                 );
@@ -1796,12 +1809,13 @@ impl Parser {
                 break;
             }
         }
-        // Consume `)`
-        if !self.expect_peek_context(
-            TokenType::RParen,
-            "Expected `)` after method parameters.".to_string(),
-            "".to_string(),
-        ) && !self.is_current_token(TokenType::RParen)
+        // Consume `)`, unless the list was empty and we are already on it.
+        if !self.is_current_token(TokenType::RParen)
+            && !self.expect_peek_context(
+                TokenType::RParen,
+                "Expected `)` after method parameters.".to_string(),
+                "".to_string(),
+            )
         {
             return None;
         }
@@ -2003,12 +2017,13 @@ impl Parser {
                 break;
             }
         }
-        // Consume `)`
-        if !self.expect_peek_context(
-            TokenType::RParen,
-            "Expected `)` after method parameters.".to_string(),
-            "".to_string(),
-        ) && !self.is_current_token(TokenType::RParen)
+        // Consume `)`, unless the list was empty and we are already on it.
+        if !self.is_current_token(TokenType::RParen)
+            && !self.expect_peek_context(
+                TokenType::RParen,
+                "Expected `)` after method parameters.".to_string(),
+                "".to_string(),
+            )
         {
             return None;
         }
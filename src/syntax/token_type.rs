@@ -112,6 +112,7 @@ define_tokens! {
         DotDotDot => "...",
         Hash      => "#",
         At        => "@",
+        Question  => "?",
         Arrow     => "->",
         FatArrow  => "=>",
         LeftArrow => "<-",
@@ -54,6 +54,7 @@ pub fn generate_dispatch_functions(
         interner,
         &mut generated,
         &mut dispatch_table,
+        None,
     );
     if needs_builtin_dispatch_support(statements) {
         generate_builtin_instance_functions(
@@ -310,6 +311,7 @@ fn generate_from_statements(
     interner: &mut Interner,
     generated: &mut Vec<Statement>,
     dispatch_table: &mut HashSet<(Identifier, Identifier)>,
+    module_scope: Option<&ModuleScope>,
 ) {
     fn resolve_instance_class_def<'a>(
         class_env: &'a ClassEnv,
@@ -389,16 +391,26 @@ fn generate_from_statements(
                     let mangled_sym = interner.intern(&mangled);
 
                     let context_params = context_dict_param_names(context, interner);
+                    let value_parameters = explicit_method
+                        .map(|method| method.params.clone())
+                        .unwrap_or_else(|| method_sig.param_names.clone());
+                    // Parameters shadow module functions of the same name.
+                    let method_scope = module_scope.map(|scope| ModuleScope {
+                        functions: scope
+                            .functions
+                            .iter()
+                            .filter(|(name, _)| !value_parameters.contains(name))
+                            .map(|(name, qualified)| (*name, *qualified))
+                            .collect(),
+                    });
                     let body = rewrite_instance_self_calls_block(
                         body,
                         method_sig.name,
                         mangled_sym,
                         &context_params,
+                        method_scope.as_ref(),
                     );
                     let mut parameters = context_params.clone();
-                    let value_parameters = explicit_method
-                        .map(|method| method.params.clone())
-                        .unwrap_or_else(|| method_sig.param_names.clone());
                     parameters.extend(value_parameters);
 
                     let mut parameter_types: Vec<Option<TypeExpr>> = vec![None; context.len()];
@@ -454,13 +466,29 @@ fn generate_from_statements(
                     dispatch_table.insert((resolved_class_name, method_sig.name));
                 }
             }
-            Statement::Module { body, .. } => {
+            Statement::Module { name, body, .. } => {
+                let scope = ModuleScope {
+                    functions: body
+                        .statements
+                        .iter()
+                        .filter_map(|statement| match statement {
+                            Statement::Function {
+                                name: function_name,
+                                ..
+                            } => {
+                                Some((*function_name, interner.intern_join(*name, *function_name)))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                };
                 generate_from_statements(
                     &body.statements,
                     class_env,
                     interner,
                     generated,
                     dispatch_table,
+                    Some(&scope),
                 );
             }
             _ => {}
@@ -495,11 +523,21 @@ fn context_dict_param_names(
         .collect()
 }
 
+/// The module an instance is declared in. Generated instance functions live
+/// at the top level, so calls to the module's own functions from the
+/// instance body name them by their qualified symbol (`Module.function`),
+/// which also reaches private helpers.
+struct ModuleScope {
+    /// Short function name to its qualified symbol.
+    functions: HashMap<Identifier, Identifier>,
+}
+
 fn rewrite_instance_self_calls(
     expr: Expression,
     method_name: Identifier,
     mangled_name: Identifier,
     context_params: &[Identifier],
+    module_scope: Option<&ModuleScope>,
 ) -> Expression {
     match expr {
         Expression::Call {
@@ -508,15 +546,30 @@ fn rewrite_instance_self_calls(
             span,
             id,
         } => {
-            let function =
-                rewrite_instance_self_calls(*function, method_name, mangled_name, context_params);
+            let function = rewrite_instance_self_calls(
+                *function,
+                method_name,
+                mangled_name,
+                context_params,
+                module_scope,
+            );
             let mut arguments = arguments
                 .into_iter()
                 .map(|arg| {
-                    rewrite_instance_self_calls(arg, method_name, mangled_name, context_params)
+                    rewrite_instance_self_calls(
+                        arg,
+                        method_name,
+                        mangled_name,
+                        context_params,
+                        module_scope,
+                    )
                 })
                 .collect::<Vec<_>>();
-            if matches!(function, Expression::Identifier { name, .. } if name == method_name) {
+            // A zero-argument call names the context's instance (as in
+            // `gen_list(arbitrary())`), so only calls with arguments recurse.
+            if !arguments.is_empty()
+                && matches!(function, Expression::Identifier { name, .. } if name == method_name)
+            {
                 let mut rewritten_args = context_params
                     .iter()
                     .map(|name| Expression::Identifier {
@@ -537,6 +590,22 @@ fn rewrite_instance_self_calls(
                     id,
                 };
             }
+            let function = match function {
+                Expression::Identifier {
+                    name,
+                    span: function_span,
+                    id: function_id,
+                } if module_scope.is_some_and(|scope| scope.functions.contains_key(&name)) => {
+                    Expression::Identifier {
+                        name: module_scope
+                            .and_then(|scope| scope.functions.get(&name).copied())
+                            .unwrap_or(name),
+                        span: function_span,
+                        id: function_id,
+                    }
+                }
+                function => function,
+            };
             Expression::Call {
                 function: Box::new(function),
                 arguments,
@@ -556,6 +625,7 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             operator,
             right: Box::new(rewrite_instance_self_calls(
@@ -563,6 +633,7 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             span,
             id,
@@ -578,6 +649,7 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             arms: arms
                 .into_iter()
@@ -588,6 +660,7 @@ fn rewrite_instance_self_calls(
                             method_name,
                             mangled_name,
                             context_params,
+                            module_scope,
                         )
                     });
                     arm.body = rewrite_instance_self_calls(
@@ -595,6 +668,7 @@ fn rewrite_instance_self_calls(
                         method_name,
                         mangled_name,
                         context_params,
+                        module_scope,
                     );
                     arm
                 })
@@ -614,15 +688,23 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             consequence: rewrite_instance_self_calls_block(
                 consequence,
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             ),
             alternative: alternative.map(|block| {
-                rewrite_instance_self_calls_block(block, method_name, mangled_name, context_params)
+                rewrite_instance_self_calls_block(
+                    block,
+                    method_name,
+                    mangled_name,
+                    context_params,
+                    module_scope,
+                )
             }),
             span,
             id,
@@ -633,6 +715,7 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             ),
             span,
             id,
@@ -640,7 +723,15 @@ fn rewrite_instance_self_calls(
         Expression::ListLiteral { elements, span, id } => Expression::ListLiteral {
             elements: elements
                 .into_iter()
-                .map(|e| rewrite_instance_self_calls(e, method_name, mangled_name, context_params))
+                .map(|e| {
+                    rewrite_instance_self_calls(
+                        e,
+                        method_name,
+                        mangled_name,
+                        context_params,
+                        module_scope,
+                    )
+                })
                 .collect(),
             span,
             id,
@@ -648,7 +739,15 @@ fn rewrite_instance_self_calls(
         Expression::ArrayLiteral { elements, span, id } => Expression::ArrayLiteral {
             elements: elements
                 .into_iter()
-                .map(|e| rewrite_instance_self_calls(e, method_name, mangled_name, context_params))
+                .map(|e| {
+                    rewrite_instance_self_calls(
+                        e,
+                        method_name,
+                        mangled_name,
+                        context_params,
+                        module_scope,
+                    )
+                })
                 .collect(),
             span,
             id,
@@ -656,7 +755,15 @@ fn rewrite_instance_self_calls(
         Expression::TupleLiteral { elements, span, id } => Expression::TupleLiteral {
             elements: elements
                 .into_iter()
-                .map(|e| rewrite_instance_self_calls(e, method_name, mangled_name, context_params))
+                .map(|e| {
+                    rewrite_instance_self_calls(
+                        e,
+                        method_name,
+                        mangled_name,
+                        context_params,
+                        module_scope,
+                    )
+                })
                 .collect(),
             span,
             id,
@@ -672,12 +779,14 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             index: Box::new(rewrite_instance_self_calls(
                 *index,
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             span,
             id,
@@ -688,6 +797,7 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             span,
             id,
@@ -703,12 +813,14 @@ fn rewrite_instance_self_calls(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             tail: Box::new(rewrite_instance_self_calls(
                 *tail,
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             )),
             span,
             id,
@@ -722,13 +834,20 @@ fn rewrite_instance_self_calls_block(
     method_name: Identifier,
     mangled_name: Identifier,
     context_params: &[Identifier],
+    module_scope: Option<&ModuleScope>,
 ) -> Block {
     Block {
         statements: block
             .statements
             .into_iter()
             .map(|stmt| {
-                rewrite_instance_self_calls_stmt(stmt, method_name, mangled_name, context_params)
+                rewrite_instance_self_calls_stmt(
+                    stmt,
+                    method_name,
+                    mangled_name,
+                    context_params,
+                    module_scope,
+                )
             })
             .collect(),
         span: block.span,
@@ -740,6 +859,7 @@ fn rewrite_instance_self_calls_stmt(
     method_name: Identifier,
     mangled_name: Identifier,
     context_params: &[Identifier],
+    module_scope: Option<&ModuleScope>,
 ) -> Statement {
     match stmt {
         Statement::Let {
@@ -752,12 +872,24 @@ fn rewrite_instance_self_calls_stmt(
             is_public,
             name,
             type_annotation,
-            value: rewrite_instance_self_calls(value, method_name, mangled_name, context_params),
+            value: rewrite_instance_self_calls(
+                value,
+                method_name,
+                mangled_name,
+                context_params,
+                module_scope,
+            ),
             span,
         },
         Statement::Return { value, span } => Statement::Return {
             value: value.map(|value| {
-                rewrite_instance_self_calls(value, method_name, mangled_name, context_params)
+                rewrite_instance_self_calls(
+                    value,
+                    method_name,
+                    mangled_name,
+                    context_params,
+                    module_scope,
+                )
             }),
             span,
         },
@@ -771,6 +903,7 @@ fn rewrite_instance_self_calls_stmt(
                 method_name,
                 mangled_name,
                 context_params,
+                module_scope,
             ),
             has_semicolon,
             span,
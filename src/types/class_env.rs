@@ -1257,6 +1257,39 @@ impl ClassEnv {
         Some(first)
     }
 
+    /// Resolve the instance for a direct class-method call from the type the
+    /// call returns.
+    ///
+    /// This covers methods whose class type parameter appears only in the
+    /// result, such as `fn arbitrary() -> Gen<a>`: matching the declared
+    /// return type against the call's inferred type recovers the class type
+    /// arguments. Returns `None` while any of them is still a type variable.
+    pub fn resolve_method_call_instance_from_result(
+        &self,
+        class_name: Identifier,
+        method_name: Identifier,
+        result_type: &InferType,
+        interner: &Interner,
+    ) -> Option<(&InstanceDef, Vec<InferType>)> {
+        let class_def = self.lookup_class(class_name)?;
+        let method = class_def.methods.iter().find(|m| m.name == method_name)?;
+        let mut subst = HashMap::new();
+        if !Self::match_instance_type_expr(&method.return_type, result_type, &mut subst, interner) {
+            return None;
+        }
+        let class_type_args = class_def
+            .type_params
+            .iter()
+            .map(|param| subst.get(param).cloned())
+            .collect::<Option<Vec<_>>>()?;
+        if !class_type_args.iter().all(InferType::is_concrete) {
+            return None;
+        }
+        let (instance, _) =
+            self.resolve_instance_with_subst(class_name, &class_type_args, interner)?;
+        Some((instance, class_type_args))
+    }
+
     /// Resolve the dictionary reference needed for a concrete class application.
     ///
     /// For plain instances this returns a leaf `ResolvedDictionaryRef` pointing
@@ -127,6 +127,24 @@ impl TypeEnv {
             .filter_map(|(name, entries)| entries.last().map(|e| (*name, &e.scheme)))
     }
 
+    /// Visible bindings introduced by nested scopes (parameters, local `let`s,
+    /// pattern binders), in binding order. Top-level bindings are excluded.
+    pub fn local_bindings(&self) -> Vec<(Identifier, &Scheme)> {
+        let mut seen = HashSet::new();
+        let mut locals = Vec::new();
+        for names in self.scope_markers.iter().skip(1).rev() {
+            for name in names.iter().rev() {
+                if seen.insert(*name)
+                    && let Some(scheme) = self.lookup(*name)
+                {
+                    locals.push((*name, scheme));
+                }
+            }
+        }
+        locals.reverse();
+        locals
+    }
+
     /// Look up a name's definition span O(1) via shadow stack top.
     pub fn lookup_span(&self, name: Identifier) -> Option<Span> {
         self.bindings.get(&name)?.last().and_then(|e| e.def_span)
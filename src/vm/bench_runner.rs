@@ -0,0 +1,132 @@
+//! Benchmark measurement for `flux bench` on the VM.
+//!
+//! A benchmark first runs repeatedly for the warmup time, which also estimates how long one
+//! iteration takes. The measurement time is then split into samples of a fixed number of
+//! iterations, so each sample lasts long enough for the clock, and every sample records the
+//! mean time of one iteration in it.
+
+use std::time::{Duration, Instant};
+
+use crate::runtime::value::Value;
+
+use super::VM;
+
+/// Fewest samples taken of a benchmark, even when that exceeds the measurement time.
+pub const MIN_SAMPLES: usize = 10;
+
+/// Most samples taken of a benchmark.
+pub const MAX_SAMPLES: usize = 100;
+
+/// Shortest sample aimed for, so clock resolution stays small against it.
+const MIN_SAMPLE_TIME: Duration = Duration::from_micros(100);
+
+/// How long a benchmark is warmed up and measured.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    pub warmup: Duration,
+    pub measurement: Duration,
+}
+
+/// The samples of one benchmark: nanoseconds per iteration, one entry per sample.
+#[derive(Debug, Clone)]
+pub struct BenchSamples {
+    pub iterations_per_sample: u64,
+    pub samples: Vec<f64>,
+}
+
+/// Number of samples and iterations per sample for a benchmark whose iterations take
+/// `per_iteration`: as many samples as fit the measurement time at [`MIN_SAMPLE_TIME`]
+/// each, within [`MIN_SAMPLES`]..=[`MAX_SAMPLES`], with the iterations that fill them.
+pub fn sample_plan(per_iteration: Duration, measurement: Duration) -> (usize, u64) {
+    let per_iteration = per_iteration.as_secs_f64().max(1e-9);
+    let samples = (measurement.as_secs_f64() / MIN_SAMPLE_TIME.as_secs_f64())
+        .clamp(MIN_SAMPLES as f64, MAX_SAMPLES as f64) as usize;
+    let sample_time = measurement.as_secs_f64() / samples as f64;
+    let iterations = (sample_time / per_iteration).floor().max(1.0) as u64;
+    (samples, iterations)
+}
+
+/// Warms up and measures the zero-argument function in global slot `global`. Program output
+/// is discarded.
+///
+/// Fails with the runtime error of the first iteration that fails, or when the function
+/// takes parameters.
+pub fn measure_bench(
+    vm: &mut VM,
+    global: usize,
+    config: BenchConfig,
+) -> Result<BenchSamples, String> {
+    let bench = vm.global_get(global);
+    let parameters = match &bench {
+        Value::Function(function) => function.num_parameters,
+        Value::Closure(closure) => closure.function.num_parameters,
+        _ => 0,
+    };
+    if parameters > 0 {
+        return Err(format!(
+            "takes {parameters} parameter(s); benchmarks take none"
+        ));
+    }
+    vm.set_capture_output(true);
+
+    let mut run = |iterations: u64| -> Result<Duration, String> {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            vm.invoke_value(bench.clone(), vec![])?;
+        }
+        let elapsed = start.elapsed();
+        vm.take_captured_output();
+        Ok(elapsed)
+    };
+
+    // Warm up in doubling batches until the warmup time has passed.
+    let mut warmup_iterations = 0u64;
+    let mut warmup_time = Duration::ZERO;
+    let mut batch = 1u64;
+    while warmup_iterations == 0 || warmup_time < config.warmup {
+        warmup_time += run(batch)?;
+        warmup_iterations += batch;
+        batch = batch.saturating_mul(2);
+    }
+
+    let per_iteration = warmup_time.div_f64(warmup_iterations as f64);
+    let (sample_count, iterations) = sample_plan(per_iteration, config.measurement);
+    let mut samples = Vec::with_capacity(sample_count);
+    for _ in 0..sample_count {
+        let elapsed = run(iterations)?;
+        samples.push(elapsed.as_nanos() as f64 / iterations as f64);
+    }
+    vm.set_capture_output(false);
+    Ok(BenchSamples {
+        iterations_per_sample: iterations,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{MAX_SAMPLES, MIN_SAMPLES, sample_plan};
+
+    #[test]
+    fn sample_plan_fills_the_measurement_time() {
+        assert_eq!(
+            sample_plan(Duration::from_micros(1), Duration::from_millis(10)),
+            (100, 100)
+        );
+        assert_eq!(
+            sample_plan(Duration::from_micros(1), Duration::from_millis(1)),
+            (MIN_SAMPLES, 100)
+        );
+    }
+
+    #[test]
+    fn slow_benchmarks_still_take_the_minimum_samples_of_one_iteration() {
+        assert_eq!(
+            sample_plan(Duration::from_secs(1), Duration::from_micros(500)),
+            (MIN_SAMPLES, 1)
+        );
+        assert!(sample_plan(Duration::from_nanos(1), Duration::from_secs(60)).0 <= MAX_SAMPLES);
+    }
+}
@@ -0,0 +1,50 @@
+//! Wall-clock limits for invoked callables, enforced through an instruction budget.
+//!
+//! Reading the clock on every instruction would dominate dispatch, so the VM counts down a
+//! budget of [`BUDGET_SLICE`] instructions and only compares against the deadline when the
+//! budget runs out. A callable that overruns its limit fails with a runtime error at whatever
+//! instruction it was executing, and the invoke unwinds like for any other error.
+
+use std::time::{Duration, Instant};
+
+use super::VM;
+
+/// Instructions executed between two deadline checks.
+const BUDGET_SLICE: u32 = 4096;
+
+pub(super) struct InstructionBudget {
+    limit: Duration,
+    deadline: Instant,
+    remaining: u32,
+}
+
+impl VM {
+    /// Fails invoked callables that are still running `limit` from now; `None` lifts the limit.
+    ///
+    /// Only execution through [`VM::invoke_value`] and the other invoke entry points is
+    /// limited, not [`VM::run`].
+    pub fn set_time_limit(&mut self, limit: Option<Duration>) {
+        self.budget = limit.map(|limit| InstructionBudget {
+            limit,
+            deadline: Instant::now() + limit,
+            remaining: BUDGET_SLICE,
+        });
+    }
+
+    /// Charges one instruction against the time limit, if there is one.
+    #[inline(always)]
+    pub(super) fn charge_instruction(&mut self) -> Result<(), String> {
+        let Some(budget) = self.budget.as_mut() else {
+            return Ok(());
+        };
+        budget.remaining -= 1;
+        if budget.remaining > 0 {
+            return Ok(());
+        }
+        budget.remaining = BUDGET_SLICE;
+        if Instant::now() < budget.deadline {
+            return Ok(());
+        }
+        Err(format!("timed out after {}ms", budget.limit.as_millis()))
+    }
+}
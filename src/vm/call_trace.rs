@@ -0,0 +1,98 @@
+//! VM state of the call tracer (`--trace-calls`).
+//!
+//! The compiler emits `OpTraceCall` at the entry of each selected function and
+//! `OpTracePerform` before each effect operation. The VM marks traced frames (see
+//! [`Frame::traced`](crate::runtime::frame::Frame::traced)) so returns and tail calls are
+//! logged when those frames leave. Operations and resumptions are only logged inside a
+//! traced call.
+
+use crate::runtime::{
+    call_trace::{TraceEvent, TraceWriter},
+    value::{Value, format_value},
+};
+
+pub struct CallTracer {
+    labels: Vec<String>,
+    /// Labels of the traced calls still running, outermost first.
+    active: Vec<u16>,
+    writer: TraceWriter,
+}
+
+impl CallTracer {
+    pub fn new(labels: Vec<String>, writer: TraceWriter) -> Self {
+        Self {
+            labels,
+            active: Vec::new(),
+            writer,
+        }
+    }
+
+    pub(super) fn call(&mut self, label: u16, args: &[Value]) {
+        let args: Vec<String> = args.iter().map(format_value).collect();
+        let function = &self.labels[label as usize];
+        self.writer.write(
+            self.active.len(),
+            &TraceEvent::Call {
+                function,
+                args: &args,
+            },
+        );
+        self.active.push(label);
+    }
+
+    pub(super) fn ret(&mut self, label: u16, value: &Value) {
+        self.leave(label);
+        let value = format_value(value);
+        let function = &self.labels[label as usize];
+        self.writer.write(
+            self.active.len(),
+            &TraceEvent::Return {
+                function,
+                value: &value,
+            },
+        );
+    }
+
+    pub(super) fn tail_call(&mut self, label: u16) {
+        self.leave(label);
+        let function = &self.labels[label as usize];
+        self.writer
+            .write(self.active.len(), &TraceEvent::TailCall { function });
+    }
+
+    pub(super) fn perform(&mut self, label: u16, args: &[Value]) {
+        if self.active.is_empty() {
+            return;
+        }
+        let args: Vec<String> = args.iter().map(format_value).collect();
+        let operation = &self.labels[label as usize];
+        self.writer.write(
+            self.active.len(),
+            &TraceEvent::Perform {
+                operation,
+                args: &args,
+            },
+        );
+    }
+
+    pub(super) fn resume(&mut self, value: &Value) {
+        if self.active.is_empty() {
+            return;
+        }
+        let value = format_value(value);
+        self.writer
+            .write(self.active.len(), &TraceEvent::Resume { value: &value });
+    }
+
+    pub(super) fn flush(&mut self) {
+        self.writer.flush();
+    }
+
+    /// Closes the innermost running call of `label`. Calls above it were abandoned by a
+    /// handler that did not resume, so they are closed with it.
+    fn leave(&mut self, label: u16) {
+        if let Some(pos) = self.active.iter().rposition(|&active| active == label) {
+            self.active.truncate(pos);
+        }
+    }
+}
@@ -10,6 +10,7 @@ use std::time::{Instant, SystemTime};
 
 use crate::core::CorePrimOp;
 use crate::runtime::RuntimeContext;
+use crate::runtime::alloc_profile::{self, AllocKind};
 use crate::runtime::hamt as rc_hamt;
 use crate::runtime::hash_key::HashKey;
 use crate::runtime::value::{Value, format_value};
@@ -157,7 +158,7 @@ pub fn execute_core_primop(
                         ));
                     }
                     let mut items = items.clone();
-                    Rc::make_mut(&mut items)[index as usize] = args[2].clone();
+                    array_make_mut(&mut items)[index as usize] = args[2].clone();
                     Ok(Value::Array(items))
                 }
                 other => Err(terr("array_set", "Array", other)),
@@ -169,7 +170,7 @@ pub fn execute_core_primop(
             let arr_obj = args.swap_remove(0);
             match arr_obj {
                 Value::Array(mut arr) => {
-                    Rc::make_mut(&mut arr).push(elem);
+                    array_make_mut(&mut arr).push(elem);
                     Ok(Value::Array(arr))
                 }
                 other => Err(terr("push", "Array", &other)),
@@ -179,7 +180,7 @@ pub fn execute_core_primop(
             let left = earr(&args[0], "concat")?;
             let right = earr(&args[1], "concat")?;
             let mut out = left.clone();
-            Rc::make_mut(&mut out).extend(right.iter().cloned());
+            array_make_mut(&mut out).extend(right.iter().cloned());
             Ok(Value::Array(out))
         }
         ArraySlice => {
@@ -194,9 +195,9 @@ pub fn execute_core_primop(
                 end as usize
             };
             if start >= end || start >= arr.len() {
-                Ok(Value::Array(vec![].into()))
+                Ok(new_array(Vec::new()))
             } else {
-                Ok(Value::Array(arr[start..end].to_vec().into()))
+                Ok(new_array(arr[start..end].to_vec()))
             }
         }
 
@@ -247,20 +248,14 @@ pub fn execute_core_primop(
         HamtKeys => {
             let node = ehamt(&args[0], "keys")?;
             let pairs = rc_hamt::hamt_iter(node);
-            Ok(Value::Array(
-                pairs
-                    .iter()
-                    .map(|(k, _)| hash_key_to_value(k))
-                    .collect::<Vec<_>>()
-                    .into(),
+            Ok(new_array(
+                pairs.iter().map(|(k, _)| hash_key_to_value(k)).collect(),
             ))
         }
         HamtValues => {
             let node = ehamt(&args[0], "values")?;
             let pairs = rc_hamt::hamt_iter(node);
-            Ok(Value::Array(
-                pairs.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into(),
-            ))
+            Ok(new_array(pairs.into_iter().map(|(_, v)| v).collect()))
         }
         HamtMerge => {
             let node1 = ehamt(&args[0], "merge")?;
@@ -325,7 +320,7 @@ pub fn execute_core_primop(
                     .map(|p| Value::String(p.to_string().into()))
                     .collect()
             };
-            Ok(Value::Array(parts.into()))
+            Ok(new_array(parts))
         }
         Trim => Ok(Value::String(
             estr(&args[0], "trim")?.trim().to_string().into(),
@@ -366,17 +361,12 @@ pub fn execute_core_primop(
 
         // ── I/O ───────────────────────────────────────────────────────
         Print => {
-            for (i, arg) in args.iter().enumerate() {
-                if i > 0 {
-                    print!(" ");
-                }
-                print!("{}", format_value(arg));
-            }
-            println!();
+            let line: Vec<String> = args.iter().map(format_value).collect();
+            ctx.write_stdout(&format!("{}\n", line.join(" ")));
             Ok(Value::None)
         }
         Println => {
-            println!("{}", format_value(&args[0]));
+            ctx.write_stdout(&format!("{}\n", format_value(&args[0])));
             Ok(Value::None)
         }
         DebugTrace => {
@@ -388,12 +378,12 @@ pub fn execute_core_primop(
             eprintln!("{}", format_value(&args[0]));
             Ok(Value::None)
         }
-        ReadFile => {
+        ReadFile => logged(ctx, "read_file", &args, || {
             let path = estr(&args[0], "read_file")?;
             let content = fs::read_to_string(path)
                 .map_err(|e| format!("read_file failed for '{}': {}", path, e))?;
             Ok(Value::String(content.into()))
-        }
+        }),
         WriteFile => {
             let path = estr(&args[0], "write_file")?;
             let content = estr(&args[1], "write_file")?;
@@ -401,14 +391,14 @@ pub fn execute_core_primop(
                 .map_err(|e| format!("write_file failed for '{}': {}", path, e))?;
             Ok(Value::None)
         }
-        ReadStdin => {
+        ReadStdin => logged(ctx, "read_stdin", &args, || {
             let mut input = String::new();
             std::io::stdin()
                 .read_to_string(&mut input)
                 .map_err(|e| format!("read_stdin failed: {}", e))?;
             Ok(Value::String(input.into()))
-        }
-        ReadLines => {
+        }),
+        ReadLines => logged(ctx, "read_lines", &args, || {
             let path = estr(&args[0], "read_lines")?;
             let content = fs::read_to_string(path)
                 .map_err(|e| format!("read_lines failed for '{}': {}", path, e))?;
@@ -416,8 +406,8 @@ pub fn execute_core_primop(
                 .lines()
                 .map(|line| Value::String(line.trim_end_matches('\r').to_string().into()))
                 .collect::<Vec<_>>();
-            Ok(Value::Array(lines.into()))
-        }
+            Ok(new_array(lines))
+        }),
 
         // ── Control ───────────────────────────────────────────────────
         Unwrap => match &args[0] {
@@ -425,12 +415,12 @@ pub fn execute_core_primop(
             other => Ok(other.clone()),
         },
         Panic => Err(format!("panic: {}", args[0].to_string_value())),
-        ClockNow => {
+        ClockNow => logged(ctx, "clock_now", &args, || {
             let now = SystemTime::now()
                 .duration_since(SystemTime::UNIX_EPOCH)
                 .map_err(|e| format!("clock_now failed: {}", e))?;
             Ok(Value::Integer(now.as_millis() as i64))
-        }
+        }),
         Time => {
             let start = Instant::now();
             let _ = ctx
@@ -523,6 +513,33 @@ pub fn execute_core_primop(
 
 // ── Compact helper functions ─────────────────────────────────────────────────
 
+/// Runs a builtin that reads the outside world through the `--record`/`--replay` log, when
+/// one is attached.
+fn logged(
+    ctx: &mut dyn RuntimeContext,
+    op: &str,
+    args: &[Value],
+    run: impl FnOnce() -> Result<Value, String>,
+) -> Result<Value, String> {
+    match ctx.effect_log() {
+        Some(log) => log.perform(op, args, run),
+        None => run(),
+    }
+}
+
+fn new_array(items: Vec<Value>) -> Value {
+    alloc_profile::record(AllocKind::Array);
+    Value::Array(items.into())
+}
+
+/// `Rc::make_mut` for arrays, counting the copy made when the array is shared.
+fn array_make_mut(items: &mut Rc<Vec<Value>>) -> &mut Vec<Value> {
+    if Rc::get_mut(items).is_none() {
+        alloc_profile::record(AllocKind::Array);
+    }
+    Rc::make_mut(items)
+}
+
 fn terr(op: &str, expected: &str, got: &Value) -> String {
     format!(
         "primop {} expected {}, got {}",
@@ -0,0 +1,304 @@
+//! Source coverage for VM runs (`--coverage`).
+//!
+//! While coverage is on, the VM counts how often each instruction runs and how often each
+//! conditional jump is taken. [`CoverageCollector::finish`] resolves those counts through the
+//! functions' location tables into [`CoverageData`], which is keyed by source position only,
+//! so the coverage of separately compiled programs (one per test file) can be merged.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use crate::{
+    bytecode::op_code::{OpCode, operand_widths},
+    runtime::compiled_function::CompiledFunction,
+};
+
+/// Name recorded for the top-level code of a program, which is not reported as a function.
+const MAIN_FUNCTION_NAME: &str = "<main>";
+
+/// Start and end of a source span, `(line, column)` with 1-based lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpanKey {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+/// A conditional jump, identified by the start of its source span and its position among the
+/// jumps of the same function that share that start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BranchKey {
+    pub line: usize,
+    pub column: usize,
+    pub index: usize,
+}
+
+/// Outcomes of one conditional jump. For an `if` condition or a match-arm test, `fallthrough`
+/// counts the times it held and `jumped` the times it did not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchCounts {
+    pub fallthrough: u64,
+    pub jumped: u64,
+}
+
+/// A function defined in a file: its name and the line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FunctionKey {
+    pub line: usize,
+    pub name: String,
+}
+
+/// Calls of a function and the lines and branches of its own body; nested lambdas are
+/// functions of their own.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FunctionCoverage {
+    pub calls: u64,
+    pub lines: BTreeSet<usize>,
+    pub branches: BTreeSet<BranchKey>,
+}
+
+/// Coverage of one source file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileCoverage {
+    /// Execution count of each source span that starts an instruction range.
+    pub spans: BTreeMap<SpanKey, u64>,
+    pub branches: BTreeMap<BranchKey, BranchCounts>,
+    pub functions: BTreeMap<FunctionKey, FunctionCoverage>,
+}
+
+impl FileCoverage {
+    /// Execution count of each line that starts a span: the count of its busiest span.
+    pub fn line_counts(&self) -> BTreeMap<usize, u64> {
+        let mut lines = BTreeMap::new();
+        for (span, &count) in &self.spans {
+            let line = lines.entry(span.start.0).or_insert(0);
+            *line = (*line).max(count);
+        }
+        lines
+    }
+
+    fn merge(&mut self, other: FileCoverage) {
+        for (span, count) in other.spans {
+            *self.spans.entry(span).or_insert(0) += count;
+        }
+        for (key, counts) in other.branches {
+            let entry = self.branches.entry(key).or_default();
+            entry.fallthrough += counts.fallthrough;
+            entry.jumped += counts.jumped;
+        }
+        for (key, function) in other.functions {
+            let entry = self.functions.entry(key).or_default();
+            entry.calls += function.calls;
+            entry.lines.extend(function.lines);
+            entry.branches.extend(function.branches);
+        }
+    }
+}
+
+/// Coverage of a run, by source file path.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CoverageData {
+    pub files: BTreeMap<String, FileCoverage>,
+}
+
+impl CoverageData {
+    /// Adds the counts of another run, e.g. of another test file.
+    pub fn merge(&mut self, other: CoverageData) {
+        for (path, file) in other.files {
+            self.files.entry(path).or_default().merge(file);
+        }
+    }
+
+    fn file_mut(&mut self, path: &str) -> &mut FileCoverage {
+        self.files.entry(path.to_string()).or_default()
+    }
+}
+
+/// Raw counters of one compiled function, indexed by instruction offset.
+struct FunctionCounters {
+    function: Rc<CompiledFunction>,
+    executed: Vec<u64>,
+    jumped: Vec<u64>,
+}
+
+/// Per-instruction counters of a running VM.
+#[derive(Default)]
+pub(crate) struct CoverageCollector {
+    functions: Vec<FunctionCounters>,
+    index: HashMap<*const CompiledFunction, usize>,
+    /// The function of the previous instruction, which is usually the next one's too.
+    last: Option<(*const CompiledFunction, usize)>,
+}
+
+impl CoverageCollector {
+    /// Registers a function up front, so it is reported even if it never runs.
+    pub(crate) fn register(&mut self, function: &Rc<CompiledFunction>) -> usize {
+        let ptr = Rc::as_ptr(function);
+        if let Some(&index) = self.index.get(&ptr) {
+            return index;
+        }
+        let len = function.instructions.len();
+        self.functions.push(FunctionCounters {
+            function: Rc::clone(function),
+            executed: vec![0; len],
+            jumped: vec![0; len],
+        });
+        let index = self.functions.len() - 1;
+        self.index.insert(ptr, index);
+        index
+    }
+
+    /// Counts one executed instruction. `ip_delta` is what dispatch returned: a conditional
+    /// jump that was taken returns 0.
+    #[inline]
+    pub(crate) fn record(
+        &mut self,
+        function: &Rc<CompiledFunction>,
+        ip: usize,
+        op: OpCode,
+        ip_delta: usize,
+    ) {
+        let ptr = Rc::as_ptr(function);
+        let index = match self.last {
+            Some((last, index)) if last == ptr => index,
+            _ => {
+                let index = self.register(function);
+                self.last = Some((ptr, index));
+                index
+            }
+        };
+        let counters = &mut self.functions[index];
+        if let Some(count) = counters.executed.get_mut(ip) {
+            *count += 1;
+            if ip_delta == 0 && is_conditional_jump(op) {
+                counters.jumped[ip] += 1;
+            }
+        }
+    }
+
+    /// Resolves the counters to source positions.
+    pub(crate) fn finish(&self) -> CoverageData {
+        let mut data = CoverageData::default();
+        for counters in &self.functions {
+            resolve_function(counters, &mut data);
+        }
+        data
+    }
+}
+
+fn is_conditional_jump(op: OpCode) -> bool {
+    matches!(
+        op,
+        OpCode::OpJumpNotTruthy
+            | OpCode::OpJumpTruthy
+            | OpCode::OpCmpEqJumpNotTruthy
+            | OpCode::OpCmpNeJumpNotTruthy
+            | OpCode::OpCmpGtJumpNotTruthy
+            | OpCode::OpCmpLeJumpNotTruthy
+            | OpCode::OpCmpGeJumpNotTruthy
+            | OpCode::OpIsAdtJump
+            | OpCode::OpIsAdtJumpLocal
+    )
+}
+
+/// Adds one function's counts to `data`. Within a run a span keeps its highest count, since
+/// a function may list the same span at several offsets.
+fn resolve_function(counters: &FunctionCounters, data: &mut CoverageData) {
+    let function = &counters.function;
+    let Some(info) = function.debug_info.as_ref() else {
+        return;
+    };
+    let file_of = |file_id: u32| info.file_for(file_id).map(str::to_string);
+
+    let defined_in = info
+        .boundary_location
+        .as_ref()
+        .or_else(|| {
+            info.locations
+                .iter()
+                .find_map(|entry| entry.location.as_ref())
+        })
+        .and_then(|location| Some((file_of(location.file_id)?, location.span.start.line)));
+    let function_key = match (info.name.as_deref(), &defined_in) {
+        (Some(MAIN_FUNCTION_NAME), _) | (_, None) => None,
+        (Some(name), Some((path, line))) => Some((
+            path.clone(),
+            FunctionKey {
+                line: *line,
+                name: name.to_string(),
+            },
+        )),
+        (None, Some((path, line))) => Some((
+            path.clone(),
+            FunctionKey {
+                line: *line,
+                name: format!("<lambda:{line}>"),
+            },
+        )),
+    };
+    let mut own = FunctionCoverage {
+        calls: counters.executed.first().copied().unwrap_or(0),
+        ..FunctionCoverage::default()
+    };
+
+    for entry in &info.locations {
+        let Some(location) = entry.location.as_ref() else {
+            continue;
+        };
+        let (Some(path), Some(&count)) = (
+            file_of(location.file_id),
+            counters.executed.get(entry.offset),
+        ) else {
+            continue;
+        };
+        let span = SpanKey {
+            start: (location.span.start.line, location.span.start.column),
+            end: (location.span.end.line, location.span.end.column),
+        };
+        let recorded = data.file_mut(&path).spans.entry(span).or_insert(0);
+        *recorded = (*recorded).max(count);
+        if function_key.as_ref().is_some_and(|(file, _)| *file == path) {
+            own.lines.insert(span.start.0);
+        }
+    }
+
+    let mut jumps_at: HashMap<(String, usize, usize), usize> = HashMap::new();
+    let mut ip = 0;
+    while ip < function.instructions.len() {
+        let op = OpCode::from(function.instructions[ip]);
+        if is_conditional_jump(op)
+            && let Some(location) = info.location_at(ip)
+            && let Some(path) = file_of(location.file_id)
+        {
+            let (line, column) = (location.span.start.line, location.span.start.column);
+            let index = jumps_at.entry((path.clone(), line, column)).or_insert(0);
+            let key = BranchKey {
+                line,
+                column,
+                index: *index,
+            };
+            *index += 1;
+            let jumped = counters.jumped[ip];
+            let counts = BranchCounts {
+                fallthrough: counters.executed[ip] - jumped,
+                jumped,
+            };
+            let recorded = data.file_mut(&path).branches.entry(key).or_default();
+            recorded.fallthrough = recorded.fallthrough.max(counts.fallthrough);
+            recorded.jumped = recorded.jumped.max(counts.jumped);
+            if function_key.as_ref().is_some_and(|(file, _)| *file == path) {
+                own.branches.insert(key);
+            }
+        }
+        ip += 1 + operand_widths(op).iter().sum::<usize>();
+    }
+
+    if let Some((path, key)) = function_key {
+        let file = data.file_mut(&path);
+        let entry = file.functions.entry(key).or_default();
+        entry.calls = entry.calls.max(own.calls);
+        entry.lines.extend(own.lines);
+        entry.branches.extend(own.branches);
+    }
+}
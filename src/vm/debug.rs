@@ -0,0 +1,283 @@
+//! Debugger support for the bytecode VM.
+//!
+//! A [`DebugHook`] attached with [`VM::set_debugger`] is consulted each time execution enters
+//! a new source line, as recorded in the functions' [`FunctionDebugInfo`] locations. A line is
+//! entered when a frame moves to another line or a new frame starts; returning to a caller
+//! that is still on the line of its call is not a new line. The hook decides whether to stop
+//! for a breakpoint or pause request; the VM itself implements stepping relative to the frame
+//! the last stop happened in.
+//!
+//! While stopped, the hook inspects the VM through [`VM::debug_frames`] and returns a
+//! [`Resume`] command. A runtime error stops once, where it is raised and before it unwinds,
+//! with the rendered diagnostic as [`StopReason::Error`]. That includes errors that a caller
+//! such as `assert_throws` goes on to handle.
+//!
+//! [`FunctionDebugInfo`]: crate::bytecode::debug_info::FunctionDebugInfo
+
+use std::rc::Rc;
+
+use crate::{diagnostics::position::Span, runtime::value::Value};
+
+use super::{VM, slot, trace::strip_ansi};
+
+/// Why execution stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+    Entry,
+    Breakpoint,
+    Step,
+    Pause,
+    /// A runtime error, with its rendered diagnostic.
+    Error(String),
+}
+
+/// How execution continues after a stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    Continue,
+    /// Stop at the next line entered, in any frame.
+    StepIn,
+    /// Stop at the next line entered in the current frame or a caller.
+    StepOver,
+    /// Stop as soon as the current frame has returned.
+    StepOut,
+}
+
+/// Front end of a debugging session, such as the DAP server.
+pub trait DebugHook {
+    /// Called when execution enters `line` of `file`; returns a reason to stop there.
+    fn should_stop(&mut self, file: &str, line: usize) -> Option<StopReason>;
+
+    /// Called while execution is stopped; returns how to resume.
+    fn stopped(&mut self, vm: &VM, reason: StopReason) -> Resume;
+
+    /// Receives program output from `print` and `println`.
+    fn output(&mut self, text: &str);
+}
+
+/// A snapshot of one call frame.
+#[derive(Debug, Clone)]
+pub struct DebugFrame {
+    pub name: String,
+    /// File and span of the instruction the frame is executing.
+    pub location: Option<(String, Span)>,
+    /// Named locals with a value; unnamed temporaries are left out.
+    pub locals: Vec<(String, Value)>,
+    /// Captured variables of the frame's closure.
+    pub captures: Vec<(String, Value)>,
+}
+
+/// Stepping state of an attached [`DebugHook`].
+pub(super) struct Debugger {
+    pub(super) hook: Box<dyn DebugHook>,
+    resume: Resume,
+    /// Frame depth of the last stop; steps are relative to it.
+    origin_depth: usize,
+    /// Function and line each active frame is on, indexed by frame depth.
+    lines: Vec<Option<(usize, usize)>>,
+    /// The error last stopped on; it is seen again at every frame it propagates through.
+    reported_error: Option<String>,
+}
+
+impl Debugger {
+    pub(super) fn new(hook: Box<dyn DebugHook>) -> Self {
+        Self {
+            hook,
+            resume: Resume::Continue,
+            origin_depth: 0,
+            lines: Vec::new(),
+            reported_error: None,
+        }
+    }
+
+    /// Tracks the line of the instruction about to run and returns a reason to stop before it.
+    fn check(&mut self, vm: &VM) -> Option<StopReason> {
+        let depth = vm.frame_index;
+        let frame = &vm.frames[depth];
+        let info = frame.closure.function.debug_info.as_ref()?;
+        let location = info.location_at(frame.ip)?;
+        let line = location.span.start.line;
+        let key = (Rc::as_ptr(&frame.closure.function) as usize, line);
+
+        self.lines.resize(depth + 1, None);
+        let entered = self.lines[depth] != Some(key);
+        self.lines[depth] = Some(key);
+
+        let returned =
+            depth < self.origin_depth && matches!(self.resume, Resume::StepOver | Resume::StepOut);
+        if returned {
+            return Some(StopReason::Step);
+        }
+        if !entered {
+            return None;
+        }
+        if let Some(reason) = info
+            .file_for(location.file_id)
+            .and_then(|file| self.hook.should_stop(file, line))
+        {
+            return Some(reason);
+        }
+        let step = match self.resume {
+            Resume::Continue | Resume::StepOut => false,
+            Resume::StepIn => true,
+            Resume::StepOver => depth <= self.origin_depth,
+        };
+        step.then_some(StopReason::Step)
+    }
+
+    fn resume(&mut self, resume: Resume, depth: usize) {
+        self.resume = resume;
+        self.origin_depth = depth;
+    }
+}
+
+impl VM {
+    /// Attaches a debugger; it is consulted before every instruction that starts a new line.
+    pub fn set_debugger(&mut self, hook: Box<dyn DebugHook>) {
+        self.debugger = Some(Debugger::new(hook));
+    }
+
+    pub(super) fn debug_instruction(&mut self) {
+        let Some(mut debugger) = self.debugger.take() else {
+            return;
+        };
+        if let Some(reason) = debugger.check(self) {
+            let resume = debugger.hook.stopped(self, reason);
+            debugger.resume(resume, self.frame_index);
+        }
+        self.debugger = Some(debugger);
+    }
+
+    /// Stops on a runtime error where it is raised, while its frames are still live.
+    pub(super) fn debug_error(&mut self, err: &str) {
+        let Some(mut debugger) = self.debugger.take() else {
+            return;
+        };
+        if debugger.reported_error.as_deref() != Some(err) {
+            debugger.reported_error = Some(err.to_string());
+            let message = strip_ansi(&self.format_runtime_error(err.to_string()));
+            debugger.hook.stopped(self, StopReason::Error(message));
+        }
+        self.debugger = Some(debugger);
+    }
+
+    /// Sends program output to the debugger or the capture buffer, or else to stdout.
+    pub(super) fn write_program_output(&mut self, text: &str) {
+        if let Some(debugger) = self.debugger.as_mut() {
+            debugger.hook.output(text);
+        } else if let Some(captured) = self.captured_output.as_mut() {
+            captured.push_str(text);
+        } else {
+            print!("{text}");
+        }
+    }
+
+    /// Returns the active call frames, innermost first.
+    pub fn debug_frames(&self) -> Vec<DebugFrame> {
+        self.frames[..=self.frame_index]
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(index, frame)| {
+                let (name, _) = self.format_frame(frame, index == self.frame_index);
+                let function = &frame.closure.function;
+                let info = function.debug_info.as_ref();
+                let location = info.and_then(|info| {
+                    let location = info.location_at(frame.ip)?;
+                    let file = info.file_for(location.file_id)?;
+                    Some((file.to_string(), location.span))
+                });
+                let locals = info
+                    .map(|info| {
+                        info.locals
+                            .iter()
+                            .filter(|local| local.index < function.num_locals)
+                            .filter_map(|local| {
+                                let slot = self.stack.get(frame.base_pointer + local.index)?;
+                                let value = slot::from_slot_ref(slot);
+                                (!matches!(value, Value::Uninit))
+                                    .then(|| (local.name.clone(), value))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let captures = info
+                    .map(|info| {
+                        info.captures
+                            .iter()
+                            .cloned()
+                            .zip(frame.closure.free.iter().cloned())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                DebugFrame {
+                    name,
+                    location,
+                    locals,
+                    captures,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the components of a structured value, labelled for display.
+pub fn value_children(value: &Value) -> Vec<(String, Value)> {
+    let indexed = |items: &mut dyn Iterator<Item = Value>| {
+        items
+            .enumerate()
+            .map(|(index, item)| (format!("[{index}]"), item))
+            .collect()
+    };
+    match value {
+        Value::Some(inner) | Value::Left(inner) | Value::Right(inner) => {
+            vec![("0".to_string(), (**inner).clone())]
+        }
+        Value::Array(items) => indexed(&mut items.iter().cloned()),
+        Value::Tuple(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (index.to_string(), item.clone()))
+            .collect(),
+        Value::Cons(_) => {
+            let mut items = Vec::new();
+            let mut current = value;
+            while let Value::Cons(cell) = current {
+                items.push(cell.head.clone());
+                current = &cell.tail;
+            }
+            indexed(&mut items.into_iter())
+        }
+        Value::Adt(adt) => adt
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| (index.to_string(), field.clone()))
+            .collect(),
+        Value::HashMap(node) => crate::runtime::hamt::hamt_iter(node)
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+        Value::Closure(closure) => {
+            let names = closure
+                .function
+                .debug_info
+                .as_ref()
+                .map(|info| info.captures.clone())
+                .unwrap_or_default();
+            closure
+                .free
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let name = names
+                        .get(index)
+                        .cloned()
+                        .unwrap_or_else(|| format!("<capture {index}>"));
+                    (name, value.clone())
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
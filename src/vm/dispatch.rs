@@ -3,6 +3,7 @@ use std::rc::Rc;
 use crate::{
     bytecode::op_code::OpCode,
     runtime::{
+        alloc_profile::{self, AllocKind},
         closure::Closure,
         cons_cell::ConsCell,
         continuation::Continuation,
@@ -196,6 +197,7 @@ impl VM {
                 if matches!(return_value, Value::Uninit) {
                     return_value = Value::None;
                 }
+                self.trace_return(&return_value);
                 if let Some(contract) = self.current_frame().closure.function.contract.as_ref()
                     && let Some(expected) = contract.ret.as_ref()
                     && !expected.matches_value(&return_value, self)
@@ -248,6 +250,7 @@ impl VM {
                         Some(&value_preview),
                     ));
                 }
+                self.trace_return(&Value::None);
                 let return_slot = self.pop_frame_return_slot();
                 self.reset_sp(return_slot)?;
                 self.push(Value::None)?;
@@ -924,6 +927,7 @@ impl VM {
                 if matches!(return_value, Value::Uninit) {
                     return_value = Value::None;
                 }
+                self.trace_return(&return_value);
                 let return_slot = self.pop_frame_return_slot();
                 self.reset_sp(return_slot)?;
                 self.push(return_value)?;
@@ -957,6 +961,7 @@ impl VM {
                 if arity == 0 {
                     self.push(Value::AdtUnit(constructor_name))?;
                 } else {
+                    alloc_profile::record(AllocKind::Adt);
                     self.push(Value::Adt(Rc::new(crate::runtime::value::AdtValue {
                         constructor: constructor_name,
                         fields: AdtFields::from_vec(fields),
@@ -1655,16 +1660,20 @@ impl VM {
                             }
                             Value::Adt(rc)
                         } else {
+                            alloc_profile::record(AllocKind::Adt);
                             Value::Adt(Rc::new(AdtValue {
                                 constructor: constructor_name,
                                 fields: AdtFields::from_vec(fields),
                             }))
                         }
                     }
-                    _ => Value::Adt(Rc::new(AdtValue {
-                        constructor: constructor_name,
-                        fields: AdtFields::from_vec(fields),
-                    })),
+                    _ => {
+                        alloc_profile::record(AllocKind::Adt);
+                        Value::Adt(Rc::new(AdtValue {
+                            constructor: constructor_name,
+                            fields: AdtFields::from_vec(fields),
+                        }))
+                    }
                 };
                 self.push(result)?;
                 Ok(5) // 1 opcode + 2 const_idx + 1 arity + 1 field_mask
@@ -1748,6 +1757,33 @@ impl VM {
                 }
                 Ok(3)
             }
+            OpCode::OpTraceCall => {
+                if let Some(tracer) = self.call_tracer.as_mut() {
+                    let label = Self::read_u16_fast(instructions, ip + 1) as u16;
+                    let frame = &mut self.frames[self.frame_index];
+                    let bp = frame.base_pointer;
+                    let args: Vec<Value> = self.stack
+                        [bp..bp + frame.closure.function.num_parameters]
+                        .iter()
+                        .map(super::slot::from_slot_ref)
+                        .collect();
+                    tracer.call(label, &args);
+                    frame.traced = Some(label);
+                }
+                Ok(3)
+            }
+            OpCode::OpTracePerform => {
+                if let Some(tracer) = self.call_tracer.as_mut() {
+                    let label = Self::read_u16_fast(instructions, ip + 1) as u16;
+                    let arity = Self::read_u8_fast(instructions, ip + 3);
+                    let args: Vec<Value> = self.stack[self.sp - arity..self.sp]
+                        .iter()
+                        .map(super::slot::from_slot_ref)
+                        .collect();
+                    tracer.perform(label, &args);
+                }
+                Ok(4)
+            }
         }
     }
 }
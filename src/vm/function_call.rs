@@ -2,6 +2,7 @@ use std::rc::Rc;
 
 use crate::diagnostics::NOT_A_FUNCTION;
 use crate::runtime::RuntimeContext;
+use crate::runtime::effect_log::EffectLog;
 use crate::runtime::value::format_value;
 use crate::runtime::{closure::Closure, continuation::Continuation, frame::Frame, value::Value};
 
@@ -169,6 +170,9 @@ impl VM {
             ));
         }
         self.check_closure_contract_stack_args(&closure, num_args)?;
+        if self.call_tracer.is_some() {
+            self.trace_identity_resume(&closure, args_start);
+        }
         let frame = Frame::new_with_return_slot(closure, args_start, return_slot);
         let num_locals = frame.closure.function.num_locals;
         let max_stack = frame.closure.function.max_stack;
@@ -185,6 +189,11 @@ impl VM {
         if self.profiling {
             self.exit_cost_centre();
         }
+        if let Some(tracer) = self.call_tracer.as_mut()
+            && let Some(label) = self.frames[self.frame_index].traced.take()
+        {
+            tracer.tail_call(label);
+        }
         let callee_idx = self.sp - 1 - num_args;
         let callee_val = self.stack_get(callee_idx);
         match &callee_val {
@@ -201,6 +210,9 @@ impl VM {
             ));
         }
         self.check_closure_contract_stack_args(&closure, num_args)?;
+        if self.call_tracer.is_some() {
+            self.trace_identity_resume(&closure, self.sp - num_args);
+        }
 
         let base_pointer = self.current_frame().base_pointer;
 
@@ -225,12 +237,28 @@ impl VM {
             super::STACK_PREGROW_HEADROOM,
         )?;
         self.reset_sp(base_pointer + closure.function.num_locals)?;
-        self.current_frame_mut().ip = 0;
-        self.current_frame_mut().closure = closure;
+        let record_tail_caller = self.record_tail_callers;
+        let frame = self.current_frame_mut();
+        if record_tail_caller && frame.tail_caller.is_none() {
+            frame.tail_caller = Some((frame.closure.clone(), frame.ip));
+        }
+        frame.ip = 0;
+        frame.closure = closure;
 
         Ok(())
     }
 
+    /// A tail-resumptive handler resumes by calling the identity closure it was given as
+    /// `resume`, so the call is traced as a resumption.
+    fn trace_identity_resume(&mut self, closure: &Rc<Closure>, args_start: usize) {
+        if Rc::ptr_eq(closure, &super::identity_closure()) {
+            let value = self.stack_get(args_start);
+            if let Some(tracer) = self.call_tracer.as_mut() {
+                tracer.resume(&value);
+            }
+        }
+    }
+
     pub(super) fn push_closure(
         &mut self,
         const_index: usize,
@@ -281,6 +309,9 @@ impl VM {
         };
         let resume_val = self.pop_untracked()?;
         let cont_val = self.pop_untracked()?; // the callee (Continuation)
+        if let Some(tracer) = self.call_tracer.as_mut() {
+            tracer.resume(&resume_val);
+        }
         let next_state_for_caller = next_state.clone();
 
         let caller = caller_ip_advance.map(|advance| {
@@ -574,4 +605,12 @@ impl RuntimeContext for VM {
             _ => None,
         }
     }
+
+    fn write_stdout(&mut self, text: &str) {
+        self.write_program_output(text);
+    }
+
+    fn effect_log(&mut self) -> Option<&mut EffectLog> {
+        self.effect_log.as_deref_mut()
+    }
 }
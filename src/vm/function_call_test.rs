@@ -1,7 +1,7 @@
-use std::rc::Rc;
+use std::{rc::Rc, time::Duration};
 
 use crate::{
-    bytecode::bytecode::Bytecode,
+    bytecode::{bytecode::Bytecode, op_code::OpCode},
     runtime::{closure::Closure, compiled_function::CompiledFunction, value::Value},
     vm::VM,
 };
@@ -57,3 +57,17 @@ fn call_self_uses_current_frame_closure_without_callee_slot() {
     assert_eq!(vm.frame_index, initial_frame_index + 1);
     assert_eq!(vm.sp, initial_sp + 2);
 }
+
+#[test]
+fn invoke_value_fails_when_the_time_limit_expires() {
+    let mut vm = new_vm();
+    // Jumps back to its own start forever.
+    let function = CompiledFunction::new(vec![OpCode::OpJump as u8, 0, 0], 0, 0, None);
+    let closure = Value::Closure(Rc::new(Closure::new(Rc::new(function), vec![])));
+    vm.set_time_limit(Some(Duration::from_millis(20)));
+
+    let err = vm.invoke_value(closure, vec![]).unwrap_err();
+
+    assert_eq!(err, "timed out after 20ms");
+    assert_eq!(vm.frame_index, 0);
+}
@@ -2,16 +2,31 @@ use std::rc::Rc;
 
 use crate::{
     bytecode::{bytecode::Bytecode, op_code::OpCode},
+    diagnostics::position::Span,
     runtime::{
-        closure::Closure, compiled_function::CompiledFunction, evidence::EvidenceVector,
-        frame::Frame, hamt, handler_frame::HandlerFrame, leak_detector, value::Value,
+        alloc_profile::{self, AllocKind},
+        call_trace::TraceWriter,
+        closure::Closure,
+        compiled_function::CompiledFunction,
+        effect_log::EffectLog,
+        evidence::EvidenceVector,
+        frame::Frame,
+        hamt,
+        handler_frame::HandlerFrame,
+        leak_detector,
+        value::Value,
         yield_state::YieldState,
     },
 };
 
+pub mod bench_runner;
 mod binary_ops;
+mod budget;
+mod call_trace;
 mod comparison_ops;
-mod core_dispatch;
+pub(crate) mod core_dispatch;
+pub mod coverage;
+pub mod debug;
 mod dispatch;
 mod function_call;
 mod index_ops;
@@ -20,6 +35,8 @@ pub mod profiling;
 pub mod test_runner;
 mod trace;
 
+pub(crate) use trace::strip_ansi;
+
 const INITIAL_STACK_SIZE: usize = 2048;
 const MAX_STACK_SIZE: usize = 1 << 20; // 1,048,576 slots
 const GLOBALS_SIZE: usize = 65536;
@@ -67,6 +84,18 @@ mod slot {
 
 use slot::Slot;
 
+/// The shared closure behind [`VM::make_identity_closure`].
+fn identity_closure() -> Rc<Closure> {
+    thread_local! {
+        static IDENTITY: Rc<Closure> = {
+            let instructions = vec![OpCode::OpReturnLocal as u8, 0];
+            let func = Rc::new(CompiledFunction::new(instructions, 1, 1, None));
+            Rc::new(Closure::new(func, vec![]))
+        };
+    }
+    IDENTITY.with(Rc::clone)
+}
+
 pub struct VM {
     constants: Vec<Slot>,
     stack: Vec<Slot>,
@@ -87,6 +116,24 @@ pub struct VM {
     pub(crate) profiling: bool,
     pub(crate) cost_centres: Vec<profiling::CostCentre>,
     pub(crate) cc_stack: Vec<profiling::CostCentreStackEntry>,
+    pub(crate) call_tree: Option<profiling::CallTree>,
+    /// Attached debugger — only set by `flux debug`.
+    debugger: Option<debug::Debugger>,
+    /// Coverage counters — only set by `--coverage`.
+    coverage: Option<Box<coverage::CoverageCollector>>,
+    /// Call tracer — only set by `--trace-calls`.
+    call_tracer: Option<Box<call_trace::CallTracer>>,
+    /// Builtin effect log — only set by `--record` or `--replay`.
+    effect_log: Option<Box<EffectLog>>,
+    /// Program output buffered instead of printed, when capture is on.
+    captured_output: Option<String>,
+    /// The last error raised inside an invoked callable, with the frame locations it was
+    /// raised under, innermost first.
+    error_trace: Option<(String, Vec<(String, Span)>)>,
+    /// Whether tail calls remember the frame they replaced (see [`Frame::tail_caller`]).
+    record_tail_callers: bool,
+    /// Time limit for invoked callables, set with [`VM::set_time_limit`].
+    budget: Option<budget::InstructionBudget>,
 }
 
 impl VM {
@@ -111,6 +158,15 @@ impl VM {
             profiling: false,
             cost_centres: Vec::new(),
             cc_stack: Vec::new(),
+            call_tree: None,
+            debugger: None,
+            coverage: None,
+            call_tracer: None,
+            effect_log: None,
+            captured_output: None,
+            error_trace: None,
+            record_tail_callers: false,
+            budget: None,
         }
     }
 
@@ -118,51 +174,142 @@ impl VM {
         self.trace = enabled;
     }
 
+    /// Buffers `print`/`println` output instead of writing it to stdout.
+    pub fn set_capture_output(&mut self, enabled: bool) {
+        self.captured_output = enabled.then(String::new);
+    }
+
+    /// Keeps the call site of each frame's first tail call, so error traces include callers
+    /// that tail-called into the failing function.
+    pub fn set_record_tail_callers(&mut self, enabled: bool) {
+        self.record_tail_callers = enabled;
+    }
+
+    /// Returns and clears the output captured since the last call.
+    pub fn take_captured_output(&mut self) -> String {
+        self.captured_output
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     pub fn set_profiling(&mut self, enabled: bool, infos: Vec<profiling::CostCentreInfo>) {
         self.profiling = enabled;
+        alloc_profile::set_enabled(enabled);
         self.cost_centres = infos
             .into_iter()
             .map(|info| profiling::CostCentre {
                 name: info.name,
                 module: info.module,
-                ..Default::default()
             })
             .collect();
+        self.cc_stack.clear();
+        self.call_tree = enabled.then(profiling::CallTree::new);
+    }
+
+    fn current_call_node(&self) -> usize {
+        self.cc_stack.last().map_or(0, |entry| entry.node)
     }
 
     #[inline(always)]
     fn enter_cost_centre(&mut self, idx: u16) {
-        let i = idx as usize;
-        if i < self.cost_centres.len() {
-            self.cost_centres[i].entries += 1;
-            self.cc_stack.push(profiling::CostCentreStackEntry {
-                cc_index: idx,
-                enter_time: std::time::Instant::now(),
-                child_time_ns: 0,
-            });
+        if idx as usize >= self.cost_centres.len() {
+            return;
+        }
+        let parent = self.current_call_node();
+        if let Some(tree) = self.call_tree.as_mut() {
+            tree.charge(parent);
+            let node = tree.child(parent, idx);
+            tree.nodes[node].entries += 1;
+            self.cc_stack.push(profiling::CostCentreStackEntry { node });
         }
     }
 
     #[inline(always)]
     fn exit_cost_centre(&mut self) {
-        if let Some(entry) = self.cc_stack.pop() {
-            let elapsed = entry.enter_time.elapsed().as_nanos() as u64;
-            let self_time = elapsed.saturating_sub(entry.child_time_ns);
-            let i = entry.cc_index as usize;
-            if i < self.cost_centres.len() {
-                self.cost_centres[i].time_ns += elapsed;
-                self.cost_centres[i].inner_time_ns += entry.child_time_ns;
+        let current = self.current_call_node();
+        if let Some(tree) = self.call_tree.as_mut()
+            && self.cc_stack.pop().is_some()
+        {
+            tree.charge(current);
+        }
+    }
+
+    /// Logs the return of the current frame if it is a traced call.
+    #[inline(always)]
+    fn trace_return(&mut self, value: &Value) {
+        if let Some(tracer) = self.call_tracer.as_mut()
+            && let Some(label) = self.frames[self.frame_index].traced
+        {
+            tracer.ret(label, value);
+        }
+    }
+
+    /// Returns the profile collected so far, with `%time` relative to `total_ns`.
+    pub fn profile(&mut self, total_ns: u64) -> profiling::Profile {
+        let current = self.current_call_node();
+        match self.call_tree.as_mut() {
+            Some(tree) => {
+                tree.charge(current);
+                tree.to_profile(&self.cost_centres, total_ns)
             }
-            // Attribute this function's total time as child time of the parent.
-            if let Some(parent) = self.cc_stack.last_mut() {
-                parent.child_time_ns += elapsed;
+            None => profiling::Profile::default(),
+        }
+    }
+
+    /// Logs calls of the functions the compiler marked with `OpTraceCall`, whose names are
+    /// `labels`, and the effect operations performed inside them.
+    pub fn set_call_trace(&mut self, labels: Vec<String>, writer: TraceWriter) {
+        self.call_tracer = Some(Box::new(call_trace::CallTracer::new(labels, writer)));
+    }
+
+    /// Flushes buffered call trace output.
+    pub fn flush_call_trace(&mut self) {
+        if let Some(tracer) = self.call_tracer.as_mut() {
+            tracer.flush();
+        }
+    }
+
+    /// Sends the builtin effects that read the outside world through `log`, which records
+    /// them or answers them from an earlier recording.
+    pub fn set_effect_log(&mut self, log: EffectLog) {
+        self.effect_log = Some(Box::new(log));
+    }
+
+    /// Flushes a recording, or reports how a replay diverged from its log.
+    pub fn finish_effect_log(&mut self) -> Result<(), String> {
+        match self.effect_log.as_mut() {
+            Some(log) => log.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts counting executed instructions and branch outcomes. Every function of the
+    /// program is registered up front, so functions that never run are reported too.
+    pub fn set_coverage(&mut self, enabled: bool) {
+        if !enabled {
+            self.coverage = None;
+            return;
+        }
+        let mut collector = Box::<coverage::CoverageCollector>::default();
+        collector.register(&self.frames[0].closure.function);
+        for constant in &self.constants {
+            match slot::from_slot_ref(constant) {
+                Value::Function(function) => {
+                    collector.register(&function);
+                }
+                Value::Closure(closure) => {
+                    collector.register(&closure.function);
+                }
+                _ => {}
             }
-            let _ = self_time; // used for individual time in the report
         }
+        self.coverage = Some(collector);
     }
 
-    pub fn print_profile_report(&self, execute_ns: u64) {
-        profiling::print_profile_report(&self.cost_centres, execute_ns);
+    /// The coverage counted so far, resolved to source positions.
+    pub fn coverage(&self) -> Option<coverage::CoverageData> {
+        self.coverage.as_ref().map(|collector| collector.finish())
     }
 
     /// A closure that acts as the identity function: `fn(x) -> x`.
@@ -177,42 +324,37 @@ impl VM {
     /// upvalues and no mutable state. Measured ~15% speedup on a 500k
     /// perform microbench.
     pub(crate) fn make_identity_closure(&self) -> Value {
-        thread_local! {
-            static IDENTITY: Rc<Closure> = {
-                let instructions = vec![OpCode::OpReturnLocal as u8, 0];
-                let func = Rc::new(CompiledFunction::new(instructions, 1, 1, None));
-                Rc::new(Closure::new(func, vec![]))
-            };
-        }
-        IDENTITY.with(|c| Value::Closure(Rc::clone(c)))
+        Value::Closure(identity_closure())
     }
 
     pub fn run(&mut self) -> Result<(), String> {
-        match self.run_inner() {
-            Ok(()) => Ok(()),
-            Err(err) => {
-                let normalized = trace::strip_ansi(&err);
-                // Check if error is already formatted (from runtime_error_enhanced / aggregator)
-                // Formatted errors may start with a rendered severity header and include an error code.
-                let has_code = normalized.contains("[E") || normalized.contains("[e");
-                let looks_formatted = has_code
-                    && (normalized.starts_with("Error[")
-                        || normalized.starts_with("error[")
-                        || normalized.starts_with("Warning[")
-                        || normalized.starts_with("Note[")
-                        || normalized.starts_with("Help[")
-                        || normalized.contains("\nError[")
-                        || normalized.contains("\nerror[")
-                        || normalized.contains("\nWarning[")
-                        || normalized.contains("\nNote[")
-                        || normalized.contains("\nHelp["));
-                if looks_formatted {
-                    Err(err)
-                } else {
-                    // Format unmigrated errors through Diagnostic system
-                    Err(self.runtime_error_from_string(&err))
-                }
-            }
+        self.run_inner()
+            .map_err(|err| self.format_runtime_error(err))
+    }
+
+    /// Renders a runtime error through the diagnostic system unless it already is a rendered
+    /// diagnostic.
+    fn format_runtime_error(&self, err: String) -> String {
+        let normalized = trace::strip_ansi(&err);
+        // Check if error is already formatted (from runtime_error_enhanced / aggregator)
+        // Formatted errors may start with a rendered severity header and include an error code.
+        let has_code = normalized.contains("[E") || normalized.contains("[e");
+        let looks_formatted = has_code
+            && (normalized.starts_with("Error[")
+                || normalized.starts_with("error[")
+                || normalized.starts_with("Warning[")
+                || normalized.starts_with("Note[")
+                || normalized.starts_with("Help[")
+                || normalized.contains("\nError[")
+                || normalized.contains("\nerror[")
+                || normalized.contains("\nWarning[")
+                || normalized.contains("\nNote[")
+                || normalized.contains("\nHelp["));
+        if looks_formatted {
+            err
+        } else {
+            // Format unmigrated errors through Diagnostic system
+            self.runtime_error_from_string(&err)
         }
     }
 
@@ -240,13 +382,25 @@ impl VM {
             if self.trace {
                 self.trace_instruction(ip, op);
             }
+            if self.debugger.is_some() {
+                self.debug_instruction();
+            }
 
             let frame_before = self.frame_index;
             // Track closure identity so continuation resume which leaves
             // frame_index unchanged numerically but swaps in a different frame
             // triggers an instruction-pointer refresh.
             let closure_ptr_before = Rc::as_ptr(&closure);
-            let ip_delta = self.dispatch_instruction(instructions, ip, op)?;
+            let ip_delta = match self.dispatch_instruction(instructions, ip, op) {
+                Ok(ip_delta) => ip_delta,
+                Err(err) => {
+                    self.debug_error(&err);
+                    return Err(err);
+                }
+            };
+            if let Some(coverage) = self.coverage.as_deref_mut() {
+                coverage.record(&closure.function, ip, op, ip_delta);
+            }
             self.apply_ip_delta(frame_before, ip_delta, None);
 
             let closure_changed =
@@ -274,9 +428,21 @@ impl VM {
         if self.trace {
             self.trace_instruction(ip, op);
         }
+        if self.debugger.is_some() {
+            self.debug_instruction();
+        }
 
         let frame_before = self.frame_index;
-        let ip_delta = self.dispatch_instruction(instructions, ip, op)?;
+        let ip_delta = self
+            .charge_instruction()
+            .and_then(|()| self.dispatch_instruction(instructions, ip, op))
+            .inspect_err(|err| {
+                self.debug_error(err);
+                self.record_error_trace(err);
+            })?;
+        if let Some(coverage) = self.coverage.as_deref_mut() {
+            coverage.record(&closure.function, ip, op, ip_delta);
+        }
         self.apply_ip_delta(frame_before, ip_delta, invoke_target_frame);
         Ok(())
     }
@@ -324,6 +490,7 @@ impl VM {
             elements.push(slot::from_slot(s));
         }
         leak_detector::record_array();
+        alloc_profile::record(AllocKind::Array);
         Value::Array(Rc::new(elements))
     }
 
@@ -605,6 +772,31 @@ impl VM {
         self.globals[idx] = slot::to_slot(v);
     }
 
+    /// Replaces the main frame with `bytecode` and runs it from `ip`, keeping globals.
+    ///
+    /// Used by the REPL, whose compiler appends each input to one growing main scope: only
+    /// the instructions emitted since the previous run are executed.
+    pub fn run_from(&mut self, bytecode: Bytecode, ip: usize) -> Result<(), String> {
+        let main_fn = CompiledFunction::new(bytecode.instructions, 0, 0, bytecode.debug_info);
+        let main_closure = Closure::new(Rc::new(main_fn), vec![]);
+        let mut main_frame = Frame::new(Rc::new(main_closure), 0);
+        main_frame.ip = ip;
+
+        self.constants = bytecode.constants.into_iter().map(slot::to_slot).collect();
+        self.sp = 0;
+        self.frames = vec![main_frame];
+        self.frame_index = 0;
+        self.handler_stack.clear();
+        self.evv = EvidenceVector::new();
+        self.yield_state = YieldState::new();
+        self.run()
+    }
+
+    /// Stores `value` in global slot `idx` from outside the dispatch loop.
+    pub fn set_global_value(&mut self, idx: usize, value: Value) {
+        self.global_set(idx, value);
+    }
+
     /// Returns the last popped value from the stack.
     ///
     /// After a program completes execution, this returns the final result.
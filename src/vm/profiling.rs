@@ -1,79 +1,177 @@
 use std::time::Instant;
 
 pub use crate::bytecode::debug_info::CostCentreInfo;
+use crate::runtime::alloc_profile::{self, AllocCounts};
 
 /// Runtime profiling data accumulated for a cost centre.
 #[derive(Debug, Clone, Default)]
 pub struct CostCentre {
     pub name: String,
     pub module: String,
-    pub entries: u64,
-    pub time_ns: u64,
-    pub inner_time_ns: u64,
 }
 
 /// A single frame on the cost centre stack, pushed on function entry.
 #[derive(Debug)]
 pub(crate) struct CostCentreStackEntry {
-    pub cc_index: u16,
-    pub enter_time: Instant,
-    pub child_time_ns: u64,
+    /// The call-tree node of the stack this frame completes.
+    pub node: usize,
+}
+
+/// One node per distinct cost-centre stack seen during the run; node 0 is the root, which is
+/// charged for everything that runs outside any cost centre.
+#[derive(Debug, Clone)]
+pub(crate) struct CallNode {
+    pub cost_centre: Option<u16>,
+    pub parent: usize,
+    pub children: Vec<usize>,
+    pub entries: u64,
+    pub self_time_ns: u64,
+    pub allocs: AllocCounts,
+}
+
+/// The call tree plus the clock and allocation readings of the last charge.
+#[derive(Debug)]
+pub(crate) struct CallTree {
+    pub nodes: Vec<CallNode>,
+    last_time: Instant,
+    last_allocs: AllocCounts,
 }
 
-/// Print the profiling report in GHC-style format.
-pub fn print_profile_report(centres: &[CostCentre], total_time_ns: u64) {
-    if centres.is_empty() || total_time_ns == 0 {
-        return;
+impl CallTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![CallNode {
+                cost_centre: None,
+                parent: 0,
+                children: Vec::new(),
+                entries: 0,
+                self_time_ns: 0,
+                allocs: AllocCounts::default(),
+            }],
+            last_time: Instant::now(),
+            last_allocs: alloc_profile::snapshot(),
+        }
     }
 
-    // Filter to cost centres that were actually entered.
-    let mut active: Vec<(usize, &CostCentre)> = centres
-        .iter()
-        .enumerate()
-        .filter(|(_, cc)| cc.entries > 0)
-        .collect();
+    /// Charges the time and allocations since the last charge to `node`.
+    pub fn charge(&mut self, node: usize) {
+        let now = Instant::now();
+        let allocs = alloc_profile::snapshot();
+        let charged = &mut self.nodes[node];
+        charged.self_time_ns += now.duration_since(self.last_time).as_nanos() as u64;
+        charged.allocs += allocs - self.last_allocs;
+        self.last_time = now;
+        self.last_allocs = allocs;
+    }
+
+    /// Returns the child of `parent` for `cost_centre`, creating it on first entry.
+    pub fn child(&mut self, parent: usize, cost_centre: u16) -> usize {
+        if let Some(&child) = self.nodes[parent]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].cost_centre == Some(cost_centre))
+        {
+            return child;
+        }
+        let child = self.nodes.len();
+        self.nodes.push(CallNode {
+            cost_centre: Some(cost_centre),
+            parent,
+            children: Vec::new(),
+            entries: 0,
+            self_time_ns: 0,
+            allocs: AllocCounts::default(),
+        });
+        self.nodes[parent].children.push(child);
+        child
+    }
 
-    if active.is_empty() {
-        return;
+    /// Flattens the tree into a [`Profile`] over the cost centres `centres`.
+    pub fn to_profile(&self, centres: &[CostCentre], total_ns: u64) -> Profile {
+        let frames = centres
+            .iter()
+            .map(|cc| ProfileFrame {
+                name: cc.name.clone(),
+                module: cc.module.clone(),
+            })
+            .collect();
+        let mut stacks: Vec<ProfileStack> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let mut frames = match node.cost_centre {
+                // Parents are always created before their children.
+                Some(_) => stacks[node.parent].frames.clone(),
+                None => Vec::new(),
+            };
+            frames.extend(node.cost_centre.map(usize::from));
+            stacks.push(ProfileStack {
+                frames,
+                entries: node.entries,
+                self_ns: node.self_time_ns,
+                allocs: node.allocs,
+            });
+        }
+        Profile {
+            frames,
+            stacks,
+            total_ns,
+        }
     }
+}
 
-    // Sort by self time descending.
-    active.sort_by(|a, b| {
-        let self_a = a.1.time_ns.saturating_sub(a.1.inner_time_ns);
-        let self_b = b.1.time_ns.saturating_sub(b.1.inner_time_ns);
-        self_b.cmp(&self_a)
-    });
+/// A finished profile: every cost-centre stack seen during a run, with the entries, self
+/// time and allocations charged while it was on top. Built by the VM, or read back from the
+/// file a profiled native binary writes at exit.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub frames: Vec<ProfileFrame>,
+    pub stacks: Vec<ProfileStack>,
+    /// Wall time of the run; the flat report's `%time` is relative to it.
+    pub total_ns: u64,
+}
 
-    eprintln!();
-    eprintln!("  ── Flux Profiling Report ─────────────────────────────────────");
-    eprintln!(
-        "  {:<24} {:<16} {:>8} {:>8}",
-        "COST CENTRE", "MODULE", "entries", "%time"
-    );
-    eprintln!(
-        "  {:<24} {:<16} {:>8} {:>8}",
-        "───────────", "──────", "───────", "─────"
-    );
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileFrame {
+    pub name: String,
+    pub module: String,
+}
 
-    for (_, cc) in &active {
-        let self_time = cc.time_ns.saturating_sub(cc.inner_time_ns);
-        let pct_time = (self_time as f64 / total_time_ns as f64) * 100.0;
+/// A cost-centre stack as indices into [`Profile::frames`], outermost first. The empty stack
+/// is time spent outside any cost centre.
+#[derive(Debug, Clone)]
+pub struct ProfileStack {
+    pub frames: Vec<usize>,
+    pub entries: u64,
+    pub self_ns: u64,
+    pub allocs: AllocCounts,
+}
 
-        eprintln!(
-            "  {:<24} {:<16} {:>8} {:>7.1}%",
-            truncate(&cc.name, 24),
-            truncate(&cc.module, 16),
-            cc.entries,
-            pct_time,
-        );
+impl ProfileFrame {
+    /// `name` for the main module, `Module.name` otherwise.
+    pub fn label(&self) -> String {
+        if self.module == "<main>" || self.module.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.module, self.name)
+        }
     }
-    eprintln!("  ──────────────────────────────────────────────────────────────");
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}…", &s[..max - 1])
+impl Profile {
+    /// The `;`-separated frame labels of `stack`.
+    pub fn stack_label(&self, stack: &ProfileStack) -> String {
+        stack
+            .frames
+            .iter()
+            .map(|&frame| self.frames[frame].label())
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    pub fn total_allocs(&self) -> AllocCounts {
+        let mut total = AllocCounts::default();
+        for stack in &self.stacks {
+            total += stack.allocs;
+        }
+        total
     }
 }
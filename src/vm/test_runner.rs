@@ -1,7 +1,9 @@
 use std::env;
-use std::time::Instant;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use crate::compiler::symbol_table::SymbolTable;
+use crate::diagnostics::position::Span;
 use crate::runtime::{RuntimeContext, value::Value};
 use crate::syntax::interner::Interner;
 
@@ -9,13 +11,33 @@ use super::VM;
 
 pub enum TestOutcome {
     Pass,
-    Fail(String),
+    Fail(TestFailure),
+}
+
+pub struct TestFailure {
+    pub message: String,
+    /// File and span of the failing assertion: the innermost frame in the test's own module,
+    /// or the innermost frame with a location when none is.
+    pub location: Option<(String, Span)>,
+}
+
+impl TestFailure {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            location: None,
+        }
+    }
 }
 
 pub struct TestResult {
     pub name: String,
+    /// Source path of the module the test was collected from.
+    pub module: String,
     pub elapsed_ms: f64,
     pub outcome: TestOutcome,
+    /// Program output captured while the test ran; empty when output was not captured.
+    pub output: String,
 }
 
 /// Collects test function names and their global slot indices from the symbol table.
@@ -29,13 +51,35 @@ pub fn collect_test_functions(
     symbol_table: &SymbolTable,
     interner: &Interner,
 ) -> Vec<(String, usize)> {
-    let mut tests: Vec<(String, usize)> = symbol_table
+    collect_prefixed_functions(symbol_table, interner, "Tests.", "test_")
+}
+
+/// Collects benchmark function names and their global slot indices from the symbol table.
+///
+/// Discovers top-level `bench_*` functions and `bench_*` functions inside a `Benches`
+/// module, in definition order, the same way [`collect_test_functions`] finds tests.
+pub fn collect_bench_functions(
+    symbol_table: &SymbolTable,
+    interner: &Interner,
+) -> Vec<(String, usize)> {
+    collect_prefixed_functions(symbol_table, interner, "Benches.", "bench_")
+}
+
+fn collect_prefixed_functions(
+    symbol_table: &SymbolTable,
+    interner: &Interner,
+    module: &str,
+    prefix: &str,
+) -> Vec<(String, usize)> {
+    let mut functions: Vec<(String, usize)> = symbol_table
         .global_definitions()
         .into_iter()
         .filter_map(|(sym, idx)| {
             let name = interner.resolve(sym);
-            if name.starts_with("test_")
-                || (name.starts_with("Tests.") && name["Tests.".len()..].starts_with("test_"))
+            if name.starts_with(prefix)
+                || name
+                    .strip_prefix(module)
+                    .is_some_and(|rest| rest.starts_with(prefix))
             {
                 Some((name.to_string(), idx))
             } else {
@@ -45,26 +89,32 @@ pub fn collect_test_functions(
         .collect();
 
     // Sort by global slot index to preserve source definition order.
-    tests.sort_by_key(|(_, idx)| *idx);
-    tests
+    functions.sort_by_key(|(_, idx)| *idx);
+    functions
 }
 
 /// Runs a resolved list of `(name, Value)` test functions via `invoke_value`
 /// on any `RuntimeContext` (VM or JIT). Returns the per-test results.
-pub fn run_test_fns(ctx: &mut dyn RuntimeContext, fns: Vec<(String, Value)>) -> Vec<TestResult> {
+pub fn run_test_fns(
+    ctx: &mut dyn RuntimeContext,
+    module: &str,
+    fns: Vec<(String, Value)>,
+) -> Vec<TestResult> {
     let mut results = Vec::new();
 
     for (name, fn_value) in fns {
         let start = Instant::now();
         let outcome = match ctx.invoke_value(fn_value, vec![]) {
             Ok(_) => TestOutcome::Pass,
-            Err(msg) => TestOutcome::Fail(msg),
+            Err(msg) => TestOutcome::Fail(TestFailure::new(msg)),
         };
         let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
         results.push(TestResult {
             name,
+            module: module.to_string(),
             elapsed_ms,
             outcome,
+            output: String::new(),
         });
     }
 
@@ -72,17 +122,72 @@ pub fn run_test_fns(ctx: &mut dyn RuntimeContext, fns: Vec<(String, Value)>) ->
 }
 
 /// VM convenience: extract test values from `vm.globals` then run them.
-pub fn run_tests(vm: &mut VM, tests: Vec<(String, usize)>) -> Vec<TestResult> {
-    // Use global_get to decode Slot -> Value, supporting both nan-boxing and non-nan-boxing builds.
-    let fns: Vec<(String, Value)> = tests
-        .into_iter()
-        .map(|(name, idx)| (name, vm.global_get(idx)))
-        .collect();
-    run_test_fns(vm, fns)
+///
+/// On the VM, failures also carry the location of the failing assertion and each result gets
+/// the output captured while it ran (see [`VM::set_capture_output`]). A test still running
+/// after `timeout` fails with a timeout error and the next test starts.
+pub fn run_tests(
+    vm: &mut VM,
+    module: &str,
+    tests: Vec<(String, usize)>,
+    timeout: Option<Duration>,
+) -> Vec<TestResult> {
+    let module_path = Path::new(module).canonicalize().ok();
+    let mut results = Vec::new();
+    vm.set_record_tail_callers(true);
+
+    for (name, idx) in tests {
+        // Use global_get to decode Slot -> Value, supporting both nan-boxing and non-nan-boxing builds.
+        let fn_value = vm.global_get(idx);
+        let start = Instant::now();
+        vm.set_time_limit(timeout);
+        let outcome = match vm.invoke_value(fn_value, vec![]) {
+            Ok(_) => TestOutcome::Pass,
+            Err(message) => {
+                let trace = vm.take_error_trace(&message);
+                TestOutcome::Fail(TestFailure {
+                    location: failure_location(trace, module, module_path.as_deref()),
+                    message,
+                })
+            }
+        };
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        results.push(TestResult {
+            name,
+            module: module.to_string(),
+            elapsed_ms,
+            outcome,
+            output: vm.take_captured_output(),
+        });
+    }
+    vm.set_time_limit(None);
+
+    results
 }
 
-/// Prints the test report and returns `true` if all tests passed.
-pub fn print_test_report(file_name: &str, results: &[TestResult]) -> bool {
+/// Picks the innermost location in the test's own module, so a failing `assert_eq` is reported
+/// at its call site rather than inside `Flow.Assert`. That location is named by `module` as
+/// given, rather than by the canonical path in the debug info.
+fn failure_location(
+    trace: Vec<(String, Span)>,
+    module: &str,
+    module_path: Option<&Path>,
+) -> Option<(String, Span)> {
+    // Generated programs, such as doc-tests, have no file to canonicalize.
+    let in_module = trace.iter().find(|(file, _)| {
+        file == module
+            || module_path
+                .is_some_and(|path| Path::new(file).canonicalize().ok().as_deref() == Some(path))
+    });
+    match in_module {
+        Some((_, span)) => Some((module.to_string(), *span)),
+        None => trace.into_iter().next(),
+    }
+}
+
+/// Prints the test report and returns `true` if all tests passed. `notes` are printed under
+/// the test counts.
+pub fn print_test_report(file_name: &str, results: &[TestResult], notes: &[String]) -> bool {
     println!("Running tests in {}\n", file_name);
     let use_color = colors_enabled();
 
@@ -145,7 +250,7 @@ pub fn print_test_report(file_name: &str, results: &[TestResult]) -> bool {
                 );
                 passed += 1;
             }
-            TestOutcome::Fail(msg) => {
+            TestOutcome::Fail(failure) => {
                 let fail = if use_color {
                     red("FAIL")
                 } else {
@@ -160,7 +265,7 @@ pub fn print_test_report(file_name: &str, results: &[TestResult]) -> bool {
                         result.name.as_str()
                     }
                 );
-                for line in msg.lines() {
+                for line in failure.message.lines() {
                     println!("          {}", line);
                 }
                 failed += 1;
@@ -170,6 +275,9 @@ pub fn print_test_report(file_name: &str, results: &[TestResult]) -> bool {
 
     let total = passed + failed;
     println!("\n{} tests: {} passed, {} failed", total, passed, failed);
+    for note in notes {
+        println!("{}", note);
+    }
 
     if failed == 0 {
         if use_color {
@@ -145,6 +145,55 @@ impl VM {
         (name, location)
     }
 
+    /// Remembers the frames an error was raised under before an invoke unwinds them.
+    ///
+    /// The error is seen again by every enclosing invoke it propagates through; only the first,
+    /// innermost sighting is kept.
+    pub(super) fn record_error_trace(&mut self, err: &str) {
+        if self
+            .error_trace
+            .as_ref()
+            .is_some_and(|(recorded, _)| recorded == err)
+        {
+            return;
+        }
+        // Caller frames have already stepped past their call instruction, so their location is
+        // looked up one byte back; a tail caller's offset is the tail call itself.
+        let locations = self.frames[..=self.frame_index]
+            .iter()
+            .rev()
+            .enumerate()
+            .flat_map(|(depth, frame)| {
+                let ip = if depth == 0 {
+                    frame.ip
+                } else {
+                    frame.ip.saturating_sub(1)
+                };
+                let tail_caller = frame
+                    .tail_caller
+                    .as_ref()
+                    .map(|(closure, ip)| (closure, *ip));
+                std::iter::once((&frame.closure, ip)).chain(tail_caller)
+            })
+            .filter_map(|(closure, ip)| {
+                let info = closure.function.debug_info.as_ref()?;
+                let location = info.location_at(ip)?;
+                let file = info.file_for(location.file_id)?;
+                Some((file.to_string(), location.span))
+            })
+            .collect();
+        self.error_trace = Some((err.to_string(), locations));
+    }
+
+    /// Returns the source locations of the frames `err` was raised under, innermost first,
+    /// when it was the last error raised inside an invoked callable.
+    pub fn take_error_trace(&mut self, err: &str) -> Vec<(String, Span)> {
+        match self.error_trace.take() {
+            Some((recorded, locations)) if recorded == err => locations,
+            _ => Vec::new(),
+        }
+    }
+
     fn render_runtime_diagnostic(&self, diag: &Diagnostic, file: &str) -> String {
         let source = std::fs::read_to_string(file).ok();
         let stack_frames = self.frames[..=self.frame_index]
@@ -219,7 +268,7 @@ impl VM {
     }
 }
 
-pub(super) fn strip_ansi(input: &str) -> String {
+pub(crate) fn strip_ansi(input: &str) -> String {
     let mut out = String::with_capacity(input.len());
     let mut chars = input.chars().peekable();
     while let Some(ch) = chars.next() {
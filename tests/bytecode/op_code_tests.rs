@@ -203,6 +203,8 @@ mod tests {
         assert_eq!(operand_widths(OpCode::OpConsumeLocal1), Vec::<usize>::new());
         assert_eq!(operand_widths(OpCode::OpIsAdtJumpLocal), vec![1, 2, 2]);
         assert_eq!(operand_widths(OpCode::OpAdd), Vec::<usize>::new());
+        assert_eq!(operand_widths(OpCode::OpTraceCall), vec![2]);
+        assert_eq!(operand_widths(OpCode::OpTracePerform), vec![2, 1]);
     }
 
     #[test]
@@ -210,7 +212,7 @@ mod tests {
         // Ensure MAX_OPCODE stays in sync when new opcodes are added.
         assert_eq!(
             flux::bytecode::op_code::MAX_OPCODE,
-            OpCode::OpReturnCheck as u8,
+            OpCode::OpTracePerform as u8,
             "MAX_OPCODE must equal the last OpCode variant"
         );
     }
@@ -1,6 +1,6 @@
 use flux::diagnostics::position::{Position, Span};
 use flux::diagnostics::{
-    DiagnosticCategory, ERROR_CODES, LabelStyle, Severity, call_arg_type_mismatch,
+    Applicability, DiagnosticCategory, ERROR_CODES, LabelStyle, Severity, call_arg_type_mismatch,
     constructor_pattern_arity_mismatch, cross_module_constructor_access_error,
     cross_module_constructor_access_warning, fun_arity_mismatch, fun_param_type_mismatch,
     fun_return_annotation_mismatch, fun_return_type_mismatch, if_branch_type_mismatch,
@@ -217,7 +217,13 @@ fn e300_constructor_shapes_have_primary_labels() {
 
 #[test]
 fn parser_diagnostic_constructor_shapes_for_059() {
-    let kw = unknown_keyword_alias(span(1, 1, 4), "def", "fn", "function declarations");
+    let kw = unknown_keyword_alias(
+        span(1, 1, 4),
+        "def",
+        "fn",
+        "function declarations",
+        Applicability::MachineApplicable,
+    );
     assert_eq!(kw.code(), Some("E030"));
     assert_eq!(kw.category(), Some(DiagnosticCategory::ParserKeyword));
     assert!(
@@ -231,6 +237,12 @@ fn parser_diagnostic_constructor_shapes_for_059() {
             .any(|h| h.text.contains("Did you mean `fn`")),
         "expected alias hint"
     );
+    assert!(
+        kw.suggestions()
+            .iter()
+            .any(|s| s.replacement == "fn" && s.is_machine_applicable()),
+        "expected machine-applicable alias suggestion"
+    );
 
     let if_brace = missing_if_body_brace(span(2, 9, 10));
     assert_eq!(if_brace.code(), Some("E034"));
@@ -0,0 +1,33 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, combined_output};
+
+#[test]
+fn check_type_checks_without_executing() {
+    let project = TempProject::new("check_cli_ok");
+    project.write(
+        "ok.flx",
+        "fn main() with IO {\n  print(\"side effect ran\")\n}\n",
+    );
+
+    let output = project.run(&["check", "ok.flx", "--no-cache"]);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "{text}");
+    assert!(text.contains("no errors"), "{text}");
+    assert!(!text.contains("side effect ran"), "{text}");
+}
+
+#[test]
+fn check_fails_on_type_errors() {
+    let project = TempProject::new("check_cli_bad");
+    project.write(
+        "bad.flx",
+        "fn main() with IO {\n  let x: Int = \"a\"\n  print(x)\n}\n",
+    );
+
+    let output = project.run(&["check", "bad.flx", "--no-cache"]);
+    let text = combined_output(&output);
+    assert_eq!(output.status.code(), Some(1), "{text}");
+    assert!(text.contains("E300"), "{text}");
+}
@@ -0,0 +1,80 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, repo_root, run_flux};
+
+const PROGRAM: &str = r#"data Shape {
+    Circle(Int),
+    Rect(Int, Int),
+}
+
+effect Counter {
+    next: () -> Int
+}
+
+fn area(s: Shape) -> Int {
+    match s {
+        Circle(r) -> r * r * 3,
+        Rect(w, h) -> w * h,
+    }
+}
+
+fn count_twice() -> Int with Counter {
+    let _first = perform Counter.next()
+    perform Counter.next()
+}
+
+fn main() with IO {
+    print(map([Circle(2), Rect(3, 4)], area))
+    let pairs = [a + b | a <- [1, 2], b <- [10, 20]]
+    print(pairs)
+    print(1 == 1)
+    print("ab" == "ab")
+    let result = count_twice() handle Counter(0) {
+        next(resume, state) -> resume(state, state + 1)
+    }
+    println("counter=" + to_string(result))
+}
+"#;
+
+#[test]
+fn core_eval_matches_vm_output() {
+    let project = TempProject::new("core_eval_matches");
+    let input = project.write("main.flx", PROGRAM);
+    let input = input.to_str().unwrap();
+    let vm = run_flux(repo_root(), &[input, "--no-cache"]);
+    let core = run_flux(repo_root(), &[input, "--core-eval", "--no-cache"]);
+
+    let core_stderr = String::from_utf8_lossy(&core.stderr);
+    assert!(
+        vm.status.success(),
+        "{}",
+        String::from_utf8_lossy(&vm.stderr)
+    );
+    assert!(core.status.success(), "{core_stderr}");
+    assert!(!core_stderr.contains("behavior changed"), "{core_stderr}");
+    assert_eq!(
+        String::from_utf8_lossy(&core.stdout),
+        String::from_utf8_lossy(&vm.stdout)
+    );
+    assert!(String::from_utf8_lossy(&core.stdout).contains("counter=1"));
+}
+
+#[test]
+fn core_eval_reports_runtime_errors() {
+    let project = TempProject::new("core_eval_panic");
+    let input = project.write(
+        "main.flx",
+        "fn main() with IO {\n    print(\"before\")\n    panic(\"boom\")\n}\n",
+    );
+    let output = run_flux(
+        repo_root(),
+        &[input.to_str().unwrap(), "--core-eval", "--no-cache"],
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "\"before\"\n");
+    assert!(stderr.contains("runtime error:"), "{stderr}");
+    assert!(stderr.contains("boom"), "{stderr}");
+}
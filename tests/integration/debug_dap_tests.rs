@@ -0,0 +1,328 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, flux_command, repo_root};
+
+const PROGRAM: &str = "\
+fn add(a: Int, b: Int) -> Int {
+  let total = a + b
+  total
+}
+
+fn main() with IO {
+  let base = 10
+  let pair = (base, [1, 2])
+  let scale = \\x -> x * base
+  let result = add(base, 5)
+  println(scale(result))
+  println(100 / (result - 15))
+}
+";
+
+/// A scripted DAP client driving `flux debug --dap` over its stdio.
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<Value>,
+    seq: i64,
+    /// Events received while waiting for responses, oldest first.
+    events: Vec<Value>,
+}
+
+impl Client {
+    fn launch(path: &Path) -> Self {
+        let mut child = flux_command(repo_root(), &["debug", path.to_str().unwrap(), "--dap"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn flux debug");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Some(message) = read_message(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            child,
+            stdin,
+            messages,
+            seq: 0,
+            events: Vec::new(),
+        }
+    }
+
+    fn next_message(&mut self) -> Value {
+        self.messages
+            .recv_timeout(Duration::from_secs(60))
+            .expect("debug adapter stopped responding")
+    }
+
+    fn request(&mut self, command: &str, arguments: Value) -> Value {
+        self.seq += 1;
+        let message = json!({
+            "seq": self.seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        });
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+        loop {
+            let message = self.next_message();
+            if message["type"] == "response" && message["request_seq"] == self.seq {
+                return message;
+            }
+            self.events.push(message);
+        }
+    }
+
+    fn body(&mut self, command: &str, arguments: Value) -> Value {
+        let response = self.request(command, arguments);
+        assert_eq!(response["success"], true, "{response}");
+        response["body"].clone()
+    }
+
+    fn wait_event(&mut self, event: &str) -> Value {
+        if let Some(index) = self.events.iter().position(|m| m["event"] == event) {
+            return self.events.remove(index);
+        }
+        loop {
+            let message = self.next_message();
+            if message["event"] == event {
+                return message;
+            }
+            self.events.push(message);
+        }
+    }
+
+    fn start(path: &Path, breakpoints: &[u64]) -> Self {
+        let mut client = Self::launch(path);
+        client.body("initialize", json!({ "adapterID": "flux" }));
+        client.wait_event("initialized");
+        let lines: Vec<Value> = breakpoints
+            .iter()
+            .map(|line| json!({ "line": line }))
+            .collect();
+        let body = client.body(
+            "setBreakpoints",
+            json!({ "source": { "path": path }, "breakpoints": lines }),
+        );
+        for breakpoint in body["breakpoints"].as_array().unwrap() {
+            assert_eq!(breakpoint["verified"], true, "{body}");
+        }
+        client.body("launch", json!({ "program": path }));
+        client.body("configurationDone", json!({}));
+        client
+    }
+
+    fn stack(&mut self) -> Vec<Value> {
+        let body = self.body("stackTrace", json!({ "threadId": 1 }));
+        body["stackFrames"].as_array().unwrap().clone()
+    }
+
+    fn variables(&mut self, frame_id: &Value, scope: &str) -> Vec<(String, Value)> {
+        let scopes = self.body("scopes", json!({ "frameId": frame_id }));
+        let reference = scopes["scopes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["name"] == scope)
+            .unwrap_or_else(|| panic!("no {scope} scope in {scopes}"))["variablesReference"]
+            .clone();
+        self.children(&reference)
+    }
+
+    fn children(&mut self, reference: &Value) -> Vec<(String, Value)> {
+        let body = self.body("variables", json!({ "variablesReference": reference }));
+        body["variables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| (v["name"].as_str().unwrap().to_string(), v.clone()))
+            .collect()
+    }
+
+    fn finish(mut self) -> String {
+        self.body("disconnect", json!({}));
+        drop(self.stdin);
+        let status = self.child.wait().unwrap();
+        let mut stderr = String::new();
+        self.child
+            .stderr
+            .take()
+            .unwrap()
+            .read_to_string(&mut stderr)
+            .unwrap();
+        assert!(status.code().is_some(), "{stderr}");
+        stderr
+    }
+}
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; length?];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+/// Writes the program into its own project; keep the project alive while debugging.
+fn program_file(name: &str) -> (TempProject, PathBuf) {
+    let project = TempProject::new(&format!("debug_dap_{name}"));
+    let path = fs::canonicalize(project.write(&format!("{name}.flx"), PROGRAM)).unwrap();
+    (project, path)
+}
+
+fn value_of<'a>(variables: &'a [(String, Value)], name: &str) -> &'a Value {
+    &variables
+        .iter()
+        .find(|(n, _)| n == name)
+        .unwrap_or_else(|| panic!("no variable `{name}` in {variables:?}"))
+        .1
+}
+
+#[test]
+fn breakpoint_shows_stack_locals_and_captures() {
+    let (_project, path) = program_file("breakpoints");
+    let mut client = Client::start(&path, &[2, 9]);
+
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "breakpoint");
+    let frames = client.stack();
+    assert_eq!(frames[0]["line"], 9);
+    assert_eq!(frames[0]["source"]["path"], path.to_string_lossy().as_ref());
+    let locals = client.variables(&frames[0]["id"], "Locals");
+    assert_eq!(value_of(&locals, "base")["value"], "10");
+    let pair = value_of(&locals, "pair");
+    assert_ne!(pair["variablesReference"], 0, "{pair}");
+    let reference = pair["variablesReference"].clone();
+    let components = client.children(&reference);
+    assert_eq!(value_of(&components, "0")["value"], "10");
+    assert_eq!(value_of(&components, "1")["value"], "[1, 2]");
+
+    client.body("continue", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "breakpoint");
+    let frames = client.stack();
+    let names: Vec<&str> = frames.iter().map(|f| f["name"].as_str().unwrap()).collect();
+    assert!(names[0].contains("add"), "{names:?}");
+    assert!(names[1].contains("main"), "{names:?}");
+    assert_eq!(frames[1]["line"], 10);
+    let locals = client.variables(&frames[0]["id"], "Locals");
+    assert_eq!(value_of(&locals, "a")["value"], "10");
+    assert_eq!(value_of(&locals, "b")["value"], "5");
+    let evaluated = client.body(
+        "evaluate",
+        json!({ "expression": "b", "frameId": frames[0]["id"] }),
+    );
+    assert_eq!(evaluated["result"], "5");
+
+    client.body("stepOut", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "step");
+    let frames = client.stack();
+    assert!(frames[0]["name"].as_str().unwrap().contains("main"));
+
+    client.body("stepIn", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(client.stack()[0]["line"], 11);
+    client.body("stepIn", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    let frames = client.stack();
+    assert_eq!(
+        frames[0]["line"], 9,
+        "stepping into the closure: {frames:?}"
+    );
+    let captures = client.variables(&frames[0]["id"], "Captured");
+    assert_eq!(value_of(&captures, "base")["value"], "10");
+
+    client.body("continue", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "exception");
+    assert_eq!(
+        stopped["body"]["description"],
+        "error[E1008]: Division By Zero"
+    );
+    assert!(
+        stopped["body"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("breakpoints.flx:12:11"),
+        "{stopped}"
+    );
+    let output = client.wait_event("output");
+    assert_eq!(output["body"]["category"], "stdout");
+    assert_eq!(output["body"]["output"], "150\n");
+
+    client.body("continue", json!({ "threadId": 1 }));
+    let exited = client.wait_event("exited");
+    assert_eq!(exited["body"]["exitCode"], 1);
+    client.wait_event("terminated");
+    let stderr = client.finish();
+    assert!(stderr.contains("Division By Zero"), "{stderr}");
+}
+
+#[test]
+fn step_over_stays_in_the_current_frame() {
+    let (_project, path) = program_file("stepping");
+    let mut client = Client::start(&path, &[10]);
+
+    client.wait_event("stopped");
+    assert_eq!(client.stack()[0]["line"], 10);
+    client.body("next", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "step");
+    let frames = client.stack();
+    assert_eq!(frames[0]["line"], 11);
+    assert!(
+        frames[0]["name"].as_str().unwrap().contains("main"),
+        "{frames:?}"
+    );
+
+    client.finish();
+}
+
+#[test]
+fn stop_on_entry_pauses_at_the_first_line_of_the_program() {
+    let (_project, path) = program_file("entry");
+    let mut client = Client::launch(&path);
+    client.body("initialize", json!({ "adapterID": "flux" }));
+    client.body("launch", json!({ "program": path, "stopOnEntry": true }));
+    client.body("configurationDone", json!({}));
+
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "entry");
+    let threads = client.body("threads", json!({}));
+    assert_eq!(threads["threads"][0]["id"], 1);
+
+    client.finish();
+}
@@ -0,0 +1,50 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, combined_output, repo_root, run_flux};
+
+fn emit_programs(project: &TempProject, out: &str, seed: &str, count: &str) {
+    let output = project.run(&[
+        "fuzz-parity",
+        "--seed",
+        seed,
+        "--count",
+        count,
+        "--emit",
+        "--out",
+        out,
+    ]);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "{text}");
+    assert!(text.contains(&format!("wrote {count} programs")), "{text}");
+}
+
+#[test]
+fn fuzz_parity_emits_the_same_programs_for_a_seed() {
+    let project = TempProject::new("fuzz_parity_repeat");
+    emit_programs(&project, "first", "42", "3");
+    emit_programs(&project, "second", "42", "3");
+
+    for seed in 42..45 {
+        let name = format!("seed-{seed}.flx");
+        let a = project.read(&format!("first/{name}"));
+        let b = project.read(&format!("second/{name}"));
+        assert_eq!(a, b, "{name} differs between runs");
+    }
+}
+
+#[test]
+fn fuzz_parity_programs_type_check() {
+    let project = TempProject::new("fuzz_parity_check");
+    emit_programs(&project, "programs", "0", "10");
+
+    for seed in 0..10 {
+        let program = project.path().join(format!("programs/seed-{seed}.flx"));
+        let output = run_flux(
+            repo_root(),
+            &["check", program.to_str().unwrap(), "--no-cache"],
+        );
+        let text = combined_output(&output);
+        assert!(output.status.success(), "seed {seed}:\n{text}");
+    }
+}
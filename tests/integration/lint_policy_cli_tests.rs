@@ -0,0 +1,104 @@
+use std::fs;
+
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::TempProject;
+
+const PROGRAM: &str = r#"fn helper(a, b, c, d, e, f) {
+    a + b + c + d + e + f
+}
+
+fn main() with IO {
+    println(helper(1, 2, 3, 4, 5, 6))
+}
+"#;
+
+fn write_program(name: &str) -> TempProject {
+    let project = TempProject::new(&format!("lint_policy_{name}"));
+    project.write("main.flx", PROGRAM);
+    project
+}
+
+#[test]
+fn lint_warnings_keep_a_zero_exit_status() {
+    let project = write_program("warn");
+    let output = project.run(&["lint", "main.flx"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("Warning[W010]: Too Many Parameters"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Warning[W007]: Unused Function"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn deny_and_allow_flags_set_lint_levels() {
+    let project = write_program("flags");
+    let output = project.run(&["lint", "main.flx", "--deny", "W010", "--allow=W007"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("error[W010]: Too Many Parameters"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("W007"), "{stdout}");
+}
+
+#[test]
+fn fluxlint_file_sets_levels_and_thresholds() {
+    let project = write_program("file");
+    project.write(
+        ".fluxlint.toml",
+        "deny = [\"W007\"]\nmax_function_params = 6\n",
+    );
+
+    let denied = project.run(&["lint", "main.flx"]);
+    let stdout = String::from_utf8_lossy(&denied.stdout);
+    assert!(!denied.status.success(), "{stdout}");
+    assert!(stdout.contains("error[W007]: Unused Function"), "{stdout}");
+    assert!(!stdout.contains("W010"), "{stdout}");
+
+    let warned = project.run(&["lint", "main.flx", "--warn", "W007"]);
+    let stdout = String::from_utf8_lossy(&warned.stdout);
+    assert!(warned.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("Warning[W007]: Unused Function"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn manifest_lint_table_is_used_without_fluxlint_file() {
+    let project = write_program("manifest");
+    fs::create_dir_all(project.path().join("src")).unwrap();
+    project.write(
+        "flux.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[lint]\ndeny = [\"W010\"]\n",
+    );
+
+    let output = project.run(&["lint", "main.flx"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("error[W010]: Too Many Parameters"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn invalid_lint_configuration_is_reported() {
+    let project = write_program("invalid");
+    project.write(".fluxlint.toml", "deny = [\"W999\"]\n");
+
+    let output = project.run(&["lint", "main.flx"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert!(stderr.contains("unknown lint `W999`"), "{stderr}");
+}
@@ -0,0 +1,222 @@
+use std::io::Cursor;
+
+use flux::diagnostics::{Severity, position::Position};
+use flux::lsp::{
+    Analysis, AnalysisOptions, Server,
+    analysis::Location,
+    range::path_to_uri,
+    transport::{read_message, write_message},
+};
+use serde_json::{Value, json};
+
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::TempProject;
+
+const GEO: &str = "module Geo {\n    public fn area(w: Int, h: Int) -> Int { w * h }\n    public fn square(n: Int) -> Int { area(n, n) }\n}\n";
+
+fn analyze(project: &TempProject, name: &str, source: &str) -> Analysis {
+    let path = project.write(name, source);
+    Analysis::new(&path, source, &AnalysisOptions::default())
+}
+
+/// A workspace with `Geo.flx` in it, so imports of `Geo` resolve to a real file.
+fn geo_workspace(name: &str) -> TempProject {
+    let project = TempProject::new(&format!("lsp_{name}"));
+    project.write("Geo.flx", GEO);
+    project
+}
+
+fn script(messages: &[Value]) -> Cursor<Vec<u8>> {
+    let mut input = Vec::new();
+    for message in messages {
+        write_message(&mut input, message).unwrap();
+    }
+    Cursor::new(input)
+}
+
+fn replies(output: Vec<u8>) -> Vec<Value> {
+    let mut reader = Cursor::new(output);
+    let mut messages = Vec::new();
+    while let Some(message) = read_message(&mut reader).unwrap() {
+        messages.push(message);
+    }
+    messages
+}
+
+#[test]
+fn hover_reports_inferred_expression_types() {
+    let project = TempProject::new("lsp_hover");
+    let analysis = analyze(
+        &project,
+        "hover.flx",
+        "fn double(x: Int) -> Int { x * 2 }\nfn main() { let y = double(21)\n print(y) }\n",
+    );
+
+    let (label, _) = analysis.hover(Position::new(3, 7)).unwrap();
+    assert_eq!(label, "y : Int");
+    let (label, _) = analysis.hover(Position::new(1, 4)).unwrap();
+    assert!(label.starts_with("double : "), "{label}");
+    let (label, _) = analysis.hover(Position::new(2, 17)).unwrap();
+    assert_eq!(label, "y : Int");
+}
+
+#[test]
+fn type_errors_are_reported_for_the_document() {
+    let project = TempProject::new("lsp_broken");
+    let analysis = analyze(
+        &project,
+        "broken.flx",
+        "fn main() { let x: Int = \"no\"\n print(x) }\n",
+    );
+
+    assert!(
+        analysis
+            .diagnostics()
+            .iter()
+            .any(|diag| diag.severity() == Severity::Error)
+    );
+}
+
+#[test]
+fn definitions_follow_aliased_and_exposed_imports_into_module_files() {
+    let project = geo_workspace("defs");
+    let geo = project.path().join("Geo.flx");
+
+    let analysis = analyze(
+        &project,
+        "defs.flx",
+        "import Geo as G\n\nfn main() {\n    print(G.area(2, 3))\n}\n",
+    );
+    let Location { path, span } = analysis.definition(Position::new(4, 13)).unwrap();
+    assert_eq!(path, geo);
+    assert_eq!(span.start, Position::new(2, 14));
+
+    let analysis = analyze(
+        &project,
+        "exposed.flx",
+        "import Geo exposing (area)\n\nfn main() {\n    print(area(4, 5))\n}\n",
+    );
+    let location = analysis.definition(Position::new(4, 11)).unwrap();
+    assert_eq!(location.path, geo);
+    assert_eq!(location.span.start, Position::new(2, 14));
+}
+
+#[test]
+fn references_to_a_global_span_the_document_and_imported_modules() {
+    let project = geo_workspace("refs");
+    let geo = project.path().join("Geo.flx");
+    let analysis = analyze(
+        &project,
+        "refs.flx",
+        "import Geo exposing (area)\n\nfn main() {\n    print(area(1, 2) + Geo.area(3, 4))\n}\n",
+    );
+    let mut found: Vec<(bool, Position)> = analysis
+        .references(Position::new(4, 11), true)
+        .into_iter()
+        .map(|location| (location.path == geo, location.span.start))
+        .collect();
+    found.sort_by_key(|(in_geo, at)| (*in_geo, at.line, at.column));
+    assert_eq!(
+        found,
+        vec![
+            (false, Position::new(4, 10)),
+            (false, Position::new(4, 27)),
+            (true, Position::new(2, 14)),
+            (true, Position::new(3, 38)),
+        ]
+    );
+
+    let without_declaration = analysis.references(Position::new(4, 11), false);
+    assert_eq!(without_declaration.len(), 3, "{without_declaration:?}");
+}
+
+#[test]
+fn headless_session_publishes_diagnostics_and_answers_hover() {
+    let project = TempProject::new("lsp_session");
+    let text = "fn main() {\n    let answer = 40 + 2\n    print(answer)\n}\n";
+    let path = project.write("main.flx", text);
+    let uri = path_to_uri(&path);
+
+    let mut input = script(&[
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": {"uri": uri, "languageId": "flux", "version": 1, "text": text}
+        }}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {
+            "textDocument": {"uri": uri}, "position": {"line": 2, "character": 11}
+        }}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/references", "params": {
+            "textDocument": {"uri": uri}, "position": {"line": 2, "character": 11},
+            "context": {"includeDeclaration": true}
+        }}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "workspace/symbol", "params": {}}),
+        json!({"jsonrpc": "2.0", "id": 5, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ]);
+
+    let mut output = Vec::new();
+    let clean = Server::new(&mut output, AnalysisOptions::default())
+        .serve(&mut input)
+        .unwrap();
+    assert!(clean);
+
+    let messages = replies(output);
+    assert!(
+        messages[0]["result"]["capabilities"]["hoverProvider"]
+            .as_bool()
+            .unwrap()
+    );
+    assert_eq!(messages[1]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(messages[1]["params"]["diagnostics"], json!([]));
+    assert_eq!(
+        messages[2]["result"]["contents"]["value"],
+        "```flux\nanswer : Int\n```"
+    );
+    assert_eq!(messages[3]["result"].as_array().unwrap().len(), 2);
+    assert_eq!(messages[4]["error"]["code"], -32601);
+    assert_eq!(messages[5]["result"], Value::Null);
+}
+
+#[test]
+fn references_include_uses_in_other_open_documents() {
+    let project = TempProject::new("lsp_open_documents");
+    let geo_text = "module Geo {\n    public fn area(w: Int, h: Int) -> Int { w * h }\n}\n";
+    let geo = project.write("Geo.flx", geo_text);
+    let main_text = "import Geo as G\n\nfn main() {\n    print(G.area(2, 3))\n}\n";
+    let main = project.write("main.flx", main_text);
+    let (geo_uri, main_uri) = (path_to_uri(&geo), path_to_uri(&main));
+
+    let mut input = script(&[
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": {"uri": geo_uri, "languageId": "flux", "version": 1, "text": geo_text}
+        }}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": {"uri": main_uri, "languageId": "flux", "version": 1, "text": main_text}
+        }}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/references", "params": {
+            "textDocument": {"uri": geo_uri}, "position": {"line": 1, "character": 15},
+            "context": {"includeDeclaration": false}
+        }}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ]);
+
+    let mut output = Vec::new();
+    Server::new(&mut output, AnalysisOptions::default())
+        .serve(&mut input)
+        .unwrap();
+
+    let messages = replies(output);
+    let reply = messages.iter().find(|message| message["id"] == 2).unwrap();
+    assert_eq!(
+        reply["result"],
+        json!([{
+            "uri": main_uri,
+            "range": {"start": {"line": 3, "character": 12}, "end": {"line": 3, "character": 16}}
+        }])
+    );
+}
@@ -0,0 +1,55 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, combined_output};
+
+/// Lays out `app` depending on `../utils`, which provides `Utils.Greet`.
+fn project(name: &str, utils_version: &str) -> TempProject {
+    let project = TempProject::new(&format!("manifest_cli_{name}"));
+    project.write(
+        "utils/flux.toml",
+        &format!("[package]\nname = \"utils\"\nversion = \"{utils_version}\"\n"),
+    );
+    project.write(
+        "utils/src/Utils/Greet.flx",
+        "module Utils.Greet {\n  public fn hello(name) {\n    \"hello \" + name\n  }\n}\n",
+    );
+    project.write(
+        "app/flux.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n\
+         [dependencies]\nutils = { path = \"../utils\", version = \"0.2.0\" }\n",
+    );
+    project.write(
+        "app/src/Main.flx",
+        "import Utils.Greet\n\nfn main() with IO {\n  print(Utils.Greet.hello(\"flux\"))\n}\n",
+    );
+    project
+}
+
+#[test]
+fn run_resolves_path_dependencies_from_manifest_and_writes_lockfile() {
+    let project = project("run", "0.2.0");
+
+    let output = project.run(&["run", "app/src/Main.flx"]);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "{text}");
+    assert!(text.contains("hello flux"), "{text}");
+
+    let lock = project.read("app/flux.lock");
+    assert!(lock.contains("name = \"utils\""), "{lock}");
+    assert!(lock.contains("source = \"path+../utils\""), "{lock}");
+    assert!(lock.contains("checksum = \"sha256:"), "{lock}");
+}
+
+#[test]
+fn run_rejects_dependency_version_mismatch() {
+    let project = project("mismatch", "0.3.0");
+
+    let output = project.run(&["run", "app/src/Main.flx"]);
+    let text = combined_output(&output);
+    assert!(!output.status.success(), "{text}");
+    assert!(
+        text.contains("dependency `utils` requires version 0.2.0, but its manifest declares 0.3.0"),
+        "{text}"
+    );
+}
@@ -305,6 +305,40 @@ fn main() {
     );
 }
 
+#[test]
+fn zero_argument_class_method_resolves_from_result_type() {
+    let source = r#"
+module PhaseX.Fallbacks {
+    class Fallback<a> {
+        fn fallback() -> a
+    }
+
+    fn six() -> Int { 6 }
+
+    instance Fallback<Int> {
+        fn fallback() { six() * 7 }
+    }
+
+    instance Fallback<String> {
+        fn fallback() { "none" }
+    }
+}
+
+fn main() {
+    let label: String = fallback()
+    let n: Int = fallback()
+    n + len(label) - 4
+}
+"#;
+    let result = compile_and_run(source);
+    assert_eq!(
+        result,
+        Value::Integer(42),
+        "each `fallback()` should pick the instance of its expected type; got {:?}",
+        result
+    );
+}
+
 /// Proposal 0151, Phase 1a, commit #6: short-form qualified call.
 ///
 /// `Quall.size_of2(...)` — referring to a same-file `module Phase1.Quall` by
@@ -0,0 +1,77 @@
+use std::fs;
+
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, repo_root, run_flux};
+
+const PROGRAM: &str = r#"fn clamp(x: Int) -> Int {
+    if x < 0 { 0 } else if x > 10 { 10 } else { x }
+}
+
+fn first_or(o: Option<Int>, d: Int) -> Int {
+    match o {
+        Some(v) -> v,
+        None -> d,
+    }
+}
+
+fn test_clamp_low() {
+    assert_eq(clamp(-5), 0)
+}
+
+fn test_clamp_mid() {
+    assert_eq(clamp(5), 5)
+}
+
+fn test_first_or() {
+    assert_eq(first_or(Some(3), 9), 3)
+}
+"#;
+
+#[test]
+fn mutate_reports_surviving_mutants_and_skips_ill_typed_ones() {
+    let project = TempProject::new("mutate_survivors");
+    let input = project.write("calc.flx", PROGRAM);
+    let output = run_flux(
+        repo_root(),
+        &["mutate", input.to_str().unwrap(), "--jobs", "2"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(
+        output.status.code(),
+        Some(1),
+        "{stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("9 mutants"), "{stdout}");
+    assert!(
+        stdout.contains("calc.flx:2:12  replace constant: `0` -> `1`"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("mutation score: 3/6 killed (50.0%), 3 survived, 3 skipped"),
+        "{stdout}"
+    );
+
+    let leftovers: Vec<_> = fs::read_dir(project.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(leftovers, vec!["calc.flx"]);
+}
+
+#[test]
+fn mutate_requires_passing_tests() {
+    let project = TempProject::new("mutate_failing");
+    let input = project.write(
+        "calc.flx",
+        "fn one() -> Int { 1 }\n\nfn test_one() {\n    assert_eq(one(), 2)\n}\n",
+    );
+    let output = run_flux(repo_root(), &["mutate", input.to_str().unwrap()]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(stderr.contains("do not pass before mutation"), "{stderr}");
+}
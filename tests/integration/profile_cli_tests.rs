@@ -0,0 +1,113 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::TempProject;
+
+const PROGRAM: &str = r#"fn build(n: Int) -> List<Int> {
+    if n == 0 { [] } else { [n | build(n - 1)] }
+}
+
+fn total(xs: List<Int>) -> Int {
+    match xs {
+        [h | t] -> h + total(t),
+        _ -> 0,
+    }
+}
+
+fn main() with IO {
+    print(total(build(50)))
+}
+"#;
+
+fn write_program(name: &str) -> TempProject {
+    let project = TempProject::new(&format!("profile_{name}"));
+    project.write("main.flx", PROGRAM);
+    project
+}
+
+#[test]
+fn flat_profile_attributes_allocations_to_cost_centres() {
+    let project = write_program("flat");
+    let output = project.run(&["main.flx", "--prof"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1275\n");
+    let build = stderr
+        .lines()
+        .find(|line| line.trim_start().starts_with("build "))
+        .unwrap_or_else(|| panic!("{stderr}"));
+    assert!(build.contains("      51 "), "{stderr}");
+    assert!(stderr.contains("%alloc"), "{stderr}");
+    assert!(stderr.contains("cons 50"), "{stderr}");
+}
+
+#[test]
+fn folded_profile_lists_call_stacks() {
+    let project = write_program("folded");
+    let output = project.run(&["main.flx", "--prof", "--prof-format=folded-alloc"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(
+        stderr.contains("profile written to profile.alloc.folded"),
+        "{stderr}"
+    );
+    let folded = project.read("profile.alloc.folded");
+    let deepest = folded
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap())
+        .max_by_key(|(stack, _)| stack.matches(';').count())
+        .unwrap();
+    assert!(deepest.0.starts_with("main;build;build;"), "{folded}");
+    let allocs: u64 = folded
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+        .sum();
+    assert!(allocs >= 50, "{folded}");
+    assert!(!folded.contains("total"), "{folded}");
+}
+
+#[test]
+fn speedscope_profile_holds_time_and_allocation_profiles() {
+    let project = write_program("speedscope");
+    let output = project.run(&[
+        "main.flx",
+        "--prof",
+        "--prof-format",
+        "speedscope",
+        "--prof-output",
+        "out.json",
+    ]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    let json: serde_json::Value = serde_json::from_str(&project.read("out.json")).unwrap();
+    let frames: Vec<&str> = json["shared"]["frames"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|frame| frame["name"].as_str().unwrap())
+        .collect();
+    assert!(
+        frames.contains(&"build") && frames.contains(&"total"),
+        "{frames:?}"
+    );
+    assert_eq!(json["profiles"][0]["unit"], "nanoseconds");
+    assert_eq!(json["profiles"][1]["name"], "allocations");
+    assert_eq!(
+        json["profiles"][0]["samples"].as_array().unwrap().len(),
+        json["profiles"][0]["weights"].as_array().unwrap().len()
+    );
+}
+
+#[test]
+fn prof_format_requires_prof() {
+    let project = write_program("requires");
+    let output = project.run(&["main.flx", "--prof-format", "folded"]);
+
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--prof-format and --prof-output require --prof")
+    );
+}
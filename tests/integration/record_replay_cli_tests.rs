@@ -0,0 +1,127 @@
+use std::io::Write;
+use std::process::{Output, Stdio};
+
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, flux_command};
+
+const PROGRAM: &str = r#"fn main() with IO, Clock, FileSystem, Stdin {
+    let started = clock_now()
+    let lines = read_lines("input.txt")
+    let input = read_stdin()
+    println(len(lines))
+    println(input)
+    println(started)
+}
+"#;
+
+fn run_flux(project: &TempProject, args: &[&str], stdin: &str) -> Output {
+    let mut child = flux_command(project.path(), args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to run flux with args {:?}: {e}", args));
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn write_program(name: &str) -> TempProject {
+    let project = TempProject::new(&format!("replay_{name}"));
+    project.write("main.flx", PROGRAM);
+    project.write("input.txt", "a\nb\nc\n");
+    project
+}
+
+#[test]
+fn replay_reproduces_a_recorded_run() {
+    let project = write_program("reproduce");
+    let recorded = run_flux(&project, &["main.flx", "--record", "run.log"], "typed");
+    let stderr = String::from_utf8_lossy(&recorded.stderr);
+    assert!(recorded.status.success(), "{stderr}");
+
+    let log = project.read("run.log");
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines[0], r#"{"flux_effect_log":1}"#);
+    assert!(lines[1].starts_with(r#"{"op":"clock_now","args":[],"result":"#));
+    assert_eq!(
+        lines[2],
+        r#"{"op":"read_lines","args":["input.txt"],"result":["a","b","c"]}"#
+    );
+    assert_eq!(
+        lines[3],
+        r#"{"op":"read_stdin","args":[],"result":"typed"}"#
+    );
+
+    project.write("input.txt", "changed\n");
+    let replayed = run_flux(&project, &["main.flx", "--replay", "run.log"], "");
+    let stderr = String::from_utf8_lossy(&replayed.stderr);
+    assert!(replayed.status.success(), "{stderr}");
+    assert_eq!(replayed.stdout, recorded.stdout);
+}
+
+#[test]
+fn replay_fails_when_the_run_diverges() {
+    let project = write_program("diverge");
+    project.write(
+        "run.log",
+        concat!(
+            "{\"flux_effect_log\":1}\n",
+            "{\"op\":\"clock_now\",\"args\":[],\"result\":7}\n",
+            "{\"op\":\"read_lines\",\"args\":[\"other.txt\"],\"result\":[]}\n",
+        ),
+    );
+    let output = run_flux(&project, &["main.flx", "--replay", "run.log"], "");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            r#"replay diverged at effect 2: expected read_lines("other.txt"), got read_lines("input.txt")"#
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn replay_fails_when_logged_calls_are_not_made() {
+    let project = write_program("missing");
+    project.write(
+        "run.log",
+        concat!(
+            "{\"flux_effect_log\":1}\n",
+            "{\"op\":\"clock_now\",\"args\":[],\"result\":7}\n",
+            "{\"op\":\"read_lines\",\"args\":[\"input.txt\"],\"result\":[\"x\"]}\n",
+            "{\"op\":\"read_stdin\",\"args\":[],\"result\":\"\"}\n",
+            "{\"op\":\"clock_now\",\"args\":[],\"result\":9}\n",
+        ),
+    );
+    let output = run_flux(&project, &["main.flx", "--replay", "run.log"], "");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("1\n\"\"\n7\n"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "replay diverged: 1 recorded effects were not performed, starting with clock_now()"
+    ));
+}
+
+#[test]
+fn record_and_replay_cannot_be_combined() {
+    let project = write_program("combined");
+    let output = run_flux(
+        &project,
+        &["main.flx", "--record", "a.log", "--replay", "b.log"],
+        "",
+    );
+
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--record and --replay cannot be used together")
+    );
+}
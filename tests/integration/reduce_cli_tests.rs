@@ -0,0 +1,65 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::{TempProject, combined_output};
+
+const PROGRAM: &str = r#"data Shape {
+    Circle(Int),
+    Rect(Int, Int),
+}
+
+fn area(s: Shape) -> Int {
+    match s {
+        Circle(r) -> r * r * 3,
+        Rect(w, h) -> w * h,
+    }
+}
+
+fn helper(a: Int, b: Int) -> Int {
+    let total = a + b
+    let scaled = total * 2
+    scaled - 1
+}
+
+fn main() with IO {
+    print(area(Circle(3)))
+    let label = "n=" + helper(1, 2)
+    print(label)
+}
+"#;
+
+#[test]
+fn reduce_keeps_the_original_diagnostic() {
+    let project = TempProject::new("reduce_diag");
+    project.write("bad.flx", PROGRAM);
+    let output = project.run(&[
+        "reduce",
+        "bad.flx",
+        "--predicate",
+        "diag=E300",
+        "--out",
+        "small.flx",
+    ]);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "{text}");
+
+    let reduced = project.read("small.flx");
+    assert!(reduced.len() < PROGRAM.len() / 4, "{reduced}");
+    assert!(!reduced.contains("data Shape"), "{reduced}");
+
+    let check = project.run(&["check", "small.flx", "--no-cache"]);
+    let check_text = combined_output(&check);
+    assert!(
+        check_text.contains("error[E300]: Type Mismatch"),
+        "{check_text}"
+    );
+}
+
+#[test]
+fn reduce_rejects_a_program_that_does_not_fail() {
+    let project = TempProject::new("reduce_passing");
+    project.write("bad.flx", PROGRAM);
+    let output = project.run(&["reduce", "bad.flx", "--predicate", "diag=E999"]);
+    let text = combined_output(&output);
+    assert!(!output.status.success(), "{text}");
+}
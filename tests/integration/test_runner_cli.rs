@@ -246,6 +246,358 @@ fn test_mode_flow_list_module_fixture_passes() {
     );
 }
 
+#[test]
+fn test_mode_flow_gen_properties_pass() {
+    let file = fixture_path("Flow/Gen_test.flx");
+    let output = run_flux(&[
+        "--test",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("PASS  test_user_adt_property"),
+        "expected the ADT generator property to pass, output:\n{text}"
+    );
+    assert!(
+        text.contains("PASS  test_arbitrary_instances"),
+        "expected properties over `Arbitrary` instances to pass, output:\n{text}"
+    );
+    assert!(
+        text.contains("8 tests: 8 passed, 0 failed"),
+        "unexpected summary, output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_flow_testing_mocks_pass() {
+    let file = fixture_path("Flow/Testing_test.flx");
+    let output = run_flux(&[
+        "--test",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("6 tests: 6 passed, 0 failed"),
+        "unexpected summary, output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_property_failure_reports_seed_and_shrunk_counterexample() {
+    let file = fixture_path("property_failure.flx");
+    let output = run_flux(&[
+        "--test",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(
+        !output.status.success(),
+        "expected failure status, output:\n{text}"
+    );
+    assert!(
+        text.contains("property \"all small\" failed after"),
+        "output:\n{text}"
+    );
+    assert!(text.contains("seed: 1278687953"), "output:\n{text}");
+    assert!(
+        text.contains("counterexample: [10]\n"),
+        "expected the list to shrink to one element, output:\n{text}"
+    );
+    assert!(
+        text.contains("counterexample: (7, 0)\n"),
+        "expected the pair to shrink towards zero, output:\n{text}"
+    );
+    assert!(
+        text.contains("error: panic: assert_neq failed"),
+        "expected the assertion message of the shrunk input, output:\n{text}"
+    );
+    assert!(
+        text.contains("3 tests: 1 passed, 2 failed"),
+        "output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_snapshots_match_recorded_files() {
+    let file = fixture_path("snapshots/shapes.flx");
+    let output = run_flux(&[
+        "--test",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("snapshots: 3 passed\n"),
+        "expected every snapshot to match, output:\n{text}"
+    );
+    assert!(
+        text.contains("\"building the report\"") && !text.contains("@@flux-snapshot"),
+        "expected test output without snapshot lines, output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_snapshot_changes_fail_until_updated() {
+    let dir = std::env::temp_dir().join(format!("flux_snapshots_{}", std::process::id()));
+    let snapshots = dir.join("__snapshots__");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&snapshots).unwrap();
+    let file = dir.join("shapes.flx");
+    std::fs::copy(fixture_path("snapshots/shapes.flx"), &file).unwrap();
+    std::fs::write(
+        snapshots.join("shapes__scaled_shapes.snap"),
+        "[Circle(2), Rect(2, 3)]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        snapshots.join("shapes__report.snap"),
+        "area: 12\nperimeter: 14\n",
+    )
+    .unwrap();
+    std::fs::write(snapshots.join("shapes__removed.snap"), "gone\n").unwrap();
+    let lib = workspace_root().join("lib");
+    let args = [
+        "--test",
+        file.to_str().unwrap(),
+        "--root",
+        lib.to_str().unwrap(),
+    ];
+
+    let output = run_flux(&args);
+    let text = combined_output(&output);
+    assert!(
+        !output.status.success(),
+        "expected failure, output:\n{text}"
+    );
+    assert!(
+        text.contains(
+            "snapshot `scaled shapes` does not match __snapshots__/shapes__scaled_shapes.snap\n"
+        ) && text.contains("- [Circle(2), Rect(2, 3)]\n")
+            && text.contains("+ [Circle(2), Rect(4, 6)]\n"),
+        "expected a diff of the changed snapshot, output:\n{text}"
+    );
+    assert!(
+        text.contains("snapshot `totals` is not recorded in __snapshots__/shapes__totals.snap"),
+        "expected the missing snapshot, output:\n{text}"
+    );
+    assert!(
+        text.contains("snapshots: 1 passed, 1 failed, 1 pending, 1 obsolete\n")
+            && text.contains("obsolete snapshot __snapshots__/shapes__removed.snap"),
+        "expected the snapshot summary, output:\n{text}"
+    );
+
+    let mut update_args = args.to_vec();
+    update_args.push("--update-snapshots");
+    let output = run_flux(&update_args);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("snapshots: 1 passed, 2 written, 1 removed\n"),
+        "output:\n{text}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(snapshots.join("shapes__scaled_shapes.snap")).unwrap(),
+        "[Circle(2), Rect(4, 6)]\n"
+    );
+    assert!(!snapshots.join("shapes__removed.snap").exists());
+
+    let output = run_flux(&args);
+    let text = combined_output(&output);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        output.status.success() && text.contains("snapshots: 3 passed\n"),
+        "expected the updated snapshots to match, output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_coverage_writes_lcov_for_lines_functions_and_branches() {
+    let dir = std::env::temp_dir().join(format!("flux_coverage_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let lcov = dir.join("lcov.info");
+    let file = fixture_path("coverage.flx");
+    let lib = workspace_root().join("lib");
+    let args = [
+        "--test",
+        file.to_str().unwrap(),
+        "--root",
+        lib.to_str().unwrap(),
+        "--coverage",
+        "--coverage-output",
+        lcov.to_str().unwrap(),
+    ];
+
+    let output = run_flux(&args);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    let report = std::fs::read_to_string(&lcov).unwrap();
+    assert!(
+        report.contains("lib/Flow/Assert.flx\n"),
+        "expected stdlib coverage by default, lcov:\n{report}"
+    );
+
+    let mut exclude_args = args.to_vec();
+    exclude_args.push("--coverage-exclude-stdlib");
+    let output = run_flux(&exclude_args);
+    let text = combined_output(&output);
+    let report = std::fs::read_to_string(&lcov).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("classify:1") && text.contains("coverage written to"),
+        "expected the coverage summary, output:\n{text}"
+    );
+    assert!(
+        !report.contains("lib/Flow/"),
+        "expected stdlib modules to be excluded, lcov:\n{report}"
+    );
+    for line in [
+        "FNDA:1,classify\n",
+        "FNDA:0,unused\n",
+        "BRDA:2,0,0,0\nBRDA:2,0,1,1\n",
+        "DA:3,0\n",
+        "DA:5,1\n",
+        "LF:6\nLH:3\n",
+    ] {
+        assert!(
+            report.contains(line),
+            "expected {line:?} in lcov:\n{report}"
+        );
+    }
+}
+
+#[test]
+fn coverage_requires_the_coverage_flag_for_its_options() {
+    let file = fixture_path("coverage.flx");
+    let output = run_flux(&[
+        "--test",
+        file.to_str().unwrap(),
+        "--coverage-exclude-stdlib",
+    ]);
+    let text = combined_output(&output);
+
+    assert!(
+        text.contains("--coverage-exclude-stdlib require --coverage.")
+            && !text.contains("Running tests"),
+        "expected the flag error before any test ran, output:\n{text}"
+    );
+}
+
+#[test]
+fn bench_reports_statistics_and_compares_against_the_baseline() {
+    let dir = std::env::temp_dir().join(format!("flux_bench_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let baseline = dir.join("baseline.json");
+    let benches = fixture_path("bench");
+    let args = [
+        "bench",
+        benches.to_str().unwrap(),
+        "--time",
+        "50",
+        "--baseline",
+        baseline.to_str().unwrap(),
+    ];
+
+    let output = run_flux(&args);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("bench_sum_small")
+            && text.contains("bench_sum_large")
+            && text.contains("mean ")
+            && text.contains("median ")
+            && text.contains("std dev ")
+            && text.contains("no baseline"),
+        "expected statistics for both benchmarks, output:\n{text}"
+    );
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&baseline).unwrap()).unwrap();
+    assert_eq!(saved["benchmarks"].as_object().unwrap().len(), 2);
+
+    let output = run_flux(&[&args[..], &["--filter", "small"]].concat());
+    let text = combined_output(&output);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("(baseline ") && !text.contains("bench_sum_large"),
+        "expected a comparison for the filtered benchmark only, output:\n{text}"
+    );
+    assert!(
+        text.contains("1 benchmarks: "),
+        "expected the summary line, output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_doc_tests_run_the_flux_examples_of_public_items() {
+    let file = fixture_path("doc_tests/DocMath.flx");
+    let output = run_flux(&["--test", file.to_str().unwrap()]);
+    let text = combined_output(&output);
+    assert!(
+        text.contains("No test functions found"),
+        "expected doc-tests to need --doc-tests, output:\n{text}"
+    );
+
+    let output = run_flux(&["--test", file.to_str().unwrap(), "--doc-tests"]);
+    let text = combined_output(&output);
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    for name in ["doc_abs", "doc_total", "doc_total_2", "doc_clamp"] {
+        assert!(text.contains(name), "expected {name}, output:\n{text}");
+    }
+    assert!(
+        text.contains("4 tests: 4 passed, 0 failed"),
+        "output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_doc_test_failures_point_into_the_doc_comment() {
+    let dir = fixture_path("doc_tests/failing");
+    let output = run_flux(&[
+        "--test",
+        dir.to_str().unwrap(),
+        "--doc-tests",
+        "--test-format",
+        "json",
+    ]);
+    let text = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(
+        !output.status.success(),
+        "expected failure, output:\n{text}"
+    );
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let tests = report["results"].as_array().unwrap();
+
+    assert_eq!(tests[0]["name"], "doc_double");
+    assert_eq!(tests[0]["message"], "panic: assert_eq failed");
+    assert_eq!(tests[0]["location"]["line"], 5);
+    assert_eq!(tests[1]["name"], "doc_halve");
+    assert!(
+        tests[1]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("doc example does not compile: error[E004]"),
+        "report:\n{text}"
+    );
+    assert_eq!(tests[1]["location"]["line"], 14);
+    assert_eq!(tests[1]["location"]["column"], 15);
+}
+
 #[test]
 fn test_mode_flow_list_module_fixture_reports_strict_stdlib_diagnostics() {
     let file = fixture_path("Flow/List_test.flx");
@@ -657,6 +1009,331 @@ fn test_mode_test_filter_no_match_reports_empty() {
     );
 }
 
+#[test]
+fn test_mode_junit_report_aggregates_a_directory() {
+    let dir = fixture_path("reports");
+    let output = run_flux(&["--test", dir.to_str().unwrap(), "--test-format", "junit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        !output.status.success(),
+        "expected failure status, output:\n{}",
+        combined_output(&output)
+    );
+    assert!(
+        stdout.starts_with("<?xml"),
+        "expected only the report on stdout:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("<testsuites name=\"flux\" tests=\"4\" failures=\"2\""),
+        "expected totals across both test files:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("reports/math.flx:11:5</failure>"),
+        "expected the failing assert_eq call site:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("<system-out>&quot;doubling -3&quot;"),
+        "expected captured test output:\n{stdout}"
+    );
+    assert!(
+        !stdout.contains("helper.flx"),
+        "expected files without tests to be skipped:\n{stdout}"
+    );
+}
+
+#[test]
+fn test_mode_json_report_is_written_to_test_output() {
+    let dir = fixture_path("reports");
+    let report_path =
+        std::env::temp_dir().join(format!("flux_test_report_{}.json", std::process::id()));
+    let output = run_flux(&[
+        "--test",
+        dir.to_str().unwrap(),
+        "--test-format=json",
+        "--test-output",
+        report_path.to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+    let report = std::fs::read_to_string(&report_path)
+        .unwrap_or_else(|e| panic!("expected a report file, output:\n{text}\n{e}"));
+    let _ = std::fs::remove_file(&report_path);
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+    assert!(
+        text.contains("2 files, 4 tests: 2 passed, 2 failed"),
+        "expected the text report on stdout, output:\n{text}"
+    );
+    assert_eq!(report["files"], 2);
+    assert_eq!(report["failed"], 2);
+    let failure = report["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|result| result["name"] == "test_double_zero")
+        .expect("expected test_double_zero in the report");
+    assert_eq!(failure["outcome"], "fail");
+    assert_eq!(failure["message"], "panic: assert_eq failed");
+    assert_eq!(failure["location"]["line"], 16);
+    assert_eq!(failure["location"]["column"], 5);
+}
+
+#[test]
+fn test_mode_tap_report_for_a_passing_file() {
+    let file = fixture_path("all_pass.flx");
+    let output = run_flux(&["--test", file.to_str().unwrap(), "--test-format", "tap"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "expected success, output:\n{stdout}"
+    );
+    assert!(
+        stdout.starts_with("TAP version 13\n1..2\n"),
+        "expected a TAP plan for both tests:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("all_pass.flx test_b\n"),
+        "stdout:\n{stdout}"
+    );
+    assert!(!stdout.contains("not ok"), "stdout:\n{stdout}");
+}
+
+#[test]
+fn test_mode_test_output_requires_a_report_format() {
+    let file = fixture_path("all_pass.flx");
+    let output = run_flux(&["--test", file.to_str().unwrap(), "--test-output", "r.xml"]);
+    let text = combined_output(&output);
+
+    assert!(text.contains("--test-output requires"), "output:\n{text}");
+    assert!(!text.contains("Running tests"), "output:\n{text}");
+}
+
+#[test]
+fn test_mode_test_timeout_fails_only_the_looping_test() {
+    let file = fixture_path("isolation/spin.flx");
+    let output = run_flux(&["--test", file.to_str().unwrap(), "--test-timeout", "200"]);
+    let text = combined_output(&output);
+
+    assert!(
+        !output.status.success(),
+        "expected failure status, output:\n{text}"
+    );
+    assert!(
+        text.contains("FAIL  test_spins_forever\n          timed out after 200ms"),
+        "expected the looping test to time out, output:\n{text}"
+    );
+    assert!(
+        text.contains("PASS  test_after"),
+        "expected later tests to still run, output:\n{text}"
+    );
+    assert!(
+        text.contains("3 tests: 2 passed, 1 failed"),
+        "output:\n{text}"
+    );
+}
+
+#[test]
+fn test_mode_test_jobs_matches_a_sequential_run() {
+    let dir = fixture_path("reports");
+    let output = run_flux(&[
+        "--test",
+        dir.to_str().unwrap(),
+        "--test-jobs=4",
+        "--test-format",
+        "junit",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("<testsuites name=\"flux\" tests=\"4\" failures=\"2\""),
+        "expected the same totals as a sequential run:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("reports/math.flx:11:5</failure>"),
+        "expected failure locations from the isolated VMs:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("<system-out>&quot;doubling -3&quot;"),
+        "expected output captured per test:\n{stdout}"
+    );
+    let order: Vec<_> = ["test_double\"", "test_double_negative", "test_double_zero"]
+        .iter()
+        .map(|name| stdout.find(name).unwrap())
+        .collect();
+    assert!(
+        order.windows(2).all(|pair| pair[0] < pair[1]),
+        "expected results in definition order:\n{stdout}"
+    );
+}
+
+#[test]
+fn test_mode_test_jobs_removes_its_temporary_directory() {
+    let dir = fixture_path("reports");
+    let child = Command::new(env!("CARGO_BIN_EXE_flux"))
+        .args(["--test", dir.to_str().unwrap(), "--test-jobs=4"])
+        .env("NO_COLOR", "1")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("spawn flux");
+    let jobs_dir = std::env::temp_dir().join(format!("flux_test_jobs_{}", child.id()));
+    child.wait_with_output().expect("wait for flux");
+
+    assert!(
+        !jobs_dir.exists(),
+        "expected {} to be removed after the run",
+        jobs_dir.display()
+    );
+}
+
+#[test]
+fn test_mode_test_jobs_requires_a_positive_count() {
+    let file = fixture_path("all_pass.flx");
+    let output = run_flux(&["--test", file.to_str().unwrap(), "--test-jobs", "0"]);
+    let text = combined_output(&output);
+
+    assert!(
+        text.contains("--test-jobs expects a positive integer"),
+        "output:\n{text}"
+    );
+    assert!(!text.contains("Running tests"), "output:\n{text}");
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn test_mode_native_test_timeout_kills_the_test_process() {
+    if !cli_supports_flag("--native") {
+        eprintln!("skipping native CLI test: binary does not advertise --native");
+        return;
+    }
+    let file = fixture_path("isolation/spin.flx");
+    let output = run_flux(&[
+        "--test",
+        "--native",
+        file.to_str().unwrap(),
+        "--test-timeout",
+        "2000",
+        "--test-jobs",
+        "3",
+    ]);
+    let text = combined_output(&output);
+
+    assert!(
+        text.contains("FAIL  test_spins_forever\n          timed out after 2000ms"),
+        "expected the native test process to be killed, output:\n{text}"
+    );
+    assert!(
+        text.contains("3 tests: 2 passed, 1 failed"),
+        "output:\n{text}"
+    );
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn test_mode_native_crash_fails_only_the_crashing_test() {
+    if !cli_supports_flag("--native") {
+        eprintln!("skipping native CLI test: binary does not advertise --native");
+        return;
+    }
+    let file = fixture_path("isolation/crash.flx");
+    let output = run_flux(&["--test", "--native", file.to_str().unwrap()]);
+    let text = combined_output(&output);
+
+    assert!(
+        text.contains("FAIL  test_overflows_the_native_stack"),
+        "output:\n{text}"
+    );
+    assert!(
+        text.contains("terminated by signal"),
+        "expected the crash signal in the failure, output:\n{text}"
+    );
+    assert!(
+        text.contains("2 tests: 1 passed, 1 failed"),
+        "expected the run to continue past the crash, output:\n{text}"
+    );
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn test_mode_native_property_failure_matches_the_vm() {
+    if !cli_supports_flag("--native") {
+        eprintln!("skipping native CLI test: binary does not advertise --native");
+        return;
+    }
+    let file = fixture_path("property_failure.flx");
+    let output = run_flux(&[
+        "--test",
+        "--native",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(text.contains("seed: 1278687953"), "output:\n{text}");
+    assert!(
+        text.contains("counterexample: [10]"),
+        "expected the same shrunk input as the VM, output:\n{text}"
+    );
+    assert!(
+        text.contains("counterexample: (7, 0)"),
+        "expected the same shrunk input as the VM, output:\n{text}"
+    );
+    assert!(
+        text.contains("3 tests: 1 passed, 2 failed"),
+        "output:\n{text}"
+    );
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn test_mode_native_snapshots_match_the_vm() {
+    if !cli_supports_flag("--native") {
+        eprintln!("skipping native CLI test: binary does not advertise --native");
+        return;
+    }
+    let file = fixture_path("snapshots/shapes.flx");
+    let output = run_flux(&[
+        "--test",
+        "--native",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("snapshots: 3 passed\n"),
+        "expected the snapshots recorded on the VM to match, output:\n{text}"
+    );
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn test_mode_native_flow_testing_mocks_pass() {
+    if !cli_supports_flag("--native") {
+        eprintln!("skipping native CLI test: binary does not advertise --native");
+        return;
+    }
+    let file = fixture_path("Flow/Testing_test.flx");
+    let output = run_flux(&[
+        "--test",
+        "--native",
+        file.to_str().unwrap(),
+        "--root",
+        workspace_root().join("lib").to_str().unwrap(),
+    ]);
+    let text = combined_output(&output);
+
+    assert!(output.status.success(), "expected success, output:\n{text}");
+    assert!(
+        text.contains("6 tests: 6 passed, 0 failed"),
+        "unexpected summary, output:\n{text}"
+    );
+}
+
 #[test]
 fn test_mode_primops_fixture_passes_on_vm() {
     let file = fixture_path("primops_all.flx");
@@ -0,0 +1,110 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::TempProject;
+
+const PROGRAM: &str = r#"effect Audit {
+    log: String -> Int
+}
+
+fn build(n: Int) -> List<Int> {
+    if n == 0 { [] } else { [n | build(n - 1)] }
+}
+
+fn total(xs: List<Int>, acc: Int) -> Int {
+    match xs {
+        [h | t] -> total(t, acc + h),
+        _ -> acc,
+    }
+}
+
+fn audited(name: String) -> Int with Audit {
+    perform Audit.log(name) + 1
+}
+
+fn main() with IO {
+    println(total(build(2), 0))
+    let v = audited("bob") handle Audit {
+        log(resume, message) -> resume(len(message))
+    }
+    println(v)
+}
+"#;
+
+fn write_program(name: &str) -> TempProject {
+    let project = TempProject::new(&format!("trace_{name}"));
+    project.write("main.flx", PROGRAM);
+    project
+}
+
+#[test]
+fn text_trace_logs_calls_returns_and_tail_calls() {
+    let project = write_program("text");
+    let output = project.run(&["main.flx", "--trace-calls", "build,total"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n4\n");
+    let trace: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.trim_start().starts_with(['→', '←']))
+        .collect();
+    assert_eq!(
+        trace,
+        [
+            "→ build(2)",
+            "  → build(1)",
+            "    → build(0)",
+            "    ← build = []",
+            "  ← build = [1]",
+            "← build = [2, 1]",
+            "→ total([2, 1], 0)",
+            "← total (tail call)",
+            "→ total([1], 2)",
+            "← total (tail call)",
+            "→ total([], 3)",
+            "← total = 3",
+        ],
+        "{stderr}"
+    );
+    assert!(!stderr.contains("perform"), "{stderr}");
+}
+
+#[test]
+fn json_trace_logs_operations_inside_traced_calls() {
+    let project = write_program("json");
+    let output = project.run(&[
+        "main.flx",
+        "--trace-calls",
+        "aud*",
+        "--trace-output",
+        "trace.jsonl",
+    ]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    let events: Vec<serde_json::Value> = project
+        .read("trace.jsonl")
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        events,
+        [
+            serde_json::json!({"event": "call", "depth": 0, "function": "audited", "args": ["\"bob\""]}),
+            serde_json::json!({"event": "perform", "depth": 1, "operation": "Audit.log", "args": ["\"bob\""]}),
+            serde_json::json!({"event": "resume", "depth": 1, "value": "3"}),
+            serde_json::json!({"event": "return", "depth": 0, "function": "audited", "value": "4"}),
+        ]
+    );
+}
+
+#[test]
+fn trace_output_requires_trace_calls() {
+    let project = write_program("requires");
+    let output = project.run(&["main.flx", "--trace-output", "trace.jsonl"]);
+
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--trace-output requires --trace-calls")
+    );
+}
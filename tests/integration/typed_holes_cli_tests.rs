@@ -0,0 +1,52 @@
+#[path = "../support/cli.rs"]
+mod cli;
+
+use cli::TempProject;
+
+const PROGRAM: &str = r#"fn safe_div(a: Int, b: Int) -> Int {
+    if b == 0 { ?zero_divisor } else { a / b }
+}
+
+fn main() with IO {
+    println(safe_div(6, 3))
+    println(safe_div(1, 0))
+}
+"#;
+
+fn write_program(name: &str) -> TempProject {
+    let project = TempProject::new(&format!("holes_{name}"));
+    project.write("main.flx", PROGRAM);
+    project
+}
+
+#[test]
+fn hole_is_reported_and_panics_with_its_name_when_reached() {
+    let project = write_program("run");
+    let output = project.run(&["main.flx"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n");
+    assert!(stderr.contains("Warning[W204]: Typed Hole"), "{stderr}");
+    assert!(
+        stderr.contains("Hole `?zero_divisor` needs a value of type `Int`."),
+        "{stderr}"
+    );
+    assert!(stderr.contains("    a: Int\n    b: Int"), "{stderr}");
+    assert!(
+        stderr.contains("typed hole ?zero_divisor reached"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn hole_is_reported_again_when_the_module_is_cached() {
+    let project = write_program("cache");
+    let first = project.run(&["main.flx"]);
+    let second = project.run(&["main.flx"]);
+
+    for output in [first, second] {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Warning[W204]: Typed Hole"), "{stderr}");
+    }
+}
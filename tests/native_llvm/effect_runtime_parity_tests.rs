@@ -130,6 +130,34 @@ fn effect_multi_shot_parity() {
     assert_parity_with_yield_checks("effect_multi_shot.flx", "\"3\"");
 }
 
+#[test]
+fn effect_parameterized_multi_op_args_parity() {
+    assert_parity_with_yield_checks("effect_parameterized_multi_op_args.flx", "\"12\"");
+}
+
+#[test]
+fn effect_handle_body_after_perform_parity() {
+    assert_parity_with_yield_checks("effect_handle_body_after_perform.flx", "\"101\"");
+}
+
+#[test]
+fn effect_forwarded_state_survives_inner_handler_parity() {
+    assert_parity_with_yield_checks(
+        "effect_forwarded_state_survives_inner_handler.flx",
+        "\"(7, 2)\"",
+    );
+}
+
+#[test]
+fn effect_lambda_escapes_handler_parity() {
+    assert_parity_with_yield_checks("effect_lambda_escapes_handler.flx", "\"7\"");
+}
+
+#[test]
+fn effect_lambda_escapes_handle_body_parity() {
+    assert_parity_with_yield_checks("effect_lambda_escapes_handle_body.flx", "\"2\"");
+}
+
 #[test]
 fn guide_io_and_time_native_matches_vm() {
     let path = "examples/guide_type_system/04_with_io_and_with_time.flx";
@@ -0,0 +1,125 @@
+//! Checks that the example corpus and the Flow standard library are already in `flux fmt` layout.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use flux::syntax::formatter::{FormatError, FormatOptions, format_source_with};
+
+fn collect_flx_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap_or_else(|e| panic!("read {}: {e}", dir.display())) {
+        let path = entry.expect("directory entry").path();
+        if path.is_dir() {
+            collect_flx_files(&path, files);
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("flx") {
+            files.push(path);
+        }
+    }
+}
+
+fn corpus(relative: &str) -> Vec<(PathBuf, String)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(relative);
+    let mut files = Vec::new();
+    collect_flx_files(&root, &mut files);
+    files.sort();
+    files
+        .into_iter()
+        .map(|path| {
+            let source = fs::read_to_string(&path).expect("read source");
+            (path, source)
+        })
+        .collect()
+}
+
+/// Example directories holding diagnostic and error fixtures. Snapshot and rule tests pin the
+/// positions and layouts these files exercise, so they are only required to format
+/// idempotently, not to already be in `flux fmt` layout.
+const ERROR_FIXTURE_DIRS: &[&str] = &[
+    "compiler_errors",
+    "diagnostics",
+    "failing",
+    "parser_errors",
+    "runtime_errors",
+];
+
+fn is_error_fixture(path: &Path) -> bool {
+    path.components().any(|component| {
+        ERROR_FIXTURE_DIRS
+            .iter()
+            .any(|dir| component.as_os_str() == *dir)
+    })
+}
+
+fn without_whitespace(text: &str) -> String {
+    text.chars().filter(|ch| !ch.is_whitespace()).collect()
+}
+
+#[test]
+fn flow_library_is_formatted() {
+    let options = FormatOptions::default();
+    for (path, source) in corpus("lib/Flow") {
+        let formatted = format_source_with(&source, &options)
+            .unwrap_or_else(|err| panic!("{} failed to format: {err:?}", path.display()));
+        assert!(
+            formatted == source,
+            "{} is not formatted; run `flux fmt lib/Flow`",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn examples_are_formatted() {
+    let options = FormatOptions::default();
+    let mut formatted_files = 0;
+    for (path, source) in corpus("examples") {
+        let formatted = match format_source_with(&source, &options) {
+            Ok(formatted) => formatted,
+            // Parser error fixtures are expected not to format.
+            Err(FormatError::Parse(_)) => continue,
+            Err(FormatError::Changed) => panic!("{} changed meaning", path.display()),
+        };
+        if is_error_fixture(&path) {
+            let again = format_source_with(&formatted, &options).expect("formatted output parses");
+            assert!(again == formatted, "{} is not idempotent", path.display());
+            continue;
+        }
+        assert!(
+            formatted == source,
+            "{0} is not formatted; run `flux fmt {0}`",
+            path.display()
+        );
+        formatted_files += 1;
+    }
+    assert!(formatted_files > 0);
+}
+
+#[test]
+fn examples_format_idempotently_and_only_change_whitespace_when_narrow() {
+    let options = FormatOptions { max_width: 60 };
+    let mut formatted_files = 0;
+    for (path, source) in corpus("examples") {
+        let formatted = match format_source_with(&source, &options) {
+            Ok(formatted) => formatted,
+            Err(FormatError::Parse(_)) => continue,
+            Err(FormatError::Changed) => {
+                panic!("{} changed meaning at width 60", path.display())
+            }
+        };
+        assert_eq!(
+            without_whitespace(&formatted),
+            without_whitespace(&source),
+            "{} lost or reordered tokens or comments at width 60",
+            path.display()
+        );
+        let again = format_source_with(&formatted, &options).expect("formatted output parses");
+        assert!(
+            again == formatted,
+            "{} is not idempotent at width 60",
+            path.display()
+        );
+        formatted_files += 1;
+    }
+    assert!(formatted_files > 0);
+}
@@ -1,15 +1,35 @@
-use flux::syntax::{lexer::Lexer, linter::Linter, parser::Parser};
+use flux::{
+    diagnostics::Severity,
+    syntax::{
+        lexer::Lexer,
+        linter::{LintLevel, LintPolicy, Linter},
+        parser::Parser,
+    },
+};
 
 fn lint(input: &str) -> String {
+    lint_with(input, LintPolicy::default())
+}
+
+fn lint_with(input: &str, policy: LintPolicy) -> String {
     let lexer = Lexer::new(input);
     let mut parser = Parser::new(lexer);
     let program = parser.parse_program();
     assert!(parser.errors.is_empty(), "parser errors in test input");
     let interner = parser.take_interner();
-    let diagnostics = Linter::new(None, &interner).lint(&program);
+    let diagnostics = Linter::new(None, &interner)
+        .with_policy(policy)
+        .lint(&program);
     diagnostics
         .iter()
-        .map(|d| format!("{}:{}", d.code().unwrap_or(""), d.title()))
+        .map(|d| {
+            let level = if d.severity() == Severity::Error {
+                "error:"
+            } else {
+                ""
+            };
+            format!("{level}{}:{}", d.code().unwrap_or(""), d.title())
+        })
         .collect::<Vec<_>>()
         .join("\n")
 }
@@ -128,6 +148,34 @@ fn warns_on_function_too_long() {
     assert!(output.contains("W009:FUNCTION TOO LONG"));
 }
 
+#[test]
+fn policy_allows_and_denies_lints() {
+    let source = "fn never_called(a, b, c, d, e, f) { 1; }";
+    let mut policy = LintPolicy::default();
+    policy.set_level("W007", LintLevel::Allow);
+    policy.set_level("W010", LintLevel::Deny);
+    let output = lint_with(source, policy);
+    assert!(!output.contains("W007"));
+    assert!(output.contains("error:W010:TOO MANY PARAMETERS"));
+    assert!(output.contains("W002:UNUSED PARAMETER"));
+    assert!(!output.contains("error:W002"));
+}
+
+#[test]
+fn policy_thresholds_replace_defaults() {
+    let source = "fn f(a, b, c) { a + b + c; } f(1, 2, 3);";
+    assert!(!lint(source).contains("W010"));
+
+    let mut policy = LintPolicy::default();
+    policy.thresholds.max_function_params = 2;
+    assert!(lint_with(source, policy).contains("W010:TOO MANY PARAMETERS"));
+
+    let source = "fn f(a, b, c, d, e, f) { a + b + c + d + e + f; } f(1, 2, 3, 4, 5, 6);";
+    let mut policy = LintPolicy::default();
+    policy.thresholds.max_function_params = 6;
+    assert!(!lint_with(source, policy).contains("W010"));
+}
+
 #[test]
 fn warns_on_high_cyclomatic_complexity() {
     // Many match arms = high complexity
@@ -208,3 +256,23 @@ fn warns_on_lambda_complexity() {
     let output = lint(source);
     assert!(output.contains("W011:HIGH CYCLOMATIC COMPLEXITY"));
 }
+
+#[test]
+fn effect_separator_style_suggests_comma_list() {
+    let source = "fn log(x: Int) -> Int with Console + Clock { x }";
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty(), "parser errors in test input");
+    let interner = parser.take_interner();
+    let diagnostics = Linter::new(None, &interner).lint(&program);
+    let diag = diagnostics
+        .iter()
+        .find(|d| d.code() == Some("W013"))
+        .expect("W013 warning");
+
+    let suggestion = &diag.suggestions()[0];
+    assert_eq!(suggestion.replacement, "Console, Clock");
+    assert!(suggestion.is_machine_applicable());
+    let (start, end) = (suggestion.span.start.column, suggestion.span.end.column);
+    assert_eq!(&source[start..end], "Console + Clock");
+}
@@ -151,6 +151,26 @@ let test2 = "this compiles";
         }
     }
 
+    #[test]
+    fn typed_holes() {
+        let tests = vec![
+            ("?;", "?;"),
+            ("?todo;", "?todo;"),
+            ("f(?x, 1);", "f(?x, 1);"),
+            ("?step + 1;", "(?step + 1);"),
+        ];
+
+        for (input, expected) in tests {
+            let (program, interner) = parse(input);
+            assert_eq!(
+                program.display_with(&interner),
+                expected,
+                "Failed for: {}",
+                input
+            );
+        }
+    }
+
     #[test]
     fn if_expression() {
         let (program, _interner) = parse("if x < y { x; };");
@@ -1015,6 +1035,18 @@ fn t(x) {
         assert_eq!(program.statements.len(), 1);
     }
 
+    #[test]
+    fn test_lambda_no_param_shorthand() {
+        let (program, _interner) = parse(r"\-> 42;");
+        match &program.statements[0] {
+            Statement::Expression {
+                expression: Expression::Function { parameters, .. },
+                ..
+            } => assert!(parameters.is_empty()),
+            _ => panic!("expected lambda function expression"),
+        }
+    }
+
     #[test]
     fn test_lambda_block_body() {
         let (program, _interner) = parse(r"\x -> { let y = x * 2; y + 1 };");
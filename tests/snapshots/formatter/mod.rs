@@ -80,8 +80,8 @@ fn snapshot_format_string_interpolation() {
 fn snapshot_format_match_expression() {
     let input = r#"
 match value{
-Some(x)=>x*2,
-None=>0
+Some(x)->x*2,
+None->0
 }
 "#;
     let output = format_source(input);
@@ -126,3 +126,10 @@ let items = [|
     let output = format_source(input);
     assert_snapshot!(output);
 }
+
+#[test]
+fn snapshot_format_typed_holes() {
+    let input = "fn f(xs){fold(xs,  ?,?step)+?mk(1)}";
+    let output = format_source(input);
+    assert_snapshot!(output);
+}
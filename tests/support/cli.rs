@@ -0,0 +1,93 @@
+//! Shared helpers for tests that run the `flux` binary against a throwaway
+//! project directory.
+//!
+//! This module is included by multiple test binaries via `#[path = ...]`,
+//! each of which uses a different subset of the helpers. Rust's dead-code
+//! analysis runs per binary, so silence the false positive at the module
+//! level.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// The repository root. Programs that import the `Flow` standard library
+/// need it as the working directory.
+pub fn repo_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Build a `flux` invocation that runs in `dir` with colors disabled.
+pub fn flux_command(dir: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_flux"));
+    command.args(args).current_dir(dir).env("NO_COLOR", "1");
+    command
+}
+
+/// Run `flux` in `dir` with an empty stdin and wait for it to exit.
+pub fn run_flux(dir: &Path, args: &[&str]) -> Output {
+    flux_command(dir, args)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run flux with args {:?}: {e}", args))
+}
+
+/// Stdout followed by stderr, for assertions that don't care which stream
+/// a message went to.
+pub fn combined_output(output: &Output) -> String {
+    let mut text = String::new();
+    text.push_str(&String::from_utf8_lossy(&output.stdout));
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    text
+}
+
+/// A directory under the system temp dir that is removed when dropped.
+///
+/// Keep the value alive for as long as the test uses the directory:
+/// `let project = TempProject::new(..)`, not `let _ = ..`.
+pub struct TempProject {
+    dir: PathBuf,
+}
+
+impl TempProject {
+    /// Create `flux_<name>_<pid>`, clearing anything a crashed run left
+    /// behind. `name` must be unique within the test binary.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("flux_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)
+            .unwrap_or_else(|e| panic!("failed to create {}: {e}", dir.display()));
+        Self { dir }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write `contents` to `relative`, creating parent directories, and
+    /// return the absolute path.
+    pub fn write(&self, relative: &str, contents: &str) -> PathBuf {
+        let path = self.dir.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents)
+            .unwrap_or_else(|e| panic!("failed to write {}: {e}", path.display()));
+        path
+    }
+
+    pub fn read(&self, relative: &str) -> String {
+        let path = self.dir.join(relative);
+        fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+    }
+
+    /// Run `flux` with the project as the working directory.
+    pub fn run(&self, args: &[&str]) -> Output {
+        run_flux(&self.dir, args)
+    }
+}
+
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
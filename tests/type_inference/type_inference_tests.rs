@@ -2406,3 +2406,94 @@ fn f(x: Int) -> Int with |e - IO {
         result.diagnostics
     );
 }
+
+fn typed_hole_diagnostics(
+    result: &flux::ast::type_infer::InferProgramResult,
+) -> Vec<&flux::diagnostics::Diagnostic> {
+    result
+        .diagnostics
+        .iter()
+        .filter(|diag| diag.code() == Some("W204"))
+        .collect()
+}
+
+#[test]
+fn infer_typed_hole_reports_expected_type_locals_and_candidates() {
+    let source = r#"
+fn plus(a: Int, b: Int) -> Int { a + b }
+
+fn describe(n: Int) -> String { "n" }
+
+fn apply(f: (Int, Int) -> Int, x: Int) -> Int { f(x, x) }
+
+fn twice(x: Int) -> Int {
+    let y = x
+    apply(?step, y)
+}
+"#;
+    let (result, _) = infer_program_from_source(source);
+    let holes = typed_hole_diagnostics(&result);
+    assert_eq!(
+        holes.len(),
+        1,
+        "expected one hole: {:#?}",
+        result.diagnostics
+    );
+    let hole = holes[0];
+    assert_eq!(hole.severity(), flux::diagnostics::Severity::Warning);
+    assert_eq!(
+        hole.message(),
+        Some("Hole `?step` needs a value of type `(Int, Int) -> Int`.")
+    );
+    let text = hole
+        .hints()
+        .iter()
+        .map(|hint| hint.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(text.contains("x: Int"), "missing local `x`: {text}");
+    assert!(text.contains("y: Int"), "missing local `y`: {text}");
+    assert!(
+        text.contains("plus: (Int, Int) -> Int"),
+        "missing `plus`: {text}"
+    );
+    assert!(
+        !text.contains("describe"),
+        "`describe` does not fit: {text}"
+    );
+    assert!(
+        !has_diagnostic_code(&result, "E300"),
+        "holes must not produce type errors: {:#?}",
+        result.diagnostics
+    );
+}
+
+#[test]
+fn infer_typed_hole_continues_inference_past_the_hole() {
+    let source = r#"
+fn pick(flag: Bool, a: Int) -> Int {
+    if flag { a } else { ? }
+}
+
+fn main() -> Unit {
+    let s: String = pick(true, 1)
+}
+"#;
+    let (result, _) = infer_program_from_source(source);
+    let holes = typed_hole_diagnostics(&result);
+    assert_eq!(
+        holes.len(),
+        1,
+        "expected one hole: {:#?}",
+        result.diagnostics
+    );
+    assert_eq!(
+        holes[0].message(),
+        Some("Hole `?` needs a value of type `Int`.")
+    );
+    assert!(
+        has_diagnostic_code(&result, "E300"),
+        "expected the mismatch after the hole to be reported: {:#?}",
+        result.diagnostics
+    );
+}
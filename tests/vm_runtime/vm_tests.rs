@@ -237,6 +237,28 @@ compute() handle Counter(0) {
     assert_eq!(result, Value::Integer(1));
 }
 
+#[test]
+fn lambda_escaping_handle_body_uses_handler_at_call_site() {
+    let result = run(r#"
+effect Ask {
+    ask : () -> Int
+}
+
+fn make() {
+    (\() -> perform Ask.ask()) handle Ask {
+        ask(resume) -> resume(1)
+    }
+}
+
+let f = make();
+f() handle Ask {
+    ask(resume) -> resume(2)
+};
+"#);
+
+    assert_eq!(result, Value::Integer(2));
+}
+
 #[test]
 fn unmigrated_runtime_error_maps_not_a_function_code() {
     let instructions = make(OpCode::OpClosure, &[0, 0]);